@@ -1,5 +1,9 @@
-use std::{sync::Arc, time::Duration};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
+use async_trait::async_trait;
 use aws_config::{
     ecs::EcsCredentialsProvider,
     environment::{EnvironmentVariableCredentialsProvider, EnvironmentVariableRegionProvider},
@@ -7,12 +11,26 @@ use aws_config::{
     meta::{credentials::CredentialsProviderChain, region::RegionProviderChain},
     profile::ProfileFileCredentialsProvider,
     provider_config::ProviderConfig,
-    web_identity_token::WebIdentityTokenCredentialsProvider,
+    sts::AssumeRoleProvider,
+    web_identity_token::{StaticConfiguration, WebIdentityTokenCredentialsProvider},
+    SdkConfig,
 };
-use aws_credential_types::provider::{self, ProvideCredentials};
-use tracing::Instrument;
+use aws_credential_types::provider::{self, ProvideCredentials, SharedCredentialsProvider};
+use deltalake_core::storage::{CredentialProviderRef, StorageCredential};
+use object_store::aws::AwsCredential;
+use object_store::CredentialProvider;
+use tracing::{debug, warn, Instrument};
+
+use crate::storage::{s3_constants, str_option};
 
 const IMDS_PROVIDER_NAME: &str = "Ec2InstanceMetadata";
+const STORE_NAME: &str = "S3";
+
+/// Credentials are refreshed in the background once they expire within this window.
+const REFRESH_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// Credentials expiring within this buffer are considered expired and refreshed before use.
+const EXPIRY_BUFFER: Duration = Duration::from_secs(60);
 
 #[derive(Debug)]
 pub struct ConfiguredCredentialChain {
@@ -106,6 +124,191 @@ impl ProvideCredentials for ConfiguredCredentialChain {
     }
 }
 
+/// Whether a role to assume has been configured, either through a web identity token or
+/// [`s3_constants::AWS_S3_ASSUME_ROLE_ARN`].
+pub(crate) fn role_configured(options: &HashMap<String, String>) -> bool {
+    str_option(options, s3_constants::AWS_S3_ASSUME_ROLE_ARN).is_some()
+        || (str_option(options, s3_constants::AWS_WEB_IDENTITY_TOKEN_FILE).is_some()
+            && str_option(options, s3_constants::AWS_ROLE_ARN).is_some())
+}
+
+fn default_session_name() -> String {
+    format!("delta-rs_{}", uuid::Uuid::new_v4())
+}
+
+/// Build a provider for the credentials of the role configured in `options`.
+///
+/// A web identity token is exchanged for credentials of [`s3_constants::AWS_ROLE_ARN`]. When
+/// [`s3_constants::AWS_S3_ASSUME_ROLE_ARN`] is set, that role is assumed on top of the web
+/// identity, or of the credentials already configured in `sdk_config`.
+pub(crate) async fn role_credentials_provider(
+    options: &HashMap<String, String>,
+    sdk_config: &SdkConfig,
+) -> Option<SharedCredentialsProvider> {
+    let web_identity = match (
+        str_option(options, s3_constants::AWS_WEB_IDENTITY_TOKEN_FILE),
+        str_option(options, s3_constants::AWS_ROLE_ARN),
+    ) {
+        (Some(token_file), Some(role_arn)) => {
+            let provider_config =
+                ProviderConfig::default().with_region(sdk_config.region().cloned());
+            let provider = WebIdentityTokenCredentialsProvider::builder()
+                .static_configuration(StaticConfiguration {
+                    web_identity_token_file: token_file.into(),
+                    role_arn,
+                    session_name: str_option(options, s3_constants::AWS_ROLE_SESSION_NAME)
+                        .unwrap_or_else(default_session_name),
+                })
+                .configure(&provider_config)
+                .build();
+            Some(SharedCredentialsProvider::new(provider))
+        }
+        _ => None,
+    };
+
+    let Some(role_arn) = str_option(options, s3_constants::AWS_S3_ASSUME_ROLE_ARN) else {
+        return web_identity;
+    };
+    let base = web_identity.or_else(|| sdk_config.credentials_provider())?;
+    let provider = AssumeRoleProvider::builder(role_arn)
+        .session_name(
+            str_option(options, s3_constants::AWS_S3_ROLE_SESSION_NAME)
+                .unwrap_or_else(default_session_name),
+        )
+        .configure(sdk_config)
+        .build_from_provider(base)
+        .await;
+    Some(SharedCredentialsProvider::new(provider))
+}
+
+/// Supplies credentials of an AWS SDK credential provider to [`object_store`].
+///
+/// Credentials are cached until they are about to expire. Shortly before that they are
+/// refreshed in the background, so long running operations like optimize or vacuum keep
+/// working past the lifetime of the initial STS credentials without blocking requests.
+#[derive(Debug, Clone)]
+pub(crate) struct AwsCredentialBridge {
+    inner: Arc<BridgeInner>,
+}
+
+#[derive(Debug)]
+struct BridgeInner {
+    provider: SharedCredentialsProvider,
+    cached: Mutex<Option<CachedCredential>>,
+    refreshing: AtomicBool,
+}
+
+#[derive(Debug, Clone)]
+struct CachedCredential {
+    credential: Arc<AwsCredential>,
+    expiry: Option<SystemTime>,
+}
+
+impl AwsCredentialBridge {
+    pub fn new(provider: SharedCredentialsProvider) -> Self {
+        Self {
+            inner: Arc::new(BridgeInner {
+                provider,
+                cached: Mutex::new(None),
+                refreshing: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    fn refresh_in_background(&self) {
+        if self.inner.refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let inner = self.inner.clone();
+        tokio::spawn(async move {
+            if let Err(err) = inner.refresh().await {
+                warn!("failed to refresh expiring AWS credentials: {err}");
+            }
+            inner.refreshing.store(false, Ordering::Release);
+        });
+    }
+}
+
+impl BridgeInner {
+    async fn refresh(&self) -> object_store::Result<Arc<AwsCredential>> {
+        let credentials = self.provider.provide_credentials().await.map_err(|err| {
+            object_store::Error::Generic {
+                store: STORE_NAME,
+                source: Box::new(err),
+            }
+        })?;
+        debug!(expiry = ?credentials.expiry(), "refreshed AWS credentials");
+        let credential = Arc::new(AwsCredential {
+            key_id: credentials.access_key_id().to_string(),
+            secret_key: credentials.secret_access_key().to_string(),
+            token: credentials.session_token().map(|token| token.to_string()),
+        });
+        *self.cached.lock().unwrap() = Some(CachedCredential {
+            credential: credential.clone(),
+            expiry: credentials.expiry(),
+        });
+        Ok(credential)
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for AwsCredentialBridge {
+    type Credential = AwsCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<AwsCredential>> {
+        let cached = self.inner.cached.lock().unwrap().clone();
+        if let Some(cached) = cached {
+            let Some(expiry) = cached.expiry else {
+                return Ok(cached.credential);
+            };
+            let remaining = expiry.duration_since(SystemTime::now()).unwrap_or_default();
+            if remaining > EXPIRY_BUFFER {
+                if remaining <= REFRESH_WINDOW {
+                    self.refresh_in_background();
+                }
+                return Ok(cached.credential);
+            }
+        }
+        self.inner.refresh().await
+    }
+}
+
+/// Supplies credentials of a user provided [`deltalake_core::storage::CredentialProvider`] to
+/// [`object_store`].
+#[derive(Debug)]
+pub(crate) struct UserCredentialProvider(pub CredentialProviderRef);
+
+#[async_trait]
+impl CredentialProvider for UserCredentialProvider {
+    type Credential = AwsCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<AwsCredential>> {
+        let credential =
+            self.0
+                .get_credential()
+                .await
+                .map_err(|err| object_store::Error::Generic {
+                    store: STORE_NAME,
+                    source: Box::new(err),
+                })?;
+        match credential {
+            StorageCredential::AwsAccessKey {
+                key_id,
+                secret_key,
+                token,
+            } => Ok(Arc::new(AwsCredential {
+                key_id,
+                secret_key,
+                token,
+            })),
+            other => Err(object_store::Error::Generic {
+                store: STORE_NAME,
+                source: format!("unsupported credential for S3: {}", other.kind()).into(),
+            }),
+        }
+    }
+}
+
 impl ProvideCredentials for NoOpCredentials {
     fn provide_credentials<'a>(&'a self) -> provider::future::ProvideCredentials<'a>
     where
@@ -116,3 +319,105 @@ impl ProvideCredentials for NoOpCredentials {
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use aws_credential_types::Credentials;
+
+    use super::*;
+
+    /// Hands out new credentials on every call, expiring after the configured lifetime
+    #[derive(Debug)]
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+        lifetime: Option<Duration>,
+    }
+
+    impl ProvideCredentials for CountingProvider {
+        fn provide_credentials<'a>(&'a self) -> provider::future::ProvideCredentials<'a>
+        where
+            Self: 'a,
+        {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            provider::future::ProvideCredentials::ready(Ok(Credentials::new(
+                format!("key-{n}"),
+                "secret",
+                Some("token".to_string()),
+                self.lifetime.map(|lifetime| SystemTime::now() + lifetime),
+                "test",
+            )))
+        }
+    }
+
+    fn counting_bridge(lifetime: Option<Duration>) -> (AwsCredentialBridge, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = CountingProvider {
+            calls: calls.clone(),
+            lifetime,
+        };
+        (
+            AwsCredentialBridge::new(SharedCredentialsProvider::new(provider)),
+            calls,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_bridge_caches_credentials() {
+        let (bridge, calls) = counting_bridge(Some(Duration::from_secs(3600)));
+        let credential = bridge.get_credential().await.unwrap();
+        assert_eq!(credential.key_id, "key-0");
+        assert_eq!(credential.token.as_deref(), Some("token"));
+        let credential = bridge.get_credential().await.unwrap();
+        assert_eq!(credential.key_id, "key-0");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let (bridge, calls) = counting_bridge(None);
+        bridge.get_credential().await.unwrap();
+        bridge.get_credential().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_user_credential_provider() {
+        use deltalake_core::storage::StaticCredentialProvider;
+
+        let provider = UserCredentialProvider(Arc::new(StaticCredentialProvider::new(
+            StorageCredential::AwsAccessKey {
+                key_id: "key".to_string(),
+                secret_key: "secret".to_string(),
+                token: None,
+            },
+        )));
+        let credential = provider.get_credential().await.unwrap();
+        assert_eq!(credential.key_id, "key");
+        assert_eq!(credential.secret_key, "secret");
+
+        let provider = UserCredentialProvider(Arc::new(StaticCredentialProvider::new(
+            StorageCredential::BearerToken("token".to_string()),
+        )));
+        assert!(provider.get_credential().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bridge_refreshes_expired_credentials() {
+        let (bridge, calls) = counting_bridge(Some(Duration::from_secs(30)));
+        assert_eq!(bridge.get_credential().await.unwrap().key_id, "key-0");
+        assert_eq!(bridge.get_credential().await.unwrap().key_id, "key-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_bridge_refreshes_expiring_credentials_in_background() {
+        let (bridge, calls) = counting_bridge(Some(Duration::from_secs(120)));
+        assert_eq!(bridge.get_credential().await.unwrap().key_id, "key-0");
+        // the expiring credentials are still handed out while the refresh is in flight
+        assert_eq!(bridge.get_credential().await.unwrap().key_id, "key-0");
+        while bridge.inner.refreshing.load(Ordering::Acquire) {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(bridge.get_credential().await.unwrap().key_id, "key-1");
+    }
+}
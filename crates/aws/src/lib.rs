@@ -29,7 +29,7 @@ use std::{
 };
 use tracing::debug;
 
-use deltalake_core::logstore::{logstores, LogStore, LogStoreFactory};
+use deltalake_core::logstore::{logstore_factories, LogStore, LogStoreFactory};
 use deltalake_core::storage::{factories, url_prefix_handler, ObjectStoreRef, StorageOptions};
 use deltalake_core::{DeltaResult, Path};
 use url::Url;
@@ -59,6 +59,13 @@ impl LogStoreFactory for S3LogStoreFactory {
             ));
         }
 
+        if storage::conditional_put_enabled(options) {
+            debug!("S3LogStoreFactory has been asked to create a LogStore where the underlying store supports conditional writes - no locking provider required");
+            return Ok(deltalake_core::logstore::default_logstore(
+                store, location, options,
+            ));
+        }
+
         let s3_options = S3StorageOptions::from_map(&options.0)?;
 
         if s3_options.locking_provider.as_deref() != Some("dynamodb") {
@@ -84,7 +91,7 @@ pub fn register_handlers(_additional_prefixes: Option<Url>) {
     for scheme in ["s3", "s3a"].iter() {
         let url = Url::parse(&format!("{}://", scheme)).unwrap();
         factories().insert(url.clone(), object_stores.clone());
-        logstores().insert(url.clone(), log_stores.clone());
+        logstore_factories().insert(url.clone(), log_stores.clone());
     }
 }
 
@@ -735,6 +742,26 @@ mod tests {
         assert_eq!(logstore.name(), "DefaultLogStore");
     }
 
+    /// Stores with conditional writes don't need a locking provider, even if one is configured
+    #[test]
+    #[serial]
+    fn test_logstore_factory_conditional_put() {
+        let factory = S3LogStoreFactory::default();
+        let store = InMemory::new();
+        let url = Url::parse("s3://test-bucket").unwrap();
+        let options = StorageOptions::from(HashMap::from([
+            (
+                storage::s3_constants::AWS_S3_LOCKING_PROVIDER.to_string(),
+                "dynamodb".to_string(),
+            ),
+            ("aws_conditional_put".to_string(), "etag".to_string()),
+        ]));
+        let logstore = factory
+            .with_options(Arc::new(store), &url, &options)
+            .unwrap();
+        assert_eq!(logstore.name(), "DefaultLogStore");
+    }
+
     #[test]
     #[serial]
     fn test_create_dynamodb_sdk_config() {
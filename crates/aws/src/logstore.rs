@@ -2,6 +2,11 @@
 //! Implementation uses DynamoDb to guarantee atomic writes of delta log entries
 //! when the underlying object storage does not support atomic `put_if_absent`
 //! or `rename_if_absent` operations, as is the case for S3.
+//!
+//! The DynamoDb schema and commit protocol are the same as the ones used by Spark's
+//! `S3DynamoDBLogStore`, so that Rust and Spark writers can safely commit to the same table:
+//! the temporary commit file is registered in DynamoDb, copied to `N.json` and only then
+//! is the entry marked as complete.
 
 use crate::errors::LockClientError;
 use crate::storage::S3StorageOptions;
@@ -15,7 +20,7 @@ use url::Url;
 use deltalake_core::logstore::*;
 use deltalake_core::{
     operations::transaction::TransactionError,
-    storage::{ObjectStoreRef, StorageOptions},
+    storage::{commit_uri_from_version, ObjectStoreRef, StorageOptions},
     DeltaResult, DeltaTableError,
 };
 
@@ -78,7 +83,7 @@ impl S3DynamoDbLogStore {
         })
     }
 
-    /// Attempt to repair an incomplete log entry by moving the temporary commit file
+    /// Attempt to repair an incomplete log entry by copying the temporary commit file
     /// to `N.json` and update the associated log entry to mark it as completed.
     pub async fn repair_entry(
         &self,
@@ -89,19 +94,17 @@ impl S3DynamoDbLogStore {
             return Ok(RepairLogEntryResult::AlreadyCompleted);
         }
         for retry in 0..=MAX_REPAIR_RETRIES {
-            match write_commit_entry(&self.storage, entry.version, &entry.temp_path).await {
-                Ok(()) => {
+            match self.copy_temp_file(entry).await {
+                Ok(copy_performed) => {
                     debug!("Successfully committed entry for version {}", entry.version);
-                    return self.try_complete_entry(entry, true).await;
+                    return self.try_complete_entry(entry, copy_performed).await;
                 }
-                // `N.json` has already been moved, complete the entry in DynamoDb just in case
-                Err(TransactionError::ObjectStore {
-                    source: ObjectStoreError::NotFound { .. },
-                }) => {
+                // the temporary file is gone, complete the entry in DynamoDb just in case
+                Err(ObjectStoreError::NotFound { .. }) => {
                     warn!("It looks like the {}.json has already been moved, we got 404 from ObjectStorage.", entry.version);
                     return self.try_complete_entry(entry, false).await;
                 }
-                Err(err) if retry == MAX_REPAIR_RETRIES => return Err(err),
+                Err(err) if retry == MAX_REPAIR_RETRIES => return Err(err.into()),
                 Err(err) => {
                     debug!("retry #{retry} on log entry {entry:?} failed to move commit: '{err}'")
                 }
@@ -110,6 +113,22 @@ impl S3DynamoDbLogStore {
         unreachable!("for loop yields Ok or Err in body when retry = MAX_REPAIR_RETRIES")
     }
 
+    /// Copy the temporary commit file to `N.json`, unless `N.json` already exists.
+    ///
+    /// As in Spark, the temporary file is copied rather than renamed, so that it stays available
+    /// to other writers repairing the same entry until the entry has been marked as complete.
+    /// Returns whether a copy was performed.
+    async fn copy_temp_file(&self, entry: &CommitEntry) -> Result<bool, ObjectStoreError> {
+        let commit_path = commit_uri_from_version(entry.version);
+        match self.storage.head(&commit_path).await {
+            Ok(_) => return Ok(false),
+            Err(ObjectStoreError::NotFound { .. }) => {}
+            Err(err) => return Err(err),
+        }
+        self.storage.copy(&entry.temp_path, &commit_path).await?;
+        Ok(true)
+    }
+
     /// Update an incomplete log entry to completed.
     async fn try_complete_entry(
         &self,
@@ -237,6 +256,10 @@ impl LogStore for S3DynamoDbLogStore {
         // of the commit is just one delta client competing to perform the repair operation, as any
         // other client could see the incomplete commit to immediately trigger a repair.
         self.repair_entry(&entry).await?;
+        // once the entry is complete nobody needs the temporary file anymore
+        if let Err(err) = self.storage.delete(tmp_commit).await {
+            warn!("Failed to delete temporary commit file {tmp_commit}: {err}");
+        }
         Ok(())
     }
 
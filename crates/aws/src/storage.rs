@@ -5,11 +5,13 @@ use aws_config::provider_config::ProviderConfig;
 use aws_config::{Region, SdkConfig};
 use bytes::Bytes;
 use deltalake_core::storage::object_store::{
-    aws::AmazonS3ConfigKey, parse_url_opts, GetOptions, GetResult, ListResult, ObjectMeta,
-    ObjectStore, PutOptions, PutResult, Result as ObjectStoreResult,
+    aws::{AmazonS3Builder, AmazonS3ConfigKey},
+    GetOptions, GetResult, ListResult, ObjectMeta, ObjectStore, PutMode, PutOptions, PutResult,
+    Result as ObjectStoreResult,
 };
 use deltalake_core::storage::{
-    limit_store_handler, str_is_truthy, ObjectStoreFactory, ObjectStoreRef, StorageOptions,
+    limit_store_handler, retry_store_handler, str_is_truthy, CredentialProviderRef,
+    ObjectStoreFactory, ObjectStoreRef, StorageOptions,
 };
 use deltalake_core::{DeltaResult, ObjectStoreError, Path};
 use futures::stream::BoxStream;
@@ -69,17 +71,59 @@ impl ObjectStoreFactory for S3ObjectStoreFactory {
         &self,
         url: &Url,
         storage_options: &StorageOptions,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        self.build_store(url, storage_options, None)
+    }
+
+    fn parse_url_opts_with_credentials(
+        &self,
+        url: &Url,
+        storage_options: &StorageOptions,
+        credentials: CredentialProviderRef,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        self.build_store(url, storage_options, Some(credentials))
+    }
+}
+
+impl S3ObjectStoreFactory {
+    fn build_store(
+        &self,
+        url: &Url,
+        storage_options: &StorageOptions,
+        credentials: Option<CredentialProviderRef>,
     ) -> DeltaResult<(ObjectStoreRef, Path)> {
         let options = self.with_env_s3(storage_options);
-        let (inner, prefix) = parse_url_opts(
-            url,
-            options.0.iter().filter_map(|(key, value)| {
+        let mut builder = options
+            .0
+            .iter()
+            .filter_map(|(key, value)| {
                 let s3_key = AmazonS3ConfigKey::from_str(&key.to_ascii_lowercase()).ok()?;
                 Some((s3_key, value.clone()))
-            }),
-        )?;
+            })
+            .fold(
+                AmazonS3Builder::new().with_url(url.as_str()),
+                |builder, (key, value)| builder.with_config(key, value),
+            );
+        builder = with_compatibility_options(builder, &options.0);
+
+        // User provided credentials take precedence. Credentials of assumed roles are short
+        // lived, so they are provided by the AWS SDK and refreshed before they expire.
+        if let Some(credentials) = credentials {
+            builder = builder.with_credentials(Arc::new(
+                crate::credentials::UserCredentialProvider(credentials),
+            ));
+        } else if crate::credentials::role_configured(&storage_options.0) {
+            let s3_options = S3StorageOptions::from_map(&storage_options.0)?;
+            if let Some(provider) = s3_options.sdk_config.credentials_provider() {
+                builder = builder.with_credentials(Arc::new(
+                    crate::credentials::AwsCredentialBridge::new(provider),
+                ));
+            }
+        }
+        let inner = builder.build()?;
+        let prefix = Path::from_url_path(url.path())?;
 
-        let store = limit_store_handler(inner, &options);
+        let store = retry_store_handler(limit_store_handler(inner, &options), &options)?;
 
         // If the copy-if-not-exists env var is set, we don't need to instantiate a locking client or check for allow-unsafe-rename.
         if options
@@ -87,6 +131,10 @@ impl ObjectStoreFactory for S3ObjectStoreFactory {
             .contains_key(AmazonS3ConfigKey::CopyIfNotExists.as_ref())
         {
             Ok((store, prefix))
+        } else if conditional_put_enabled(&options) {
+            // Conditional writes provide put-if-absent, so commits are safe without a lock.
+            let store = S3StorageBackend::try_new(store, false)?.with_conditional_put(true);
+            Ok((Arc::new(store), prefix))
         } else {
             let s3_options = S3StorageOptions::from_map(&storage_options.0)?;
 
@@ -101,6 +149,28 @@ impl ObjectStoreFactory for S3ObjectStoreFactory {
     }
 }
 
+/// Apply options needed by S3 compatible stores like MinIO, Ceph RGW or Cloudflare R2
+/// which are not recognized by [AmazonS3Builder] itself.
+fn with_compatibility_options(
+    mut builder: AmazonS3Builder,
+    options: &HashMap<String, String>,
+) -> AmazonS3Builder {
+    if let Some(style) = str_option(options, s3_constants::AWS_S3_ADDRESSING_STYLE) {
+        builder = builder.with_virtual_hosted_style_request(style.eq_ignore_ascii_case("virtual"));
+    }
+    if str_option(options, s3_constants::AWS_FORCE_PATH_STYLE).is_some_and(|v| str_is_truthy(&v)) {
+        builder = builder.with_virtual_hosted_style_request(false);
+    }
+    if let Some(payer) = str_option(options, s3_constants::AWS_REQUEST_PAYER) {
+        builder = builder
+            .with_request_payer(payer.eq_ignore_ascii_case("requester") || str_is_truthy(&payer));
+    }
+    if str_option(options, s3_constants::AWS_ALLOW_HTTP).is_some_and(|v| str_is_truthy(&v)) {
+        builder = builder.with_allow_http(true);
+    }
+    builder
+}
+
 /// Options used to configure the [S3StorageBackend].
 ///
 /// Available options are described in [s3_constants].
@@ -217,6 +287,16 @@ impl S3StorageOptions {
         #[cfg(feature = "rustls")]
         let sdk_config =
             execute_sdk_future(loader.credentials_provider(credentials_provider).load())?;
+        let sdk_config = match execute_sdk_future(crate::credentials::role_credentials_provider(
+            options,
+            &sdk_config,
+        ))? {
+            Some(provider) => sdk_config
+                .into_builder()
+                .credentials_provider(provider)
+                .build(),
+            None => sdk_config,
+        };
 
         Ok(Self {
             virtual_hosted_style_request,
@@ -306,6 +386,8 @@ pub struct S3StorageBackend {
     inner: ObjectStoreRef,
     /// Whether allowed to performance rename_if_not_exist as rename
     allow_unsafe_rename: bool,
+    /// Whether the store supports conditional writes (`If-None-Match: *`)
+    conditional_put: bool,
 }
 
 impl std::fmt::Display for S3StorageBackend {
@@ -322,8 +404,25 @@ impl S3StorageBackend {
         Ok(Self {
             inner: storage,
             allow_unsafe_rename,
+            conditional_put: false,
         })
     }
+
+    /// Use conditional writes to implement `copy_if_not_exists` and `rename_if_not_exists`.
+    ///
+    /// Requires the inner store to be configured with [AmazonS3ConfigKey::ConditionalPut].
+    pub fn with_conditional_put(mut self, conditional_put: bool) -> Self {
+        self.conditional_put = conditional_put;
+        self
+    }
+
+    async fn put_if_absent_copy(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        let bytes = self.inner.get(from).await?.bytes().await?;
+        self.inner
+            .put_opts(to, bytes.into(), PutMode::Create.into())
+            .await?;
+        Ok(())
+    }
 }
 
 impl std::fmt::Debug for S3StorageBackend {
@@ -367,6 +466,13 @@ impl ObjectStore for S3StorageBackend {
         self.inner.delete(location).await
     }
 
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'a, ObjectStoreResult<Path>> {
+        self.inner.delete_stream(locations)
+    }
+
     fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
         self.inner.list(prefix)
     }
@@ -387,12 +493,19 @@ impl ObjectStore for S3StorageBackend {
         self.inner.copy(from, to).await
     }
 
-    async fn copy_if_not_exists(&self, _from: &Path, _to: &Path) -> ObjectStoreResult<()> {
-        todo!()
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        if self.conditional_put {
+            self.put_if_absent_copy(from, to).await
+        } else {
+            self.inner.copy_if_not_exists(from, to).await
+        }
     }
 
     async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
-        if self.allow_unsafe_rename {
+        if self.conditional_put {
+            self.put_if_absent_copy(from, to).await?;
+            self.inner.delete(from).await
+        } else if self.allow_unsafe_rename {
             self.inner.rename(from, to).await
         } else {
             Err(ObjectStoreError::Generic {
@@ -438,11 +551,19 @@ pub mod s3_constants {
     /// Uses either "path" (the default) or "virtual", which turns on
     /// [virtual host addressing](http://docs.aws.amazon.com/AmazonS3/latest/dev/VirtualHosting.html).
     pub const AWS_S3_ADDRESSING_STYLE: &str = "AWS_S3_ADDRESSING_STYLE";
+    /// If set to "true", forces path style requests even if virtual host addressing was
+    /// configured otherwise. Most S3 compatible stores like MinIO or Ceph RGW require this.
+    pub const AWS_FORCE_PATH_STYLE: &str = "AWS_FORCE_PATH_STYLE";
+    /// Set to "requester" to access buckets with
+    /// [requester pays](https://docs.aws.amazon.com/AmazonS3/latest/userguide/RequesterPaysBuckets.html)
+    /// enabled, charging the request and transfer costs to the caller.
+    pub const AWS_REQUEST_PAYER: &str = "AWS_REQUEST_PAYER";
     /// Locking provider to use for safe atomic rename.
     /// `dynamodb` is currently the only supported locking provider.
     /// If not set, safe atomic rename is not available.
     pub const AWS_S3_LOCKING_PROVIDER: &str = "AWS_S3_LOCKING_PROVIDER";
-    /// The role to assume for S3 writes.
+    /// The role to assume for S3 access, using the web identity or the otherwise configured
+    /// credentials. Credentials of the assumed role are refreshed before they expire.
     pub const AWS_S3_ASSUME_ROLE_ARN: &str = "AWS_S3_ASSUME_ROLE_ARN";
     /// The role session name to use when a role is assumed. If not provided a random session name is generated.
     pub const AWS_S3_ROLE_SESSION_NAME: &str = "AWS_S3_ROLE_SESSION_NAME";
@@ -464,7 +585,8 @@ pub mod s3_constants {
     /// creating an instance of [crate::storage::s3::S3StorageOptions].
     /// See also <https://docs.rs/rusoto_sts/0.47.0/rusoto_sts/struct.WebIdentityProvider.html#method.from_k8s_env>.
     pub const AWS_WEB_IDENTITY_TOKEN_FILE: &str = "AWS_WEB_IDENTITY_TOKEN_FILE";
-    /// The role name to use for web identity.
+    /// The role name to use for web identity. Together with [AWS_WEB_IDENTITY_TOKEN_FILE] the
+    /// token is exchanged for credentials of this role, which are refreshed before they expire.
     /// NOTE: web identity related options are set in the environment when
     /// creating an instance of [crate::storage::s3::S3StorageOptions].
     /// See also <https://docs.rs/rusoto_sts/0.47.0/rusoto_sts/struct.WebIdentityProvider.html#method.from_k8s_env>.
@@ -501,6 +623,8 @@ pub mod s3_constants {
         AWS_SECRET_ACCESS_KEY,
         AWS_SESSION_TOKEN,
         AWS_S3_LOCKING_PROVIDER,
+        AWS_FORCE_PATH_STYLE,
+        AWS_REQUEST_PAYER,
         AWS_S3_ASSUME_ROLE_ARN,
         AWS_S3_ROLE_SESSION_NAME,
         AWS_WEB_IDENTITY_TOKEN_FILE,
@@ -514,6 +638,19 @@ pub mod s3_constants {
     ];
 }
 
+/// Whether conditional writes have been configured for the store, either through the
+/// given options or the `AWS_CONDITIONAL_PUT` environment variable.
+///
+/// When enabled, commits are created with put-if-absent and no locking provider is required.
+pub(crate) fn conditional_put_enabled(options: &StorageOptions) -> bool {
+    options.0.keys().any(|key| {
+        matches!(
+            AmazonS3ConfigKey::from_str(&key.to_ascii_lowercase()),
+            Ok(AmazonS3ConfigKey::ConditionalPut)
+        )
+    }) || std::env::var_os("AWS_CONDITIONAL_PUT").is_some()
+}
+
 pub(crate) fn str_option(map: &HashMap<String, String>, key: &str) -> Option<String> {
     if let Some(s) = map.get(key) {
         return Some(s.to_owned());
@@ -874,6 +1011,46 @@ mod tests {
         });
     }
 
+    #[test]
+    #[serial]
+    fn compatibility_options_configure_builder() {
+        ScopedEnv::run(|| {
+            clear_env_of_aws_keys();
+            let builder = with_compatibility_options(
+                AmazonS3Builder::new().with_virtual_hosted_style_request(true),
+                &hashmap! {
+                    s3_constants::AWS_FORCE_PATH_STYLE.to_string() => "true".to_string(),
+                    s3_constants::AWS_REQUEST_PAYER.to_string() => "requester".to_string(),
+                },
+            );
+            assert_eq!(
+                builder
+                    .get_config_value(&AmazonS3ConfigKey::VirtualHostedStyleRequest)
+                    .as_deref(),
+                Some("false")
+            );
+            assert_eq!(
+                builder
+                    .get_config_value(&AmazonS3ConfigKey::RequestPayer)
+                    .as_deref(),
+                Some("true")
+            );
+
+            let builder = with_compatibility_options(
+                AmazonS3Builder::new(),
+                &hashmap! {
+                    s3_constants::AWS_S3_ADDRESSING_STYLE.to_string() => "virtual".to_string(),
+                },
+            );
+            assert_eq!(
+                builder
+                    .get_config_value(&AmazonS3ConfigKey::VirtualHostedStyleRequest)
+                    .as_deref(),
+                Some("true")
+            );
+        });
+    }
+
     #[tokio::test]
     #[serial]
     async fn storage_options_toggle_imds() {
@@ -917,4 +1094,41 @@ mod tests {
         _ = provider.provide_credentials().await;
         now.elapsed().unwrap()
     }
+
+    #[tokio::test]
+    async fn conditional_put_rename_if_not_exists() {
+        let inner = Arc::new(object_store::memory::InMemory::new());
+        let store = S3StorageBackend::try_new(inner, false)
+            .unwrap()
+            .with_conditional_put(true);
+        let tmp = Path::from("_delta_log/_commit_tmp.json.tmp");
+        let commit = Path::from("_delta_log/00000000000000000000.json");
+
+        store.put(&tmp, Bytes::from("first").into()).await.unwrap();
+        store.rename_if_not_exists(&tmp, &commit).await.unwrap();
+        assert!(store.head(&tmp).await.is_err());
+
+        store.put(&tmp, Bytes::from("second").into()).await.unwrap();
+        let result = store.rename_if_not_exists(&tmp, &commit).await;
+        assert!(matches!(
+            result,
+            Err(ObjectStoreError::AlreadyExists { .. })
+        ));
+        let committed = store.get(&commit).await.unwrap().bytes().await.unwrap();
+        assert_eq!(committed, Bytes::from("first"));
+    }
+
+    #[test]
+    #[serial]
+    fn conditional_put_enabled_from_options() {
+        ScopedEnv::run(|| {
+            clear_env_of_aws_keys();
+            assert!(!conditional_put_enabled(&StorageOptions::default()));
+            assert!(conditional_put_enabled(&StorageOptions(hashmap! {
+                "conditional_put".to_string() => "etag".to_string(),
+            })));
+            std::env::set_var("AWS_CONDITIONAL_PUT", "etag");
+            assert!(conditional_put_enabled(&StorageOptions::default()));
+        });
+    }
 }
@@ -155,6 +155,58 @@ async fn test_repair_commit_entry() -> TestResult<()> {
     Ok(())
 }
 
+/// Spark stages commits as `_delta_log/.tmp/.N.json.<uuid>.tmp` and expects the temporary file
+/// to survive the repair, since any other writer may be repairing the same entry concurrently.
+#[tokio::test]
+#[serial]
+async fn test_repair_spark_commit_entry() -> TestResult<()> {
+    let context = IntegrationContext::new(Box::new(S3Integration::default()))?;
+    let client = make_client()?;
+    let table = prepare_table(&context, "repair_spark_entry").await?;
+    let options: StorageOptions = OPTIONS.clone().into();
+    let log_store: S3DynamoDbLogStore = S3DynamoDbLogStore::try_new(
+        ensure_table_uri(table.table_uri())?,
+        options.clone(),
+        &S3_OPTIONS,
+        std::sync::Arc::new(table.object_store()),
+    )?;
+
+    let entry = create_incomplete_commit_entry(&table, 1, "spark_commit").await?;
+    client.delete_commit_entry(1, &table.table_uri()).await?;
+    let spark_entry = CommitEntry::new(
+        1,
+        Path::from(format!(
+            "_delta_log/.tmp/.{:020}.json.{}.tmp",
+            1,
+            uuid::Uuid::new_v4()
+        )),
+    );
+    log_store
+        .object_store()
+        .rename_if_not_exists(&entry.temp_path, &spark_entry.temp_path)
+        .await?;
+    client
+        .put_commit_entry(&table.table_uri(), &spark_entry)
+        .await?;
+
+    let read_entry = client
+        .get_latest_entry(&table.table_uri())
+        .await?
+        .expect("no latest entry!");
+    assert_eq!(spark_entry, read_entry);
+    assert_eq!(
+        RepairLogEntryResult::MovedFileAndFixedEntry,
+        log_store.repair_entry(&read_entry).await?
+    );
+    assert!(log_store
+        .object_store()
+        .head(&spark_entry.temp_path)
+        .await
+        .is_ok());
+    validate_lock_table_state(&table, 1).await?;
+    Ok(())
+}
+
 #[tokio::test]
 #[serial]
 async fn test_repair_on_update() -> TestResult<()> {
@@ -10,7 +10,7 @@ use std::str::FromStr;
 use object_store::azure::AzureConfigKey;
 use object_store::Error as ObjectStoreError;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 
 lazy_static::lazy_static! {
     static ref CREDENTIAL_KEYS: Vec<AzureConfigKey> =
@@ -27,20 +27,39 @@ lazy_static::lazy_static! {
 }
 
 /// Credential
-enum AzureCredential {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AzureCredential {
     /// Using the account master key
     AccessKey,
     /// Using a static bearer token
     BearerToken,
     /// Authorizing with secret
     ClientSecret,
-    /// Using a shared access signature
-    #[allow(dead_code)]
+    /// Using the managed identity of the host
     ManagedIdentity,
     /// Using a shared access signature
     SasKey,
     /// Using workload identity
     WorkloadIdentity,
+    /// Using the credential of the azure cli
+    AzureCli,
+}
+
+impl FromStr for AzureCredential {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().replace('-', "_").as_str() {
+            "access_key" => Ok(Self::AccessKey),
+            "bearer_token" => Ok(Self::BearerToken),
+            "client_secret" => Ok(Self::ClientSecret),
+            "managed_identity" => Ok(Self::ManagedIdentity),
+            "sas_key" => Ok(Self::SasKey),
+            "workload_identity" => Ok(Self::WorkloadIdentity),
+            "azure_cli" => Ok(Self::AzureCli),
+            _ => Err(Error::Parse(format!("unknown azure credential type: {s}"))),
+        }
+    }
 }
 
 impl AzureCredential {
@@ -60,7 +79,21 @@ impl AzureCredential {
                 AzureConfigKey::FederatedTokenFile,
             ]),
             Self::SasKey => Vec::from_iter([AzureConfigKey::SasKey]),
-            Self::ManagedIdentity => Vec::new(),
+            Self::ManagedIdentity | Self::AzureCli => Vec::new(),
+        }
+    }
+
+    /// optional configuration keys for variant
+    fn optional_keys(&self) -> Vec<AzureConfigKey> {
+        match self {
+            Self::ManagedIdentity => Vec::from_iter([
+                AzureConfigKey::ClientId,
+                AzureConfigKey::ObjectId,
+                AzureConfigKey::MsiResourceId,
+                AzureConfigKey::MsiEndpoint,
+            ]),
+            Self::WorkloadIdentity => Vec::from_iter([AzureConfigKey::AuthorityHost]),
+            _ => Vec::new(),
         }
     }
 }
@@ -73,6 +106,7 @@ pub(crate) struct AzureConfigHelper {
     config: HashMap<AzureConfigKey, String>,
     env_config: HashMap<AzureConfigKey, String>,
     priority: Vec<AzureCredential>,
+    credential: Option<AzureCredential>,
 }
 
 impl AzureConfigHelper {
@@ -104,9 +138,16 @@ impl AzureConfigHelper {
                 AzureCredential::ClientSecret,
                 AzureCredential::WorkloadIdentity,
             ]),
+            credential: None,
         })
     }
 
+    /// Authenticate with the given credential instead of the first fully configured one
+    pub fn with_credential(mut self, credential: Option<AzureCredential>) -> Self {
+        self.credential = credential;
+        self
+    }
+
     /// Check if all credential keys are contained in passed config
     fn has_full_config(&self, cred: &AzureCredential) -> bool {
         cred.keys().iter().all(|key| self.config.contains_key(key))
@@ -124,8 +165,63 @@ impl AzureConfigHelper {
             .all(|key| self.config.contains_key(key) || self.env_config.contains_key(key))
     }
 
+    /// Generate a configuration for the selected credential, augmented with options from the environment.
+    ///
+    /// Configuration for other credentials is removed, since the object store would otherwise
+    /// prefer them over the selected one.
+    fn build_credential(
+        mut self,
+        credential: AzureCredential,
+    ) -> Result<HashMap<AzureConfigKey, String>> {
+        if !self.has_full_config_with_env(&credential) {
+            return Err(Error::Parse(format!(
+                "missing configuration for azure credential {credential:?}, required keys: {:?}",
+                credential.keys()
+            )));
+        }
+        let keys = credential
+            .keys()
+            .into_iter()
+            .chain(credential.optional_keys())
+            .collect::<Vec<_>>();
+        for key in &keys {
+            if let (Entry::Vacant(e), Some(value)) =
+                (self.config.entry(*key), self.env_config.get(key))
+            {
+                e.insert(value.to_owned());
+            }
+        }
+        let omit_keys = CREDENTIAL_KEYS
+            .iter()
+            .chain([&AzureConfigKey::AccessKey, &AzureConfigKey::UseAzureCli])
+            .filter(|key| !keys.contains(key))
+            .copied()
+            .collect::<Vec<_>>();
+        self.config.retain(|key, _| !omit_keys.contains(key));
+        if credential == AzureCredential::AzureCli {
+            self.config
+                .insert(AzureConfigKey::UseAzureCli, "true".to_string());
+        }
+        self.add_env_config(&omit_keys);
+        Ok(self.config)
+    }
+
+    /// Add keys from the environment to the configuration, as e.g. client configuration options.
+    fn add_env_config(&mut self, omit_keys: &[AzureConfigKey]) {
+        for key in self.env_config.keys() {
+            if !omit_keys.contains(key) {
+                if let Entry::Vacant(e) = self.config.entry(*key) {
+                    e.insert(self.env_config.get(key).unwrap().to_owned());
+                }
+            }
+        }
+    }
+
     /// Generate a cofiguration augmented with options from the environment
     pub fn build(mut self) -> Result<HashMap<AzureConfigKey, String>> {
+        if let Some(credential) = self.credential {
+            return self.build_credential(credential);
+        }
         let mut has_credential = false;
 
         if self.config.contains_key(&AzureConfigKey::UseAzureCli) {
@@ -178,18 +274,116 @@ impl AzureConfigHelper {
             Vec::new()
         };
 
-        // Add keys from the environment to the configuration, as e.g. client configuration options.
         // NOTE We have to specifically configure omitting keys, since workload identity can
         // work purely using defaults, but partial config may be present in the environment.
         // Preference of conflicting configs (e.g. msi resource id vs. client id is handled in object store)
-        for key in self.env_config.keys() {
-            if !omit_keys.contains(key) {
-                if let Entry::Vacant(e) = self.config.entry(*key) {
-                    e.insert(self.env_config.get(key).unwrap().to_owned());
-                }
-            }
-        }
+        self.add_env_config(&omit_keys);
 
         Ok(self.config)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn helper(
+        config: &[(AzureConfigKey, &str)],
+        env_config: &[(AzureConfigKey, &str)],
+    ) -> AzureConfigHelper {
+        let to_map = |entries: &[(AzureConfigKey, &str)]| {
+            entries
+                .iter()
+                .map(|(key, value)| (*key, value.to_string()))
+                .collect()
+        };
+        AzureConfigHelper {
+            config: to_map(config),
+            env_config: to_map(env_config),
+            priority: Vec::from_iter([
+                AzureCredential::AccessKey,
+                AzureCredential::SasKey,
+                AzureCredential::BearerToken,
+                AzureCredential::ClientSecret,
+                AzureCredential::WorkloadIdentity,
+            ]),
+            credential: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_credential() {
+        assert_eq!(
+            AzureCredential::from_str("workload-identity").unwrap(),
+            AzureCredential::WorkloadIdentity
+        );
+        assert_eq!(
+            AzureCredential::from_str("MANAGED_IDENTITY").unwrap(),
+            AzureCredential::ManagedIdentity
+        );
+        assert!(AzureCredential::from_str("password").is_err());
+    }
+
+    #[test]
+    fn test_workload_identity_from_env() {
+        let config = helper(
+            &[(AzureConfigKey::AccessKey, "key")],
+            &[
+                (AzureConfigKey::ClientId, "client"),
+                (AzureConfigKey::AuthorityId, "tenant"),
+                (AzureConfigKey::FederatedTokenFile, "/var/run/token"),
+            ],
+        )
+        .with_credential(Some(AzureCredential::WorkloadIdentity))
+        .build()
+        .unwrap();
+        assert!(!config.contains_key(&AzureConfigKey::AccessKey));
+        assert_eq!(
+            config.get(&AzureConfigKey::FederatedTokenFile),
+            Some(&"/var/run/token".to_string())
+        );
+        assert_eq!(
+            config.get(&AzureConfigKey::ClientId),
+            Some(&"client".to_string())
+        );
+    }
+
+    #[test]
+    fn test_managed_identity() {
+        let config = helper(
+            &[
+                (AzureConfigKey::ClientId, "client"),
+                (AzureConfigKey::ClientSecret, "secret"),
+                (AzureConfigKey::AuthorityId, "tenant"),
+            ],
+            &[(AzureConfigKey::SasKey, "sas")],
+        )
+        .with_credential(Some(AzureCredential::ManagedIdentity))
+        .build()
+        .unwrap();
+        assert_eq!(
+            config.get(&AzureConfigKey::ClientId),
+            Some(&"client".to_string())
+        );
+        assert!(!config.contains_key(&AzureConfigKey::ClientSecret));
+        assert!(!config.contains_key(&AzureConfigKey::SasKey));
+    }
+
+    #[test]
+    fn test_azure_cli_and_missing_config() {
+        let config = helper(&[(AzureConfigKey::Token, "token")], &[])
+            .with_credential(Some(AzureCredential::AzureCli))
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.get(&AzureConfigKey::UseAzureCli),
+            Some(&"true".to_string())
+        );
+        assert!(!config.contains_key(&AzureConfigKey::Token));
+
+        let result = helper(&[(AzureConfigKey::ClientId, "client")], &[])
+            .with_credential(Some(AzureCredential::ClientSecret))
+            .build();
+        assert!(result.is_err());
+    }
+}
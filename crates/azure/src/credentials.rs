@@ -0,0 +1,42 @@
+//! Adapter for user provided credentials
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use deltalake_core::storage::{CredentialProviderRef, StorageCredential};
+use object_store::azure::{AzureAccessKey, AzureCredential};
+use object_store::CredentialProvider;
+
+const STORE_NAME: &str = "MicrosoftAzure";
+
+/// Supplies credentials of a user provided [`deltalake_core::storage::CredentialProvider`] to
+/// [`object_store`].
+#[derive(Debug)]
+pub(crate) struct UserCredentialProvider(pub CredentialProviderRef);
+
+#[async_trait]
+impl CredentialProvider for UserCredentialProvider {
+    type Credential = AzureCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<AzureCredential>> {
+        let credential =
+            self.0
+                .get_credential()
+                .await
+                .map_err(|err| object_store::Error::Generic {
+                    store: STORE_NAME,
+                    source: Box::new(err),
+                })?;
+        match credential {
+            StorageCredential::BearerToken(token) => {
+                Ok(Arc::new(AzureCredential::BearerToken(token)))
+            }
+            StorageCredential::AzureAccessKey(key) => Ok(Arc::new(AzureCredential::AccessKey(
+                AzureAccessKey::try_new(&key)?,
+            ))),
+            other => Err(object_store::Error::Generic {
+                store: STORE_NAME,
+                source: format!("unsupported credential for Azure: {}", other.kind()).into(),
+            }),
+        }
+    }
+}
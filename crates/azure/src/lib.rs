@@ -2,19 +2,34 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use deltalake_core::logstore::{default_logstore, logstores, LogStore, LogStoreFactory};
+use deltalake_core::logstore::{default_logstore, logstore_factories, LogStore, LogStoreFactory};
 use deltalake_core::storage::{
-    factories, limit_store_handler, url_prefix_handler, ObjectStoreFactory, ObjectStoreRef,
-    StorageOptions,
+    factories, limit_store_handler, retry_store_handler, url_prefix_handler, CredentialProviderRef,
+    ObjectStoreFactory, ObjectStoreRef, StorageOptions,
 };
 use deltalake_core::{DeltaResult, Path};
-use object_store::azure::AzureConfigKey;
-use object_store::parse_url_opts;
+use object_store::azure::{AzureConfigKey, MicrosoftAzureBuilder};
+use object_store::ObjectStoreScheme;
 use url::Url;
 
 mod config;
+mod credentials;
 pub mod error;
 
+/// Storage option keys of the Azure backend, in addition to the keys of [AzureConfigKey]
+pub mod azure_constants {
+    /// The credential used to authenticate against the storage account, one of `access_key`,
+    /// `sas_key`, `bearer_token`, `client_secret`, `workload_identity`, `managed_identity` or
+    /// `azure_cli`. Configuration for the credential is completed from the environment, e.g. the
+    /// `AZURE_CLIENT_ID`, `AZURE_TENANT_ID` and `AZURE_FEDERATED_TOKEN_FILE` variables set by
+    /// workload identity on AKS.
+    ///
+    /// If not set, the first credential with a complete configuration is used, falling back to
+    /// the managed identity of the host. Tokens of Entra ID credentials are refreshed
+    /// automatically before they expire.
+    pub const AZURE_CREDENTIAL_TYPE: &str = "AZURE_CREDENTIAL_TYPE";
+}
+
 trait AzureOptions {
     fn as_azure_options(&self) -> HashMap<AzureConfigKey, String>;
 }
@@ -42,9 +57,52 @@ impl ObjectStoreFactory for AzureFactory {
         url: &Url,
         options: &StorageOptions,
     ) -> DeltaResult<(ObjectStoreRef, Path)> {
-        let config = config::AzureConfigHelper::try_new(options.as_azure_options())?.build()?;
-        let (inner, prefix) = parse_url_opts(url, config)?;
-        let store = limit_store_handler(url_prefix_handler(inner, prefix.clone()), options);
+        self.build_store(url, options, None)
+    }
+
+    fn parse_url_opts_with_credentials(
+        &self,
+        url: &Url,
+        options: &StorageOptions,
+        credentials: CredentialProviderRef,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        self.build_store(url, options, Some(credentials))
+    }
+}
+
+impl AzureFactory {
+    fn build_store(
+        &self,
+        url: &Url,
+        options: &StorageOptions,
+        credentials: Option<CredentialProviderRef>,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let credential = options
+            .0
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(azure_constants::AZURE_CREDENTIAL_TYPE))
+            .map(|(_, value)| value.clone())
+            .or_else(|| std::env::var(azure_constants::AZURE_CREDENTIAL_TYPE).ok())
+            .map(|value| value.parse::<config::AzureCredential>())
+            .transpose()?;
+        let config = config::AzureConfigHelper::try_new(options.as_azure_options())?
+            .with_credential(credential)
+            .build()?;
+        let (_, prefix) = ObjectStoreScheme::parse(url)?;
+        let mut builder = config.into_iter().fold(
+            MicrosoftAzureBuilder::new().with_url(url.as_str()),
+            |builder, (key, value)| builder.with_config(key, value),
+        );
+        if let Some(credentials) = credentials {
+            builder = builder.with_credentials(Arc::new(
+                crate::credentials::UserCredentialProvider(credentials),
+            ));
+        }
+        let inner = builder.build()?;
+        let store = retry_store_handler(
+            limit_store_handler(url_prefix_handler(inner, prefix.clone()), options),
+            options,
+        )?;
         Ok((store, prefix))
     }
 }
@@ -66,6 +124,6 @@ pub fn register_handlers(_additional_prefixes: Option<Url>) {
     for scheme in ["az", "adl", "azure", "abfs", "abfss"].iter() {
         let url = Url::parse(&format!("{}://", scheme)).unwrap();
         factories().insert(url.clone(), factory.clone());
-        logstores().insert(url.clone(), factory.clone());
+        logstore_factories().insert(url.clone(), factory.clone());
     }
 }
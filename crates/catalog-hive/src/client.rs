@@ -0,0 +1,324 @@
+//! Minimal Thrift client for the Hive Metastore.
+//!
+//! Only the `get_table` call of the `ThriftHiveMetastore` service is implemented, using the
+//! binary protocol over a buffered socket transport, which is the default configuration of
+//! the metastore. Fields of the response which are not needed to locate the table are skipped.
+use thrift::protocol::{
+    TBinaryInputProtocol, TBinaryOutputProtocol, TFieldIdentifier, TInputProtocol,
+    TMessageIdentifier, TMessageType, TOutputProtocol, TStructIdentifier, TType,
+};
+use thrift::transport::{TBufferedReadTransport, TBufferedWriteTransport, TIoChannel, TTcpChannel};
+
+use crate::HiveError;
+
+/// Blocking client for the Hive Metastore Thrift API
+#[derive(Debug, Clone)]
+pub struct HiveMetastoreClient {
+    address: String,
+}
+
+impl HiveMetastoreClient {
+    /// Create a client for the metastore listening on `address`, e.g. `localhost:9083`
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+        }
+    }
+
+    /// Address of the metastore
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Get the storage location of a table.
+    ///
+    /// Tables created by Spark as data source tables store their location in the `path`
+    /// parameter of the serde info, which takes precedence over the location of the storage
+    /// descriptor.
+    pub fn get_table_location(
+        &self,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<String, HiveError> {
+        let mut channel = TTcpChannel::new();
+        channel.open(self.address.as_str())?;
+        let (read, write) = channel.split()?;
+        let mut input = TBinaryInputProtocol::new(TBufferedReadTransport::new(read), true);
+        let mut output = TBinaryOutputProtocol::new(TBufferedWriteTransport::new(write), true);
+
+        write_get_table(&mut output, database_name, table_name)?;
+        read_get_table(&mut input)
+    }
+}
+
+fn write_get_table<O: TOutputProtocol>(
+    output: &mut O,
+    database_name: &str,
+    table_name: &str,
+) -> thrift::Result<()> {
+    output.write_message_begin(&TMessageIdentifier::new("get_table", TMessageType::Call, 1))?;
+    output.write_struct_begin(&TStructIdentifier::new("get_table_args"))?;
+    output.write_field_begin(&TFieldIdentifier::new("dbname", TType::String, 1))?;
+    output.write_string(database_name)?;
+    output.write_field_end()?;
+    output.write_field_begin(&TFieldIdentifier::new("tbl_name", TType::String, 2))?;
+    output.write_string(table_name)?;
+    output.write_field_end()?;
+    output.write_field_stop()?;
+    output.write_struct_end()?;
+    output.write_message_end()?;
+    output.flush()
+}
+
+fn read_get_table<I: TInputProtocol>(input: &mut I) -> Result<String, HiveError> {
+    let message = input.read_message_begin()?;
+    if message.message_type == TMessageType::Exception {
+        let err = thrift::Error::read_application_error_from_in_protocol(input)?;
+        input.read_message_end()?;
+        return Err(thrift::Error::Application(err).into());
+    }
+
+    let mut location = None;
+    let mut error = None;
+    input.read_struct_begin()?;
+    loop {
+        let field = input.read_field_begin()?;
+        match (field.id, field.field_type) {
+            (_, TType::Stop) => break,
+            // success: Table
+            (Some(0), TType::Struct) => location = read_table_location(input)?,
+            // o1: MetaException
+            (Some(1), TType::Struct) => {
+                error = Some(HiveError::Metastore {
+                    message: read_exception_message(input)?,
+                })
+            }
+            // o2: NoSuchObjectException
+            (Some(2), TType::Struct) => {
+                error = Some(HiveError::NoSuchTable {
+                    message: read_exception_message(input)?,
+                })
+            }
+            (_, field_type) => input.skip(field_type)?,
+        }
+        input.read_field_end()?;
+    }
+    input.read_struct_end()?;
+    input.read_message_end()?;
+
+    match error {
+        Some(err) => Err(err),
+        None => location.ok_or(HiveError::MissingMetadata {
+            metadata: "Location".to_string(),
+        }),
+    }
+}
+
+/// Read a `Table` struct, returning the location of its storage descriptor (field 7)
+fn read_table_location<I: TInputProtocol>(input: &mut I) -> thrift::Result<Option<String>> {
+    let mut location = None;
+    input.read_struct_begin()?;
+    loop {
+        let field = input.read_field_begin()?;
+        match (field.id, field.field_type) {
+            (_, TType::Stop) => break,
+            (Some(7), TType::Struct) => location = read_storage_location(input)?,
+            (_, field_type) => input.skip(field_type)?,
+        }
+        input.read_field_end()?;
+    }
+    input.read_struct_end()?;
+    Ok(location)
+}
+
+/// Read a `StorageDescriptor` struct, returning the `path` serde parameter (field 7) or the
+/// location (field 2)
+fn read_storage_location<I: TInputProtocol>(input: &mut I) -> thrift::Result<Option<String>> {
+    let mut location = None;
+    let mut path = None;
+    input.read_struct_begin()?;
+    loop {
+        let field = input.read_field_begin()?;
+        match (field.id, field.field_type) {
+            (_, TType::Stop) => break,
+            (Some(2), TType::String) => location = Some(input.read_string()?),
+            (Some(7), TType::Struct) => path = read_serde_path(input)?,
+            (_, field_type) => input.skip(field_type)?,
+        }
+        input.read_field_end()?;
+    }
+    input.read_struct_end()?;
+    Ok(path.or(location))
+}
+
+/// Read a `SerDeInfo` struct, returning the `path` entry of its parameters (field 3)
+fn read_serde_path<I: TInputProtocol>(input: &mut I) -> thrift::Result<Option<String>> {
+    let mut path = None;
+    input.read_struct_begin()?;
+    loop {
+        let field = input.read_field_begin()?;
+        match (field.id, field.field_type) {
+            (_, TType::Stop) => break,
+            (Some(3), TType::Map) => {
+                let map = input.read_map_begin()?;
+                for _ in 0..map.size {
+                    let key = input.read_string()?;
+                    let value = input.read_string()?;
+                    if key == "path" {
+                        path = Some(value);
+                    }
+                }
+                input.read_map_end()?;
+            }
+            (_, field_type) => input.skip(field_type)?,
+        }
+        input.read_field_end()?;
+    }
+    input.read_struct_end()?;
+    Ok(path)
+}
+
+/// Read the message (field 1) of a metastore exception struct
+fn read_exception_message<I: TInputProtocol>(input: &mut I) -> thrift::Result<String> {
+    let mut message = String::new();
+    input.read_struct_begin()?;
+    loop {
+        let field = input.read_field_begin()?;
+        match (field.id, field.field_type) {
+            (_, TType::Stop) => break,
+            (Some(1), TType::String) => message = input.read_string()?,
+            (_, field_type) => input.skip(field_type)?,
+        }
+        input.read_field_end()?;
+    }
+    input.read_struct_end()?;
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use thrift::protocol::TMapIdentifier;
+
+    use super::*;
+
+    /// Serve a single `get_table` call, responding with a table or a `NoSuchObjectException`
+    fn serve_get_table(listener: TcpListener, location: Option<&'static str>) {
+        let (stream, _) = listener.accept().unwrap();
+        let (read, write) = TTcpChannel::with_stream(stream).split().unwrap();
+        let mut input = TBinaryInputProtocol::new(TBufferedReadTransport::new(read), true);
+        let mut output = TBinaryOutputProtocol::new(TBufferedWriteTransport::new(write), true);
+
+        let message = input.read_message_begin().unwrap();
+        assert_eq!(message.name, "get_table");
+        input.read_struct_begin().unwrap();
+        let mut args = vec![];
+        loop {
+            let field = input.read_field_begin().unwrap();
+            if field.field_type == TType::Stop {
+                break;
+            }
+            args.push(input.read_string().unwrap());
+            input.read_field_end().unwrap();
+        }
+        assert_eq!(args, vec!["db", "table"]);
+
+        let reply = TMessageIdentifier::new("get_table", TMessageType::Reply, 1);
+        output.write_message_begin(&reply).unwrap();
+        output
+            .write_struct_begin(&TStructIdentifier::new("get_table_result"))
+            .unwrap();
+        match location {
+            Some(location) => {
+                output
+                    .write_field_begin(&TFieldIdentifier::new("success", TType::Struct, 0))
+                    .unwrap();
+                output
+                    .write_struct_begin(&TStructIdentifier::new("Table"))
+                    .unwrap();
+                output
+                    .write_field_begin(&TFieldIdentifier::new("tableName", TType::String, 1))
+                    .unwrap();
+                output.write_string("table").unwrap();
+                output.write_field_end().unwrap();
+                output
+                    .write_field_begin(&TFieldIdentifier::new("sd", TType::Struct, 7))
+                    .unwrap();
+                output
+                    .write_struct_begin(&TStructIdentifier::new("StorageDescriptor"))
+                    .unwrap();
+                output
+                    .write_field_begin(&TFieldIdentifier::new("location", TType::String, 2))
+                    .unwrap();
+                output.write_string("s3a://bucket/placeholder").unwrap();
+                output.write_field_end().unwrap();
+                output
+                    .write_field_begin(&TFieldIdentifier::new("serdeInfo", TType::Struct, 7))
+                    .unwrap();
+                output
+                    .write_struct_begin(&TStructIdentifier::new("SerDeInfo"))
+                    .unwrap();
+                output
+                    .write_field_begin(&TFieldIdentifier::new("parameters", TType::Map, 3))
+                    .unwrap();
+                output
+                    .write_map_begin(&TMapIdentifier::new(TType::String, TType::String, 1))
+                    .unwrap();
+                output.write_string("path").unwrap();
+                output.write_string(location).unwrap();
+                output.write_map_end().unwrap();
+                output.write_field_end().unwrap();
+                output.write_field_stop().unwrap();
+                output.write_struct_end().unwrap();
+                output.write_field_end().unwrap();
+                output.write_field_stop().unwrap();
+                output.write_struct_end().unwrap();
+                output.write_field_end().unwrap();
+                output.write_field_stop().unwrap();
+                output.write_struct_end().unwrap();
+            }
+            None => {
+                output
+                    .write_field_begin(&TFieldIdentifier::new("o2", TType::Struct, 2))
+                    .unwrap();
+                output
+                    .write_struct_begin(&TStructIdentifier::new("NoSuchObjectException"))
+                    .unwrap();
+                output
+                    .write_field_begin(&TFieldIdentifier::new("message", TType::String, 1))
+                    .unwrap();
+                output.write_string("db.table table not found").unwrap();
+                output.write_field_end().unwrap();
+                output.write_field_stop().unwrap();
+                output.write_struct_end().unwrap();
+            }
+        }
+        output.write_field_end().unwrap();
+        output.write_field_stop().unwrap();
+        output.write_struct_end().unwrap();
+        output.write_message_end().unwrap();
+        output.flush().unwrap();
+    }
+
+    fn client_for(location: Option<&'static str>) -> HiveMetastoreClient {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || serve_get_table(listener, location));
+        HiveMetastoreClient::new(address)
+    }
+
+    #[test]
+    fn test_get_table_location() {
+        let client = client_for(Some("s3a://bucket/table"));
+        let location = client.get_table_location("db", "table").unwrap();
+        assert_eq!(location, "s3a://bucket/table");
+    }
+
+    #[test]
+    fn test_get_missing_table() {
+        let client = client_for(None);
+        let err = client.get_table_location("db", "table").unwrap_err();
+        assert!(matches!(err, HiveError::NoSuchTable { .. }), "{err:?}");
+    }
+}
@@ -0,0 +1,185 @@
+//! Hive Metastore Data Catalog.
+//!
+//! Resolves the storage location of tables registered in a Hive Metastore, e.g. by Spark,
+//! through the Thrift API of the metastore. After registering the catalog with
+//! [`register_catalog`], tables can be loaded with
+//! [`DeltaTableBuilder::from_catalog`](deltalake_core::DeltaTableBuilder::from_catalog):
+//!
+//! ```rust,no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use deltalake_catalog_hive::{register_catalog, HiveDataCatalog};
+//! use deltalake_core::DeltaTableBuilder;
+//!
+//! register_catalog(HiveDataCatalog::try_new("thrift://localhost:9083")?);
+//! let table = DeltaTableBuilder::from_catalog("hive", "database", "table")
+//!     .await?
+//!     .load()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+use std::sync::Arc;
+
+use deltalake_core::data_catalog::{data_catalogs, DataCatalog, DataCatalogError};
+use url::Url;
+
+pub mod client;
+
+pub use client::HiveMetastoreClient;
+
+/// Environment variable holding the uri of the metastore, e.g. `thrift://localhost:9083`
+pub const HIVE_METASTORE_URI: &str = "HIVE_METASTORE_URI";
+
+/// Name under which the catalog is registered
+pub const CATALOG_NAME: &str = "hive";
+
+const DEFAULT_PORT: u16 = 9083;
+
+// Placeholder suffix created by Spark for the location of data source tables
+const PLACEHOLDER_SUFFIX: &str = "-__PLACEHOLDER__";
+
+#[derive(thiserror::Error, Debug)]
+pub enum HiveError {
+    /// Missing metadata in the catalog
+    #[error("Missing Metadata {metadata} in the Data Catalog")]
+    MissingMetadata {
+        /// The missing metadata property
+        metadata: String,
+    },
+
+    /// The metastore uri is not set
+    #[error("Missing Hive Metastore uri, set HIVE_METASTORE_URI")]
+    MissingUri,
+
+    /// The metastore uri is invalid
+    #[error("Invalid Hive Metastore uri: {uri}")]
+    InvalidUri {
+        /// The invalid uri
+        uri: String,
+    },
+
+    /// The table or database does not exist
+    #[error("Table not found: {message}")]
+    NoSuchTable {
+        /// Message returned by the metastore
+        message: String,
+    },
+
+    /// Error returned by the metastore
+    #[error("Hive Metastore error: {message}")]
+    Metastore {
+        /// Message returned by the metastore
+        message: String,
+    },
+
+    /// Error calling the Thrift API
+    #[error("Failed in a Thrift call: {source}")]
+    Thrift {
+        #[from]
+        source: thrift::Error,
+    },
+
+    /// The blocking metastore call panicked or was cancelled
+    #[error("Failed to call the Hive Metastore: {source}")]
+    Task {
+        #[from]
+        source: tokio::task::JoinError,
+    },
+}
+
+impl From<HiveError> for DataCatalogError {
+    fn from(val: HiveError) -> Self {
+        DataCatalogError::Generic {
+            catalog: CATALOG_NAME,
+            source: Box::new(val),
+        }
+    }
+}
+
+/// A Hive Metastore implementation of the [`DataCatalog`] trait
+#[derive(Debug, Clone)]
+pub struct HiveDataCatalog {
+    client: HiveMetastoreClient,
+}
+
+impl HiveDataCatalog {
+    /// Creates a new [HiveDataCatalog] for the metastore at `uri`, e.g. `thrift://localhost:9083`
+    pub fn try_new(uri: &str) -> Result<Self, HiveError> {
+        let invalid_uri = || HiveError::InvalidUri {
+            uri: uri.to_string(),
+        };
+        let url = Url::parse(uri).map_err(|_| invalid_uri())?;
+        if url.scheme() != "thrift" {
+            return Err(invalid_uri());
+        }
+        let host = url.host_str().ok_or_else(invalid_uri)?;
+        let port = url.port().unwrap_or(DEFAULT_PORT);
+        Ok(Self {
+            client: HiveMetastoreClient::new(format!("{host}:{port}")),
+        })
+    }
+
+    /// Creates a new [HiveDataCatalog] for the metastore at [HIVE_METASTORE_URI]
+    pub fn from_env() -> Result<Self, HiveError> {
+        let uri = std::env::var(HIVE_METASTORE_URI).map_err(|_| HiveError::MissingUri)?;
+        Self::try_new(&uri)
+    }
+
+    /// Client used to call the metastore
+    pub fn client(&self) -> &HiveMetastoreClient {
+        &self.client
+    }
+}
+
+#[async_trait::async_trait]
+impl DataCatalog for HiveDataCatalog {
+    /// Get the table storage location from the Hive Metastore
+    async fn get_table_storage_location(
+        &self,
+        _catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<String, DataCatalogError> {
+        let client = self.client.clone();
+        let (database_name, table_name) = (database_name.to_string(), table_name.to_string());
+        let location = tokio::task::spawn_blocking(move || {
+            client.get_table_location(&database_name, &table_name)
+        })
+        .await
+        .map_err(HiveError::from)??;
+
+        let location = location.replace("s3a://", "s3://");
+        match location.strip_suffix(PLACEHOLDER_SUFFIX) {
+            Some(location) => Ok(location.to_string()),
+            None => Ok(location),
+        }
+    }
+}
+
+/// Register `catalog` as the `hive` catalog used by
+/// [`DeltaTableBuilder::from_catalog`](deltalake_core::DeltaTableBuilder::from_catalog)
+pub fn register_catalog(catalog: HiveDataCatalog) {
+    data_catalogs().insert(CATALOG_NAME.to_string(), Arc::new(catalog));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_new() {
+        let catalog = HiveDataCatalog::try_new("thrift://metastore:9084").unwrap();
+        assert_eq!(catalog.client().address(), "metastore:9084");
+        let catalog = HiveDataCatalog::try_new("thrift://metastore").unwrap();
+        assert_eq!(catalog.client().address(), "metastore:9083");
+
+        assert!(HiveDataCatalog::try_new("http://metastore:9083").is_err());
+        assert!(HiveDataCatalog::try_new("metastore:9083").is_err());
+    }
+
+    #[test]
+    fn test_register_catalog() {
+        register_catalog(HiveDataCatalog::try_new("thrift://localhost").unwrap());
+        assert!(data_catalogs().contains_key(CATALOG_NAME));
+    }
+}
@@ -0,0 +1,255 @@
+//! Catalog keeping the registered tables in files of an object store
+//!
+//! Every table is registered by a json file at `<database_name>/<table_name>.json` below the
+//! root of the catalog, holding the location of the table. Tables are registered with
+//! conditional writes, so concurrent registrations of the same name fail with
+//! [`DataCatalogError::TableAlreadyExists`] instead of overwriting each other.
+
+use std::collections::HashMap;
+
+use object_store::path::Path;
+use object_store::{PutMode, PutOptions};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{CatalogTable, DataCatalog, DataCatalogError, DataCatalogResult};
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::storage::{factories, ObjectStoreRef};
+use crate::table::builder::ensure_table_uri;
+
+const CATALOG_NAME: &str = "file";
+const TABLE_FILE_EXTENSION: &str = "json";
+
+#[derive(Serialize, Deserialize)]
+struct TableEntry {
+    location: String,
+}
+
+/// A [`DataCatalog`] registering tables in files of an object store, see the
+/// [module level documentation](self) for the layout
+#[derive(Debug, Clone)]
+pub struct FileCatalog {
+    store: ObjectStoreRef,
+}
+
+impl FileCatalog {
+    /// Create a [`FileCatalog`] in a store whose root is the root of the catalog
+    pub fn new(store: ObjectStoreRef) -> Self {
+        Self { store }
+    }
+
+    /// Create a [`FileCatalog`] rooted at `location`, e.g. `s3://bucket/catalog` or a local path
+    pub fn try_new(
+        location: impl AsRef<str>,
+        storage_options: HashMap<String, String>,
+    ) -> DeltaResult<Self> {
+        let url = ensure_table_uri(location)?;
+        let scheme = Url::parse(&format!("{}://", url.scheme()))
+            .map_err(|_| DeltaTableError::InvalidTableLocation(url.clone().into()))?;
+        let factory = factories()
+            .get(&scheme)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| DeltaTableError::InvalidTableLocation(url.clone().into()))?;
+        let (store, _prefix) = factory.parse_url_opts(&url, &storage_options.into())?;
+        Ok(Self::new(store))
+    }
+
+    async fn read_entry(
+        &self,
+        database_name: &str,
+        table_name: &str,
+    ) -> DataCatalogResult<TableEntry> {
+        let result = match self.store.get(&table_path(database_name, table_name)).await {
+            Ok(result) => result,
+            Err(object_store::Error::NotFound { .. }) => {
+                return Err(DataCatalogError::TableNotFound {
+                    database_name: database_name.to_string(),
+                    table_name: table_name.to_string(),
+                })
+            }
+            Err(err) => return Err(catalog_error(err)),
+        };
+        let bytes = result.bytes().await.map_err(catalog_error)?;
+        serde_json::from_slice(&bytes).map_err(catalog_error)
+    }
+
+    async fn write_entry(
+        &self,
+        database_name: &str,
+        table_name: &str,
+        location: &str,
+        mode: PutMode,
+    ) -> DataCatalogResult<CatalogTable> {
+        let entry = TableEntry {
+            location: location.to_string(),
+        };
+        let bytes = serde_json::to_vec(&entry).map_err(catalog_error)?;
+        let options = PutOptions {
+            mode,
+            ..Default::default()
+        };
+        match self
+            .store
+            .put_opts(
+                &table_path(database_name, table_name),
+                bytes.into(),
+                options,
+            )
+            .await
+        {
+            Ok(_) => Ok(CatalogTable {
+                database_name: database_name.to_string(),
+                table_name: table_name.to_string(),
+                location: entry.location,
+            }),
+            Err(object_store::Error::AlreadyExists { .. }) => {
+                Err(DataCatalogError::TableAlreadyExists {
+                    database_name: database_name.to_string(),
+                    table_name: table_name.to_string(),
+                })
+            }
+            Err(err) => Err(catalog_error(err)),
+        }
+    }
+}
+
+fn table_path(database_name: &str, table_name: &str) -> Path {
+    let file_name = format!("{table_name}.{TABLE_FILE_EXTENSION}");
+    Path::from_iter([database_name, file_name.as_str()])
+}
+
+fn catalog_error(err: impl std::error::Error + Send + Sync + 'static) -> DataCatalogError {
+    DataCatalogError::Generic {
+        catalog: CATALOG_NAME,
+        source: Box::new(err),
+    }
+}
+
+#[async_trait::async_trait]
+impl DataCatalog for FileCatalog {
+    async fn get_table_storage_location(
+        &self,
+        _catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<String, DataCatalogError> {
+        Ok(self.read_entry(database_name, table_name).await?.location)
+    }
+
+    fn supports_create_table(&self) -> bool {
+        true
+    }
+
+    async fn create_table(
+        &self,
+        database_name: &str,
+        table_name: &str,
+        location: &str,
+    ) -> DataCatalogResult<CatalogTable> {
+        self.write_entry(database_name, table_name, location, PutMode::Create)
+            .await
+    }
+
+    async fn update_table_location(
+        &self,
+        database_name: &str,
+        table_name: &str,
+        location: &str,
+    ) -> DataCatalogResult<CatalogTable> {
+        self.read_entry(database_name, table_name).await?;
+        self.write_entry(database_name, table_name, location, PutMode::Overwrite)
+            .await
+    }
+
+    async fn list_tables(&self, database_name: &str) -> DataCatalogResult<Vec<String>> {
+        let prefix = Path::from_iter([database_name]);
+        let listing = self
+            .store
+            .list_with_delimiter(Some(&prefix))
+            .await
+            .map_err(catalog_error)?;
+        let mut tables = listing
+            .objects
+            .into_iter()
+            .filter_map(|meta| {
+                meta.location
+                    .filename()
+                    .and_then(|name| name.strip_suffix(&format!(".{TABLE_FILE_EXTENSION}")))
+                    .map(|name| name.to_string())
+            })
+            .collect::<Vec<_>>();
+        tables.sort();
+        Ok(tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_catalog() {
+        let catalog = FileCatalog::new(Arc::new(InMemory::new()));
+        assert!(matches!(
+            catalog.get_table("db", "table").await,
+            Err(DataCatalogError::TableNotFound { .. })
+        ));
+
+        catalog
+            .create_table("db", "table", "s3://bucket/table")
+            .await
+            .unwrap();
+        catalog
+            .create_table("db", "other", "s3://bucket/other")
+            .await
+            .unwrap();
+        assert!(matches!(
+            catalog
+                .create_table("db", "table", "s3://bucket/moved")
+                .await,
+            Err(DataCatalogError::TableAlreadyExists { .. })
+        ));
+        assert_eq!(
+            catalog.get_table("db", "table").await.unwrap().location,
+            "s3://bucket/table"
+        );
+
+        catalog
+            .update_table_location("db", "table", "s3://bucket/moved")
+            .await
+            .unwrap();
+        assert_eq!(
+            catalog.get_table("db", "table").await.unwrap().location,
+            "s3://bucket/moved"
+        );
+        assert!(matches!(
+            catalog
+                .update_table_location("db", "missing", "s3://bucket/missing")
+                .await,
+            Err(DataCatalogError::TableNotFound { .. })
+        ));
+
+        assert_eq!(catalog.list_tables("db").await.unwrap(), ["other", "table"]);
+        assert!(catalog.list_tables("missing").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_catalog_local() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let root = tmp_dir.path().join("catalog");
+        let catalog = FileCatalog::try_new(root.to_str().unwrap(), HashMap::new()).unwrap();
+        catalog
+            .create_table("db", "table", "s3://bucket/table")
+            .await
+            .unwrap();
+        assert!(root.join("db").join("table.json").exists());
+
+        // a second catalog at the same location sees the registered table
+        let catalog = FileCatalog::try_new(root.to_str().unwrap(), HashMap::new()).unwrap();
+        assert_eq!(catalog.list_tables("db").await.unwrap(), ["table"]);
+    }
+}
@@ -0,0 +1,146 @@
+//! Catalog keeping the registered tables in memory
+
+use std::collections::BTreeMap;
+
+use parking_lot::RwLock;
+
+use super::{CatalogTable, DataCatalog, DataCatalogError, DataCatalogResult};
+
+/// A [`DataCatalog`] registering tables in memory, e.g. for tests or short lived sessions
+#[derive(Debug, Default)]
+pub struct InMemoryCatalog {
+    // locations of the tables by database and table name
+    tables: RwLock<BTreeMap<String, BTreeMap<String, String>>>,
+}
+
+impl InMemoryCatalog {
+    /// Create a new, empty [`InMemoryCatalog`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn table_not_found(database_name: &str, table_name: &str) -> DataCatalogError {
+    DataCatalogError::TableNotFound {
+        database_name: database_name.to_string(),
+        table_name: table_name.to_string(),
+    }
+}
+
+fn catalog_table(database_name: &str, table_name: &str, location: &str) -> CatalogTable {
+    CatalogTable {
+        database_name: database_name.to_string(),
+        table_name: table_name.to_string(),
+        location: location.to_string(),
+    }
+}
+
+#[async_trait::async_trait]
+impl DataCatalog for InMemoryCatalog {
+    async fn get_table_storage_location(
+        &self,
+        _catalog_id: Option<String>,
+        database_name: &str,
+        table_name: &str,
+    ) -> Result<String, DataCatalogError> {
+        self.tables
+            .read()
+            .get(database_name)
+            .and_then(|tables| tables.get(table_name))
+            .cloned()
+            .ok_or_else(|| table_not_found(database_name, table_name))
+    }
+
+    fn supports_create_table(&self) -> bool {
+        true
+    }
+
+    async fn create_table(
+        &self,
+        database_name: &str,
+        table_name: &str,
+        location: &str,
+    ) -> DataCatalogResult<CatalogTable> {
+        let mut tables = self.tables.write();
+        let database = tables.entry(database_name.to_string()).or_default();
+        if database.contains_key(table_name) {
+            return Err(DataCatalogError::TableAlreadyExists {
+                database_name: database_name.to_string(),
+                table_name: table_name.to_string(),
+            });
+        }
+        database.insert(table_name.to_string(), location.to_string());
+        Ok(catalog_table(database_name, table_name, location))
+    }
+
+    async fn update_table_location(
+        &self,
+        database_name: &str,
+        table_name: &str,
+        location: &str,
+    ) -> DataCatalogResult<CatalogTable> {
+        let mut tables = self.tables.write();
+        let current = tables
+            .get_mut(database_name)
+            .and_then(|tables| tables.get_mut(table_name))
+            .ok_or_else(|| table_not_found(database_name, table_name))?;
+        *current = location.to_string();
+        Ok(catalog_table(database_name, table_name, location))
+    }
+
+    async fn list_tables(&self, database_name: &str) -> DataCatalogResult<Vec<String>> {
+        Ok(self
+            .tables
+            .read()
+            .get(database_name)
+            .map(|tables| tables.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_memory_catalog() {
+        let catalog = InMemoryCatalog::new();
+        assert!(matches!(
+            catalog.get_table("db", "table").await,
+            Err(DataCatalogError::TableNotFound { .. })
+        ));
+
+        catalog
+            .create_table("db", "table", "memory:///table")
+            .await
+            .unwrap();
+        catalog
+            .create_table("db", "other", "memory:///other")
+            .await
+            .unwrap();
+        assert!(matches!(
+            catalog.create_table("db", "table", "memory:///moved").await,
+            Err(DataCatalogError::TableAlreadyExists { .. })
+        ));
+        assert_eq!(
+            catalog
+                .get_table_storage_location(None, "db", "table")
+                .await
+                .unwrap(),
+            "memory:///table"
+        );
+
+        let table = catalog
+            .update_table_location("db", "table", "memory:///moved")
+            .await
+            .unwrap();
+        assert_eq!(catalog.get_table("db", "table").await.unwrap(), table);
+        assert!(catalog
+            .update_table_location("db", "missing", "memory:///missing")
+            .await
+            .is_err());
+
+        assert_eq!(catalog.list_tables("db").await.unwrap(), ["other", "table"]);
+        assert!(catalog.list_tables("missing").await.unwrap().is_empty());
+    }
+}
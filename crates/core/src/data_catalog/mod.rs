@@ -1,12 +1,28 @@
 //! Catalog abstraction for Delta Table
+//!
+//! Catalogs map the names of tables within databases to their storage location. Every catalog
+//! integration implements [`DataCatalog`], which only requires resolving the location of a table.
+//! Catalogs which can also register tables implement the remaining methods, which allows
+//! [`CreateBuilder::with_catalog`](crate::operations::create::CreateBuilder::with_catalog) to
+//! register new tables. [`InMemoryCatalog`] and [`FileCatalog`] are reference implementations
+//! which are also useful for tests and for deployments without a metastore.
 
 use std::fmt::Debug;
+use std::sync::{Arc, OnceLock};
 
+use dashmap::DashMap;
+
+use crate::errors::DeltaTableError;
+
+pub use file::FileCatalog;
+pub use memory::InMemoryCatalog;
 #[cfg(feature = "unity-experimental")]
 pub use unity::*;
 
 #[cfg(feature = "unity-experimental")]
 pub mod client;
+pub mod file;
+pub mod memory;
 #[cfg(feature = "datafusion")]
 pub mod storage;
 #[cfg(feature = "unity-experimental")]
@@ -74,9 +90,57 @@ pub enum DataCatalogError {
         /// configuration key
         key: String,
     },
+
+    /// The table is not registered in the catalog
+    #[error("Table {database_name}.{table_name} does not exist in the catalog")]
+    TableNotFound {
+        /// Name of the database
+        database_name: String,
+        /// Name of the table
+        table_name: String,
+    },
+
+    /// A table with the same name is already registered in the catalog
+    #[error("Table {database_name}.{table_name} already exists in the catalog")]
+    TableAlreadyExists {
+        /// Name of the database
+        database_name: String,
+        /// Name of the table
+        table_name: String,
+    },
+
+    /// The catalog does not implement the operation
+    #[error("The catalog does not support the {operation} operation")]
+    NotSupported {
+        /// Name of the operation
+        operation: &'static str,
+    },
+}
+
+impl From<DataCatalogError> for DeltaTableError {
+    fn from(err: DataCatalogError) -> Self {
+        DeltaTableError::GenericError {
+            source: Box::new(err),
+        }
+    }
+}
+
+/// A table registered in a [`DataCatalog`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogTable {
+    /// Name of the database containing the table
+    pub database_name: String,
+    /// Name of the table
+    pub table_name: String,
+    /// Storage location of the table
+    pub location: String,
 }
 
 /// Abstractions for data catalog for the Delta table. To add support for new cloud, simply implement this trait.
+///
+/// Only [`get_table_storage_location`](Self::get_table_storage_location) is required, the
+/// methods registering tables return [`DataCatalogError::NotSupported`] unless implemented.
+/// Catalogs implementing them also override [`supports_create_table`](Self::supports_create_table).
 #[async_trait::async_trait]
 pub trait DataCatalog: Send + Sync + Debug {
     /// Get the table storage location from the Data Catalog
@@ -86,4 +150,75 @@ pub trait DataCatalog: Send + Sync + Debug {
         database_name: &str,
         table_name: &str,
     ) -> Result<String, DataCatalogError>;
+
+    /// Get a table registered in the catalog
+    async fn get_table(
+        &self,
+        database_name: &str,
+        table_name: &str,
+    ) -> DataCatalogResult<CatalogTable> {
+        let location = self
+            .get_table_storage_location(None, database_name, table_name)
+            .await?;
+        Ok(CatalogTable {
+            database_name: database_name.to_string(),
+            table_name: table_name.to_string(),
+            location,
+        })
+    }
+
+    /// Whether tables can be registered with [`create_table`](Self::create_table)
+    fn supports_create_table(&self) -> bool {
+        false
+    }
+
+    /// Register a table stored at `location`.
+    ///
+    /// Fails with [`DataCatalogError::TableAlreadyExists`] if the name is already taken.
+    async fn create_table(
+        &self,
+        database_name: &str,
+        table_name: &str,
+        location: &str,
+    ) -> DataCatalogResult<CatalogTable> {
+        let _ = (database_name, table_name, location);
+        Err(DataCatalogError::NotSupported {
+            operation: "create_table",
+        })
+    }
+
+    /// Change the storage location of a registered table.
+    ///
+    /// Fails with [`DataCatalogError::TableNotFound`] if the table is not registered.
+    async fn update_table_location(
+        &self,
+        database_name: &str,
+        table_name: &str,
+        location: &str,
+    ) -> DataCatalogResult<CatalogTable> {
+        let _ = (database_name, table_name, location);
+        Err(DataCatalogError::NotSupported {
+            operation: "update_table_location",
+        })
+    }
+
+    /// Names of the tables registered in a database, in ascending order
+    async fn list_tables(&self, database_name: &str) -> DataCatalogResult<Vec<String>> {
+        let _ = database_name;
+        Err(DataCatalogError::NotSupported {
+            operation: "list_tables",
+        })
+    }
+}
+
+/// Reference to a [`DataCatalog`]
+pub type DataCatalogRef = Arc<dyn DataCatalog>;
+
+/// Registry of [DataCatalog]s by name
+pub type DataCatalogRegistry = Arc<DashMap<String, Arc<dyn DataCatalog>>>;
+
+/// Data catalogs available to [`DeltaTableBuilder::from_catalog`](crate::DeltaTableBuilder::from_catalog)
+pub fn data_catalogs() -> DataCatalogRegistry {
+    static REGISTRY: OnceLock<DataCatalogRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(DataCatalogRegistry::default).clone()
 }
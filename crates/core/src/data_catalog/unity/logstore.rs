@@ -0,0 +1,354 @@
+//! Log store for Unity Catalog managed tables.
+//!
+//! Managed tables use Unity Catalog as their commit coordinator: every commit is first staged
+//! under `_delta_log/_commits` and then registered with the catalog, which only accepts a single
+//! commit per version. Once accepted, the staged commit is backfilled into the regular delta log,
+//! so readers that are not aware of the coordinator still observe a consistent table.
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::Utc;
+use object_store::{path::Path, ObjectStore};
+use tracing::warn;
+use url::Url;
+use uuid::Uuid;
+
+use super::models::{CommitRequest, CommitResponse, GetCommitsResponse, UnityCommit};
+use super::storage::{catalog_error, UnityTableHandle};
+use crate::logstore::{
+    abort_commit_entry, get_latest_version, read_commit_entry, LogStore, LogStoreConfig,
+    LogStoreFactory,
+};
+use crate::operations::transaction::TransactionError;
+use crate::storage::{commit_uri_from_version, ObjectStoreRef, StorageOptions};
+use crate::{DeltaResult, DeltaTableError};
+
+/// Error codes returned by the commit coordinator when the version is already taken
+const CONFLICT_ERROR_CODES: [&str; 2] = ["ALREADY_EXISTS", "RESOURCE_ALREADY_EXISTS"];
+
+/// [`LogStore`] implementation routing commits through the Unity Catalog commit coordinator
+pub struct UnityCatalogLogStore {
+    storage: ObjectStoreRef,
+    table: Arc<UnityTableHandle>,
+    config: LogStoreConfig,
+    /// Latest version known to be backfilled into the delta log, -1 if none is known yet
+    latest_backfilled_version: AtomicI64,
+}
+
+impl std::fmt::Debug for UnityCatalogLogStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "UnityCatalogLogStore({})", self.config.location)
+    }
+}
+
+impl UnityCatalogLogStore {
+    pub(crate) fn new(
+        storage: ObjectStoreRef,
+        table: Arc<UnityTableHandle>,
+        config: LogStoreConfig,
+    ) -> Self {
+        Self {
+            storage,
+            table,
+            config,
+            latest_backfilled_version: AtomicI64::new(-1),
+        }
+    }
+
+    fn record_backfilled_version(&self, version: i64) {
+        self.latest_backfilled_version
+            .fetch_max(version, Ordering::Relaxed);
+    }
+
+    fn latest_backfilled_version(&self) -> Option<i64> {
+        let version = self.latest_backfilled_version.load(Ordering::Relaxed);
+        (version >= 0).then_some(version)
+    }
+
+    fn commits_path(&self) -> Path {
+        self.log_path().child("_commits")
+    }
+
+    /// Commits that were accepted by the coordinator but are not yet part of the delta log
+    async fn unbackfilled_commits(
+        &self,
+        start_version: i64,
+    ) -> DeltaResult<(Vec<UnityCommit>, i64)> {
+        let table = self.table.resolve().await?;
+        match self
+            .table
+            .catalog()
+            .get_commits(
+                &table.table_id,
+                table.storage_location.as_str(),
+                start_version,
+            )
+            .await
+            .map_err(catalog_error)?
+        {
+            GetCommitsResponse::Success {
+                commits,
+                latest_table_version,
+            } => Ok((commits, latest_table_version)),
+            GetCommitsResponse::Error(err) => Err(DeltaTableError::GenericError {
+                source: Box::new(err),
+            }),
+        }
+    }
+
+    /// Copy an accepted commit into the delta log
+    async fn backfill(&self, commit: &UnityCommit) -> DeltaResult<()> {
+        let staged = self.commits_path().child(commit.file_name.as_str());
+        match self
+            .storage
+            .copy_if_not_exists(&staged, &commit_uri_from_version(commit.version))
+            .await
+        {
+            Ok(_) | Err(object_store::Error::AlreadyExists { .. }) => {}
+            // not all stores support conditional copies, an accepted commit is immutable anyway
+            Err(object_store::Error::NotImplemented) => {
+                self.storage
+                    .copy(&staged, &commit_uri_from_version(commit.version))
+                    .await?
+            }
+            Err(err) => return Err(err.into()),
+        }
+        self.record_backfilled_version(commit.version);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl LogStore for UnityCatalogLogStore {
+    fn name(&self) -> String {
+        "UnityCatalogLogStore".into()
+    }
+
+    async fn read_commit_entry(&self, version: i64) -> DeltaResult<Option<Bytes>> {
+        if let Some(entry) = read_commit_entry(self.storage.as_ref(), version).await? {
+            return Ok(Some(entry));
+        }
+        let (commits, _) = self.unbackfilled_commits(version).await?;
+        match commits.iter().find(|commit| commit.version == version) {
+            Some(commit) => {
+                let staged = self.commits_path().child(commit.file_name.as_str());
+                Ok(Some(self.storage.get(&staged).await?.bytes().await?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn write_commit_entry(
+        &self,
+        version: i64,
+        tmp_commit: &Path,
+    ) -> Result<(), TransactionError> {
+        let log_store_error = |err: DeltaTableError| TransactionError::LogStoreError {
+            msg: format!("Failed to register commit {version} with Unity Catalog"),
+            source: Box::new(err),
+        };
+        let table = self.table.resolve().await.map_err(log_store_error)?;
+
+        // the temporary commit is kept until the commit is accepted, so the transaction can
+        // retry it with the next version if the coordinator rejects this one
+        let file_name = format!("{version:020}.{}.json", Uuid::new_v4());
+        let staged = self.commits_path().child(file_name.as_str());
+        self.storage.copy(tmp_commit, &staged).await?;
+        let meta = self.storage.head(&staged).await?;
+
+        let commit = UnityCommit {
+            version,
+            timestamp: Utc::now().timestamp_millis(),
+            file_name,
+            file_size: meta.size as i64,
+            file_modification_timestamp: meta.last_modified.timestamp_millis(),
+        };
+        let request = CommitRequest {
+            table_id: table.table_id.clone(),
+            table_uri: table.storage_location.to_string(),
+            commit_info: Some(commit.clone()),
+            latest_backfilled_version: self.latest_backfilled_version(),
+        };
+        let response = self
+            .table
+            .catalog()
+            .commit(&request)
+            .await
+            .map_err(|err| log_store_error(catalog_error(err)));
+
+        match response {
+            Ok(CommitResponse::Success {}) => {}
+            Ok(CommitResponse::Error(err)) => {
+                self.storage.delete(&staged).await?;
+                if CONFLICT_ERROR_CODES.contains(&err.error_code.as_str()) {
+                    return Err(TransactionError::VersionAlreadyExists(version));
+                }
+                return Err(log_store_error(DeltaTableError::GenericError {
+                    source: Box::new(err),
+                }));
+            }
+            Err(err) => {
+                self.storage.delete(&staged).await?;
+                return Err(err);
+            }
+        }
+
+        if let Err(err) = self.storage.delete(tmp_commit).await {
+            warn!("Failed to delete the temporary commit {tmp_commit}: {err}");
+        }
+        // the commit is durable once the coordinator accepted it, failing to backfill only
+        // delays its visibility to readers that list the delta log directly.
+        if let Err(err) = self.backfill(&commit).await {
+            warn!("Failed to backfill commit {version} into the delta log: {err}");
+        }
+        Ok(())
+    }
+
+    async fn abort_commit_entry(
+        &self,
+        version: i64,
+        tmp_commit: &Path,
+    ) -> Result<(), TransactionError> {
+        abort_commit_entry(self.storage.as_ref(), version, tmp_commit).await
+    }
+
+    async fn get_latest_version(&self, current_version: i64) -> DeltaResult<i64> {
+        let listed = get_latest_version(self, current_version).await;
+        let (commits, latest_table_version) = self.unbackfilled_commits(current_version).await?;
+        for commit in commits.iter() {
+            if let Err(err) = self.backfill(commit).await {
+                warn!("Failed to backfill commit {}: {err}", commit.version);
+            }
+        }
+        match listed {
+            Ok(version) => {
+                // versions listed in the delta log are backfilled
+                self.record_backfilled_version(version);
+                Ok(version.max(latest_table_version))
+            }
+            Err(_) if latest_table_version >= 0 => Ok(latest_table_version),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn object_store(&self) -> Arc<dyn ObjectStore> {
+        self.storage.clone()
+    }
+
+    fn config(&self) -> &LogStoreConfig {
+        &self.config
+    }
+}
+
+/// [`LogStoreFactory`] for `uc://` table urls
+#[derive(Clone, Debug, Default)]
+pub struct UnityCatalogLogStoreFactory {}
+
+impl LogStoreFactory for UnityCatalogLogStoreFactory {
+    fn with_options(
+        &self,
+        store: ObjectStoreRef,
+        location: &Url,
+        options: &StorageOptions,
+    ) -> DeltaResult<Arc<dyn LogStore>> {
+        let table = Arc::new(UnityTableHandle::try_from_url(location, options)?);
+        Ok(Arc::new(UnityCatalogLogStore::new(
+            store,
+            table,
+            LogStoreConfig {
+                location: location.clone(),
+                options: options.clone(),
+            },
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_catalog::client::mock_server::MockServer;
+    use crate::data_catalog::client::ClientOptions;
+    use crate::data_catalog::unity::models::tests::ERROR_RESPONSE;
+    use crate::data_catalog::unity::storage::tests::table_response;
+    use crate::data_catalog::unity::storage::UnityCatalogObjectStore;
+    use crate::data_catalog::unity::UnityCatalogBuilder;
+    use hyper::{Body, Response};
+    use reqwest::Method;
+
+    const CREDENTIALS_RESPONSE: &str = r#"
+        {
+            "azure_user_delegation_sas": { "sas_token": "sas_token" },
+            "expiration_time": 4102444800000
+        }
+    "#;
+
+    #[tokio::test]
+    async fn test_commits_are_routed_through_unity() {
+        let server = MockServer::new();
+        let catalog = UnityCatalogBuilder::new()
+            .with_workspace_url(server.url())
+            .with_bearer_token("bearer_token")
+            .with_client_options(ClientOptions::default().with_allow_http(true))
+            .build()
+            .unwrap();
+        let table = Arc::new(UnityTableHandle::new(
+            catalog,
+            "catalog_name.schema_name.table_name",
+        ));
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let location = Url::from_directory_path(tmp_dir.path()).unwrap();
+        let storage = Arc::new(UnityCatalogObjectStore::new(
+            table.clone(),
+            StorageOptions::default(),
+        ));
+        let log_store = UnityCatalogLogStore::new(
+            storage,
+            table,
+            LogStoreConfig {
+                location: Url::parse("uc://catalog_name.schema_name.table_name").unwrap(),
+                options: StorageOptions::default(),
+            },
+        );
+
+        let body = table_response("table_id", &location);
+        server.push_fn(move |_| Response::new(Body::from(body)));
+        server.push(Response::new(Body::from(CREDENTIALS_RESPONSE)));
+
+        let tmp_commit = Path::from("_delta_log/_commit_tmp.json.tmp");
+        log_store
+            .object_store()
+            .put(&tmp_commit, Bytes::from("{}").into())
+            .await
+            .unwrap();
+
+        // a rejected commit surfaces as a version conflict so the transaction can retry
+        server.push_fn(|_| {
+            Response::new(Body::from(ERROR_RESPONSE.replace("404", "ALREADY_EXISTS")))
+        });
+        let result = log_store.write_commit_entry(0, &tmp_commit).await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::VersionAlreadyExists(0))
+        ));
+
+        // the retry with the next version commits the same temporary commit
+        server.push_fn(|req| {
+            assert_eq!(
+                req.uri().path(),
+                "/api/2.1/unity-catalog/delta/preview/commits"
+            );
+            assert_eq!(req.method(), &Method::POST);
+            Response::new(Body::from("{}"))
+        });
+        log_store.write_commit_entry(1, &tmp_commit).await.unwrap();
+        assert!(log_store.object_store().head(&tmp_commit).await.is_err());
+
+        // the accepted commit has been backfilled into the delta log
+        let entry = read_commit_entry(log_store.object_store().as_ref(), 1)
+            .await
+            .unwrap();
+        assert_eq!(entry, Some(Bytes::from("{}")));
+        assert_eq!(log_store.latest_backfilled_version(), Some(1));
+    }
+}
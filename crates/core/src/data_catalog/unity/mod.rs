@@ -2,22 +2,42 @@
 //!
 //! This module is gated behind the "unity-experimental" feature.
 use std::str::FromStr;
+use std::sync::Arc;
 
 use reqwest::header::{HeaderValue, AUTHORIZATION};
+use url::Url;
 
 use self::credential::{AzureCliCredential, ClientSecretOAuthProvider, CredentialProvider};
+use self::logstore::UnityCatalogLogStoreFactory;
 use self::models::{
-    GetSchemaResponse, GetTableResponse, ListCatalogsResponse, ListSchemasResponse,
-    ListTableSummariesResponse,
+    CommitRequest, CommitResponse, GetCommitsResponse, GetSchemaResponse, GetTableResponse,
+    ListCatalogsResponse, ListSchemasResponse, ListTableSummariesResponse, TableOperation,
+    TemporaryTableCredentialsRequest, TemporaryTableCredentialsResponse,
 };
+use self::storage::UnityCatalogObjectStoreFactory;
 use super::client::retry::RetryExt;
 use super::{client::retry::RetryConfig, DataCatalog, DataCatalogError, DataCatalogResult};
-use crate::storage::str_is_truthy;
+use crate::logstore::logstore_factories;
+use crate::storage::{factories, str_is_truthy};
 
 pub mod credential;
 #[cfg(feature = "datafusion")]
 pub mod datafusion;
+pub mod logstore;
 pub mod models;
+pub mod storage;
+
+/// Register the [ObjectStoreFactory](crate::storage::ObjectStoreFactory) and
+/// [LogStoreFactory](crate::logstore::LogStoreFactory) for Unity Catalog managed tables,
+/// addressed as `uc://catalog.schema.table`
+pub fn register_handlers(_additional_prefixes: Option<Url>) {
+    let url = Url::parse("uc://").unwrap();
+    factories().insert(
+        url.clone(),
+        Arc::new(UnityCatalogObjectStoreFactory::default()),
+    );
+    logstore_factories().insert(url, Arc::new(UnityCatalogLogStoreFactory::default()));
+}
 
 /// Possible errors from the unity-catalog/tables API call
 #[derive(thiserror::Error, Debug)]
@@ -199,7 +219,7 @@ impl FromStr for UnityCatalogConfigKey {
             "federated_token_file"
             | "unity_federated_token_file"
             | "databricks_federated_token_file" => Ok(UnityCatalogConfigKey::FederatedTokenFile),
-            "host" => Ok(UnityCatalogConfigKey::Host),
+            "host" | "unity_host" | "databricks_host" => Ok(UnityCatalogConfigKey::Host),
             "msi_endpoint" | "unity_msi_endpoint" | "databricks_msi_endpoint" => {
                 Ok(UnityCatalogConfigKey::MsiEndpoint)
             }
@@ -209,7 +229,7 @@ impl FromStr for UnityCatalogConfigKey {
             "object_id" | "unity_object_id" | "databricks_object_id" => {
                 Ok(UnityCatalogConfigKey::ObjectId)
             }
-            "token" => Ok(UnityCatalogConfigKey::Token),
+            "token" | "unity_token" | "databricks_token" => Ok(UnityCatalogConfigKey::Token),
             "use_azure_cli" | "unity_use_azure_cli" | "databricks_use_azure_cli" => {
                 Ok(UnityCatalogConfigKey::UseAzureCli)
             }
@@ -621,6 +641,73 @@ impl UnityCatalog {
 
         Ok(resp.json().await?)
     }
+
+    /// Generates short-lived credentials for the storage location of a table.
+    ///
+    /// The caller must have the EXTERNAL USE SCHEMA privilege on the parent schema.
+    pub async fn get_temp_table_credentials(
+        &self,
+        table_id: impl AsRef<str>,
+        operation: TableOperation,
+    ) -> DataCatalogResult<TemporaryTableCredentialsResponse> {
+        let token = self.get_credential().await?;
+        // https://docs.databricks.com/api/workspace/temporarytablecredentials/generatetemporarytablecredentials
+        let resp = self
+            .client
+            .post(format!(
+                "{}/temporary-table-credentials",
+                self.catalog_url()
+            ))
+            .header(AUTHORIZATION, token)
+            .json(&TemporaryTableCredentialsRequest {
+                table_id: table_id.as_ref().to_string(),
+                operation,
+            })
+            .send_retry(&self.retry_config)
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Registers a staged commit with the commit coordinator of a managed table.
+    ///
+    /// The coordinator rejects the request if a commit for the same version already exists.
+    pub async fn commit(&self, request: &CommitRequest) -> DataCatalogResult<CommitResponse> {
+        let token = self.get_credential().await?;
+        let resp = self
+            .client
+            .post(format!("{}/delta/preview/commits", self.catalog_url()))
+            .header(AUTHORIZATION, token)
+            .json(request)
+            .send_retry(&self.retry_config)
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Gets the commits registered with the commit coordinator of a managed table
+    /// that have not yet been backfilled into the delta log.
+    pub async fn get_commits(
+        &self,
+        table_id: impl AsRef<str>,
+        table_uri: impl AsRef<str>,
+        start_version: i64,
+    ) -> DataCatalogResult<GetCommitsResponse> {
+        let token = self.get_credential().await?;
+        let resp = self
+            .client
+            .get(format!("{}/delta/preview/commits", self.catalog_url()))
+            .header(AUTHORIZATION, token)
+            .query(&[
+                ("table_id", table_id.as_ref()),
+                ("table_uri", table_uri.as_ref()),
+                ("start_version", &start_version.to_string()),
+            ])
+            .send_retry(&self.retry_config)
+            .await?;
+
+        Ok(resp.json().await?)
+    }
 }
 
 #[async_trait::async_trait]
@@ -666,6 +753,14 @@ mod tests {
     use hyper::{Body, Response};
     use reqwest::Method;
 
+    #[test]
+    fn test_config_key_roundtrip() {
+        for key in [UnityCatalogConfigKey::Host, UnityCatalogConfigKey::Token] {
+            let parsed = UnityCatalogConfigKey::from_str(key.as_ref()).unwrap();
+            assert_eq!(parsed.as_ref(), key.as_ref());
+        }
+    }
+
     #[tokio::test]
     async fn test_unity_client() {
         let server = MockServer::new();
@@ -3,7 +3,7 @@
 use core::fmt;
 use std::collections::HashMap;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Error response from unity API
 #[derive(Debug, Deserialize)]
@@ -293,6 +293,161 @@ pub struct Table {
 
     /// Unique identifier of parent metastore.
     pub metastore_id: String,
+
+    /// Unique identifier for the table.
+    #[serde(default)]
+    pub table_id: String,
+
+    /// Type of table
+    #[serde(default)]
+    pub table_type: TableType,
+}
+
+/// Operation for which temporary table credentials are requested
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[allow(missing_docs)]
+pub enum TableOperation {
+    Read,
+    ReadWrite,
+}
+
+/// Request body for generating temporary table credentials
+#[derive(Serialize, Debug)]
+pub struct TemporaryTableCredentialsRequest {
+    /// Unique identifier of the table
+    pub table_id: String,
+    /// The operation the credentials are used for
+    pub operation: TableOperation,
+}
+
+/// Temporary table credentials response
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum TemporaryTableCredentialsResponse {
+    /// Successful response
+    Success(TemporaryTableCredentials),
+    /// Error response
+    Error(ErrorResponse),
+}
+
+/// Short-lived credentials vended by unity catalog to access the storage location of a table
+#[derive(Deserialize, Clone, Debug)]
+pub struct TemporaryTableCredentials {
+    /// Credentials for tables stored in AWS S3
+    pub aws_temp_credentials: Option<AwsTempCredentials>,
+
+    /// Credentials for tables stored in Azure storage
+    pub azure_user_delegation_sas: Option<AzureUserDelegationSas>,
+
+    /// Credentials for tables stored in Google cloud storage
+    pub gcp_oauth_token: Option<GcpOauthToken>,
+
+    /// Time at which the credentials expire, in epoch milliseconds.
+    pub expiration_time: i64,
+
+    /// Storage location the credentials are scoped to.
+    #[serde(default)]
+    pub url: String,
+}
+
+/// Temporary AWS credentials
+#[derive(Deserialize, Clone)]
+pub struct AwsTempCredentials {
+    /// The access key id
+    pub access_key_id: String,
+    /// The secret access key
+    pub secret_access_key: String,
+    /// The session token
+    pub session_token: String,
+}
+
+impl fmt::Debug for AwsTempCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AwsTempCredentials({})", self.access_key_id)
+    }
+}
+
+/// Azure user delegation SAS token
+#[derive(Deserialize, Clone)]
+pub struct AzureUserDelegationSas {
+    /// The signed URI (SAS token)
+    pub sas_token: String,
+}
+
+impl fmt::Debug for AzureUserDelegationSas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AzureUserDelegationSas")
+    }
+}
+
+/// GCP OAuth token
+#[derive(Deserialize, Clone)]
+pub struct GcpOauthToken {
+    /// The oauth token
+    pub oauth_token: String,
+}
+
+impl fmt::Debug for GcpOauthToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GcpOauthToken")
+    }
+}
+
+/// A commit registered with the unity catalog commit coordinator
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UnityCommit {
+    /// Version of the commit
+    pub version: i64,
+    /// Time at which the commit was created, in epoch milliseconds.
+    pub timestamp: i64,
+    /// Name of the staged commit file within `_delta_log/_commits`
+    pub file_name: String,
+    /// Size of the staged commit file in bytes
+    pub file_size: i64,
+    /// Time at which the staged commit file was last modified, in epoch milliseconds.
+    pub file_modification_timestamp: i64,
+}
+
+/// Request body for registering a commit with unity catalog
+#[derive(Serialize, Debug)]
+pub struct CommitRequest {
+    /// Unique identifier of the table
+    pub table_id: String,
+    /// Storage location of the table
+    pub table_uri: String,
+    /// The commit to register
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_info: Option<UnityCommit>,
+    /// Latest version that has been backfilled into `_delta_log`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_backfilled_version: Option<i64>,
+}
+
+/// Commit response
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum CommitResponse {
+    /// Error response
+    Error(ErrorResponse),
+    /// Successful response
+    Success {},
+}
+
+/// Get commits response
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum GetCommitsResponse {
+    /// Successful response
+    Success {
+        /// Commits that have not yet been backfilled
+        #[serde(default)]
+        commits: Vec<UnityCommit>,
+        /// Latest version known to the commit coordinator
+        latest_table_version: i64,
+    },
+    /// Error response
+    Error(ErrorResponse),
 }
 
 #[cfg(test)]
@@ -459,6 +614,31 @@ pub(crate) mod tests {
 		"#;
     pub(crate) const LIST_TABLES_EMPTY: &str = "{}";
 
+    pub(crate) const TEMPORARY_TABLE_CREDENTIALS_RESPONSE: &str = r#"
+        {
+            "aws_temp_credentials": {
+                "access_key_id": "access_key_id",
+                "secret_access_key": "secret_access_key",
+                "session_token": "session_token"
+            },
+            "expiration_time": 4102444800000,
+            "url": "memory:///"
+        }
+    "#;
+
+    pub(crate) const GET_COMMITS_RESPONSE: &str = r#"
+        {
+            "commits": [{
+                "version": 1,
+                "timestamp": 0,
+                "file_name": "00000000000000000001.uuid.json",
+                "file_size": 100,
+                "file_modification_timestamp": 0
+            }],
+            "latest_table_version": 1
+        }
+    "#;
+
     #[test]
     fn test_responses() {
         let list_schemas: Result<ListSchemasResponse, _> =
@@ -493,7 +673,25 @@ pub(crate) mod tests {
 
         let get_schema: Result<GetSchemaResponse, _> = serde_json::from_str(GET_SCHEMA_RESPONSE);
         assert!(get_schema.is_ok());
-        assert!(matches!(get_schema.unwrap(), GetSchemaResponse::Success(_)))
+        assert!(matches!(get_schema.unwrap(), GetSchemaResponse::Success(_)));
+
+        let credentials: Result<TemporaryTableCredentialsResponse, _> =
+            serde_json::from_str(TEMPORARY_TABLE_CREDENTIALS_RESPONSE);
+        assert!(credentials.is_ok());
+        assert!(matches!(
+            credentials.unwrap(),
+            TemporaryTableCredentialsResponse::Success(_)
+        ));
+
+        let commits: Result<GetCommitsResponse, _> = serde_json::from_str(GET_COMMITS_RESPONSE);
+        assert!(commits.is_ok());
+        assert!(matches!(
+            commits.unwrap(),
+            GetCommitsResponse::Success { .. }
+        ));
+
+        let commit: Result<CommitResponse, _> = serde_json::from_str("{}");
+        assert!(matches!(commit.unwrap(), CommitResponse::Success {}));
     }
 
     #[test]
@@ -507,6 +705,16 @@ pub(crate) mod tests {
 
         let get_table: Result<GetTableResponse, _> = serde_json::from_str(ERROR_RESPONSE);
         assert!(get_table.is_ok());
-        assert!(matches!(get_table.unwrap(), GetTableResponse::Error(_)))
+        assert!(matches!(get_table.unwrap(), GetTableResponse::Error(_)));
+
+        let credentials: Result<TemporaryTableCredentialsResponse, _> =
+            serde_json::from_str(ERROR_RESPONSE);
+        assert!(matches!(
+            credentials.unwrap(),
+            TemporaryTableCredentialsResponse::Error(_)
+        ));
+
+        let commit: Result<CommitResponse, _> = serde_json::from_str(ERROR_RESPONSE);
+        assert!(matches!(commit.unwrap(), CommitResponse::Error(_)))
     }
 }
@@ -0,0 +1,512 @@
+//! Object store for Unity Catalog managed tables addressed via `uc://catalog.schema.table`.
+//!
+//! The storage location of the table is resolved through the catalog, and all requests are
+//! authorized with short-lived credentials vended by Unity Catalog. Those credentials are
+//! re-fetched transparently once they are about to expire, so long running scans keep working.
+use std::collections::HashMap;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::Utc;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts,
+    PutOptions, PutPayload, PutResult, Result as ObjectStoreResult,
+};
+use tokio::sync::{OnceCell, RwLock};
+use url::Url;
+
+use super::models::{
+    GetTableResponse, TableOperation, TemporaryTableCredentials, TemporaryTableCredentialsResponse,
+};
+use super::{UnityCatalog, UnityCatalogBuilder, UnityCatalogConfigKey, UnityCatalogError};
+use crate::data_catalog::DataCatalogError;
+use crate::storage::{factories, ObjectStoreFactory, ObjectStoreRef, StorageOptions};
+use crate::{DeltaResult, DeltaTableError, ObjectStoreError, Path};
+
+const STORE_NAME: &str = "UnityCatalogObjectStore";
+
+/// Credentials are refreshed this long before they actually expire, so that requests
+/// which are already in flight do not fail half way through.
+const CREDENTIAL_REFRESH_MARGIN_MS: i64 = 5 * 60 * 1000;
+
+/// Location and identity of a managed table as resolved by Unity Catalog
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedTable {
+    pub(crate) table_id: String,
+    pub(crate) storage_location: Url,
+}
+
+/// A table in Unity Catalog referenced by its fully qualified name.
+///
+/// The table is resolved lazily on first use and the result is cached for the lifetime
+/// of the handle.
+pub(crate) struct UnityTableHandle {
+    catalog: UnityCatalog,
+    full_name: String,
+    resolved: OnceCell<ResolvedTable>,
+}
+
+impl std::fmt::Debug for UnityTableHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "UnityTableHandle({})", self.full_name)
+    }
+}
+
+impl UnityTableHandle {
+    pub(crate) fn new(catalog: UnityCatalog, full_name: impl Into<String>) -> Self {
+        Self {
+            catalog,
+            full_name: full_name.into(),
+            resolved: OnceCell::new(),
+        }
+    }
+
+    /// Create a handle for a `uc://catalog.schema.table` url.
+    ///
+    /// The catalog client is configured from the environment, with any unity specific
+    /// keys in `options` taking precedence.
+    pub(crate) fn try_from_url(url: &Url, options: &StorageOptions) -> DeltaResult<Self> {
+        let full_name = table_name_from_url(url)?;
+        let catalog = UnityCatalogBuilder::from_env()
+            .try_with_options(
+                options
+                    .0
+                    .iter()
+                    .filter(|(key, _)| UnityCatalogConfigKey::from_str(key).is_ok()),
+            )
+            .and_then(|builder| builder.build())
+            .map_err(catalog_error)?;
+        Ok(Self::new(catalog, full_name))
+    }
+
+    pub(crate) fn catalog(&self) -> &UnityCatalog {
+        &self.catalog
+    }
+
+    /// Look up the table id and storage location of the table.
+    pub(crate) async fn resolve(&self) -> DeltaResult<&ResolvedTable> {
+        self.resolved
+            .get_or_try_init(|| async {
+                let mut parts = self.full_name.splitn(3, '.');
+                let (catalog, schema, table) = (
+                    parts.next().unwrap_or_default(),
+                    parts.next().unwrap_or_default(),
+                    parts.next().unwrap_or_default(),
+                );
+                match self
+                    .catalog
+                    .get_table(catalog, schema, table)
+                    .await
+                    .map_err(catalog_error)?
+                {
+                    GetTableResponse::Success(table) => {
+                        let storage_location =
+                            Url::parse(&table.storage_location).map_err(|_| {
+                                DeltaTableError::InvalidTableLocation(table.storage_location)
+                            })?;
+                        Ok(ResolvedTable {
+                            table_id: table.table_id,
+                            storage_location,
+                        })
+                    }
+                    GetTableResponse::Error(err) => {
+                        Err(catalog_error(UnityCatalogError::InvalidTable {
+                            error_code: err.error_code,
+                            message: err.message,
+                        }))
+                    }
+                }
+            })
+            .await
+    }
+
+    /// Request short-lived credentials for the storage location of the table.
+    ///
+    /// Read-write credentials are requested first, falling back to read-only credentials
+    /// for principals which are not allowed to modify the table.
+    async fn temporary_credentials(&self) -> DeltaResult<TemporaryTableCredentials> {
+        let table = self.resolve().await?;
+        let mut last_error = None;
+        for operation in [TableOperation::ReadWrite, TableOperation::Read] {
+            match self
+                .catalog
+                .get_temp_table_credentials(&table.table_id, operation)
+                .await
+                .map_err(catalog_error)?
+            {
+                TemporaryTableCredentialsResponse::Success(credentials) => return Ok(credentials),
+                TemporaryTableCredentialsResponse::Error(err) => last_error = Some(err),
+            }
+        }
+        let err = last_error.expect("at least one credential request was made");
+        Err(catalog_error(UnityCatalogError::InvalidTable {
+            error_code: err.error_code,
+            message: err.message,
+        }))
+    }
+}
+
+/// Extract the fully qualified table name from a `uc://catalog.schema.table` url
+pub(crate) fn table_name_from_url(url: &Url) -> DeltaResult<String> {
+    match url.host_str() {
+        Some(name) if name.split('.').count() == 3 && !name.split('.').any(str::is_empty) => {
+            Ok(name.to_string())
+        }
+        _ => Err(DeltaTableError::InvalidTableLocation(url.to_string())),
+    }
+}
+
+pub(crate) fn catalog_error(err: impl Into<DataCatalogError>) -> DeltaTableError {
+    DeltaTableError::GenericError {
+        source: Box::new(err.into()),
+    }
+}
+
+/// Translate vended credentials into the storage options understood by the object store
+/// factory of the underlying storage location.
+fn credential_options(
+    credentials: &TemporaryTableCredentials,
+) -> DeltaResult<HashMap<String, String>> {
+    if let Some(aws) = &credentials.aws_temp_credentials {
+        return Ok(HashMap::from([
+            ("aws_access_key_id".to_string(), aws.access_key_id.clone()),
+            (
+                "aws_secret_access_key".to_string(),
+                aws.secret_access_key.clone(),
+            ),
+            ("aws_session_token".to_string(), aws.session_token.clone()),
+        ]));
+    }
+    if let Some(azure) = &credentials.azure_user_delegation_sas {
+        return Ok(HashMap::from([(
+            "azure_storage_sas_token".to_string(),
+            azure.sas_token.clone(),
+        )]));
+    }
+    Err(DeltaTableError::Generic(
+        "Unity Catalog vended credentials of an unsupported type".to_string(),
+    ))
+}
+
+struct VendedStore {
+    store: ObjectStoreRef,
+    expires_at: i64,
+}
+
+impl VendedStore {
+    fn is_fresh(&self) -> bool {
+        self.expires_at - CREDENTIAL_REFRESH_MARGIN_MS > Utc::now().timestamp_millis()
+    }
+}
+
+/// [`ObjectStore`] for Unity Catalog managed tables using vended credentials
+pub struct UnityCatalogObjectStore {
+    table: Arc<UnityTableHandle>,
+    options: StorageOptions,
+    current: RwLock<Option<VendedStore>>,
+}
+
+impl UnityCatalogObjectStore {
+    pub(crate) fn new(table: Arc<UnityTableHandle>, options: StorageOptions) -> Self {
+        // unity specific keys are not meant for the underlying storage
+        let options = StorageOptions(
+            options
+                .0
+                .into_iter()
+                .filter(|(key, _)| UnityCatalogConfigKey::from_str(key).is_err())
+                .collect(),
+        );
+        Self {
+            table,
+            options,
+            current: RwLock::new(None),
+        }
+    }
+
+    /// Get the store for the storage location, refreshing credentials when necessary.
+    async fn inner(&self) -> ObjectStoreResult<ObjectStoreRef> {
+        if let Some(vended) = self.current.read().await.as_ref() {
+            if vended.is_fresh() {
+                return Ok(vended.store.clone());
+            }
+        }
+
+        let mut current = self.current.write().await;
+        // another task may have refreshed the credentials while we waited for the lock
+        if let Some(vended) = current.as_ref() {
+            if vended.is_fresh() {
+                return Ok(vended.store.clone());
+            }
+        }
+
+        let vended = self
+            .vend_store()
+            .await
+            .map_err(|err| ObjectStoreError::Generic {
+                store: STORE_NAME,
+                source: Box::new(err),
+            })?;
+        let store = vended.store.clone();
+        *current = Some(vended);
+        Ok(store)
+    }
+
+    async fn vend_store(&self) -> DeltaResult<VendedStore> {
+        let credentials = self.table.temporary_credentials().await?;
+        let location = &self.table.resolve().await?.storage_location;
+
+        let mut options = self.options.0.clone();
+        options.extend(credential_options(&credentials)?);
+
+        let scheme = Url::parse(&format!("{}://", location.scheme()))
+            .map_err(|_| DeltaTableError::InvalidTableLocation(location.to_string()))?;
+        let factory = factories()
+            .get(&scheme)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| DeltaTableError::InvalidTableLocation(location.to_string()))?;
+        let (store, _prefix) = factory.parse_url_opts(location, &options.into())?;
+
+        Ok(VendedStore {
+            store,
+            expires_at: credentials.expiration_time,
+        })
+    }
+}
+
+impl std::fmt::Display for UnityCatalogObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UnityCatalogObjectStore({})", self.table.full_name)
+    }
+}
+
+impl std::fmt::Debug for UnityCatalogObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UnityCatalogObjectStore({})", self.table.full_name)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for UnityCatalogObjectStore {
+    async fn put(&self, location: &Path, bytes: PutPayload) -> ObjectStoreResult<PutResult> {
+        self.inner().await?.put(location, bytes).await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        bytes: PutPayload,
+        options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        self.inner().await?.put_opts(location, bytes, options).await
+    }
+
+    async fn get(&self, location: &Path) -> ObjectStoreResult<GetResult> {
+        self.inner().await?.get(location).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        self.inner().await?.get_opts(location, options).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> ObjectStoreResult<Bytes> {
+        self.inner().await?.get_range(location, range).await
+    }
+
+    async fn head(&self, location: &Path) -> ObjectStoreResult<ObjectMeta> {
+        self.inner().await?.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> ObjectStoreResult<()> {
+        self.inner().await?.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        let prefix = prefix.cloned();
+        futures::stream::once(async move {
+            let store = self.inner().await?;
+            store.list(prefix.as_ref()).try_collect::<Vec<_>>().await
+        })
+        .map_ok(|metas| futures::stream::iter(metas.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        let prefix = prefix.cloned();
+        let offset = offset.clone();
+        futures::stream::once(async move {
+            let store = self.inner().await?;
+            store
+                .list_with_offset(prefix.as_ref(), &offset)
+                .try_collect::<Vec<_>>()
+                .await
+        })
+        .map_ok(|metas| futures::stream::iter(metas.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.inner().await?.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.inner().await?.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.inner().await?.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.inner().await?.rename(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.inner().await?.rename_if_not_exists(from, to).await
+    }
+
+    async fn put_multipart(&self, location: &Path) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.inner().await?.put_multipart(location).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        options: PutMultipartOpts,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.inner()
+            .await?
+            .put_multipart_opts(location, options)
+            .await
+    }
+}
+
+/// [`ObjectStoreFactory`] for `uc://` table urls
+#[derive(Clone, Debug, Default)]
+pub struct UnityCatalogObjectStoreFactory {}
+
+impl ObjectStoreFactory for UnityCatalogObjectStoreFactory {
+    fn parse_url_opts(
+        &self,
+        url: &Url,
+        options: &StorageOptions,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let table = Arc::new(UnityTableHandle::try_from_url(url, options)?);
+        let store = UnityCatalogObjectStore::new(table, options.clone());
+        Ok((Arc::new(store), Path::from("/")))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::data_catalog::client::mock_server::MockServer;
+    use crate::data_catalog::client::ClientOptions;
+    use hyper::{Body, Response};
+    use reqwest::Method;
+
+    pub(crate) fn table_response(table_id: &str, location: &Url) -> String {
+        format!(
+            r#"{{
+                "name": "table_name",
+                "data_source_format": "DELTA",
+                "full_name": "catalog_name.schema_name.table_name",
+                "schema_name": "schema_name",
+                "storage_location": "{location}",
+                "metastore_id": "metastore_id",
+                "table_id": "{table_id}",
+                "table_type": "MANAGED"
+            }}"#
+        )
+    }
+
+    fn credentials_response(expiration_time: i64) -> String {
+        format!(
+            r#"{{
+                "aws_temp_credentials": {{
+                    "access_key_id": "access_key_id",
+                    "secret_access_key": "secret_access_key",
+                    "session_token": "session_token"
+                }},
+                "expiration_time": {expiration_time}
+            }}"#
+        )
+    }
+
+    #[test]
+    fn test_table_name_from_url() {
+        let url = Url::parse("uc://catalog_name.schema_name.table_name").unwrap();
+        assert_eq!(
+            table_name_from_url(&url).unwrap(),
+            "catalog_name.schema_name.table_name"
+        );
+        for invalid in ["uc://schema_name.table_name", "uc://catalog..table_name"] {
+            let url = Url::parse(invalid).unwrap();
+            assert!(table_name_from_url(&url).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_credentials_are_refreshed_on_expiry() {
+        let server = MockServer::new();
+        let catalog = UnityCatalogBuilder::new()
+            .with_workspace_url(server.url())
+            .with_bearer_token("bearer_token")
+            .with_client_options(ClientOptions::default().with_allow_http(true))
+            .build()
+            .unwrap();
+        let table = Arc::new(UnityTableHandle::new(
+            catalog,
+            "catalog_name.schema_name.table_name",
+        ));
+
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let location = Url::from_directory_path(tmp_dir.path()).unwrap();
+        let body = table_response("table_id", &location);
+        server.push_fn(move |req| {
+            assert_eq!(
+                req.uri().path(),
+                "/api/2.1/unity-catalog/tables/catalog_name.schema_name.table_name"
+            );
+            Response::new(Body::from(body))
+        });
+        // the first credentials are already expired and must be replaced on the next request
+        let expired = credentials_response(Utc::now().timestamp_millis());
+        server.push_fn(move |req| {
+            assert_eq!(
+                req.uri().path(),
+                "/api/2.1/unity-catalog/temporary-table-credentials"
+            );
+            assert_eq!(req.method(), &Method::POST);
+            Response::new(Body::from(expired))
+        });
+        let valid = credentials_response(Utc::now().timestamp_millis() + 60 * 60 * 1000);
+        server.push_fn(move |req| {
+            assert_eq!(
+                req.uri().path(),
+                "/api/2.1/unity-catalog/temporary-table-credentials"
+            );
+            Response::new(Body::from(valid))
+        });
+
+        let store = UnityCatalogObjectStore::new(table, StorageOptions::default());
+        let path = Path::from("data.json");
+        store.put(&path, Bytes::from("data").into()).await.unwrap();
+        let data = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data, Bytes::from("data"));
+
+        // fresh credentials are reused, so no further requests are made
+        let listed = store.list(None).try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(store.current.read().await.as_ref().unwrap().is_fresh());
+    }
+}
@@ -4,11 +4,14 @@ use arrow_schema::{DataType, Field, TimeUnit};
 use lazy_static::lazy_static;
 use std::collections::HashMap;
 
+pub(crate) use provider::CommitRange;
+pub use provider::DeltaCdfTableProvider;
 pub(crate) use scan::*;
 pub(crate) use scan_utils::*;
 
 use crate::kernel::{Add, AddCDCFile};
 
+mod provider;
 mod scan;
 mod scan_utils;
 
@@ -0,0 +1,324 @@
+//! Table provider exposing the change data feed of a table to DataFusion
+//!
+//! Registering a [`DeltaCdfTableProvider`] allows querying the change data feed with SQL, e.g.
+//! `SELECT * FROM table_changes WHERE _commit_version >= 2`. Comparisons of the
+//! `_commit_version` and `_commit_timestamp` columns with literals restrict the commits read from
+//! the log, all filters are still applied by DataFusion on the returned changes.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow_schema::{DataType, SchemaRef, TimeUnit};
+use async_trait::async_trait;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::{SessionContext, SessionState};
+use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion_common::{Result as DataFusionResult, ScalarValue};
+use datafusion_expr::utils::split_conjunction;
+use datafusion_expr::{BinaryExpr, Expr, Operator, TableProviderFilterPushDown};
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::PhysicalExpr;
+
+use crate::delta_datafusion::cdf::{COMMIT_TIMESTAMP_COL, COMMIT_VERSION_COL};
+use crate::errors::DeltaResult;
+use crate::operations::load_cdf::CdfLoadBuilder;
+
+/// Range of commits selected by the filters of a scan, all bounds are inclusive
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct CommitRange {
+    /// Lowest commit version to read
+    pub(crate) min_version: Option<i64>,
+    /// Highest commit version to read
+    pub(crate) max_version: Option<i64>,
+    /// Earliest commit timestamp to read, in milliseconds
+    pub(crate) min_timestamp: Option<i64>,
+    /// Latest commit timestamp to read, in milliseconds
+    pub(crate) max_timestamp: Option<i64>,
+}
+
+impl CommitRange {
+    /// Derive the range of commits from comparisons of the commit columns with literals
+    ///
+    /// Other filters are ignored, so the range may select more commits than the filters do.
+    pub(crate) fn from_filters(filters: &[Expr]) -> Self {
+        let mut range = Self::default();
+        for expr in filters.iter().flat_map(split_conjunction) {
+            match expr {
+                Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                    match (left.as_ref(), right.as_ref()) {
+                        (Expr::Column(column), Expr::Literal(value)) => {
+                            range.add_bound(&column.name, *op, value)
+                        }
+                        (Expr::Literal(value), Expr::Column(column)) => {
+                            if let Some(op) = op.swap() {
+                                range.add_bound(&column.name, op, value)
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Expr::Between(between) if !between.negated => {
+                    if let (Expr::Column(column), Expr::Literal(low), Expr::Literal(high)) = (
+                        between.expr.as_ref(),
+                        between.low.as_ref(),
+                        between.high.as_ref(),
+                    ) {
+                        range.add_bound(&column.name, Operator::GtEq, low);
+                        range.add_bound(&column.name, Operator::LtEq, high);
+                    }
+                }
+                _ => {}
+            }
+        }
+        range
+    }
+
+    fn add_bound(&mut self, column: &str, op: Operator, value: &ScalarValue) {
+        if column == COMMIT_VERSION_COL {
+            let Some(version) = scalar_to_i64(value, &DataType::Int64) else {
+                return;
+            };
+            // versions are integers, so strict bounds can be made inclusive
+            match op {
+                Operator::Eq => {
+                    self.raise_min_version(version);
+                    self.lower_max_version(version);
+                }
+                Operator::Gt => self.raise_min_version(version.saturating_add(1)),
+                Operator::GtEq => self.raise_min_version(version),
+                Operator::Lt => self.lower_max_version(version.saturating_sub(1)),
+                Operator::LtEq => self.lower_max_version(version),
+                _ => {}
+            }
+        } else if column == COMMIT_TIMESTAMP_COL {
+            let Some(timestamp) =
+                scalar_to_i64(value, &DataType::Timestamp(TimeUnit::Millisecond, None))
+            else {
+                return;
+            };
+            // literals with a finer precision are truncated to milliseconds, so strict bounds
+            // are kept inclusive and only applied by the filters
+            match op {
+                Operator::Eq => {
+                    self.raise_min_timestamp(timestamp);
+                    self.lower_max_timestamp(timestamp);
+                }
+                Operator::Gt | Operator::GtEq => self.raise_min_timestamp(timestamp),
+                Operator::Lt | Operator::LtEq => self.lower_max_timestamp(timestamp),
+                _ => {}
+            }
+        }
+    }
+
+    fn raise_min_version(&mut self, version: i64) {
+        self.min_version = Some(self.min_version.map_or(version, |v| v.max(version)));
+    }
+
+    fn lower_max_version(&mut self, version: i64) {
+        self.max_version = Some(self.max_version.map_or(version, |v| v.min(version)));
+    }
+
+    fn raise_min_timestamp(&mut self, timestamp: i64) {
+        self.min_timestamp = Some(self.min_timestamp.map_or(timestamp, |t| t.max(timestamp)));
+    }
+
+    fn lower_max_timestamp(&mut self, timestamp: i64) {
+        self.max_timestamp = Some(self.max_timestamp.map_or(timestamp, |t| t.min(timestamp)));
+    }
+}
+
+fn scalar_to_i64(value: &ScalarValue, data_type: &DataType) -> Option<i64> {
+    match value.cast_to(data_type).ok()? {
+        ScalarValue::Int64(value) => value,
+        ScalarValue::TimestampMillisecond(value, _) => value,
+        _ => None,
+    }
+}
+
+/// A [`TableProvider`] reading the change data feed of a Delta table
+///
+/// The changes are read as configured on the [`CdfLoadBuilder`], filters on the commit version
+/// and timestamp columns further restrict the commits read by each scan.
+pub struct DeltaCdfTableProvider {
+    cdf_builder: CdfLoadBuilder,
+    schema: SchemaRef,
+}
+
+impl DeltaCdfTableProvider {
+    /// Build a table provider reading the changes selected by `cdf_builder`
+    pub fn try_new(cdf_builder: CdfLoadBuilder) -> DeltaResult<Self> {
+        Ok(Self {
+            schema: cdf_builder.output_schema()?,
+            cdf_builder,
+        })
+    }
+}
+
+#[async_trait]
+impl TableProvider for DeltaCdfTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        session: &SessionState,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let range = CommitRange::from_filters(filters);
+        let cdf_builder = self
+            .cdf_builder
+            .clone()
+            .with_session_ctx(SessionContext::new_with_state(session.clone()))
+            .with_commit_range(&range)
+            .await?;
+
+        let Some(cdf_builder) = cdf_builder else {
+            let schema = match projection {
+                Some(projection) => Arc::new(self.schema.project(projection)?),
+                None => self.schema.clone(),
+            };
+            return Ok(Arc::new(EmptyExec::new(schema)));
+        };
+
+        let plan: Arc<dyn ExecutionPlan> = Arc::new(cdf_builder.build().await?);
+        match projection {
+            Some(projection) => {
+                let expressions = projection
+                    .iter()
+                    .map(|idx| {
+                        let name = self.schema.field(*idx).name();
+                        (
+                            Arc::new(Column::new(name, *idx)) as Arc<dyn PhysicalExpr>,
+                            name.to_owned(),
+                        )
+                    })
+                    .collect();
+                Ok(Arc::new(ProjectionExec::try_new(expressions, plan)?))
+            }
+            None => Ok(plan),
+        }
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filter: &[&Expr],
+    ) -> DataFusionResult<Vec<TableProviderFilterPushDown>> {
+        Ok(vec![TableProviderFilterPushDown::Inexact; filter.len()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{NaiveDateTime, TimeZone, Utc};
+    use datafusion_common::assert_batches_sorted_eq;
+    use datafusion_expr::{col, lit};
+
+    use crate::writer::test_utils::TestResult;
+    use crate::DeltaOps;
+
+    #[test]
+    fn test_commit_range_from_filters() {
+        let filters = vec![
+            col(COMMIT_VERSION_COL)
+                .gt(lit(1_i64))
+                .and(lit(4_i64).gt_eq(col(COMMIT_VERSION_COL))),
+            col(COMMIT_VERSION_COL).lt(lit(6_i64)),
+            col(COMMIT_TIMESTAMP_COL).between(
+                lit(ScalarValue::TimestampMillisecond(Some(1_000), None)),
+                lit(ScalarValue::TimestampMillisecond(Some(2_000), None)),
+            ),
+            col("id").eq(lit(1_i32)),
+        ];
+        assert_eq!(
+            CommitRange::from_filters(&filters),
+            CommitRange {
+                min_version: Some(2),
+                max_version: Some(4),
+                min_timestamp: Some(1_000),
+                max_timestamp: Some(2_000),
+            }
+        );
+
+        let filters = vec![col("id").gt(lit(1_i32)), col(COMMIT_VERSION_COL).is_null()];
+        assert_eq!(CommitRange::from_filters(&filters), CommitRange::default());
+    }
+
+    #[tokio::test]
+    async fn test_cdf_table_provider_sql() -> TestResult {
+        let cdf_builder = DeltaOps::try_from_uri("../test/tests/data/cdf-table")
+            .await?
+            .load_cdf();
+        let ctx = SessionContext::new();
+        ctx.register_table(
+            "table_changes",
+            Arc::new(DeltaCdfTableProvider::try_new(cdf_builder)?),
+        )?;
+
+        let batches = ctx
+            .sql(
+                "SELECT id, name, _change_type, _commit_version FROM table_changes \
+                 WHERE _commit_version >= 2 AND _commit_version < 3",
+            )
+            .await?
+            .collect()
+            .await?;
+        assert_batches_sorted_eq! {
+            ["+----+--------+------------------+-----------------+",
+             "| id | name   | _change_type     | _commit_version |",
+             "+----+--------+------------------+-----------------+",
+             "| 5  | Emily  | update_postimage | 2               |",
+             "| 5  | Emily  | update_preimage  | 2               |",
+             "| 6  | Carl   | update_postimage | 2               |",
+             "| 6  | Carl   | update_preimage  | 2               |",
+             "| 7  | Dennis | update_postimage | 2               |",
+             "| 7  | Dennis | update_preimage  | 2               |",
+             "+----+--------+------------------+-----------------+"],
+            &batches
+        }
+
+        let timestamp = NaiveDateTime::parse_from_str("2024-01-01 00:00:00", "%Y-%m-%d %H:%M:%S")?;
+        let batches = ctx
+            .table("table_changes")
+            .await?
+            .filter(
+                col(COMMIT_TIMESTAMP_COL).gt(lit(ScalarValue::TimestampMillisecond(
+                    Some(Utc.from_utc_datetime(&timestamp).timestamp_millis()),
+                    None,
+                ))),
+            )?
+            .select_columns(&["id", "_change_type", "_commit_version"])?
+            .collect()
+            .await?;
+        assert_batches_sorted_eq! {
+            ["+----+--------------+-----------------+",
+             "| id | _change_type | _commit_version |",
+             "+----+--------------+-----------------+",
+             "| 7  | delete       | 3               |",
+             "+----+--------------+-----------------+"],
+            &batches
+        }
+
+        let batches = ctx
+            .sql("SELECT id FROM table_changes WHERE _commit_version > 100")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+        Ok(())
+    }
+}
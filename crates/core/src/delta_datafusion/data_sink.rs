@@ -0,0 +1,109 @@
+//! Sink writing the output of DataFusion plans into a Delta table
+//!
+//! The sink backs `INSERT INTO` and `INSERT OVERWRITE` statements on a registered [`DeltaTable`].
+//! All inserted batches are committed in a single write, which enforces the constraints and
+//! invariants of the table like any other write. The batches are streamed into the writer as
+//! they are produced, so the inserted data is never collected in memory.
+//!
+//! [`DeltaTable`]: crate::DeltaTable
+
+use std::any::Any;
+use std::fmt::{self, Debug};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::execution::TaskContext;
+use datafusion::physical_plan::insert::DataSink;
+use datafusion::physical_plan::metrics::MetricsSet;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, SendableRecordBatchStream};
+use datafusion_common::Result as DataFusionResult;
+use futures::{StreamExt, TryStreamExt};
+
+use crate::logstore::LogStoreRef;
+use crate::operations::merge::stream::stream_to_dataframe;
+use crate::operations::write::WriteBuilder;
+use crate::protocol::SaveMode;
+use crate::table::state::DeltaTableState;
+
+/// Commits the batches written to it to a Delta table
+pub(crate) struct DeltaDataSink {
+    log_store: LogStoreRef,
+    snapshot: DeltaTableState,
+    mode: SaveMode,
+}
+
+impl DeltaDataSink {
+    /// Create a sink writing to the table at `snapshot`, appending to or overwriting its data
+    pub(crate) fn new(log_store: LogStoreRef, snapshot: DeltaTableState, mode: SaveMode) -> Self {
+        Self {
+            log_store,
+            snapshot,
+            mode,
+        }
+    }
+}
+
+impl Debug for DeltaDataSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeltaDataSink")
+            .field("table_uri", &self.log_store.root_uri())
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+impl DisplayAs for DeltaDataSink {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DeltaDataSink: mode={:?}", self.mode)
+    }
+}
+
+#[async_trait]
+impl DataSink for DeltaDataSink {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        None
+    }
+
+    async fn write_all(
+        &self,
+        mut data: SendableRecordBatchStream,
+        _context: &Arc<TaskContext>,
+    ) -> DataFusionResult<u64> {
+        let schema = data.schema();
+
+        // read up to the first rows, so that appending no rows does not create a commit
+        let mut head = Vec::new();
+        while let Some(batch) = data.try_next().await? {
+            let has_rows = batch.num_rows() > 0;
+            head.push(batch);
+            if has_rows {
+                break;
+            }
+        }
+        if self.mode == SaveMode::Append && head.iter().all(|batch| batch.num_rows() == 0) {
+            return Ok(0);
+        }
+
+        let count = Arc::new(AtomicU64::new(0));
+        let counter = count.clone();
+        let stream = futures::stream::iter(head.into_iter().map(Ok))
+            .chain(data)
+            .inspect_ok(move |batch| {
+                counter.fetch_add(batch.num_rows() as u64, Ordering::Relaxed);
+            });
+        let stream = Box::pin(RecordBatchStreamAdapter::new(schema, stream));
+        let plan = stream_to_dataframe(stream)?.create_physical_plan().await?;
+
+        WriteBuilder::new(self.log_store.clone(), Some(self.snapshot.clone()))
+            .with_input_execution_plan(plan)
+            .with_save_mode(self.mode)
+            .await?;
+        Ok(count.load(Ordering::Relaxed))
+    }
+}
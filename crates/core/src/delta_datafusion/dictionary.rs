@@ -0,0 +1,194 @@
+//! Preserve the dictionary encoding of parquet columns when scanning a table.
+//!
+//! The arrow parquet reader decodes dictionary encoded pages directly into a
+//! [`DictionaryArray`](arrow_array::DictionaryArray) when the arrow schema embedded in the file
+//! asks for a dictionary type. Files usually do not carry such a hint, so the reader created by
+//! [`DictionaryReaderFactory`] adds it to the file metadata of the selected columns before the
+//! scan plans its reads.
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow_schema::{DataType, Field, Schema};
+use bytes::Bytes;
+use datafusion::datasource::physical_plan::parquet::{
+    DefaultParquetFileReaderFactory, ParquetFileReaderFactory,
+};
+use datafusion::datasource::physical_plan::FileMeta;
+use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
+use futures::future::BoxFuture;
+use object_store::ObjectStore;
+use parquet::arrow::async_reader::AsyncFileReader;
+use parquet::arrow::{add_encoded_arrow_schema_to_metadata, parquet_to_arrow_schema};
+use parquet::file::metadata::{FileMetaData, ParquetMetaData};
+use parquet::file::properties::WriterProperties;
+
+/// Dictionary encode the string and binary columns of `schema` named in `columns`
+pub(crate) fn dictionary_encode_schema(schema: &Schema, columns: &[String]) -> Schema {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| match field.data_type() {
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Binary | DataType::LargeBinary
+                if columns.contains(field.name()) =>
+            {
+                Arc::new(
+                    Field::new_dictionary(
+                        field.name(),
+                        DataType::Int32,
+                        field.data_type().clone(),
+                        field.is_nullable(),
+                    )
+                    .with_metadata(field.metadata().clone()),
+                )
+            }
+            _ => field.clone(),
+        })
+        .collect::<Vec<_>>();
+    Schema::new_with_metadata(fields, schema.metadata().clone())
+}
+
+/// Creates parquet readers decoding the selected columns into dictionary arrays
+#[derive(Debug)]
+pub(crate) struct DictionaryReaderFactory {
+    inner: DefaultParquetFileReaderFactory,
+    columns: Arc<Vec<String>>,
+}
+
+impl DictionaryReaderFactory {
+    pub(crate) fn new(store: Arc<dyn ObjectStore>, columns: Vec<String>) -> Self {
+        Self {
+            inner: DefaultParquetFileReaderFactory::new(store),
+            columns: Arc::new(columns),
+        }
+    }
+}
+
+impl ParquetFileReaderFactory for DictionaryReaderFactory {
+    fn create_reader(
+        &self,
+        partition_index: usize,
+        file_meta: FileMeta,
+        metadata_size_hint: Option<usize>,
+        metrics: &ExecutionPlanMetricsSet,
+    ) -> datafusion_common::Result<Box<dyn AsyncFileReader + Send>> {
+        let inner =
+            self.inner
+                .create_reader(partition_index, file_meta, metadata_size_hint, metrics)?;
+        Ok(Box::new(DictionaryReader {
+            inner,
+            columns: self.columns.clone(),
+        }))
+    }
+}
+
+struct DictionaryReader {
+    inner: Box<dyn AsyncFileReader + Send>,
+    columns: Arc<Vec<String>>,
+}
+
+impl AsyncFileReader for DictionaryReader {
+    fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, parquet::errors::Result<Bytes>> {
+        self.inner.get_bytes(range)
+    }
+
+    fn get_byte_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+    ) -> BoxFuture<'_, parquet::errors::Result<Vec<Bytes>>> {
+        self.inner.get_byte_ranges(ranges)
+    }
+
+    fn get_metadata(&mut self) -> BoxFuture<'_, parquet::errors::Result<Arc<ParquetMetaData>>> {
+        Box::pin(async move {
+            let metadata = self.inner.get_metadata().await?;
+            with_dictionary_hint(metadata, &self.columns)
+        })
+    }
+}
+
+/// Replace the arrow schema hint of the file with one requesting dictionary arrays for `columns`
+fn with_dictionary_hint(
+    metadata: Arc<ParquetMetaData>,
+    columns: &[String],
+) -> parquet::errors::Result<Arc<ParquetMetaData>> {
+    let file_metadata = metadata.file_metadata();
+    let schema = parquet_to_arrow_schema(
+        file_metadata.schema_descr(),
+        file_metadata.key_value_metadata(),
+    )?;
+    let hint = dictionary_encode_schema(&schema, columns);
+    if hint == schema {
+        return Ok(metadata);
+    }
+
+    let mut props = WriterProperties::builder()
+        .set_key_value_metadata(file_metadata.key_value_metadata().cloned())
+        .build();
+    add_encoded_arrow_schema_to_metadata(&hint, &mut props);
+
+    let file_metadata = FileMetaData::new(
+        file_metadata.version(),
+        file_metadata.num_rows(),
+        file_metadata.created_by().map(|s| s.to_string()),
+        props.key_value_metadata().cloned(),
+        file_metadata.schema_descr_ptr(),
+        file_metadata.column_orders().cloned(),
+    );
+    Ok(Arc::new(ParquetMetaData::new_with_page_index(
+        file_metadata,
+        metadata.row_groups().to_vec(),
+        metadata.column_index().cloned(),
+        metadata.offset_index().cloned(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{ArrayRef, RecordBatch, StringArray};
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::footer::parse_metadata;
+
+    use super::*;
+
+    #[test]
+    fn test_dictionary_encode_schema() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new("value", DataType::Int32, true),
+            Field::new("name", DataType::Utf8, false),
+        ]);
+        let encoded = dictionary_encode_schema(&schema, &["id".to_string(), "value".to_string()]);
+        assert_eq!(
+            encoded.field(0).data_type(),
+            &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+        );
+        assert_eq!(encoded.field(1).data_type(), &DataType::Int32);
+        assert_eq!(encoded.field(2).data_type(), &DataType::Utf8);
+    }
+
+    #[test]
+    fn test_dictionary_hint() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "id",
+            Arc::new(StringArray::from(vec!["a", "b", "a"])) as ArrayRef,
+        )])
+        .unwrap();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let metadata = Arc::new(parse_metadata(&Bytes::from(buffer)).unwrap());
+        let metadata = with_dictionary_hint(metadata, &["id".to_string()]).unwrap();
+        let file_metadata = metadata.file_metadata();
+        let schema = parquet_to_arrow_schema(
+            file_metadata.schema_descr(),
+            file_metadata.key_value_metadata(),
+        )
+        .unwrap();
+        assert_eq!(
+            schema.field(0).data_type(),
+            &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+        );
+    }
+}
@@ -33,13 +33,16 @@ use arrow::datatypes::{
 };
 use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
+use arrow_array::cast::AsArray;
 use arrow_array::types::UInt16Type;
 use arrow_array::{Array, DictionaryArray, StringArray, TypedDictionaryArray};
 use arrow_cast::display::array_value_to_string;
 use arrow_schema::Field;
 use async_trait::async_trait;
 use chrono::{DateTime, TimeZone, Utc};
-use datafusion::datasource::physical_plan::parquet::ParquetExecBuilder;
+use datafusion::datasource::physical_plan::parquet::{
+    DefaultParquetFileReaderFactory, ParquetExecBuilder, ParquetFileReaderFactory,
+};
 use datafusion::datasource::physical_plan::{
     wrap_partition_type_in_dict, wrap_partition_value_in_dict, FileScanConfig,
 };
@@ -50,6 +53,7 @@ use datafusion::execution::runtime_env::RuntimeEnv;
 use datafusion::execution::FunctionRegistry;
 use datafusion::physical_optimizer::pruning::PruningPredicate;
 use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::insert::DataSinkExec;
 use datafusion::physical_plan::limit::LocalLimitExec;
 use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
 use datafusion::physical_plan::{
@@ -57,7 +61,7 @@ use datafusion::physical_plan::{
     Statistics,
 };
 use datafusion_common::scalar::ScalarValue;
-use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion, TreeNodeVisitor};
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion, TreeNodeVisitor};
 use datafusion_common::{
     config::ConfigOptions, Column, DFSchema, DataFusionError, Result as DataFusionResult,
     ToDFSchema,
@@ -65,6 +69,7 @@ use datafusion_common::{
 use datafusion_expr::logical_plan::CreateExternalTable;
 use datafusion_expr::utils::conjunction;
 use datafusion_expr::{col, Expr, Extension, LogicalPlan, TableProviderFilterPushDown, Volatility};
+use datafusion_physical_expr::PhysicalExpr;
 use datafusion_proto::logical_plan::LogicalExtensionCodec;
 use datafusion_proto::physical_plan::PhysicalExtensionCodec;
 use datafusion_sql::planner::ParserOptions;
@@ -72,18 +77,29 @@ use either::Either;
 use futures::TryStreamExt;
 use itertools::Itertools;
 use object_store::ObjectMeta;
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::delta_datafusion::data_sink::DeltaDataSink;
+use crate::delta_datafusion::dictionary::{dictionary_encode_schema, DictionaryReaderFactory};
 use crate::delta_datafusion::expr::parse_predicate_expression;
+use crate::delta_datafusion::prefetch::PrefetchReaderFactory;
 use crate::delta_datafusion::schema_adapter::DeltaSchemaAdapterFactory;
 use crate::errors::{DeltaResult, DeltaTableError};
-use crate::kernel::{Add, DataCheck, EagerSnapshot, Invariant, Snapshot, StructTypeExt};
+use crate::kernel::{
+    Add, DataCheck, EagerSnapshot, Invariant, Snapshot, StructType, StructTypeExt,
+};
 use crate::logstore::LogStoreRef;
+use crate::metrics::{self, Counter};
+use crate::operations::cast::cast_to_canonical_types;
+use crate::operations::create::CreateBuilder;
+use crate::operations::transaction::AddContainer;
+use crate::protocol::SaveMode;
 use crate::table::builder::ensure_table_uri;
 use crate::table::state::DeltaTableState;
 use crate::table::Constraint;
-use crate::{open_table, open_table_with_storage_options, DeltaTable};
+use crate::{DeltaTable, DeltaTableBuilder};
 
 const PATH_COLUMN: &str = "__delta_rs_path";
 
@@ -92,7 +108,10 @@ pub mod expr;
 pub mod logical;
 pub mod physical;
 
+mod data_sink;
+mod dictionary;
 mod find_files;
+mod prefetch;
 mod schema_adapter;
 
 impl From<DeltaTableError> for DataFusionError {
@@ -100,7 +119,9 @@ impl From<DeltaTableError> for DataFusionError {
         match err {
             DeltaTableError::Arrow { source } => DataFusionError::ArrowError(source, None),
             DeltaTableError::Io { source } => DataFusionError::IoError(source),
-            DeltaTableError::ObjectStore { source } => DataFusionError::ObjectStore(source),
+            DeltaTableError::ObjectStore { source } | DeltaTableError::Storage { source } => {
+                DataFusionError::ObjectStore(source)
+            }
             DeltaTableError::Parquet { source } => DataFusionError::ParquetError(source),
             _ => DataFusionError::External(Box::new(err)),
         }
@@ -112,7 +133,7 @@ impl From<DataFusionError> for DeltaTableError {
         match err {
             DataFusionError::ArrowError(source, _) => DeltaTableError::Arrow { source },
             DataFusionError::IoError(source) => DeltaTableError::Io { source },
-            DataFusionError::ObjectStore(source) => DeltaTableError::ObjectStore { source },
+            DataFusionError::ObjectStore(source) => source.into(),
             DataFusionError::ParquetError(source) => DeltaTableError::Parquet { source },
             _ => DeltaTableError::Generic(err.to_string()),
         }
@@ -238,6 +259,57 @@ impl DataFusionFileMixins for EagerSnapshot {
     }
 }
 
+/// Create a [`PruningPredicate`] evaluating `predicate` against the file statistics.
+///
+/// Accesses to nested struct fields, e.g. `get_field(s, 'a')`, are rewritten to columns named
+/// by the dot separated path of the field (`s.a`), which resolve to the statistics collected
+/// for the nested field.
+pub(crate) fn stats_pruning_predicate(
+    predicate: Expr,
+    schema: &ArrowSchemaRef,
+) -> DeltaResult<PruningPredicate> {
+    let predicate = predicate
+        .transform_up(&|expr| {
+            if let Expr::ScalarFunction(func) = &expr {
+                if let (
+                    "get_field",
+                    [Expr::Column(column), Expr::Literal(ScalarValue::Utf8(Some(field)))],
+                ) = (func.name(), func.args.as_slice())
+                {
+                    return Ok(Transformed::yes(Expr::Column(Column::new(
+                        column.relation.clone(),
+                        format!("{}.{}", column.name, field),
+                    ))));
+                }
+            }
+            Ok(Transformed::no(expr))
+        })?
+        .data;
+
+    // expose the nested fields as top level columns named by their path
+    let mut fields = schema.fields().iter().cloned().collect::<Vec<_>>();
+    let mut nested = schema
+        .fields()
+        .iter()
+        .map(|field| (field.name().clone(), field.clone()))
+        .collect::<Vec<_>>();
+    while let Some((path, field)) = nested.pop() {
+        if let DataType::Struct(children) = field.data_type() {
+            for child in children.iter() {
+                let child_path = format!("{path}.{}", child.name());
+                if schema.column_with_name(&child_path).is_none() {
+                    fields.push(Arc::new(child.as_ref().clone().with_name(&child_path)));
+                    nested.push((child_path, child.clone()));
+                }
+            }
+        }
+    }
+    let schema = Arc::new(ArrowSchema::new(fields));
+
+    let expr = SessionContext::new().create_physical_expr(predicate, &schema.to_dfschema()?)?;
+    Ok(PruningPredicate::try_new(expr, schema)?)
+}
+
 pub(crate) fn files_matching_predicate<'a>(
     snapshot: &'a EagerSnapshot,
     filters: &[Expr],
@@ -245,10 +317,7 @@ pub(crate) fn files_matching_predicate<'a>(
     if let Some(Some(predicate)) =
         (!filters.is_empty()).then_some(conjunction(filters.iter().cloned()))
     {
-        //let expr = logical_expr_to_physical_expr(predicate, snapshot.arrow_schema()?.as_ref());
-        let expr = SessionContext::new()
-            .create_physical_expr(predicate, &snapshot.arrow_schema()?.to_dfschema()?)?;
-        let pruning_predicate = PruningPredicate::try_new(expr, snapshot.arrow_schema()?)?;
+        let pruning_predicate = stats_pruning_predicate(predicate, &snapshot.arrow_schema()?)?;
         Ok(Either::Left(
             snapshot
                 .file_actions()?
@@ -305,7 +374,8 @@ pub(crate) fn df_logical_schema(
     snapshot: &DeltaTableState,
     scan_config: &DeltaScanConfig,
 ) -> DeltaResult<SchemaRef> {
-    let input_schema = snapshot.arrow_schema()?;
+    let input_schema =
+        dictionary_encode_schema(&snapshot.arrow_schema()?, &scan_config.dictionary_columns);
     let table_partition_cols = &snapshot.metadata().partition_columns;
 
     let mut fields: Vec<Arc<Field>> = input_schema
@@ -344,6 +414,12 @@ pub struct DeltaScanConfigBuilder {
     /// Whether to wrap partition values in a dictionary encoding to potentially save space
     wrap_partition_values: Option<bool>,
     enable_parquet_pushdown: bool,
+    /// String and binary columns read as dictionary arrays
+    dictionary_columns: Vec<String>,
+    /// Options for reading parquet files written by other engines
+    parquet_read_options: ParquetReadOptions,
+    /// Options for the IO of parquet reads
+    parquet_io_options: ParquetIoOptions,
 }
 
 impl Default for DeltaScanConfigBuilder {
@@ -353,6 +429,9 @@ impl Default for DeltaScanConfigBuilder {
             file_column_name: None,
             wrap_partition_values: None,
             enable_parquet_pushdown: true,
+            dictionary_columns: Vec::new(),
+            parquet_read_options: ParquetReadOptions::default(),
+            parquet_io_options: ParquetIoOptions::default(),
         }
     }
 }
@@ -391,6 +470,66 @@ impl DeltaScanConfigBuilder {
         self
     }
 
+    /// Read the given string or binary columns as dictionary arrays.
+    ///
+    /// Dictionary encoded parquet pages are decoded into dictionary arrays without materializing
+    /// the values of every row, which reduces memory usage and speeds up aggregations on columns
+    /// with few distinct values.
+    pub fn with_dictionary_columns(
+        mut self,
+        columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.dictionary_columns = columns.into_iter().map(|c| c.into()).collect();
+        self
+    }
+
+    /// Convert nanosecond timestamps, as INT96 timestamps are read, to the timestamp type of the
+    /// table. When disabled, scanning files with such columns fails.
+    pub fn with_int96_coercion(mut self, coerce: bool) -> Self {
+        self.parquet_read_options.coerce_int96_timestamps = coerce;
+        self
+    }
+
+    /// Read integer columns of files as the unscaled values of the decimal columns of the table.
+    ///
+    /// Some engines store decimals as plain INT32 or INT64 without a decimal annotation. By
+    /// default integer values are cast to decimals of the same value instead.
+    pub fn with_decimals_from_integers(mut self, enable: bool) -> Self {
+        self.parquet_read_options.decimals_from_integers = enable;
+        self
+    }
+
+    /// Cast the columns of files written with an older schema to the current table schema,
+    /// filling columns missing from the files with nulls. When disabled, scanning files whose
+    /// schema differs from the table schema fails.
+    pub fn with_schema_evolution(mut self, enable: bool) -> Self {
+        self.parquet_read_options.schema_evolution = enable;
+        self
+    }
+
+    /// Fetch the columns of the next `row_groups` row groups of a file while the current row
+    /// group is decoded. Prefetching is disabled by default.
+    ///
+    /// Speeds up scans of high latency stores like S3, at the cost of keeping more data in
+    /// memory and of fetching row groups which may be skipped by the scan filter.
+    pub fn with_prefetch_row_groups(mut self, row_groups: usize) -> Self {
+        self.parquet_io_options.prefetch_row_groups = row_groups;
+        self
+    }
+
+    /// Maximum number of concurrent requests when reading a parquet file, 10 by default
+    pub fn with_io_concurrency(mut self, concurrency: usize) -> Self {
+        self.parquet_io_options.concurrency = concurrency;
+        self
+    }
+
+    /// Fetch byte ranges of a parquet file separated by at most `gap` bytes in a single request,
+    /// 1 MiB by default
+    pub fn with_coalesce_gap(mut self, gap: usize) -> Self {
+        self.parquet_io_options.coalesce_gap = gap;
+        self
+    }
+
     /// Build a DeltaScanConfig and ensure no column name conflicts occur during downstream processing
     pub fn build(&self, snapshot: &DeltaTableState) -> DeltaResult<DeltaScanConfig> {
         let file_column_name = if self.include_file_column {
@@ -428,10 +567,31 @@ impl DeltaScanConfigBuilder {
             None
         };
 
+        if !self.dictionary_columns.is_empty() {
+            let input_schema = snapshot.input_schema()?;
+            let partition_columns = &snapshot.metadata().partition_columns;
+            for column in &self.dictionary_columns {
+                let field = input_schema.field_with_name(column)?;
+                let supported = matches!(
+                    field.data_type(),
+                    DataType::Utf8 | DataType::LargeUtf8 | DataType::Binary | DataType::LargeBinary
+                );
+                if !supported || partition_columns.contains(column) {
+                    return Err(DeltaTableError::Generic(format!(
+                        "Unable to read column {} as dictionary, only non partition string and binary columns are supported",
+                        column
+                    )));
+                }
+            }
+        }
+
         Ok(DeltaScanConfig {
             file_column_name,
             wrap_partition_values: self.wrap_partition_values.unwrap_or(true),
             enable_parquet_pushdown: self.enable_parquet_pushdown,
+            dictionary_columns: self.dictionary_columns.clone(),
+            parquet_read_options: self.parquet_read_options.clone(),
+            parquet_io_options: self.parquet_io_options.clone(),
         })
     }
 }
@@ -445,6 +605,59 @@ pub struct DeltaScanConfig {
     pub wrap_partition_values: bool,
     /// Allow pushdown of the scan filter
     pub enable_parquet_pushdown: bool,
+    /// String and binary columns read as dictionary arrays
+    #[serde(default)]
+    pub dictionary_columns: Vec<String>,
+    /// Options for reading parquet files written by other engines
+    #[serde(default)]
+    pub parquet_read_options: ParquetReadOptions,
+    /// Options for the IO of parquet reads
+    #[serde(default)]
+    pub parquet_io_options: ParquetIoOptions,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+/// Options for reading parquet files whose physical types differ from the table schema
+pub struct ParquetReadOptions {
+    /// Convert nanosecond timestamps, as INT96 timestamps are read, to the table timestamp type
+    pub coerce_int96_timestamps: bool,
+    /// Read integer columns as the unscaled values of decimal columns
+    pub decimals_from_integers: bool,
+    /// Cast files written with an older schema to the current table schema
+    pub schema_evolution: bool,
+}
+
+impl Default for ParquetReadOptions {
+    fn default() -> Self {
+        Self {
+            coerce_int96_timestamps: true,
+            decimals_from_integers: false,
+            schema_evolution: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+/// Options for the IO of parquet reads over high latency object stores
+pub struct ParquetIoOptions {
+    /// Number of row groups fetched ahead of the row group being decoded
+    pub prefetch_row_groups: usize,
+    /// Maximum number of concurrent requests per file
+    pub concurrency: usize,
+    /// Byte ranges separated by at most this many bytes are fetched in a single request
+    pub coalesce_gap: usize,
+}
+
+impl Default for ParquetIoOptions {
+    fn default() -> Self {
+        Self {
+            prefetch_row_groups: 0,
+            concurrency: 10,
+            coalesce_gap: 1024 * 1024,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -505,6 +718,9 @@ impl<'a> DeltaScanBuilder<'a> {
     }
 
     pub async fn build(self) -> DeltaResult<DeltaScan> {
+        if self.files.is_none() && !self.snapshot.load_config().require_files {
+            return Err(DeltaTableError::NotInitializedWithFiles("scanning".into()));
+        }
         let config = match self.config {
             Some(config) => config,
             None => DeltaScanConfigBuilder::new().build(self.snapshot)?,
@@ -514,6 +730,14 @@ impl<'a> DeltaScanBuilder<'a> {
             Some(schema) => schema,
             None => self.snapshot.arrow_schema()?,
         };
+        let schema = if config.dictionary_columns.is_empty() {
+            schema
+        } else {
+            Arc::new(dictionary_encode_schema(
+                &schema,
+                &config.dictionary_columns,
+            ))
+        };
         let logical_schema = df_logical_schema(self.snapshot, &config)?;
 
         let logical_schema = if let Some(used_columns) = self.projection {
@@ -530,44 +754,75 @@ impl<'a> DeltaScanBuilder<'a> {
         let df_schema = logical_schema.clone().to_dfschema()?;
         let logical_filter = self
             .filter
+            .clone()
             .map(|expr| context.create_physical_expr(expr, &df_schema).unwrap());
 
         // Perform Pruning of files to scan
-        let (files, files_scanned, files_pruned) = match self.files {
+        let mut scan_metrics = DeltaScanMetrics::default();
+        let mut record_pruned = |action: &Add| {
+            scan_metrics.files_pruned += 1;
+            scan_metrics.rows_skipped_by_stats += action
+                .get_stats()
+                .ok()
+                .flatten()
+                .map(|stats| stats.num_records as usize)
+                .unwrap_or(0);
+        };
+        let files = match self.files {
             Some(files) => {
                 let files = files.to_owned();
-                let files_scanned = files.len();
-                (files, files_scanned, 0)
+                if let Some(predicate) = &self.filter {
+                    // evaluate the file statistics of the selected files against the predicate
+                    let pruning_predicate =
+                        stats_pruning_predicate(predicate.clone(), &logical_schema)?;
+                    let files_to_prune = pruning_predicate.prune(&AddContainer::new(
+                        &files,
+                        &self.snapshot.metadata().partition_columns,
+                        logical_schema.clone(),
+                    ))?;
+                    files
+                        .into_iter()
+                        .zip(files_to_prune)
+                        .filter_map(|(action, keep)| {
+                            if !keep {
+                                record_pruned(&action);
+                            }
+                            keep.then_some(action)
+                        })
+                        .collect::<Vec<_>>()
+                } else {
+                    files
+                }
             }
             None => {
-                if let Some(predicate) = &logical_filter {
+                if let Some(predicate) = &self.filter {
                     let pruning_predicate =
-                        PruningPredicate::try_new(predicate.clone(), logical_schema.clone())?;
+                        stats_pruning_predicate(predicate.clone(), &logical_schema)?;
                     let files_to_prune = pruning_predicate.prune(self.snapshot)?;
-                    let mut files_pruned = 0usize;
-                    let files = self
-                        .snapshot
+                    self.snapshot
                         .file_actions_iter()?
                         .zip(files_to_prune.into_iter())
                         .filter_map(|(action, keep)| {
                             if keep {
                                 Some(action.to_owned())
                             } else {
-                                files_pruned += 1;
+                                record_pruned(&action);
                                 None
                             }
                         })
-                        .collect::<Vec<_>>();
-
-                    let files_scanned = files.len();
-                    (files, files_scanned, files_pruned)
+                        .collect::<Vec<_>>()
                 } else {
-                    let files = self.snapshot.file_actions()?;
-                    let files_scanned = files.len();
-                    (files, files_scanned, 0)
+                    self.snapshot.file_actions()?
                 }
             }
         };
+        scan_metrics.files_scanned = files.len();
+        metrics::increment(
+            Counter::ScanFilesPruned,
+            scan_metrics.files_pruned as u64,
+            &[],
+        );
+        scan_metrics.bytes_scanned = files.iter().map(|action| action.size as usize).sum();
 
         // TODO we group files together by their partition values. If the table is partitioned
         // and partitions are somewhat evenly distributed, probably not the worst choice ...
@@ -636,7 +891,31 @@ impl<'a> DeltaScanBuilder<'a> {
             table_partition_cols,
             output_ordering: vec![],
         })
-        .with_schema_adapter_factory(Arc::new(DeltaSchemaAdapterFactory {}));
+        .with_schema_adapter_factory(Arc::new(DeltaSchemaAdapterFactory::new(
+            config.parquet_read_options.clone(),
+        )));
+
+        let mut reader_factory: Option<Arc<dyn ParquetFileReaderFactory>> = None;
+        if !config.dictionary_columns.is_empty() {
+            reader_factory = Some(Arc::new(DictionaryReaderFactory::new(
+                self.log_store.object_store(),
+                config.dictionary_columns.clone(),
+            )));
+        }
+        if config.parquet_io_options != ParquetIoOptions::default() {
+            let store = self.log_store.object_store();
+            let inner = reader_factory
+                .take()
+                .unwrap_or_else(|| Arc::new(DefaultParquetFileReaderFactory::new(store.clone())));
+            reader_factory = Some(Arc::new(PrefetchReaderFactory::new(
+                store,
+                inner,
+                config.parquet_io_options.clone(),
+            )));
+        }
+        if let Some(reader_factory) = reader_factory {
+            exec_plan_builder = exec_plan_builder.with_parquet_file_reader_factory(reader_factory);
+        }
 
         // Sometimes (i.e Merge) we want to prune files that don't make the
         // filter and read the entire contents for files that do match the
@@ -648,12 +927,7 @@ impl<'a> DeltaScanBuilder<'a> {
         };
 
         let metrics = ExecutionPlanMetricsSet::new();
-        MetricBuilder::new(&metrics)
-            .global_counter("files_scanned")
-            .add(files_scanned);
-        MetricBuilder::new(&metrics)
-            .global_counter("files_pruned")
-            .add(files_pruned);
+        scan_metrics.register(&metrics);
 
         Ok(DeltaScan {
             table_uri: ensure_table_uri(self.log_store.root_uri())?.as_str().into(),
@@ -697,13 +971,17 @@ impl TableProvider for DeltaTable {
     ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
         register_store(self.log_store(), session.runtime_env().clone());
         let filter_expr = conjunction(filters.iter().cloned());
+        let snapshot = self.snapshot()?;
+        let files = files_matching_partition_filters(snapshot, filters).await?;
 
-        let scan = DeltaScanBuilder::new(self.snapshot()?, self.log_store())
+        let mut scan_builder = DeltaScanBuilder::new(snapshot, self.log_store())
             .with_projection(projection)
             .with_limit(limit)
-            .with_filter(filter_expr)
-            .build()
-            .await?;
+            .with_filter(filter_expr);
+        if let Some(files) = &files {
+            scan_builder = scan_builder.with_files(files);
+        }
+        let scan = scan_builder.build().await?;
 
         Ok(Arc::new(scan))
     }
@@ -712,15 +990,37 @@ impl TableProvider for DeltaTable {
         &self,
         filter: &[&Expr],
     ) -> DataFusionResult<Vec<TableProviderFilterPushDown>> {
-        Ok(filter
-            .into_iter()
-            .map(|_| TableProviderFilterPushDown::Inexact)
-            .collect())
+        Ok(filters_pushdown(
+            filter,
+            &self.snapshot()?.metadata().partition_columns,
+        ))
     }
 
     fn statistics(&self) -> Option<Statistics> {
         self.snapshot().ok()?.datafusion_table_statistics()
     }
+
+    /// Write the output of `input` to the table in a single commit. The registered table keeps
+    /// the snapshot it was registered with, so it has to be reloaded to read the inserted data.
+    async fn insert_into(
+        &self,
+        _state: &SessionState,
+        input: Arc<dyn ExecutionPlan>,
+        overwrite: bool,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let mode = if overwrite {
+            SaveMode::Overwrite
+        } else {
+            SaveMode::Append
+        };
+        let sink = DeltaDataSink::new(self.log_store(), self.snapshot()?.clone(), mode);
+        Ok(Arc::new(DataSinkExec::new(
+            input,
+            Arc::new(sink),
+            self.schema(),
+            None,
+        )))
+    }
 }
 
 /// A Delta table provider that enables additional metadata columns to be included during the scan
@@ -778,23 +1078,29 @@ impl TableProvider for DeltaTableProvider {
     ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
         register_store(self.log_store.clone(), session.runtime_env().clone());
         let filter_expr = conjunction(filters.iter().cloned());
+        let files = files_matching_partition_filters(&self.snapshot, filters).await?;
 
-        let scan = DeltaScanBuilder::new(&self.snapshot, self.log_store.clone())
+        let mut scan_builder = DeltaScanBuilder::new(&self.snapshot, self.log_store.clone())
             .with_projection(projection)
             .with_limit(limit)
             .with_filter(filter_expr)
-            .with_scan_config(self.config.clone())
-            .build()
-            .await?;
+            .with_scan_config(self.config.clone());
+        if let Some(files) = &files {
+            scan_builder = scan_builder.with_files(files);
+        }
+        let scan = scan_builder.build().await?;
 
         Ok(Arc::new(scan))
     }
 
     fn supports_filters_pushdown(
         &self,
-        _filter: &[&Expr],
+        filter: &[&Expr],
     ) -> DataFusionResult<Vec<TableProviderFilterPushDown>> {
-        Ok(vec![TableProviderFilterPushDown::Inexact])
+        Ok(filters_pushdown(
+            filter,
+            &self.snapshot.metadata().partition_columns,
+        ))
     }
 
     fn statistics(&self) -> Option<Statistics> {
@@ -802,6 +1108,106 @@ impl TableProvider for DeltaTableProvider {
     }
 }
 
+/// Whether `filter` only references partition columns, so that it can be evaluated exactly
+/// against the partition values of the files
+fn is_partition_filter(filter: &Expr, partition_columns: &[String]) -> bool {
+    let mut expr_properties = FindFilesExprProperties {
+        partition_only: true,
+        partition_columns: partition_columns.to_vec(),
+        result: Ok(()),
+    };
+    TreeNode::visit(filter, &mut expr_properties).is_ok()
+        && expr_properties.result.is_ok()
+        && expr_properties.partition_only
+}
+
+/// Classify the pushdown of filters for the scan of a table partitioned by `partition_columns`
+///
+/// Filters on partition columns are [`Exact`](TableProviderFilterPushDown::Exact), as the scan
+/// only reads the files whose partition values match them. File statistics only bound the values
+/// of the other columns, so filters on these are [`Inexact`](TableProviderFilterPushDown::Inexact)
+/// and still applied by DataFusion.
+fn filters_pushdown(
+    filters: &[&Expr],
+    partition_columns: &[String],
+) -> Vec<TableProviderFilterPushDown> {
+    filters
+        .iter()
+        .map(|filter| {
+            if is_partition_filter(filter, partition_columns) {
+                TableProviderFilterPushDown::Exact
+            } else {
+                TableProviderFilterPushDown::Inexact
+            }
+        })
+        .collect()
+}
+
+/// The files matching the partition filters among `filters`, if there are any
+async fn files_matching_partition_filters(
+    snapshot: &DeltaTableState,
+    filters: &[Expr],
+) -> DeltaResult<Option<Vec<Add>>> {
+    if snapshot.files_count() == 0 {
+        return Ok(None);
+    }
+    let partition_columns = &snapshot.metadata().partition_columns;
+    let partition_filter = conjunction(
+        filters
+            .iter()
+            .filter(|filter| is_partition_filter(filter, partition_columns))
+            .cloned(),
+    );
+    match partition_filter {
+        Some(predicate) => Ok(Some(scan_memory_table(snapshot, &predicate).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Data skipping metrics of a [`DeltaScan`]
+///
+/// The metrics are also reported through [`ExecutionPlan::metrics`] under the names of the
+/// fields, e.g. to be shown by `EXPLAIN ANALYZE`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeltaScanMetrics {
+    /// Number of files read by the scan
+    pub files_scanned: usize,
+    /// Number of files skipped based on their partition values and statistics
+    pub files_pruned: usize,
+    /// Total size in bytes of the files read by the scan
+    pub bytes_scanned: usize,
+    /// Number of rows in the skipped files, as recorded in their statistics
+    pub rows_skipped_by_stats: usize,
+}
+
+impl DeltaScanMetrics {
+    const FILES_SCANNED: &'static str = "files_scanned";
+    const FILES_PRUNED: &'static str = "files_pruned";
+    const BYTES_SCANNED: &'static str = "bytes_scanned";
+    const ROWS_SKIPPED_BY_STATS: &'static str = "rows_skipped_by_stats";
+
+    fn register(&self, metrics: &ExecutionPlanMetricsSet) {
+        for (name, value) in [
+            (Self::FILES_SCANNED, self.files_scanned),
+            (Self::FILES_PRUNED, self.files_pruned),
+            (Self::BYTES_SCANNED, self.bytes_scanned),
+            (Self::ROWS_SKIPPED_BY_STATS, self.rows_skipped_by_stats),
+        ] {
+            MetricBuilder::new(metrics).global_counter(name).add(value);
+        }
+    }
+
+    fn from_metrics(metrics: &MetricsSet) -> Self {
+        let value = |name: &str| metrics.sum_by_name(name).map(|m| m.as_usize()).unwrap_or(0);
+        Self {
+            files_scanned: value(Self::FILES_SCANNED),
+            files_pruned: value(Self::FILES_PRUNED),
+            bytes_scanned: value(Self::BYTES_SCANNED),
+            rows_skipped_by_stats: value(Self::ROWS_SKIPPED_BY_STATS),
+        }
+    }
+}
+
 // TODO: this will likely also need to perform column mapping later when we support reader protocol v2
 /// A wrapper for parquet scans
 #[derive(Debug)]
@@ -818,6 +1224,13 @@ pub struct DeltaScan {
     metrics: ExecutionPlanMetricsSet,
 }
 
+impl DeltaScan {
+    /// Data skipping metrics of the scan
+    pub fn scan_metrics(&self) -> DeltaScanMetrics {
+        DeltaScanMetrics::from_metrics(&self.metrics.clone_inner())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct DeltaScanWire {
     pub table_uri: String,
@@ -1115,50 +1528,83 @@ pub(crate) async fn execute_plan_to_batch(
 }
 
 /// Responsible for checking batches of data conform to table's invariants.
+///
+/// Check expressions are compiled once per input schema and cached, clones of a checker share
+/// the same cache so that batches processed in parallel do not need to plan the checks again.
 #[derive(Clone)]
 pub struct DeltaDataChecker {
     constraints: Vec<Constraint>,
     invariants: Vec<Invariant>,
     ctx: SessionContext,
+    compiled: Arc<RwLock<HashMap<SchemaRef, Arc<CompiledChecks>>>>,
+}
+
+/// Invariants and constraints compiled against a specific schema
+struct CompiledChecks {
+    invariants: Vec<CompiledCheck>,
+    constraints: Vec<CompiledCheck>,
+}
+
+struct CompiledCheck {
+    expression: String,
+    /// Columns reported when the check is violated
+    columns: Vec<usize>,
+    predicate: Arc<dyn PhysicalExpr>,
+}
+
+impl CompiledCheck {
+    /// Index of the first row for which the check evaluates to false
+    fn first_violation(&self, record_batch: &RecordBatch) -> DeltaResult<Option<usize>> {
+        let result = self
+            .predicate
+            .evaluate(record_batch)?
+            .into_array(record_batch.num_rows())?;
+        let result = result.as_boolean_opt().ok_or_else(|| {
+            DeltaTableError::Generic(format!(
+                "Check or Invariant ({}) does not evaluate to a boolean",
+                self.expression
+            ))
+        })?;
+        Ok(result.iter().position(|value| value == Some(false)))
+    }
 }
 
 impl DeltaDataChecker {
-    /// Create a new DeltaDataChecker with no invariants or constraints
-    pub fn empty() -> Self {
+    fn with_checks(invariants: Vec<Invariant>, constraints: Vec<Constraint>) -> Self {
         Self {
-            invariants: vec![],
-            constraints: vec![],
+            invariants,
+            constraints,
             ctx: DeltaSessionContext::default().into(),
+            compiled: Default::default(),
         }
     }
 
+    /// Create a new DeltaDataChecker with no invariants or constraints
+    pub fn empty() -> Self {
+        Self::with_checks(vec![], vec![])
+    }
+
     /// Create a new DeltaDataChecker with a specified set of invariants
     pub fn new_with_invariants(invariants: Vec<Invariant>) -> Self {
-        Self {
-            invariants,
-            constraints: vec![],
-            ctx: DeltaSessionContext::default().into(),
-        }
+        Self::with_checks(invariants, vec![])
     }
 
     /// Create a new DeltaDataChecker with a specified set of constraints
     pub fn new_with_constraints(constraints: Vec<Constraint>) -> Self {
-        Self {
-            constraints,
-            invariants: vec![],
-            ctx: DeltaSessionContext::default().into(),
-        }
+        Self::with_checks(vec![], constraints)
     }
 
     /// Specify the Datafusion context
     pub fn with_session_context(mut self, context: SessionContext) -> Self {
         self.ctx = context;
+        self.compiled = Default::default();
         self
     }
 
     /// Add the specified set of constraints to the current DeltaDataChecker's constraints
     pub fn with_extra_constraints(mut self, constraints: Vec<Constraint>) -> Self {
         self.constraints.extend(constraints);
+        self.compiled = Default::default();
         self
     }
 
@@ -1166,11 +1612,7 @@ impl DeltaDataChecker {
     pub fn new(snapshot: &DeltaTableState) -> Self {
         let invariants = snapshot.schema().get_invariants().unwrap_or_default();
         let constraints = snapshot.table_config().get_constraints();
-        Self {
-            invariants,
-            constraints,
-            ctx: DeltaSessionContext::default().into(),
-        }
+        Self::with_checks(invariants, constraints)
     }
 
     /// Check that a record batch conforms to table's invariants.
@@ -1178,63 +1620,84 @@ impl DeltaDataChecker {
     /// If it does not, it will return [DeltaTableError::InvalidData] with a list
     /// of values that violated each invariant.
     pub async fn check_batch(&self, record_batch: &RecordBatch) -> Result<(), DeltaTableError> {
-        self.enforce_checks(record_batch, &self.invariants).await?;
-        self.enforce_checks(record_batch, &self.constraints).await
+        if self.invariants.is_empty() && self.constraints.is_empty() {
+            return Ok(());
+        }
+        let record_batch = &cast_to_canonical_types(record_batch)?;
+        let compiled = self.compiled_checks(record_batch.schema())?;
+        enforce_checks(record_batch, &compiled.invariants)?;
+        enforce_checks(record_batch, &compiled.constraints)
     }
 
-    async fn enforce_checks<C: DataCheck>(
-        &self,
-        record_batch: &RecordBatch,
-        checks: &[C],
-    ) -> Result<(), DeltaTableError> {
-        if checks.is_empty() {
-            return Ok(());
+    fn compiled_checks(&self, schema: SchemaRef) -> DeltaResult<Arc<CompiledChecks>> {
+        if let Some(compiled) = self.compiled.read().get(&schema) {
+            return Ok(compiled.clone());
         }
-        let table = MemTable::try_new(record_batch.schema(), vec![vec![record_batch.clone()]])?;
+        let compiled = Arc::new(CompiledChecks {
+            invariants: self.compile(&schema, &self.invariants)?,
+            constraints: self.compile(&schema, &self.constraints)?,
+        });
+        self.compiled.write().insert(schema, compiled.clone());
+        Ok(compiled)
+    }
 
-        // Use a random table name to avoid clashes when running multiple parallel tasks, e.g. when using a partitioned table
-        let table_name: String = uuid::Uuid::new_v4().to_string();
-        self.ctx.register_table(&table_name, Arc::new(table))?;
+    fn compile<C: DataCheck>(
+        &self,
+        schema: &SchemaRef,
+        checks: &[C],
+    ) -> DeltaResult<Vec<CompiledCheck>> {
+        let df_schema = schema.clone().to_dfschema()?;
+        let state = self.ctx.state();
+        checks
+            .iter()
+            .map(|check| {
+                if check.get_name().contains('.') {
+                    return Err(DeltaTableError::Generic(
+                        "Support for nested columns is not supported.".to_string(),
+                    ));
+                }
+                let columns = if check.get_name() == "*" {
+                    (0..schema.fields().len()).collect()
+                } else {
+                    vec![schema.index_of(check.get_name())?]
+                };
+                let expr = parse_predicate_expression(&df_schema, check.get_expression(), &state)?;
+                Ok(CompiledCheck {
+                    expression: check.get_expression().to_string(),
+                    columns,
+                    predicate: state.create_physical_expr(expr, &df_schema)?,
+                })
+            })
+            .collect()
+    }
+}
 
-        let mut violations: Vec<String> = Vec::new();
+fn enforce_checks(record_batch: &RecordBatch, checks: &[CompiledCheck]) -> DeltaResult<()> {
+    let mut violations: Vec<String> = Vec::new();
 
-        for check in checks {
-            if check.get_name().contains('.') {
-                return Err(DeltaTableError::Generic(
-                    "Support for nested columns is not supported.".to_string(),
-                ));
-            }
+    for check in checks {
+        if let Some(row) = check.first_violation(record_batch)? {
+            let value: String = check
+                .columns
+                .iter()
+                .map(|i| {
+                    array_value_to_string(record_batch.column(*i), row)
+                        .unwrap_or(String::from("null"))
+                })
+                .join(", ");
 
-            let sql = format!(
-                "SELECT {} FROM `{}` WHERE NOT ({}) LIMIT 1",
-                check.get_name(),
-                table_name,
-                check.get_expression()
+            let msg = format!(
+                "Check or Invariant ({}) violated by value in row: [{}]",
+                check.expression, value
             );
-
-            let dfs: Vec<RecordBatch> = self.ctx.sql(&sql).await?.collect().await?;
-            if !dfs.is_empty() && dfs[0].num_rows() > 0 {
-                let value: String = dfs[0]
-                    .columns()
-                    .iter()
-                    .map(|c| array_value_to_string(c, 0).unwrap_or(String::from("null")))
-                    .join(", ");
-
-                let msg = format!(
-                    "Check or Invariant ({}) violated by value in row: [{}]",
-                    check.get_expression(),
-                    value
-                );
-                violations.push(msg);
-            }
+            violations.push(msg);
         }
+    }
 
-        self.ctx.deregister_table(&table_name)?;
-        if !violations.is_empty() {
-            Err(DeltaTableError::InvalidData { violations })
-        } else {
-            Ok(())
-        }
+    if !violations.is_empty() {
+        Err(DeltaTableError::InvalidData { violations })
+    } else {
+        Ok(())
     }
 }
 
@@ -1328,7 +1791,14 @@ impl LogicalExtensionCodec for DeltaLogicalCodec {
     }
 }
 
+/// File types under which [`register_delta_table_factory`] registers the [`DeltaTableFactory`]
+pub const DELTA_TABLE_FILE_TYPES: [&str; 2] = ["DELTA", "DELTATABLE"];
+
 /// Responsible for creating deltatables
+///
+/// `CREATE EXTERNAL TABLE` attaches the Delta table at the location, passing the `OPTIONS` of the
+/// statement as storage options. When there is no table at the location yet, it is created with
+/// the columns and partition columns of the statement.
 pub struct DeltaTableFactory {}
 
 #[async_trait]
@@ -1338,12 +1808,39 @@ impl TableProviderFactory for DeltaTableFactory {
         _ctx: &SessionState,
         cmd: &CreateExternalTable,
     ) -> datafusion::error::Result<Arc<dyn TableProvider>> {
-        let provider = if cmd.options.is_empty() {
-            open_table(cmd.to_owned().location).await?
-        } else {
-            open_table_with_storage_options(cmd.to_owned().location, cmd.to_owned().options).await?
-        };
-        Ok(Arc::new(provider))
+        let mut table = DeltaTableBuilder::from_uri(&cmd.location)
+            .with_storage_options(cmd.options.clone())
+            .build()?;
+        if table.log_store().is_delta_table_location().await? {
+            table.load().await?;
+            return Ok(Arc::new(table));
+        }
+
+        if cmd.schema.fields().is_empty() {
+            return Err(DataFusionError::Plan(format!(
+                "No Delta table found at {}, columns must be provided to create one",
+                cmd.location
+            )));
+        }
+        let schema: ArrowSchema = cmd.schema.as_ref().into();
+        let schema: StructType = (&schema).try_into()?;
+        let table = CreateBuilder::new()
+            .with_log_store(table.log_store())
+            .with_table_name(cmd.name.table())
+            .with_columns(schema.fields().cloned())
+            .with_partition_columns(cmd.table_partition_cols.clone())
+            .await?;
+        Ok(Arc::new(table))
+    }
+}
+
+/// Register the [`DeltaTableFactory`] with `state`, so that `CREATE EXTERNAL TABLE` statements
+/// `STORED AS DELTA` create or attach Delta tables
+pub fn register_delta_table_factory(state: &mut SessionState) {
+    for file_type in DELTA_TABLE_FILE_TYPES {
+        state
+            .table_factories_mut()
+            .insert(file_type.to_string(), Arc::new(DeltaTableFactory {}));
     }
 }
 
@@ -1736,14 +2233,14 @@ impl From<Column> for DeltaColumn {
 #[cfg(test)]
 mod tests {
     use crate::operations::write::SchemaMode;
-    use crate::writer::test_utils::get_delta_schema;
+    use crate::writer::test_utils::{get_delta_schema, get_record_batch};
     use arrow::array::StructArray;
     use arrow::datatypes::{DataType, Field, Schema};
     use chrono::{TimeZone, Utc};
     use datafusion::assert_batches_sorted_eq;
     use datafusion::datasource::physical_plan::ParquetExec;
     use datafusion::physical_plan::empty::EmptyExec;
-    use datafusion::physical_plan::{visit_execution_plan, ExecutionPlanVisitor};
+    use datafusion::physical_plan::{displayable, visit_execution_plan, ExecutionPlanVisitor};
     use datafusion_expr::lit;
     use datafusion_physical_expr::PhysicalExpr;
     use datafusion_proto::physical_plan::AsExecutionPlan;
@@ -1940,6 +2437,43 @@ mod tests {
         assert!(matches!(result, Err(DeltaTableError::Generic { .. })));
     }
 
+    #[tokio::test]
+    async fn test_checks_compiled_once_per_schema() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["a", "b"])),
+                Arc::new(arrow::array::Int32Array::from(vec![Some(1), None])),
+            ],
+        )
+        .unwrap();
+        let checker = DeltaDataChecker::new_with_constraints(vec![Constraint::new("*", "b < 10")]);
+        let cloned = checker.clone();
+        checker.check_batch(&batch).await.unwrap();
+        cloned.check_batch(&batch).await.unwrap();
+        assert_eq!(checker.compiled.read().len(), 1);
+
+        let schema = Arc::new(Schema::new(vec![Field::new("b", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(arrow::array::Int32Array::from(vec![5, 20]))],
+        )
+        .unwrap();
+        let result = cloned.check_batch(&batch).await;
+        assert_eq!(checker.compiled.read().len(), 2);
+        match result {
+            Err(DeltaTableError::InvalidData { violations }) => assert_eq!(
+                violations,
+                vec!["Check or Invariant (b < 10) violated by value in row: [20]".to_string()]
+            ),
+            other => panic!("Expected violation, got {other:?}"),
+        }
+    }
+
     #[test]
     fn roundtrip_test_delta_exec_plan() {
         let ctx = SessionContext::new();
@@ -1998,6 +2532,73 @@ mod tests {
         assert_batches_sorted_eq!(&expected, &actual);
     }
 
+    #[tokio::test]
+    async fn delta_table_provider_with_dictionary_columns() {
+        let table = crate::DeltaOps::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_partition_columns(["modified"])
+            .await
+            .unwrap();
+        let table = crate::DeltaOps(table)
+            .write(vec![get_record_batch(None, false)])
+            .await
+            .unwrap();
+
+        let snapshot = table.snapshot().unwrap();
+        assert!(DeltaScanConfigBuilder::new()
+            .with_dictionary_columns(["value"])
+            .build(snapshot)
+            .is_err());
+        assert!(DeltaScanConfigBuilder::new()
+            .with_dictionary_columns(["modified"])
+            .build(snapshot)
+            .is_err());
+
+        let config = DeltaScanConfigBuilder::new()
+            .with_dictionary_columns(["id"])
+            .build(snapshot)
+            .unwrap();
+        let provider =
+            DeltaTableProvider::try_new(snapshot.clone(), table.log_store(), config).unwrap();
+        let dictionary_type =
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        assert_eq!(
+            provider.schema().field_with_name("id").unwrap().data_type(),
+            &dictionary_type
+        );
+
+        let ctx = SessionContext::new();
+        ctx.register_table("test", Arc::new(provider)).unwrap();
+        let actual = ctx
+            .sql("select id from test where value > 5")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert!(actual
+            .iter()
+            .all(|batch| batch.column(0).data_type() == &dictionary_type));
+
+        let actual = ctx
+            .sql("select id, count(*) as n from test group by id order by id")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let expected = vec![
+            "+----+---+",
+            "| id | n |",
+            "+----+---+",
+            "| A  | 7 |",
+            "| B  | 4 |",
+            "+----+---+",
+        ];
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
     #[tokio::test]
     async fn delta_scan_mixed_partition_order() {
         // Tests issue (1787) where partition columns were incorrect when they
@@ -2415,6 +3016,146 @@ mod tests {
         assert!(visitor.pruning_predicate.is_none());
     }
 
+    #[tokio::test]
+    async fn test_scan_table_from_object_store() {
+        let store: crate::storage::ObjectStoreRef = Arc::new(object_store::memory::InMemory::new());
+        let table = DeltaTableBuilder::from_object_store(store, Path::from("a/table"))
+            .build()
+            .unwrap();
+        let arr: Arc<dyn Array> = Arc::new(arrow::array::StringArray::from(vec!["s", "t"]));
+        let batch = RecordBatch::try_from_iter_with_nullable(vec![("a", arr, false)]).unwrap();
+        let table = crate::DeltaOps(table).write(vec![batch]).await.unwrap();
+
+        let ctx = SessionContext::new();
+        ctx.register_table("test", Arc::new(table)).unwrap();
+        let batches = ctx
+            .sql("SELECT a FROM test")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let expected = vec!["+---+", "| a |", "+---+", "| s |", "| t |", "+---+"];
+        assert_batches_sorted_eq!(&expected, &batches);
+    }
+
+    #[tokio::test]
+    async fn test_delta_scan_builder_prunes_selected_files() {
+        let batch = |value: &str| {
+            let arr: Arc<dyn Array> = Arc::new(arrow::array::StringArray::from(vec![value]));
+            RecordBatch::try_from_iter_with_nullable(vec![("a", arr, false)]).unwrap()
+        };
+        let table = crate::DeltaOps::new_in_memory()
+            .write(vec![batch("s")])
+            .await
+            .unwrap();
+        let table = crate::DeltaOps(table)
+            .write(vec![batch("t")])
+            .with_save_mode(crate::protocol::SaveMode::Append)
+            .await
+            .unwrap();
+
+        let snapshot = table.snapshot().unwrap();
+        let files = snapshot.file_actions().unwrap();
+        assert_eq!(files.len(), 2);
+        let scan = DeltaScanBuilder::new(snapshot, table.log_store())
+            .with_files(&files)
+            .with_filter(Some(col("a").eq(lit("s"))))
+            .build()
+            .await
+            .unwrap();
+
+        let metrics = scan.metrics().unwrap();
+        assert_eq!(metrics.sum_by_name("files_scanned").unwrap().as_usize(), 1);
+        assert_eq!(metrics.sum_by_name("files_pruned").unwrap().as_usize(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delta_scan_metrics() {
+        let batch = |values: Vec<&str>| {
+            let arr: Arc<dyn Array> = Arc::new(arrow::array::StringArray::from(values));
+            RecordBatch::try_from_iter_with_nullable(vec![("a", arr, false)]).unwrap()
+        };
+        let table = crate::DeltaOps::new_in_memory()
+            .write(vec![batch(vec!["s"])])
+            .await
+            .unwrap();
+        let table = crate::DeltaOps(table)
+            .write(vec![batch(vec!["t", "u"])])
+            .with_save_mode(crate::protocol::SaveMode::Append)
+            .await
+            .unwrap();
+
+        let snapshot = table.snapshot().unwrap();
+        let scan = DeltaScanBuilder::new(snapshot, table.log_store())
+            .with_filter(Some(col("a").eq(lit("s"))))
+            .build()
+            .await
+            .unwrap();
+
+        let scanned = snapshot
+            .file_actions()
+            .unwrap()
+            .into_iter()
+            .find(|add| add.get_stats().unwrap().unwrap().num_records == 1)
+            .unwrap();
+        assert_eq!(
+            scan.scan_metrics(),
+            DeltaScanMetrics {
+                files_scanned: 1,
+                files_pruned: 1,
+                bytes_scanned: scanned.size as usize,
+                rows_skipped_by_stats: 2,
+            }
+        );
+        let metrics = scan.metrics().unwrap();
+        assert_eq!(
+            metrics
+                .sum_by_name("rows_skipped_by_stats")
+                .unwrap()
+                .as_usize(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delta_scan_prunes_nested_fields() {
+        use datafusion_functions::core::expr_ext::FieldAccessor;
+
+        let batch = |value: i32| {
+            let fields = vec![Field::new("a", ArrowDataType::Int32, true)];
+            let values: Arc<dyn Array> = Arc::new(arrow::array::Int32Array::from(vec![value]));
+            let arr: Arc<dyn Array> = Arc::new(StructArray::new(fields.into(), vec![values], None));
+            RecordBatch::try_from_iter(vec![("s", arr)]).unwrap()
+        };
+        let table = crate::DeltaOps::new_in_memory()
+            .write(vec![batch(1)])
+            .await
+            .unwrap();
+        let table = crate::DeltaOps(table)
+            .write(vec![batch(2)])
+            .with_save_mode(crate::protocol::SaveMode::Append)
+            .await
+            .unwrap();
+
+        let snapshot = table.snapshot().unwrap();
+        let scan = DeltaScanBuilder::new(snapshot, table.log_store())
+            .with_filter(Some(col("s").field("a").eq(lit(1))))
+            .build()
+            .await
+            .unwrap();
+        let metrics = scan.scan_metrics();
+        assert_eq!(metrics.files_scanned, 1);
+        assert_eq!(metrics.files_pruned, 1);
+
+        let files = snapshot
+            .snapshot
+            .files_matching_predicate(&[col("s").field("a").gt(lit(1))])
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(files.len(), 1);
+    }
+
     #[derive(Default)]
     struct ParquetPredicateVisitor {
         predicate: Option<Arc<dyn PhysicalExpr>>,
@@ -2432,4 +3173,56 @@ mod tests {
             Ok(true)
         }
     }
+
+    #[tokio::test]
+    async fn test_exact_partition_filters_pushdown() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", ArrowDataType::Int32, false),
+            Field::new("part", ArrowDataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(arrow::array::Int32Array::from(vec![1, 2, 3])),
+                Arc::new(arrow::array::StringArray::from(vec![
+                    Some("a"),
+                    Some("b"),
+                    None,
+                ])),
+            ],
+        )
+        .unwrap();
+        let table = crate::DeltaOps::new_in_memory()
+            .write(vec![batch])
+            .with_partition_columns(["part"])
+            .await
+            .unwrap();
+
+        let pushdown = table
+            .supports_filters_pushdown(&[&col("part").eq(lit("a")), &col("id").eq(lit(1))])
+            .unwrap();
+        assert_eq!(
+            pushdown,
+            vec![
+                TableProviderFilterPushDown::Exact,
+                TableProviderFilterPushDown::Inexact
+            ]
+        );
+
+        let ctx = SessionContext::new();
+        ctx.register_table("test", Arc::new(table)).unwrap();
+        for (predicate, expected_id) in [("part = 'a'", "| 1  |"), ("part IS NULL", "| 3  |")] {
+            let df = ctx
+                .sql(&format!("SELECT id FROM test WHERE {predicate}"))
+                .await
+                .unwrap();
+            let plan = df.clone().create_physical_plan().await.unwrap();
+            let plan = displayable(plan.as_ref()).indent(true).to_string();
+            assert!(!plan.contains("FilterExec"), "{plan}");
+
+            let batches = df.collect().await.unwrap();
+            let expected = vec!["+----+", "| id |", "+----+", expected_id, "+----+"];
+            assert_batches_sorted_eq!(&expected, &batches);
+        }
+    }
 }
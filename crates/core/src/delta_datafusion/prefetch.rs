@@ -0,0 +1,367 @@
+//! Coalesce and prefetch the reads of parquet files during a scan.
+//!
+//! Object stores like S3 or Azure Blob Storage serve large requests efficiently, but add tens of
+//! milliseconds of latency to every request. The readers created by [`PrefetchReaderFactory`]
+//! merge the byte ranges requested by the parquet decoder when the gap between them is small,
+//! fetch the merged ranges concurrently and start fetching the same columns of the following row
+//! groups while the decoder processes the current one.
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use datafusion::datasource::physical_plan::parquet::ParquetFileReaderFactory;
+use datafusion::datasource::physical_plan::FileMeta;
+use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
+use futures::future::BoxFuture;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::ObjectStore;
+use parquet::arrow::async_reader::AsyncFileReader;
+use parquet::errors::{ParquetError, Result as ParquetResult};
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use tokio::task::JoinHandle;
+
+use super::ParquetIoOptions;
+
+/// Creates parquet readers coalescing and prefetching the reads of the `inner` readers
+#[derive(Debug)]
+pub(crate) struct PrefetchReaderFactory {
+    store: Arc<dyn ObjectStore>,
+    inner: Arc<dyn ParquetFileReaderFactory>,
+    options: ParquetIoOptions,
+}
+
+impl PrefetchReaderFactory {
+    pub(crate) fn new(
+        store: Arc<dyn ObjectStore>,
+        inner: Arc<dyn ParquetFileReaderFactory>,
+        options: ParquetIoOptions,
+    ) -> Self {
+        Self {
+            store,
+            inner,
+            options,
+        }
+    }
+}
+
+impl ParquetFileReaderFactory for PrefetchReaderFactory {
+    fn create_reader(
+        &self,
+        partition_index: usize,
+        file_meta: FileMeta,
+        metadata_size_hint: Option<usize>,
+        metrics: &ExecutionPlanMetricsSet,
+    ) -> datafusion_common::Result<Box<dyn AsyncFileReader + Send>> {
+        let location = file_meta.location().clone();
+        let inner =
+            self.inner
+                .create_reader(partition_index, file_meta, metadata_size_hint, metrics)?;
+        Ok(Box::new(PrefetchReader {
+            inner,
+            store: self.store.clone(),
+            location,
+            options: self.options.clone(),
+            metadata: None,
+            prefetched: BTreeMap::new(),
+        }))
+    }
+}
+
+/// Ranges of a row group fetched in the background
+struct Prefetch {
+    ranges: Vec<Range<usize>>,
+    data: JoinHandle<ParquetResult<Vec<Bytes>>>,
+}
+
+/// Reads the file metadata with the inner reader and the column chunks with the object store
+struct PrefetchReader {
+    inner: Box<dyn AsyncFileReader + Send>,
+    store: Arc<dyn ObjectStore>,
+    location: Path,
+    options: ParquetIoOptions,
+    metadata: Option<Arc<ParquetMetaData>>,
+    prefetched: BTreeMap<usize, Prefetch>,
+}
+
+impl PrefetchReader {
+    async fn read_ranges(&mut self, ranges: Vec<Range<usize>>) -> ParquetResult<Vec<Bytes>> {
+        let metadata = self.metadata.clone();
+        let row_group = metadata
+            .as_ref()
+            .zip(ranges.first())
+            .and_then(|(metadata, range)| {
+                row_group_of(metadata, range.start).map(|row_group| (metadata, row_group))
+            });
+        let Some((metadata, row_group)) = row_group else {
+            return fetch_ranges(self.store.as_ref(), &self.location, &ranges, &self.options).await;
+        };
+
+        // row groups before the current one were skipped by the decoder
+        let pending = self.prefetched.split_off(&row_group);
+        for (_, skipped) in std::mem::replace(&mut self.prefetched, pending) {
+            skipped.data.abort();
+        }
+        let prefetch = self.prefetched.remove(&row_group);
+        self.schedule(metadata, row_group, &ranges);
+
+        let prefetched = match prefetch {
+            Some(prefetch) => match prefetch.data.await {
+                Ok(Ok(data)) => Some((prefetch.ranges, data)),
+                // failed prefetches are retried with the actual request
+                _ => None,
+            },
+            None => None,
+        };
+        let Some((prefetched_ranges, prefetched_data)) = prefetched else {
+            return fetch_ranges(self.store.as_ref(), &self.location, &ranges, &self.options).await;
+        };
+
+        let mut result = ranges
+            .iter()
+            .map(|range| {
+                prefetched_ranges
+                    .iter()
+                    .zip(&prefetched_data)
+                    .find(|(prefetched, _)| {
+                        prefetched.start <= range.start && range.end <= prefetched.end
+                    })
+                    .map(|(prefetched, data)| {
+                        data.slice(range.start - prefetched.start..range.end - prefetched.start)
+                    })
+            })
+            .collect::<Vec<_>>();
+        let missing = ranges
+            .iter()
+            .zip(&result)
+            .filter(|(_, data)| data.is_none())
+            .map(|(range, _)| range.clone())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            let mut fetched =
+                fetch_ranges(self.store.as_ref(), &self.location, &missing, &self.options)
+                    .await?
+                    .into_iter();
+            for data in result.iter_mut().filter(|data| data.is_none()) {
+                *data = fetched.next();
+            }
+        }
+        Ok(result.into_iter().flatten().collect())
+    }
+
+    /// Start fetching the columns read from `row_group` for the following row groups
+    fn schedule(&mut self, metadata: &ParquetMetaData, row_group: usize, ranges: &[Range<usize>]) {
+        if self.options.prefetch_row_groups == 0 {
+            return;
+        }
+        let columns = metadata
+            .row_group(row_group)
+            .columns()
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| {
+                let chunk = chunk_range(column);
+                ranges
+                    .iter()
+                    .any(|range| range.start < chunk.end && chunk.start < range.end)
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        let next_row_groups = (row_group + 1..metadata.num_row_groups())
+            .take(self.options.prefetch_row_groups)
+            .collect::<Vec<_>>();
+        for next in next_row_groups {
+            if self.prefetched.contains_key(&next) {
+                continue;
+            }
+            let ranges = columns
+                .iter()
+                .map(|index| chunk_range(metadata.row_group(next).column(*index)))
+                .collect::<Vec<_>>();
+            let store = self.store.clone();
+            let location = self.location.clone();
+            let options = self.options.clone();
+            let requested = ranges.clone();
+            let data = tokio::spawn(async move {
+                fetch_ranges(store.as_ref(), &location, &requested, &options).await
+            });
+            self.prefetched.insert(next, Prefetch { ranges, data });
+        }
+    }
+}
+
+impl AsyncFileReader for PrefetchReader {
+    fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, ParquetResult<Bytes>> {
+        self.inner.get_bytes(range)
+    }
+
+    fn get_byte_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+    ) -> BoxFuture<'_, ParquetResult<Vec<Bytes>>> {
+        Box::pin(self.read_ranges(ranges))
+    }
+
+    fn get_metadata(&mut self) -> BoxFuture<'_, ParquetResult<Arc<ParquetMetaData>>> {
+        Box::pin(async move {
+            let metadata = self.inner.get_metadata().await?;
+            self.metadata = Some(metadata.clone());
+            Ok(metadata)
+        })
+    }
+}
+
+impl Drop for PrefetchReader {
+    fn drop(&mut self) {
+        for prefetch in self.prefetched.values() {
+            prefetch.data.abort();
+        }
+    }
+}
+
+fn chunk_range(column: &ColumnChunkMetaData) -> Range<usize> {
+    let (start, length) = column.byte_range();
+    start as usize..(start + length) as usize
+}
+
+fn row_group_of(metadata: &ParquetMetaData, offset: usize) -> Option<usize> {
+    metadata.row_groups().iter().position(|row_group| {
+        row_group
+            .columns()
+            .iter()
+            .any(|column| chunk_range(column).contains(&offset))
+    })
+}
+
+/// Merge ranges separated by at most `gap` bytes, the merged ranges are sorted by their start
+fn coalesce_ranges(ranges: &[Range<usize>], gap: usize) -> Vec<Range<usize>> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort_unstable_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(sorted.len());
+    for range in sorted {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end.saturating_add(gap) => {
+                last.end = last.end.max(range.end);
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Fetch the ranges in as few requests as allowed by the coalescing gap
+async fn fetch_ranges(
+    store: &dyn ObjectStore,
+    location: &Path,
+    ranges: &[Range<usize>],
+    options: &ParquetIoOptions,
+) -> ParquetResult<Vec<Bytes>> {
+    let merged = coalesce_ranges(ranges, options.coalesce_gap);
+    let fetched = futures::stream::iter(merged.iter().cloned())
+        .map(|range| store.get_range(location, range))
+        .buffered(options.concurrency.max(1))
+        .try_collect::<Vec<_>>()
+        .await
+        .map_err(|err| ParquetError::External(Box::new(err)))?;
+    Ok(ranges
+        .iter()
+        .map(|range| {
+            let index = merged.partition_point(|merged| merged.start <= range.start) - 1;
+            let offset = merged[index].start;
+            fetched[index].slice(range.start - offset..range.end - offset)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+    use datafusion::datasource::physical_plan::parquet::DefaultParquetFileReaderFactory;
+    use object_store::memory::InMemory;
+    use parquet::arrow::{ArrowWriter, ParquetRecordBatchStreamBuilder};
+    use parquet::file::properties::WriterProperties;
+
+    use super::*;
+    use crate::storage::{InstrumentedStore, ObjectStoreMetrics, ObjectStoreOperation};
+
+    #[test]
+    fn test_coalesce_ranges() {
+        let ranges = vec![10..20, 0..5, 22..30, 100..110, 25..40];
+        assert_eq!(coalesce_ranges(&ranges, 2), vec![0..5, 10..40, 100..110]);
+        assert_eq!(coalesce_ranges(&ranges, 100), vec![0..110]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_ranges() {
+        let metrics = Arc::new(ObjectStoreMetrics::new());
+        metrics.set_enabled(true);
+        let store = InstrumentedStore::new(InMemory::new(), metrics.clone());
+        let location = Path::from("data.parquet");
+        store.put(&location, "0123456789".into()).await.unwrap();
+
+        let options = ParquetIoOptions {
+            coalesce_gap: 2,
+            ..Default::default()
+        };
+        let data = fetch_ranges(&store, &location, &[6..9, 0..2, 3..5], &options)
+            .await
+            .unwrap();
+        assert_eq!(data, vec![Bytes::from("678"), "01".into(), "34".into()]);
+        let requests = metrics.snapshot()[&ObjectStoreOperation::Get].requests;
+        assert_eq!(requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_reader() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "id",
+                Arc::new(Int64Array::from_iter_values(0..100)) as ArrayRef,
+            ),
+            (
+                "value",
+                Arc::new(StringArray::from_iter_values(
+                    (0..100).map(|i| format!("value-{i}")),
+                )) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(10)
+            .build();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let location = Path::from("part-00000.parquet");
+        store.put(&location, buffer.into()).await.unwrap();
+        let meta = store.head(&location).await.unwrap();
+
+        let options = ParquetIoOptions {
+            prefetch_row_groups: 2,
+            ..Default::default()
+        };
+        let factory = PrefetchReaderFactory::new(
+            store.clone(),
+            Arc::new(DefaultParquetFileReaderFactory::new(store.clone())),
+            options,
+        );
+        let reader = factory
+            .create_reader(0, meta.into(), None, &ExecutionPlanMetricsSet::new())
+            .unwrap();
+        let batches = ParquetRecordBatchStreamBuilder::new(reader)
+            .await
+            .unwrap()
+            .build()
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let result = arrow::compute::concat_batches(&batch.schema(), &batches).unwrap();
+        assert_eq!(result, batch);
+    }
+}
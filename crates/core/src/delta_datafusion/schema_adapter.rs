@@ -1,19 +1,33 @@
+use crate::delta_datafusion::ParquetReadOptions;
 use crate::operations::cast::cast_record_batch;
-use arrow_array::RecordBatch;
-use arrow_schema::{Schema, SchemaRef};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Decimal128Type, Int64Type};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_cast::cast;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
 use datafusion::datasource::schema_adapter::{SchemaAdapter, SchemaAdapterFactory, SchemaMapper};
+use datafusion_common::DataFusionError;
 use std::fmt::Debug;
 use std::sync::Arc;
 
 /// A Schema Adapter Factory which provides casting record batches from parquet to meet
 /// delta lake conventions.
 #[derive(Debug)]
-pub(crate) struct DeltaSchemaAdapterFactory {}
+pub(crate) struct DeltaSchemaAdapterFactory {
+    options: ParquetReadOptions,
+}
+
+impl DeltaSchemaAdapterFactory {
+    pub(crate) fn new(options: ParquetReadOptions) -> Self {
+        Self { options }
+    }
+}
 
 impl SchemaAdapterFactory for DeltaSchemaAdapterFactory {
     fn create(&self, schema: SchemaRef) -> Box<dyn SchemaAdapter> {
         Box::new(DeltaSchemaAdapter {
             table_schema: schema,
+            options: self.options.clone(),
         })
     }
 }
@@ -21,6 +35,50 @@ impl SchemaAdapterFactory for DeltaSchemaAdapterFactory {
 pub(crate) struct DeltaSchemaAdapter {
     /// Schema for the table
     table_schema: SchemaRef,
+    /// Options for reading files whose types differ from the table schema
+    options: ParquetReadOptions,
+}
+
+impl DeltaSchemaAdapter {
+    /// Ensure the columns of a file can be read into the table schema
+    fn check_file_schema(&self, file_schema: &Schema) -> datafusion_common::Result<()> {
+        for field in self.table_schema.fields() {
+            let Some((_, file_field)) = file_schema.fields().find(field.name()) else {
+                if self.options.schema_evolution {
+                    continue;
+                }
+                return Err(DataFusionError::Execution(format!(
+                    "Column {} of the table schema is missing from the file, enable schema evolution to read it as nulls",
+                    field.name()
+                )));
+            };
+            if file_field.data_type().equals_datatype(field.data_type()) {
+                continue;
+            }
+            match (file_field.data_type(), field.data_type()) {
+                (DataType::Timestamp(TimeUnit::Nanosecond, _), DataType::Timestamp(_, _))
+                    if !self.options.coerce_int96_timestamps =>
+                {
+                    return Err(DataFusionError::Execution(format!(
+                        "Column {} is stored as nanosecond timestamps, enable int96 coercion to read it as {}",
+                        field.name(),
+                        field.data_type()
+                    )));
+                }
+                (DataType::Timestamp(TimeUnit::Nanosecond, _), DataType::Timestamp(_, _)) => {}
+                (DataType::Int32 | DataType::Int64, DataType::Decimal128(_, _))
+                    if self.options.decimals_from_integers => {}
+                (file_type, table_type) if !self.options.schema_evolution => {
+                    return Err(DataFusionError::Execution(format!(
+                        "Column {} is stored as {file_type} but the table schema expects {table_type}, enable schema evolution to cast it",
+                        field.name()
+                    )));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
 }
 
 impl SchemaAdapter for DeltaSchemaAdapter {
@@ -33,6 +91,8 @@ impl SchemaAdapter for DeltaSchemaAdapter {
         &self,
         file_schema: &Schema,
     ) -> datafusion_common::Result<(Arc<dyn SchemaMapper>, Vec<usize>)> {
+        self.check_file_schema(file_schema)?;
+
         let mut projection = Vec::with_capacity(file_schema.fields().len());
 
         for (file_idx, file_field) in file_schema.fields.iter().enumerate() {
@@ -44,6 +104,7 @@ impl SchemaAdapter for DeltaSchemaAdapter {
         Ok((
             Arc::new(SchemaMapping {
                 table_schema: self.table_schema.clone(),
+                options: self.options.clone(),
             }),
             projection,
         ))
@@ -53,16 +114,174 @@ impl SchemaAdapter for DeltaSchemaAdapter {
 #[derive(Debug)]
 pub(crate) struct SchemaMapping {
     table_schema: SchemaRef,
+    options: ParquetReadOptions,
+}
+
+impl SchemaMapping {
+    /// Reinterpret integer columns as the unscaled values of the decimal columns of the table
+    fn decimals_from_integers(&self, batch: RecordBatch) -> datafusion_common::Result<RecordBatch> {
+        let schema = batch.schema();
+        let mut fields = Vec::with_capacity(schema.fields().len());
+        let mut columns = Vec::with_capacity(batch.num_columns());
+        for (field, column) in schema.fields().iter().zip(batch.columns()) {
+            match (
+                field.data_type(),
+                self.table_schema
+                    .field_with_name(field.name())
+                    .map(|f| f.data_type()),
+            ) {
+                (DataType::Int32 | DataType::Int64, Ok(DataType::Decimal128(precision, scale))) => {
+                    let values = cast(column, &DataType::Int64)?;
+                    let decimals = values
+                        .as_primitive::<Int64Type>()
+                        .unary::<_, Decimal128Type>(|v| v as i128)
+                        .with_precision_and_scale(*precision, *scale)?;
+                    fields.push(Arc::new(Field::new(
+                        field.name(),
+                        DataType::Decimal128(*precision, *scale),
+                        field.is_nullable(),
+                    )));
+                    columns.push(Arc::new(decimals) as ArrayRef);
+                }
+                _ => {
+                    fields.push(field.clone());
+                    columns.push(column.clone());
+                }
+            }
+        }
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+            columns,
+        )?)
+    }
+
+    fn map(&self, batch: RecordBatch, add_missing: bool) -> datafusion_common::Result<RecordBatch> {
+        let batch = if self.options.decimals_from_integers {
+            self.decimals_from_integers(batch)?
+        } else {
+            batch
+        };
+        let record_batch =
+            cast_record_batch(&batch, self.table_schema.clone(), false, add_missing)?;
+        Ok(record_batch)
+    }
 }
 
 impl SchemaMapper for SchemaMapping {
     fn map_batch(&self, batch: RecordBatch) -> datafusion_common::Result<RecordBatch> {
-        let record_batch = cast_record_batch(&batch, self.table_schema.clone(), false, true)?;
-        Ok(record_batch)
+        self.map(batch, self.options.schema_evolution)
     }
 
     fn map_partial_batch(&self, batch: RecordBatch) -> datafusion_common::Result<RecordBatch> {
-        let record_batch = cast_record_batch(&batch, self.table_schema.clone(), false, true)?;
-        Ok(record_batch)
+        // partial batches only hold the columns of a filter
+        self.map(batch, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::types::TimestampMicrosecondType;
+    use arrow_array::{
+        Decimal128Array, Int32Array, Int64Array, TimestampMicrosecondArray,
+        TimestampNanosecondArray,
+    };
+
+    fn table_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("amount", DataType::Decimal128(10, 2), true),
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                true,
+            ),
+        ]))
+    }
+
+    fn file_batch() -> RecordBatch {
+        RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("id", DataType::Int32, true),
+                Field::new("amount", DataType::Int64, true),
+                Field::new("ts", DataType::Timestamp(TimeUnit::Nanosecond, None), true),
+            ])),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(Int64Array::from(vec![1234, -5])),
+                Arc::new(TimestampNanosecondArray::from(vec![
+                    1_000_001_000,
+                    2_000_002_999,
+                ])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn map_file_batch(options: ParquetReadOptions) -> datafusion_common::Result<RecordBatch> {
+        let adapter = DeltaSchemaAdapterFactory::new(options).create(table_schema());
+        let batch = file_batch();
+        let (mapper, _) = adapter.map_schema(batch.schema().as_ref())?;
+        mapper.map_batch(batch)
+    }
+
+    #[test]
+    fn test_coerce_file_columns() {
+        let batch = map_file_batch(ParquetReadOptions {
+            decimals_from_integers: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(batch.schema(), table_schema());
+        assert_eq!(
+            batch.column(1).as_primitive::<Decimal128Type>(),
+            &Decimal128Array::from(vec![1234, -5])
+                .with_precision_and_scale(10, 2)
+                .unwrap()
+        );
+        assert_eq!(
+            batch.column(2).as_primitive::<TimestampMicrosecondType>(),
+            &TimestampMicrosecondArray::from(vec![1_000_001, 2_000_002]).with_timezone("UTC")
+        );
+
+        // integers are cast to decimals of the same value by default
+        let batch = map_file_batch(ParquetReadOptions::default()).unwrap();
+        assert_eq!(
+            batch.column(1).as_primitive::<Decimal128Type>(),
+            &Decimal128Array::from(vec![123400, -500])
+                .with_precision_and_scale(10, 2)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_reject_file_columns() {
+        let result = map_file_batch(ParquetReadOptions {
+            coerce_int96_timestamps: false,
+            ..Default::default()
+        });
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("enable int96 coercion"));
+
+        let result = map_file_batch(ParquetReadOptions {
+            schema_evolution: false,
+            decimals_from_integers: true,
+            ..Default::default()
+        });
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Column id is stored as Int32"));
+
+        let adapter = DeltaSchemaAdapterFactory::new(ParquetReadOptions {
+            schema_evolution: false,
+            ..Default::default()
+        })
+        .create(table_schema());
+        let file_schema = Schema::new(vec![Field::new("id", DataType::Int64, true)]);
+        assert!(adapter.map_schema(&file_schema).is_err());
     }
 }
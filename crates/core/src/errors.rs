@@ -1,7 +1,15 @@
 //! Exceptions for the deltalake crate
+//!
+//! Every [`DeltaTableError`] is classified by an [`ErrorCode`], see [`DeltaTableError::code`],
+//! and [`DeltaTableError::is_retryable`] tells whether an operation failing with it may succeed
+//! when run again.
+use std::fmt::{self, Display, Formatter};
+use std::io::ErrorKind;
+
+use chrono::{DateTime, Utc};
 use object_store::Error as ObjectStoreError;
 
-use crate::operations::transaction::{CommitBuilderError, TransactionError};
+use crate::operations::transaction::{CommitBuilderError, CommitConflictError, TransactionError};
 use crate::protocol::ProtocolError;
 
 /// A result returned by delta-rs
@@ -21,7 +29,14 @@ pub enum DeltaTableError {
     #[error("Failed to read delta log object: {}", .source)]
     ObjectStore {
         /// Storage error details when reading the delta log object failed.
-        #[from]
+        source: ObjectStoreError,
+    },
+
+    /// Error returned when the storage backend failed to serve a request, e.g. due to
+    /// throttling, a network failure or a timeout
+    #[error("Storage operation failed: {source}")]
+    Storage {
+        /// The error of the storage backend
         source: ObjectStoreError,
     },
 
@@ -120,6 +135,19 @@ pub enum DeltaTableError {
         msg: String,
     },
 
+    /// Error returned when existing data violates a constraint that is added to the table
+    #[error("Constraint {constraint} is violated by existing data: {violations:#?}")]
+    ConstraintViolation {
+        /// Name of the constraint
+        constraint: String,
+        /// Descriptions of the violating rows
+        violations: Vec<String>,
+    },
+
+    /// Error returned when an operation is configured with an invalid or missing argument
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
     /// Error returned when a partition is not formatted as a Hive Partition.
     #[error("This partition is not formatted with key=value: {}", .partition)]
     PartitionError {
@@ -163,6 +191,13 @@ pub enum DeltaTableError {
         source: TransactionError,
     },
 
+    /// Error returned when the commit conflicts with a concurrently committed transaction
+    #[error("Commit failed: {kind}")]
+    CommitConflict {
+        /// The kind of conflict
+        kind: CommitConflictError,
+    },
+
     /// Error returned when transaction is failed to be committed because given version already exists.
     #[error("Delta transaction failed, version {0} already exists.")]
     VersionAlreadyExists(i64),
@@ -221,6 +256,16 @@ pub enum DeltaTableError {
     #[error("Table has not yet been initialized")]
     NotInitialized,
 
+    #[error("Table has not yet been initialized with files, therefore {0} is not supported")]
+    NotInitializedWithFiles(String),
+
+    #[error("Table has been loaded with partition filters, therefore {0} is not supported")]
+    LoadedWithPartitionFilters(String),
+
+    /// The operation has been cancelled through its cancellation token
+    #[error("Operation was cancelled")]
+    Cancelled,
+
     #[error("Change Data not enabled for version: {version}, Start: {start}, End: {end}")]
     ChangeDataNotRecorded { version: i64, start: i64, end: i64 },
 
@@ -229,6 +274,32 @@ pub enum DeltaTableError {
 
     #[error("Invalid version start version {start} is greater than version {end}")]
     ChangeDataInvalidVersionRange { start: i64, end: i64 },
+
+    /// The timestamp to time travel to is before the creation of the table
+    #[error("The provided timestamp {timestamp} is before the table was created at {committed_at} with version {version}")]
+    TimestampBeforeEarliestVersion {
+        timestamp: DateTime<Utc>,
+        version: i64,
+        committed_at: DateTime<Utc>,
+    },
+
+    /// The timestamp to time travel to is after the latest commit
+    #[error("The provided timestamp {timestamp} is after the latest version {version} committed at {committed_at}")]
+    TimestampAfterLatestVersion {
+        timestamp: DateTime<Utc>,
+        version: i64,
+        committed_at: DateTime<Utc>,
+    },
+}
+
+impl From<ObjectStoreError> for DeltaTableError {
+    fn from(source: ObjectStoreError) -> Self {
+        if is_backend_failure(&source) {
+            DeltaTableError::Storage { source }
+        } else {
+            DeltaTableError::ObjectStore { source }
+        }
+    }
 }
 
 impl From<object_store::path::Error> for DeltaTableError {
@@ -244,7 +315,7 @@ impl From<ProtocolError> for DeltaTableError {
         match value {
             ProtocolError::Arrow { source } => DeltaTableError::Arrow { source },
             ProtocolError::IO { source } => DeltaTableError::Io { source },
-            ProtocolError::ObjectStore { source } => DeltaTableError::ObjectStore { source },
+            ProtocolError::ObjectStore { source } => source.into(),
             ProtocolError::ParquetParseError { source } => DeltaTableError::Parquet { source },
             _ => DeltaTableError::Protocol { source: value },
         }
@@ -257,7 +328,163 @@ impl From<serde_json::Error> for DeltaTableError {
     }
 }
 
+/// Machine readable classification of a [`DeltaTableError`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// Reading from or writing to the storage backend failed
+    Storage,
+    /// The commit conflicts with a transaction committed concurrently
+    CommitConflict,
+    /// Data violates a constraint, an invariant or a property of the table
+    ConstraintViolation,
+    /// Data or a schema does not match the schema of the table
+    SchemaMismatch,
+    /// The table, a version of it or one of its files does not exist
+    NotFound,
+    /// The log of the table is invalid or violates the Delta protocol
+    Protocol,
+    /// The table requires a feature that is not supported or not enabled
+    Unsupported,
+    /// An operation was configured with an invalid argument
+    InvalidArgument,
+    /// The operation was cancelled
+    Cancelled,
+    /// Any other error
+    Internal,
+}
+
+impl ErrorCode {
+    /// The code as a stable string, e.g. for logs and metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Storage => "DELTA_STORAGE",
+            Self::CommitConflict => "DELTA_COMMIT_CONFLICT",
+            Self::ConstraintViolation => "DELTA_CONSTRAINT_VIOLATION",
+            Self::SchemaMismatch => "DELTA_SCHEMA_MISMATCH",
+            Self::NotFound => "DELTA_NOT_FOUND",
+            Self::Protocol => "DELTA_PROTOCOL",
+            Self::Unsupported => "DELTA_UNSUPPORTED",
+            Self::InvalidArgument => "DELTA_INVALID_ARGUMENT",
+            Self::Cancelled => "DELTA_CANCELLED",
+            Self::Internal => "DELTA_INTERNAL",
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl DeltaTableError {
+    /// Classify the error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::ObjectStore { source } => object_store_code(source),
+            Self::Storage { .. } | Self::Io { .. } => ErrorCode::Storage,
+            Self::CommitConflict { .. } => ErrorCode::CommitConflict,
+            Self::Transaction { source } => match source {
+                TransactionError::VersionAlreadyExists(_)
+                | TransactionError::CommitConflict(_)
+                | TransactionError::MaxCommitAttempts(_) => ErrorCode::CommitConflict,
+                TransactionError::ObjectStore { source } => object_store_code(source),
+                TransactionError::LogStoreError { .. } => ErrorCode::Storage,
+                TransactionError::DeltaTableAppendOnly => ErrorCode::ConstraintViolation,
+                TransactionError::UnsupportedReaderFeatures(_)
+                | TransactionError::UnsupportedWriterFeatures(_)
+                | TransactionError::WriterFeaturesRequired(_)
+                | TransactionError::ReaderFeaturesRequired(_) => ErrorCode::Unsupported,
+                TransactionError::SerializeLogJson { .. } => ErrorCode::Internal,
+            },
+            Self::VersionAlreadyExists(_) | Self::VersionMismatch(_, _) => {
+                ErrorCode::CommitConflict
+            }
+            Self::InvalidData { .. } | Self::ConstraintViolation { .. } => {
+                ErrorCode::ConstraintViolation
+            }
+            Self::SchemaMismatch { .. } => ErrorCode::SchemaMismatch,
+            Self::NotATable(_) | Self::MissingDataFile { .. } | Self::NotInitialized => {
+                ErrorCode::NotFound
+            }
+            Self::Protocol { .. }
+            | Self::InvalidJsonLog { .. }
+            | Self::InvalidStatsJson { .. }
+            | Self::InvalidInvariantJson { .. }
+            | Self::NoMetadata
+            | Self::NoSchema
+            | Self::MetadataError(_) => ErrorCode::Protocol,
+            Self::MissingFeature { .. }
+            | Self::ChangeDataNotRecorded { .. }
+            | Self::ChangeDataNotEnabled { .. }
+            | Self::NotInitializedWithFiles(_)
+            | Self::LoadedWithPartitionFilters(_) => ErrorCode::Unsupported,
+            Self::InvalidArgument(_)
+            | Self::InvalidVersion(_)
+            | Self::InvalidDateTimeString { .. }
+            | Self::InvalidTableLocation(_)
+            | Self::LoadPartitions
+            | Self::PartitionError { .. }
+            | Self::InvalidPartitionFilter { .. }
+            | Self::ColumnsNotPartitioned { .. }
+            | Self::ChangeDataInvalidVersionRange { .. }
+            | Self::TimestampBeforeEarliestVersion { .. }
+            | Self::TimestampAfterLatestVersion { .. } => ErrorCode::InvalidArgument,
+            Self::Cancelled => ErrorCode::Cancelled,
+            Self::KernelError(_)
+            | Self::Kernel { .. }
+            | Self::Parquet { .. }
+            | Self::Arrow { .. }
+            | Self::CommitValidation { .. }
+            | Self::SerializeLogJson { .. }
+            | Self::SerializeSchemaJson { .. }
+            | Self::Generic(_)
+            | Self::GenericError { .. } => ErrorCode::Internal,
+        }
+    }
+
+    /// The kind of conflict, if a concurrent transaction prevented the commit
+    pub fn commit_conflict(&self) -> Option<&CommitConflictError> {
+        match self {
+            Self::CommitConflict { kind }
+            | Self::Transaction {
+                source: TransactionError::CommitConflict(kind),
+            } => Some(kind),
+            _ => None,
+        }
+    }
+
+    /// Whether running the failed operation again may succeed.
+    ///
+    /// This is the case for [`Storage`](Self::Storage) errors and for commits that lost the race
+    /// against a concurrent transaction, as long as the table's metadata and protocol are
+    /// unchanged. Operations have to be retried from a freshly loaded table, not just
+    /// re-committed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Storage { .. } => true,
+            Self::CommitConflict { kind } => commit_conflict_is_retryable(kind),
+            Self::Io { source } => matches!(
+                source.kind(),
+                ErrorKind::TimedOut
+                    | ErrorKind::Interrupted
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+            ),
+            Self::VersionAlreadyExists(_) => true,
+            Self::Transaction { source } => match source {
+                TransactionError::VersionAlreadyExists(_)
+                | TransactionError::MaxCommitAttempts(_)
+                | TransactionError::LogStoreError { .. } => true,
+                TransactionError::ObjectStore { source } => is_backend_failure(source),
+                TransactionError::CommitConflict(kind) => commit_conflict_is_retryable(kind),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
     /// Crate a NotATable Error with message for given path.
     pub fn not_a_table(path: impl AsRef<str>) -> Self {
         let msg = format!(
@@ -267,3 +494,86 @@ impl DeltaTableError {
         Self::NotATable(msg)
     }
 }
+
+fn object_store_code(err: &ObjectStoreError) -> ErrorCode {
+    match err {
+        ObjectStoreError::NotFound { .. } => ErrorCode::NotFound,
+        ObjectStoreError::InvalidPath { .. } => ErrorCode::InvalidArgument,
+        ObjectStoreError::NotSupported { .. } | ObjectStoreError::NotImplemented => {
+            ErrorCode::Unsupported
+        }
+        _ => ErrorCode::Storage,
+    }
+}
+
+/// Only generic errors of the object store, e.g. raised by network failures or timeouts of
+/// the backend, are failures of the backend. Missing, existing or inaccessible objects are not.
+fn is_backend_failure(err: &ObjectStoreError) -> bool {
+    matches!(err, ObjectStoreError::Generic { .. })
+}
+
+/// Conflicts with blind appends or deletes of other files can be resolved by running the
+/// operation again, changes to the metadata or protocol of the table can't.
+fn commit_conflict_is_retryable(kind: &CommitConflictError) -> bool {
+    matches!(
+        kind,
+        CommitConflictError::ConcurrentAppend
+            | CommitConflictError::ConcurrentDeleteRead
+            | CommitConflictError::ConcurrentDeleteDelete
+            | CommitConflictError::ConcurrentTransaction
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        let err = DeltaTableError::from(TransactionError::CommitConflict(
+            CommitConflictError::ConcurrentAppend,
+        ));
+        assert!(matches!(
+            err,
+            DeltaTableError::CommitConflict {
+                kind: CommitConflictError::ConcurrentAppend
+            }
+        ));
+        assert_eq!(err.code(), ErrorCode::CommitConflict);
+        assert!(matches!(
+            err.commit_conflict(),
+            Some(CommitConflictError::ConcurrentAppend)
+        ));
+        assert!(err.is_retryable());
+
+        let err = DeltaTableError::from(TransactionError::CommitConflict(
+            CommitConflictError::MetadataChanged,
+        ));
+        assert_eq!(err.code(), ErrorCode::CommitConflict);
+        assert!(!err.is_retryable());
+
+        let err = DeltaTableError::from(ObjectStoreError::NotFound {
+            path: "a".to_string(),
+            source: "missing".into(),
+        });
+        assert!(matches!(err, DeltaTableError::ObjectStore { .. }));
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        assert!(!err.is_retryable());
+
+        let err = DeltaTableError::from(ObjectStoreError::Generic {
+            store: "S3",
+            source: "connection reset".into(),
+        });
+        assert!(matches!(err, DeltaTableError::Storage { .. }));
+        assert_eq!(err.code(), ErrorCode::Storage);
+        assert!(err.is_retryable());
+
+        let err = DeltaTableError::ConstraintViolation {
+            constraint: "id_positive".to_string(),
+            violations: vec![],
+        };
+        assert_eq!(err.code().as_str(), "DELTA_CONSTRAINT_VIOLATION");
+        assert!(!err.is_retryable());
+        assert_eq!(DeltaTableError::Cancelled.code(), ErrorCode::Cancelled);
+    }
+}
@@ -180,10 +180,13 @@ macro_rules! arrow_defs {
 /// * `partition_columns` - The list of partition columns of the table.
 /// * `use_extended_remove_schema` - Whether to include extended file metadata in remove action schema.
 ///    Required for compatibility with different versions of Databricks runtime.
+/// * `write_stats_as_struct` - Whether to include the typed `stats_parsed` and
+///    `partitionValues_parsed` columns in the add action schema.
 pub(crate) fn delta_log_schema_for_table(
     table_schema: ArrowSchema,
     partition_columns: &[String],
     use_extended_remove_schema: bool,
+    write_stats_as_struct: bool,
 ) -> ArrowSchemaRef {
     lazy_static! {
         static ref SCHEMA_FIELDS: Vec<ArrowField> = arrow_defs![
@@ -273,12 +276,14 @@ pub(crate) fn delta_log_schema_for_table(
         stats_parsed_fields.push(null_count_struct);
     }
     let mut add_fields = ADD_FIELDS.clone();
-    add_fields.push(ArrowField::new(
-        "stats_parsed",
-        ArrowDataType::Struct(stats_parsed_fields.into()),
-        true,
-    ));
-    if !partition_fields.is_empty() {
+    if write_stats_as_struct {
+        add_fields.push(ArrowField::new(
+            "stats_parsed",
+            ArrowDataType::Struct(stats_parsed_fields.into()),
+            true,
+        ));
+    }
+    if write_stats_as_struct && !partition_fields.is_empty() {
         add_fields.push(ArrowField::new(
             "partitionValues_parsed",
             ArrowDataType::Struct(partition_fields.into()),
@@ -377,8 +382,12 @@ mod tests {
             ArrowField::new("col1", ArrowDataType::Int32, true),
         ]);
         let partition_columns = vec!["pcol".to_string()];
-        let log_schema =
-            delta_log_schema_for_table(table_schema.clone(), partition_columns.as_slice(), false);
+        let log_schema = delta_log_schema_for_table(
+            table_schema.clone(),
+            partition_columns.as_slice(),
+            false,
+            true,
+        );
 
         // verify top-level schema contains all expected fields and they are named correctly.
         let expected_fields = ["metaData", "protocol", "txn", "remove", "add"];
@@ -479,8 +488,12 @@ mod tests {
         assert_eq!(4, num_remove_fields);
 
         // verify extended remove schema fields **ARE** included when `use_extended_remove_schema` is true.
-        let log_schema =
-            delta_log_schema_for_table(table_schema, partition_columns.as_slice(), true);
+        let log_schema = delta_log_schema_for_table(
+            table_schema.clone(),
+            partition_columns.as_slice(),
+            true,
+            true,
+        );
         let remove_fields: Vec<_> = log_schema
             .fields()
             .iter()
@@ -506,6 +519,28 @@ mod tests {
         for f in remove_fields.iter() {
             assert!(expected_fields.contains(&f.name().as_str()));
         }
+
+        // verify parsed stats and partition values **ARE NOT** included when `write_stats_as_struct` is false.
+        let log_schema =
+            delta_log_schema_for_table(table_schema, partition_columns.as_slice(), false, false);
+        let add_field_names: Vec<_> = log_schema
+            .fields()
+            .iter()
+            .filter(|f| f.name() == "add")
+            .flat_map(|f| {
+                if let ArrowDataType::Struct(fields) = f.data_type() {
+                    fields
+                        .iter()
+                        .map(|f| f.name().to_owned())
+                        .collect::<Vec<_>>()
+                } else {
+                    unreachable!();
+                }
+            })
+            .collect();
+        assert!(add_field_names.contains(&"stats".to_string()));
+        assert!(!add_field_names.contains(&"stats_parsed".to_string()));
+        assert!(!add_field_names.contains(&"partitionValues_parsed".to_string()));
     }
 
     #[test]
@@ -269,6 +269,8 @@ pub enum WriterFeatures {
     V2Checkpoint,
     /// Iceberg compatibility support
     IcebergCompatV1,
+    /// Protection of the checkpoints required by readers of older versions
+    CheckpointProtection,
     /// If we do not match any other reader features
     #[serde(untagged)]
     Other(String),
@@ -296,6 +298,7 @@ impl From<&str> for WriterFeatures {
             "domainMetadata" => WriterFeatures::DomainMetadata,
             "v2Checkpoint" => WriterFeatures::V2Checkpoint,
             "icebergCompatV1" => WriterFeatures::IcebergCompatV1,
+            "checkpointProtection" => WriterFeatures::CheckpointProtection,
             f => WriterFeatures::Other(f.to_string()),
         }
     }
@@ -317,6 +320,7 @@ impl AsRef<str> for WriterFeatures {
             WriterFeatures::DomainMetadata => "domainMetadata",
             WriterFeatures::V2Checkpoint => "v2Checkpoint",
             WriterFeatures::IcebergCompatV1 => "icebergCompatV1",
+            WriterFeatures::CheckpointProtection => "checkpointProtection",
             WriterFeatures::Other(f) => f,
         }
     }
@@ -345,6 +349,7 @@ impl From<&parquet::record::Field> for WriterFeatures {
                 "domainMetadata" => WriterFeatures::DomainMetadata,
                 "v2Checkpoint" => WriterFeatures::V2Checkpoint,
                 "icebergCompatV1" => WriterFeatures::IcebergCompatV1,
+                "checkpointProtection" => WriterFeatures::CheckpointProtection,
                 f => WriterFeatures::Other(f.to_string()),
             },
             f => WriterFeatures::Other(f.to_string()),
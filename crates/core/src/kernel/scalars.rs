@@ -9,7 +9,6 @@ use delta_kernel::{
     expressions::{Scalar, StructData},
     schema::StructField,
 };
-use object_store::path::Path;
 
 use crate::NULL_PARTITION_VALUE_DATA_PATH;
 
@@ -32,8 +31,8 @@ impl ScalarExt for Scalar {
             Self::Short(s) => s.to_string(),
             Self::Integer(i) => i.to_string(),
             Self::Long(l) => l.to_string(),
-            Self::Float(f) => f.to_string(),
-            Self::Double(d) => d.to_string(),
+            Self::Float(f) => serialize_float(*f),
+            Self::Double(d) => serialize_float(*d),
             Self::Boolean(b) => if *b { "true" } else { "false" }.to_string(),
             Self::TimestampNtz(ts) | Self::Timestamp(ts) => {
                 let ts = Utc.timestamp_micros(*ts).single().unwrap();
@@ -46,13 +45,19 @@ impl ScalarExt for Scalar {
             Self::Decimal(value, _, scale) => match scale.cmp(&0) {
                 Ordering::Equal => value.to_string(),
                 Ordering::Greater => {
-                    let scalar_multiple = 10_i128.pow(*scale as u32);
+                    // the sign is written separately, so the fraction of negative values
+                    // is not signed as well
+                    let scalar_multiple = 10_u128.pow(*scale as u32);
+                    let abs = value.unsigned_abs();
                     let mut s = String::new();
-                    s.push_str((value / scalar_multiple).to_string().as_str());
+                    if *value < 0 {
+                        s.push('-');
+                    }
+                    s.push_str((abs / scalar_multiple).to_string().as_str());
                     s.push('.');
                     s.push_str(&format!(
                         "{:0>scale$}",
-                        value % scalar_multiple,
+                        abs % scalar_multiple,
                         scale = *scale as usize
                     ));
                     s
@@ -72,11 +77,15 @@ impl ScalarExt for Scalar {
     }
 
     /// Serializes this scalar as a string for use in hive partition file names.
+    ///
+    /// Nulls and empty strings are written as the default partition, characters which are not
+    /// allowed in a path segment are escaped the same way Spark does.
     fn serialize_encoded(&self) -> String {
-        if self.is_null() {
-            return NULL_PARTITION_VALUE_DATA_PATH.to_string();
+        match self {
+            Self::Null(_) => NULL_PARTITION_VALUE_DATA_PATH.to_string(),
+            Self::String(s) if s.is_empty() => NULL_PARTITION_VALUE_DATA_PATH.to_string(),
+            _ => escape_path_name(&self.serialize()),
         }
-        Path::from(self.serialize()).to_string()
     }
 
     /// Create a [`Scalar`] form a row in an arrow array.
@@ -100,6 +109,10 @@ impl ScalarExt for Scalar {
                 .as_any()
                 .downcast_ref::<LargeStringArray>()
                 .map(|v| Self::String(v.value(index).to_string())),
+            Utf8View => arr
+                .as_any()
+                .downcast_ref::<StringViewArray>()
+                .map(|v| Self::String(v.value(index).to_string())),
             Boolean => arr
                 .as_any()
                 .downcast_ref::<BooleanArray>()
@@ -112,6 +125,10 @@ impl ScalarExt for Scalar {
                 .as_any()
                 .downcast_ref::<LargeBinaryArray>()
                 .map(|v| Self::Binary(v.value(index).to_vec())),
+            BinaryView => arr
+                .as_any()
+                .downcast_ref::<BinaryViewArray>()
+                .map(|v| Self::Binary(v.value(index).to_vec())),
             FixedSizeBinary(_) => arr
                 .as_any()
                 .downcast_ref::<FixedSizeBinaryArray>()
@@ -132,22 +149,24 @@ impl ScalarExt for Scalar {
                 .as_any()
                 .downcast_ref::<Int64Array>()
                 .map(|v| Self::Long(v.value(index))),
+            // unsigned integers are widened to the signed type written for them
             UInt8 => arr
                 .as_any()
                 .downcast_ref::<UInt8Array>()
-                .map(|v| Self::Byte(v.value(index) as i8)),
+                .map(|v| Self::Short(v.value(index) as i16)),
             UInt16 => arr
                 .as_any()
                 .downcast_ref::<UInt16Array>()
-                .map(|v| Self::Short(v.value(index) as i16)),
+                .map(|v| Self::Integer(v.value(index) as i32)),
             UInt32 => arr
                 .as_any()
                 .downcast_ref::<UInt32Array>()
-                .map(|v| Self::Integer(v.value(index) as i32)),
+                .map(|v| Self::Long(v.value(index) as i64)),
             UInt64 => arr
                 .as_any()
                 .downcast_ref::<UInt64Array>()
-                .map(|v| Self::Long(v.value(index) as i64)),
+                .and_then(|v| i64::try_from(v.value(index)).ok())
+                .map(Self::Long),
             Float32 => arr
                 .as_any()
                 .downcast_ref::<Float32Array>()
@@ -166,14 +185,39 @@ impl ScalarExt for Scalar {
                 .as_any()
                 .downcast_ref::<Date32Array>()
                 .map(|v| Self::Date(v.value(index))),
-            Timestamp(TimeUnit::Microsecond, None) => arr
-                .as_any()
-                .downcast_ref::<TimestampMicrosecondArray>()
-                .map(|v| Self::TimestampNtz(v.value(index))),
-            Timestamp(TimeUnit::Microsecond, Some(tz)) if tz.eq_ignore_ascii_case("utc") => arr
+            Date64 => arr
                 .as_any()
-                .downcast_ref::<TimestampMicrosecondArray>()
-                .map(|v| Self::Timestamp(v.clone().value(index))),
+                .downcast_ref::<Date64Array>()
+                .map(|v| Self::Date(v.value(index).div_euclid(24 * 3600 * 1000) as i32)),
+            Timestamp(unit, tz) => {
+                // delta timestamps are stored with microsecond precision, the values of arrow
+                // timestamps with a timezone are always relative to UTC
+                let micros = match unit {
+                    TimeUnit::Second => arr
+                        .as_any()
+                        .downcast_ref::<TimestampSecondArray>()?
+                        .value(index)
+                        .checked_mul(1_000_000)?,
+                    TimeUnit::Millisecond => arr
+                        .as_any()
+                        .downcast_ref::<TimestampMillisecondArray>()?
+                        .value(index)
+                        .checked_mul(1_000)?,
+                    TimeUnit::Microsecond => arr
+                        .as_any()
+                        .downcast_ref::<TimestampMicrosecondArray>()?
+                        .value(index),
+                    TimeUnit::Nanosecond => arr
+                        .as_any()
+                        .downcast_ref::<TimestampNanosecondArray>()?
+                        .value(index)
+                        .div_euclid(1_000),
+                };
+                match tz {
+                    Some(_) => Some(Self::Timestamp(micros)),
+                    None => Some(Self::TimestampNtz(micros)),
+                }
+            }
             Struct(fields) => {
                 let struct_fields = fields
                     .iter()
@@ -202,8 +246,6 @@ impl ScalarExt for Scalar {
             | LargeList(_)
             | FixedSizeList(_, _)
             | Map(_, _)
-            | Date64
-            | Timestamp(_, _)
             | Time32(_)
             | Time64(_)
             | Duration(_)
@@ -211,8 +253,6 @@ impl ScalarExt for Scalar {
             | Dictionary(_, _)
             | RunEndEncoded(_, _)
             | Union(_, _)
-            | Utf8View
-            | BinaryView
             | ListView(_)
             | LargeListView(_)
             | Null => None,
@@ -220,6 +260,49 @@ impl ScalarExt for Scalar {
     }
 }
 
+fn serialize_float<T: Into<f64> + ToString + Copy>(value: T) -> String {
+    let double: f64 = value.into();
+    if double.is_infinite() {
+        if double.is_sign_positive() {
+            "Infinity".to_string()
+        } else {
+            "-Infinity".to_string()
+        }
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape the characters of a partition column name or value which may not be used in a
+/// path segment, following the escaping of Spark's `ExternalCatalogUtils.escapePathName`
+///
+/// Spaces and non-ascii characters are kept as is.
+pub(crate) fn escape_path_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '\u{00}'..='\u{1F}'
+            | '"'
+            | '#'
+            | '%'
+            | '\''
+            | '*'
+            | '/'
+            | ':'
+            | '='
+            | '?'
+            | '\\'
+            | '\u{7F}'
+            | '{'
+            | '['
+            | ']'
+            | '^' => escaped.push_str(&format!("%{:02X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 fn create_escaped_binary_string(data: &[u8]) -> String {
     let mut escaped_string = String::new();
     for &byte in data {
@@ -231,3 +314,108 @@ fn create_escaped_binary_string(data: &[u8]) -> String {
     }
     escaped_string
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{
+        ArrayRef, Date64Array, TimestampMillisecondArray, TimestampNanosecondArray,
+        TimestampSecondArray,
+    };
+    use delta_kernel::schema::{DataType, PrimitiveType};
+
+    use super::*;
+
+    #[test]
+    fn test_serialize_partition_values() {
+        let cases = [
+            (Scalar::String("a".into()), "a"),
+            (Scalar::Byte(-1), "-1"),
+            (Scalar::Short(12), "12"),
+            (Scalar::Integer(-123), "-123"),
+            (Scalar::Long(1234567890123), "1234567890123"),
+            (Scalar::Float(1.5), "1.5"),
+            (Scalar::Double(-0.25), "-0.25"),
+            (Scalar::Double(f64::INFINITY), "Infinity"),
+            (Scalar::Boolean(true), "true"),
+            (Scalar::Date(19_000), "2022-01-08"),
+            (Scalar::Date(-1), "1969-12-31"),
+            (
+                Scalar::Timestamp(1_641_600_000_123_456),
+                "2022-01-08 00:00:00.123456",
+            ),
+            (Scalar::TimestampNtz(-1), "1969-12-31 23:59:59.999999"),
+            (Scalar::Decimal(1234, 5, 2), "12.34"),
+            (Scalar::Decimal(-15, 3, 1), "-1.5"),
+            (Scalar::Decimal(-5, 3, 2), "-0.05"),
+            (Scalar::Decimal(-5, 3, 0), "-5"),
+        ];
+        for (scalar, expected) in cases {
+            assert_eq!(scalar.serialize(), expected);
+        }
+
+        // serialized values can be parsed back into the same scalar
+        let cases = [
+            (Scalar::Integer(-123), PrimitiveType::Integer),
+            (Scalar::Double(-0.25), PrimitiveType::Double),
+            (Scalar::Date(-1), PrimitiveType::Date),
+            (Scalar::Decimal(-15, 3, 1), PrimitiveType::Decimal(3, 1)),
+            (Scalar::String("a b".into()), PrimitiveType::String),
+        ];
+        for (scalar, primitive_type) in cases {
+            assert_eq!(
+                primitive_type.parse_scalar(&scalar.serialize()).unwrap(),
+                scalar
+            );
+        }
+    }
+
+    #[test]
+    fn test_serialize_encoded_partition_values() {
+        let cases = [
+            (Scalar::String("A/A".into()), "A%2FA"),
+            (Scalar::String("B B".into()), "B B"),
+            (Scalar::String("a=b%c".into()), "a%3Db%25c"),
+            (
+                Scalar::String("\u{1}\"#'*:?\\{[]^".into()),
+                "%01%22%23%27%2A%3A%3F%5C%7B%5B%5D%5E",
+            ),
+            (Scalar::String("ünï©ødé 🦀".into()), "ünï©ødé 🦀"),
+            (Scalar::String("".into()), NULL_PARTITION_VALUE_DATA_PATH),
+            (
+                Scalar::Null(DataType::Primitive(PrimitiveType::Integer)),
+                NULL_PARTITION_VALUE_DATA_PATH,
+            ),
+            (
+                Scalar::TimestampNtz(1_641_600_000_000_000),
+                "2022-01-08 00%3A00%3A00.000000",
+            ),
+        ];
+        for (scalar, expected) in cases {
+            assert_eq!(scalar.serialize_encoded(), expected);
+        }
+    }
+
+    #[test]
+    fn test_scalar_from_temporal_arrays() {
+        let arr: ArrayRef = Arc::new(TimestampSecondArray::from(vec![1]).with_timezone("+02:00"));
+        assert_eq!(
+            Scalar::from_array(arr.as_ref(), 0),
+            Some(Scalar::Timestamp(1_000_000))
+        );
+        let arr: ArrayRef = Arc::new(TimestampMillisecondArray::from(vec![-1]));
+        assert_eq!(
+            Scalar::from_array(arr.as_ref(), 0),
+            Some(Scalar::TimestampNtz(-1_000))
+        );
+        let arr: ArrayRef = Arc::new(TimestampNanosecondArray::from(vec![-1]).with_timezone("UTC"));
+        assert_eq!(
+            Scalar::from_array(arr.as_ref(), 0),
+            Some(Scalar::Timestamp(-1))
+        );
+        let arr: ArrayRef = Arc::new(Date64Array::from(vec![-1, 86_400_000]));
+        assert_eq!(Scalar::from_array(arr.as_ref(), 0), Some(Scalar::Date(-1)));
+        assert_eq!(Scalar::from_array(arr.as_ref(), 1), Some(Scalar::Date(1)));
+    }
+}
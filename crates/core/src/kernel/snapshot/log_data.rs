@@ -10,7 +10,7 @@ use object_store::path::Path;
 use object_store::ObjectMeta;
 use percent_encoding::percent_decode_str;
 
-use super::super::scalars::ScalarExt;
+use super::super::scalars::{escape_path_name, ScalarExt};
 use crate::kernel::arrow::extract::{extract_and_cast, extract_and_cast_opt};
 use crate::kernel::{
     DataType, DeletionVectorDescriptor, Metadata, Remove, StructField, StructType,
@@ -35,7 +35,7 @@ impl PartitionsExt for IndexMap<&str, Scalar> {
             .iter()
             .map(|(k, v)| {
                 let encoded = v.serialize_encoded();
-                format!("{k}={encoded}")
+                format!("{}={encoded}", escape_path_name(k))
             })
             .collect::<Vec<_>>();
         fields.join("/")
@@ -48,7 +48,7 @@ impl PartitionsExt for IndexMap<String, Scalar> {
             .iter()
             .map(|(k, v)| {
                 let encoded = v.serialize_encoded();
-                format!("{k}={encoded}")
+                format!("{}={encoded}", escape_path_name(k))
             })
             .collect::<Vec<_>>();
         fields.join("/")
@@ -2,21 +2,25 @@ use std::cmp::Ordering;
 use std::collections::VecDeque;
 use std::sync::Arc;
 
-use arrow_array::RecordBatch;
+use arrow_arith::boolean::{is_not_null, or};
+use arrow_array::{BooleanArray, RecordBatch};
 use chrono::Utc;
 use futures::{stream::BoxStream, StreamExt, TryStreamExt};
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use object_store::path::Path;
 use object_store::{Error as ObjectStoreError, ObjectMeta, ObjectStore};
-use parquet::arrow::arrow_reader::ArrowReaderOptions;
+use parquet::arrow::arrow_reader::{ArrowPredicateFn, ArrowReaderOptions, RowFilter};
 use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+use parquet::arrow::ProjectionMask;
+use parquet::errors::ParquetError;
+use parquet::schema::types::SchemaDescriptor;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 use super::parse;
-use crate::kernel::{arrow::json, ActionType, Metadata, Protocol, Schema, StructType};
+use crate::kernel::{arrow::json, ActionType, DataType, Metadata, Protocol, Schema, StructType};
 use crate::logstore::LogStore;
 use crate::operations::transaction::CommitData;
 use crate::{DeltaResult, DeltaTableConfig, DeltaTableError};
@@ -31,6 +35,71 @@ lazy_static! {
         StructType::new(vec![ActionType::Remove.schema_field().clone(),]);
 }
 
+/// Indices of the checkpoint leaf columns needed to read the fields of `read_schema`.
+///
+/// Besides the fields of the read schema, the typed `stats_parsed` and `partitionValues_parsed`
+/// columns of add actions are read, as they are used instead of the JSON encoded statistics.
+fn checkpoint_leaves(parquet_schema: &SchemaDescriptor, read_schema: &Schema) -> Vec<usize> {
+    parquet_schema
+        .columns()
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| is_read_column(read_schema, column.path().parts()))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+fn is_read_column(read_schema: &Schema, path: &[String]) -> bool {
+    let Some((root, rest)) = path.split_first() else {
+        return false;
+    };
+    let Some(field) = read_schema.field(root) else {
+        return false;
+    };
+    if root == "add"
+        && rest
+            .first()
+            .is_some_and(|name| name == "stats_parsed" || name == "partitionValues_parsed")
+    {
+        return true;
+    }
+    let mut data_type = field.data_type();
+    for name in rest {
+        match data_type {
+            DataType::Struct(fields) => match fields.field(name) {
+                Some(field) => data_type = field.data_type(),
+                None => return false,
+            },
+            // lists and maps are read as a whole
+            _ => return true,
+        }
+    }
+    true
+}
+
+/// Skip the checkpoint rows which contain none of the actions read via `leaves`.
+///
+/// Every row of a checkpoint holds a single action, so only the first leaf of every action is
+/// decoded to check if the action is present.
+fn action_row_filter(parquet_schema: &SchemaDescriptor, leaves: &[usize]) -> RowFilter {
+    let action_leaves = leaves
+        .iter()
+        .map(|idx| (parquet_schema.get_column_root_idx(*idx), *idx))
+        .unique_by(|(root, _)| *root)
+        .map(|(_, idx)| idx)
+        .collect_vec();
+    let projection = ProjectionMask::leaves(parquet_schema, action_leaves);
+    let predicate = ArrowPredicateFn::new(projection, |batch: RecordBatch| {
+        batch
+            .columns()
+            .iter()
+            .map(|column| is_not_null(column.as_ref()))
+            .reduce(|acc, valid| or(&acc?, &valid?))
+            .unwrap_or_else(|| Ok(BooleanArray::from(vec![false; batch.num_rows()])))
+    });
+    RowFilter::new(vec![Box::new(predicate)])
+}
+
 /// Trait to extend a file path representation with delta specific functionality
 ///
 /// specifically, this trait adds the ability to recognize valid log files and
@@ -169,6 +238,65 @@ impl LogSegment {
         Ok(segment)
     }
 
+    /// Try to create a new [`LogSegment`] holding only the commit files of a slice of the log.
+    ///
+    /// Checkpoints are ignored, so the segment can be applied on top of a state loaded at
+    /// `start_version - 1` by replaying the newer commits only. Returns `None` if commit files
+    /// in the slice are missing, e.g. because they have already been removed by log cleanup,
+    /// and fails if `end_version` does not exist.
+    pub async fn try_new_commits(
+        table_root: &Path,
+        start_version: i64,
+        end_version: Option<i64>,
+        log_store: &dyn LogStore,
+    ) -> DeltaResult<Option<Self>> {
+        debug!(
+            "try_new_commits: start_version: {}, end_version: {:?}",
+            start_version, end_version
+        );
+        log_store.refresh().await?;
+        let log_url = table_root.child("_delta_log");
+        let start_from = log_url.child(format!("{:020}", start_version).as_str());
+        let max_version = end_version.unwrap_or(i64::MAX);
+
+        let mut commit_files = log_store
+            .object_store()
+            .list_with_offset(Some(&log_url), &start_from)
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .filter(|meta| {
+                meta.location.is_commit_file()
+                    && matches!(
+                        meta.location.commit_version(),
+                        Some(v) if v >= start_version && v <= max_version
+                    )
+            })
+            .collect_vec();
+        // NOTE: this will sort in reverse order
+        commit_files.sort_unstable_by(|a, b| b.location.cmp(&a.location));
+
+        let contiguous = commit_files
+            .iter()
+            .rev()
+            .zip(start_version..)
+            .all(|(meta, version)| meta.location.commit_version() == Some(version));
+        if !contiguous {
+            return Ok(None);
+        }
+
+        let version = start_version + commit_files.len() as i64 - 1;
+        if let Some(end_version) = end_version.filter(|v| *v != version) {
+            return Err(DeltaTableError::InvalidVersion(end_version));
+        }
+
+        Ok(Some(Self {
+            version,
+            commit_files: commit_files.into(),
+            checkpoint_files: vec![],
+        }))
+    }
+
     pub fn validate(&self) -> DeltaResult<()> {
         let checkpoint_version = self
             .checkpoint_files
@@ -247,22 +375,41 @@ impl LogSegment {
         Ok(json::decode_stream(decoder, stream).boxed())
     }
 
+    /// Read the actions of `read_schema` from the checkpoint files.
+    ///
+    /// Only the parquet columns backing the fields of the read schema are decoded, and rows
+    /// not containing any of the requested actions are skipped.
     pub(super) fn checkpoint_stream(
         &self,
         store: Arc<dyn ObjectStore>,
-        _read_schema: &Schema,
+        read_schema: &Schema,
         config: &DeltaTableConfig,
     ) -> BoxStream<'_, DeltaResult<RecordBatch>> {
         let batch_size = config.log_batch_size;
+        let read_schema = Arc::new(read_schema.clone());
         futures::stream::iter(self.checkpoint_files.clone())
             .map(move |meta| {
                 let store = store.clone();
+                let read_schema = read_schema.clone();
                 async move {
                     let reader = ParquetObjectReader::new(store, meta);
                     let options = ArrowReaderOptions::new(); //.with_page_index(enable_page_index);
                     let builder =
                         ParquetRecordBatchStreamBuilder::new_with_options(reader, options).await?;
-                    builder.with_batch_size(batch_size).build()
+                    let parquet_schema = builder.parquet_schema();
+                    let leaves = checkpoint_leaves(parquet_schema, &read_schema);
+                    if leaves.is_empty() {
+                        // the checkpoint contains none of the requested actions
+                        return Ok(futures::stream::empty().boxed());
+                    }
+                    let row_filter = action_row_filter(parquet_schema, &leaves);
+                    let projection = ProjectionMask::leaves(parquet_schema, leaves);
+                    let stream = builder
+                        .with_projection(projection)
+                        .with_row_filter(row_filter)
+                        .with_batch_size(batch_size)
+                        .build()?;
+                    Ok::<_, ParquetError>(stream.boxed())
                 }
             })
             .buffered(config.log_buffer_size)
@@ -511,6 +658,7 @@ pub(super) async fn list_log_files(
 
 #[cfg(test)]
 pub(super) mod tests {
+    use arrow_array::Array;
     use deltalake_test::utils::*;
     use tokio::task::JoinHandle;
 
@@ -521,6 +669,7 @@ pub(super) mod tests {
     pub(crate) async fn test_log_segment(context: &IntegrationContext) -> TestResult {
         read_log_files(context).await?;
         read_metadata(context).await?;
+        read_checkpoint_projection(context).await?;
         log_segment_serde(context).await?;
 
         Ok(())
@@ -595,6 +744,38 @@ pub(super) mod tests {
         Ok(())
     }
 
+    async fn read_checkpoint_projection(context: &IntegrationContext) -> TestResult {
+        let store = context
+            .table_builder(TestTables::SimpleWithCheckpoint)
+            .build_storage()?
+            .object_store();
+        let segment = LogSegment::try_new(&Path::default(), None, store.as_ref()).await?;
+        let read_schema = StructType::new(vec![ActionType::Add.schema_field().clone()]);
+
+        let batches = segment
+            .checkpoint_stream(store.clone(), &read_schema, &Default::default())
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert!(!batches.is_empty());
+        for batch in batches {
+            // only add actions are read from the checkpoint
+            let schema = batch.schema();
+            let columns = schema.fields().iter().map(|f| f.name()).collect_vec();
+            assert_eq!(columns, vec!["add"]);
+            assert_eq!(batch.column(0).null_count(), 0);
+        }
+
+        // checkpoints never contain change data actions
+        let read_schema = StructType::new(vec![ActionType::Cdc.schema_field().clone()]);
+        let num_rows = segment
+            .checkpoint_stream(store.clone(), &read_schema, &Default::default())
+            .try_fold(0, |acc, batch| async move { Ok(acc + batch.num_rows()) })
+            .await?;
+        assert_eq!(num_rows, 0);
+
+        Ok(())
+    }
+
     async fn read_metadata(context: &IntegrationContext) -> TestResult {
         let store = context
             .table_builder(TestTables::WithDvSmall)
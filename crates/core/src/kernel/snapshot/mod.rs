@@ -20,11 +20,13 @@ use std::io::{BufRead, BufReader, Cursor};
 use std::sync::Arc;
 
 use ::serde::{Deserialize, Serialize};
-use arrow_array::RecordBatch;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, BooleanArray, RecordBatch};
+use arrow_select::filter::filter_record_batch;
 use futures::stream::BoxStream;
 use futures::{StreamExt, TryStreamExt};
 use object_store::path::Path;
-use object_store::ObjectStore;
+use object_store::{ObjectMeta, ObjectStore};
 
 use self::log_segment::{LogSegment, PathExt};
 use self::parse::{read_adds, read_removes};
@@ -38,7 +40,10 @@ use crate::kernel::parse::read_cdf_adds;
 use crate::kernel::{ActionType, StructType};
 use crate::logstore::LogStore;
 use crate::operations::transaction::CommitData;
+use crate::partitions::{DeltaTablePartition, PartitionFilter};
 use crate::table::config::TableConfig;
+use crate::table::get_partition_col_data_types;
+use crate::table::history::HistoryOptions;
 use crate::{DeltaResult, DeltaTableConfig, DeltaTableError};
 
 pub use self::log_data::*;
@@ -120,14 +125,31 @@ impl Snapshot {
         log_store: Arc<dyn LogStore>,
         target_version: Option<i64>,
     ) -> DeltaResult<()> {
-        self.update_inner(log_store, target_version).await?;
+        self.update_inner(log_store, target_version, false).await?;
         Ok(())
     }
 
+    /// Update the snapshot to the given version by applying only the commits newer than the
+    /// loaded version, without reading any newer checkpoint.
+    pub async fn update_from_commits(
+        &mut self,
+        log_store: Arc<dyn LogStore>,
+        target_version: Option<i64>,
+    ) -> DeltaResult<()> {
+        self.update_inner(log_store, target_version, true).await?;
+        Ok(())
+    }
+
+    /// Apply the log following the loaded version up to `target_version`.
+    ///
+    /// When `commits_only` is set, newer checkpoints are ignored as long as all commit files
+    /// since the loaded version are still available, and the commit files since the last
+    /// checkpoint read stay within the checkpoint interval of the table.
     async fn update_inner(
         &mut self,
         log_store: Arc<dyn LogStore>,
         target_version: Option<i64>,
+        commits_only: bool,
     ) -> DeltaResult<Option<LogSegment>> {
         if let Some(version) = target_version {
             if version == self.version() {
@@ -137,20 +159,51 @@ impl Snapshot {
                 return Err(DeltaTableError::Generic("Cannot downgrade snapshot".into()));
             }
         }
-        let log_segment = LogSegment::try_new_slice(
-            &Path::default(),
-            self.version() + 1,
-            target_version,
-            log_store.as_ref(),
-        )
-        .await?;
+        let commits = if commits_only {
+            let max_commit_files = self.table_config().checkpoint_interval().max(1) as usize;
+            LogSegment::try_new_commits(
+                &Path::default(),
+                self.version() + 1,
+                target_version,
+                log_store.as_ref(),
+            )
+            .await?
+            .filter(|segment| {
+                // read a newer checkpoint rather than growing the segment without bound
+                self.log_segment.commit_files.len() + segment.commit_files.len() <= max_commit_files
+            })
+        } else {
+            None
+        };
+        let log_segment = match commits {
+            Some(log_segment) => log_segment,
+            None => {
+                LogSegment::try_new_slice(
+                    &Path::default(),
+                    self.version() + 1,
+                    target_version,
+                    log_store.as_ref(),
+                )
+                .await?
+            }
+        };
         if log_segment.commit_files.is_empty() && log_segment.checkpoint_files.is_empty() {
             return Ok(None);
         }
 
-        let (protocol, metadata) = log_segment
-            .read_metadata(log_store.object_store().clone(), &self.config)
+        self.apply_log_segment(&log_segment, log_store.object_store())
             .await?;
+
+        Ok(Some(log_segment))
+    }
+
+    /// Apply protocol and metadata changes from a log segment directly following this snapshot.
+    async fn apply_log_segment(
+        &mut self,
+        log_segment: &LogSegment,
+        store: Arc<dyn ObjectStore>,
+    ) -> DeltaResult<()> {
+        let (protocol, metadata) = log_segment.read_metadata(store, &self.config).await?;
         if let Some(protocol) = protocol {
             self.protocol = protocol;
         }
@@ -163,14 +216,14 @@ impl Snapshot {
             self.log_segment.checkpoint_files = log_segment.checkpoint_files.clone();
             self.log_segment.commit_files = log_segment.commit_files.clone();
         } else {
-            for file in &log_segment.commit_files {
+            for file in log_segment.commit_files.iter().rev() {
                 self.log_segment.commit_files.push_front(file.clone());
             }
         }
 
         self.log_segment.version = log_segment.version;
 
-        Ok(Some(log_segment))
+        Ok(())
     }
 
     /// Get the table version of the snapshot
@@ -178,6 +231,14 @@ impl Snapshot {
         self.log_segment.version()
     }
 
+    /// The checkpoint and commit files the snapshot has been loaded from
+    pub(crate) fn log_files(&self) -> impl Iterator<Item = &ObjectMeta> {
+        self.log_segment
+            .checkpoint_files
+            .iter()
+            .chain(self.log_segment.commit_files.iter())
+    }
+
     /// Get the table schema of the snapshot
     pub fn schema(&self) -> &StructType {
         &self.schema
@@ -203,6 +264,11 @@ impl Snapshot {
         TableConfig(&self.metadata.configuration)
     }
 
+    /// Get the configuration the snapshot was loaded with
+    pub fn load_config(&self) -> &DeltaTableConfig {
+        &self.config
+    }
+
     /// Get the files in the snapshot
     pub fn files<'a>(
         &self,
@@ -239,6 +305,42 @@ impl Snapshot {
         ReplayStream::try_new(log_stream, checkpoint_stream, self, visitors)
     }
 
+    /// Stream the active file actions of the snapshot directly from the log.
+    ///
+    /// In contrast to [`EagerSnapshot::file_actions`], no file data is kept in memory. Every
+    /// batch read from the commit or checkpoint files is reconciled against the actions seen
+    /// so far and converted into [`Add`] actions as the stream is consumed, so tables with
+    /// millions of files can be listed without materializing the whole state.
+    pub fn stream_file_actions(
+        &self,
+        store: Arc<dyn ObjectStore>,
+    ) -> DeltaResult<BoxStream<'_, DeltaResult<Vec<Add>>>> {
+        let checkpoint_stream = self.log_segment.checkpoint_stream(
+            store.clone(),
+            &StructType::new(vec![ActionType::Add.schema_field().clone()]),
+            &self.config,
+        );
+        let log_stream = self.log_segment.commit_stream(
+            store,
+            &StructType::new(vec![
+                ActionType::Add.schema_field().clone(),
+                ActionType::Remove.schema_field().clone(),
+            ]),
+            &self.config,
+        )?;
+
+        let mut scanner = LogReplayScanner::new();
+        Ok(log_stream
+            .map(|batch| (batch, true))
+            .chain(checkpoint_stream.map(|batch| (batch, false)))
+            .map(move |(batch, is_log_batch)| {
+                let batch = scanner.process_files_batch(&batch?, is_log_batch)?;
+                read_adds(&batch)
+            })
+            .try_filter(|adds| futures::future::ready(!adds.is_empty()))
+            .boxed())
+    }
+
     /// Get the commit infos in the snapshot
     pub(crate) async fn commit_infos(
         &self,
@@ -270,19 +372,89 @@ impl Snapshot {
         Ok(futures::stream::iter(commit_files)
             .map(move |meta| {
                 let store = store.clone();
-                async move {
-                    let commit_log_bytes = store.get(&meta.location).await?.bytes().await?;
-                    let reader = BufReader::new(Cursor::new(commit_log_bytes));
-                    for line in reader.lines() {
-                        let action: Action = serde_json::from_str(line?.as_str())?;
-                        if let Action::CommitInfo(commit_info) = action {
-                            return Ok::<_, DeltaTableError>(Some(commit_info));
+                async move { read_commit_info(store.as_ref(), &meta.location).await }
+            })
+            .buffered(self.config.log_buffer_size)
+            .boxed())
+    }
+
+    /// Stream the versions and commit infos of the commits up to the snapshot version selected
+    /// by `options`, newest first
+    ///
+    /// Commits without a commit info are skipped. The timestamp of a commit is taken from its
+    /// commit info, falling back to the modification time of the commit file.
+    pub(crate) async fn commit_history(
+        &self,
+        store: Arc<dyn ObjectStore>,
+        options: HistoryOptions,
+    ) -> DeltaResult<BoxStream<'_, DeltaResult<(i64, CommitInfo)>>> {
+        let (offset, limit) = (options.offset, options.limit.unwrap_or(usize::MAX));
+        // without filters, the commits are listed in windows starting at the requested page
+        // size. Older windows, doubling in size, are only listed while the page is not full yet,
+        // e.g. because some commits have no commit info, and the log has not been cleaned up.
+        let window = match options.limit {
+            Some(_) if !options.has_filters() => {
+                i64::try_from(offset.saturating_add(limit)).unwrap_or(i64::MAX)
+            }
+            _ => i64::MAX,
+        }
+        .max(1);
+        let log_root = self.table_root().child("_delta_log");
+        let list_store = store.clone();
+        let initial = Some((self.version(), window));
+        let commit_files = futures::stream::try_unfold(initial, move |state| {
+            let (store, log_root) = (list_store.clone(), log_root.clone());
+            async move {
+                let Some((end, window)) = state else {
+                    return Ok::<_, DeltaTableError>(None);
+                };
+                let start = end.saturating_sub(window - 1).max(0);
+                let start_from = log_root.child(format!("{:020}", start).as_str());
+                let mut commit_files = Vec::new();
+                for meta in store
+                    .list_with_offset(Some(&log_root), &start_from)
+                    .try_collect::<Vec<_>>()
+                    .await?
+                {
+                    match meta.location.commit_version() {
+                        Some(commit_version)
+                            if meta.location.is_commit_file()
+                                && (start..=end).contains(&commit_version) =>
+                        {
+                            commit_files.push((commit_version, meta))
                         }
+                        _ => {}
                     }
-                    Ok(None)
+                }
+                commit_files.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+                let next = (start > 0 && !commit_files.is_empty())
+                    .then_some((start - 1, window.saturating_mul(2)));
+                let commit_files = commit_files.into_iter().map(Ok::<_, DeltaTableError>);
+                Ok(Some((futures::stream::iter(commit_files), next)))
+            }
+        })
+        .try_flatten();
+
+        Ok(commit_files
+            .map_ok(move |(commit_version, meta)| {
+                let store = store.clone();
+                async move {
+                    let commit_info = read_commit_info(store.as_ref(), &meta.location).await?;
+                    Ok::<_, DeltaTableError>((commit_version, meta, commit_info))
                 }
             })
-            .buffered(self.config.log_buffer_size)
+            .try_buffered(self.config.log_buffer_size)
+            .try_filter_map(move |(commit_version, meta, commit_info)| {
+                let selected = commit_info.filter(|info| {
+                    let timestamp = info
+                        .timestamp
+                        .unwrap_or_else(|| meta.last_modified.timestamp_millis());
+                    options.matches(info, timestamp)
+                });
+                futures::future::ready(Ok(selected.map(|info| (commit_version, info))))
+            })
+            .skip(offset)
+            .take(limit)
             .boxed())
     }
 
@@ -358,6 +530,45 @@ impl Snapshot {
     }
 }
 
+/// Estimated in-memory footprint of a loaded snapshot, in bytes.
+///
+/// Sizes are computed from the arrow buffers backing the snapshot, so buffers shared between
+/// batches may be counted more than once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SnapshotMemoryUsage {
+    /// Add actions without their statistics, e.g. paths, partition values and tags
+    pub add_metadata: usize,
+    /// Raw and parsed statistics of the add actions
+    pub stats: usize,
+    /// Remove actions retained from the log replay
+    pub tombstones: usize,
+    /// Everything else, e.g. the table metadata and tracked app transactions
+    pub other: usize,
+}
+
+impl SnapshotMemoryUsage {
+    /// Total estimated size of the snapshot
+    pub fn total(&self) -> usize {
+        self.add_metadata + self.stats + self.tombstones + self.other
+    }
+}
+
+/// Read the commit info of the commit file at `location`, if it contains one
+async fn read_commit_info(
+    store: &dyn ObjectStore,
+    location: &Path,
+) -> DeltaResult<Option<CommitInfo>> {
+    let commit_log_bytes = store.get(location).await?.bytes().await?;
+    let reader = BufReader::new(Cursor::new(commit_log_bytes));
+    for line in reader.lines() {
+        let action: Action = serde_json::from_str(line?.as_str())?;
+        if let Action::CommitInfo(commit_info) = action {
+            return Ok(Some(commit_info));
+        }
+    }
+    Ok(None)
+}
+
 /// A snapshot of a Delta table that has been eagerly loaded into memory.
 #[derive(Debug, Clone, PartialEq)]
 pub struct EagerSnapshot {
@@ -370,6 +581,11 @@ pub struct EagerSnapshot {
     // NOTE: this is a Vec of RecordBatch instead of a single RecordBatch because
     //       we do not yet enforce a consistent schema across all batches we read from the log.
     files: Vec<RecordBatch>,
+
+    // files not matching these filters are dropped during log replay.
+    // NOTE: the filters are not serialized, a deserialized snapshot retains the pruned files
+    //       but will not prune files added by later updates.
+    partition_filters: Vec<PartitionFilter>,
 }
 
 impl EagerSnapshot {
@@ -390,19 +606,81 @@ impl EagerSnapshot {
         config: DeltaTableConfig,
         version: Option<i64>,
         tracked_actions: HashSet<ActionType>,
+    ) -> DeltaResult<Self> {
+        Self::try_new_with_partition_filters(
+            table_root,
+            store,
+            config,
+            version,
+            tracked_actions,
+            vec![],
+        )
+        .await
+    }
+
+    /// Create a new [`EagerSnapshot`] instance retaining only the files matching all
+    /// `partition_filters`.
+    ///
+    /// Files are pruned while the log is replayed and on every subsequent update, so the
+    /// memory held by the snapshot is proportional to the selected partitions only. Such a
+    /// snapshot does not reflect the full table and should only be used for reading.
+    pub async fn try_new_with_partition_filters(
+        table_root: &Path,
+        store: Arc<dyn ObjectStore>,
+        config: DeltaTableConfig,
+        version: Option<i64>,
+        tracked_actions: HashSet<ActionType>,
+        partition_filters: Vec<PartitionFilter>,
     ) -> DeltaResult<Self> {
         let mut visitors = tracked_actions
             .iter()
             .flat_map(get_visitor)
             .collect::<Vec<_>>();
         let snapshot = Snapshot::try_new(table_root, store.clone(), config, version).await?;
-        let files = snapshot.files(store, &mut visitors)?.try_collect().await?;
+
+        let nonpartitioned_columns = partition_filters
+            .iter()
+            .filter(|f| !snapshot.metadata().partition_columns.contains(&f.key))
+            .map(|f| f.key.to_string())
+            .collect::<Vec<_>>();
+        if !nonpartitioned_columns.is_empty() {
+            return Err(DeltaTableError::ColumnsNotPartitioned {
+                nonpartitioned_columns,
+            });
+        }
+
+        let files = if snapshot.config.require_files {
+            snapshot
+                .files(store, &mut visitors)?
+                .map(|batch| {
+                    batch.and_then(|batch| {
+                        prune_partitions(
+                            batch,
+                            &partition_filters,
+                            snapshot.metadata(),
+                            snapshot.schema(),
+                        )
+                    })
+                })
+                .try_collect()
+                .await?
+        } else {
+            // the log still has to be replayed for the tracked actions, but no files are kept.
+            if !visitors.is_empty() {
+                snapshot
+                    .files(store, &mut visitors)?
+                    .try_for_each(|_| futures::future::ready(Ok(())))
+                    .await?;
+            }
+            vec![]
+        };
 
         let mut sn = Self {
             snapshot,
             files,
             tracked_actions,
             transactions: None,
+            partition_filters,
         };
 
         sn.process_visitors(visitors)?;
@@ -443,6 +721,7 @@ impl EagerSnapshot {
             files,
             tracked_actions: Default::default(),
             transactions: None,
+            partition_filters: vec![],
         })
     }
 
@@ -451,6 +730,29 @@ impl EagerSnapshot {
         &mut self,
         log_store: Arc<dyn LogStore>,
         target_version: Option<i64>,
+    ) -> DeltaResult<()> {
+        self.update_inner(log_store, target_version, false).await
+    }
+
+    /// Update the snapshot to the given version by replaying only the commits newer than the
+    /// loaded version on top of the current state.
+    ///
+    /// Newer checkpoints are not read, which keeps updates cheap when tailing an active table.
+    /// Falls back to [`EagerSnapshot::update`] if some of the commits are no longer available,
+    /// or the commits since the last checkpoint read exceed the checkpoint interval.
+    pub async fn update_from_commits(
+        &mut self,
+        log_store: Arc<dyn LogStore>,
+        target_version: Option<i64>,
+    ) -> DeltaResult<()> {
+        self.update_inner(log_store, target_version, true).await
+    }
+
+    async fn update_inner(
+        &mut self,
+        log_store: Arc<dyn LogStore>,
+        target_version: Option<i64>,
+        commits_only: bool,
     ) -> DeltaResult<()> {
         if Some(self.version()) == target_version {
             return Ok(());
@@ -458,7 +760,7 @@ impl EagerSnapshot {
 
         let new_slice = self
             .snapshot
-            .update_inner(log_store.clone(), target_version)
+            .update_inner(log_store.clone(), target_version, commits_only)
             .await?;
 
         if new_slice.is_none() {
@@ -466,6 +768,16 @@ impl EagerSnapshot {
         }
         let new_slice = new_slice.unwrap();
 
+        self.replay_log_segment(log_store.object_store(), &new_slice)
+            .await
+    }
+
+    /// Replay the file actions of a log segment directly following the currently loaded state.
+    async fn replay_log_segment(
+        &mut self,
+        store: Arc<dyn ObjectStore>,
+        new_slice: &LogSegment,
+    ) -> DeltaResult<()> {
         let mut visitors = self
             .tracked_actions
             .iter()
@@ -488,11 +800,7 @@ impl EagerSnapshot {
                     .collect(),
             );
             new_slice
-                .checkpoint_stream(
-                    log_store.object_store(),
-                    &read_schema,
-                    &self.snapshot.config,
-                )
+                .checkpoint_stream(store.clone(), &read_schema, &self.snapshot.config)
                 .boxed()
         };
 
@@ -503,17 +811,29 @@ impl EagerSnapshot {
                 .map(|a| a.schema_field().clone())
                 .collect(),
         );
-        let log_stream = new_slice.commit_stream(
-            log_store.object_store().clone(),
-            &read_schema,
-            &self.snapshot.config,
-        )?;
+        let log_stream = new_slice.commit_stream(store, &read_schema, &self.snapshot.config)?;
+
+        if !self.snapshot.config.require_files {
+            ReplayStream::try_new(log_stream, checkpoint_stream, &self.snapshot, &mut visitors)?
+                .try_for_each(|_| futures::future::ready(Ok(())))
+                .await?;
+            return self.process_visitors(visitors);
+        }
 
         let mapper = LogMapper::try_new(&self.snapshot, None)?;
 
         let files =
             ReplayStream::try_new(log_stream, checkpoint_stream, &self.snapshot, &mut visitors)?
-                .map(|batch| batch.and_then(|b| mapper.map_batch(b)))
+                .map(|batch| {
+                    batch.and_then(|b| mapper.map_batch(b)).and_then(|b| {
+                        prune_partitions(
+                            b,
+                            &self.partition_filters,
+                            self.snapshot.metadata(),
+                            self.snapshot.schema(),
+                        )
+                    })
+                })
                 .try_collect()
                 .await?;
 
@@ -523,6 +843,74 @@ impl EagerSnapshot {
         Ok(())
     }
 
+    /// Materialize the snapshots for every version between `start_version` and `end_version`
+    /// (both inclusive).
+    ///
+    /// The state at `start_version` is loaded once, including its checkpoint, and each
+    /// subsequent commit is then applied exactly once on top of the previous snapshot. This is
+    /// much cheaper than loading every version independently, which is useful for tools that
+    /// need to analyze how the table changed across a range of versions.
+    pub async fn try_new_range(
+        log_store: Arc<dyn LogStore>,
+        config: DeltaTableConfig,
+        start_version: i64,
+        end_version: i64,
+        tracked_actions: HashSet<ActionType>,
+    ) -> DeltaResult<Vec<Self>> {
+        if start_version > end_version {
+            return Err(DeltaTableError::Generic(format!(
+                "Invalid version range: start version {start_version} is greater than end version {end_version}"
+            )));
+        }
+
+        let store = log_store.object_store();
+        let mut snapshot = Self::try_new_with_visitor(
+            &Path::default(),
+            store.clone(),
+            config,
+            Some(start_version),
+            tracked_actions,
+        )
+        .await?;
+
+        let slice = LogSegment::try_new_slice(
+            &Path::default(),
+            start_version + 1,
+            Some(end_version),
+            log_store.as_ref(),
+        )
+        .await?;
+
+        // NOTE: commit files are sorted in reverse order within a log segment
+        let commit_files = slice.commit_files.iter().rev().collect::<Vec<_>>();
+        if commit_files.len() as i64 != end_version - start_version {
+            return Err(DeltaTableError::Generic(format!(
+                "Missing commit files between versions {start_version} and {end_version}"
+            )));
+        }
+
+        let mut snapshots = Vec::with_capacity(commit_files.len() + 1);
+        snapshots.push(snapshot.clone());
+        for meta in commit_files {
+            let version = meta.location.commit_version().ok_or_else(|| {
+                DeltaTableError::Generic(format!("Invalid commit file: {}", meta.location))
+            })?;
+            let segment = LogSegment {
+                version,
+                commit_files: [meta.clone()].into(),
+                checkpoint_files: vec![],
+            };
+            snapshot
+                .snapshot
+                .apply_log_segment(&segment, store.clone())
+                .await?;
+            snapshot.replay_log_segment(store.clone(), &segment).await?;
+            snapshots.push(snapshot.clone());
+        }
+
+        Ok(snapshots)
+    }
+
     /// Get the underlying snapshot
     pub(crate) fn snapshot(&self) -> &Snapshot {
         &self.snapshot
@@ -566,16 +954,67 @@ impl EagerSnapshot {
         self.snapshot.table_config()
     }
 
+    /// Get the configuration the snapshot was loaded with
+    pub fn load_config(&self) -> &DeltaTableConfig {
+        self.snapshot.load_config()
+    }
+
     /// Get a [`LogDataHandler`] for the snapshot to inspect the currently loaded state of the log.
     pub fn log_data(&self) -> LogDataHandler<'_> {
         LogDataHandler::new(&self.files, self.metadata(), self.schema())
     }
 
+    /// Get the partition filters files are pruned with during log replay
+    pub fn partition_filters(&self) -> &[PartitionFilter] {
+        &self.partition_filters
+    }
+
     /// Get the number of files in the snapshot
     pub fn files_count(&self) -> usize {
         self.files.iter().map(|f| f.num_rows()).sum()
     }
 
+    /// Estimate the memory held by the snapshot
+    pub fn memory_usage(&self) -> SnapshotMemoryUsage {
+        let mut usage = SnapshotMemoryUsage::default();
+        for batch in &self.files {
+            for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+                match (field.name().as_str(), column.as_struct_opt()) {
+                    ("add", Some(add)) => {
+                        for (child, array) in add.fields().iter().zip(add.columns()) {
+                            match child.name().as_str() {
+                                "stats" | "stats_parsed" => {
+                                    usage.stats += array.get_array_memory_size()
+                                }
+                                _ => usage.add_metadata += array.get_array_memory_size(),
+                            }
+                        }
+                        usage.add_metadata +=
+                            add.nulls().map(|n| n.buffer().capacity()).unwrap_or(0);
+                    }
+                    ("remove", _) => usage.tombstones += column.get_array_memory_size(),
+                    _ => usage.other += column.get_array_memory_size(),
+                }
+            }
+        }
+
+        let metadata = self.metadata();
+        usage.other += std::mem::size_of::<Self>()
+            + metadata.schema_string.len()
+            + metadata
+                .configuration
+                .iter()
+                .map(|(k, v)| k.len() + v.as_ref().map(|v| v.len()).unwrap_or(0))
+                .sum::<usize>();
+        if let Some(transactions) = &self.transactions {
+            usage.other += transactions
+                .keys()
+                .map(|app_id| 2 * app_id.len() + std::mem::size_of::<Transaction>())
+                .sum::<usize>();
+        }
+        usage
+    }
+
     /// Get the files in the snapshot
     pub fn file_actions(&self) -> DeltaResult<impl Iterator<Item = Add> + '_> {
         Ok(self.files.iter().flat_map(|b| read_adds(b)).flatten())
@@ -665,15 +1104,17 @@ impl EagerSnapshot {
             LogMapper::try_new(&self.snapshot, None)?
         };
 
-        self.files = files
-            .into_iter()
-            .chain(
-                self.files
-                    .iter()
-                    .flat_map(|batch| scanner.process_files_batch(batch, false)),
-            )
-            .map(|b| mapper.map_batch(b))
-            .collect::<DeltaResult<Vec<_>>>()?;
+        if self.snapshot.config.require_files {
+            self.files = files
+                .into_iter()
+                .chain(
+                    self.files
+                        .iter()
+                        .flat_map(|batch| scanner.process_files_batch(batch, false)),
+                )
+                .map(|b| mapper.map_batch(b))
+                .collect::<DeltaResult<Vec<_>>>()?;
+        }
 
         if let Some(metadata) = metadata {
             self.snapshot.metadata = metadata;
@@ -682,12 +1123,57 @@ impl EagerSnapshot {
         if let Some(protocol) = protocol {
             self.snapshot.protocol = protocol;
         }
+        if !self.partition_filters.is_empty() {
+            self.files = std::mem::take(&mut self.files)
+                .into_iter()
+                .map(|batch| {
+                    prune_partitions(
+                        batch,
+                        &self.partition_filters,
+                        self.snapshot.metadata(),
+                        self.snapshot.schema(),
+                    )
+                })
+                .collect::<DeltaResult<Vec<_>>>()?;
+        }
         self.process_visitors(visitors)?;
 
         Ok(self.snapshot.version())
     }
 }
 
+/// Keep only the files in `batch` whose partition values match all `filters`
+fn prune_partitions(
+    batch: RecordBatch,
+    filters: &[PartitionFilter],
+    metadata: &Metadata,
+    schema: &StructType,
+) -> DeltaResult<RecordBatch> {
+    if filters.is_empty() || batch.num_rows() == 0 {
+        return Ok(batch);
+    }
+    let partition_col_data_types: HashMap<&String, &DataType> =
+        get_partition_col_data_types(schema, metadata)
+            .into_iter()
+            .collect();
+
+    let batches = vec![batch];
+    let mask = LogDataHandler::new(&batches, metadata, schema)
+        .into_iter()
+        .map(|file| {
+            let partitions = file
+                .partition_values()?
+                .iter()
+                .map(|(k, v)| DeltaTablePartition::from_partition_value((*k, v)))
+                .collect::<Vec<_>>();
+            Ok(Some(filters.iter().all(|filter| {
+                filter.match_partitions(&partitions, &partition_col_data_types)
+            })))
+        })
+        .collect::<DeltaResult<BooleanArray>>()?;
+    Ok(filter_record_batch(&batches[0], &mask)?)
+}
+
 fn stats_field(idx: usize, num_indexed_cols: i32, field: &StructField) -> Option<StructField> {
     if !(num_indexed_cols < 0 || (idx as i32) < num_indexed_cols) {
         return None;
@@ -844,6 +1330,23 @@ mod tests {
                 .await?;
             let num_files = batches.iter().map(|b| b.num_rows() as i64).sum::<i64>();
             assert_eq!(num_files, version);
+
+            let streamed = snapshot
+                .stream_file_actions(store.clone())?
+                .try_collect::<Vec<_>>()
+                .await?
+                .into_iter()
+                .flatten()
+                .map(|add| add.path)
+                .sorted()
+                .collect_vec();
+            let expected = batches
+                .iter()
+                .flat_map(|b| read_adds(b).unwrap())
+                .map(|add| add.path)
+                .sorted()
+                .collect_vec();
+            assert_eq!(streamed, expected);
         }
 
         Ok(())
@@ -887,6 +1390,154 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_eager_snapshot_range() -> TestResult {
+        let context = IntegrationContext::new(Box::<LocalStorageIntegration>::default())?;
+        context.load_table(TestTables::Checkpoints).await?;
+
+        let log_store = context
+            .table_builder(TestTables::Checkpoints)
+            .build_storage()?;
+
+        let snapshots = EagerSnapshot::try_new_range(
+            log_store.clone(),
+            Default::default(),
+            5,
+            12,
+            HashSet::new(),
+        )
+        .await?;
+        assert_eq!(snapshots.len(), 8);
+        for (snapshot, version) in snapshots.iter().zip(5..=12) {
+            assert_eq!(snapshot.version(), version);
+            assert_eq!(snapshot.file_actions()?.count(), version as usize);
+        }
+
+        let result =
+            EagerSnapshot::try_new_range(log_store, Default::default(), 12, 5, HashSet::new())
+                .await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_eager_snapshot_memory_usage() -> TestResult {
+        let context = IntegrationContext::new(Box::<LocalStorageIntegration>::default())?;
+        context.load_table(TestTables::Checkpoints).await?;
+
+        let store = context
+            .table_builder(TestTables::Checkpoints)
+            .build_storage()?
+            .object_store();
+
+        let small =
+            EagerSnapshot::try_new(&Path::default(), store.clone(), Default::default(), Some(1))
+                .await?
+                .memory_usage();
+        let large = EagerSnapshot::try_new(&Path::default(), store, Default::default(), None)
+            .await?
+            .memory_usage();
+
+        assert!(large.add_metadata > 0);
+        assert!(large.stats > 0);
+        assert!(large.other > 0);
+        assert!(large.add_metadata > small.add_metadata);
+        assert_eq!(
+            large.total(),
+            large.add_metadata + large.stats + large.tombstones + large.other
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_eager_snapshot_without_files() -> TestResult {
+        let context = IntegrationContext::new(Box::<LocalStorageIntegration>::default())?;
+        context.load_table(TestTables::Checkpoints).await?;
+
+        let log_store = context
+            .table_builder(TestTables::Checkpoints)
+            .build_storage()?;
+        let config = DeltaTableConfig {
+            require_files: false,
+            ..Default::default()
+        };
+
+        let mut snapshot =
+            EagerSnapshot::try_new(&Path::default(), log_store.object_store(), config, Some(5))
+                .await?;
+        assert_eq!(snapshot.files_count(), 0);
+
+        snapshot.update(log_store.clone(), None).await?;
+        assert_eq!(snapshot.version(), 12);
+        assert_eq!(snapshot.files_count(), 0);
+
+        let streamed = snapshot
+            .snapshot()
+            .stream_file_actions(log_store.object_store())?
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(streamed.into_iter().flatten().count(), 12);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_eager_snapshot_update_from_commits() -> TestResult {
+        let context = IntegrationContext::new(Box::<LocalStorageIntegration>::default())?;
+        context.load_table(TestTables::SimpleWithCheckpoint).await?;
+
+        let log_store = context
+            .table_builder(TestTables::SimpleWithCheckpoint)
+            .build_storage()?;
+
+        let mut snapshot = EagerSnapshot::try_new(
+            &Path::default(),
+            log_store.object_store(),
+            Default::default(),
+            Some(2),
+        )
+        .await?;
+        let result = snapshot
+            .update_from_commits(log_store.clone(), Some(20))
+            .await;
+        assert!(matches!(result, Err(DeltaTableError::InvalidVersion(20))));
+        assert_eq!(snapshot.version(), 2);
+
+        snapshot
+            .update_from_commits(log_store.clone(), None)
+            .await?;
+
+        // the checkpoint written at version 10 is skipped
+        assert_eq!(snapshot.version(), 10);
+        assert!(snapshot.snapshot.log_segment.checkpoint_files.is_empty());
+        assert_eq!(snapshot.snapshot.log_segment.commit_files.len(), 11);
+
+        let expected = EagerSnapshot::try_new(
+            &Path::default(),
+            log_store.object_store(),
+            Default::default(),
+            None,
+        )
+        .await?;
+        assert_eq!(
+            snapshot
+                .file_actions()?
+                .map(|add| add.path)
+                .sorted()
+                .collect_vec(),
+            expected
+                .file_actions()?
+                .map(|add| add.path)
+                .sorted()
+                .collect_vec()
+        );
+        assert_eq!(snapshot.metadata(), expected.metadata());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_eager_snapshot_advance() -> TestResult {
         let context = IntegrationContext::new(Box::<LocalStorageIntegration>::default())?;
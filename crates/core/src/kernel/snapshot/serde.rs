@@ -182,6 +182,7 @@ impl<'de> Visitor<'de> for EagerSnapshotVisitor {
             files,
             tracked_actions,
             transactions,
+            partition_filters: vec![],
         })
     }
 }
@@ -44,6 +44,13 @@
 //!   for Delta Tables, allowing them to be queried using [DataFusion](https://github.com/apache/arrow-datafusion).
 //! - `datafusion-ext` - DEPRECATED: alias for `datafusion` feature.
 //!
+//! # WebAssembly
+//!
+//! Without default features the crate builds for `wasm32-unknown-unknown`. There is no local
+//! file system in the browser, so tables are read through an object store registered for their
+//! url scheme in [`storage::factories`], e.g. one fetching files over HTTP. Loading snapshots
+//! and scanning files is supported, writing operations are not.
+//!
 //! # Querying Delta Tables with Datafusion
 //!
 //! Querying from local filesystem:
@@ -73,6 +80,7 @@ pub mod data_catalog;
 pub mod errors;
 pub mod kernel;
 pub mod logstore;
+pub mod metrics;
 pub mod operations;
 pub mod protocol;
 pub mod schema;
@@ -166,6 +174,8 @@ pub fn crate_version() -> &'static str {
 
 #[cfg(test)]
 mod tests {
+    use delta_kernel::expressions::Scalar;
+    use futures::TryStreamExt;
     use itertools::Itertools;
 
     use super::*;
@@ -196,6 +206,15 @@ mod tests {
             .unwrap()
             .collect_vec();
         assert_eq!(tombstones.len(), 4);
+        let streamed = table
+            .snapshot()
+            .unwrap()
+            .tombstones_stream(table.object_store().clone())
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(streamed, tombstones);
         assert!(tombstones.contains(&crate::kernel::Remove {
             path: "part-00000-512e1537-8aaa-4193-b8b4-bef3de0de409-c000.snappy.parquet".to_string(),
             deletion_timestamp: Some(1564524298213),
@@ -428,6 +447,124 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_partitions_with_statistics() {
+        let table = crate::open_table("../test/tests/data/http_requests")
+            .await
+            .unwrap();
+        let partitions = table.get_partitions().unwrap();
+        assert_eq!(partitions.len(), 2);
+        assert_eq!(
+            partitions[0].partition_values,
+            indexmap::IndexMap::from([("date".to_string(), Some("2023-04-13".to_string()))])
+        );
+        assert_eq!(partitions[0].num_files, 1);
+        assert_eq!(partitions[0].size_bytes, 3780);
+        assert_eq!(partitions[0].num_records, Some(144));
+        assert_eq!(partitions[1].size_bytes, 5976);
+        assert_eq!(partitions[1].num_records, Some(1437));
+
+        // files written without statistics have no record count
+        let table = crate::open_table("../test/tests/data/delta-0.8.0-partitioned")
+            .await
+            .unwrap();
+        let partitions = table.get_partitions().unwrap();
+        assert_eq!(partitions.len(), 6);
+        assert!(partitions.iter().all(|p| p.num_files == 1));
+        assert!(partitions.iter().all(|p| p.num_records.is_none()));
+        assert_eq!(
+            partitions[0].partition_values.keys().collect_vec(),
+            vec!["year", "month", "day"]
+        );
+    }
+
+    #[tokio::test]
+    async fn get_stats_summary() {
+        let table = crate::open_table("../test/tests/data/http_requests")
+            .await
+            .unwrap();
+        let summary = table.get_stats_summary().unwrap();
+        assert_eq!(summary.num_files, 2);
+        assert_eq!(summary.size_bytes, 3780 + 5976);
+        assert_eq!(summary.num_records, Some(144 + 1437));
+        assert_eq!(
+            summary.min_values.get("EdgeResponseBytes"),
+            Some(&Scalar::Long(303))
+        );
+        assert_eq!(
+            summary.max_values.get("EdgeResponseBytes"),
+            Some(&Scalar::Long(307))
+        );
+        assert_eq!(
+            summary.min_values.get("ClientRequestHost"),
+            Some(&Scalar::String("example.com".to_string()))
+        );
+        assert!(!summary.min_values.contains_key("date"));
+
+        let table = crate::open_table("../test/tests/data/delta-0.8.0-partitioned")
+            .await
+            .unwrap();
+        let summary = table.get_stats_summary().unwrap();
+        assert_eq!(summary.num_files, 6);
+        assert_eq!(summary.num_records, None);
+        assert!(summary.min_values.is_empty());
+    }
+
+    #[tokio::test]
+    async fn load_delta_8_0_table_with_partition_filters() {
+        let mut table =
+            crate::DeltaTableBuilder::from_uri("../test/tests/data/delta-0.8.0-partitioned")
+                .build()
+                .unwrap();
+        let filters = vec![crate::PartitionFilter {
+            key: "month".to_string(),
+            value: crate::PartitionValue::In(vec!["2".to_string(), "12".to_string()]),
+        }];
+        table.load_with_partition_filters(&filters).await.unwrap();
+
+        assert_eq!(table.get_files_count(), 4);
+        assert_eq!(
+            table.snapshot().unwrap().snapshot().partition_filters(),
+            filters.as_slice()
+        );
+        assert_eq!(
+            table.get_files_iter().unwrap().sorted().collect_vec(),
+            vec![
+                Path::from("year=2020/month=2/day=3/part-00000-94d16827-f2fd-42cd-a060-f67ccc63ced9.c000.snappy.parquet"),
+                Path::from("year=2020/month=2/day=5/part-00000-89cdd4c8-2af7-4add-8ea3-3990b2f027b5.c000.snappy.parquet"),
+                Path::from("year=2021/month=12/day=20/part-00000-9275fdf4-3961-4184-baa0-1c8a2bb98104.c000.snappy.parquet"),
+                Path::from("year=2021/month=12/day=4/part-00000-6dc763c0-3e8b-4d52-b19e-1f92af3fbb25.c000.snappy.parquet")
+            ]
+        );
+
+        // the state only holds a part of the table and cannot be written to
+        let result = crate::DeltaOps(table.clone()).delete().await;
+        assert!(matches!(
+            result,
+            Err(DeltaTableError::LoadedWithPartitionFilters(_))
+        ));
+        let result = crate::DeltaOps(table.clone())
+            .vacuum()
+            .with_dry_run(true)
+            .await;
+        assert!(matches!(
+            result,
+            Err(DeltaTableError::LoadedWithPartitionFilters(_))
+        ));
+
+        let filters = vec![crate::PartitionFilter {
+            key: "value".to_string(),
+            value: crate::PartitionValue::Equal("1".to_string()),
+        }];
+        assert!(matches!(
+            table
+                .load_with_partition_filters(&filters)
+                .await
+                .unwrap_err(),
+            DeltaTableError::ColumnsNotPartitioned { .. }
+        ));
+    }
+
     #[tokio::test]
     async fn read_delta_8_0_table_with_null_partition() {
         let table = crate::open_table("../test/tests/data/delta-0.8.0-null-partition")
@@ -600,6 +737,90 @@ mod tests {
         assert_eq!(history.len(), 8);
     }
 
+    #[tokio::test]
+    async fn test_table_history_stream() {
+        use crate::table::history::HistoryOptions;
+        use chrono::{TimeZone, Utc};
+        use futures::TryStreamExt;
+
+        async fn versions(table: &DeltaTable, options: HistoryOptions) -> Vec<i64> {
+            table
+                .history_stream(options)
+                .await
+                .unwrap()
+                .map_ok(|(version, _)| version)
+                .try_collect()
+                .await
+                .unwrap()
+        }
+
+        let path = "../test/tests/data/simple_table";
+        let table = crate::open_table(path).await.unwrap();
+        assert_eq!(
+            versions(&table, HistoryOptions::new()).await,
+            vec![4, 3, 2, 1, 0]
+        );
+        assert_eq!(
+            versions(&table, HistoryOptions::new().with_limit(2).with_offset(1)).await,
+            vec![3, 2]
+        );
+        assert_eq!(
+            versions(&table, HistoryOptions::new().with_operations(["WRITE"])).await,
+            vec![2, 0]
+        );
+        let options = HistoryOptions::new()
+            .with_start_time(Utc.timestamp_millis_opt(1587968600000).unwrap())
+            .with_end_time(Utc.timestamp_millis_opt(1587968620000).unwrap());
+        assert_eq!(versions(&table, options).await, vec![3, 2]);
+
+        let table = crate::open_table_with_version(path, 2).await.unwrap();
+        assert_eq!(versions(&table, HistoryOptions::new()).await, vec![2, 1, 0]);
+
+        // commits removed from the log are not part of the history
+        let path = "../test/tests/data/checkpoints_vacuumed";
+        let table = crate::open_table(path).await.unwrap();
+        assert_eq!(
+            versions(&table, HistoryOptions::new().with_limit(10)).await,
+            vec![12, 11, 10, 9, 8, 7, 6, 5]
+        );
+
+        // commits without a commit info do not count towards a page
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let log_dir = tmp_dir.path().join("_delta_log");
+        std::fs::create_dir(&log_dir).unwrap();
+        for version in 0..=4 {
+            let name = format!("{version:020}.json");
+            let commit = std::fs::read_to_string(format!(
+                "../test/tests/data/simple_table/_delta_log/{name}"
+            ))
+            .unwrap();
+            let commit = commit
+                .lines()
+                .filter(|line| version < 3 || !line.contains("commitInfo"))
+                .join("\n");
+            std::fs::write(log_dir.join(name), commit).unwrap();
+        }
+        let table = crate::open_table(tmp_dir.path().to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            versions(&table, HistoryOptions::new().with_limit(2)).await,
+            vec![2, 1]
+        );
+        assert_eq!(
+            versions(&table, HistoryOptions::new().with_limit(2).with_offset(1)).await,
+            vec![1, 0]
+        );
+        assert_eq!(
+            versions(
+                &table,
+                HistoryOptions::new().with_limit(1).with_offset(usize::MAX)
+            )
+            .await,
+            Vec::<i64>::new()
+        );
+    }
+
     #[tokio::test]
     async fn read_empty_folder() {
         let dir = std::env::temp_dir();
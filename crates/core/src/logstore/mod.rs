@@ -12,6 +12,7 @@ use std::io::{BufRead, BufReader, Cursor};
 use std::sync::OnceLock;
 use std::{cmp::max, collections::HashMap, sync::Arc};
 use url::Url;
+use uuid::Uuid;
 
 use crate::{
     errors::DeltaResult,
@@ -19,7 +20,9 @@ use crate::{
     operations::transaction::TransactionError,
     protocol::{get_last_checkpoint, ProtocolError},
     storage::{
-        commit_uri_from_version, retry_ext::ObjectStoreRetryExt, ObjectStoreRef, StorageOptions,
+        cache_store_handler, commit_uri_from_version, object_store_metrics,
+        retry_ext::ObjectStoreRetryExt, CredentialProviderRef, InstrumentedStore, ObjectStoreRef,
+        StorageOptions,
     },
     DeltaTableError,
 };
@@ -33,6 +36,35 @@ use datafusion::datasource::object_store::ObjectStoreUrl;
 pub(crate) mod default_logstore;
 
 /// Trait for generating [LogStore] implementations
+///
+/// Factories are registered per url scheme in the [logstore_factories] registry, which allows
+/// crates outside of `deltalake_core` to provide the commit semantics for new storage backends.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use deltalake_core::logstore::{default_logstore, logstore_factories, LogStore, LogStoreFactory};
+/// # use deltalake_core::storage::{ObjectStoreRef, StorageOptions};
+/// # use deltalake_core::DeltaResult;
+/// # use url::Url;
+/// #[derive(Debug)]
+/// struct MyLogStoreFactory {}
+///
+/// impl LogStoreFactory for MyLogStoreFactory {
+///     fn with_options(
+///         &self,
+///         store: ObjectStoreRef,
+///         location: &Url,
+///         options: &StorageOptions,
+///     ) -> DeltaResult<Arc<dyn LogStore>> {
+///         Ok(default_logstore(store, location, options))
+///     }
+/// }
+///
+/// logstore_factories().insert(
+///     Url::parse("myscheme://").unwrap(),
+///     Arc::new(MyLogStoreFactory {}),
+/// );
+/// ```
 pub trait LogStoreFactory: Send + Sync {
     /// Create a new [LogStore]
     fn with_options(
@@ -67,8 +99,11 @@ impl LogStoreFactory for DefaultLogStoreFactory {}
 /// Registry of [LogStoreFactory] instances
 pub type FactoryRegistry = Arc<DashMap<Url, Arc<dyn LogStoreFactory>>>;
 
-/// TODO
-pub fn logstores() -> FactoryRegistry {
+/// The process global registry of [LogStoreFactory] instances, keyed by url scheme (e.g. `s3://`)
+///
+/// [logstore_for] and [logstore_with] use the factory registered for the scheme of the table
+/// location to create its [LogStore].
+pub fn logstore_factories() -> FactoryRegistry {
     static REGISTRY: OnceLock<FactoryRegistry> = OnceLock::new();
     REGISTRY
         .get_or_init(|| {
@@ -86,6 +121,12 @@ pub fn logstores() -> FactoryRegistry {
         .clone()
 }
 
+/// The process global registry of [LogStoreFactory] instances
+#[deprecated(since = "0.18.2", note = "Please use logstore_factories")]
+pub fn logstores() -> FactoryRegistry {
+    logstore_factories()
+}
+
 /// Sharable reference to [`LogStore`]
 pub type LogStoreRef = Arc<dyn LogStore>;
 
@@ -107,6 +148,25 @@ lazy_static! {
 pub fn logstore_for(
     location: Url,
     options: impl Into<StorageOptions> + Clone,
+) -> DeltaResult<LogStoreRef> {
+    build_logstore(location, options, None)
+}
+
+/// Return the [LogStoreRef] for the provided [Url] and options, authenticating against the
+/// storage backend with the credentials of the given
+/// [CredentialProvider](crate::storage::CredentialProvider)
+pub fn logstore_for_with_credentials(
+    location: Url,
+    options: impl Into<StorageOptions> + Clone,
+    credentials: CredentialProviderRef,
+) -> DeltaResult<LogStoreRef> {
+    build_logstore(location, options, Some(credentials))
+}
+
+fn build_logstore(
+    location: Url,
+    options: impl Into<StorageOptions> + Clone,
+    credentials: Option<CredentialProviderRef>,
 ) -> DeltaResult<LogStoreRef> {
     // turn location into scheme
     let scheme = Url::parse(&format!("{}://", location.scheme()))
@@ -114,9 +174,19 @@ pub fn logstore_for(
 
     if let Some(entry) = crate::storage::factories().get(&scheme) {
         debug!("Found a storage provider for {scheme} ({location})");
-        let (store, _prefix) = entry
-            .value()
-            .parse_url_opts(&location, &options.clone().into())?;
+        let storage_options = options.clone().into();
+        let (store, _prefix) = match credentials {
+            Some(credentials) => entry.value().parse_url_opts_with_credentials(
+                &location,
+                &storage_options,
+                credentials,
+            )?,
+            None => entry.value().parse_url_opts(&location, &storage_options)?,
+        };
+        let store = cache_store_handler(
+            InstrumentedStore::new(store, object_store_metrics()),
+            &storage_options,
+        )?;
         return logstore_with(store, location, options);
     }
     Err(DeltaTableError::InvalidTableLocation(location.into()))
@@ -131,11 +201,10 @@ pub fn logstore_with(
     let scheme = Url::parse(&format!("{}://", location.scheme()))
         .map_err(|_| DeltaTableError::InvalidTableLocation(location.clone().into()))?;
 
-    if let Some(factory) = logstores().get(&scheme) {
+    if let Some(factory) = logstore_factories().get(&scheme) {
         debug!("Found a logstore provider for {scheme}");
         return factory.with_options(store, &location, &options.into());
     } else {
-        println!("Could not find a logstore for the scheme {scheme}");
         warn!("Could not find a logstore for the scheme {scheme}");
     }
     Err(DeltaTableError::InvalidTableLocation(
@@ -198,6 +267,16 @@ pub trait LogStore: Sync + Send {
     /// Get underlying object store.
     fn object_store(&self) -> Arc<dyn ObjectStore>;
 
+    /// Return a log store scoped to the operation identified by `operation_id`.
+    ///
+    /// Log stores which stage the files written by an operation until its commit, e.g. on a
+    /// branch of a versioned object store, return a dedicated instance here, so that concurrent
+    /// operations cannot see or abort each other's files. The default returns `None`, i.e. all
+    /// operations share this log store.
+    fn for_operation(&self, _operation_id: Uuid) -> Option<LogStoreRef> {
+        None
+    }
+
     /// [Path] to Delta log
     fn to_uri(&self, location: &Path) -> String {
         let root = &self.config().location;
@@ -256,6 +335,15 @@ fn object_store_url(location: &Url) -> ObjectStoreUrl {
     .expect("Invalid object store url.")
 }
 
+/// Return the log store a new operation on `log_store` writes and commits through.
+///
+/// See [`LogStore::for_operation`].
+pub(crate) fn operation_log_store(log_store: &LogStoreRef) -> LogStoreRef {
+    log_store
+        .for_operation(Uuid::new_v4())
+        .unwrap_or_else(|| log_store.clone())
+}
+
 /// TODO
 pub fn to_uri(root: &Url, location: &Path) -> String {
     match root.scheme() {
@@ -485,6 +573,39 @@ mod tests {
         let store = logstore_for(location, HashMap::default());
         assert!(store.is_ok());
     }
+
+    #[test]
+    fn logstore_with_registered_factory() {
+        #[derive(Debug)]
+        struct TestLogStoreFactory {}
+
+        impl LogStoreFactory for TestLogStoreFactory {
+            fn with_options(
+                &self,
+                _store: ObjectStoreRef,
+                location: &Url,
+                _options: &StorageOptions,
+            ) -> DeltaResult<Arc<dyn LogStore>> {
+                Err(DeltaTableError::Generic(format!(
+                    "custom log store for {location}"
+                )))
+            }
+        }
+
+        let location = Url::parse("custom://table").unwrap();
+        let store: ObjectStoreRef = Arc::new(object_store::memory::InMemory::new());
+        assert!(matches!(
+            logstore_with(store.clone(), location.clone(), HashMap::default()),
+            Err(DeltaTableError::InvalidTableLocation(_))
+        ));
+
+        logstore_factories().insert(
+            Url::parse("custom://").unwrap(),
+            Arc::new(TestLogStoreFactory {}),
+        );
+        let result = logstore_with(store, location, HashMap::default());
+        assert!(matches!(result, Err(DeltaTableError::Generic(msg)) if msg.starts_with("custom")));
+    }
 }
 
 #[cfg(feature = "datafusion")]
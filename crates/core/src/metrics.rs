@@ -0,0 +1,201 @@
+//! Counters of the work done by tables of this process
+//!
+//! The counters are always recorded in the process wide [`delta_metrics`], which can be polled
+//! with [`DeltaMetrics::snapshot`]. To export them to a metrics system, register a
+//! [`MetricsRecorder`] which is called whenever a counter is incremented. Counters follow the
+//! Prometheus naming conventions, so a recorder forwarding to the `metrics` crate or an
+//! OpenTelemetry meter can use [`Counter::name`] as is.
+//!
+//! ```rust ignore
+//! use std::sync::Arc;
+//!
+//! use deltalake_core::metrics::{delta_metrics, Counter, MetricsRecorder};
+//!
+//! #[derive(Debug)]
+//! struct MetricsBridge;
+//!
+//! impl MetricsRecorder for MetricsBridge {
+//!     fn increment_counter(&self, counter: Counter, value: u64, labels: &[(&'static str, &str)]) {
+//!         let labels = labels
+//!             .iter()
+//!             .map(|(key, value)| metrics::Label::new(*key, value.to_string()))
+//!             .collect::<Vec<_>>();
+//!         metrics::counter!(counter.name(), labels).increment(value);
+//!     }
+//! }
+//!
+//! delta_metrics().set_recorder(Some(Arc::new(MetricsBridge)));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// A counter of the work done by tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Counter {
+    /// Successful commits, labeled by `operation`
+    Commits,
+    /// Commits retried with the next version after a non conflicting concurrent commit,
+    /// labeled by `operation`
+    CommitConflictRetries,
+    /// Data files written
+    FilesWritten,
+    /// Bytes of the data files written
+    BytesWritten,
+    /// Files skipped by scans based on their statistics or partition values
+    ScanFilesPruned,
+}
+
+impl Counter {
+    const ALL: [Self; 5] = [
+        Self::Commits,
+        Self::CommitConflictRetries,
+        Self::FilesWritten,
+        Self::BytesWritten,
+        Self::ScanFilesPruned,
+    ];
+
+    /// Name of the counter
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Commits => "deltalake_commits_total",
+            Self::CommitConflictRetries => "deltalake_commit_conflict_retries_total",
+            Self::FilesWritten => "deltalake_files_written_total",
+            Self::BytesWritten => "deltalake_bytes_written_total",
+            Self::ScanFilesPruned => "deltalake_scan_files_pruned_total",
+        }
+    }
+
+    /// Description of the counter, e.g. for the help text of an exporter
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Commits => "Number of successful commits",
+            Self::CommitConflictRetries => {
+                "Number of commits retried after a non conflicting concurrent commit"
+            }
+            Self::FilesWritten => "Number of data files written",
+            Self::BytesWritten => "Number of bytes of the data files written",
+            Self::ScanFilesPruned => "Number of files skipped by scans",
+        }
+    }
+}
+
+impl Display for Counter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Receiver of counter increments, to bridge them to a metrics system
+pub trait MetricsRecorder: Debug + Send + Sync {
+    /// Called when `counter` is incremented by `value`
+    fn increment_counter(&self, counter: Counter, value: u64, labels: &[(&'static str, &str)]);
+}
+
+/// Sharable reference to a [`MetricsRecorder`]
+pub type MetricsRecorderRef = Arc<dyn MetricsRecorder>;
+
+/// Counters of the work done by all tables of the process
+#[derive(Debug, Default)]
+pub struct DeltaMetrics {
+    counters: [AtomicU64; Counter::ALL.len()],
+    recorder: RwLock<Option<MetricsRecorderRef>>,
+}
+
+impl DeltaMetrics {
+    /// Create new metrics without a recorder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the recorder called on every increment, or remove it with `None`
+    pub fn set_recorder(&self, recorder: Option<MetricsRecorderRef>) {
+        *self.recorder.write().unwrap_or_else(|err| err.into_inner()) = recorder;
+    }
+
+    /// Increment a counter and forward the increment to the recorder
+    pub fn increment(&self, counter: Counter, value: u64, labels: &[(&'static str, &str)]) {
+        if value == 0 {
+            return;
+        }
+        self.counters[counter as usize].fetch_add(value, Ordering::Relaxed);
+        let recorder = self.recorder.read().unwrap_or_else(|err| err.into_inner());
+        if let Some(recorder) = recorder.as_ref() {
+            recorder.increment_counter(counter, value, labels);
+        }
+    }
+
+    /// Current value of a counter, summed over all labels
+    pub fn get(&self, counter: Counter) -> u64 {
+        self.counters[counter as usize].load(Ordering::Relaxed)
+    }
+
+    /// Current values of all counters
+    pub fn snapshot(&self) -> BTreeMap<Counter, u64> {
+        Counter::ALL
+            .into_iter()
+            .map(|counter| (counter, self.get(counter)))
+            .collect()
+    }
+}
+
+/// The process wide metrics recorded by all tables
+pub fn delta_metrics() -> Arc<DeltaMetrics> {
+    static METRICS: OnceLock<Arc<DeltaMetrics>> = OnceLock::new();
+    METRICS.get_or_init(Default::default).clone()
+}
+
+/// Increment a counter of the process wide metrics
+pub(crate) fn increment(counter: Counter, value: u64, labels: &[(&'static str, &str)]) {
+    delta_metrics().increment(counter, value, labels);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct RecordingRecorder {
+        calls: Mutex<Vec<(Counter, u64, Vec<(&'static str, String)>)>>,
+    }
+
+    impl MetricsRecorder for RecordingRecorder {
+        fn increment_counter(&self, counter: Counter, value: u64, labels: &[(&'static str, &str)]) {
+            let labels = labels
+                .iter()
+                .map(|(key, value)| (*key, value.to_string()))
+                .collect();
+            self.calls.lock().unwrap().push((counter, value, labels));
+        }
+    }
+
+    #[test]
+    fn test_increment() {
+        let metrics = DeltaMetrics::new();
+        metrics.increment(Counter::FilesWritten, 2, &[]);
+        metrics.increment(Counter::BytesWritten, 0, &[]);
+        assert_eq!(metrics.get(Counter::FilesWritten), 2);
+        assert_eq!(metrics.snapshot()[&Counter::BytesWritten], 0);
+
+        let recorder = Arc::new(RecordingRecorder::default());
+        metrics.set_recorder(Some(recorder.clone()));
+        metrics.increment(Counter::Commits, 1, &[("operation", "WRITE")]);
+        metrics.set_recorder(None);
+        metrics.increment(Counter::Commits, 1, &[("operation", "DELETE")]);
+        assert_eq!(metrics.get(Counter::Commits), 2);
+        assert_eq!(
+            *recorder.calls.lock().unwrap(),
+            vec![(
+                Counter::Commits,
+                1,
+                vec![("operation", "WRITE".to_string())]
+            )]
+        );
+        assert_eq!(Counter::Commits.to_string(), "deltalake_commits_total");
+    }
+}
@@ -0,0 +1,154 @@
+//! Audit the files of a Delta Table for orphan files not referenced by the log.
+//!
+//! Orphan files are data files in the table directory that no reachable version of the table
+//! references, e.g. files left behind by failed or cancelled writers. Vacuum only deletes files
+//! that have been removed by a commit, so orphan files are never cleaned up by it.
+//!
+//! A file is considered referenced when it is either active in the current version, or removed
+//! by a remove action still present in the log. Hidden files and directories, such as the
+//! `_delta_log` and `_change_data` directories, and deletion vector files are not audited.
+//!
+//! Files written by concurrent operations that have not been committed yet are reported as orphan
+//! files as well, so the modification time of each file is included in the report.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table("../path/to/table")?;
+//! let (table, metrics) = DeltaOps(table).audit_files().await?;
+//! ````
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use serde::Serialize;
+
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::logstore::LogStoreRef;
+use crate::table::state::DeltaTableState;
+use crate::DeltaTable;
+
+/// Prefix of the names of deletion vector files
+const DELETION_VECTOR_PREFIX: &str = "deletion_vector_";
+
+/// Find the files of a Delta Table not referenced by any reachable version
+/// See this module's documentation for more information
+#[derive(Debug)]
+pub struct AuditFilesBuilder {
+    /// A snapshot of the to-be-audited table's state
+    snapshot: DeltaTableState,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+}
+
+/// A file in the table directory not referenced by the table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrphanFile {
+    /// Path of the file relative to the table root
+    pub path: String,
+    /// Size of the file in bytes
+    pub size: usize,
+    /// Last modification time of the file
+    pub last_modified: DateTime<Utc>,
+}
+
+/// Details of the audit including the orphan files found
+#[derive(Debug, Default, Serialize)]
+pub struct AuditFilesMetrics {
+    /// Orphan files, ordered by path
+    pub orphan_files: Vec<OrphanFile>,
+    /// Total size of the orphan files in bytes
+    pub orphan_files_size: u64,
+}
+
+impl super::Operation<()> for AuditFilesBuilder {}
+
+impl AuditFilesBuilder {
+    /// Create a new [`AuditFilesBuilder`]
+    pub fn new(log_store: LogStoreRef, snapshot: DeltaTableState) -> Self {
+        Self {
+            snapshot,
+            log_store,
+        }
+    }
+
+    async fn find_orphan_files(&self) -> DeltaResult<AuditFilesMetrics> {
+        let object_store = self.log_store.object_store();
+
+        let mut referenced = self.snapshot.file_paths_iter().collect::<HashSet<Path>>();
+        let mut tombstones = self.snapshot.tombstones_stream(object_store.clone())?;
+        while let Some(tombstone) = tombstones.try_next().await? {
+            referenced.insert(
+                Path::parse(&tombstone.path).unwrap_or_else(|_| Path::from(tombstone.path)),
+            );
+        }
+
+        let mut orphan_files = vec![];
+        let mut all_files = object_store.list(None);
+        while let Some(obj_meta) = all_files.next().await {
+            let obj_meta = obj_meta.map_err(DeltaTableError::from)?;
+            if referenced.contains(&obj_meta.location)
+                || is_hidden(&obj_meta.location)
+                || obj_meta
+                    .location
+                    .filename()
+                    .is_some_and(|name| name.starts_with(DELETION_VECTOR_PREFIX))
+            {
+                continue;
+            }
+            orphan_files.push(OrphanFile {
+                path: obj_meta.location.to_string(),
+                size: obj_meta.size,
+                last_modified: obj_meta.last_modified,
+            });
+        }
+        orphan_files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(AuditFilesMetrics {
+            orphan_files_size: orphan_files.iter().map(|file| file.size as u64).sum(),
+            orphan_files,
+        })
+    }
+}
+
+/// Whether any file or directory name in `path` starts with `.` or `_`, ignoring partition
+/// directories as partition columns may start with an underscore
+fn is_hidden(path: &Path) -> bool {
+    path.parts().any(|part| {
+        let name = part.as_ref();
+        (name.starts_with('.') || name.starts_with('_')) && !name.contains('=')
+    })
+}
+
+impl std::future::IntoFuture for AuditFilesBuilder {
+    type Output = DeltaResult<(DeltaTable, AuditFilesMetrics)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let metrics = this.find_orphan_files().await?;
+            Ok((
+                DeltaTable::new_with_state(this.log_store, this.snapshot),
+                metrics,
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_hidden() {
+        assert!(is_hidden(&Path::from("_delta_log/_last_checkpoint")));
+        assert!(is_hidden(&Path::from("_change_data/cdc.parquet")));
+        assert!(is_hidden(&Path::from("date=2022-07-03/.part.parquet.crc")));
+        assert!(!is_hidden(&Path::from("date=2022-07-03/part.parquet")));
+        assert!(!is_hidden(&Path::from("_date=2022-07-03/part.parquet")));
+    }
+}
@@ -0,0 +1,136 @@
+//! Cancellation of long running operations.
+//!
+//! Operations accepting a [`CancellationToken`] stop as soon as the token is cancelled and
+//! return [`DeltaTableError::Cancelled`]. Stopping the operation drops its DataFusion streams
+//! and aborts the tasks writing or scanning data, so pending uploads are not completed. Data
+//! files written by the operation which have not been committed are deleted in the background.
+//! Files of tasks aborted while writing may still be left behind, and are removed by a vacuum.
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use object_store::path::Path;
+use tokio::task::{JoinError, JoinHandle};
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::Action;
+use crate::storage::ObjectStoreRef;
+
+/// Run `future` to completion, unless `token` is cancelled first
+pub(crate) async fn cancellable<T>(
+    token: Option<CancellationToken>,
+    future: impl Future<Output = DeltaResult<T>>,
+) -> DeltaResult<T> {
+    match token {
+        Some(token) => tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(DeltaTableError::Cancelled),
+            result = future => result,
+        },
+        None => future.await,
+    }
+}
+
+/// A spawned task which is aborted when its handle is dropped
+pub(crate) struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T> AbortOnDrop<T> {
+    pub(crate) fn new(handle: JoinHandle<T>) -> Self {
+        Self(handle)
+    }
+}
+
+impl<T> Future for AbortOnDrop<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Data files written by an operation that are not part of a commit yet
+///
+/// Files still tracked when the guard is dropped, e.g. because the operation has been
+/// cancelled or failed before committing, are deleted from the object store.
+pub(crate) struct UncommittedFiles {
+    object_store: ObjectStoreRef,
+    paths: Vec<Path>,
+}
+
+impl UncommittedFiles {
+    pub(crate) fn new(object_store: ObjectStoreRef) -> Self {
+        Self {
+            object_store,
+            paths: vec![],
+        }
+    }
+
+    /// Track the files added by `actions`
+    pub(crate) fn track<'a>(&mut self, actions: impl IntoIterator<Item = &'a Action>) {
+        self.paths
+            .extend(actions.into_iter().filter_map(|action| match action {
+                Action::Add(add) => Path::parse(&add.path).ok(),
+                _ => None,
+            }));
+    }
+
+    /// Stop tracking the files, as they are about to be committed
+    pub(crate) fn committed(&mut self) {
+        self.paths.clear();
+    }
+}
+
+impl Drop for UncommittedFiles {
+    fn drop(&mut self) {
+        if self.paths.is_empty() {
+            return;
+        }
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let object_store = self.object_store.clone();
+        let paths = std::mem::take(&mut self.paths);
+        handle.spawn(async move {
+            for path in paths {
+                debug!("deleting uncommitted file {path}");
+                let _ = object_store.delete(&path).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancellable() {
+        let token = CancellationToken::new();
+        let result = cancellable(Some(token.clone()), async { Ok(1) }).await;
+        assert_eq!(result.unwrap(), 1);
+
+        token.cancel();
+        let result = cancellable(Some(token), async { Ok(1) }).await;
+        assert!(matches!(result, Err(DeltaTableError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_abort_on_drop() {
+        let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+        let task = AbortOnDrop::new(tokio::spawn(async move {
+            std::future::pending::<()>().await;
+            drop(sender);
+        }));
+        drop(task);
+        // the sender is dropped when the task is aborted
+        assert!(receiver.await.is_err());
+    }
+}
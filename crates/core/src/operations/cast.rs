@@ -9,7 +9,9 @@ use arrow_array::{
     RecordBatchOptions, StructArray,
 };
 use arrow_cast::{cast_with_options, CastOptions};
-use arrow_schema::{ArrowError, DataType, FieldRef, Fields, SchemaRef as ArrowSchemaRef};
+use arrow_schema::{
+    ArrowError, DataType, FieldRef, Fields, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -300,16 +302,72 @@ pub fn cast_record_batch(
     )?)
 }
 
+/// The arrow type delta-rs writes for data of `data_type`
+///
+/// Large and view types hold the same values as their regular counterparts and map to the same
+/// delta type, so they are replaced recursively. Delta has no unsigned integers, unsigned types
+/// are widened to the next signed type which holds all their values, except for 64 bit integers
+/// whose values are checked for overflows when cast.
+pub(crate) fn canonical_data_type(data_type: &DataType) -> DataType {
+    match data_type {
+        DataType::UInt8 => DataType::Int16,
+        DataType::UInt16 => DataType::Int32,
+        DataType::UInt32 | DataType::UInt64 => DataType::Int64,
+        DataType::LargeUtf8 | DataType::Utf8View => DataType::Utf8,
+        DataType::LargeBinary | DataType::BinaryView => DataType::Binary,
+        DataType::List(field) | DataType::LargeList(field) => {
+            DataType::List(canonical_field(field))
+        }
+        DataType::Struct(fields) => DataType::Struct(fields.iter().map(canonical_field).collect()),
+        DataType::Map(entries, sorted) => DataType::Map(canonical_field(entries), *sorted),
+        _ => data_type.clone(),
+    }
+}
+
+fn canonical_field(field: &FieldRef) -> FieldRef {
+    Arc::new(
+        field
+            .as_ref()
+            .clone()
+            .with_data_type(canonical_data_type(field.data_type())),
+    )
+}
+
+/// The schema with all types replaced by the types delta-rs writes, see [`canonical_data_type`]
+pub(crate) fn canonical_schema(schema: &ArrowSchemaRef) -> ArrowSchemaRef {
+    Arc::new(ArrowSchema::new_with_metadata(
+        schema
+            .fields()
+            .iter()
+            .map(canonical_field)
+            .collect::<Fields>(),
+        schema.metadata().clone(),
+    ))
+}
+
+/// Cast the columns of a batch to the types delta-rs writes, see [`canonical_data_type`]
+///
+/// Fails when a value does not fit into its new type.
+pub(crate) fn cast_to_canonical_types(batch: &RecordBatch) -> DeltaResult<RecordBatch> {
+    let schema = canonical_schema(&batch.schema());
+    if schema == batch.schema() {
+        return Ok(batch.clone());
+    }
+    cast_record_batch(batch, schema, false, false)
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
     use std::ops::Deref;
     use std::sync::Arc;
 
-    use arrow::array::types::Int32Type;
+    use arrow::array::types::{Int16Type, Int32Type, Int64Type};
     use arrow::array::{
         new_empty_array, new_null_array, Array, ArrayData, ArrayRef, AsArray, Int32Array,
-        ListArray, PrimitiveArray, RecordBatch, StringArray, StructArray,
+        LargeBinaryBuilder, LargeListBuilder, LargeStringArray, ListArray, PrimitiveArray,
+        RecordBatch, StringArray, StringViewArray, StructArray, UInt16Array, UInt32Array,
+        UInt64Array, UInt8Array,
     };
     use arrow::buffer::{Buffer, NullBuffer};
     use arrow_schema::{DataType, Field, FieldRef, Fields, Schema, SchemaRef};
@@ -342,6 +400,111 @@ mod tests {
         assert!(result.fields()[0].is_nullable());
     }
 
+    #[test]
+    fn test_cast_to_canonical_types() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("large_string", DataType::LargeUtf8, true),
+            Field::new("string_view", DataType::Utf8View, true),
+            Field::new(
+                "large_list",
+                DataType::LargeList(Arc::new(Field::new("item", DataType::LargeBinary, true))),
+                true,
+            ),
+            Field::new("int", DataType::Int32, true),
+        ]));
+        let mut list = LargeListBuilder::new(LargeBinaryBuilder::new());
+        list.values().append_value(b"a");
+        list.append(true);
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(LargeStringArray::from(vec!["a"])),
+                Arc::new(StringViewArray::from(vec!["b"])),
+                Arc::new(list.finish()),
+                Arc::new(Int32Array::from(vec![1])),
+            ],
+        )
+        .unwrap();
+
+        let result = super::cast_to_canonical_types(&batch).unwrap();
+        let expected = Schema::new(vec![
+            Field::new("large_string", DataType::Utf8, true),
+            Field::new("string_view", DataType::Utf8, true),
+            Field::new(
+                "large_list",
+                DataType::List(Arc::new(Field::new("item", DataType::Binary, true))),
+                true,
+            ),
+            Field::new("int", DataType::Int32, true),
+        ]);
+        assert_eq!(result.schema().as_ref(), &expected);
+        assert_eq!(result.column(1).as_string::<i32>().value(0), "b");
+        assert_eq!(
+            result
+                .column(2)
+                .as_list::<i32>()
+                .value(0)
+                .as_binary::<i32>()
+                .value(0),
+            b"a"
+        );
+
+        // batches without large or view types are returned as is
+        let unchanged = super::cast_to_canonical_types(&result).unwrap();
+        assert_eq!(unchanged, result);
+    }
+
+    #[test]
+    fn test_cast_unsigned_to_canonical_types() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("u8", DataType::UInt8, true),
+            Field::new("u16", DataType::UInt16, true),
+            Field::new("u32", DataType::UInt32, true),
+            Field::new("u64", DataType::UInt64, true),
+        ]));
+        let batch = |u64_value: u64| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(UInt8Array::from(vec![u8::MAX])),
+                    Arc::new(UInt16Array::from(vec![u16::MAX])),
+                    Arc::new(UInt32Array::from(vec![u32::MAX])),
+                    Arc::new(UInt64Array::from(vec![u64_value])),
+                ],
+            )
+            .unwrap()
+        };
+
+        let result = super::cast_to_canonical_types(&batch(i64::MAX as u64)).unwrap();
+        let data_types = result
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect_vec();
+        assert_eq!(
+            data_types,
+            vec![
+                DataType::Int16,
+                DataType::Int32,
+                DataType::Int64,
+                DataType::Int64
+            ]
+        );
+        assert_eq!(result.column(0).as_primitive::<Int16Type>().value(0), 255);
+        assert_eq!(
+            result.column(2).as_primitive::<Int64Type>().value(0),
+            u32::MAX as i64
+        );
+        assert_eq!(
+            result.column(3).as_primitive::<Int64Type>().value(0),
+            i64::MAX
+        );
+
+        // 64 bit values which do not fit into a long are rejected
+        assert!(super::cast_to_canonical_types(&batch(u64::MAX)).is_err());
+    }
+
     #[test]
     fn test_merge_schema_with_meta() {
         let mut left_meta = HashMap::new();
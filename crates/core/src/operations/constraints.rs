@@ -8,7 +8,10 @@ use datafusion::physical_plan::ExecutionPlan;
 use datafusion::prelude::SessionContext;
 use datafusion_common::ToDFSchema;
 use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
 use futures::StreamExt;
+use tokio_util::sync::CancellationToken;
+use tracing::{field, info_span, Instrument};
 
 use crate::delta_datafusion::expr::fmt_expr_to_sql;
 use crate::delta_datafusion::{
@@ -22,7 +25,9 @@ use crate::table::state::DeltaTableState;
 use crate::table::Constraint;
 use crate::{DeltaResult, DeltaTable, DeltaTableError};
 
+use super::cancellation::{cancellable, AbortOnDrop};
 use super::datafusion_utils::into_expr;
+use super::progress::{report, ProgressReporterRef, ProgressStage};
 use super::transaction::{CommitBuilder, CommitProperties};
 
 /// Build a constraint to add to a table
@@ -39,6 +44,10 @@ pub struct ConstraintBuilder {
     state: Option<SessionState>,
     /// Additional information to add to the commit
     commit_properties: CommitProperties,
+    /// Token to cancel the validation of the existing data
+    cancellation_token: Option<CancellationToken>,
+    /// Receiver of the number of validated partitions of the table scan
+    progress_reporter: Option<ProgressReporterRef>,
 }
 
 impl super::Operation<()> for ConstraintBuilder {}
@@ -53,6 +62,8 @@ impl ConstraintBuilder {
             log_store,
             state: None,
             commit_properties: CommitProperties::default(),
+            cancellation_token: None,
+            progress_reporter: None,
         }
     }
 
@@ -78,6 +89,20 @@ impl ConstraintBuilder {
         self.commit_properties = commit_properties;
         self
     }
+
+    /// Abort the operation once `token` is cancelled, stopping the scan validating the
+    /// existing data against the constraint
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Report the number of partitions of the table scan which have been validated against
+    /// the constraint as [`ProgressStage::ConstraintCheck`]
+    pub fn with_progress_reporter(mut self, reporter: ProgressReporterRef) -> Self {
+        self.progress_reporter = Some(reporter);
+        self
+    }
 }
 
 impl std::future::IntoFuture for ConstraintBuilder {
@@ -87,22 +112,34 @@ impl std::future::IntoFuture for ConstraintBuilder {
 
     fn into_future(self) -> Self::IntoFuture {
         let this = self;
+        let cancellation_token = this.cancellation_token.clone();
+        let span = info_span!(
+            "delta.add_constraint",
+            table_uri = %this.log_store.root_uri(),
+            version = this.snapshot.version(),
+            name = this.name.as_deref(),
+            expr = this.expr.as_ref().map(field::display),
+        );
 
-        Box::pin(async move {
+        let future = cancellable(cancellation_token, async move {
             let name = match this.name {
                 Some(v) => v,
-                None => return Err(DeltaTableError::Generic("No name provided".to_string())),
+                None => {
+                    return Err(DeltaTableError::InvalidArgument(
+                        "No name provided".to_string(),
+                    ))
+                }
             };
 
-            let expr = this
-                .expr
-                .ok_or_else(|| DeltaTableError::Generic("No Expresion provided".to_string()))?;
+            let expr = this.expr.ok_or_else(|| {
+                DeltaTableError::InvalidArgument("No Expresion provided".to_string())
+            })?;
 
             let mut metadata = this.snapshot.metadata().clone();
             let configuration_key = format!("delta.constraints.{}", name);
 
             if metadata.configuration.contains_key(&configuration_key) {
-                return Err(DeltaTableError::Generic(format!(
+                return Err(DeltaTableError::InvalidArgument(format!(
                     "Constraint with name: {} already exists",
                     name
                 )));
@@ -127,7 +164,7 @@ impl std::future::IntoFuture for ConstraintBuilder {
                 DeltaDataChecker::new_with_constraints(vec![Constraint::new("*", &expr_str)]);
 
             let plan: Arc<dyn ExecutionPlan> = Arc::new(scan);
-            let mut tasks = vec![];
+            let mut tasks = FuturesUnordered::new();
             for p in 0..plan.properties().output_partitioning().partition_count() {
                 let inner_plan = plan.clone();
                 let inner_checker = checker.clone();
@@ -142,15 +179,32 @@ impl std::future::IntoFuture for ConstraintBuilder {
                         }
                         Ok(())
                     });
-                tasks.push(handle);
+                tasks.push(AbortOnDrop::new(handle));
+            }
+            let total = tasks.len() as u64;
+            let mut done = 0;
+            while let Some(result) = tasks.next().await {
+                result
+                    .map_err(|err| DeltaTableError::GenericError {
+                        source: Box::new(err),
+                    })?
+                    .map_err(|err| match err {
+                        DeltaTableError::InvalidData { violations } => {
+                            DeltaTableError::ConstraintViolation {
+                                constraint: name.clone(),
+                                violations,
+                            }
+                        }
+                        err => err,
+                    })?;
+                done += 1;
+                report(
+                    this.progress_reporter.as_ref(),
+                    ProgressStage::ConstraintCheck,
+                    done,
+                    total,
+                );
             }
-            futures::future::join_all(tasks)
-                .await
-                .into_iter()
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|err| DeltaTableError::Generic(err.to_string()))?
-                .into_iter()
-                .collect::<Result<Vec<_>, _>>()?;
 
             // We have validated the table passes it's constraints, now to add the constraint to
             // the table.
@@ -202,7 +256,9 @@ impl std::future::IntoFuture for ConstraintBuilder {
                 this.log_store,
                 commit.snapshot(),
             ))
-        })
+        });
+
+        Box::pin(future.instrument(span))
     }
 }
 
@@ -215,8 +271,10 @@ mod tests {
     use arrow_schema::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
     use datafusion_expr::{col, lit};
 
+    use crate::operations::progress::tests::RecordingReporter;
+    use crate::operations::progress::ProgressStage;
     use crate::writer::test_utils::{create_bare_table, get_arrow_schema, get_record_batch};
-    use crate::{DeltaOps, DeltaResult, DeltaTable};
+    use crate::{DeltaOps, DeltaResult, DeltaTable, DeltaTableError};
 
     fn get_constraint(table: &DeltaTable, name: &str) -> String {
         table
@@ -255,7 +313,10 @@ mod tests {
             .add_constraint()
             .with_constraint("id", "value > 5")
             .await;
-        assert!(constraint.is_err());
+        assert!(matches!(
+            constraint,
+            Err(DeltaTableError::ConstraintViolation { ref constraint, .. }) if constraint == "id"
+        ));
         Ok(())
     }
 
@@ -283,6 +344,30 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn add_constraint_progress() -> DeltaResult<()> {
+        let batch = get_record_batch(None, false);
+        let write = DeltaOps(create_bare_table())
+            .write(vec![batch.clone()])
+            .await?;
+
+        let reporter = Arc::new(RecordingReporter::default());
+        DeltaOps(write)
+            .add_constraint()
+            .with_constraint("id", "value < 1000")
+            .with_progress_reporter(reporter.clone())
+            .await?;
+
+        let calls = reporter.calls.lock().clone();
+        assert!(!calls.is_empty());
+        for (i, (stage, done, total)) in calls.iter().enumerate() {
+            assert_eq!(*stage, ProgressStage::ConstraintCheck);
+            assert_eq!(*done, i as u64 + 1);
+            assert_eq!(*total, calls.len() as u64);
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn add_constraint_datafusion() -> DeltaResult<()> {
         // Add constraint by providing a datafusion expression.
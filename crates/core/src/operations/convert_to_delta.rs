@@ -17,6 +17,7 @@ use serde_json::{Map, Value};
 use tracing::debug;
 
 use crate::operations::get_num_idx_cols_and_stats_columns;
+use crate::operations::transaction::CommitProperties;
 use crate::{
     kernel::{scalars::ScalarExt, Add, DataType, Schema, StructField},
     logstore::{LogStore, LogStoreRef},
@@ -60,7 +61,7 @@ enum Error {
 impl From<Error> for DeltaTableError {
     fn from(err: Error) -> Self {
         match err {
-            Error::ObjectStore(e) => DeltaTableError::ObjectStore { source: e },
+            Error::ObjectStore(e) => e.into(),
             Error::Arrow(e) => DeltaTableError::Arrow { source: e },
             Error::Parquet(e) => DeltaTableError::Parquet { source: e },
             Error::DeltaTable(e) => e,
@@ -107,6 +108,7 @@ pub struct ConvertToDeltaBuilder {
     comment: Option<String>,
     configuration: HashMap<String, Option<String>>,
     metadata: Option<Map<String, Value>>,
+    commit_properties: CommitProperties,
 }
 
 impl Default for ConvertToDeltaBuilder {
@@ -131,6 +133,7 @@ impl ConvertToDeltaBuilder {
             comment: None,
             configuration: Default::default(),
             metadata: Default::default(),
+            commit_properties: CommitProperties::default(),
         }
     }
 
@@ -229,6 +232,12 @@ impl ConvertToDeltaBuilder {
         self
     }
 
+    /// Additional information to write to the commit
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+
     /// Consume self into CreateBuilder with corresponding add actions, schemas and operation meta
     async fn into_create_builder(self) -> Result<CreateBuilder, Error> {
         // Use the specified log store. If a log store is not provided, create a new store from the specified path.
@@ -402,7 +411,8 @@ impl ConvertToDeltaBuilder {
             .with_partition_columns(partition_columns.into_iter())
             .with_actions(actions)
             .with_save_mode(self.mode)
-            .with_configuration(self.configuration);
+            .with_configuration(self.configuration)
+            .with_commit_properties(self.commit_properties);
         if let Some(name) = self.name {
             builder = builder.with_table_name(name);
         }
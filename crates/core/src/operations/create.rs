@@ -1,7 +1,7 @@
 //! Command for creating a new delta table
 // https://github.com/delta-io/delta/blob/master/core/src/main/scala/org/apache/spark/sql/delta/commands/CreateDeltaTableCommand.scala
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use delta_kernel::schema::MetadataValue;
@@ -9,7 +9,8 @@ use futures::future::BoxFuture;
 use maplit::hashset;
 use serde_json::Value;
 
-use super::transaction::{CommitBuilder, TableReference, PROTOCOL};
+use super::transaction::{CommitBuilder, CommitProperties, TableReference, PROTOCOL};
+use crate::data_catalog::{DataCatalogError, DataCatalogRef};
 use crate::errors::{DeltaResult, DeltaTableError};
 use crate::kernel::{
     Action, DataType, Metadata, Protocol, ReaderFeatures, StructField, StructType, WriterFeatures,
@@ -18,9 +19,11 @@ use crate::logstore::{LogStore, LogStoreRef};
 use crate::operations::set_tbl_properties::{
     apply_properties_to_protocol, convert_properties_to_features,
 };
+use crate::operations::table_features::legacy_writer_features;
 use crate::protocol::{DeltaOperation, SaveMode};
 use crate::table::builder::ensure_table_uri;
 use crate::table::config::DeltaConfigKey;
+use crate::writer::AuditColumns;
 use crate::{DeltaTable, DeltaTableBuilder};
 
 #[derive(thiserror::Error, Debug)]
@@ -39,6 +42,18 @@ enum CreateError {
 
     #[error("SaveMode `append` is not allowed for create operation.")]
     AppendNotAllowed,
+
+    #[error("Column {0} is not part of the table schema.")]
+    ColumnNotFound(String),
+
+    #[error("Partition column {0} is specified more than once.")]
+    DuplicatePartitionColumn(String),
+
+    #[error("Partition column {name} has the unsupported type {data_type}, only primitive types are supported.")]
+    UnsupportedPartitionColumnType { name: String, data_type: DataType },
+
+    #[error("Table {0} is already registered in the catalog for another location.")]
+    CatalogTableExists(String),
 }
 
 impl From<CreateError> for DeltaTableError {
@@ -49,6 +64,79 @@ impl From<CreateError> for DeltaTableError {
     }
 }
 
+/// Key of the column metadata holding the comment of a column
+pub const COLUMN_COMMENT_KEY: &str = "comment";
+
+/// Name under which a created table is registered in a data catalog
+#[derive(Debug, Clone)]
+struct CatalogRegistration {
+    catalog: DataCatalogRef,
+    database_name: String,
+    table_name: String,
+}
+
+impl CatalogRegistration {
+    fn name_taken(&self) -> DeltaTableError {
+        CreateError::CatalogTableExists(format!("{}.{}", self.database_name, self.table_name))
+            .into()
+    }
+
+    /// Fail before the table is created if it can't be registered, i.e. the catalog does not
+    /// support registering tables or the name is registered for another location
+    async fn check(&self, location: &str, mode: SaveMode) -> DeltaResult<()> {
+        if !self.catalog.supports_create_table() {
+            return Err(DataCatalogError::NotSupported {
+                operation: "create_table",
+            }
+            .into());
+        }
+        if mode == SaveMode::Overwrite {
+            return Ok(());
+        }
+        match self
+            .catalog
+            .get_table(&self.database_name, &self.table_name)
+            .await
+        {
+            Ok(table) if !same_location(&table.location, location) => Err(self.name_taken()),
+            Ok(_) | Err(DataCatalogError::TableNotFound { .. }) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Register the table, tables which are overwritten point the name at their new location
+    async fn register(&self, location: &str, mode: SaveMode) -> DeltaResult<()> {
+        match self
+            .catalog
+            .create_table(&self.database_name, &self.table_name, location)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(DataCatalogError::TableAlreadyExists { .. }) => {
+                let table = self
+                    .catalog
+                    .get_table(&self.database_name, &self.table_name)
+                    .await?;
+                if same_location(&table.location, location) {
+                    Ok(())
+                } else if mode == SaveMode::Overwrite {
+                    self.catalog
+                        .update_table_location(&self.database_name, &self.table_name, location)
+                        .await?;
+                    Ok(())
+                } else {
+                    Err(self.name_taken())
+                }
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+fn same_location(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
 /// Build an operation to create a new [DeltaTable]
 #[derive(Debug, Clone)]
 pub struct CreateBuilder {
@@ -57,6 +145,7 @@ pub struct CreateBuilder {
     mode: SaveMode,
     comment: Option<String>,
     columns: Vec<StructField>,
+    column_comments: HashMap<String, String>,
     partition_columns: Option<Vec<String>>,
     storage_options: Option<HashMap<String, String>>,
     actions: Vec<Action>,
@@ -64,6 +153,8 @@ pub struct CreateBuilder {
     configuration: HashMap<String, Option<String>>,
     metadata: Option<HashMap<String, Value>>,
     raise_if_key_not_exists: bool,
+    commit_properties: CommitProperties,
+    catalog: Option<CatalogRegistration>,
 }
 
 impl super::Operation<()> for CreateBuilder {}
@@ -83,6 +174,7 @@ impl CreateBuilder {
             mode: SaveMode::ErrorIfExists,
             comment: None,
             columns: Default::default(),
+            column_comments: Default::default(),
             partition_columns: None,
             storage_options: None,
             actions: Default::default(),
@@ -90,6 +182,8 @@ impl CreateBuilder {
             configuration: Default::default(),
             metadata: Default::default(),
             raise_if_key_not_exists: true,
+            commit_properties: CommitProperties::default(),
+            catalog: None,
         }
     }
 
@@ -132,8 +226,8 @@ impl CreateBuilder {
             field = field.with_metadata(meta.iter().map(|(k, v)| {
                 (
                     k,
-                    if let Value::Number(n) = v {
-                        n.as_i64().map_or_else(
+                    match v {
+                        Value::Number(n) => n.as_i64().map_or_else(
                             || MetadataValue::String(v.to_string()),
                             |i| {
                                 i32::try_from(i)
@@ -141,9 +235,10 @@ impl CreateBuilder {
                                     .map(MetadataValue::Number)
                                     .unwrap_or_else(|| MetadataValue::String(v.to_string()))
                             },
-                        )
-                    } else {
-                        MetadataValue::String(v.to_string())
+                        ),
+                        // keep strings unquoted, so they round-trip through the schema
+                        Value::String(s) => MetadataValue::String(s.clone()),
+                        _ => MetadataValue::String(v.to_string()),
                     },
                 )
             }));
@@ -161,6 +256,21 @@ impl CreateBuilder {
         self
     }
 
+    /// Comment to describe a column of the table, stored in the `comment` metadata of the column.
+    pub fn with_column_comment(
+        mut self,
+        column: impl Into<String>,
+        comment: impl Into<String>,
+    ) -> Self {
+        self.column_comments.insert(column.into(), comment.into());
+        self
+    }
+
+    /// Append the audit columns filled by writers configured with [`AuditColumns`]
+    pub fn with_audit_columns(self) -> Self {
+        self.with_columns(AuditColumns::fields())
+    }
+
     /// Specify table partitioning
     pub fn with_partition_columns(
         mut self,
@@ -216,6 +326,12 @@ impl CreateBuilder {
         self
     }
 
+    /// Additional information to write to the commit
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+
     /// Specify whether to raise an error if the table properties in the configuration are not DeltaConfigKeys
     pub fn with_raise_if_key_not_exists(mut self, raise_if_key_not_exists: bool) -> Self {
         self.raise_if_key_not_exists = raise_if_key_not_exists;
@@ -237,6 +353,26 @@ impl CreateBuilder {
         self
     }
 
+    /// Register the created table in `catalog` as `database_name.table_name`.
+    ///
+    /// Fails before creating the table if the catalog does not support registering tables, see
+    /// [`DataCatalog::supports_create_table`](crate::data_catalog::DataCatalog::supports_create_table),
+    /// or if the name is registered for another location, unless the save mode is
+    /// [`SaveMode::Overwrite`], which points the name at the new location.
+    pub fn with_catalog(
+        mut self,
+        catalog: DataCatalogRef,
+        database_name: impl Into<String>,
+        table_name: impl Into<String>,
+    ) -> Self {
+        self.catalog = Some(CatalogRegistration {
+            catalog,
+            database_name: database_name.into(),
+            table_name: table_name.into(),
+        });
+        self
+    }
+
     /// Consume self into uninitialized table with corresponding create actions and operation meta
     pub(crate) fn into_table_and_actions(
         self,
@@ -251,6 +387,11 @@ impl CreateBuilder {
         if self.columns.is_empty() {
             return Err(CreateError::MissingSchema.into());
         }
+        validate_partition_columns(
+            &self.columns,
+            self.partition_columns.as_deref().unwrap_or_default(),
+        )?;
+        let columns = add_column_comments(self.columns, self.column_comments)?;
 
         let (storage_url, table) = if let Some(log_store) = self.log_store {
             (
@@ -268,7 +409,7 @@ impl CreateBuilder {
         };
 
         let configuration = self.configuration;
-        let contains_timestampntz = PROTOCOL.contains_timestampntz(self.columns.iter());
+        let contains_timestampntz = PROTOCOL.contains_timestampntz(columns.iter());
         // TODO configure more permissive versions based on configuration. Also how should this ideally be handled?
         // We set the lowest protocol we can, and if subsequent writes use newer features we update metadata?
 
@@ -310,7 +451,7 @@ impl CreateBuilder {
         let protocol = convert_properties_to_features(protocol, &configuration);
 
         let mut metadata = Metadata::try_new(
-            StructType::new(self.columns),
+            StructType::new(columns),
             self.partition_columns.unwrap_or_default(),
             configuration,
         )?
@@ -341,6 +482,90 @@ impl CreateBuilder {
     }
 }
 
+/// Check that the partition columns are distinct primitive columns of the schema
+fn validate_partition_columns(
+    columns: &[StructField],
+    partition_columns: &[String],
+) -> Result<(), CreateError> {
+    let mut seen = HashSet::new();
+    for name in partition_columns {
+        let field = columns
+            .iter()
+            .find(|field| field.name() == name)
+            .ok_or_else(|| CreateError::ColumnNotFound(name.clone()))?;
+        if !matches!(field.data_type(), DataType::Primitive(_)) {
+            return Err(CreateError::UnsupportedPartitionColumnType {
+                name: name.clone(),
+                data_type: field.data_type().clone(),
+            });
+        }
+        if !seen.insert(name) {
+            return Err(CreateError::DuplicatePartitionColumn(name.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// The protocol of a replaced table, which must not be downgraded from its `current_protocol`
+fn merge_protocols(current_protocol: &Protocol, new_protocol: &Protocol) -> Protocol {
+    let writer_features = |protocol: &Protocol| {
+        protocol
+            .writer_features
+            .clone()
+            .unwrap_or_else(|| legacy_writer_features(protocol.min_writer_version))
+    };
+    let reader_features = |protocol: &Protocol| match &protocol.reader_features {
+        Some(features) => features.clone(),
+        None if protocol.min_reader_version >= 2 => hashset! {ReaderFeatures::ColumnMapping},
+        None => HashSet::new(),
+    };
+
+    let mut protocol = Protocol {
+        min_reader_version: current_protocol
+            .min_reader_version
+            .max(new_protocol.min_reader_version),
+        min_writer_version: current_protocol
+            .min_writer_version
+            .max(new_protocol.min_writer_version),
+        reader_features: None,
+        writer_features: None,
+    };
+    if protocol.min_writer_version >= 7 {
+        let mut features = writer_features(current_protocol);
+        features.extend(writer_features(new_protocol));
+        protocol.writer_features = Some(features);
+    }
+    if protocol.min_reader_version >= 3 {
+        let mut features = reader_features(current_protocol);
+        features.extend(reader_features(new_protocol));
+        protocol.reader_features = Some(features);
+    }
+    protocol
+}
+
+/// Store the comments in the metadata of the commented columns
+fn add_column_comments(
+    columns: Vec<StructField>,
+    mut comments: HashMap<String, String>,
+) -> Result<Vec<StructField>, CreateError> {
+    let columns = columns
+        .into_iter()
+        .map(|mut field| {
+            if let Some(comment) = comments.remove(field.name()) {
+                field.metadata.insert(
+                    COLUMN_COMMENT_KEY.to_string(),
+                    MetadataValue::String(comment),
+                );
+            }
+            field
+        })
+        .collect();
+    match comments.into_keys().next() {
+        Some(column) => Err(CreateError::ColumnNotFound(column)),
+        None => Ok(columns),
+    }
+}
+
 impl std::future::IntoFuture for CreateBuilder {
     type Output = DeltaResult<DeltaTable>;
     type IntoFuture = BoxFuture<'static, Self::Output>;
@@ -349,9 +574,17 @@ impl std::future::IntoFuture for CreateBuilder {
         let this = self;
         Box::pin(async move {
             let mode = this.mode;
-            let app_metadata = this.metadata.clone().unwrap_or_default();
-            let (mut table, mut actions, operation) = this.into_table_and_actions()?;
+            let mut commit_properties = this.commit_properties.clone();
+            if let Some(metadata) = this.metadata.clone() {
+                commit_properties.app_metadata.extend(metadata);
+            }
+            let catalog = this.catalog.clone();
+            let (mut table, mut actions, mut operation) = this.into_table_and_actions()?;
             let log_store = table.log_store();
+            let location = log_store.root_uri();
+            if let Some(catalog) = &catalog {
+                catalog.check(&location, mode).await?;
+            }
 
             let table_state = if log_store.is_delta_table_location().await? {
                 match mode {
@@ -359,10 +592,24 @@ impl std::future::IntoFuture for CreateBuilder {
                     SaveMode::Append => return Err(CreateError::AppendNotAllowed.into()),
                     SaveMode::Ignore => {
                         table.load().await?;
+                        if let Some(catalog) = &catalog {
+                            catalog.register(&location, mode).await?;
+                        }
                         return Ok(table);
                     }
                     SaveMode::Overwrite => {
+                        // replace the table with a commit on the existing log, so that its history
+                        // and version numbers are preserved
                         table.load().await?;
+                        let current_protocol = table.protocol()?;
+                        for action in actions.iter_mut() {
+                            if let Action::Protocol(protocol) = action {
+                                *protocol = merge_protocols(current_protocol, protocol);
+                            }
+                        }
+                        if let DeltaOperation::Create { protocol, .. } = &mut operation {
+                            *protocol = merge_protocols(current_protocol, protocol);
+                        }
                         let remove_actions = table
                             .snapshot()?
                             .log_data()
@@ -376,9 +623,8 @@ impl std::future::IntoFuture for CreateBuilder {
                 None
             };
 
-            let version = CommitBuilder::default()
+            let version = CommitBuilder::from(commit_properties)
                 .with_actions(actions)
-                .with_app_metadata(app_metadata)
                 .build(
                     table_state.map(|f| f as &dyn TableReference),
                     table.log_store.clone(),
@@ -387,6 +633,9 @@ impl std::future::IntoFuture for CreateBuilder {
                 .await?
                 .version();
             table.load_version(version).await?;
+            if let Some(catalog) = &catalog {
+                catalog.register(&location, mode).await?;
+            }
 
             Ok(table)
         })
@@ -396,6 +645,7 @@ impl std::future::IntoFuture for CreateBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kernel::PrimitiveType;
     use crate::operations::DeltaOps;
     use crate::table::config::DeltaConfigKey;
     use crate::writer::test_utils::{get_delta_schema, get_record_batch};
@@ -595,6 +845,68 @@ mod tests {
         assert_eq!(table.get_files_count(), 0);
     }
 
+    #[tokio::test]
+    async fn test_create_or_replace_keeps_history_and_protocol() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let schema = get_delta_schema();
+        let table = CreateBuilder::new()
+            .with_location(tmp_dir.path().to_str().unwrap())
+            .with_columns(schema.fields().cloned())
+            .with_column("ts", DataType::TIMESTAMP_NTZ, true, None)
+            .await
+            .unwrap();
+        let first_protocol = table.protocol().unwrap().clone();
+
+        let mut table = CreateBuilder::new()
+            .with_log_store(table.log_store())
+            .with_columns(schema.fields().cloned())
+            .with_save_mode(SaveMode::Overwrite)
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 1);
+        assert_eq!(table.get_schema().unwrap(), &schema);
+
+        // the protocol is not downgraded, and lists the features of both tables
+        let protocol = table.protocol().unwrap();
+        assert_eq!(protocol.min_reader_version, 3);
+        assert_eq!(protocol.min_writer_version, 7);
+        assert_eq!(protocol.reader_features, first_protocol.reader_features);
+        assert_eq!(
+            protocol.writer_features,
+            Some(hashset! {
+                WriterFeatures::TimestampWithoutTimezone,
+                WriterFeatures::AppendOnly,
+                WriterFeatures::Invariants,
+            })
+        );
+
+        // the previous version is still readable
+        table.load_version(0).await.unwrap();
+        assert_eq!(table.get_schema().unwrap().fields().count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_create_table_commit_properties() {
+        let schema = get_delta_schema();
+        let table = CreateBuilder::new()
+            .with_location("memory://")
+            .with_columns(schema.fields().cloned())
+            .with_metadata(vec![("source".to_string(), Value::String("cli".into()))])
+            .with_commit_properties(
+                CommitProperties::default()
+                    .with_user_metadata("initial load")
+                    .with_engine_info("my-app/1.0"),
+            )
+            .await
+            .unwrap();
+
+        let history = table.history(None).await.unwrap();
+        let commit_info = &history[0];
+        assert_eq!(commit_info.user_metadata.as_deref(), Some("initial load"));
+        assert_eq!(commit_info.engine_info.as_deref(), Some("my-app/1.0"));
+        assert_eq!(commit_info.info["source"], Value::String("cli".into()));
+    }
+
     #[tokio::test]
     async fn test_create_table_metadata_raise_if_key_not_exists() {
         let schema = get_delta_schema();
@@ -633,4 +945,130 @@ mod tests {
             .clone();
         assert_eq!(String::from("value"), value);
     }
+
+    #[tokio::test]
+    async fn test_create_table_invalid_partition_columns() {
+        let schema = get_delta_schema();
+        let nested = StructField::new(
+            "nested",
+            DataType::Struct(Box::new(StructType::new(schema.fields().cloned()))),
+            true,
+        );
+
+        for partition_columns in [vec!["missing"], vec!["id", "id"], vec!["nested"]] {
+            let table = CreateBuilder::new()
+                .with_location("memory://")
+                .with_columns(schema.fields().cloned())
+                .with_columns([nested.clone()])
+                .with_partition_columns(partition_columns)
+                .await;
+            assert!(table.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_table_column_comments() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let table = CreateBuilder::new()
+            .with_location(tmp_dir.path().to_str().unwrap())
+            .with_column(
+                "id",
+                DataType::Primitive(PrimitiveType::String),
+                true,
+                Some(HashMap::from([(
+                    "owner".to_string(),
+                    Value::String("team".to_string()),
+                )])),
+            )
+            .with_column_comment("id", "The identifier")
+            .await
+            .unwrap();
+
+        let table = crate::open_table(table.table_uri()).await.unwrap();
+        let metadata = &table.get_schema().unwrap().field("id").unwrap().metadata;
+        assert_eq!(
+            metadata.get(COLUMN_COMMENT_KEY),
+            Some(&MetadataValue::String("The identifier".to_string()))
+        );
+        assert_eq!(
+            metadata.get("owner"),
+            Some(&MetadataValue::String("team".to_string()))
+        );
+
+        let table = CreateBuilder::new()
+            .with_location("memory://")
+            .with_column("id", DataType::Primitive(PrimitiveType::String), true, None)
+            .with_column_comment("missing", "The identifier")
+            .await;
+        assert!(table.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_table_registers_in_catalog() {
+        use crate::data_catalog::{DataCatalog, InMemoryCatalog};
+
+        let schema = get_delta_schema();
+        let catalog = Arc::new(InMemoryCatalog::new());
+        let (tmp_dir, other_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        let create = |dir: &TempDir, mode: SaveMode| {
+            CreateBuilder::new()
+                .with_location(dir.path().to_str().unwrap())
+                .with_columns(schema.fields().cloned())
+                .with_save_mode(mode)
+                .with_catalog(catalog.clone(), "db", "table")
+        };
+
+        let table = create(&tmp_dir, SaveMode::ErrorIfExists).await.unwrap();
+        let registered = catalog.get_table("db", "table").await.unwrap();
+        assert_eq!(registered.location, table.table_uri());
+
+        // registering the same location again is a no-op
+        create(&tmp_dir, SaveMode::Ignore).await.unwrap();
+
+        // the name is taken by the first table, so no table is created
+        assert!(create(&other_dir, SaveMode::ErrorIfExists).await.is_err());
+        assert!(!other_dir.path().join("_delta_log").exists());
+
+        // overwriting points the name at the new location
+        let table = create(&other_dir, SaveMode::Overwrite).await.unwrap();
+        let registered = catalog.get_table("db", "table").await.unwrap();
+        assert_eq!(registered.location, table.table_uri());
+        assert_eq!(catalog.list_tables("db").await.unwrap(), ["table"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_table_fails_early_for_read_only_catalog() {
+        use crate::data_catalog::{DataCatalog, DataCatalogError};
+
+        /// Catalog only resolving table locations, like Glue or Unity
+        #[derive(Debug)]
+        struct ReadOnlyCatalog;
+
+        #[async_trait::async_trait]
+        impl DataCatalog for ReadOnlyCatalog {
+            async fn get_table_storage_location(
+                &self,
+                _catalog_id: Option<String>,
+                database_name: &str,
+                table_name: &str,
+            ) -> Result<String, DataCatalogError> {
+                Err(DataCatalogError::TableNotFound {
+                    database_name: database_name.to_string(),
+                    table_name: table_name.to_string(),
+                })
+            }
+        }
+
+        let tmp_dir = TempDir::new().unwrap();
+        for mode in [SaveMode::ErrorIfExists, SaveMode::Overwrite] {
+            let result = CreateBuilder::new()
+                .with_location(tmp_dir.path().to_str().unwrap())
+                .with_columns(get_delta_schema().fields().cloned())
+                .with_save_mode(mode)
+                .with_catalog(Arc::new(ReadOnlyCatalog), "db", "table")
+                .await;
+            assert!(result.is_err());
+            assert!(!tmp_dir.path().join("_delta_log").exists());
+        }
+    }
 }
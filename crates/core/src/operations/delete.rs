@@ -21,7 +21,8 @@ use core::panic;
 use std::sync::Arc;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use crate::logstore::LogStoreRef;
+use crate::logstore::{operation_log_store, LogStoreRef};
+use chrono::Utc;
 use datafusion::execution::context::{SessionContext, SessionState};
 use datafusion::physical_plan::filter::FilterExec;
 use datafusion::physical_plan::ExecutionPlan;
@@ -30,17 +31,20 @@ use datafusion_common::scalar::ScalarValue;
 use datafusion_common::DFSchema;
 use futures::future::BoxFuture;
 use parquet::file::properties::WriterProperties;
-use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{field, info_span, Instrument};
 
+use super::cancellation::{cancellable, UncommittedFiles};
 use super::datafusion_utils::Expression;
 use super::transaction::{CommitBuilder, CommitProperties, PROTOCOL};
 use super::write::WriterStatsConfig;
+pub use super::DeleteMetrics;
 use crate::delta_datafusion::expr::fmt_expr_to_sql;
 use crate::delta_datafusion::{
     find_files, register_store, DataFusionMixins, DeltaScanBuilder, DeltaSessionContext,
 };
 use crate::errors::DeltaResult;
-use crate::kernel::{Action, Add, Remove};
+use crate::kernel::{Action, Add, Remove, Transaction};
 use crate::operations::write::write_execution_plan;
 use crate::protocol::DeltaOperation;
 use crate::table::state::DeltaTableState;
@@ -61,25 +65,8 @@ pub struct DeleteBuilder {
     writer_properties: Option<WriterProperties>,
     /// Commit properties and configuration
     commit_properties: CommitProperties,
-}
-
-#[derive(Default, Debug, Serialize)]
-/// Metrics for the Delete Operation
-pub struct DeleteMetrics {
-    /// Number of files added
-    pub num_added_files: usize,
-    /// Number of files removed
-    pub num_removed_files: usize,
-    /// Number of rows removed
-    pub num_deleted_rows: Option<usize>,
-    /// Number of rows copied in the process of deleting files
-    pub num_copied_rows: Option<usize>,
-    /// Time taken to execute the entire operation
-    pub execution_time_ms: u64,
-    /// Time taken to scan the file for matches
-    pub scan_time_ms: u64,
-    /// Time taken to rewrite the matched files
-    pub rewrite_time_ms: u64,
+    /// Token to cancel the delete
+    cancellation_token: Option<CancellationToken>,
 }
 
 impl super::Operation<()> for DeleteBuilder {}
@@ -94,6 +81,7 @@ impl DeleteBuilder {
             state: None,
             commit_properties: CommitProperties::default(),
             writer_properties: None,
+            cancellation_token: None,
         }
     }
 
@@ -115,11 +103,33 @@ impl DeleteBuilder {
         self
     }
 
+    /// Record an application transaction in the delete commit, like
+    /// [`WriteBuilder::with_application_transaction`](crate::operations::write::WriteBuilder::with_application_transaction)
+    pub fn with_application_transaction(mut self, app_id: impl ToString, version: i64) -> Self {
+        self.commit_properties =
+            self.commit_properties
+                .with_application_transaction(Transaction::new_with_last_update(
+                    app_id,
+                    version,
+                    Some(Utc::now().timestamp_millis()),
+                ));
+        self
+    }
+
     /// Writer properties passed to parquet writer for when files are rewritten
     pub fn with_writer_properties(mut self, writer_properties: WriterProperties) -> Self {
         self.writer_properties = Some(writer_properties);
         self
     }
+
+    /// Abort the delete once `token` is cancelled.
+    ///
+    /// The delete then returns [`DeltaTableError::Cancelled`](crate::DeltaTableError::Cancelled)
+    /// without committing, and deletes the rewritten files.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
 }
 
 async fn excute_non_empty_expr(
@@ -234,6 +244,8 @@ async fn execute(
         .as_millis() as i64;
 
     let mut actions: Vec<Action> = add.into_iter().map(Action::Add).collect();
+    let mut uncommitted = UncommittedFiles::new(log_store.object_store());
+    uncommitted.track(&actions);
     metrics.num_removed_files = remove.len();
     metrics.num_added_files = actions.len();
 
@@ -259,7 +271,7 @@ async fn execute(
         .insert("readVersion".to_owned(), snapshot.version().into());
     commit_properties.app_metadata.insert(
         "operationMetrics".to_owned(),
-        serde_json::to_value(&metrics)?,
+        super::operation_metrics(&metrics)?,
     );
 
     // Do not make a commit when there are zero updates to the state
@@ -270,6 +282,7 @@ async fn execute(
         return Ok((snapshot.clone(), metrics));
     }
 
+    uncommitted.committed();
     let commit = CommitBuilder::from(commit_properties)
         .with_actions(actions)
         .build(Some(&snapshot), log_store, operation)
@@ -283,16 +296,25 @@ impl std::future::IntoFuture for DeleteBuilder {
 
     fn into_future(self) -> Self::IntoFuture {
         let this = self;
+        let cancellation_token = this.cancellation_token.clone();
+        let span = info_span!(
+            "delta.delete",
+            table_uri = %this.log_store.root_uri(),
+            version = this.snapshot.version(),
+            predicate = this.predicate.as_ref().map(field::display),
+        );
 
-        Box::pin(async move {
+        let future = cancellable(cancellation_token, async move {
             PROTOCOL.check_append_only(&this.snapshot.snapshot)?;
             PROTOCOL.can_write_to(&this.snapshot.snapshot)?;
+            this.snapshot.ensure_all_files("DELETE")?;
+            let log_store = operation_log_store(&this.log_store);
 
             let state = this.state.unwrap_or_else(|| {
                 let session: SessionContext = DeltaSessionContext::default().into();
 
                 // If a user provides their own their DF state then they must register the store themselves
-                register_store(this.log_store.clone(), session.runtime_env());
+                register_store(log_store.clone(), session.runtime_env());
 
                 session.state()
             });
@@ -309,7 +331,7 @@ impl std::future::IntoFuture for DeleteBuilder {
 
             let (new_snapshot, metrics) = execute(
                 predicate,
-                this.log_store.clone(),
+                log_store,
                 this.snapshot,
                 state,
                 this.writer_properties,
@@ -321,7 +343,9 @@ impl std::future::IntoFuture for DeleteBuilder {
                 DeltaTable::new_with_state(this.log_store, new_snapshot),
                 metrics,
             ))
-        })
+        });
+
+        Box::pin(future.instrument(span))
     }
 }
 
@@ -413,11 +437,17 @@ mod tests {
 
         let commit_info = table.history(None).await.unwrap();
         let last_commit = &commit_info[0];
-        let _extra_info = last_commit.info.clone();
-        // assert_eq!(
-        //     extra_info["operationMetrics"],
-        //     serde_json::to_value(&metrics).unwrap()
-        // );
+        let extra_info = last_commit.info.clone();
+        assert_eq!(
+            extra_info["operationMetrics"],
+            json!({
+                "numAddedFiles": 0,
+                "numRemovedFiles": 1,
+                "executionTimeMs": metrics.execution_time_ms,
+                "scanTimeMs": metrics.scan_time_ms,
+                "rewriteTimeMs": 0,
+            })
+        );
 
         // rewrite is not required
         assert_eq!(metrics.rewrite_time_ms, 0);
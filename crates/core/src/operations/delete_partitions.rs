@@ -0,0 +1,212 @@
+//! Delete whole partitions from a Delta Table without DataFusion
+//!
+//! Without the `datafusion` feature records can only be deleted by predicates on partition
+//! columns. The predicate is parsed into [`PartitionFilter`]s, which are evaluated against the
+//! partition values of the files, and all matching files are removed from the table without
+//! rewriting any data. When a predicate is not provided all files are removed.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table("../path/to/table")?;
+//! let (table, metrics) = DeltaOps(table)
+//!     .delete()
+//!     .with_predicate("date = '2022-05-22'")
+//!     .await?;
+//! ````
+
+use std::time::Instant;
+
+use futures::future::BoxFuture;
+
+use super::transaction::{CommitBuilder, CommitProperties, PROTOCOL};
+pub use super::DeleteMetrics;
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::Action;
+use crate::logstore::LogStoreRef;
+use crate::protocol::DeltaOperation;
+use crate::table::state::DeltaTableState;
+use crate::{DeltaTable, PartitionFilter};
+
+/// Delete the files of the partitions matching a predicate from the Delta Table.
+/// See this module's documentation for more information
+pub struct DeleteBuilder {
+    /// Which partitions to delete
+    predicate: Option<String>,
+    /// A snapshot of the table's state
+    snapshot: DeltaTableState,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Commit properties and configuration
+    commit_properties: CommitProperties,
+}
+
+impl super::Operation<()> for DeleteBuilder {}
+
+impl DeleteBuilder {
+    /// Create a new [`DeleteBuilder`]
+    pub fn new(log_store: LogStoreRef, snapshot: DeltaTableState) -> Self {
+        Self {
+            predicate: None,
+            snapshot,
+            log_store,
+            commit_properties: CommitProperties::default(),
+        }
+    }
+
+    /// A predicate on partition columns that determines which files are deleted,
+    /// see [`PartitionFilter::from_predicate`] for the supported syntax
+    pub fn with_predicate(mut self, predicate: impl Into<String>) -> Self {
+        self.predicate = Some(predicate.into());
+        self
+    }
+
+    /// Additonal information to write to the commit
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+}
+
+async fn execute(
+    predicate: Option<String>,
+    log_store: LogStoreRef,
+    snapshot: DeltaTableState,
+    mut commit_properties: CommitProperties,
+) -> DeltaResult<(DeltaTableState, DeleteMetrics)> {
+    let exec_start = Instant::now();
+    let mut metrics = DeleteMetrics::default();
+
+    let filters = match &predicate {
+        Some(predicate) => PartitionFilter::from_predicate(predicate)?,
+        None => vec![],
+    };
+    let partition_columns = &snapshot.metadata().partition_columns;
+    if let Some(filter) = filters
+        .iter()
+        .find(|filter| !partition_columns.contains(&filter.key))
+    {
+        return Err(DeltaTableError::Generic(format!(
+            "Deleting by a predicate on the non-partition column {} requires the datafusion feature",
+            filter.key
+        )));
+    }
+
+    let scan_start = Instant::now();
+    let actions = snapshot
+        .get_active_add_actions_by_partitions(&filters)?
+        .map(|file| Ok(Action::Remove(file?.remove_action(true))))
+        .collect::<DeltaResult<Vec<_>>>()?;
+    metrics.scan_time_ms = Instant::now().duration_since(scan_start).as_millis() as u64;
+    metrics.num_removed_files = actions.len();
+    metrics.execution_time_ms = Instant::now().duration_since(exec_start).as_millis() as u64;
+
+    // Do not make a commit when there are zero updates to the state
+    if actions.is_empty() {
+        return Ok((snapshot, metrics));
+    }
+
+    commit_properties
+        .app_metadata
+        .insert("readVersion".to_owned(), snapshot.version().into());
+    commit_properties.app_metadata.insert(
+        "operationMetrics".to_owned(),
+        super::operation_metrics(&metrics)?,
+    );
+
+    let operation = DeltaOperation::Delete { predicate };
+    let commit = CommitBuilder::from(commit_properties)
+        .with_actions(actions)
+        .build(Some(&snapshot), log_store, operation)
+        .await?;
+    Ok((commit.snapshot(), metrics))
+}
+
+impl std::future::IntoFuture for DeleteBuilder {
+    type Output = DeltaResult<(DeltaTable, DeleteMetrics)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            PROTOCOL.check_append_only(&this.snapshot.snapshot)?;
+            PROTOCOL.can_write_to(&this.snapshot.snapshot)?;
+            this.snapshot.ensure_all_files("DELETE")?;
+
+            let (new_snapshot, metrics) = execute(
+                this.predicate,
+                this.log_store.clone(),
+                this.snapshot,
+                this.commit_properties,
+            )
+            .await?;
+
+            Ok((
+                DeltaTable::new_with_state(this.log_store, new_snapshot),
+                metrics,
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operations::DeltaOps;
+    use crate::writer::test_utils::{create_initialized_table, get_record_batch};
+    use crate::writer::{DeltaWriter, RecordBatchWriter};
+    use crate::DeltaTable;
+
+    async fn setup_table() -> DeltaTable {
+        let mut table = create_initialized_table(&["modified".to_string()]).await;
+        let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+        writer.write(get_record_batch(None, false)).await.unwrap();
+        writer.flush_and_commit(&mut table).await.unwrap();
+        assert_eq!(table.version(), 1);
+        assert_eq!(table.get_files_count(), 2);
+        table
+    }
+
+    #[tokio::test]
+    async fn test_delete_partitions() {
+        let table = setup_table().await;
+
+        let (table, metrics) = DeltaOps(table)
+            .delete()
+            .with_predicate("modified = '2021-02-02'")
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 2);
+        assert_eq!(table.get_files_count(), 1);
+        assert_eq!(metrics.num_removed_files, 1);
+        assert_eq!(metrics.num_added_files, 0);
+        assert!(table
+            .get_files_iter()
+            .unwrap()
+            .all(|path| path.as_ref().starts_with("modified=2021-02-01/")));
+
+        // nothing matches, so no commit is made
+        let (table, metrics) = DeltaOps(table)
+            .delete()
+            .with_predicate("modified IN ('2021-02-03')")
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 2);
+        assert_eq!(metrics.num_removed_files, 0);
+
+        let (table, metrics) = DeltaOps(table).delete().await.unwrap();
+        assert_eq!(table.version(), 3);
+        assert_eq!(table.get_files_count(), 0);
+        assert_eq!(metrics.num_removed_files, 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_non_partition_predicate() {
+        let table = setup_table().await;
+
+        let res = DeltaOps(table)
+            .delete()
+            .with_predicate("modified = '2021-02-02' AND value = 1")
+            .await;
+        assert!(res.is_err());
+    }
+}
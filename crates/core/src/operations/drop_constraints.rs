@@ -1,11 +1,14 @@
 //! Drop a constraint from a table
 
+use std::sync::Arc;
+
 use futures::future::BoxFuture;
 
 use super::transaction::{CommitBuilder, CommitProperties};
 use crate::kernel::Action;
 use crate::logstore::LogStoreRef;
 use crate::protocol::DeltaOperation;
+use crate::table::governance::{DestructiveOperation, GovernancePolicy};
 use crate::table::state::DeltaTableState;
 use crate::DeltaTable;
 use crate::{DeltaResult, DeltaTableError};
@@ -20,6 +23,8 @@ pub struct DropConstraintBuilder {
     raise_if_not_exists: bool,
     /// Delta object store for handling data files
     log_store: LogStoreRef,
+    /// Policy checked before removing the constraint
+    governance_policy: Option<Arc<dyn GovernancePolicy>>,
     /// Additional information to add to the commit
     commit_properties: CommitProperties,
 }
@@ -34,6 +39,7 @@ impl DropConstraintBuilder {
             raise_if_not_exists: true,
             snapshot,
             log_store,
+            governance_policy: None,
             commit_properties: CommitProperties::default(),
         }
     }
@@ -50,6 +56,12 @@ impl DropConstraintBuilder {
         self
     }
 
+    /// Check the removal against a governance policy before committing
+    pub fn with_governance_policy(mut self, policy: Arc<dyn GovernancePolicy>) -> Self {
+        self.governance_policy = Some(policy);
+        self
+    }
+
     /// Additional metadata to be added to commit info
     pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
         self.commit_properties = commit_properties;
@@ -66,22 +78,27 @@ impl std::future::IntoFuture for DropConstraintBuilder {
         let this = self;
 
         Box::pin(async move {
-            let name = this
-                .name
-                .ok_or(DeltaTableError::Generic("No name provided".to_string()))?;
+            let name = this.name.ok_or(DeltaTableError::InvalidArgument(
+                "No name provided".to_string(),
+            ))?;
 
             let mut metadata = this.snapshot.metadata().clone();
             let configuration_key = format!("delta.constraints.{}", name);
 
             if metadata.configuration.remove(&configuration_key).is_none() {
                 if this.raise_if_not_exists {
-                    return Err(DeltaTableError::Generic(format!(
+                    return Err(DeltaTableError::InvalidArgument(format!(
                         "Constraint with name: {} doesn't exists",
                         name
                     )));
                 }
                 return Ok(DeltaTable::new_with_state(this.log_store, this.snapshot));
             }
+
+            if let Some(policy) = &this.governance_policy {
+                let operation = DestructiveOperation::DropConstraint { name: name.clone() };
+                policy.check(&this.snapshot.table_config().governance(), &operation)?;
+            }
             let operation = DeltaOperation::DropConstraint { name: name.clone() };
 
             let actions = vec![Action::Metadata(metadata)];
@@ -103,7 +120,7 @@ impl std::future::IntoFuture for DropConstraintBuilder {
 #[cfg(test)]
 mod tests {
     use crate::writer::test_utils::{create_bare_table, get_record_batch};
-    use crate::{DeltaOps, DeltaResult, DeltaTable};
+    use crate::{DeltaOps, DeltaResult, DeltaTable, DeltaTableError};
 
     async fn get_constraint_op_params(table: &mut DeltaTable) -> String {
         let commit_info = table.history(None).await.unwrap();
@@ -155,7 +172,7 @@ mod tests {
             .drop_constraints()
             .with_constraint("not_existing")
             .await;
-        assert!(table.is_err());
+        assert!(matches!(table, Err(DeltaTableError::InvalidArgument(_))));
 
         Ok(())
     }
@@ -180,4 +197,37 @@ mod tests {
         assert_eq!(version, version_after);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn drop_constraint_rejected_by_governance_policy() -> DeltaResult<()> {
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        use crate::table::governance::{DefaultGovernancePolicy, CLASSIFICATION_KEY};
+
+        let batch = get_record_batch(None, false);
+        let write = DeltaOps(create_bare_table())
+            .write(vec![batch.clone()])
+            .await?;
+        let table = DeltaOps(write)
+            .add_constraint()
+            .with_constraint("id", "value < 1000")
+            .await?;
+        let table = DeltaOps(table)
+            .set_tbl_properties()
+            .with_properties(HashMap::from([(
+                CLASSIFICATION_KEY.to_string(),
+                "pii".to_string(),
+            )]))
+            .with_raise_if_not_exists(false)
+            .await?;
+
+        let result = DeltaOps(table)
+            .drop_constraints()
+            .with_constraint("id")
+            .with_governance_policy(Arc::new(DefaultGovernancePolicy::default()))
+            .await;
+        assert!(result.is_err());
+        Ok(())
+    }
 }
@@ -64,7 +64,7 @@ struct FileSystemCheckPlan {
     pub files_to_remove: Vec<Add>,
 }
 
-fn is_absolute_path(path: &str) -> DeltaResult<bool> {
+pub(crate) fn is_absolute_path(path: &str) -> DeltaResult<bool> {
     match Url::parse(path) {
         Ok(_) => Ok(true),
         Err(ParseError::RelativeUrlWithoutBase) => Ok(false),
@@ -205,6 +205,8 @@ impl std::future::IntoFuture for FileSystemCheckBuilder {
         let this = self;
 
         Box::pin(async move {
+            this.snapshot.ensure_all_files("FSCK")?;
+
             let plan = this.create_fsck_plan().await?;
             if this.dry_run {
                 return Ok((
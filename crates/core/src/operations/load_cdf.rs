@@ -3,7 +3,7 @@
 use std::sync::Arc;
 use std::time::SystemTime;
 
-use arrow_schema::{ArrowError, Field};
+use arrow_schema::{ArrowError, Field, Schema, SchemaRef};
 use chrono::{DateTime, Utc};
 use datafusion::datasource::file_format::parquet::ParquetFormat;
 use datafusion::datasource::file_format::FileFormat;
@@ -202,6 +202,77 @@ impl CdfLoadBuilder {
         Ok((change_files, add_files))
     }
 
+    /// Restrict the read to the commits in `range`
+    ///
+    /// Returns `None` when the range does not select any commit of the table.
+    pub(crate) async fn with_commit_range(
+        mut self,
+        range: &CommitRange,
+    ) -> DeltaResult<Option<Self>> {
+        if let Some(min_version) = range.min_version {
+            self.starting_version = self.starting_version.max(min_version);
+        }
+        if let Some(max_version) = range.max_version {
+            self.ending_version = Some(
+                self.ending_version
+                    .map_or(max_version, |version| version.min(max_version)),
+            );
+        }
+        if range.min_version.is_some() || range.max_version.is_some() {
+            let latest_version = self.log_store.get_latest_version(0).await?;
+            let end = self
+                .ending_version
+                .map_or(latest_version, |version| version.min(latest_version));
+            if self.starting_version > end {
+                return Ok(None);
+            }
+            self.ending_version = Some(end);
+        }
+
+        if let Some(min_timestamp) = range
+            .min_timestamp
+            .and_then(DateTime::from_timestamp_millis)
+        {
+            self.starting_timestamp = Some(
+                self.starting_timestamp
+                    .map_or(min_timestamp, |timestamp| timestamp.max(min_timestamp)),
+            );
+        }
+        if let Some(max_timestamp) = range
+            .max_timestamp
+            .and_then(DateTime::from_timestamp_millis)
+        {
+            self.ending_timestamp = Some(
+                self.ending_timestamp
+                    .map_or(max_timestamp, |timestamp| timestamp.min(max_timestamp)),
+            );
+        }
+        if let (Some(start), Some(end)) = (self.starting_timestamp, self.ending_timestamp) {
+            if start > end {
+                return Ok(None);
+            }
+        }
+        Ok(Some(self))
+    }
+
+    /// Schema of the changes read by [`build`](Self::build): the data columns followed by the
+    /// change type, commit version and commit timestamp, and the partition columns
+    pub(crate) fn output_schema(&self) -> DeltaResult<SchemaRef> {
+        let partition_columns = &self.snapshot.metadata().partition_columns;
+        let schema = self.snapshot.arrow_schema()?;
+        let mut fields: Vec<Field> = schema
+            .all_fields()
+            .into_iter()
+            .filter(|f| !partition_columns.contains(f.name()))
+            .cloned()
+            .collect();
+        fields.extend(ADD_PARTITION_SCHEMA.iter().cloned());
+        for name in partition_columns {
+            fields.push(schema.field_with_name(name)?.to_owned());
+        }
+        Ok(Arc::new(Schema::new(fields)))
+    }
+
     #[inline]
     fn get_add_action_type() -> Option<ScalarValue> {
         Some(ScalarValue::Utf8(Some(String::from("insert"))))
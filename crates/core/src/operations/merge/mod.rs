@@ -33,6 +33,7 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
+use chrono::Utc;
 use datafusion::datasource::provider_as_source;
 use datafusion::error::Result as DataFusionResult;
 use datafusion::execution::context::{QueryPlanner, SessionConfig};
@@ -42,25 +43,30 @@ use datafusion::{
     execution::context::SessionState,
     physical_plan::{
         metrics::{MetricBuilder, MetricsSet},
-        ExecutionPlan,
+        ExecutionPlan, SendableRecordBatchStream,
     },
     prelude::{DataFrame, SessionContext},
 };
 use datafusion_common::tree_node::{Transformed, TreeNode};
 use datafusion_common::{Column, DFSchema, ScalarValue, TableReference};
 use datafusion_expr::expr::Placeholder;
-use datafusion_expr::{col, conditional_expressions::CaseBuilder, lit, when, Expr, JoinType};
 use datafusion_expr::{
-    BinaryExpr, Distinct, Extension, LogicalPlan, LogicalPlanBuilder, Operator, Projection,
-    UserDefinedLogicalNode, UNNAMED_TABLE,
+    cast, col, conditional_expressions::CaseBuilder, lit, max, min, when, Expr, JoinType,
+};
+use datafusion_expr::{
+    BinaryExpr, Extension, LogicalPlan, LogicalPlanBuilder, Operator, UserDefinedLogicalNode,
+    UNNAMED_TABLE,
 };
 use futures::future::BoxFuture;
 use itertools::Itertools;
 use parquet::file::properties::WriterProperties;
 use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{info_span, Instrument};
 
 use self::barrier::{MergeBarrier, MergeBarrierExec};
 
+use super::cancellation::{cancellable, UncommittedFiles};
 use super::datafusion_utils::{into_expr, maybe_into_expr, Expression};
 use super::transaction::{CommitProperties, PROTOCOL};
 use crate::delta_datafusion::expr::{fmt_expr_to_sql, parse_predicate_expression};
@@ -70,8 +76,9 @@ use crate::delta_datafusion::{
     execute_plan_to_batch, register_store, DeltaColumn, DeltaScanConfigBuilder, DeltaSessionConfig,
     DeltaTableProvider,
 };
-use crate::kernel::Action;
-use crate::logstore::LogStoreRef;
+use crate::kernel::{Action, Transaction};
+use crate::logstore::{operation_log_store, LogStoreRef};
+use crate::operations::cast::canonical_data_type;
 use crate::operations::merge::barrier::find_barrier_node;
 use crate::operations::transaction::CommitBuilder;
 use crate::operations::write::{write_execution_plan, WriterStatsConfig};
@@ -80,6 +87,7 @@ use crate::table::state::DeltaTableState;
 use crate::{DeltaResult, DeltaTable, DeltaTableError};
 
 mod barrier;
+pub(crate) mod stream;
 
 const SOURCE_COLUMN: &str = "__delta_rs_source";
 const TARGET_COLUMN: &str = "__delta_rs_target";
@@ -131,6 +139,10 @@ pub struct MergeBuilder {
     /// safe_cast determines how data types that do not match the underlying table are handled
     /// By default an error is returned
     safe_cast: bool,
+    /// Token to cancel the merge
+    cancellation_token: Option<CancellationToken>,
+    /// Whether the source can only be read once
+    streaming: bool,
 }
 
 impl super::Operation<()> for MergeBuilder {}
@@ -158,9 +170,25 @@ impl MergeBuilder {
             not_match_operations: Vec::new(),
             not_match_source_operations: Vec::new(),
             safe_cast: false,
+            cancellation_token: None,
+            streaming: false,
         }
     }
 
+    /// Create a new [`MergeBuilder`] reading the source from a stream of record batches
+    ///
+    /// The batches are pulled from the stream while the merge executes, instead of collecting
+    /// the source first. The builder is in streaming mode, see [`MergeBuilder::with_streaming`].
+    pub fn new_from_stream<E: Into<Expression>>(
+        log_store: LogStoreRef,
+        snapshot: DeltaTableState,
+        predicate: E,
+        source: SendableRecordBatchStream,
+    ) -> DeltaResult<Self> {
+        let source = stream::stream_to_dataframe(source)?;
+        Ok(Self::new(log_store, snapshot, predicate, source).with_streaming(true))
+    }
+
     /// Update a target record when it matches with a source record
     ///
     /// The update expressions can specify both source and target columns.
@@ -169,6 +197,7 @@ impl MergeBuilder {
     /// evaluated to determine if the corresponding operation are performed.
     /// Only the first clause that results in a satisfy predicate is executed.
     /// The order of match clauses matter.
+    /// Only the last of these clauses may omit its predicate.
     ///
     /// #Example
     /// ```rust ignore
@@ -197,7 +226,7 @@ impl MergeBuilder {
         let builder = builder(UpdateBuilder::default());
         let op =
             MergeOperationConfig::new(builder.predicate, builder.updates, OperationType::Update)?;
-        self.match_operations.push(op);
+        push_clause(&mut self.match_operations, op, "WHEN MATCHED")?;
         Ok(self)
     }
 
@@ -207,6 +236,7 @@ impl MergeBuilder {
     /// evaluated to determine if the corresponding operation are performed.
     /// Only the first clause that results in a satisfy predicate is executed.
     /// The order of match clauses matter.
+    /// Only the last of these clauses may omit its predicate.
     ///
     /// #Example
     /// ```rust ignore
@@ -230,7 +260,7 @@ impl MergeBuilder {
             HashMap::default(),
             OperationType::Delete,
         )?;
-        self.match_operations.push(op);
+        push_clause(&mut self.match_operations, op, "WHEN MATCHED")?;
         Ok(self)
     }
 
@@ -240,6 +270,7 @@ impl MergeBuilder {
     /// evaluated to determine if the corresponding operation are performed.
     /// Only the first clause that results in a satisfy predicate is executed.
     /// The order of not match clauses matter.
+    /// Only the last of these clauses may omit its predicate.
     ///
     /// #Example
     /// ```rust ignore
@@ -262,7 +293,7 @@ impl MergeBuilder {
     {
         let builder = builder(InsertBuilder::default());
         let op = MergeOperationConfig::new(builder.predicate, builder.set, OperationType::Insert)?;
-        self.not_match_operations.push(op);
+        push_clause(&mut self.not_match_operations, op, "WHEN NOT MATCHED")?;
         Ok(self)
     }
 
@@ -275,6 +306,7 @@ impl MergeBuilder {
     /// are evaluated to determine if the corresponding operation are performed.
     /// Only the first clause that results in a satisfy predicate is executed.
     /// The order of source not match clauses matter.
+    /// Only the last of these clauses may omit its predicate.
     ///
     /// #Example
     /// ```rust ignore
@@ -297,7 +329,11 @@ impl MergeBuilder {
         let builder = builder(UpdateBuilder::default());
         let op =
             MergeOperationConfig::new(builder.predicate, builder.updates, OperationType::Update)?;
-        self.not_match_source_operations.push(op);
+        push_clause(
+            &mut self.not_match_source_operations,
+            op,
+            "WHEN NOT MATCHED BY SOURCE",
+        )?;
         Ok(self)
     }
 
@@ -307,6 +343,7 @@ impl MergeBuilder {
     /// are evaluated to determine if the corresponding operations are performed.
     /// Only the first clause that results in a satisfy predicate is executed.
     /// The order of source "not match" clauses matter.
+    /// Only the last of these clauses may omit its predicate.
     ///
     /// #Example
     /// ```rust ignore
@@ -330,7 +367,11 @@ impl MergeBuilder {
             HashMap::default(),
             OperationType::Delete,
         )?;
-        self.not_match_source_operations.push(op);
+        push_clause(
+            &mut self.not_match_source_operations,
+            op,
+            "WHEN NOT MATCHED BY SOURCE",
+        )?;
         Ok(self)
     }
 
@@ -358,6 +399,19 @@ impl MergeBuilder {
         self
     }
 
+    /// Record an application transaction in the merge commit, e.g. to merge the batches of a
+    /// stream exactly once, see [`WriteBuilder::with_application_transaction`](crate::operations::write::WriteBuilder::with_application_transaction)
+    pub fn with_application_transaction(mut self, app_id: impl ToString, version: i64) -> Self {
+        self.commit_properties =
+            self.commit_properties
+                .with_application_transaction(Transaction::new_with_last_update(
+                    app_id,
+                    version,
+                    Some(Utc::now().timestamp_millis()),
+                ));
+        self
+    }
+
     /// Writer properties passed to parquet writer for when fiiles are rewritten
     pub fn with_writer_properties(mut self, writer_properties: WriterProperties) -> Self {
         self.writer_properties = Some(writer_properties);
@@ -377,6 +431,26 @@ impl MergeBuilder {
         self.safe_cast = safe_cast;
         self
     }
+
+    /// Abort the merge once `token` is cancelled.
+    ///
+    /// The merge then returns [`DeltaTableError::Cancelled`] without committing, and deletes
+    /// the data files it has written so far.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Read the source only once.
+    ///
+    /// By default the source is scanned before the merge to find the partitions and the range of
+    /// join keys it touches, which are used to skip target files. Sources that cannot be read
+    /// twice, e.g. a [`DataFrame`] over a stream, must enable streaming mode, which disables this
+    /// pruning. Filters of the join predicate only referencing the target are still applied.
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
 }
 
 #[derive(Default)]
@@ -468,6 +542,24 @@ struct MergeOperationConfig {
     r#type: OperationType,
 }
 
+/// Append a clause to the clauses of its kind.
+///
+/// Clauses are evaluated in order and only the first satisfied one is applied, so any clause
+/// following one without a predicate could never be reached.
+fn push_clause(
+    clauses: &mut Vec<MergeOperationConfig>,
+    clause: MergeOperationConfig,
+    kind: &str,
+) -> DeltaResult<()> {
+    if clauses.iter().any(|existing| existing.predicate.is_none()) {
+        return Err(DeltaTableError::Generic(format!(
+            "Only the last {kind} clause may omit its predicate"
+        )));
+    }
+    clauses.push(clause);
+    Ok(())
+}
+
 struct MergeOperation {
     /// Which records to update
     predicate: Option<Expr>,
@@ -685,10 +777,14 @@ impl ExtensionPlanner for MergeMetricExtensionPlanner {
 /// This leaves us with a predicate that we can push into delta scan after expanding it out to
 /// a conjunction between the distinct partitions in the source input.
 ///
-/// TODO: A further improvement here might be for non-partition columns to be replaced with min/max
-/// checks, so the above example could become:
+/// Equalities on non-partition columns are replaced with a range check against the minimum and
+/// maximum of the source values, so the above example actually becomes:
+///
+/// `$date = target.date and target.id between $id_min and $id_max and frob > 42`
 ///
-/// `$date = target.date and target.id between 12345 and 99999 and frob > 42`
+/// The placeholders of these ranges map to `min` and `max` aggregates of the source expression,
+/// which are computed for every distinct partition of the source. Files can then also be pruned
+/// using the statistics of the join keys.
 fn generalize_filter(
     predicate: Expr,
     partition_columns: &Vec<String>,
@@ -707,6 +803,26 @@ fn generalize_filter(
             matches!(self, ReferenceTableCheck::HasReference(_))
         }
     }
+    fn placeholder(id: String) -> Expr {
+        Expr::Placeholder(datafusion_expr::expr::Placeholder {
+            id,
+            data_type: None,
+        })
+    }
+    /// Check `target` against the range of values `source` takes in the source table
+    fn range_filter(
+        target: Expr,
+        source: Expr,
+        target_column: &str,
+        placeholders: &mut HashMap<String, Expr>,
+    ) -> Expr {
+        let index = placeholders.len();
+        let min_name = format!("{target_column}_{index}_min");
+        let max_name = format!("{target_column}_{index}_max");
+        placeholders.insert(min_name.clone(), min(source.clone()));
+        placeholders.insert(max_name.clone(), max(source));
+        target.between(placeholder(min_name), placeholder(max_name))
+    }
     fn references_table(expr: &Expr, table: &TableReference) -> ReferenceTableCheck {
         let res = match expr {
             Expr::Alias(alias) => references_table(&alias.expr, table),
@@ -761,6 +877,14 @@ fn generalize_filter(
 
                         return Some(replaced);
                     }
+                    if binary.op == Operator::Eq {
+                        return Some(range_filter(
+                            *binary.left,
+                            *binary.right,
+                            &left_target,
+                            placeholders,
+                        ));
+                    }
                 }
                 return None;
             }
@@ -785,6 +909,14 @@ fn generalize_filter(
 
                         return Some(replaced);
                     }
+                    if binary.op == Operator::Eq {
+                        return Some(range_filter(
+                            *binary.right,
+                            *binary.left,
+                            &right_target,
+                            placeholders,
+                        ));
+                    }
                 }
                 return None;
             }
@@ -865,6 +997,7 @@ async fn try_construct_early_filter(
     source: &LogicalPlan,
     source_name: &TableReference,
     target_name: &TableReference,
+    streaming: bool,
 ) -> DeltaResult<Option<Expr>> {
     let table_metadata = table_snapshot.metadata();
     let partition_columns = &table_metadata.partition_columns;
@@ -881,24 +1014,27 @@ async fn try_construct_early_filter(
         None => Ok(None),
         Some(filter) => {
             if placeholders.is_empty() {
-                // if we haven't recognised any partition-based predicates in the join predicate, return our reduced filter
+                // if we haven't recognised any source references in the join predicate, return our reduced filter
                 Ok(Some(filter))
+            } else if streaming {
+                // the source can only be read once, by the merge itself
+                Ok(None)
             } else {
-                // if we have some recognised partitions, then discover the distinct set of partitions in the source data and
-                // make a new filter, which expands out the placeholders for each distinct partition (and then OR these together)
-                let distinct_partitions = LogicalPlan::Distinct(Distinct::All(
-                    LogicalPlan::Projection(Projection::try_new(
-                        placeholders
+                // otherwise discover the distinct set of partitions in the source data, along with the range of the
+                // join keys within each partition, and make a new filter which expands out the placeholders for each
+                // distinct partition (and then OR these together)
+                let (aggregates, groups): (Vec<_>, Vec<_>) = placeholders
+                    .into_iter()
+                    .partition(|(_, expr)| matches!(expr, Expr::AggregateFunction(_)));
+                let source_values = LogicalPlanBuilder::from(source.clone())
+                    .aggregate(
+                        groups.into_iter().map(|(alias, expr)| expr.alias(alias)),
+                        aggregates
                             .into_iter()
-                            .map(|(alias, expr)| expr.alias(alias))
-                            .collect_vec(),
-                        source.clone().into(),
-                    )?)
-                    .into(),
-                ));
-                let execution_plan = session_state
-                    .create_physical_plan(&distinct_partitions)
-                    .await?;
+                            .map(|(alias, expr)| expr.alias(alias)),
+                    )?
+                    .build()?;
+                let execution_plan = session_state.create_physical_plan(&source_values).await?;
                 let items = execute_plan_to_batch(session_state, execution_plan).await?;
                 let placeholder_names = items
                     .schema()
@@ -927,6 +1063,32 @@ async fn try_construct_early_filter(
     }
 }
 
+/// Cast the large, view and unsigned type columns of the source to the types written for their
+/// delta type, so they are matched and written like the columns of the target
+fn cast_to_canonical_types(source: DataFrame) -> DeltaResult<DataFrame> {
+    let schema = source.schema().clone();
+    if schema
+        .fields()
+        .iter()
+        .all(|f| &canonical_data_type(f.data_type()) == f.data_type())
+    {
+        return Ok(source);
+    }
+    let columns = schema
+        .iter()
+        .map(|(qualifier, field)| {
+            let column = Expr::Column(Column::from((qualifier, field.as_ref())));
+            let data_type = canonical_data_type(field.data_type());
+            if &data_type == field.data_type() {
+                column
+            } else {
+                cast(column, data_type).alias(field.name())
+            }
+        })
+        .collect_vec();
+    Ok(source.select(columns)?)
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn execute(
     predicate: Expression,
@@ -942,6 +1104,7 @@ async fn execute(
     match_operations: Vec<MergeOperationConfig>,
     not_match_target_operations: Vec<MergeOperationConfig>,
     not_match_source_operations: Vec<MergeOperationConfig>,
+    streaming: bool,
 ) -> DeltaResult<(DeltaTableState, MergeMetrics)> {
     let mut metrics = MergeMetrics::default();
     let exec_start = Instant::now();
@@ -968,6 +1131,8 @@ async fn execute(
         None => TableReference::bare(UNNAMED_TABLE),
     };
 
+    let source = cast_to_canonical_types(source)?;
+
     // This is only done to provide the source columns with a correct table reference. Just renaming the columns does not work
     let source = LogicalPlanBuilder::scan(
         source_name.clone(),
@@ -1022,6 +1187,7 @@ async fn execute(
             &source,
             &source_name,
             &target_name,
+            streaming,
         )
         .await?
     };
@@ -1396,6 +1562,8 @@ async fn execute(
     metrics.rewrite_time_ms = Instant::now().duration_since(rewrite_start).as_millis() as u64;
 
     let mut actions: Vec<Action> = add_actions.clone();
+    let mut uncommitted = UncommittedFiles::new(log_store.object_store());
+    uncommitted.track(&actions);
     metrics.num_target_files_added = actions.len();
 
     let survivors = barrier
@@ -1433,7 +1601,7 @@ async fn execute(
 
     let app_metadata = &mut commit_properties.app_metadata;
     app_metadata.insert("readVersion".to_owned(), snapshot.version().into());
-    if let Ok(map) = serde_json::to_value(&metrics) {
+    if let Ok(map) = super::operation_metrics(&metrics) {
         app_metadata.insert("operationMetrics".to_owned(), map);
     }
 
@@ -1462,6 +1630,7 @@ async fn execute(
         return Ok((snapshot, metrics));
     }
 
+    uncommitted.committed();
     let commit = CommitBuilder::from(commit_properties)
         .with_actions(actions)
         .build(Some(&snapshot), log_store.clone(), operation)
@@ -1510,16 +1679,26 @@ impl std::future::IntoFuture for MergeBuilder {
 
     fn into_future(self) -> Self::IntoFuture {
         let this = self;
+        let cancellation_token = this.cancellation_token.clone();
+        let span = info_span!(
+            "delta.merge",
+            table_uri = %this.log_store.root_uri(),
+            version = this.snapshot.version(),
+            predicate = %this.predicate,
+            streaming = this.streaming,
+        );
 
-        Box::pin(async move {
+        let future = cancellable(cancellation_token, async move {
             PROTOCOL.can_write_to(&this.snapshot.snapshot)?;
+            this.snapshot.ensure_all_files("MERGE")?;
+            let log_store = operation_log_store(&this.log_store);
 
             let state = this.state.unwrap_or_else(|| {
                 let config: SessionConfig = DeltaSessionConfig::default().into();
                 let session = SessionContext::new_with_config(config);
 
                 // If a user provides their own their DF state then they must register the store themselves
-                register_store(this.log_store.clone(), session.runtime_env());
+                register_store(log_store.clone(), session.runtime_env());
 
                 session.state()
             });
@@ -1527,7 +1706,7 @@ impl std::future::IntoFuture for MergeBuilder {
             let (snapshot, metrics) = execute(
                 this.predicate,
                 this.source,
-                this.log_store.clone(),
+                log_store,
                 this.snapshot,
                 state,
                 this.writer_properties,
@@ -1538,6 +1717,7 @@ impl std::future::IntoFuture for MergeBuilder {
                 this.match_operations,
                 this.not_match_operations,
                 this.not_match_source_operations,
+                this.streaming,
             )
             .await?;
 
@@ -1545,7 +1725,9 @@ impl std::future::IntoFuture for MergeBuilder {
                 DeltaTable::new_with_state(this.log_store, snapshot),
                 metrics,
             ))
-        })
+        });
+
+        Box::pin(future.instrument(span))
     }
 }
 
@@ -1556,7 +1738,7 @@ mod tests {
     use crate::kernel::StructField;
     use crate::operations::merge::generalize_filter;
     use crate::operations::merge::try_construct_early_filter;
-    use crate::operations::DeltaOps;
+    use crate::operations::{operation_metrics, DeltaOps};
     use crate::protocol::*;
     use crate::writer::test_utils::datafusion::get_data;
     use crate::writer::test_utils::get_arrow_schema;
@@ -1570,6 +1752,7 @@ mod tests {
     use arrow_schema::Field;
     use datafusion::assert_batches_sorted_eq;
     use datafusion::datasource::provider_as_source;
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
     use datafusion::prelude::DataFrame;
     use datafusion::prelude::SessionContext;
     use datafusion_common::Column;
@@ -1578,6 +1761,8 @@ mod tests {
     use datafusion_expr::col;
     use datafusion_expr::expr::Placeholder;
     use datafusion_expr::lit;
+    use datafusion_expr::max;
+    use datafusion_expr::min;
     use datafusion_expr::Expr;
     use datafusion_expr::LogicalPlanBuilder;
     use datafusion_expr::Operator;
@@ -1751,6 +1936,61 @@ mod tests {
         assert_merge(table, metrics).await;
     }
 
+    #[tokio::test]
+    async fn test_merge_stream() {
+        let schema = get_arrow_schema(&None);
+        let table = setup_table(Some(vec!["modified"])).await;
+        let table = write_data(table, &schema).await;
+
+        let batches = merge_source(schema.clone()).collect().await.unwrap();
+        let source = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(batches.into_iter().map(Ok)),
+        ));
+
+        // the range of the join keys would be computed by scanning the source upfront if it was
+        // not streamed
+        let (table, metrics) = DeltaOps(table)
+            .merge_stream(source, col("target.id").eq(col("source.id")))
+            .unwrap()
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_matched_update(|update| update.update("value", col("source.value")))
+            .unwrap()
+            .when_not_matched_insert(|insert| {
+                insert
+                    .set("id", col("source.id"))
+                    .set("value", col("source.value"))
+                    .set("modified", col("source.modified"))
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(table.version(), 2);
+        assert_eq!(metrics.num_source_rows, 3);
+        assert_eq!(metrics.num_target_rows_updated, 2);
+        assert_eq!(metrics.num_target_rows_inserted, 1);
+
+        let commit_info = table.history(None).await.unwrap();
+        let parameters = commit_info[0].operation_parameters.clone().unwrap();
+        assert!(!parameters.contains_key("predicate"));
+
+        let expected = vec![
+            "+----+-------+------------+",
+            "| id | value | modified   |",
+            "+----+-------+------------+",
+            "| A  | 1     | 2021-02-01 |",
+            "| B  | 10    | 2021-02-01 |",
+            "| C  | 20    | 2021-02-02 |",
+            "| D  | 100   | 2021-02-02 |",
+            "| X  | 30    | 2023-07-04 |",
+            "+----+-------+------------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
     #[tokio::test]
     async fn test_merge_str() {
         // Validate that users can use string predicates
@@ -1917,6 +2157,79 @@ mod tests {
         assert!(res.is_err())
     }
 
+    #[tokio::test]
+    async fn test_merge_multiple_when_matched() {
+        let (table, source) = setup().await;
+
+        let (table, metrics) = DeltaOps(table)
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_matched_update(|update| {
+                update
+                    .predicate(col("source.value").gt(lit(15)))
+                    .update("value", col("source.value"))
+                    .update("modified", col("source.modified"))
+            })
+            .unwrap()
+            // also satisfied by `C`, which is already handled by the first clause
+            .when_matched_delete(|delete| delete.predicate(col("source.value").gt(lit(5))))
+            .unwrap()
+            .when_matched_update(|update| update.update("value", lit(0)))
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(metrics.num_target_rows_updated, 1);
+        assert_eq!(metrics.num_target_rows_deleted, 1);
+        assert_eq!(metrics.num_target_rows_inserted, 0);
+
+        let commit_info = table.history(None).await.unwrap();
+        let parameters = commit_info[0].operation_parameters.clone().unwrap();
+        assert_eq!(
+            parameters["matchedPredicates"],
+            json!(
+                r#"[{"actionType":"update","predicate":"source.value > 15"},{"actionType":"delete","predicate":"source.value > 5"},{"actionType":"update"}]"#
+            )
+        );
+
+        let expected = vec![
+            "+----+-------+------------+",
+            "| id | value | modified   |",
+            "+----+-------+------------+",
+            "| A  | 1     | 2021-02-01 |",
+            "| C  | 20    | 2023-07-04 |",
+            "| D  | 100   | 2021-02-02 |",
+            "+----+-------+------------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
+    #[tokio::test]
+    async fn test_merge_unconditional_clause_must_be_last() {
+        let (table, source) = setup().await;
+        let res = DeltaOps(table)
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_matched_update(|update| update.update("value", lit(0)))
+            .unwrap()
+            .when_matched_delete(|delete| delete.predicate(col("source.value").gt(lit(5))));
+        assert!(res.is_err());
+
+        // the restriction only applies to clauses of the same kind
+        let (table, source) = setup().await;
+        let res = DeltaOps(table)
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_matched_delete(|delete| delete)
+            .unwrap()
+            .when_not_matched_by_source_delete(|delete| delete);
+        assert!(res.is_ok());
+    }
+
     #[tokio::test]
     async fn test_merge_partitions() {
         /* Validate the join predicate works with table partitions */
@@ -2064,7 +2377,10 @@ mod tests {
         let commit_info = table.history(None).await.unwrap();
         let last_commit = &commit_info[0];
         let parameters = last_commit.operation_parameters.clone().unwrap();
-        assert_eq!(parameters["predicate"], "modified = '2021-02-02'");
+        assert_eq!(
+            parameters["predicate"],
+            "id BETWEEN 'B' AND 'C' AND modified = '2021-02-02'"
+        );
         assert_eq!(
             parameters["mergePredicate"],
             "target.id = source.id AND target.modified = '2021-02-02'"
@@ -2150,6 +2466,100 @@ mod tests {
         assert_batches_sorted_eq!(&expected, &actual);
     }
 
+    #[tokio::test]
+    async fn test_merge_join_keys_skipping() {
+        /* Validate the range of the join keys can be used for skipping files */
+        let schema = get_arrow_schema(&None);
+        let table = setup_table(None).await;
+
+        let table = write_data(table, &schema).await;
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["X", "Y"])),
+                Arc::new(arrow::array::Int32Array::from(vec![1, 2])),
+                Arc::new(arrow::array::StringArray::from(vec![
+                    "2023-07-04",
+                    "2023-07-04",
+                ])),
+            ],
+        )
+        .unwrap();
+        let table = DeltaOps(table)
+            .write(vec![batch])
+            .with_save_mode(SaveMode::Append)
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 2);
+        assert_eq!(table.get_files_count(), 2);
+
+        let ctx = SessionContext::new();
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["Y", "Z"])),
+                Arc::new(arrow::array::Int32Array::from(vec![999, 999])),
+                Arc::new(arrow::array::StringArray::from(vec![
+                    "2023-07-05",
+                    "2023-07-05",
+                ])),
+            ],
+        )
+        .unwrap();
+        let source = ctx.read_batch(batch).unwrap();
+
+        let (table, metrics) = DeltaOps(table)
+            .merge(source, col("target.id").eq(col("source.id")))
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_matched_update(|update| {
+                update
+                    .update("value", col("source.value"))
+                    .update("modified", col("source.modified"))
+            })
+            .unwrap()
+            .when_not_matched_insert(|insert| {
+                insert
+                    .set("id", col("source.id"))
+                    .set("value", col("source.value"))
+                    .set("modified", col("source.modified"))
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(table.version(), 3);
+        assert_eq!(metrics.num_target_files_added, 1);
+        assert_eq!(metrics.num_target_files_removed, 1);
+        assert_eq!(metrics.num_target_rows_copied, 1);
+        assert_eq!(metrics.num_target_rows_updated, 1);
+        assert_eq!(metrics.num_target_rows_inserted, 1);
+        assert_eq!(metrics.num_target_rows_deleted, 0);
+        assert_eq!(metrics.num_output_rows, 3);
+        assert_eq!(metrics.num_source_rows, 2);
+
+        let commit_info = table.history(None).await.unwrap();
+        let last_commit = &commit_info[0];
+        let parameters = last_commit.operation_parameters.clone().unwrap();
+        assert_eq!(parameters["predicate"], json!("id BETWEEN 'Y' AND 'Z'"));
+
+        let expected = vec![
+            "+----+-------+------------+",
+            "| id | value | modified   |",
+            "+----+-------+------------+",
+            "| A  | 1     | 2021-02-01 |",
+            "| B  | 10    | 2021-02-01 |",
+            "| C  | 10    | 2021-02-02 |",
+            "| D  | 100   | 2021-02-02 |",
+            "| X  | 1     | 2023-07-04 |",
+            "| Y  | 999   | 2023-07-05 |",
+            "| Z  | 999   | 2023-07-05 |",
+            "+----+-------+------------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
     #[tokio::test]
     async fn test_merge_delete_matched() {
         // Validate behaviours of match delete
@@ -2203,9 +2613,13 @@ mod tests {
         let extra_info = last_commit.info.clone();
         assert_eq!(
             extra_info["operationMetrics"],
-            serde_json::to_value(&metrics).unwrap()
+            operation_metrics(&metrics).unwrap()
         );
-        assert!(!parameters.contains_key("predicate"));
+        assert_eq!(
+            extra_info["operationMetrics"]["numTargetRowsDeleted"],
+            json!(2)
+        );
+        assert_eq!(parameters["predicate"], json!("id BETWEEN 'B' AND 'X'"));
         assert_eq!(parameters["mergePredicate"], json!("target.id = source.id"));
         assert_eq!(
             parameters["matchedPredicates"],
@@ -2487,7 +2901,10 @@ mod tests {
         let last_commit = &commit_info[0];
         let parameters = last_commit.operation_parameters.clone().unwrap();
 
-        assert_eq!(parameters["predicate"], json!("modified = '2021-02-02'"));
+        assert_eq!(
+            parameters["predicate"],
+            json!("id BETWEEN 'B' AND 'X' AND modified = '2021-02-02'")
+        );
 
         let expected = vec![
             "+----+-------+------------+",
@@ -2747,9 +3164,52 @@ mod tests {
         )
         .unwrap();
 
+        // target.id between id_0_min and id_0_max and target.id = 'C'
+        let expected_filter = col(Column::new(target.clone().into(), "id"))
+            .between(
+                Expr::Placeholder(Placeholder {
+                    id: "id_0_min".to_owned(),
+                    data_type: None,
+                }),
+                Expr::Placeholder(Placeholder {
+                    id: "id_0_max".to_owned(),
+                    data_type: None,
+                }),
+            )
+            .and(col(Column::new(target.clone().into(), "id")).eq(lit("C")));
+
+        assert_eq!(generalized, expected_filter);
+
+        let source_id = col(Column::new(source.clone().into(), "id"));
+        assert_eq!(placeholders.len(), 2);
+        assert_eq!(placeholders["id_0_min"], min(source_id.clone()));
+        assert_eq!(placeholders["id_0_max"], max(source_id));
+    }
+
+    #[tokio::test]
+    async fn test_generalize_filter_ignores_non_equality_join_keys() {
+        let source = TableReference::parse_str("source");
+        let target = TableReference::parse_str("target");
+
+        let parsed_filter = col(Column::new(source.clone().into(), "id"))
+            .lt(col(Column::new(target.clone().into(), "id")))
+            .and(col(Column::new(target.clone().into(), "id")).eq(lit("C")));
+
+        let mut placeholders = HashMap::default();
+
+        let generalized = generalize_filter(
+            parsed_filter,
+            &vec!["other".to_owned()],
+            &source,
+            &target,
+            &mut placeholders,
+        )
+        .unwrap();
+
         let expected_filter = col(Column::new(target.clone().into(), "id")).eq(lit("C"));
 
         assert_eq!(generalized, expected_filter);
+        assert!(placeholders.is_empty());
     }
 
     #[tokio::test]
@@ -2833,6 +3293,7 @@ mod tests {
             &source,
             &source_name,
             &target_name,
+            false,
         )
         .await
         .unwrap();
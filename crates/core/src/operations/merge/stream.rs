@@ -0,0 +1,97 @@
+//! Merge sources backed by a stream of record batches
+//!
+//! A [`SendableRecordBatchStream`] is exposed to DataFusion as a streaming table with a single
+//! partition. The batches are pulled by the merge plan as it executes, so the source is never
+//! collected upfront. The same is used to stream the batches inserted through SQL into a write. The stream can only be read once: executing the table a second time
+//! fails instead of silently returning no rows.
+
+use std::fmt::{self, Debug};
+use std::sync::{Arc, Mutex};
+
+use arrow_schema::SchemaRef;
+use datafusion::datasource::streaming::StreamingTable;
+use datafusion::execution::TaskContext;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use datafusion::prelude::{DataFrame, SessionContext};
+use datafusion_common::{DataFusionError, Result as DataFusionResult};
+
+/// Partition yielding the batches of a stream, which can be executed once
+struct OnceStream {
+    schema: SchemaRef,
+    stream: Mutex<Option<SendableRecordBatchStream>>,
+}
+
+impl Debug for OnceStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnceStream")
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+impl PartitionStream for OnceStream {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        match self.stream.lock().unwrap().take() {
+            Some(stream) => stream,
+            None => Box::pin(RecordBatchStreamAdapter::new(
+                self.schema.clone(),
+                futures::stream::once(async {
+                    Err(DataFusionError::Execution(
+                        "The source stream has already been consumed".to_string(),
+                    ))
+                }),
+            )),
+        }
+    }
+}
+
+/// Wrap `stream` into a [`DataFrame`] that reads the stream when it is executed
+pub(crate) fn stream_to_dataframe(
+    stream: SendableRecordBatchStream,
+) -> DataFusionResult<DataFrame> {
+    let schema = stream.schema();
+    let partition = Arc::new(OnceStream {
+        schema: schema.clone(),
+        stream: Mutex::new(Some(stream)),
+    });
+    let table = StreamingTable::try_new(schema, vec![partition])?;
+    SessionContext::new().read_table(Arc::new(table))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Int32Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::physical_plan::common::collect;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stream_read_once() {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )
+        .unwrap();
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            schema.clone(),
+            futures::stream::iter(vec![Ok(batch.clone())]),
+        ));
+
+        let partition = OnceStream {
+            schema,
+            stream: Mutex::new(Some(stream)),
+        };
+        let ctx = SessionContext::new();
+        let batches = collect(partition.execute(ctx.task_ctx())).await.unwrap();
+        assert_eq!(batches, vec![batch]);
+        assert!(collect(partition.execute(ctx.task_ctx())).await.is_err());
+    }
+}
@@ -6,8 +6,17 @@
 //! the operations' behaviors and will return an updated table potentially in conjunction
 //! with a [data stream][datafusion::physical_plan::SendableRecordBatchStream],
 //! if the operation returns data as well.
+//!
+//! # Tracing
+//!
+//! Operations run within an `info` level [`tracing`] span named after the operation, e.g.
+//! `delta.merge`, which records the table uri, the version read and the main parameters of the
+//! operation. Commits run within a `delta.commit` span recording the committed version, with
+//! `debug` level spans for every commit attempt and post commit hook.
 
+use self::audit_files::AuditFilesBuilder;
 use self::create::CreateBuilder;
+use self::drop_constraints::DropConstraintBuilder;
 use self::filesystem_check::FileSystemCheckBuilder;
 use self::vacuum::VacuumBuilder;
 use crate::errors::{DeltaResult, DeltaTableError};
@@ -15,21 +24,25 @@ use crate::table::builder::DeltaTableBuilder;
 use crate::DeltaTable;
 use std::collections::HashMap;
 
+pub mod audit_files;
+pub(crate) mod cancellation;
 pub mod cast;
 pub mod convert_to_delta;
 pub mod create;
 pub mod drop_constraints;
 pub mod filesystem_check;
 pub mod optimize;
+pub mod progress;
 pub mod restore;
+pub mod table_features;
 pub mod transaction;
 pub mod vacuum;
 
 #[cfg(feature = "datafusion")]
 use self::{
     constraints::ConstraintBuilder, datafusion_utils::Expression, delete::DeleteBuilder,
-    drop_constraints::DropConstraintBuilder, load::LoadBuilder, load_cdf::CdfLoadBuilder,
-    merge::MergeBuilder, update::UpdateBuilder, write::WriteBuilder,
+    load::LoadBuilder, load_cdf::CdfLoadBuilder, merge::MergeBuilder, sql::SqlBuilder,
+    update::UpdateBuilder, write::WriteBuilder,
 };
 #[cfg(feature = "datafusion")]
 pub use ::datafusion::physical_plan::common::collect as collect_sendable_stream;
@@ -38,6 +51,8 @@ use arrow::record_batch::RecordBatch;
 use optimize::OptimizeBuilder;
 use restore::RestoreBuilder;
 use set_tbl_properties::SetTablePropertiesBuilder;
+use table_features::{AddTableFeatureBuilder, DropTableFeatureBuilder, TableFeature};
+pub use tokio_util::sync::CancellationToken;
 
 #[cfg(all(feature = "cdf", feature = "datafusion"))]
 mod cdc;
@@ -45,6 +60,10 @@ mod cdc;
 pub mod constraints;
 #[cfg(feature = "datafusion")]
 pub mod delete;
+#[cfg(not(feature = "datafusion"))]
+pub mod delete_partitions;
+#[cfg(not(feature = "datafusion"))]
+pub use delete_partitions as delete;
 #[cfg(feature = "datafusion")]
 mod load;
 #[cfg(feature = "datafusion")]
@@ -53,7 +72,13 @@ pub mod load_cdf;
 pub mod merge;
 pub mod set_tbl_properties;
 #[cfg(feature = "datafusion")]
+pub mod sql;
+#[cfg(feature = "datafusion")]
 pub mod update;
+#[cfg(not(feature = "datafusion"))]
+pub mod update_partitions;
+#[cfg(not(feature = "datafusion"))]
+pub use update_partitions as update;
 #[cfg(feature = "datafusion")]
 pub mod write;
 pub mod writer;
@@ -168,6 +193,12 @@ impl DeltaOps {
         FileSystemCheckBuilder::new(self.0.log_store, self.0.state.unwrap())
     }
 
+    /// Find files in the table directory that are not referenced by the table
+    #[must_use]
+    pub fn audit_files(self) -> AuditFilesBuilder {
+        AuditFilesBuilder::new(self.0.log_store, self.0.state.unwrap())
+    }
+
     /// Audit active files with files present on the filesystem
     #[must_use]
     pub fn optimize<'a>(self) -> OptimizeBuilder<'a> {
@@ -181,6 +212,14 @@ impl DeltaOps {
         DeleteBuilder::new(self.0.log_store, self.0.state.unwrap())
     }
 
+    /// Delete partitions from Delta table, only predicates on partition columns are supported
+    /// without the `datafusion` feature
+    #[cfg(not(feature = "datafusion"))]
+    #[must_use]
+    pub fn delete(self) -> delete::DeleteBuilder {
+        delete::DeleteBuilder::new(self.0.log_store, self.0.state.unwrap())
+    }
+
     /// Update data from Delta table
     #[cfg(feature = "datafusion")]
     #[must_use]
@@ -188,6 +227,14 @@ impl DeltaOps {
         UpdateBuilder::new(self.0.log_store, self.0.state.unwrap())
     }
 
+    /// Update partition values of a Delta table, only partition columns can be updated to
+    /// literal values without the `datafusion` feature
+    #[cfg(not(feature = "datafusion"))]
+    #[must_use]
+    pub fn update(self) -> update::UpdateBuilder {
+        update::UpdateBuilder::new(self.0.log_store, self.0.state.unwrap())
+    }
+
     /// Restore delta table to a specified version or datetime
     #[must_use]
     pub fn restore(self) -> RestoreBuilder {
@@ -210,6 +257,23 @@ impl DeltaOps {
         )
     }
 
+    /// Merge a stream of record batches into the Delta table
+    ///
+    /// The stream is read once while the merge executes, see [`MergeBuilder::new_from_stream`].
+    #[cfg(feature = "datafusion")]
+    pub fn merge_stream<E: Into<Expression>>(
+        self,
+        source: datafusion::physical_plan::SendableRecordBatchStream,
+        predicate: E,
+    ) -> DeltaResult<MergeBuilder> {
+        MergeBuilder::new_from_stream(
+            self.0.log_store,
+            self.0.state.unwrap(),
+            predicate.into(),
+            source,
+        )
+    }
+
     /// Add a check constraint to a table
     #[cfg(feature = "datafusion")]
     #[must_use]
@@ -218,16 +282,35 @@ impl DeltaOps {
     }
 
     /// Drops constraints from a table
-    #[cfg(feature = "datafusion")]
     #[must_use]
     pub fn drop_constraints(self) -> DropConstraintBuilder {
         DropConstraintBuilder::new(self.0.log_store, self.0.state.unwrap())
     }
 
+    /// Execute a Delta flavored SQL statement, see [`sql`](crate::operations::sql) for the
+    /// supported statements
+    #[cfg(feature = "datafusion")]
+    #[must_use]
+    pub fn sql(self, sql: impl Into<String>) -> SqlBuilder {
+        SqlBuilder::new(self.0.log_store, self.0.state.unwrap(), sql)
+    }
+
     /// Set table properties
     pub fn set_tbl_properties(self) -> SetTablePropertiesBuilder {
         SetTablePropertiesBuilder::new(self.0.log_store, self.0.state.unwrap())
     }
+
+    /// Add a table feature to a table
+    #[must_use]
+    pub fn add_feature(self, feature: TableFeature) -> AddTableFeatureBuilder {
+        AddTableFeatureBuilder::new(self.0.log_store, self.0.state.unwrap()).with_feature(feature)
+    }
+
+    /// Drop a table feature from a table
+    #[must_use]
+    pub fn drop_feature(self, feature: TableFeature) -> DropTableFeatureBuilder {
+        DropTableFeatureBuilder::new(self.0.log_store, self.0.state.unwrap()).with_feature(feature)
+    }
 }
 
 impl From<DeltaTable> for DeltaOps {
@@ -264,7 +347,10 @@ pub fn get_num_idx_cols_and_stats_columns(
                 .unwrap_or(crate::table::config::DEFAULT_NUM_INDEX_COLS),
             configuration
                 .get("delta.dataSkippingStatsColumns")
-                .and_then(|v| v.as_ref().map(|v| v.split(',').collect::<Vec<&str>>())),
+                .and_then(|v| {
+                    v.as_ref()
+                        .map(|v| v.split(',').map(|c| c.trim()).collect::<Vec<&str>>())
+                }),
         ),
     };
     (
@@ -275,6 +361,74 @@ pub fn get_num_idx_cols_and_stats_columns(
     )
 }
 
+/// Metrics of an operation as recorded in the `operationMetrics` of the commit info.
+///
+/// Metrics are named like the ones written by Spark, i.e. `num_copied_rows` is recorded as
+/// `numCopiedRows`, so the history of a table is comparable across engines. Unknown metrics are
+/// omitted.
+pub(crate) fn operation_metrics(metrics: &impl serde::Serialize) -> DeltaResult<serde_json::Value> {
+    let serde_json::Value::Object(metrics) = serde_json::to_value(metrics)? else {
+        return Err(DeltaTableError::Generic(
+            "Operation metrics must serialize to an object".to_string(),
+        ));
+    };
+    Ok(metrics
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(name, value)| {
+            let mut parts = name.split('_');
+            let first = parts.next().unwrap_or_default().to_string();
+            let name = parts.fold(first, |mut name, part| {
+                let mut chars = part.chars();
+                if let Some(c) = chars.next() {
+                    name.extend(c.to_uppercase());
+                    name.push_str(chars.as_str());
+                }
+                name
+            });
+            (name, value)
+        })
+        .collect())
+}
+
+#[derive(Default, Debug, serde::Serialize)]
+/// Metrics for the Delete Operation
+pub struct DeleteMetrics {
+    /// Number of files added
+    pub num_added_files: usize,
+    /// Number of files removed
+    pub num_removed_files: usize,
+    /// Number of rows removed
+    pub num_deleted_rows: Option<usize>,
+    /// Number of rows copied in the process of deleting files
+    pub num_copied_rows: Option<usize>,
+    /// Time taken to execute the entire operation
+    pub execution_time_ms: u64,
+    /// Time taken to scan the file for matches
+    pub scan_time_ms: u64,
+    /// Time taken to rewrite the matched files
+    pub rewrite_time_ms: u64,
+}
+
+#[derive(Default, serde::Serialize, Debug)]
+/// Metrics collected during the Update operation
+pub struct UpdateMetrics {
+    /// Number of files added.
+    pub num_added_files: usize,
+    /// Number of files removed.
+    pub num_removed_files: usize,
+    /// Number of rows updated.
+    pub num_updated_rows: usize,
+    /// Number of rows just copied over in the process of updating files.
+    pub num_copied_rows: usize,
+    /// Time taken to execute the entire operation.
+    pub execution_time_ms: u64,
+    /// Time taken to scan the files for matches.
+    pub scan_time_ms: u64,
+    /// Time taken to rewrite the matched files.
+    pub rewrite_time_ms: u64,
+}
+
 #[cfg(feature = "datafusion")]
 mod datafusion_utils {
     use datafusion::execution::context::SessionState;
@@ -291,6 +445,15 @@ mod datafusion_utils {
         String(String),
     }
 
+    impl std::fmt::Display for Expression {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Expression::DataFusion(expr) => write!(f, "{expr}"),
+                Expression::String(expr) => f.write_str(expr),
+            }
+        }
+    }
+
     impl From<Expr> for Expression {
         fn from(val: Expr) -> Self {
             Expression::DataFusion(val)
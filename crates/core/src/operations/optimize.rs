@@ -12,6 +12,9 @@
 //! optimized files. Optimize does not delete files from storage. To delete
 //! files that were removed, call `vacuum` on [`DeltaTable`].
 //!
+//! Compaction can also drop duplicate rows within each partition, see
+//! [`OptimizeType::Deduplicate`]. Unlike plain compaction this changes the data of the table.
+//!
 //! See [`OptimizeBuilder`] for configuration.
 //!
 //! # Example
@@ -20,8 +23,9 @@
 //! let (table, metrics) = OptimizeBuilder::new(table.object_store(), table.state).await?;
 //! ````
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
@@ -39,13 +43,16 @@ use parquet::basic::{Compression, ZstdLevel};
 use parquet::errors::ParquetError;
 use parquet::file::properties::WriterProperties;
 use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
-use tracing::debug;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info_span, Instrument};
 
+use super::cancellation::{cancellable, AbortOnDrop, UncommittedFiles};
+use super::progress::{report, ProgressReporterRef, ProgressStage};
 use super::transaction::PROTOCOL;
 use super::writer::{PartitionWriter, PartitionWriterConfig};
 use crate::errors::{DeltaResult, DeltaTableError};
-use crate::kernel::{scalars::ScalarExt, Action, PartitionsExt, Remove};
-use crate::logstore::LogStoreRef;
+use crate::kernel::{scalars::ScalarExt, Action, Add, PartitionsExt, Remove};
+use crate::logstore::{operation_log_store, LogStoreRef};
 use crate::operations::transaction::{CommitBuilder, CommitProperties, DEFAULT_RETRIES};
 use crate::protocol::DeltaOperation;
 use crate::storage::ObjectStoreRef;
@@ -83,6 +90,9 @@ pub struct Metrics {
     pub total_files_skipped: usize,
     /// The order of records from source files is preserved
     pub preserve_insertion_order: bool,
+    /// Number of duplicate rows dropped, only reported when deduplicating
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_duplicates_removed: Option<u64>,
 }
 
 // Custom serialization function that serializes metric details as a string
@@ -149,6 +159,8 @@ pub struct PartialMetrics {
     pub files_removed: MetricDetails,
     /// The number of batches written
     pub num_batches: u64,
+    /// Number of duplicate rows dropped, only set when deduplicating
+    pub num_duplicates_removed: Option<u64>,
 }
 
 impl Metrics {
@@ -159,6 +171,9 @@ impl Metrics {
         self.files_added.add(&partial.files_added);
         self.files_removed.add(&partial.files_removed);
         self.num_batches += partial.num_batches;
+        if let Some(removed) = partial.num_duplicates_removed {
+            *self.num_duplicates_removed.get_or_insert(0) += removed;
+        }
     }
 }
 
@@ -181,6 +196,27 @@ pub enum OptimizeType {
     Compact,
     /// Z-order files based on provided columns
     ZOrder(Vec<String>),
+    /// Compact all files of each partition while dropping duplicate rows.
+    ///
+    /// Since rows are removed from the table, the rewrite is committed as a data change and
+    /// recorded as a delete rather than an optimize, which readers may skip.
+    Deduplicate(Deduplication),
+}
+
+/// Which rows are considered duplicates when optimizing with [OptimizeType::Deduplicate].
+///
+/// Rows are only compared to other rows within the same partition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Deduplication {
+    /// Drop rows that are identical to an earlier row in all columns
+    Exact,
+    /// Keep a single row for each combination of key columns
+    ByKey {
+        /// Columns identifying a row
+        keys: Vec<String>,
+        /// The row with the greatest value in this column is kept
+        version_column: String,
+    },
 }
 
 /// Optimize a Delta table with given options
@@ -210,6 +246,16 @@ pub struct OptimizeBuilder<'a> {
     /// Optimize type
     optimize_type: OptimizeType,
     min_commit_interval: Option<Duration>,
+    /// Token to cancel the optimize
+    cancellation_token: Option<CancellationToken>,
+    /// Receiver of the number of rewritten bins
+    progress_reporter: Option<ProgressReporterRef>,
+    /// Hive paths of the partitions compacted, all partitions matching the filters if not set
+    partitions: Option<HashSet<String>>,
+    /// Maximum number of files rewritten by a compaction
+    max_files: Option<usize>,
+    /// Maximum number of bytes rewritten by a compaction
+    max_bytes: Option<i64>,
 }
 
 impl super::Operation<()> for OptimizeBuilder<'_> {}
@@ -229,6 +275,11 @@ impl<'a> OptimizeBuilder<'a> {
             max_spill_size: 20 * 1024 * 1024 * 2014, // 20 GB.
             optimize_type: OptimizeType::Compact,
             min_commit_interval: None,
+            cancellation_token: None,
+            progress_reporter: None,
+            partitions: None,
+            max_files: None,
+            max_bytes: None,
         }
     }
 
@@ -285,6 +336,45 @@ impl<'a> OptimizeBuilder<'a> {
         self.min_commit_interval = Some(min_commit_interval);
         self
     }
+
+    /// Abort the optimize once `token` is cancelled.
+    ///
+    /// Rewrites which have not been committed yet are discarded and their files deleted.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Report the number of rewritten bins, i.e. groups of files merged into new files, as
+    /// [`ProgressStage::OptimizeRewrite`]
+    pub fn with_progress_reporter(mut self, reporter: ProgressReporterRef) -> Self {
+        self.progress_reporter = Some(reporter);
+        self
+    }
+
+    /// Limit the number of small files a compaction rewrites.
+    ///
+    /// Bins of files are skipped once the limit would be exceeded, they are left for the next
+    /// compaction. Only applies to [`OptimizeType::Compact`].
+    pub fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = Some(max_files);
+        self
+    }
+
+    /// Limit the number of bytes a compaction rewrites.
+    ///
+    /// Bins of files are skipped once the limit would be exceeded, they are left for the next
+    /// compaction. Only applies to [`OptimizeType::Compact`].
+    pub fn with_max_bytes(mut self, max_bytes: i64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Only compact the partitions with the given hive partition paths
+    pub(crate) fn with_partitions(mut self, partitions: HashSet<String>) -> Self {
+        self.partitions = Some(partitions);
+        self
+    }
 }
 
 impl<'a> std::future::IntoFuture for OptimizeBuilder<'a> {
@@ -293,9 +383,20 @@ impl<'a> std::future::IntoFuture for OptimizeBuilder<'a> {
 
     fn into_future(self) -> Self::IntoFuture {
         let this = self;
+        let cancellation_token = this.cancellation_token.clone();
+        let span = info_span!(
+            "delta.optimize",
+            table_uri = %this.log_store.root_uri(),
+            version = this.snapshot.version(),
+            optimize_type = ?this.optimize_type,
+            target_size = this.target_size,
+            filters = ?this.filters,
+        );
 
-        Box::pin(async move {
+        let future = cancellable(cancellation_token, async move {
             PROTOCOL.can_write_to(&this.snapshot.snapshot)?;
+            this.snapshot.ensure_all_files("OPTIMIZE")?;
+            let log_store = operation_log_store(&this.log_store);
 
             let writer_properties = this.writer_properties.unwrap_or_else(|| {
                 WriterProperties::builder()
@@ -303,16 +404,18 @@ impl<'a> std::future::IntoFuture for OptimizeBuilder<'a> {
                     .set_created_by(format!("delta-rs version {}", crate_version()))
                     .build()
             });
-            let plan = create_merge_plan(
+            let mut plan = create_merge_plan(
                 this.optimize_type,
                 &this.snapshot,
                 this.filters,
                 this.target_size.to_owned(),
                 writer_properties,
             )?;
+            plan.limit_compaction(this.partitions.as_ref(), this.max_files, this.max_bytes);
+            plan.progress_reporter = this.progress_reporter;
             let metrics = plan
                 .execute(
-                    this.log_store.clone(),
+                    log_store,
                     &this.snapshot,
                     this.max_concurrent_tasks,
                     this.max_spill_size,
@@ -323,7 +426,9 @@ impl<'a> std::future::IntoFuture for OptimizeBuilder<'a> {
             let mut table = DeltaTable::new_with_state(this.log_store, this.snapshot);
             table.update().await?;
             Ok((table, metrics))
-        })
+        });
+
+        Box::pin(future.instrument(span))
     }
 }
 
@@ -395,6 +500,11 @@ enum OptimizeOperations {
         Vec<String>,
         HashMap<String, (IndexMap<String, Scalar>, MergeBin)>,
     ),
+    /// Plan to rewrite each partition without duplicate rows
+    Deduplicate(
+        Deduplication,
+        HashMap<String, (IndexMap<String, Scalar>, MergeBin)>,
+    ),
     // TODO: Sort
 }
 
@@ -414,6 +524,8 @@ pub struct MergePlan {
     task_parameters: Arc<MergeTaskParameters>,
     /// Version of the table at beginning of optimization. Used for conflict resolution.
     read_table_version: i64,
+    /// Receiver of the number of rewritten bins
+    progress_reporter: Option<ProgressReporterRef>,
 }
 
 /// Parameters passed to individual merge tasks
@@ -435,6 +547,42 @@ pub struct MergeTaskParameters {
 type ParquetReadStream = BoxStream<'static, Result<RecordBatch, ParquetError>>;
 
 impl MergePlan {
+    /// Restrict a compaction to the given partitions and to at most `max_files` files and
+    /// `max_bytes` bytes, taking whole bins in the order of the partition paths
+    fn limit_compaction(
+        &mut self,
+        partitions: Option<&HashSet<String>>,
+        max_files: Option<usize>,
+        max_bytes: Option<i64>,
+    ) {
+        let OptimizeOperations::Compact(operations) = &mut self.operations else {
+            return;
+        };
+        if let Some(partitions) = partitions {
+            operations.retain(|part, _| partitions.contains(part));
+        }
+        let max_files = max_files.unwrap_or(usize::MAX);
+        let max_bytes = max_bytes.unwrap_or(i64::MAX);
+        let (mut num_files, mut num_bytes) = (0usize, 0i64);
+        let mut parts = operations.keys().cloned().collect::<Vec<_>>();
+        parts.sort();
+        for part in parts {
+            if let Some((_, bins)) = operations.get_mut(&part) {
+                bins.retain(|bin| {
+                    let files = num_files.saturating_add(bin.len());
+                    let bytes = num_bytes.saturating_add(bin.total_file_size());
+                    let fits = files <= max_files && bytes <= max_bytes;
+                    if fits {
+                        (num_files, num_bytes) = (files, bytes);
+                    }
+                    fits
+                });
+            }
+        }
+        operations.retain(|_, (_, bins)| !bins.is_empty());
+        self.metrics.partitions_optimized = operations.len() as u64;
+    }
+
     /// Rewrites files in a single partition.
     ///
     /// Returns a vector of add and remove actions, as well as the partial metrics
@@ -478,6 +626,7 @@ impl MergePlan {
             files_added: MetricDetails::default(),
             files_removed,
             num_batches: 0,
+            num_duplicates_removed: None,
         };
 
         // Next, initialize the writer
@@ -549,7 +698,7 @@ impl MergePlan {
 
         let object_store_ref = context.object_store.clone();
         // Read all batches into a vec
-        let batches = zorder::collect_batches(object_store_ref, files).await?;
+        let batches = util::collect_batches(object_store_ref, files).await?;
 
         // For each batch, compute the zorder key
         let zorder_keys: Vec<ArrayRef> =
@@ -658,6 +807,19 @@ impl MergePlan {
         commit_properties: CommitProperties,
     ) -> Result<Metrics, DeltaTableError> {
         let operations = std::mem::take(&mut self.operations);
+        let operation = match &operations {
+            OptimizeOperations::Deduplicate(..) => DeltaOperation::Delete { predicate: None },
+            _ => self.task_parameters.input_parameters.clone().into(),
+        };
+        let total_bins = match &operations {
+            OptimizeOperations::Compact(bins) => {
+                bins.values().map(|(_, bins)| bins.len() as u64).sum()
+            }
+            OptimizeOperations::ZOrder(_, bins) | OptimizeOperations::Deduplicate(_, bins) => {
+                bins.len() as u64
+            }
+        };
+        let mut rewritten_bins = 0;
 
         let stream = match operations {
             OptimizeOperations::Compact(bins) => futures::stream::iter(bins)
@@ -687,13 +849,13 @@ impl MergePlan {
                         .try_flatten()
                         .boxed();
 
-                    let rewrite_result = tokio::task::spawn(Self::rewrite_files(
+                    let rewrite_result = AbortOnDrop::new(tokio::task::spawn(Self::rewrite_files(
                         self.task_parameters.clone(),
                         partition,
                         files,
                         log_store.object_store().clone(),
                         futures::future::ready(Ok(batch_stream)),
-                    ));
+                    )));
                     util::flatten_join_error(rewrite_result)
                 })
                 .boxed(),
@@ -720,13 +882,69 @@ impl MergePlan {
                 futures::stream::iter(bins)
                     .map(move |(_, (partition, files))| {
                         let batch_stream = Self::read_zorder(files.clone(), exec_context.clone());
-                        let rewrite_result = tokio::task::spawn(Self::rewrite_files(
-                            task_parameters.clone(),
-                            partition,
-                            files,
-                            log_store.object_store(),
-                            batch_stream,
-                        ));
+                        let rewrite_result =
+                            AbortOnDrop::new(tokio::task::spawn(Self::rewrite_files(
+                                task_parameters.clone(),
+                                partition,
+                                files,
+                                log_store.object_store(),
+                                batch_stream,
+                            )));
+                        util::flatten_join_error(rewrite_result)
+                    })
+                    .boxed()
+            }
+            OptimizeOperations::Deduplicate(deduplication, bins) => {
+                let deduplication = Arc::new(deduplication);
+                let task_parameters = self.task_parameters.clone();
+                let log_store = log_store.clone();
+                futures::stream::iter(bins)
+                    .map(move |(_, (partition, files))| {
+                        let task_parameters = task_parameters.clone();
+                        let deduplication = deduplication.clone();
+                        let object_store = log_store.object_store();
+                        let rewrite_result = AbortOnDrop::new(tokio::task::spawn(async move {
+                            let mut deduplicator = dedup::Deduplicator::try_new(
+                                task_parameters.file_schema.clone(),
+                                &deduplication,
+                            )?;
+                            deduplicator
+                                .find_latest(util::read_files(object_store.clone(), files.clone()))
+                                .await?;
+                            let removed = deduplicator.removed();
+                            let batch_stream =
+                                util::read_files(object_store.clone(), files.clone())
+                                    .map(move |batch| {
+                                        deduplicator
+                                            .filter(&batch?)
+                                            .map_err(|err| ParquetError::External(Box::new(err)))
+                                    })
+                                    .boxed();
+                            let (actions, mut metrics) = Self::rewrite_files(
+                                task_parameters,
+                                partition,
+                                files,
+                                object_store,
+                                futures::future::ready(Ok(batch_stream)),
+                            )
+                            .await?;
+                            metrics.num_duplicates_removed = Some(removed.load(Ordering::Relaxed));
+                            let actions = actions
+                                .into_iter()
+                                .map(|action| match action {
+                                    Action::Add(add) => Action::Add(Add {
+                                        data_change: true,
+                                        ..add
+                                    }),
+                                    Action::Remove(remove) => Action::Remove(Remove {
+                                        data_change: true,
+                                        ..remove
+                                    }),
+                                    action => action,
+                                })
+                                .collect_vec();
+                            Ok::<_, DeltaTableError>((actions, metrics))
+                        }));
                         util::flatten_join_error(rewrite_result)
                     })
                     .boxed()
@@ -740,6 +958,8 @@ impl MergePlan {
         // Actions buffered so far. These will be flushed either at the end
         // or when we reach the commit interval.
         let mut actions = vec![];
+        // Files written by the rewrites which have not been committed yet
+        let mut uncommitted = UncommittedFiles::new(log_store.object_store());
 
         // Each time we commit, we'll reset buffered_metrics to orig_metrics.
         let orig_metrics = std::mem::take(&mut self.metrics);
@@ -755,6 +975,14 @@ impl MergePlan {
 
             if let Some((partial_actions, partial_metrics)) = next {
                 debug!("Recording metrics for a completed partition");
+                rewritten_bins += 1;
+                report(
+                    self.progress_reporter.as_ref(),
+                    ProgressStage::OptimizeRewrite,
+                    rewritten_bins,
+                    total_bins,
+                );
+                uncommitted.track(&partial_actions);
                 actions.extend(partial_actions);
                 buffered_metrics.add(&partial_metrics);
                 total_metrics.add(&partial_metrics);
@@ -786,15 +1014,12 @@ impl MergePlan {
                 }
 
                 debug!("committing {} actions", actions.len());
+                uncommitted.committed();
 
                 CommitBuilder::from(properties)
                     .with_actions(actions)
                     .with_max_retries(DEFAULT_RETRIES + commits_made)
-                    .build(
-                        Some(snapshot),
-                        log_store.clone(),
-                        self.task_parameters.input_parameters.clone().into(),
-                    )
+                    .build(Some(snapshot), log_store.clone(), operation.clone())
                     .await?;
 
                 commits_made += 1;
@@ -835,6 +1060,9 @@ pub fn create_merge_plan(
         OptimizeType::ZOrder(zorder_columns) => {
             build_zorder_plan(zorder_columns, snapshot, partitions_keys, filters)?
         }
+        OptimizeType::Deduplicate(deduplication) => {
+            build_deduplication_plan(deduplication, snapshot, partitions_keys, filters)?
+        }
     };
 
     let input_parameters = OptimizeInput {
@@ -858,6 +1086,7 @@ pub fn create_merge_plan(
                 .map(|v| v.iter().map(|v| v.to_string()).collect::<Vec<String>>()),
         }),
         read_table_version: snapshot.version(),
+        progress_reporter: None,
     })
 }
 
@@ -1016,7 +1245,79 @@ fn build_zorder_plan(
 
     // For now, just be naive and optimize all files in each selected partition.
     let mut metrics = Metrics::default();
+    let partition_files = group_files_by_partition(snapshot, filters, &mut metrics)?;
+
+    let operation = OptimizeOperations::ZOrder(zorder_columns, partition_files);
+    Ok((operation, metrics))
+}
 
+fn build_deduplication_plan(
+    deduplication: Deduplication,
+    snapshot: &DeltaTableState,
+    partition_keys: &[String],
+    filters: &[PartitionFilter],
+) -> Result<(OptimizeOperations, Metrics), DeltaTableError> {
+    let deduplication = match deduplication {
+        Deduplication::Exact => Deduplication::Exact,
+        Deduplication::ByKey {
+            keys,
+            version_column,
+        } => {
+            let field_names = snapshot
+                .schema()
+                .fields()
+                .map(|field| field.name().to_string())
+                .collect_vec();
+            let unknown_columns = keys
+                .iter()
+                .chain(std::iter::once(&version_column))
+                .filter(|col| !field_names.contains(col))
+                .collect_vec();
+            if !unknown_columns.is_empty() {
+                return Err(DeltaTableError::Generic(format!(
+                    "Deduplication columns must be present in the table schema. Unknown columns: {unknown_columns:?}"
+                )));
+            }
+            if partition_keys.contains(&version_column) {
+                return Err(DeltaTableError::Generic(format!(
+                    "Deduplication version column cannot be a partition column. Found: {version_column}"
+                )));
+            }
+            // Partition values are the same for all rows that are compared
+            let keys = keys
+                .into_iter()
+                .filter(|col| !partition_keys.contains(col))
+                .collect_vec();
+            if keys.is_empty() {
+                return Err(DeltaTableError::Generic(
+                    "Deduplication requires at least one non-partition key column".to_string(),
+                ));
+            }
+            Deduplication::ByKey {
+                keys,
+                version_column,
+            }
+        }
+    };
+
+    // Duplicates may be spread over any files, so all files of a partition are rewritten.
+    let mut metrics = Metrics {
+        num_duplicates_removed: Some(0),
+        ..Default::default()
+    };
+    let partition_files = group_files_by_partition(snapshot, filters, &mut metrics)?;
+    metrics.partitions_optimized = partition_files.len() as u64;
+
+    let operation = OptimizeOperations::Deduplicate(deduplication, partition_files);
+    Ok((operation, metrics))
+}
+
+/// Collect all files of the selected partitions into a single bin per partition
+fn group_files_by_partition(
+    snapshot: &DeltaTableState,
+    filters: &[PartitionFilter],
+    metrics: &mut Metrics,
+) -> Result<HashMap<String, (IndexMap<String, Scalar>, MergeBin)>, DeltaTableError> {
     let mut partition_files: HashMap<String, (IndexMap<String, Scalar>, MergeBin)> = HashMap::new();
     for add in snapshot.get_active_add_actions_by_partitions(filters)? {
         let add = add?;
@@ -1034,9 +1335,7 @@ fn build_zorder_plan(
             .1
             .add(object_meta);
     }
-
-    let operation = OptimizeOperations::ZOrder(zorder_columns, partition_files);
-    Ok((operation, metrics))
+    Ok(partition_files)
 }
 
 pub(super) mod util {
@@ -1111,6 +1410,31 @@ pub(super) mod util {
             .boxed()
     }
 
+    /// Read all batches into a vec - is an async function in disguise
+    #[cfg(not(feature = "datafusion"))]
+    pub fn collect_batches(
+        object_store: ObjectStoreRef,
+        files: MergeBin,
+    ) -> impl Future<Output = Result<Vec<RecordBatch>, ParquetError>> {
+        read_files(object_store, files).try_collect::<Vec<_>>()
+    }
+
+    /// Stream the batches of `files` one file after the other
+    pub fn read_files(object_store: ObjectStoreRef, files: MergeBin) -> ParquetReadStream {
+        futures::stream::iter(files)
+            .then(move |file| {
+                let object_store = object_store.clone();
+                async move {
+                    let file_reader = ParquetObjectReader::new(object_store.clone(), file);
+                    ParquetRecordBatchStreamBuilder::new(file_reader)
+                        .await?
+                        .build()
+                }
+            })
+            .try_flatten()
+            .boxed()
+    }
+
     pub async fn flatten_join_error<T, E>(
         future: impl Future<Output = Result<Result<T, E>, JoinError>>,
     ) -> Result<T, DeltaTableError>
@@ -1127,6 +1451,254 @@ pub(super) mod util {
     }
 }
 
+/// Deduplication utilities
+pub(super) mod dedup {
+    use super::*;
+
+    use std::collections::hash_map::Entry;
+    use std::collections::HashSet;
+    use std::sync::atomic::AtomicU64;
+
+    use arrow_array::{ArrayRef, BooleanArray};
+    use arrow_row::{OwnedRow, RowConverter, SortField};
+    use arrow_select::filter::filter_record_batch;
+
+    fn columns(batch: &RecordBatch, names: &[String]) -> DeltaResult<Vec<ArrayRef>> {
+        names
+            .iter()
+            .map(|name| {
+                batch.column_by_name(name).cloned().ok_or_else(|| {
+                    DeltaTableError::Generic(format!("Column not found in data file: {name}"))
+                })
+            })
+            .collect()
+    }
+
+    fn converter(schema: &ArrowSchemaRef, names: &[String]) -> DeltaResult<RowConverter> {
+        let fields = names
+            .iter()
+            .map(|name| {
+                Ok(SortField::new(
+                    schema.field_with_name(name)?.data_type().clone(),
+                ))
+            })
+            .collect::<DeltaResult<Vec<_>>>()?;
+        Ok(RowConverter::new(fields)?)
+    }
+
+    /// Drops duplicate rows from the batches of a partition, preserving the order of the
+    /// remaining rows.
+    ///
+    /// Only the keys of the rows are held in memory, not the rows themselves. Keeping the
+    /// latest row for each key requires a first pass over the partition, see
+    /// [`Deduplicator::find_latest`].
+    pub struct Deduplicator {
+        file_schema: ArrowSchemaRef,
+        key_names: Vec<String>,
+        key_converter: RowConverter,
+        /// The version column and its converter when deduplicating by key
+        version: Option<(String, RowConverter)>,
+        /// Keys of the rows kept so far when dropping exact duplicates
+        seen: HashSet<OwnedRow>,
+        /// Positions of the latest row for each key when deduplicating by key
+        latest: HashSet<u64>,
+        /// Position of the next row within the partition
+        position: u64,
+        removed: Arc<AtomicU64>,
+    }
+
+    impl Deduplicator {
+        /// Create a deduplicator for batches with the given file schema
+        pub fn try_new(
+            file_schema: ArrowSchemaRef,
+            deduplication: &Deduplication,
+        ) -> DeltaResult<Self> {
+            let (key_names, version) = match deduplication {
+                Deduplication::Exact => (
+                    file_schema
+                        .fields()
+                        .iter()
+                        .map(|field| field.name().clone())
+                        .collect_vec(),
+                    None,
+                ),
+                Deduplication::ByKey {
+                    keys,
+                    version_column,
+                } => (
+                    keys.clone(),
+                    Some((
+                        version_column.clone(),
+                        converter(&file_schema, std::slice::from_ref(version_column))?,
+                    )),
+                ),
+            };
+            Ok(Self {
+                key_converter: converter(&file_schema, &key_names)?,
+                file_schema,
+                key_names,
+                version,
+                seen: HashSet::new(),
+                latest: HashSet::new(),
+                position: 0,
+                removed: Arc::new(AtomicU64::new(0)),
+            })
+        }
+
+        fn cast(&self, batch: &RecordBatch) -> DeltaResult<RecordBatch> {
+            super::super::cast::cast_record_batch(batch, self.file_schema.clone(), false, true)
+        }
+
+        /// Find the latest row for each key in a first pass over the batches of the partition.
+        ///
+        /// This is a no-op when dropping exact duplicates.
+        pub async fn find_latest(&mut self, mut batches: ParquetReadStream) -> DeltaResult<()> {
+            let Some((version_column, version_converter)) = &self.version else {
+                return Ok(());
+            };
+            let version_names = std::slice::from_ref(version_column);
+            // Position and version of the latest row for each key
+            let mut latest: HashMap<OwnedRow, (u64, OwnedRow)> = HashMap::new();
+            let mut position = 0;
+            while let Some(batch) = batches.next().await {
+                let batch = self.cast(&batch?)?;
+                let keys = self
+                    .key_converter
+                    .convert_columns(&columns(&batch, &self.key_names)?)?;
+                let versions =
+                    version_converter.convert_columns(&columns(&batch, version_names)?)?;
+                for (key, version) in keys.iter().zip(versions.iter()) {
+                    match latest.entry(key.owned()) {
+                        Entry::Occupied(mut entry) => {
+                            if version > entry.get().1.row() {
+                                entry.insert((position, version.owned()));
+                            }
+                        }
+                        Entry::Vacant(entry) => {
+                            entry.insert((position, version.owned()));
+                        }
+                    }
+                    position += 1;
+                }
+            }
+            self.latest = latest.into_values().map(|(position, _)| position).collect();
+            Ok(())
+        }
+
+        /// Drop the duplicates from the next batch of the partition
+        pub fn filter(&mut self, batch: &RecordBatch) -> DeltaResult<RecordBatch> {
+            let batch = self.cast(batch)?;
+            let mask = if self.version.is_none() {
+                let keys = self
+                    .key_converter
+                    .convert_columns(&columns(&batch, &self.key_names)?)?;
+                keys.iter()
+                    .map(|key| self.seen.insert(key.owned()))
+                    .collect_vec()
+            } else {
+                (self.position..self.position + batch.num_rows() as u64)
+                    .map(|position| self.latest.contains(&position))
+                    .collect_vec()
+            };
+            self.position += batch.num_rows() as u64;
+            let mask = BooleanArray::from(mask);
+            self.removed
+                .fetch_add((mask.len() - mask.true_count()) as u64, Ordering::Relaxed);
+            Ok(filter_record_batch(&batch, &mask)?)
+        }
+
+        /// Number of rows dropped so far
+        pub fn removed(&self) -> Arc<AtomicU64> {
+            self.removed.clone()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use arrow_array::{Int32Array, StringArray};
+        use arrow_schema::{DataType, Field, Schema};
+
+        fn batch(ids: Vec<i32>, versions: Vec<Option<i32>>, values: Vec<&str>) -> RecordBatch {
+            let schema = Arc::new(Schema::new(vec![
+                Field::new("id", DataType::Int32, false),
+                Field::new("version", DataType::Int32, true),
+                Field::new("value", DataType::Utf8, false),
+            ]));
+            RecordBatch::try_new(
+                schema,
+                vec![
+                    Arc::new(Int32Array::from(ids)),
+                    Arc::new(Int32Array::from(versions)),
+                    Arc::new(StringArray::from(values)),
+                ],
+            )
+            .unwrap()
+        }
+
+        async fn deduplicate(
+            batches: Vec<RecordBatch>,
+            deduplication: &Deduplication,
+        ) -> (Vec<RecordBatch>, u64) {
+            let mut deduplicator =
+                Deduplicator::try_new(batches[0].schema(), deduplication).unwrap();
+            let stream = futures::stream::iter(batches.clone().into_iter().map(Ok)).boxed();
+            deduplicator.find_latest(stream).await.unwrap();
+            let result = batches
+                .iter()
+                .map(|batch| deduplicator.filter(batch).unwrap())
+                .collect();
+            (result, deduplicator.removed().load(Ordering::Relaxed))
+        }
+
+        #[tokio::test]
+        async fn test_deduplicate_exact() {
+            let batches = vec![
+                batch(
+                    vec![1, 2, 1],
+                    vec![Some(1), Some(1), Some(1)],
+                    vec!["a", "b", "a"],
+                ),
+                batch(vec![2, 1], vec![Some(1), Some(2)], vec!["b", "a"]),
+            ];
+            let (result, removed) = deduplicate(batches, &Deduplication::Exact).await;
+            assert_eq!(removed, 2);
+            let expected = vec![
+                batch(vec![1, 2], vec![Some(1), Some(1)], vec!["a", "b"]),
+                batch(vec![1], vec![Some(2)], vec!["a"]),
+            ];
+            assert_eq!(result, expected);
+        }
+
+        #[tokio::test]
+        async fn test_deduplicate_by_key() {
+            let batches = vec![
+                batch(
+                    vec![1, 2, 3],
+                    vec![Some(1), Some(3), None],
+                    vec!["a", "b", "c"],
+                ),
+                batch(
+                    vec![2, 1, 3],
+                    vec![Some(2), Some(2), Some(1)],
+                    vec!["d", "e", "f"],
+                ),
+            ];
+            let deduplication = Deduplication::ByKey {
+                keys: vec!["id".to_string()],
+                version_column: "version".to_string(),
+            };
+            let (result, removed) = deduplicate(batches, &deduplication).await;
+            assert_eq!(removed, 3);
+            let expected = vec![
+                batch(vec![2], vec![Some(3)], vec!["b"]),
+                batch(vec![1, 3], vec![Some(2), Some(1)], vec!["e", "f"]),
+            ];
+            assert_eq!(result, expected);
+        }
+    }
+}
+
 /// Z-order utilities
 pub(super) mod zorder {
     use super::*;
@@ -1165,26 +1737,6 @@ pub(super) mod zorder {
         }
     }
 
-    /// Read all batches into a vec - is an async function in disguise
-    #[cfg(not(feature = "datafusion"))]
-    pub(super) fn collect_batches(
-        object_store: ObjectStoreRef,
-        files: MergeBin,
-    ) -> impl Future<Output = Result<Vec<RecordBatch>, ParquetError>> {
-        futures::stream::iter(files.clone())
-            .then(move |file| {
-                let object_store = object_store.clone();
-                async move {
-                    let file_reader = ParquetObjectReader::new(object_store.clone(), file);
-                    ParquetRecordBatchStreamBuilder::new(file_reader)
-                        .await?
-                        .build()
-                }
-            })
-            .try_flatten()
-            .try_collect::<Vec<_>>()
-    }
-
     #[cfg(feature = "datafusion")]
     pub use self::datafusion::ZOrderExecContext;
 
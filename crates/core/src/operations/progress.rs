@@ -0,0 +1,101 @@
+//! Progress reporting for long running operations
+//!
+//! Operations given a [`ProgressReporter`] call [`ProgressReporter::on_progress`] whenever a
+//! unit of work of one of their stages completes, e.g. to render progress bars for jobs over
+//! large tables. Reporters are called from the task running the operation and should return
+//! quickly.
+//!
+//! | Stage | Operation | Unit |
+//! |-------|-----------|------|
+//! | [`ProgressStage::OptimizeRewrite`] | [`OptimizeBuilder`](super::optimize::OptimizeBuilder) | rewritten bins of files |
+//! | [`ProgressStage::VacuumDelete`] | [`VacuumBuilder`](super::vacuum::VacuumBuilder) | deleted files |
+//! | [`ProgressStage::ConstraintCheck`] | `ConstraintBuilder` | checked partitions of the table scan |
+//! | [`ProgressStage::CheckpointWrite`] | [`create_checkpoint_with_progress`](crate::checkpoints::create_checkpoint_with_progress) | actions written to the checkpoint |
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::Arc;
+
+/// A stage of an operation whose progress is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ProgressStage {
+    /// Optimize rewriting groups of files
+    OptimizeRewrite,
+    /// Vacuum deleting files
+    VacuumDelete,
+    /// Checking the existing data against a new constraint
+    ConstraintCheck,
+    /// Writing the actions of a checkpoint
+    CheckpointWrite,
+}
+
+impl ProgressStage {
+    /// Name of the stage, e.g. to label progress bars
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OptimizeRewrite => "optimize.rewrite",
+            Self::VacuumDelete => "vacuum.delete",
+            Self::ConstraintCheck => "constraint.check",
+            Self::CheckpointWrite => "checkpoint.write",
+        }
+    }
+}
+
+impl Display for ProgressStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Receiver of the progress of operations
+pub trait ProgressReporter: Debug + Send + Sync {
+    /// Called when `done` out of `total` units of work of `stage` are completed
+    fn on_progress(&self, stage: ProgressStage, done: u64, total: u64);
+}
+
+/// Sharable reference to a [`ProgressReporter`]
+pub type ProgressReporterRef = Arc<dyn ProgressReporter>;
+
+/// Report progress to an optional reporter
+pub(crate) fn report(
+    reporter: Option<&ProgressReporterRef>,
+    stage: ProgressStage,
+    done: u64,
+    total: u64,
+) {
+    if let Some(reporter) = reporter {
+        reporter.on_progress(stage, done, total);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    /// Reporter recording every call, for tests of operations
+    #[derive(Debug, Default)]
+    pub(crate) struct RecordingReporter {
+        pub(crate) calls: Mutex<Vec<(ProgressStage, u64, u64)>>,
+    }
+
+    impl ProgressReporter for RecordingReporter {
+        fn on_progress(&self, stage: ProgressStage, done: u64, total: u64) {
+            self.calls.lock().push((stage, done, total));
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let reporter = Arc::new(RecordingReporter::default());
+        let reporter_ref: ProgressReporterRef = reporter.clone();
+        report(Some(&reporter_ref), ProgressStage::VacuumDelete, 1, 2);
+        report(None, ProgressStage::VacuumDelete, 2, 2);
+        assert_eq!(
+            *reporter.calls.lock(),
+            vec![(ProgressStage::VacuumDelete, 1, 2)]
+        );
+        assert_eq!(ProgressStage::VacuumDelete.to_string(), "vacuum.delete");
+    }
+}
@@ -152,6 +152,7 @@ async fn execute(
     {
         return Err(DeltaTableError::from(RestoreError::InvalidRestoreParameter));
     }
+    snapshot.ensure_all_files("RESTORE")?;
     let mut table = DeltaTable::new(log_store.clone(), DeltaTableConfig::default());
 
     let version = match datetime_to_restore {
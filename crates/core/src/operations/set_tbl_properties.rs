@@ -1,10 +1,16 @@
 //! Set table properties on a table
+//!
+//! The values of well known `delta.*` properties are validated, and properties implying table
+//! features upgrade the protocol of the table as needed. The protocol is never downgraded: when
+//! upgrading to table features, the features implied by the previous protocol versions are listed
+//! explicitly, and lower versions than the current ones are ignored.
 
 use std::collections::{HashMap, HashSet};
 
 use futures::future::BoxFuture;
 use maplit::hashset;
 
+use super::table_features::legacy_writer_features;
 use super::transaction::{CommitBuilder, CommitProperties};
 use crate::kernel::{Action, Protocol, ReaderFeatures, WriterFeatures};
 use crate::logstore::LogStoreRef;
@@ -70,6 +76,9 @@ pub fn apply_properties_to_protocol(
 
     for (key, value) in new_properties {
         if let Ok(parsed_key) = key.parse::<DeltaConfigKey>() {
+            parsed_key.validate_value(value).map_err(|err| {
+                DeltaTableError::Generic(format!("Invalid value for property '{key}': {err}"))
+            })?;
             parsed_properties.insert(parsed_key, value.to_string());
         } else if raise_if_not_exists {
             return Err(DeltaTableError::Generic(format!(
@@ -133,6 +142,24 @@ pub fn apply_properties_to_protocol(
         }
     }
 
+    // Append only tables require writer version 2, or the appendOnly writer feature which is
+    // added when converting the properties to features
+    if let Some(append_only) = parsed_properties.get(&DeltaConfigKey::AppendOnly) {
+        if append_only.eq_ignore_ascii_case("true") && new_protocol.min_writer_version < 2 {
+            new_protocol.min_writer_version = 2;
+        }
+    }
+
+    // Column mapping requires rewriting the schema with physical names, which is not supported
+    if let Some(mode) = parsed_properties.get(&DeltaConfigKey::ColumnMappingMode) {
+        if !mode.eq_ignore_ascii_case("none") {
+            return Err(DeltaTableError::Generic(format!(
+                "delta.columnMapping.mode = '{}' is not supported, valid values are ['none']",
+                mode
+            )));
+        }
+    }
+
     // Check enableChangeDataFeed and bump protocol or add writerFeature if writer versions is >=7
     if let Some(enable_cdf) = parsed_properties.get(&DeltaConfigKey::EnableChangeDataFeed) {
         let if_enable_cdf = enable_cdf.to_ascii_lowercase().parse::<bool>();
@@ -199,6 +226,26 @@ pub fn apply_properties_to_protocol(
     Ok(new_protocol)
 }
 
+/// Lists the features implied by the legacy versions of `current_protocol` when `new_protocol`
+/// upgrades to table features, so that upgrading never drops support of a feature
+pub fn keep_legacy_features(current_protocol: &Protocol, mut new_protocol: Protocol) -> Protocol {
+    if current_protocol.min_writer_version < 7 && new_protocol.min_writer_version >= 7 {
+        new_protocol
+            .writer_features
+            .get_or_insert_with(HashSet::new)
+            .extend(legacy_writer_features(current_protocol.min_writer_version));
+    }
+    if current_protocol.min_reader_version < 3 && new_protocol.min_reader_version >= 3 {
+        let reader_features = new_protocol
+            .reader_features
+            .get_or_insert_with(HashSet::new);
+        if current_protocol.min_reader_version >= 2 {
+            reader_features.insert(ReaderFeatures::ColumnMapping);
+        }
+    }
+    new_protocol
+}
+
 /// Converts existing properties into features if the reader_version is >=3 or writer_version >=3
 /// only converts features that are "true"
 pub fn convert_properties_to_features(
@@ -284,8 +331,10 @@ impl std::future::IntoFuture for SetTablePropertiesBuilder {
                     .collect::<HashMap<String, Option<String>>>(),
             );
 
-            let final_protocol =
-                convert_properties_to_features(new_protocol, &metadata.configuration);
+            let final_protocol = keep_legacy_features(
+                current_protocol,
+                convert_properties_to_features(new_protocol, &metadata.configuration),
+            );
 
             let operation = DeltaOperation::SetTableProperties { properties };
 
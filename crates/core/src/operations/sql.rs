@@ -0,0 +1,978 @@
+//! Execute Delta flavored SQL statements against a table
+//!
+//! [`DeltaOps::sql`] parses a single statement and runs it with the corresponding operation
+//! builder, so services accepting SQL from users do not need to map statements to builders
+//! themselves. The table name in the statement is not resolved, the statement always applies
+//! to the table the [`DeltaOps`] was created for. The supported statements are
+//!
+//! * `UPDATE table SET col = expr [, ...] [WHERE predicate]`
+//! * `DELETE FROM table [WHERE predicate]`
+//! * `MERGE INTO table [[AS] alias] USING source [[AS] alias] ON predicate WHEN ...` with
+//!   `WHEN MATCHED [AND predicate] THEN UPDATE SET ... | DELETE`,
+//!   `WHEN NOT MATCHED [BY TARGET] [AND predicate] THEN INSERT (col, ...) VALUES (expr, ...)`
+//!   and `WHEN NOT MATCHED BY SOURCE [AND predicate] THEN UPDATE SET ... | DELETE` clauses. The
+//!   source has to be registered as a table in the session state passed with
+//!   [`SqlBuilder::with_session_state`]. Without an alias the target and source are referred to
+//!   by the last part of their names.
+//! * `OPTIMIZE table [WHERE partition_predicate] [ZORDER BY (col, ...)]`, where the partition
+//!   predicate is a conjunction of comparisons of partition columns with literals
+//! * `VACUUM table [RETAIN num HOURS] [DRY RUN]`
+//! * `ALTER TABLE table SET TBLPROPERTIES ('key' = 'value', ...)`
+//! * `ALTER TABLE table ADD CONSTRAINT name CHECK (predicate)`
+//! * `ALTER TABLE table DROP CONSTRAINT [IF EXISTS] name`
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table("../path/to/table")?;
+//! let (table, metrics) = DeltaOps(table)
+//!     .sql("UPDATE my_table SET value = value + 1 WHERE id = 'A'")
+//!     .await?;
+//! ```
+
+use std::collections::HashMap;
+
+use chrono::Duration;
+use datafusion::execution::context::{SessionContext, SessionState};
+use datafusion_sql::sqlparser::ast::ObjectName;
+use datafusion_sql::sqlparser::dialect::GenericDialect;
+use datafusion_sql::sqlparser::parser::{Parser, ParserError};
+use datafusion_sql::sqlparser::tokenizer::{Token, Tokenizer};
+use futures::future::BoxFuture;
+
+use super::delete::DeleteMetrics;
+use super::merge::MergeMetrics;
+use super::optimize::{Metrics as OptimizeMetrics, OptimizeType};
+use super::update::UpdateMetrics;
+use super::vacuum::VacuumMetrics;
+use super::DeltaOps;
+use crate::logstore::LogStoreRef;
+use crate::table::state::DeltaTableState;
+use crate::{DeltaResult, DeltaTable, DeltaTableError, PartitionFilter};
+
+/// Metrics of the operation a SQL statement was executed with
+#[derive(Debug)]
+pub enum SqlMetrics {
+    /// Metrics of an `UPDATE` statement
+    Update(UpdateMetrics),
+    /// Metrics of a `DELETE` statement
+    Delete(DeleteMetrics),
+    /// Metrics of a `MERGE` statement
+    Merge(MergeMetrics),
+    /// Metrics of an `OPTIMIZE` statement
+    Optimize(OptimizeMetrics),
+    /// Metrics of a `VACUUM` statement
+    Vacuum(VacuumMetrics),
+    /// `ALTER TABLE` statements only change the table metadata
+    Alter,
+}
+
+/// Execute a Delta flavored SQL statement against a table
+///
+/// See the [module level documentation](self) for the supported statements.
+pub struct SqlBuilder {
+    /// The statement to execute
+    sql: String,
+    /// A snapshot of the table's state
+    snapshot: DeltaTableState,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Datafusion session state relevant for executing the statement
+    state: Option<SessionState>,
+}
+
+impl SqlBuilder {
+    /// Create a new builder
+    pub fn new(log_store: LogStoreRef, snapshot: DeltaTableState, sql: impl Into<String>) -> Self {
+        Self {
+            sql: sql.into(),
+            snapshot,
+            log_store,
+            state: None,
+        }
+    }
+
+    /// The Datafusion session state to use, it has to contain the source of `MERGE` statements
+    pub fn with_session_state(mut self, state: SessionState) -> Self {
+        self.state = Some(state);
+        self
+    }
+}
+
+/// A column assignment of an update or insert, as column name and expression
+type Assignment = (String, String);
+
+#[derive(Debug, Clone, PartialEq)]
+enum DeltaStatement {
+    Update {
+        assignments: Vec<Assignment>,
+        predicate: Option<String>,
+    },
+    Delete {
+        predicate: Option<String>,
+    },
+    Merge(MergeStatement),
+    Optimize {
+        filters: Vec<PartitionFilter>,
+        zorder_columns: Vec<String>,
+    },
+    Vacuum {
+        retention_hours: Option<i64>,
+        dry_run: bool,
+    },
+    SetProperties(HashMap<String, String>),
+    AddConstraint {
+        name: String,
+        expr: String,
+    },
+    DropConstraint {
+        name: String,
+        if_exists: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct MergeStatement {
+    target_alias: String,
+    source: String,
+    source_alias: String,
+    predicate: String,
+    clauses: Vec<MergeClause>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MergeClauseKind {
+    Matched,
+    NotMatched,
+    NotMatchedBySource,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum MergeAction {
+    Update(Vec<Assignment>),
+    Delete,
+    Insert(Vec<Assignment>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct MergeClause {
+    kind: MergeClauseKind,
+    predicate: Option<String>,
+    action: MergeAction,
+}
+
+fn parser_err<T>(msg: impl Into<String>) -> Result<T, ParserError> {
+    Err(ParserError::ParserError(msg.into()))
+}
+
+/// The last part of a possibly qualified table name, which refers to the table without an alias
+fn base_name(name: &ObjectName) -> String {
+    name.0
+        .last()
+        .map(|ident| ident.value.clone())
+        .unwrap_or_default()
+}
+
+/// Parse a single statement, optionally terminated by a semicolon
+fn parse_statement(sql: &str) -> DeltaResult<DeltaStatement> {
+    let dialect = GenericDialect {};
+    let parse = || -> Result<DeltaStatement, ParserError> {
+        let tokens = Tokenizer::new(&dialect, sql)
+            .tokenize()
+            .map_err(|err| ParserError::TokenizerError(err.to_string()))?;
+        let mut parser = StatementParser {
+            parser: Parser::new(&dialect).with_tokens(tokens),
+        };
+        let statement = parser.parse_statement()?;
+        parser.parser.consume_token(&Token::SemiColon);
+        if parser.parser.peek_token().token != Token::EOF {
+            return parser.expected("end of statement");
+        }
+        Ok(statement)
+    };
+    parse().map_err(|err| DeltaTableError::Generic(format!("Failed to parse SQL statement: {err}")))
+}
+
+struct StatementParser<'a> {
+    parser: Parser<'a>,
+}
+
+impl StatementParser<'_> {
+    fn expected<T>(&self, expected: &str) -> Result<T, ParserError> {
+        parser_err(format!(
+            "Expected {expected}, found: {}",
+            self.parser.peek_token()
+        ))
+    }
+
+    /// Consume the next token if it is the unquoted word `word`
+    fn parse_word(&mut self, word: &str) -> bool {
+        match self.parser.peek_token().token {
+            Token::Word(w) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(word) => {
+                self.parser.next_token();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), ParserError> {
+        if self.parse_word(word) {
+            Ok(())
+        } else {
+            self.expected(word)
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, ParserError> {
+        match self.parser.next_token().token {
+            Token::Word(w) => Ok(w.value),
+            other => parser_err(format!("Expected identifier, found: {other}")),
+        }
+    }
+
+    /// Parse a possibly qualified column name, keeping quotes
+    fn parse_column(&mut self) -> Result<String, ParserError> {
+        Ok(self.parser.parse_object_name(false)?.to_string())
+    }
+
+    fn parse_expr(&mut self) -> Result<String, ParserError> {
+        Ok(self.parser.parse_expr()?.to_string())
+    }
+
+    /// Parse a string, numeric or boolean literal into its string representation
+    fn parse_literal(&mut self) -> Result<String, ParserError> {
+        match self.parser.next_token().token {
+            Token::SingleQuotedString(value) => Ok(value),
+            Token::Number(value, _) => Ok(value),
+            Token::Minus => match self.parser.next_token().token {
+                Token::Number(value, _) => Ok(format!("-{value}")),
+                other => parser_err(format!("Expected number, found: {other}")),
+            },
+            Token::Word(w)
+                if w.quote_style.is_none()
+                    && (w.value.eq_ignore_ascii_case("true")
+                        || w.value.eq_ignore_ascii_case("false")) =>
+            {
+                Ok(w.value.to_lowercase())
+            }
+            other => parser_err(format!("Expected literal, found: {other}")),
+        }
+    }
+
+    /// Parse an optional `[AS] alias`, a word equal to `next_keyword` is not taken as alias
+    fn parse_alias(&mut self, next_keyword: &str) -> Result<Option<String>, ParserError> {
+        if self.parse_word("AS") {
+            return self.parse_identifier().map(Some);
+        }
+        match self.parser.peek_token().token {
+            Token::Word(w)
+                if w.quote_style.is_some() || !w.value.eq_ignore_ascii_case(next_keyword) =>
+            {
+                self.parser.next_token();
+                Ok(Some(w.value))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_where(&mut self) -> Result<Option<String>, ParserError> {
+        if self.parse_word("WHERE") {
+            Ok(Some(self.parse_expr()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parse `col = expr [, ...]`
+    fn parse_assignments(&mut self) -> Result<Vec<Assignment>, ParserError> {
+        let mut assignments = Vec::new();
+        loop {
+            let column = self.parse_column()?;
+            self.parser.expect_token(&Token::Eq)?;
+            assignments.push((column, self.parse_expr()?));
+            if !self.parser.consume_token(&Token::Comma) {
+                return Ok(assignments);
+            }
+        }
+    }
+
+    /// Parse a parenthesized, comma separated list
+    fn parse_parenthesized<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParserError>,
+    ) -> Result<Vec<T>, ParserError> {
+        self.parser.expect_token(&Token::LParen)?;
+        let mut items = vec![item(self)?];
+        while self.parser.consume_token(&Token::Comma) {
+            items.push(item(self)?);
+        }
+        self.parser.expect_token(&Token::RParen)?;
+        Ok(items)
+    }
+
+    fn parse_statement(&mut self) -> Result<DeltaStatement, ParserError> {
+        if self.parse_word("UPDATE") {
+            self.parse_update()
+        } else if self.parse_word("DELETE") {
+            self.parse_delete()
+        } else if self.parse_word("MERGE") {
+            self.parse_merge()
+        } else if self.parse_word("OPTIMIZE") {
+            self.parse_optimize()
+        } else if self.parse_word("VACUUM") {
+            self.parse_vacuum()
+        } else if self.parse_word("ALTER") {
+            self.parse_alter()
+        } else {
+            self.expected("UPDATE, DELETE, MERGE, OPTIMIZE, VACUUM or ALTER")
+        }
+    }
+
+    fn parse_update(&mut self) -> Result<DeltaStatement, ParserError> {
+        self.parser.parse_object_name(false)?;
+        self.expect_word("SET")?;
+        let assignments = self.parse_assignments()?;
+        let predicate = self.parse_where()?;
+        Ok(DeltaStatement::Update {
+            assignments,
+            predicate,
+        })
+    }
+
+    fn parse_delete(&mut self) -> Result<DeltaStatement, ParserError> {
+        self.expect_word("FROM")?;
+        self.parser.parse_object_name(false)?;
+        let predicate = self.parse_where()?;
+        Ok(DeltaStatement::Delete { predicate })
+    }
+
+    fn parse_merge(&mut self) -> Result<DeltaStatement, ParserError> {
+        self.expect_word("INTO")?;
+        let target = self.parser.parse_object_name(false)?;
+        let target_alias = self
+            .parse_alias("USING")?
+            .unwrap_or_else(|| base_name(&target));
+        self.expect_word("USING")?;
+        let source = self.parser.parse_object_name(false)?;
+        let source_alias = self
+            .parse_alias("ON")?
+            .unwrap_or_else(|| base_name(&source));
+        let source = source.to_string();
+        self.expect_word("ON")?;
+        let predicate = self.parse_expr()?;
+
+        let mut clauses = Vec::new();
+        while self.parse_word("WHEN") {
+            clauses.push(self.parse_merge_clause()?);
+        }
+        if clauses.is_empty() {
+            return self.expected("WHEN");
+        }
+        Ok(DeltaStatement::Merge(MergeStatement {
+            target_alias,
+            source,
+            source_alias,
+            predicate,
+            clauses,
+        }))
+    }
+
+    fn parse_merge_clause(&mut self) -> Result<MergeClause, ParserError> {
+        let kind = if self.parse_word("MATCHED") {
+            MergeClauseKind::Matched
+        } else {
+            self.expect_word("NOT")?;
+            self.expect_word("MATCHED")?;
+            if !self.parse_word("BY") {
+                MergeClauseKind::NotMatched
+            } else if self.parse_word("SOURCE") {
+                MergeClauseKind::NotMatchedBySource
+            } else {
+                self.expect_word("TARGET")?;
+                MergeClauseKind::NotMatched
+            }
+        };
+        let predicate = if self.parse_word("AND") {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+        self.expect_word("THEN")?;
+
+        let action = match kind {
+            MergeClauseKind::Matched | MergeClauseKind::NotMatchedBySource => {
+                if self.parse_word("UPDATE") {
+                    self.expect_word("SET")?;
+                    MergeAction::Update(self.parse_assignments()?)
+                } else if self.parse_word("DELETE") {
+                    MergeAction::Delete
+                } else {
+                    return self.expected("UPDATE or DELETE");
+                }
+            }
+            MergeClauseKind::NotMatched => {
+                self.expect_word("INSERT")?;
+                let columns = self.parse_parenthesized(Self::parse_column)?;
+                self.expect_word("VALUES")?;
+                let values = self.parse_parenthesized(Self::parse_expr)?;
+                if columns.len() != values.len() {
+                    return parser_err(format!(
+                        "INSERT lists {} columns but {} values",
+                        columns.len(),
+                        values.len()
+                    ));
+                }
+                MergeAction::Insert(columns.into_iter().zip(values).collect())
+            }
+        };
+        Ok(MergeClause {
+            kind,
+            predicate,
+            action,
+        })
+    }
+
+    fn parse_optimize(&mut self) -> Result<DeltaStatement, ParserError> {
+        self.parser.parse_object_name(false)?;
+        let mut filters = Vec::new();
+        if self.parse_word("WHERE") {
+            loop {
+                let key = self.parse_identifier()?;
+                let op = match self.parser.next_token().token {
+                    Token::Eq => "=",
+                    Token::Neq => "!=",
+                    Token::Gt => ">",
+                    Token::GtEq => ">=",
+                    Token::Lt => "<",
+                    Token::LtEq => "<=",
+                    other => {
+                        return parser_err(format!("Expected comparison operator, found: {other}"))
+                    }
+                };
+                let value = self.parse_literal()?;
+                let filter = PartitionFilter::try_from((key.as_str(), op, value.as_str()))
+                    .map_err(|err| ParserError::ParserError(err.to_string()))?;
+                filters.push(filter);
+                if !self.parse_word("AND") {
+                    break;
+                }
+            }
+        }
+
+        let mut zorder_columns = Vec::new();
+        if self.parse_word("ZORDER") {
+            self.expect_word("BY")?;
+            zorder_columns = if self.parser.peek_token().token == Token::LParen {
+                self.parse_parenthesized(Self::parse_identifier)?
+            } else {
+                let mut columns = vec![self.parse_identifier()?];
+                while self.parser.consume_token(&Token::Comma) {
+                    columns.push(self.parse_identifier()?);
+                }
+                columns
+            };
+        }
+        Ok(DeltaStatement::Optimize {
+            filters,
+            zorder_columns,
+        })
+    }
+
+    fn parse_vacuum(&mut self) -> Result<DeltaStatement, ParserError> {
+        self.parser.parse_object_name(false)?;
+        let retention_hours = if self.parse_word("RETAIN") {
+            let hours = match self.parser.next_token().token {
+                Token::Number(value, _) => value.parse::<i64>().map_err(|_| {
+                    ParserError::ParserError(format!("Invalid retention hours: {value}"))
+                })?,
+                other => return parser_err(format!("Expected number, found: {other}")),
+            };
+            self.expect_word("HOURS")?;
+            Some(hours)
+        } else {
+            None
+        };
+        let dry_run = if self.parse_word("DRY") {
+            self.expect_word("RUN")?;
+            true
+        } else {
+            false
+        };
+        Ok(DeltaStatement::Vacuum {
+            retention_hours,
+            dry_run,
+        })
+    }
+
+    fn parse_alter(&mut self) -> Result<DeltaStatement, ParserError> {
+        self.expect_word("TABLE")?;
+        self.parser.parse_object_name(false)?;
+        if self.parse_word("SET") {
+            self.expect_word("TBLPROPERTIES")?;
+            let properties = self.parse_parenthesized(|parser| {
+                let key = match parser.parser.peek_token().token {
+                    Token::SingleQuotedString(key) => {
+                        parser.parser.next_token();
+                        key
+                    }
+                    _ => parser.parse_column()?,
+                };
+                parser.parser.expect_token(&Token::Eq)?;
+                Ok((key, parser.parse_literal()?))
+            })?;
+            Ok(DeltaStatement::SetProperties(
+                properties.into_iter().collect(),
+            ))
+        } else if self.parse_word("ADD") {
+            self.expect_word("CONSTRAINT")?;
+            let name = self.parse_identifier()?;
+            self.expect_word("CHECK")?;
+            self.parser.expect_token(&Token::LParen)?;
+            let expr = self.parse_expr()?;
+            self.parser.expect_token(&Token::RParen)?;
+            Ok(DeltaStatement::AddConstraint { name, expr })
+        } else if self.parse_word("DROP") {
+            self.expect_word("CONSTRAINT")?;
+            let if_exists = if self.parse_word("IF") {
+                self.expect_word("EXISTS")?;
+                true
+            } else {
+                false
+            };
+            let name = self.parse_identifier()?;
+            Ok(DeltaStatement::DropConstraint { name, if_exists })
+        } else {
+            self.expected("SET, ADD or DROP")
+        }
+    }
+}
+
+async fn execute_merge(
+    ops: DeltaOps,
+    merge: MergeStatement,
+    state: Option<SessionState>,
+) -> DeltaResult<(DeltaTable, SqlMetrics)> {
+    let state = state.ok_or_else(|| {
+        DeltaTableError::Generic(
+            "MERGE statements require a session state with the source table registered".to_string(),
+        )
+    })?;
+    let source = SessionContext::new_with_state(state.clone())
+        .table(merge.source.as_str())
+        .await?;
+
+    let mut builder = ops
+        .merge(source, merge.predicate)
+        .with_session_state(state)
+        .with_target_alias(merge.target_alias)
+        .with_source_alias(merge.source_alias);
+    for clause in merge.clauses {
+        let predicate = clause.predicate;
+        builder = match (clause.kind, clause.action) {
+            (MergeClauseKind::Matched, MergeAction::Update(assignments)) => builder
+                .when_matched_update(|mut update| {
+                    if let Some(predicate) = predicate {
+                        update = update.predicate(predicate);
+                    }
+                    assignments
+                        .into_iter()
+                        .fold(update, |update, (column, expr)| update.update(column, expr))
+                })?,
+            (MergeClauseKind::Matched, MergeAction::Delete) => {
+                builder.when_matched_delete(|delete| match predicate {
+                    Some(predicate) => delete.predicate(predicate),
+                    None => delete,
+                })?
+            }
+            (MergeClauseKind::NotMatched, MergeAction::Insert(assignments)) => builder
+                .when_not_matched_insert(|mut insert| {
+                    if let Some(predicate) = predicate {
+                        insert = insert.predicate(predicate);
+                    }
+                    assignments
+                        .into_iter()
+                        .fold(insert, |insert, (column, expr)| insert.set(column, expr))
+                })?,
+            (MergeClauseKind::NotMatchedBySource, MergeAction::Update(assignments)) => builder
+                .when_not_matched_by_source_update(|mut update| {
+                    if let Some(predicate) = predicate {
+                        update = update.predicate(predicate);
+                    }
+                    assignments
+                        .into_iter()
+                        .fold(update, |update, (column, expr)| update.update(column, expr))
+                })?,
+            (MergeClauseKind::NotMatchedBySource, MergeAction::Delete) => builder
+                .when_not_matched_by_source_delete(|delete| match predicate {
+                    Some(predicate) => delete.predicate(predicate),
+                    None => delete,
+                })?,
+            (kind, action) => {
+                return Err(DeltaTableError::Generic(format!(
+                    "Unsupported merge clause {kind:?} with action {action:?}"
+                )))
+            }
+        };
+    }
+    let (table, metrics) = builder.await?;
+    Ok((table, SqlMetrics::Merge(metrics)))
+}
+
+async fn execute(
+    ops: DeltaOps,
+    statement: DeltaStatement,
+    state: Option<SessionState>,
+) -> DeltaResult<(DeltaTable, SqlMetrics)> {
+    match statement {
+        DeltaStatement::Update {
+            assignments,
+            predicate,
+        } => {
+            let mut builder = ops.update();
+            if let Some(predicate) = predicate {
+                builder = builder.with_predicate(predicate);
+            }
+            for (column, expr) in assignments {
+                builder = builder.with_update(column, expr);
+            }
+            if let Some(state) = state {
+                builder = builder.with_session_state(state);
+            }
+            let (table, metrics) = builder.await?;
+            Ok((table, SqlMetrics::Update(metrics)))
+        }
+        DeltaStatement::Delete { predicate } => {
+            let mut builder = ops.delete();
+            if let Some(predicate) = predicate {
+                builder = builder.with_predicate(predicate);
+            }
+            if let Some(state) = state {
+                builder = builder.with_session_state(state);
+            }
+            let (table, metrics) = builder.await?;
+            Ok((table, SqlMetrics::Delete(metrics)))
+        }
+        DeltaStatement::Merge(merge) => execute_merge(ops, merge, state).await,
+        DeltaStatement::Optimize {
+            filters,
+            zorder_columns,
+        } => {
+            let optimize_type = if zorder_columns.is_empty() {
+                OptimizeType::Compact
+            } else {
+                OptimizeType::ZOrder(zorder_columns)
+            };
+            let (table, metrics) = ops
+                .optimize()
+                .with_type(optimize_type)
+                .with_filters(&filters)
+                .await?;
+            Ok((table, SqlMetrics::Optimize(metrics)))
+        }
+        DeltaStatement::Vacuum {
+            retention_hours,
+            dry_run,
+        } => {
+            let mut builder = ops.vacuum().with_dry_run(dry_run);
+            if let Some(hours) = retention_hours {
+                builder = builder.with_retention_period(Duration::hours(hours));
+            }
+            let (table, metrics) = builder.await?;
+            Ok((table, SqlMetrics::Vacuum(metrics)))
+        }
+        DeltaStatement::SetProperties(properties) => {
+            let table = ops.set_tbl_properties().with_properties(properties).await?;
+            Ok((table, SqlMetrics::Alter))
+        }
+        DeltaStatement::AddConstraint { name, expr } => {
+            let mut builder = ops.add_constraint().with_constraint(name, expr);
+            if let Some(state) = state {
+                builder = builder.with_session_state(state);
+            }
+            Ok((builder.await?, SqlMetrics::Alter))
+        }
+        DeltaStatement::DropConstraint { name, if_exists } => {
+            let table = ops
+                .drop_constraints()
+                .with_constraint(name)
+                .with_raise_if_not_exists(!if_exists)
+                .await?;
+            Ok((table, SqlMetrics::Alter))
+        }
+    }
+}
+
+impl std::future::IntoFuture for SqlBuilder {
+    type Output = DeltaResult<(DeltaTable, SqlMetrics)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let statement = parse_statement(&this.sql)?;
+            let ops = DeltaOps(DeltaTable::new_with_state(this.log_store, this.snapshot));
+            execute(ops, statement, this.state).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::record_batch::RecordBatch;
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+    use crate::writer::test_utils::datafusion::{get_data, write_batch};
+    use crate::writer::test_utils::{get_delta_schema, get_record_batch};
+    use crate::PartitionValue;
+
+    async fn setup_table() -> DeltaTable {
+        let table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_partition_columns(["modified"])
+            .await
+            .unwrap();
+        write_batch(table, get_record_batch(None, false)).await
+    }
+
+    #[test]
+    fn test_parse_update_and_delete() {
+        assert_eq!(
+            parse_statement("UPDATE t SET value = value + 1, id = 'C' WHERE id = 'A';").unwrap(),
+            DeltaStatement::Update {
+                assignments: vec![
+                    ("value".to_string(), "value + 1".to_string()),
+                    ("id".to_string(), "'C'".to_string()),
+                ],
+                predicate: Some("id = 'A'".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_statement("delete from t").unwrap(),
+            DeltaStatement::Delete { predicate: None }
+        );
+        assert!(parse_statement("DELETE FROM t WHERE").is_err());
+        assert!(parse_statement("DELETE FROM t; DELETE FROM t").is_err());
+        assert!(parse_statement("SELECT * FROM t").is_err());
+    }
+
+    #[test]
+    fn test_parse_merge() {
+        let sql = "MERGE INTO t AS target USING src source ON target.id = source.id \
+            WHEN MATCHED AND source.value < 0 THEN DELETE \
+            WHEN MATCHED THEN UPDATE SET value = source.value \
+            WHEN NOT MATCHED THEN INSERT (id, value) VALUES (source.id, source.value) \
+            WHEN NOT MATCHED BY SOURCE THEN DELETE";
+        assert_eq!(
+            parse_statement(sql).unwrap(),
+            DeltaStatement::Merge(MergeStatement {
+                target_alias: "target".to_string(),
+                source: "src".to_string(),
+                source_alias: "source".to_string(),
+                predicate: "target.id = source.id".to_string(),
+                clauses: vec![
+                    MergeClause {
+                        kind: MergeClauseKind::Matched,
+                        predicate: Some("source.value < 0".to_string()),
+                        action: MergeAction::Delete,
+                    },
+                    MergeClause {
+                        kind: MergeClauseKind::Matched,
+                        predicate: None,
+                        action: MergeAction::Update(vec![(
+                            "value".to_string(),
+                            "source.value".to_string()
+                        )]),
+                    },
+                    MergeClause {
+                        kind: MergeClauseKind::NotMatched,
+                        predicate: None,
+                        action: MergeAction::Insert(vec![
+                            ("id".to_string(), "source.id".to_string()),
+                            ("value".to_string(), "source.value".to_string()),
+                        ]),
+                    },
+                    MergeClause {
+                        kind: MergeClauseKind::NotMatchedBySource,
+                        predicate: None,
+                        action: MergeAction::Delete,
+                    },
+                ],
+            })
+        );
+        let sql = "MERGE INTO db.t USING db.src ON t.id = src.id WHEN MATCHED THEN DELETE";
+        let DeltaStatement::Merge(merge) = parse_statement(sql).unwrap() else {
+            panic!("Expected a merge statement");
+        };
+        assert_eq!(merge.target_alias, "t");
+        assert_eq!(merge.source, "db.src");
+        assert_eq!(merge.source_alias, "src");
+        assert!(parse_statement("MERGE INTO t USING s ON t.id = s.id").is_err());
+        assert!(parse_statement(
+            "MERGE INTO t USING s ON t.id = s.id WHEN NOT MATCHED THEN INSERT (id) VALUES (1, 2)"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_maintenance() {
+        assert_eq!(
+            parse_statement("OPTIMIZE t WHERE modified = '2021-02-01' ZORDER BY (id, value)")
+                .unwrap(),
+            DeltaStatement::Optimize {
+                filters: vec![PartitionFilter {
+                    key: "modified".to_string(),
+                    value: PartitionValue::Equal("2021-02-01".to_string()),
+                }],
+                zorder_columns: vec!["id".to_string(), "value".to_string()],
+            }
+        );
+        assert_eq!(
+            parse_statement("VACUUM t RETAIN 200 HOURS DRY RUN").unwrap(),
+            DeltaStatement::Vacuum {
+                retention_hours: Some(200),
+                dry_run: true,
+            }
+        );
+        assert_eq!(
+            parse_statement("ALTER TABLE t SET TBLPROPERTIES ('delta.appendOnly' = true)").unwrap(),
+            DeltaStatement::SetProperties(HashMap::from([(
+                "delta.appendOnly".to_string(),
+                "true".to_string()
+            )]))
+        );
+        assert_eq!(
+            parse_statement("ALTER TABLE t ADD CONSTRAINT positive CHECK (value > 0)").unwrap(),
+            DeltaStatement::AddConstraint {
+                name: "positive".to_string(),
+                expr: "value > 0".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_statement("ALTER TABLE t DROP CONSTRAINT IF EXISTS positive").unwrap(),
+            DeltaStatement::DropConstraint {
+                name: "positive".to_string(),
+                if_exists: true,
+            }
+        );
+        assert!(parse_statement("VACUUM t DRY").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sql_update_and_delete() {
+        let table = setup_table().await;
+
+        let (table, metrics) = DeltaOps(table)
+            .sql("UPDATE my_table SET value = 0 WHERE id = 'B'")
+            .await
+            .unwrap();
+        let SqlMetrics::Update(metrics) = metrics else {
+            panic!("Expected update metrics, got {metrics:?}");
+        };
+        assert_eq!(metrics.num_updated_rows, 4);
+
+        let (table, metrics) = DeltaOps(table)
+            .sql("DELETE FROM my_table WHERE value = 0")
+            .await
+            .unwrap();
+        let SqlMetrics::Delete(metrics) = metrics else {
+            panic!("Expected delete metrics, got {metrics:?}");
+        };
+        assert_eq!(metrics.num_deleted_rows, Some(4));
+        assert_eq!(table.version(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_sql_merge() {
+        let table = setup_table().await;
+
+        let ctx = SessionContext::new();
+        let source = RecordBatch::try_new(
+            crate::writer::test_utils::get_arrow_schema(&None),
+            vec![
+                std::sync::Arc::new(arrow::array::StringArray::from(vec!["A", "C"])),
+                std::sync::Arc::new(arrow::array::Int32Array::from(vec![1, 100])),
+                std::sync::Arc::new(arrow::array::StringArray::from(vec![
+                    "2021-02-02",
+                    "2021-02-02",
+                ])),
+            ],
+        )
+        .unwrap();
+        ctx.register_batch("source", source).unwrap();
+
+        let (table, metrics) = DeltaOps(table)
+            .sql(
+                "MERGE INTO t USING source s ON t.value = s.value \
+                WHEN MATCHED THEN DELETE \
+                WHEN NOT MATCHED THEN INSERT (id, value, modified) \
+                VALUES (s.id, s.value, s.modified)",
+            )
+            .with_session_state(ctx.state())
+            .await
+            .unwrap();
+        let SqlMetrics::Merge(metrics) = metrics else {
+            panic!("Expected merge metrics, got {metrics:?}");
+        };
+        assert_eq!(metrics.num_target_rows_deleted, 1);
+        assert_eq!(metrics.num_target_rows_inserted, 1);
+
+        let expected = vec![
+            "+----+-------+------------+",
+            "| id | value | modified   |",
+            "+----+-------+------------+",
+            "| A  | 3     | 2021-02-02 |",
+            "| A  | 5     | 2021-02-01 |",
+            "| A  | 6     | 2021-02-01 |",
+            "| A  | 7     | 2021-02-01 |",
+            "| A  | 10    | 2021-02-01 |",
+            "| A  | 11    | 2021-02-01 |",
+            "| B  | 2     | 2021-02-02 |",
+            "| B  | 4     | 2021-02-01 |",
+            "| B  | 8     | 2021-02-01 |",
+            "| B  | 9     | 2021-02-01 |",
+            "| C  | 100   | 2021-02-02 |",
+            "+----+-------+------------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &get_data(&table).await);
+    }
+
+    #[tokio::test]
+    async fn test_sql_merge_requires_session_state() {
+        let table = setup_table().await;
+        let err = DeltaOps(table)
+            .sql("MERGE INTO t USING source ON t.id = source.id WHEN MATCHED THEN DELETE")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("session state"));
+    }
+
+    #[tokio::test]
+    async fn test_sql_alter_table() {
+        let table = setup_table().await;
+        let (table, _) = DeltaOps(table)
+            .sql("ALTER TABLE t ADD CONSTRAINT positive CHECK (value > 0)")
+            .await
+            .unwrap();
+        assert_eq!(
+            table
+                .metadata()
+                .unwrap()
+                .configuration
+                .get("delta.constraints.positive"),
+            Some(&Some("value > 0".to_string()))
+        );
+        let (table, _) = DeltaOps(table)
+            .sql("ALTER TABLE t DROP CONSTRAINT positive")
+            .await
+            .unwrap();
+        assert!(!table
+            .metadata()
+            .unwrap()
+            .configuration
+            .contains_key("delta.constraints.positive"));
+    }
+}
@@ -0,0 +1,697 @@
+//! Add or drop table features
+//!
+//! Adding a feature upgrades the protocol of the table to writer version 7, and to reader
+//! version 3 for features readers must support as well. The features implied by the legacy
+//! protocol versions are listed explicitly when upgrading.
+//!
+//! Dropping a feature follows the feature-drop protocol. The feature must not be in use by the
+//! current version of the table, e.g. the property enabling it must be disabled first. Writer
+//! features are then removed from the protocol right away. Readers of older versions still need
+//! to support reader-writer features, so these can only be dropped when truncating the history
+//! of the table: the log is checkpointed and the commits older than the truncation retention are
+//! removed, after which none of the remaining commits may use the feature.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table("../path/to/table")?;
+//! let table = DeltaOps(table).add_feature(TableFeature::ChangeDataFeed).await?;
+//! ````
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, Utc};
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+
+use super::transaction::{CommitBuilder, CommitProperties, PROTOCOL};
+use crate::kernel::{
+    Action, ColumnMetadataKey, Metadata, Protocol, ReaderFeatures, StructTypeExt, WriterFeatures,
+};
+use crate::logstore::{self, extract_version_from_filename, LogStoreRef};
+use crate::protocol::checkpoints::{cleanup_expired_logs_for_snapshot, create_checkpoint_for};
+use crate::protocol::DeltaOperation;
+use crate::table::state::DeltaTableState;
+use crate::DeltaTable;
+use crate::{DeltaResult, DeltaTableError};
+
+/// Default retention of the history kept when truncating it to drop a feature
+pub const DEFAULT_TRUNCATE_HISTORY_RETENTION_HOURS: i64 = 24;
+
+/// A table feature which can be added to or dropped from a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TableFeature {
+    /// Append Only Tables
+    AppendOnly,
+    /// Table invariants
+    Invariants,
+    /// Check constraints on columns
+    CheckConstraints,
+    /// CDF on a table
+    ChangeDataFeed,
+    /// Columns with generated values
+    GeneratedColumns,
+    /// Mapping of one column to another
+    ColumnMapping,
+    /// ID Columns
+    IdentityColumns,
+    /// Deletion vectors for merge, update, delete
+    DeletionVectors,
+    /// Row tracking on tables
+    RowTracking,
+    /// timestamps without timezone support
+    TimestampWithoutTimezone,
+    /// domain specific metadata
+    DomainMetadata,
+    /// version 2 of checkpointing
+    V2Checkpoint,
+    /// Iceberg compatibility support
+    IcebergCompatV1,
+    /// Protection of the checkpoints required by readers of older versions
+    CheckpointProtection,
+}
+
+impl TableFeature {
+    /// The reader feature of features that readers must support as well
+    pub fn reader_feature(&self) -> Option<ReaderFeatures> {
+        match self {
+            Self::ColumnMapping => Some(ReaderFeatures::ColumnMapping),
+            Self::DeletionVectors => Some(ReaderFeatures::DeletionVectors),
+            Self::TimestampWithoutTimezone => Some(ReaderFeatures::TimestampWithoutTimezone),
+            Self::V2Checkpoint => Some(ReaderFeatures::V2Checkpoint),
+            _ => None,
+        }
+    }
+
+    /// The writer feature
+    pub fn writer_feature(&self) -> WriterFeatures {
+        match self {
+            Self::AppendOnly => WriterFeatures::AppendOnly,
+            Self::Invariants => WriterFeatures::Invariants,
+            Self::CheckConstraints => WriterFeatures::CheckConstraints,
+            Self::ChangeDataFeed => WriterFeatures::ChangeDataFeed,
+            Self::GeneratedColumns => WriterFeatures::GeneratedColumns,
+            Self::ColumnMapping => WriterFeatures::ColumnMapping,
+            Self::IdentityColumns => WriterFeatures::IdentityColumns,
+            Self::DeletionVectors => WriterFeatures::DeletionVectors,
+            Self::RowTracking => WriterFeatures::RowTracking,
+            Self::TimestampWithoutTimezone => WriterFeatures::TimestampWithoutTimezone,
+            Self::DomainMetadata => WriterFeatures::DomainMetadata,
+            Self::V2Checkpoint => WriterFeatures::V2Checkpoint,
+            Self::IcebergCompatV1 => WriterFeatures::IcebergCompatV1,
+            Self::CheckpointProtection => WriterFeatures::CheckpointProtection,
+        }
+    }
+}
+
+impl FromStr for TableFeature {
+    type Err = DeltaTableError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match WriterFeatures::from(s) {
+            WriterFeatures::AppendOnly => Ok(Self::AppendOnly),
+            WriterFeatures::Invariants => Ok(Self::Invariants),
+            WriterFeatures::CheckConstraints => Ok(Self::CheckConstraints),
+            WriterFeatures::ChangeDataFeed => Ok(Self::ChangeDataFeed),
+            WriterFeatures::GeneratedColumns => Ok(Self::GeneratedColumns),
+            WriterFeatures::ColumnMapping => Ok(Self::ColumnMapping),
+            WriterFeatures::IdentityColumns => Ok(Self::IdentityColumns),
+            WriterFeatures::DeletionVectors => Ok(Self::DeletionVectors),
+            WriterFeatures::RowTracking => Ok(Self::RowTracking),
+            WriterFeatures::TimestampWithoutTimezone => Ok(Self::TimestampWithoutTimezone),
+            WriterFeatures::DomainMetadata => Ok(Self::DomainMetadata),
+            WriterFeatures::V2Checkpoint => Ok(Self::V2Checkpoint),
+            WriterFeatures::IcebergCompatV1 => Ok(Self::IcebergCompatV1),
+            WriterFeatures::CheckpointProtection => Ok(Self::CheckpointProtection),
+            WriterFeatures::Other(name) => Err(DeltaTableError::Generic(format!(
+                "Unknown table feature: {name}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for TableFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.writer_feature().as_ref())
+    }
+}
+
+/// Add table features to a table
+pub struct AddTableFeatureBuilder {
+    /// A snapshot of the table's state
+    snapshot: DeltaTableState,
+    /// Features to add
+    features: Vec<TableFeature>,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Additional information to add to the commit
+    commit_properties: CommitProperties,
+}
+
+impl super::Operation<()> for AddTableFeatureBuilder {}
+
+impl AddTableFeatureBuilder {
+    /// Create a new builder
+    pub fn new(log_store: LogStoreRef, snapshot: DeltaTableState) -> Self {
+        Self {
+            snapshot,
+            features: vec![],
+            log_store,
+            commit_properties: CommitProperties::default(),
+        }
+    }
+
+    /// Specify a feature to be added
+    pub fn with_feature(mut self, feature: TableFeature) -> Self {
+        self.features.push(feature);
+        self
+    }
+
+    /// Additional metadata to be added to commit info
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+}
+
+/// Writer features implied by a legacy writer version
+pub(crate) fn legacy_writer_features(min_writer_version: i32) -> HashSet<WriterFeatures> {
+    let mut features = HashSet::new();
+    if min_writer_version >= 2 {
+        features.extend([WriterFeatures::AppendOnly, WriterFeatures::Invariants]);
+    }
+    if min_writer_version >= 3 {
+        features.insert(WriterFeatures::CheckConstraints);
+    }
+    if min_writer_version >= 4 {
+        features.extend([
+            WriterFeatures::ChangeDataFeed,
+            WriterFeatures::GeneratedColumns,
+        ]);
+    }
+    if min_writer_version >= 5 {
+        features.insert(WriterFeatures::ColumnMapping);
+    }
+    if min_writer_version >= 6 {
+        features.insert(WriterFeatures::IdentityColumns);
+    }
+    features
+}
+
+/// Upgrade `protocol` to support `features`, keeping the features implied by its versions
+fn add_features_to_protocol(protocol: &Protocol, features: &[TableFeature]) -> Protocol {
+    let mut protocol = protocol.clone();
+    if protocol.min_writer_version < 7 {
+        protocol.writer_features = Some(legacy_writer_features(protocol.min_writer_version));
+        protocol.min_writer_version = 7;
+    }
+    let reader_features = features
+        .iter()
+        .filter_map(|feature| feature.reader_feature())
+        .collect::<Vec<_>>();
+    if !reader_features.is_empty() && protocol.min_reader_version < 3 {
+        let mut legacy_reader_features = HashSet::new();
+        if protocol.min_reader_version >= 2 {
+            legacy_reader_features.insert(ReaderFeatures::ColumnMapping);
+        }
+        protocol.reader_features = Some(legacy_reader_features);
+        protocol.min_reader_version = 3;
+    }
+
+    protocol
+        .writer_features
+        .get_or_insert_with(HashSet::new)
+        .extend(features.iter().map(|feature| feature.writer_feature()));
+    if !reader_features.is_empty() {
+        protocol
+            .reader_features
+            .get_or_insert_with(HashSet::new)
+            .extend(reader_features);
+    }
+    protocol
+}
+
+impl std::future::IntoFuture for AddTableFeatureBuilder {
+    type Output = DeltaResult<DeltaTable>;
+
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            if this.features.is_empty() {
+                return Err(DeltaTableError::Generic("No features provided".to_string()));
+            }
+
+            let protocol = add_features_to_protocol(this.snapshot.protocol(), &this.features);
+            if &protocol == this.snapshot.protocol() {
+                return Ok(DeltaTable::new_with_state(this.log_store, this.snapshot));
+            }
+
+            let operation = DeltaOperation::AddFeature {
+                name: this.features.iter().map(|f| f.to_string()).collect(),
+            };
+            let commit = CommitBuilder::from(this.commit_properties)
+                .with_actions(vec![Action::Protocol(protocol)])
+                .build(Some(&this.snapshot), this.log_store.clone(), operation)
+                .await?;
+
+            Ok(DeltaTable::new_with_state(
+                this.log_store,
+                commit.snapshot(),
+            ))
+        })
+    }
+}
+
+/// Drop a table feature from a table
+pub struct DropTableFeatureBuilder {
+    /// A snapshot of the table's state
+    snapshot: DeltaTableState,
+    /// Feature to drop
+    feature: Option<TableFeature>,
+    /// Raise if the feature is not supported by the table
+    raise_if_not_exists: bool,
+    /// Truncate the history of the table, required to drop reader-writer features
+    truncate_history: bool,
+    /// Retention of the history kept when truncating it
+    truncate_history_retention: Duration,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Additional information to add to the commit
+    commit_properties: CommitProperties,
+}
+
+impl super::Operation<()> for DropTableFeatureBuilder {}
+
+impl DropTableFeatureBuilder {
+    /// Create a new builder
+    pub fn new(log_store: LogStoreRef, snapshot: DeltaTableState) -> Self {
+        Self {
+            snapshot,
+            feature: None,
+            raise_if_not_exists: true,
+            truncate_history: false,
+            truncate_history_retention: Duration::hours(DEFAULT_TRUNCATE_HISTORY_RETENTION_HOURS),
+            log_store,
+            commit_properties: CommitProperties::default(),
+        }
+    }
+
+    /// Specify the feature to be dropped
+    pub fn with_feature(mut self, feature: TableFeature) -> Self {
+        self.feature = Some(feature);
+        self
+    }
+
+    /// Specify if you want to raise if the feature is not supported by the table
+    pub fn with_raise_if_not_exists(mut self, raise: bool) -> Self {
+        self.raise_if_not_exists = raise;
+        self
+    }
+
+    /// Truncate the history of the table, which is required to drop reader-writer features
+    pub fn with_truncate_history(mut self, truncate_history: bool) -> Self {
+        self.truncate_history = truncate_history;
+        self
+    }
+
+    /// Override the retention of the history kept when truncating it
+    pub fn with_truncate_history_retention(mut self, retention: Duration) -> Self {
+        self.truncate_history_retention = retention;
+        self
+    }
+
+    /// Additional metadata to be added to commit info
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+}
+
+/// Describes how `metadata` uses `feature`, if it does
+fn metadata_usage(feature: TableFeature, metadata: &Metadata) -> DeltaResult<Option<String>> {
+    let enabled = |key: &str| {
+        metadata
+            .configuration
+            .get(key)
+            .and_then(|value| value.as_deref())
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+    };
+    let property = |key: &str| enabled(key).then(|| format!("the property {key} is enabled"));
+    let schema = metadata.schema()?;
+    let column_with = |key: ColumnMetadataKey| {
+        schema
+            .fields()
+            .find(|field| field.metadata.contains_key(key.as_ref()))
+            .map(|field| format!("the column {} has {}", field.name(), key.as_ref()))
+    };
+
+    Ok(match feature {
+        TableFeature::AppendOnly => property("delta.appendOnly"),
+        TableFeature::ChangeDataFeed => property("delta.enableChangeDataFeed"),
+        TableFeature::DeletionVectors => property("delta.enableDeletionVectors"),
+        TableFeature::RowTracking => property("delta.enableRowTracking"),
+        TableFeature::IcebergCompatV1 => property("delta.enableIcebergCompatV1"),
+        TableFeature::Invariants => {
+            (!schema.get_invariants()?.is_empty()).then(|| "the schema has invariants".to_string())
+        }
+        TableFeature::CheckConstraints => metadata
+            .configuration
+            .keys()
+            .find(|key| key.starts_with("delta.constraints."))
+            .map(|key| format!("the constraint {key} exists")),
+        TableFeature::GeneratedColumns => column_with(ColumnMetadataKey::GenerationExpression),
+        TableFeature::IdentityColumns => column_with(ColumnMetadataKey::IdentityStart),
+        TableFeature::ColumnMapping => metadata
+            .configuration
+            .get("delta.columnMapping.mode")
+            .and_then(|mode| mode.as_deref())
+            .filter(|mode| !mode.eq_ignore_ascii_case("none"))
+            .map(|mode| format!("the column mapping mode is {mode}")),
+        TableFeature::TimestampWithoutTimezone => PROTOCOL
+            .contains_timestampntz(schema.fields())
+            .then(|| "the schema has timestamp_ntz columns".to_string()),
+        TableFeature::V2Checkpoint => metadata
+            .configuration
+            .get("delta.checkpointPolicy")
+            .and_then(|policy| policy.as_deref())
+            .is_some_and(|policy| policy.eq_ignore_ascii_case("v2"))
+            .then(|| "the checkpoint policy is v2".to_string()),
+        TableFeature::CheckpointProtection => metadata
+            .configuration
+            .get("delta.requireCheckpointProtectionBeforeVersion")
+            .and_then(|version| version.as_deref())
+            .map(|version| format!("the checkpoints before version {version} are protected")),
+        TableFeature::DomainMetadata => None,
+    })
+}
+
+/// Whether the commit action `action` uses `feature`
+fn action_uses_feature(feature: TableFeature, action: &Action) -> DeltaResult<bool> {
+    Ok(match action {
+        Action::Metadata(metadata) => metadata_usage(feature, metadata)?.is_some(),
+        Action::Add(add) => {
+            feature == TableFeature::DeletionVectors && add.deletion_vector.is_some()
+        }
+        Action::Remove(remove) => {
+            feature == TableFeature::DeletionVectors && remove.deletion_vector.is_some()
+        }
+        Action::DomainMetadata(_) => feature == TableFeature::DomainMetadata,
+        _ => false,
+    })
+}
+
+/// Fail if the current version of the table uses `feature`
+fn check_not_in_use(feature: TableFeature, snapshot: &DeltaTableState) -> DeltaResult<()> {
+    let mut usage = metadata_usage(feature, snapshot.metadata())?;
+    if feature == TableFeature::DeletionVectors && usage.is_none() {
+        usage = snapshot
+            .log_data()
+            .into_iter()
+            .find(|file| file.deletion_vector().is_some())
+            .map(|file| format!("the file {} has a deletion vector", file.path()));
+    }
+    match usage {
+        Some(usage) => Err(DeltaTableError::Generic(format!(
+            "Cannot drop the table feature {feature} as it is in use: {usage}"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Remove the commits older than `retention` from the log, and fail if any of the remaining
+/// commits uses `feature`
+async fn truncate_history(
+    feature: TableFeature,
+    snapshot: &DeltaTableState,
+    log_store: &LogStoreRef,
+    retention: Duration,
+) -> DeltaResult<()> {
+    let version = snapshot.version();
+    create_checkpoint_for(version, snapshot, log_store.as_ref()).await?;
+    let cutoff_timestamp = Utc::now().timestamp_millis() - retention.num_milliseconds();
+    cleanup_expired_logs_for_snapshot(snapshot, log_store.as_ref(), cutoff_timestamp).await?;
+
+    let mut versions = log_store
+        .object_store()
+        .list(Some(log_store.log_path()))
+        .try_filter_map(|meta| async move {
+            Ok(match meta.location.extension() {
+                Some("json") => extract_version_from_filename(meta.location.as_ref()),
+                _ => None,
+            })
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+    versions.retain(|commit_version| *commit_version < version);
+    versions.sort_unstable();
+
+    for commit_version in versions {
+        let Some(bytes) = log_store.read_commit_entry(commit_version).await? else {
+            continue;
+        };
+        for action in logstore::get_actions(commit_version, bytes).await? {
+            if action_uses_feature(feature, &action)? {
+                return Err(DeltaTableError::Generic(format!(
+                    "Cannot drop the table feature {feature} as version {commit_version} uses it \
+                     and is more recent than the history retention of {} hours, retry once the \
+                     retention has passed",
+                    retention.num_hours()
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+impl std::future::IntoFuture for DropTableFeatureBuilder {
+    type Output = DeltaResult<DeltaTable>;
+
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            let feature = this
+                .feature
+                .ok_or(DeltaTableError::Generic("No feature provided".to_string()))?;
+
+            let mut protocol = this.snapshot.protocol().clone();
+            if protocol.min_writer_version < 7 {
+                return Err(DeltaTableError::Generic(format!(
+                    "Dropping table features requires writer version 7, the table uses version {}",
+                    protocol.min_writer_version
+                )));
+            }
+            let supported = protocol
+                .writer_features
+                .as_mut()
+                .is_some_and(|features| features.remove(&feature.writer_feature()));
+            if !supported {
+                if this.raise_if_not_exists {
+                    return Err(DeltaTableError::Generic(format!(
+                        "Table feature {feature} is not supported by the table"
+                    )));
+                }
+                return Ok(DeltaTable::new_with_state(this.log_store, this.snapshot));
+            }
+
+            check_not_in_use(feature, &this.snapshot)?;
+            if let Some(reader_feature) = feature.reader_feature() {
+                if !this.truncate_history {
+                    return Err(DeltaTableError::Generic(format!(
+                        "Table feature {feature} must be supported by readers, dropping it \
+                         requires truncating the history of the table"
+                    )));
+                }
+                truncate_history(
+                    feature,
+                    &this.snapshot,
+                    &this.log_store,
+                    this.truncate_history_retention,
+                )
+                .await?;
+                if let Some(features) = protocol.reader_features.as_mut() {
+                    features.remove(&reader_feature);
+                }
+            }
+
+            let operation = DeltaOperation::DropFeature {
+                name: feature.to_string(),
+                truncate_history: this.truncate_history,
+            };
+            let commit = CommitBuilder::from(this.commit_properties)
+                .with_actions(vec![Action::Protocol(protocol)])
+                .build(Some(&this.snapshot), this.log_store.clone(), operation)
+                .await?;
+
+            Ok(DeltaTable::new_with_state(
+                this.log_store,
+                commit.snapshot(),
+            ))
+        })
+    }
+}
+
+#[cfg(feature = "datafusion")]
+#[cfg(test)]
+mod tests {
+    use maplit::hashset;
+
+    use super::*;
+    use crate::kernel::{DataType, PrimitiveType, StructField, StructType};
+    use crate::operations::create::CreateBuilder;
+    use crate::writer::test_utils::create_initialized_table;
+    use crate::DeltaOps;
+
+    #[test]
+    fn test_add_features_to_protocol() {
+        let protocol =
+            add_features_to_protocol(&Protocol::new(1, 2), &[TableFeature::ChangeDataFeed]);
+        assert_eq!(protocol.min_reader_version, 1);
+        assert_eq!(protocol.min_writer_version, 7);
+        assert_eq!(protocol.reader_features, None);
+        assert_eq!(
+            protocol.writer_features,
+            Some(hashset! {
+                WriterFeatures::AppendOnly,
+                WriterFeatures::Invariants,
+                WriterFeatures::ChangeDataFeed,
+            })
+        );
+
+        let protocol =
+            add_features_to_protocol(&Protocol::new(2, 5), &[TableFeature::DeletionVectors]);
+        assert_eq!(protocol.min_reader_version, 3);
+        assert_eq!(
+            protocol.reader_features,
+            Some(hashset! {ReaderFeatures::ColumnMapping, ReaderFeatures::DeletionVectors})
+        );
+        assert!(protocol
+            .writer_features
+            .unwrap()
+            .is_superset(&legacy_writer_features(5)));
+    }
+
+    #[tokio::test]
+    async fn test_drop_writer_feature() {
+        let table = create_initialized_table(&[]).await;
+        let table = DeltaOps(table)
+            .add_feature(TableFeature::AppendOnly)
+            .await
+            .unwrap();
+        let table = DeltaOps(table)
+            .drop_feature(TableFeature::AppendOnly)
+            .await
+            .unwrap();
+        let protocol = table.protocol().unwrap();
+        assert_eq!(protocol.min_writer_version, 7);
+        assert_eq!(
+            protocol.writer_features,
+            Some(hashset! {WriterFeatures::Invariants})
+        );
+
+        let result = DeltaOps(table).drop_feature(TableFeature::AppendOnly).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_drop_feature_in_use() {
+        let table = create_initialized_table(&[]).await;
+        let table = DeltaOps(table)
+            .add_feature(TableFeature::AppendOnly)
+            .await
+            .unwrap();
+        let table = DeltaOps(table)
+            .set_tbl_properties()
+            .with_properties([("delta.appendOnly".to_string(), "true".to_string())].into())
+            .await
+            .unwrap();
+
+        let result = DeltaOps(table).drop_feature(TableFeature::AppendOnly).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_drop_reader_writer_feature() {
+        let table = create_initialized_table(&[]).await;
+        let table = DeltaOps(table)
+            .add_feature(TableFeature::TimestampWithoutTimezone)
+            .await
+            .unwrap();
+        assert_eq!(table.protocol().unwrap().min_reader_version, 3);
+
+        // reader-writer features can only be dropped by truncating the history
+        let result = DeltaOps(table.clone())
+            .drop_feature(TableFeature::TimestampWithoutTimezone)
+            .await;
+        assert!(result.is_err());
+
+        let table = DeltaOps(table)
+            .drop_feature(TableFeature::TimestampWithoutTimezone)
+            .with_truncate_history(true)
+            .with_truncate_history_retention(Duration::zero())
+            .await
+            .unwrap();
+        let protocol = table.protocol().unwrap();
+        assert_eq!(protocol.reader_features, Some(HashSet::new()));
+        assert!(!protocol
+            .writer_features
+            .as_ref()
+            .unwrap()
+            .contains(&WriterFeatures::TimestampWithoutTimezone));
+    }
+
+    #[tokio::test]
+    async fn test_drop_feature_used_in_history() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = CreateBuilder::new()
+            .with_location(table_dir.path().to_str().unwrap())
+            .with_columns(vec![
+                StructField::new("id", DataType::Primitive(PrimitiveType::Integer), true),
+                StructField::new("ts", DataType::TIMESTAMP_NTZ, true),
+            ])
+            .await
+            .unwrap();
+
+        // drop the timestamp_ntz column, so that only the history uses the feature
+        let schema = StructType::new(vec![StructField::new(
+            "id",
+            DataType::Primitive(PrimitiveType::Integer),
+            true,
+        )]);
+        let mut metadata = table.metadata().unwrap().clone();
+        metadata.schema_string = serde_json::to_string(&schema).unwrap();
+        let snapshot = table.snapshot().unwrap();
+        let commit = CommitBuilder::default()
+            .with_actions(vec![Action::Metadata(metadata)])
+            .build(
+                Some(snapshot),
+                table.log_store(),
+                DeltaOperation::SetTableProperties {
+                    properties: Default::default(),
+                },
+            )
+            .await
+            .unwrap();
+        let table = DeltaTable::new_with_state(table.log_store(), commit.snapshot());
+
+        let result = DeltaOps(table.clone())
+            .drop_feature(TableFeature::TimestampWithoutTimezone)
+            .with_truncate_history(true)
+            .await;
+        assert!(result.is_err());
+
+        let table = DeltaOps(table)
+            .drop_feature(TableFeature::TimestampWithoutTimezone)
+            .with_truncate_history(true)
+            .with_truncate_history_retention(Duration::zero())
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 2);
+    }
+}
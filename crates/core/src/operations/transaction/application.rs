@@ -6,6 +6,7 @@ mod tests {
     };
 
     #[tokio::test]
+    #[allow(deprecated)]
     async fn test_app_txn_workload() {
         // Test that the transaction ids can be read from different scenarios
         // 1. Write new table to storage
@@ -133,4 +134,27 @@ mod tests {
             "Transaction failed: Failed to commit transaction: Concurrent transaction failed."
         );
     }
+
+    #[tokio::test]
+    async fn test_app_txn_builder_shorthand() {
+        let batch = get_record_batch(None, false);
+        let table = DeltaOps::new_in_memory()
+            .write(vec![batch.clone()])
+            .with_save_mode(SaveMode::ErrorIfExists)
+            .with_application_transaction("my-app", 1)
+            .await
+            .unwrap();
+        assert_eq!(table.get_app_transaction_version_for("my-app"), Some(1));
+        assert_eq!(table.get_app_transaction_version_for("other-app"), None);
+
+        let table = DeltaOps::from(table)
+            .write(vec![batch])
+            .with_application_transaction("my-app", 2)
+            .with_application_transaction("other-app", 7)
+            .await
+            .unwrap();
+        assert_eq!(table.get_app_transaction_version_for("my-app"), Some(2));
+        assert_eq!(table.get_app_transaction_version_for("other-app"), Some(7));
+        assert_eq!(table.get_app_transactions().len(), 2);
+    }
 }
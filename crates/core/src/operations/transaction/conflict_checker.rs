@@ -358,7 +358,7 @@ impl<'a> ConflictChecker<'a> {
         let isolation_level = operation
             .and_then(|op| {
                 if can_downgrade_to_snapshot_isolation(
-                    &winning_commit_summary.actions,
+                    transaction_info.actions,
                     op,
                     &transaction_info
                         .read_snapshot
@@ -624,8 +624,11 @@ pub(super) fn can_downgrade_to_snapshot_isolation<'a>(
     let mut has_non_file_actions = false;
     for action in actions {
         match action {
-            Action::Add(act) if act.data_change => data_changed = true,
-            Action::Remove(rem) if rem.data_change => data_changed = true,
+            Action::Add(act) => data_changed |= act.data_change,
+            Action::Remove(rem) => data_changed |= rem.data_change,
+            Action::Cdc(cdc) => data_changed |= cdc.data_change,
+            // commit info is only provenance and does not take part in conflict detection
+            Action::CommitInfo(_) => (),
             _ => has_non_file_actions = true,
         }
     }
@@ -638,7 +641,8 @@ pub(super) fn can_downgrade_to_snapshot_isolation<'a>(
     match isolation_level {
         IsolationLevel::Serializable => !data_changed,
         IsolationLevel::WriteSerializable => !data_changed && !operation.changes_data(),
-        IsolationLevel::SnapshotIsolation => false, // this case should never happen, since spanpshot isolation canot be configured on table
+        // the table is already configured for the weakest level, there is nothing to downgrade
+        IsolationLevel::SnapshotIsolation => false,
     }
 }
 
@@ -677,6 +681,15 @@ mod tests {
             target_size: 0,
         };
         let add = tu::create_add_action("p", false, None);
+        let res = can_downgrade_to_snapshot_isolation(&[add.clone()], &operation, &isolation);
+        assert!(res);
+
+        // metadata updates can never be reordered
+        let metadata = tu::create_metadata_action(None, None);
+        let res = can_downgrade_to_snapshot_isolation(&[add, metadata], &operation, &isolation);
+        assert!(!res);
+
+        let add = tu::create_add_action("p", true, None);
         let res = can_downgrade_to_snapshot_isolation(&[add], &operation, &isolation);
         assert!(!res)
     }
@@ -768,6 +781,54 @@ mod tests {
         // TODO disjoint transactions
     }
 
+    #[tokio::test]
+    #[cfg(feature = "datafusion")]
+    async fn test_isolation_levels() {
+        use crate::table::state::DeltaTableState;
+        use std::collections::HashMap;
+
+        // the current transaction rewrites the whole table, while a concurrent transaction
+        // appends a new file to it
+        let check = |isolation_level: &str, winner_is_blind_append: bool| {
+            let configuration = HashMap::from([(
+                "delta.isolationLevel".to_string(),
+                Some(isolation_level.to_string()),
+            )]);
+            let mut setup_actions = init_table_actions(Some(configuration));
+            setup_actions.push(tu::create_add_action("file_read", true, get_stats(1, 10)));
+            let state = DeltaTableState::from_actions(setup_actions).unwrap();
+            let actions = vec![
+                tu::create_remove_action("file_read", true),
+                tu::create_add_action("file_rewritten", true, get_stats(1, 10)),
+            ];
+            let transaction_info = TransactionInfo::new(state.snapshot(), None, &actions, true);
+            let summary = WinningCommitSummary {
+                actions: vec![tu::create_add_action(
+                    "file_appended",
+                    true,
+                    get_stats(1, 10),
+                )],
+                commit_info: Some(CommitInfo {
+                    is_blind_append: Some(winner_is_blind_append),
+                    ..Default::default()
+                }),
+            };
+            let operation = DeltaOperation::Delete { predicate: None };
+            ConflictChecker::new(transaction_info, summary, Some(&operation)).check_conflicts()
+        };
+
+        assert!(matches!(
+            check("Serializable", true),
+            Err(CommitConflictError::ConcurrentAppend)
+        ));
+        assert!(check("WriteSerializable", true).is_ok());
+        assert!(matches!(
+            check("WriteSerializable", false),
+            Err(CommitConflictError::ConcurrentAppend)
+        ));
+        assert!(check("SnapshotIsolation", false).is_ok());
+    }
+
     #[tokio::test]
     #[cfg(feature = "datafusion")]
     // tests adopted from https://github.com/delta-io/delta/blob/24c025128612a4ae02d0ad958621f928cda9a3ec/core/src/test/scala/org/apache/spark/sql/delta/OptimisticTransactionSuite.scala#L40-L94
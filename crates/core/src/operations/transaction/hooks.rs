@@ -0,0 +1,322 @@
+//! Hooks executed after a commit was written to the Delta log.
+//!
+//! Every successful commit runs the built-in hooks enabled by its [`PostCommitHookProperties`],
+//! followed by the custom hooks registered via [`CommitProperties::with_post_commit_hook`].
+//! Custom hooks can be used to e.g. synchronize a catalog or invalidate caches.
+//!
+//! [`PostCommitHookProperties`]: super::PostCommitHookProperties
+//! [`CommitProperties::with_post_commit_hook`]: super::CommitProperties::with_post_commit_hook
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::Utc;
+use tracing::{debug, warn};
+
+use super::{
+    CommitData, CommitProperties, DEFAULT_AUTO_COMPACT_MAX_BYTES, DEFAULT_AUTO_COMPACT_MAX_FILES,
+};
+use crate::checkpoints::{cleanup_expired_logs_for_snapshot, create_checkpoint_for};
+use crate::kernel::{Action, DataType, PartitionsExt};
+use crate::logstore::LogStoreRef;
+use crate::operations::optimize::OptimizeBuilder;
+use crate::protocol::uniform::{write_iceberg_metadata, ICEBERG_FORMAT};
+use crate::table::state::DeltaTableState;
+use crate::{DeltaResult, DeltaTableError};
+
+/// A commit that was written to the log, passed to each [`PostCommitHook`]
+pub struct PostCommitContext<'a> {
+    /// Version of the commit
+    pub version: i64,
+    /// The actions and operation of the commit
+    pub data: &'a CommitData,
+    /// State of the table, including the changes of hooks that ran before
+    pub snapshot: &'a DeltaTableState,
+    /// Log store of the table
+    pub log_store: &'a LogStoreRef,
+}
+
+/// Logic executed after a successful commit.
+///
+/// Hooks run in the order they are registered, after the built-in hooks. An error returned by a
+/// hook is returned to the caller of the commit, the commit itself is not reverted.
+#[async_trait::async_trait]
+pub trait PostCommitHook: std::fmt::Debug + Send + Sync {
+    /// Name of the hook
+    fn name(&self) -> &str;
+
+    /// Run the hook for a commit.
+    ///
+    /// Hooks modifying the table return the new state of the table.
+    async fn run(&self, context: &PostCommitContext<'_>) -> DeltaResult<Option<DeltaTableState>>;
+}
+
+/// Create a checkpoint when the commit reaches the `delta.checkpointInterval` of the table.
+///
+/// No checkpoint is created from a table state loaded without all of its files, those are left
+/// to writers tracking all files of the table.
+#[derive(Debug, Default, Clone)]
+pub struct CheckpointHook;
+
+#[async_trait::async_trait]
+impl PostCommitHook for CheckpointHook {
+    fn name(&self) -> &str {
+        "checkpoint"
+    }
+
+    async fn run(&self, context: &PostCommitContext<'_>) -> DeltaResult<Option<DeltaTableState>> {
+        if let Err(err) = context.snapshot.ensure_all_files("CHECKPOINT") {
+            debug!("skipping checkpoint for version {}: {err}", context.version);
+            return Ok(None);
+        }
+        if checkpoint_due(context.snapshot, context.version) {
+            create_checkpoint_for(
+                context.version,
+                context.snapshot,
+                context.log_store.as_ref(),
+            )
+            .await?;
+        }
+        Ok(None)
+    }
+}
+
+/// Delete log files older than `delta.logRetentionDuration` when a checkpoint is created.
+///
+/// Without an explicit setting, `delta.enableExpiredLogCleanup` of the table decides whether
+/// log files are deleted.
+#[derive(Debug, Default, Clone)]
+pub struct LogCleanupHook {
+    cleanup_expired_logs: Option<bool>,
+}
+
+impl LogCleanupHook {
+    /// Create a new [`LogCleanupHook`], overriding the table configuration if set
+    pub fn new(cleanup_expired_logs: Option<bool>) -> Self {
+        Self {
+            cleanup_expired_logs,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PostCommitHook for LogCleanupHook {
+    fn name(&self) -> &str {
+        "log_cleanup"
+    }
+
+    async fn run(&self, context: &PostCommitContext<'_>) -> DeltaResult<Option<DeltaTableState>> {
+        let config = context.snapshot.table_config();
+        let enabled = self
+            .cleanup_expired_logs
+            .unwrap_or_else(|| config.enable_expired_log_cleanup());
+        if !enabled || !checkpoint_due(context.snapshot, context.version) {
+            return Ok(None);
+        }
+        let cutoff_timestamp =
+            Utc::now().timestamp_millis() - config.log_retention_duration().as_millis() as i64;
+        let deleted = cleanup_expired_logs_for_snapshot(
+            context.snapshot,
+            context.log_store.as_ref(),
+            cutoff_timestamp,
+        )
+        .await?;
+        debug!("deleted {deleted} expired log files");
+        Ok(None)
+    }
+}
+
+/// Compact the partitions written by the commit which accumulated too many small files, if
+/// `delta.autoOptimize.autoCompact` is enabled on the table.
+///
+/// All qualifying partitions are compacted in a single optimize commit, which rewrites at most
+/// [`DEFAULT_AUTO_COMPACT_MAX_FILES`] files and [`DEFAULT_AUTO_COMPACT_MAX_BYTES`] bytes unless
+/// configured otherwise. Failing to compact does not fail the commit.
+#[derive(Debug, Clone)]
+pub struct AutoCompactHook {
+    min_num_files: usize,
+    max_files: usize,
+    max_bytes: i64,
+    create_checkpoint: bool,
+}
+
+impl AutoCompactHook {
+    /// Create a new [`AutoCompactHook`] compacting partitions with at least `min_num_files`
+    /// small files
+    pub fn new(min_num_files: usize) -> Self {
+        Self {
+            min_num_files,
+            max_files: DEFAULT_AUTO_COMPACT_MAX_FILES,
+            max_bytes: DEFAULT_AUTO_COMPACT_MAX_BYTES,
+            create_checkpoint: true,
+        }
+    }
+
+    /// Maximum number of files rewritten by a single compaction
+    pub fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    /// Maximum number of bytes rewritten by a single compaction
+    pub fn with_max_bytes(mut self, max_bytes: i64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Specify if the optimize commit should create a checkpoint when it is due
+    pub fn with_create_checkpoint(mut self, create_checkpoint: bool) -> Self {
+        self.create_checkpoint = create_checkpoint;
+        self
+    }
+
+    async fn compact_partitions(
+        &self,
+        context: &PostCommitContext<'_>,
+    ) -> DeltaResult<Option<DeltaTableState>> {
+        let table_state = context.snapshot;
+        let schema = table_state.schema();
+
+        // only partitions receiving new data are considered. The raw partition values of the
+        // commit are parsed, so they compare equal to the typed values of the snapshot.
+        let mut written = HashSet::new();
+        for action in &context.data.actions {
+            let Action::Add(add) = action else {
+                continue;
+            };
+            if !add.data_change {
+                continue;
+            }
+            let partition_values = add
+                .partition_values
+                .iter()
+                .map(|(key, value)| {
+                    let data_type = schema.field(key).map(|field| field.data_type());
+                    let Some(DataType::Primitive(primitive)) = data_type else {
+                        return Err(DeltaTableError::Generic(format!(
+                            "Invalid partition column {key}"
+                        )));
+                    };
+                    let value = value
+                        .as_deref()
+                        .map(|value| primitive.parse_scalar(value))
+                        .transpose()?
+                        .filter(|value| !value.is_null())
+                        .map(|value| value.serialize());
+                    Ok((key.clone(), value))
+                })
+                .collect::<DeltaResult<BTreeMap<_, _>>>()?;
+            written.insert(partition_values);
+        }
+        if written.is_empty() {
+            return Ok(None);
+        }
+
+        let target_size = table_state.table_config().target_file_size();
+        let mut small_files: HashMap<String, usize> = HashMap::new();
+        for file in table_state.log_data() {
+            if file.size() >= target_size {
+                continue;
+            }
+            let partition_values = file.partition_values()?;
+            let serialized = partition_values
+                .iter()
+                .map(|(k, v)| {
+                    let value = (!v.is_null()).then(|| v.serialize());
+                    (k.to_string(), value)
+                })
+                .collect::<BTreeMap<_, _>>();
+            if written.contains(&serialized) {
+                *small_files
+                    .entry(partition_values.hive_partition_path())
+                    .or_default() += 1;
+            }
+        }
+        let partitions = small_files
+            .into_iter()
+            .filter(|(_, num_files)| *num_files >= self.min_num_files)
+            .map(|(partition, _)| partition)
+            .collect::<HashSet<_>>();
+        if partitions.is_empty() {
+            return Ok(None);
+        }
+
+        debug!("auto compacting the small files of partitions {partitions:?}");
+        let (table, _) = OptimizeBuilder::new(context.log_store.clone(), table_state.clone())
+            .with_partitions(partitions)
+            .with_max_files(self.max_files)
+            .with_max_bytes(self.max_bytes)
+            .with_commit_properties(
+                CommitProperties::default().with_create_checkpoint(self.create_checkpoint),
+            )
+            .await?;
+        Ok(Some(table.snapshot()?.clone()))
+    }
+}
+
+#[async_trait::async_trait]
+impl PostCommitHook for AutoCompactHook {
+    fn name(&self) -> &str {
+        "auto_compact"
+    }
+
+    async fn run(&self, context: &PostCommitContext<'_>) -> DeltaResult<Option<DeltaTableState>> {
+        if !context.snapshot.table_config().auto_compact() {
+            return Ok(None);
+        }
+        match self.compact_partitions(context).await {
+            Ok(state) => Ok(state),
+            Err(err) => {
+                warn!(
+                    "Failed to auto compact the table after version {}: {err}",
+                    context.version
+                );
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Write Iceberg metadata for the new version if UniForm is enabled on the table.
+///
+/// Failing to do so does not fail the commit, readers using the Delta log are not affected.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct IcebergMetadataHook;
+
+#[async_trait::async_trait]
+impl PostCommitHook for IcebergMetadataHook {
+    fn name(&self) -> &str {
+        "iceberg_metadata"
+    }
+
+    async fn run(&self, context: &PostCommitContext<'_>) -> DeltaResult<Option<DeltaTableState>> {
+        let formats = context.snapshot.table_config().universal_formats();
+        if !formats.iter().any(|format| format == ICEBERG_FORMAT) {
+            return Ok(None);
+        }
+        if let Err(err) = context.snapshot.ensure_all_files("UniForm") {
+            warn!(
+                "Skipping Iceberg metadata for version {}: {err}",
+                context.version
+            );
+            return Ok(None);
+        }
+        if let Err(err) = write_iceberg_metadata(
+            context.snapshot,
+            context.log_store,
+            Some(&context.data.actions),
+        )
+        .await
+        {
+            warn!(
+                "Failed to write Iceberg metadata for version {}: {err}",
+                context.version
+            );
+        }
+        Ok(None)
+    }
+}
+
+fn checkpoint_due(table_state: &DeltaTableState, version: i64) -> bool {
+    let checkpoint_interval = table_state.config().checkpoint_interval() as i64;
+    ((version + 1) % checkpoint_interval) == 0
+}
@@ -10,7 +10,8 @@
 //!
 //! A [`PreparedCommit`] represents a temporary commit marker written to storage.
 //! To convert to a [`FinalizedCommit`] an atomic rename is attempted. If the rename fails
-//! then conflict resolution is performed and the atomic rename is tried for the latest version.
+//! then conflict resolution is performed against all commits made since, and after a randomized
+//! [`CommitRetryBackoff`] the atomic rename is tried for the version following the latest one.
 //!
 //!<pre>
 //!                                          Client Interface
@@ -81,25 +82,37 @@ use object_store::path::Path;
 use object_store::{Error as ObjectStoreError, ObjectStore};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, debug_span, field, info_span, Instrument, Span};
 
-use self::conflict_checker::{CommitConflictError, TransactionInfo, WinningCommitSummary};
-use crate::checkpoints::create_checkpoint_for;
+use self::conflict_checker::{TransactionInfo, WinningCommitSummary};
 use crate::errors::DeltaTableError;
 use crate::kernel::{
     Action, CommitInfo, EagerSnapshot, Metadata, Protocol, ReaderFeatures, Transaction,
     WriterFeatures,
 };
 use crate::logstore::LogStoreRef;
-use crate::protocol::DeltaOperation;
+use crate::metrics::{self, Counter};
+use crate::protocol::{DeltaOperation, OutputMode, SaveMode};
+use crate::storage::retry_store::jittered_backoff;
 use crate::table::config::TableConfig;
 use crate::table::state::DeltaTableState;
 use crate::{crate_version, DeltaResult};
 
+pub use self::conflict_checker::CommitConflictError;
+use self::hooks::IcebergMetadataHook;
+pub use self::hooks::{
+    AutoCompactHook, CheckpointHook, LogCleanupHook, PostCommitContext, PostCommitHook,
+};
 pub use self::protocol::INSTANCE as PROTOCOL;
+#[cfg(feature = "datafusion")]
+pub(crate) use self::state::AddContainer;
 
 #[cfg(test)]
 pub(crate) mod application;
 mod conflict_checker;
+mod hooks;
 mod protocol;
 #[cfg(feature = "datafusion")]
 mod state;
@@ -108,6 +121,51 @@ pub(crate) mod test_utils;
 
 const DELTA_LOG_FOLDER: &str = "_delta_log";
 pub(crate) const DEFAULT_RETRIES: usize = 15;
+const USER_METADATA_KEY: &str = "userMetadata";
+const ENGINE_INFO_KEY: &str = "engineInfo";
+
+/// Default number of small files a partition needs to accumulate before it is auto compacted
+pub const DEFAULT_AUTO_COMPACT_MIN_NUM_FILES: usize = 50;
+
+/// Default maximum number of files rewritten by a single auto compaction
+pub const DEFAULT_AUTO_COMPACT_MAX_FILES: usize = 1000;
+
+/// Default maximum number of bytes rewritten by a single auto compaction
+pub const DEFAULT_AUTO_COMPACT_MAX_BYTES: i64 = 1024 * 1024 * 1024;
+
+/// Backoff before retrying a commit which lost the race for a version to concurrent writers
+///
+/// The backoff is randomized, so that writers which lost the same race spread out their
+/// retries instead of colliding again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitRetryBackoff {
+    /// The backoff before the first retry, doubled for every following retry
+    pub init_backoff: Duration,
+    /// The upper bound of the backoff between two retries
+    pub max_backoff: Duration,
+}
+
+impl Default for CommitRetryBackoff {
+    fn default() -> Self {
+        Self {
+            init_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl CommitRetryBackoff {
+    /// Retry immediately
+    pub const NONE: Self = Self {
+        init_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+    };
+
+    /// Exponential backoff with jitter before the given retry, starting at zero
+    fn backoff(&self, retry: usize) -> Duration {
+        jittered_backoff(self.init_backoff, self.max_backoff, retry)
+    }
+}
 
 /// Error raised while commititng transaction
 #[derive(thiserror::Error, Debug)]
@@ -181,7 +239,8 @@ impl From<TransactionError> for DeltaTableError {
             TransactionError::SerializeLogJson { json_err } => {
                 DeltaTableError::SerializeLogJson { json_err }
             }
-            TransactionError::ObjectStore { source } => DeltaTableError::ObjectStore { source },
+            TransactionError::ObjectStore { source } => source.into(),
+            TransactionError::CommitConflict(kind) => DeltaTableError::CommitConflict { kind },
             other => DeltaTableError::Transaction { source: other },
         }
     }
@@ -272,12 +331,15 @@ impl CommitData {
         if !actions.iter().any(|a| matches!(a, Action::CommitInfo(..))) {
             let mut commit_info = operation.get_commit_info();
             commit_info.timestamp = Some(Utc::now().timestamp_millis());
+            commit_info.is_blind_append = Some(is_blind_append(&operation, &actions));
             app_metadata.insert(
                 "clientVersion".to_string(),
                 Value::String(format!("delta-rs.{}", crate_version())),
             );
             app_metadata.extend(commit_info.info);
             commit_info.info = app_metadata.clone();
+            commit_info.user_metadata = take_string(&mut commit_info.info, USER_METADATA_KEY);
+            commit_info.engine_info = take_string(&mut commit_info.info, ENGINE_INFO_KEY);
             actions.push(Action::CommitInfo(commit_info))
         }
 
@@ -305,10 +367,46 @@ impl CommitData {
     }
 }
 
+/// Remove `key` from the commit info if it holds a string, so it can be stored in a typed field
+fn take_string(info: &mut HashMap<String, Value>, key: &str) -> Option<String> {
+    match info.remove(key) {
+        Some(Value::String(value)) => Some(value),
+        Some(other) => {
+            info.insert(key.to_string(), other);
+            None
+        }
+        None => None,
+    }
+}
+
+/// A blind append only adds new data without reading from the table. Concurrent transactions
+/// running at `WriteSerializable` isolation do not conflict with such commits.
+fn is_blind_append(operation: &DeltaOperation, actions: &[Action]) -> bool {
+    let appends_only = match operation {
+        DeltaOperation::Write {
+            mode: SaveMode::Append,
+            predicate: None,
+            ..
+        } => true,
+        DeltaOperation::StreamingUpdate { output_mode, .. } => {
+            matches!(output_mode, OutputMode::Append)
+        }
+        _ => false,
+    };
+    appends_only
+        && actions.iter().all(|action| match action {
+            Action::Add(add) => add.data_change,
+            Action::CommitInfo(_) | Action::Txn(_) => true,
+            _ => false,
+        })
+}
+
 #[derive(Clone, Debug, Copy)]
 /// Properties for post commit hook.
 pub struct PostCommitHookProperties {
     create_checkpoint: bool,
+    cleanup_expired_logs: Option<bool>,
+    auto_compact_min_num_files: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -317,8 +415,14 @@ pub struct PostCommitHookProperties {
 pub struct CommitProperties {
     pub(crate) app_metadata: HashMap<String, Value>,
     pub(crate) app_transaction: Vec<Transaction>,
+    user_metadata: Option<String>,
+    engine_info: Option<String>,
     max_retries: usize,
+    retry_backoff: CommitRetryBackoff,
     create_checkpoint: bool,
+    cleanup_expired_logs: Option<bool>,
+    auto_compact_min_num_files: usize,
+    custom_hooks: Vec<Arc<dyn PostCommitHook>>,
 }
 
 impl Default for CommitProperties {
@@ -326,8 +430,14 @@ impl Default for CommitProperties {
         Self {
             app_metadata: Default::default(),
             app_transaction: Vec::new(),
+            user_metadata: None,
+            engine_info: None,
             max_retries: DEFAULT_RETRIES,
+            retry_backoff: CommitRetryBackoff::default(),
             create_checkpoint: true,
+            cleanup_expired_logs: None,
+            auto_compact_min_num_files: DEFAULT_AUTO_COMPACT_MIN_NUM_FILES,
+            custom_hooks: Vec::new(),
         }
     }
 }
@@ -342,12 +452,63 @@ impl CommitProperties {
         self
     }
 
+    /// User defined metadata recorded as `userMetadata` in the commit info
+    pub fn with_user_metadata(mut self, user_metadata: impl Into<String>) -> Self {
+        self.user_metadata = Some(user_metadata.into());
+        self
+    }
+
+    /// Name and version of the application creating the commit, recorded as `engineInfo`
+    /// in the commit info
+    pub fn with_engine_info(mut self, engine_info: impl Into<String>) -> Self {
+        self.engine_info = Some(engine_info.into());
+        self
+    }
+
+    /// Maximum number of commit attempts, including the first one, when concurrent commits are
+    /// detected. A value of zero is treated as a single attempt.
+    ///
+    /// Before every retry, the concurrently committed transactions are checked for conflicts
+    /// with this transaction according to the table's isolation level. Non-conflicting
+    /// transactions are retried with the next available version.
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Backoff before retrying the transaction after concurrent commits were detected
+    pub fn with_retry_backoff(mut self, retry_backoff: CommitRetryBackoff) -> Self {
+        self.retry_backoff = retry_backoff;
+        self
+    }
+
     /// Specify if it should create a checkpoint when the commit interval condition is met
     pub fn with_create_checkpoint(mut self, create_checkpoint: bool) -> Self {
         self.create_checkpoint = create_checkpoint;
         self
     }
 
+    /// Specify if expired log files should be deleted when a checkpoint is created.
+    ///
+    /// Defaults to the `delta.enableExpiredLogCleanup` property of the table.
+    pub fn with_cleanup_expired_logs(mut self, cleanup_expired_logs: Option<bool>) -> Self {
+        self.cleanup_expired_logs = cleanup_expired_logs;
+        self
+    }
+
+    /// Add a custom hook to be executed after the commit, following the built-in hooks
+    pub fn with_post_commit_hook(mut self, hook: Arc<dyn PostCommitHook>) -> Self {
+        self.custom_hooks.push(hook);
+        self
+    }
+
+    /// Number of small files a partition written by the commit needs to hold before it is
+    /// compacted, if `delta.autoOptimize.autoCompact` is enabled on the table
+    pub fn with_auto_compact_min_num_files(mut self, min_num_files: usize) -> Self {
+        self.auto_compact_min_num_files = min_num_files;
+        self
+    }
+
     /// Add an additonal application transaction to the commit
     pub fn with_application_transaction(mut self, txn: Transaction) -> Self {
         self.app_transaction.push(txn);
@@ -363,12 +524,23 @@ impl CommitProperties {
 
 impl From<CommitProperties> for CommitBuilder {
     fn from(value: CommitProperties) -> Self {
+        let mut app_metadata = value.app_metadata;
+        if let Some(user_metadata) = value.user_metadata {
+            app_metadata.insert(USER_METADATA_KEY.to_string(), Value::String(user_metadata));
+        }
+        if let Some(engine_info) = value.engine_info {
+            app_metadata.insert(ENGINE_INFO_KEY.to_string(), Value::String(engine_info));
+        }
         CommitBuilder {
             max_retries: value.max_retries,
-            app_metadata: value.app_metadata,
+            retry_backoff: value.retry_backoff,
+            app_metadata,
             post_commit_hook: Some(PostCommitHookProperties {
                 create_checkpoint: value.create_checkpoint,
+                cleanup_expired_logs: value.cleanup_expired_logs,
+                auto_compact_min_num_files: value.auto_compact_min_num_files,
             }),
+            custom_hooks: value.custom_hooks,
             app_transaction: value.app_transaction,
             ..Default::default()
         }
@@ -381,7 +553,9 @@ pub struct CommitBuilder {
     app_metadata: HashMap<String, Value>,
     app_transaction: Vec<Transaction>,
     max_retries: usize,
+    retry_backoff: CommitRetryBackoff,
     post_commit_hook: Option<PostCommitHookProperties>,
+    custom_hooks: Vec<Arc<dyn PostCommitHook>>,
 }
 
 impl Default for CommitBuilder {
@@ -391,7 +565,9 @@ impl Default for CommitBuilder {
             app_metadata: HashMap::new(),
             app_transaction: Vec::new(),
             max_retries: DEFAULT_RETRIES,
+            retry_backoff: CommitRetryBackoff::default(),
             post_commit_hook: None,
+            custom_hooks: Vec::new(),
         }
     }
 }
@@ -409,18 +585,31 @@ impl<'a> CommitBuilder {
         self
     }
 
-    /// Maximum number of times to retry the transaction before failing to commit
+    /// Maximum number of commit attempts, including the first one, before failing to commit.
+    /// A value of zero is treated as a single attempt.
     pub fn with_max_retries(mut self, max_retries: usize) -> Self {
         self.max_retries = max_retries;
         self
     }
 
+    /// Backoff before retrying the transaction after concurrent commits were detected
+    pub fn with_retry_backoff(mut self, retry_backoff: CommitRetryBackoff) -> Self {
+        self.retry_backoff = retry_backoff;
+        self
+    }
+
     /// Specify all the post commit hook properties
     pub fn with_post_commit_hook(mut self, post_commit_hook: PostCommitHookProperties) -> Self {
         self.post_commit_hook = Some(post_commit_hook);
         self
     }
 
+    /// Add a custom hook to be executed after the commit, following the built-in hooks
+    pub fn with_custom_post_commit_hook(mut self, hook: Arc<dyn PostCommitHook>) -> Self {
+        self.custom_hooks.push(hook);
+        self
+    }
+
     /// Prepare a Commit operation using the configured builder
     pub fn build(
         self,
@@ -438,8 +627,10 @@ impl<'a> CommitBuilder {
             log_store,
             table_data,
             max_retries: self.max_retries,
+            retry_backoff: self.retry_backoff,
             data,
             post_commit_hook: self.post_commit_hook,
+            custom_hooks: self.custom_hooks,
         }
     }
 }
@@ -450,7 +641,9 @@ pub struct PreCommit<'a> {
     table_data: Option<&'a dyn TableReference>,
     data: CommitData,
     max_retries: usize,
+    retry_backoff: CommitRetryBackoff,
     post_commit_hook: Option<PostCommitHookProperties>,
+    custom_hooks: Vec<Arc<dyn PostCommitHook>>,
 }
 
 impl<'a> std::future::IntoFuture for PreCommit<'a> {
@@ -458,7 +651,24 @@ impl<'a> std::future::IntoFuture for PreCommit<'a> {
     type IntoFuture = BoxFuture<'a, Self::Output>;
 
     fn into_future(self) -> Self::IntoFuture {
-        Box::pin(async move { self.into_prepared_commit_future().await?.await?.await })
+        let span = info_span!(
+            "delta.commit",
+            table_uri = %self.log_store.root_uri(),
+            operation = self.data.operation.name(),
+            read_version = self
+                .table_data
+                .map(|table| table.eager_snapshot().version()),
+            num_actions = self.data.actions.len(),
+            version = field::Empty,
+        );
+
+        let future = async move {
+            let commit = self.into_prepared_commit_future().await?.await?.await?;
+            Span::current().record("version", commit.version);
+            Ok(commit)
+        };
+
+        Box::pin(future.instrument(span))
     }
 }
 
@@ -487,8 +697,10 @@ impl<'a> PreCommit<'a> {
                 log_store: this.log_store,
                 table_data: this.table_data,
                 max_retries: this.max_retries,
+                retry_backoff: this.retry_backoff,
                 data: this.data,
                 post_commit: this.post_commit_hook,
+                custom_hooks: this.custom_hooks,
             })
         })
     }
@@ -501,7 +713,9 @@ pub struct PreparedCommit<'a> {
     data: CommitData,
     table_data: Option<&'a dyn TableReference>,
     max_retries: usize,
+    retry_backoff: CommitRetryBackoff,
     post_commit: Option<PostCommitHookProperties>,
+    custom_hooks: Vec<Arc<dyn PostCommitHook>>,
 }
 
 impl<'a> PreparedCommit<'a> {
@@ -523,10 +737,18 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
 
             if this.table_data.is_none() {
                 this.log_store.write_commit_entry(0, tmp_commit).await?;
+                metrics::increment(
+                    Counter::Commits,
+                    1,
+                    &[("operation", this.data.operation.name())],
+                );
                 return Ok(PostCommit {
                     version: 0,
                     data: this.data,
                     create_checkpoint: false,
+                    cleanup_expired_logs: None,
+                    auto_compact_min_num_files: None,
+                    custom_hooks: this.custom_hooks,
                     log_store: this.log_store,
                     table_data: this.table_data,
                 });
@@ -536,11 +758,24 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
             // TODO: refactor to only depend on TableReference Trait
             let read_snapshot = this.table_data.unwrap().eager_snapshot();
 
+            let mut version = read_snapshot.version() + 1;
+            let max_attempts = this.max_retries.max(1);
             let mut attempt_number = 1;
-            while attempt_number <= this.max_retries {
-                let version = read_snapshot.version() + attempt_number as i64;
-                match this.log_store.write_commit_entry(version, tmp_commit).await {
+            while attempt_number <= max_attempts {
+                let attempt_span =
+                    debug_span!("delta.commit_attempt", version, attempt = attempt_number);
+                match this
+                    .log_store
+                    .write_commit_entry(version, tmp_commit)
+                    .instrument(attempt_span)
+                    .await
+                {
                     Ok(()) => {
+                        metrics::increment(
+                            Counter::Commits,
+                            1,
+                            &[("operation", this.data.operation.name())],
+                        );
                         return Ok(PostCommit {
                             version,
                             data: this.data,
@@ -548,39 +783,63 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                                 .post_commit
                                 .map(|v| v.create_checkpoint)
                                 .unwrap_or_default(),
+                            cleanup_expired_logs: this
+                                .post_commit
+                                .and_then(|v| v.cleanup_expired_logs),
+                            auto_compact_min_num_files: this
+                                .post_commit
+                                .map(|v| v.auto_compact_min_num_files),
+                            custom_hooks: this.custom_hooks,
                             log_store: this.log_store,
                             table_data: this.table_data,
                         });
                     }
-                    Err(TransactionError::VersionAlreadyExists(version)) => {
-                        let summary = WinningCommitSummary::try_new(
-                            this.log_store.as_ref(),
-                            version - 1,
-                            version,
-                        )
-                        .await?;
-                        let transaction_info = TransactionInfo::try_new(
-                            read_snapshot,
-                            this.data.operation.read_predicate(),
-                            &this.data.actions,
-                            this.data.operation.read_whole_table(),
-                        )?;
-                        let conflict_checker = ConflictChecker::new(
-                            transaction_info,
-                            summary,
-                            Some(&this.data.operation),
-                        );
-                        match conflict_checker.check_conflicts() {
-                            Ok(_) => {
-                                attempt_number += 1;
-                            }
-                            Err(err) => {
+                    Err(TransactionError::VersionAlreadyExists(lost_version)) => {
+                        // Catch up with all commits made since, instead of racing for one
+                        // version after the other, which starves writers under contention.
+                        let latest_version =
+                            this.log_store.get_latest_version(lost_version).await?;
+                        for winning_version in lost_version..=latest_version {
+                            let summary = WinningCommitSummary::try_new(
+                                this.log_store.as_ref(),
+                                winning_version - 1,
+                                winning_version,
+                            )
+                            .await?;
+                            let transaction_info = TransactionInfo::try_new(
+                                read_snapshot,
+                                this.data.operation.read_predicate(),
+                                &this.data.actions,
+                                this.data.operation.read_whole_table(),
+                            )?;
+                            let conflict_checker = ConflictChecker::new(
+                                transaction_info,
+                                summary,
+                                Some(&this.data.operation),
+                            );
+                            if let Err(err) = conflict_checker.check_conflicts() {
                                 this.log_store
-                                    .abort_commit_entry(version, tmp_commit)
+                                    .abort_commit_entry(lost_version, tmp_commit)
                                     .await?;
                                 return Err(TransactionError::CommitConflict(err).into());
                             }
-                        };
+                        }
+                        debug!(
+                            "versions {lost_version} to {latest_version} were taken by non conflicting commits, retrying"
+                        );
+                        metrics::increment(
+                            Counter::CommitConflictRetries,
+                            1,
+                            &[("operation", this.data.operation.name())],
+                        );
+                        version = latest_version + 1;
+                        attempt_number += 1;
+                        if attempt_number <= max_attempts {
+                            let backoff = this.retry_backoff.backoff(attempt_number - 2);
+                            if !backoff.is_zero() {
+                                tokio::time::sleep(backoff).await;
+                            }
+                        }
                     }
                     Err(err) => {
                         this.log_store
@@ -591,7 +850,7 @@ impl<'a> std::future::IntoFuture for PreparedCommit<'a> {
                 }
             }
 
-            Err(TransactionError::MaxCommitAttempts(this.max_retries as i32).into())
+            Err(TransactionError::MaxCommitAttempts(max_attempts as i32).into())
         })
     }
 }
@@ -603,6 +862,10 @@ pub struct PostCommit<'a> {
     /// The data that was comitted to the log store
     pub data: CommitData,
     create_checkpoint: bool,
+    cleanup_expired_logs: Option<bool>,
+    /// Compact small files if enabled on the table, unset if the commit has no post commit hook
+    auto_compact_min_num_files: Option<usize>,
+    custom_hooks: Vec<Arc<dyn PostCommitHook>>,
     log_store: LogStoreRef,
     table_data: Option<&'a dyn TableReference>,
 }
@@ -610,7 +873,7 @@ pub struct PostCommit<'a> {
 impl<'a> PostCommit<'a> {
     /// Runs the post commit activities
     async fn run_post_commit_hook(&self) -> DeltaResult<DeltaTableState> {
-        if let Some(table) = self.table_data {
+        let mut state = if let Some(table) = self.table_data {
             let mut snapshot = table.eager_snapshot().clone();
             if self.version - snapshot.version() > 1 {
                 // This may only occur during concurrent write actions. We need to update the state first to - 1
@@ -622,35 +885,49 @@ impl<'a> PostCommit<'a> {
             } else {
                 snapshot.advance(vec![&self.data])?;
             }
-            let state = DeltaTableState { snapshot };
-            // Execute each hook
-            if self.create_checkpoint {
-                self.create_checkpoint(&state, &self.log_store, self.version)
-                    .await?;
-            }
-            Ok(state)
+            DeltaTableState { snapshot }
         } else {
-            let state = DeltaTableState::try_new(
+            DeltaTableState::try_new(
                 &Path::default(),
                 self.log_store.object_store(),
                 Default::default(),
                 Some(self.version),
             )
-            .await?;
-            Ok(state)
+            .await?
+        };
+
+        // Execute each hook
+        for hook in self.hooks() {
+            debug!("running post commit hook {}", hook.name());
+            let context = PostCommitContext {
+                version: self.version,
+                data: &self.data,
+                snapshot: &state,
+                log_store: &self.log_store,
+            };
+            let hook_span = debug_span!("delta.post_commit_hook", hook = hook.name());
+            if let Some(new_state) = hook.run(&context).instrument(hook_span).await? {
+                state = new_state;
+            }
         }
+        Ok(state)
     }
-    async fn create_checkpoint(
-        &self,
-        table_state: &DeltaTableState,
-        log_store: &LogStoreRef,
-        version: i64,
-    ) -> DeltaResult<()> {
-        let checkpoint_interval = table_state.config().checkpoint_interval() as i64;
-        if ((version + 1) % checkpoint_interval) == 0 {
-            create_checkpoint_for(version, table_state, log_store.as_ref()).await?
+
+    /// The built-in hooks enabled for the commit, followed by the custom hooks
+    fn hooks(&self) -> Vec<Arc<dyn PostCommitHook>> {
+        let mut hooks: Vec<Arc<dyn PostCommitHook>> = Vec::new();
+        if self.create_checkpoint {
+            hooks.push(Arc::new(CheckpointHook));
+            hooks.push(Arc::new(LogCleanupHook::new(self.cleanup_expired_logs)));
+        }
+        hooks.push(Arc::new(IcebergMetadataHook));
+        if let Some(min_num_files) = self.auto_compact_min_num_files {
+            hooks.push(Arc::new(
+                AutoCompactHook::new(min_num_files).with_create_checkpoint(self.create_checkpoint),
+            ));
         }
-        Ok(())
+        hooks.extend(self.custom_hooks.iter().cloned());
+        hooks
     }
 }
 
@@ -680,8 +957,9 @@ impl<'a> std::future::IntoFuture for PostCommit<'a> {
 
     fn into_future(self) -> Self::IntoFuture {
         let this = self;
+        let span = info_span!("delta.post_commit", version = this.version);
 
-        Box::pin(async move {
+        let future = async move {
             match this.run_post_commit_hook().await {
                 Ok(snapshot) => Ok(FinalizedCommit {
                     snapshot,
@@ -689,7 +967,9 @@ impl<'a> std::future::IntoFuture for PostCommit<'a> {
                 }),
                 Err(err) => Err(err),
             }
-        })
+        };
+
+        Box::pin(future.instrument(span))
     }
 }
 
@@ -697,10 +977,13 @@ impl<'a> std::future::IntoFuture for PostCommit<'a> {
 mod tests {
     use std::{collections::HashMap, sync::Arc};
 
+    use super::test_utils::create_add_action;
     use super::*;
+    use crate::kernel::{DataType, PrimitiveType, StructField};
     use crate::{
         logstore::{default_logstore::DefaultLogStore, LogStore},
         storage::commit_uri_from_version,
+        DeltaOps,
     };
     use object_store::{memory::InMemory, PutPayload};
     use url::Url;
@@ -713,6 +996,294 @@ mod tests {
         assert_eq!(version, Path::from("_delta_log/00000000000000000123.json"))
     }
 
+    #[test]
+    fn test_commit_info_records_blind_appends() {
+        let is_blind_append = |operation: DeltaOperation, actions: Vec<Action>| {
+            CommitData::new(actions, operation, HashMap::new(), Vec::new())
+                .actions
+                .iter()
+                .find_map(|action| match action {
+                    Action::CommitInfo(info) => info.is_blind_append,
+                    _ => None,
+                })
+                .unwrap()
+        };
+        let append = DeltaOperation::Write {
+            mode: SaveMode::Append,
+            partition_by: None,
+            predicate: None,
+        };
+        let add = create_add_action("file.parquet", true, None);
+
+        assert!(is_blind_append(append.clone(), vec![add.clone()]));
+        assert!(!is_blind_append(
+            append,
+            vec![
+                add.clone(),
+                test_utils::create_remove_action("old.parquet", true)
+            ]
+        ));
+        assert!(!is_blind_append(
+            DeltaOperation::Write {
+                mode: SaveMode::Overwrite,
+                partition_by: None,
+                predicate: None,
+            },
+            vec![add]
+        ));
+    }
+
+    #[test]
+    fn test_commit_info_records_user_metadata_and_engine_info() {
+        let properties = CommitProperties::default()
+            .with_metadata(vec![("team".to_string(), Value::String("ingest".into()))])
+            .with_user_metadata("nightly backfill")
+            .with_engine_info("my-app/1.2.0");
+        let app_metadata = CommitBuilder::from(properties).app_metadata;
+        let operation = DeltaOperation::Write {
+            mode: SaveMode::Append,
+            partition_by: None,
+            predicate: None,
+        };
+        let data = CommitData::new(Vec::new(), operation, app_metadata, Vec::new());
+        let commit_info = data
+            .actions
+            .iter()
+            .find_map(|action| match action {
+                Action::CommitInfo(info) => Some(info),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(
+            commit_info.user_metadata.as_deref(),
+            Some("nightly backfill")
+        );
+        assert_eq!(commit_info.engine_info.as_deref(), Some("my-app/1.2.0"));
+        assert_eq!(commit_info.info["team"], Value::String("ingest".into()));
+        assert!(!commit_info.info.contains_key(USER_METADATA_KEY));
+
+        let json: Value = serde_json::from_slice(&data.get_bytes().unwrap()).unwrap();
+        assert_eq!(json["commitInfo"]["userMetadata"], "nightly backfill");
+        assert_eq!(json["commitInfo"]["engineInfo"], "my-app/1.2.0");
+    }
+
+    #[tokio::test]
+    async fn test_commit_retries_after_concurrent_append() {
+        let table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(vec![StructField::new(
+                "id".to_string(),
+                DataType::Primitive(PrimitiveType::Integer),
+                true,
+            )])
+            .await
+            .unwrap();
+        let read_snapshot = table.snapshot().unwrap().clone();
+        let operation = DeltaOperation::Write {
+            mode: SaveMode::Append,
+            partition_by: None,
+            predicate: None,
+        };
+
+        // a concurrent writer wins the race for version 1
+        let winner = CommitBuilder::from(CommitProperties::default())
+            .with_actions(vec![create_add_action("winner.parquet", true, None)])
+            .build(Some(&read_snapshot), table.log_store(), operation.clone())
+            .await
+            .unwrap();
+        assert_eq!(winner.version(), 1);
+
+        // with a single commit attempt, i.e. no retries, the losing transaction fails
+        let res = CommitBuilder::from(CommitProperties::default().with_max_retries(1))
+            .with_actions(vec![create_add_action("loser.parquet", true, None)])
+            .build(Some(&read_snapshot), table.log_store(), operation.clone())
+            .await;
+        assert!(matches!(
+            res,
+            Err(DeltaTableError::Transaction {
+                source: TransactionError::MaxCommitAttempts(1)
+            })
+        ));
+
+        // zero attempts are treated as a single attempt
+        let res = CommitBuilder::from(CommitProperties::default().with_max_retries(0))
+            .with_actions(vec![create_add_action("loser.parquet", true, None)])
+            .build(Some(&read_snapshot), table.log_store(), operation.clone())
+            .await;
+        assert!(matches!(
+            res,
+            Err(DeltaTableError::Transaction {
+                source: TransactionError::MaxCommitAttempts(1)
+            })
+        ));
+
+        // non conflicting blind appends are transparently retried
+        let metrics = metrics::delta_metrics();
+        let commits = metrics.get(Counter::Commits);
+        let retries = metrics.get(Counter::CommitConflictRetries);
+        let retried = CommitBuilder::from(CommitProperties::default().with_max_retries(2))
+            .with_actions(vec![create_add_action("loser.parquet", true, None)])
+            .build(Some(&read_snapshot), table.log_store(), operation)
+            .await
+            .unwrap();
+        assert_eq!(retried.version(), 2);
+        assert_eq!(retried.snapshot().files_count(), 2);
+        // other tests may commit concurrently
+        assert!(metrics.get(Counter::Commits) > commits);
+        assert!(metrics.get(Counter::CommitConflictRetries) > retries);
+    }
+
+    #[tokio::test]
+    async fn test_commit_with_zero_max_retries() {
+        let table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(vec![StructField::new(
+                "id".to_string(),
+                DataType::Primitive(PrimitiveType::Integer),
+                true,
+            )])
+            .await
+            .unwrap();
+        let operation = DeltaOperation::Write {
+            mode: SaveMode::Append,
+            partition_by: None,
+            predicate: None,
+        };
+        let committed = CommitBuilder::from(CommitProperties::default().with_max_retries(0))
+            .with_actions(vec![create_add_action("first.parquet", true, None)])
+            .build(
+                Some(table.snapshot().unwrap()),
+                table.log_store(),
+                operation,
+            )
+            .await
+            .unwrap();
+        assert_eq!(committed.version(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_commit_catches_up_with_concurrent_appends() {
+        let table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(vec![StructField::new(
+                "id".to_string(),
+                DataType::Primitive(PrimitiveType::Integer),
+                true,
+            )])
+            .await
+            .unwrap();
+        let read_snapshot = table.snapshot().unwrap().clone();
+        let operation = DeltaOperation::Write {
+            mode: SaveMode::Append,
+            partition_by: None,
+            predicate: None,
+        };
+
+        // concurrent writers win the races for versions 1 to 3
+        let mut snapshot = read_snapshot.clone();
+        for winner in ["a.parquet", "b.parquet", "c.parquet"] {
+            snapshot = CommitBuilder::from(CommitProperties::default())
+                .with_actions(vec![create_add_action(winner, true, None)])
+                .build(Some(&snapshot), table.log_store(), operation.clone())
+                .await
+                .unwrap()
+                .snapshot();
+        }
+        assert_eq!(snapshot.version(), 3);
+
+        // all winning commits are checked in a single retry
+        let properties = CommitProperties::default()
+            .with_max_retries(2)
+            .with_retry_backoff(CommitRetryBackoff::NONE);
+        let retried = CommitBuilder::from(properties)
+            .with_actions(vec![create_add_action("loser.parquet", true, None)])
+            .build(Some(&read_snapshot), table.log_store(), operation)
+            .await
+            .unwrap();
+        assert_eq!(retried.version(), 4);
+        assert_eq!(retried.snapshot().files_count(), 4);
+    }
+
+    #[test]
+    fn test_commit_retry_backoff() {
+        let backoff = CommitRetryBackoff {
+            init_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+        };
+        let first = backoff.backoff(0);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+        let capped = backoff.backoff(10);
+        assert!(capped >= Duration::from_millis(150) && capped <= Duration::from_millis(300));
+        assert_eq!(CommitRetryBackoff::NONE.backoff(3), Duration::ZERO);
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingHook {
+        commits: std::sync::Mutex<Vec<(i64, usize)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PostCommitHook for RecordingHook {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn run(
+            &self,
+            context: &PostCommitContext<'_>,
+        ) -> DeltaResult<Option<DeltaTableState>> {
+            let num_adds = context
+                .data
+                .actions
+                .iter()
+                .filter(|action| matches!(action, Action::Add(_)))
+                .count();
+            assert_eq!(context.snapshot.version(), context.version);
+            self.commits
+                .lock()
+                .unwrap()
+                .push((context.version, num_adds));
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_post_commit_hook() {
+        let hook = Arc::new(RecordingHook::default());
+        let properties = CommitProperties::default().with_post_commit_hook(hook.clone());
+        let table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(vec![StructField::new(
+                "id".to_string(),
+                DataType::Primitive(PrimitiveType::Integer),
+                true,
+            )])
+            .with_commit_properties(properties.clone())
+            .await
+            .unwrap();
+        let operation = DeltaOperation::Write {
+            mode: SaveMode::Append,
+            partition_by: None,
+            predicate: None,
+        };
+        let commit = CommitBuilder::from(properties)
+            .with_actions(vec![
+                create_add_action("a.parquet", true, None),
+                create_add_action("b.parquet", true, None),
+            ])
+            .build(
+                Some(table.snapshot().unwrap()),
+                table.log_store(),
+                operation,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(commit.version(), 1);
+        assert_eq!(*hook.commits.lock().unwrap(), vec![(0, 0), (1, 2)]);
+    }
+
     #[tokio::test]
     async fn test_try_commit_transaction() {
         let store = Arc::new(InMemory::new());
@@ -244,6 +244,7 @@ pub static INSTANCE: Lazy<ProtocolChecker> = Lazy::new(|| {
     let mut writer_features = HashSet::new();
     writer_features.insert(WriterFeatures::AppendOnly);
     writer_features.insert(WriterFeatures::TimestampWithoutTimezone);
+    writer_features.insert(WriterFeatures::CheckpointProtection);
     #[cfg(feature = "cdf")]
     {
         writer_features.insert(WriterFeatures::ChangeDataFeed);
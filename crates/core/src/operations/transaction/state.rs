@@ -1,24 +1,27 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use arrow::array::{ArrayRef, BooleanArray};
 use arrow::datatypes::{
     DataType, Field as ArrowField, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
 };
-use datafusion::execution::context::SessionContext;
-use datafusion::physical_optimizer::pruning::{PruningPredicate, PruningStatistics};
+use datafusion::physical_optimizer::pruning::PruningStatistics;
 use datafusion_common::scalar::ScalarValue;
-use datafusion_common::{Column, ToDFSchema};
+use datafusion_common::Column;
 use datafusion_expr::Expr;
 use itertools::Itertools;
 use object_store::ObjectStore;
 use parquet::arrow::arrow_reader::ArrowReaderOptions;
 use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
 
-use crate::delta_datafusion::{get_null_of_arrow_type, to_correct_scalar_value, DataFusionMixins};
+use crate::delta_datafusion::{
+    get_null_of_arrow_type, stats_pruning_predicate, to_correct_scalar_value, DataFusionMixins,
+};
 use crate::errors::DeltaResult;
 use crate::kernel::{Add, EagerSnapshot};
+use crate::protocol::{ColumnCountStat, ColumnValueStat};
 use crate::table::state::DeltaTableState;
+use crate::writer::{MAX_VALUE_TAG_PREFIX, MIN_VALUE_TAG_PREFIX};
 
 impl DeltaTableState {
     /// Get the physical table schema.
@@ -78,6 +81,58 @@ impl EagerSnapshot {
     }
 }
 
+/// Read the min or max value of `column` recorded in the tags of `add`
+fn tag_value(add: &Add, column: &str, get_max: bool) -> Option<serde_json::Value> {
+    let prefix = if get_max {
+        MAX_VALUE_TAG_PREFIX
+    } else {
+        MIN_VALUE_TAG_PREFIX
+    };
+    let value = add
+        .tags
+        .as_ref()?
+        .get(&format!("{prefix}{column}"))?
+        .as_ref()?;
+    serde_json::from_str(value).ok()
+}
+
+/// Resolve `name` to a field of `schema`, following the dot separated path of nested fields
+fn field_at_path<'s>(schema: &'s ArrowSchema, name: &str) -> Option<&'s ArrowField> {
+    if let Ok(field) = schema.field_with_name(name) {
+        return Some(field);
+    }
+    let mut parts = name.split('.');
+    let mut field = schema.field_with_name(parts.next()?).ok()?;
+    for part in parts {
+        field = match field.data_type() {
+            DataType::Struct(children) => children.iter().find(|f| f.name() == part)?.as_ref(),
+            _ => return None,
+        };
+    }
+    Some(field)
+}
+
+/// Look up the min or max value of `name`, following the dot separated path of nested fields
+fn value_stat<'v>(
+    values: &'v HashMap<String, ColumnValueStat>,
+    name: &str,
+) -> Option<&'v serde_json::Value> {
+    if let Some(value) = values.get(name) {
+        return value.as_value();
+    }
+    let (parent, child) = name.split_once('.')?;
+    value_stat(values.get(parent)?.as_column()?, child)
+}
+
+/// Look up the null count of `name`, following the dot separated path of nested fields
+fn count_stat(counts: &HashMap<String, ColumnCountStat>, name: &str) -> Option<i64> {
+    if let Some(count) = counts.get(name) {
+        return count.as_value();
+    }
+    let (parent, child) = name.split_once('.')?;
+    count_stat(counts.get(parent)?.as_column()?, child)
+}
+
 pub struct AddContainer<'a> {
     inner: &'a Vec<Add>,
     partition_columns: &'a Vec<String>,
@@ -99,7 +154,7 @@ impl<'a> AddContainer<'a> {
     }
 
     pub fn get_prune_stats(&self, column: &Column, get_max: bool) -> Option<ArrayRef> {
-        let (_, field) = self.schema.column_with_name(&column.name)?;
+        let field = field_at_path(&self.schema, &column.name)?;
 
         // See issue 1214. Binary type does not support natural order which is required for Datafusion to prune
         if field.data_type() == &DataType::Binary {
@@ -121,25 +176,23 @@ impl<'a> AddContainer<'a> {
                     .unwrap_or(
                         get_null_of_arrow_type(data_type).expect("Could not determine null type"),
                     )
-            } else if let Ok(Some(statistics)) = add.get_stats() {
-                let values = if get_max {
-                    statistics.max_values
-                } else {
-                    statistics.min_values
-                };
-
-                values
-                    .get(&column.name)
-                    .and_then(|f| {
-                        to_correct_scalar_value(f.as_value()?, data_type)
-                            .ok()
-                            .flatten()
-                    })
+            } else {
+                let stats_value = add.get_stats().ok().flatten().and_then(|statistics| {
+                    let values = if get_max {
+                        statistics.max_values
+                    } else {
+                        statistics.min_values
+                    };
+                    value_stat(&values, &column.name).cloned()
+                });
+
+                // Fall back to the min / max tags for columns without file statistics
+                stats_value
+                    .or_else(|| tag_value(add, &column.name, get_max))
+                    .and_then(|value| to_correct_scalar_value(&value, data_type).ok().flatten())
                     .unwrap_or(
                         get_null_of_arrow_type(data_type).expect("Could not determine null type"),
                     )
-            } else {
-                get_null_of_arrow_type(data_type).expect("Could not determine null type")
             }
         });
         ScalarValue::iter_to_array(values).ok()
@@ -151,10 +204,7 @@ impl<'a> AddContainer<'a> {
     /// so evaluating expressions is inexact. However, excluded files are guaranteed (for a correct log)
     /// to not contain matches by the predicate expression.
     pub fn predicate_matches(&self, predicate: Expr) -> DeltaResult<impl Iterator<Item = &Add>> {
-        //let expr = logical_expr_to_physical_expr(predicate, &self.schema);
-        let expr = SessionContext::new()
-            .create_physical_expr(predicate, &self.schema.clone().to_dfschema()?)?;
-        let pruning_predicate = PruningPredicate::try_new(expr, self.schema.clone())?;
+        let pruning_predicate = stats_pruning_predicate(predicate, &self.schema)?;
         Ok(self
             .inner
             .iter()
@@ -204,11 +254,9 @@ impl<'a> PruningStatistics for AddContainer<'a> {
                         None => ScalarValue::UInt64(Some(statistics.num_records as u64)),
                     }
                 } else {
-                    statistics
-                        .null_count
-                        .get(&column.name)
-                        .map(|f| ScalarValue::UInt64(f.as_value().map(|val| val as u64)))
-                        .unwrap_or(ScalarValue::UInt64(None))
+                    ScalarValue::UInt64(
+                        count_stat(&statistics.null_count, &column.name).map(|val| val as u64),
+                    )
                 }
             } else if self.partition_columns.contains(&column.name) {
                 let value = add.partition_values.get(&column.name).unwrap();
@@ -329,6 +377,7 @@ impl PruningStatistics for DeltaTableState {
 mod tests {
     use super::*;
     use crate::delta_datafusion::DataFusionFileMixins;
+    use crate::kernel::Action;
     use crate::operations::transaction::test_utils::{create_add_action, init_table_actions};
     use datafusion::prelude::SessionContext;
     use datafusion_expr::{col, lit};
@@ -387,4 +436,40 @@ mod tests {
         assert_eq!(files.len(), 2);
         assert!(files.iter().all(|add| add.path.contains("included")));
     }
+
+    #[test]
+    fn test_files_matching_predicate_with_tags() {
+        let tagged = |path: &str, min: i32, max: i32| {
+            let mut action = create_add_action(path, true, Some("{\"numRecords\":10}".into()));
+            if let Action::Add(add) = &mut action {
+                add.tags = Some(
+                    [
+                        (
+                            format!("{MIN_VALUE_TAG_PREFIX}value"),
+                            Some(min.to_string()),
+                        ),
+                        (
+                            format!("{MAX_VALUE_TAG_PREFIX}value"),
+                            Some(max.to_string()),
+                        ),
+                    ]
+                    .into(),
+                );
+            }
+            action
+        };
+        let mut actions = init_table_actions(None);
+        actions.push(tagged("excluded", 1, 10));
+        actions.push(tagged("included-1", 1, 100));
+        actions.push(create_add_action("included-untagged", true, None));
+
+        let state = DeltaTableState::from_actions(actions).unwrap();
+        let files = state
+            .snapshot
+            .files_matching_predicate(&[col("value").gt(lit::<i32>(10))])
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(files.len(), 2);
+        assert!(files.iter().all(|add| add.path.contains("included")));
+    }
 }
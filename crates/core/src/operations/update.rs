@@ -26,14 +26,17 @@ use std::{
 };
 
 use arrow::datatypes::Schema as ArrowSchema;
-use arrow_schema::Field;
+use arrow_schema::{DataType, Field, Fields};
+use chrono::Utc;
 use datafusion::{
     execution::context::SessionState,
     physical_plan::{metrics::MetricBuilder, projection::ProjectionExec, ExecutionPlan},
     prelude::SessionContext,
 };
 use datafusion_common::{Column, DFSchema, ScalarValue};
-use datafusion_expr::{case, col, lit, when, Expr};
+use datafusion_expr::{case, cast, col, lit, when, Expr};
+use datafusion_functions::core::expr_ext::FieldAccessor;
+use datafusion_functions::core::expr_fn::named_struct;
 use datafusion_physical_expr::{
     expressions::{self},
     PhysicalExpr,
@@ -41,10 +44,13 @@ use datafusion_physical_expr::{
 use futures::future::BoxFuture;
 use object_store::prefix::PrefixStore;
 use parquet::file::properties::WriterProperties;
-use serde::Serialize;
+use tokio_util::sync::CancellationToken;
 use tracing::log::*;
+use tracing::{field, info_span, Instrument};
 
+use super::cancellation::{cancellable, UncommittedFiles};
 use super::write::write_execution_plan;
+pub use super::UpdateMetrics;
 use super::{
     datafusion_utils::Expression,
     transaction::{CommitBuilder, CommitProperties},
@@ -55,13 +61,13 @@ use crate::delta_datafusion::{
     DeltaSessionContext,
 };
 use crate::delta_datafusion::{find_files, register_store, DeltaScanBuilder};
-use crate::kernel::{Action, AddCDCFile, Remove};
-use crate::logstore::LogStoreRef;
+use crate::kernel::{Action, AddCDCFile, Remove, Transaction};
+use crate::logstore::{operation_log_store, LogStoreRef};
 use crate::operations::cdc::*;
 use crate::operations::writer::{DeltaWriter, WriterConfig};
 use crate::protocol::DeltaOperation;
 use crate::table::state::DeltaTableState;
-use crate::{DeltaResult, DeltaTable};
+use crate::{DeltaResult, DeltaTable, DeltaTableError};
 
 /// Custom column name used for marking internal [RecordBatch] rows as updated
 pub(crate) const UPDATE_PREDICATE_COLNAME: &str = "__delta_rs_update_predicate";
@@ -86,23 +92,8 @@ pub struct UpdateBuilder {
     /// safe_cast determines how data types that do not match the underlying table are handled
     /// By default an error is returned
     safe_cast: bool,
-}
-
-#[derive(Default, Serialize, Debug)]
-/// Metrics collected during the Update operation
-pub struct UpdateMetrics {
-    /// Number of files added.
-    pub num_added_files: usize,
-    /// Number of files removed.
-    pub num_removed_files: usize,
-    /// Number of rows updated.
-    pub num_updated_rows: usize,
-    /// Number of rows just copied over in the process of updating files.
-    pub num_copied_rows: usize,
-    /// Time taken to execute the entire operation.
-    pub execution_time_ms: u64,
-    /// Time taken to scan the files for matches.
-    pub scan_time_ms: u64,
+    /// Token to cancel the update
+    cancellation_token: Option<CancellationToken>,
 }
 
 impl super::Operation<()> for UpdateBuilder {}
@@ -119,6 +110,7 @@ impl UpdateBuilder {
             writer_properties: None,
             commit_properties: CommitProperties::default(),
             safe_cast: false,
+            cancellation_token: None,
         }
     }
 
@@ -129,6 +121,9 @@ impl UpdateBuilder {
     }
 
     /// Perform an additional update expression during the operaton
+    ///
+    /// Fields of struct columns are addressed by their dot separated path, e.g. `address.city`.
+    /// Only the targeted field is replaced, all other fields of the struct are preserved.
     pub fn with_update<S: Into<DeltaColumn>, E: Into<Expression>>(
         mut self,
         column: S,
@@ -150,6 +145,19 @@ impl UpdateBuilder {
         self
     }
 
+    /// Record an application transaction in the update commit, like
+    /// [`WriteBuilder::with_application_transaction`](crate::operations::write::WriteBuilder::with_application_transaction)
+    pub fn with_application_transaction(mut self, app_id: impl ToString, version: i64) -> Self {
+        self.commit_properties =
+            self.commit_properties
+                .with_application_transaction(Transaction::new_with_last_update(
+                    app_id,
+                    version,
+                    Some(Utc::now().timestamp_millis()),
+                ));
+        self
+    }
+
     /// Writer properties passed to parquet writer for when fiiles are rewritten
     pub fn with_writer_properties(mut self, writer_properties: WriterProperties) -> Self {
         self.writer_properties = Some(writer_properties);
@@ -169,6 +177,115 @@ impl UpdateBuilder {
         self.safe_cast = safe_cast;
         self
     }
+
+    /// Abort the update once `token` is cancelled.
+    ///
+    /// The update then returns [`DeltaTableError::Cancelled`] without committing, and deletes
+    /// the rewritten files.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+}
+
+/// Combine the updates of nested struct fields into updates of their top level columns.
+///
+/// Updates are keyed by the name of a top level column, or the dot separated path of a field
+/// nested in struct columns.
+fn nested_updates(
+    updates: Vec<(String, Expr)>,
+    fields: &Fields,
+) -> DeltaResult<HashMap<String, Expr>> {
+    let updates = updates
+        .into_iter()
+        .map(|(name, expr)| {
+            let path = if fields.find(&name).is_some() {
+                vec![name]
+            } else {
+                name.split('.').map(|part| part.to_string()).collect()
+            };
+            (path, expr)
+        })
+        .collect::<Vec<_>>();
+
+    let mut columns: HashMap<String, Vec<(&[String], Expr)>> = HashMap::new();
+    for (path, expr) in updates.iter() {
+        columns
+            .entry(path[0].clone())
+            .or_default()
+            .push((&path[1..], expr.clone()));
+    }
+
+    columns
+        .into_iter()
+        .map(|(name, updates)| {
+            let expr = match fields.find(&name) {
+                Some((_, field)) => assign_fields(
+                    Expr::Column(Column::from_name(&name)),
+                    field,
+                    updates,
+                    &name,
+                )?,
+                // unknown columns are reported when planning the update
+                None => updates.into_iter().next().unwrap().1,
+            };
+            Ok((name, expr))
+        })
+        .collect()
+}
+
+/// Expression replacing the fields of `base` targeted by `updates`, keyed by their path
+/// relative to `field`.
+fn assign_fields(
+    base: Expr,
+    field: &Field,
+    updates: Vec<(&[String], Expr)>,
+    name: &str,
+) -> DeltaResult<Expr> {
+    if let Some((_, expr)) = updates.iter().find(|(path, _)| path.is_empty()) {
+        if updates.len() > 1 {
+            return Err(DeltaTableError::Generic(format!(
+                "Conflicting updates of column {name} and its nested fields"
+            )));
+        }
+        return Ok(expr.clone());
+    }
+    let DataType::Struct(children) = field.data_type() else {
+        return Err(DeltaTableError::Generic(format!(
+            "Cannot update nested fields of column {name}, it is not a struct"
+        )));
+    };
+    for (path, _) in updates.iter() {
+        if children.find(&path[0]).is_none() {
+            return Err(DeltaTableError::Generic(format!(
+                "Field {} does not exist in struct {name}",
+                path[0]
+            )));
+        }
+    }
+
+    let mut args = Vec::with_capacity(children.len() * 2);
+    for child in children.iter() {
+        let child_updates = updates
+            .iter()
+            .filter(|(path, _)| &path[0] == child.name())
+            .map(|(path, expr)| (&path[1..], expr.clone()))
+            .collect::<Vec<_>>();
+        let child_base = base.clone().field(child.name().as_str());
+        let expr = if child_updates.is_empty() {
+            child_base
+        } else {
+            assign_fields(
+                child_base,
+                child,
+                child_updates,
+                &format!("{name}.{}", child.name()),
+            )?
+        };
+        args.push(lit(child.name().as_str()));
+        args.push(expr);
+    }
+    Ok(cast(named_struct(args), field.data_type().clone()))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -210,12 +327,13 @@ async fn execute(
     let updates = updates
         .into_iter()
         .map(|(key, expr)| match expr {
-            Expression::DataFusion(e) => Ok((key.name, e)),
+            Expression::DataFusion(e) => Ok((key.flat_name(), e)),
             Expression::String(s) => snapshot
                 .parse_predicate_expression(s, &state)
-                .map(|e| (key.name, e)),
+                .map(|e| (key.flat_name(), e)),
         })
-        .collect::<Result<HashMap<String, Expr>, _>>()?;
+        .collect::<Result<Vec<(String, Expr)>, _>>()?;
+    let updates = nested_updates(updates, snapshot.input_schema()?.fields())?;
 
     let current_metadata = snapshot.metadata();
     let table_partition_cols = current_metadata.partition_columns.clone();
@@ -343,6 +461,7 @@ async fn execute(
             .map(|v| v.iter().map(|v| v.to_string()).collect::<Vec<String>>()),
     );
 
+    let rewrite_start = Instant::now();
     let add_actions = write_execution_plan(
         Some(&snapshot),
         state.clone(),
@@ -358,6 +477,7 @@ async fn execute(
         Some(tracker.post_sender()),
     )
     .await?;
+    metrics.rewrite_time_ms = Instant::now().duration_since(rewrite_start).as_millis() as u64;
 
     let count_metrics = count_plan.metrics().unwrap();
 
@@ -376,6 +496,8 @@ async fn execute(
         .unwrap()
         .as_millis() as i64;
     let mut actions: Vec<Action> = add_actions.clone();
+    let mut uncommitted = UncommittedFiles::new(log_store.object_store());
+    uncommitted.track(&actions);
 
     metrics.num_added_files = actions.len();
     metrics.num_removed_files = candidates.candidates.len();
@@ -407,7 +529,7 @@ async fn execute(
 
     commit_properties.app_metadata.insert(
         "operationMetrics".to_owned(),
-        serde_json::to_value(&metrics)?,
+        super::operation_metrics(&metrics)?,
     );
 
     match tracker.collect().await {
@@ -456,6 +578,7 @@ async fn execute(
         }
     };
 
+    uncommitted.committed();
     let commit = CommitBuilder::from(commit_properties)
         .with_actions(actions)
         .build(Some(&snapshot), log_store, operation)
@@ -470,16 +593,26 @@ impl std::future::IntoFuture for UpdateBuilder {
 
     fn into_future(self) -> Self::IntoFuture {
         let this = self;
+        let cancellation_token = this.cancellation_token.clone();
+        let span = info_span!(
+            "delta.update",
+            table_uri = %this.log_store.root_uri(),
+            version = this.snapshot.version(),
+            predicate = this.predicate.as_ref().map(field::display),
+            columns = ?this.updates.keys().map(|column| column.to_string()).collect::<Vec<_>>(),
+        );
 
-        Box::pin(async move {
+        let future = cancellable(cancellation_token, async move {
             PROTOCOL.check_append_only(&this.snapshot.snapshot)?;
             PROTOCOL.can_write_to(&this.snapshot.snapshot)?;
+            this.snapshot.ensure_all_files("UPDATE")?;
+            let log_store = operation_log_store(&this.log_store);
 
             let state = this.state.unwrap_or_else(|| {
                 let session: SessionContext = DeltaSessionContext::default().into();
 
                 // If a user provides their own their DF state then they must register the store themselves
-                register_store(this.log_store.clone(), session.runtime_env());
+                register_store(log_store.clone(), session.runtime_env());
 
                 session.state()
             });
@@ -487,7 +620,7 @@ impl std::future::IntoFuture for UpdateBuilder {
             let (snapshot, metrics) = execute(
                 this.predicate,
                 this.updates,
-                this.log_store.clone(),
+                log_store,
                 this.snapshot,
                 state,
                 this.writer_properties,
@@ -500,7 +633,9 @@ impl std::future::IntoFuture for UpdateBuilder {
                 DeltaTable::new_with_state(this.log_store, snapshot),
                 metrics,
             ))
-        })
+        });
+
+        Box::pin(future.instrument(span))
     }
 }
 
@@ -512,7 +647,7 @@ mod tests {
     use crate::kernel::DataType as DeltaDataType;
     use crate::kernel::{Action, PrimitiveType, Protocol, StructField, StructType};
     use crate::operations::collect_sendable_stream;
-    use crate::operations::DeltaOps;
+    use crate::operations::{operation_metrics, DeltaOps};
     use crate::writer::test_utils::datafusion::get_data;
     use crate::writer::test_utils::datafusion::write_batch;
     use crate::writer::test_utils::{
@@ -907,8 +1042,10 @@ mod tests {
         let extra_info = last_commit.info.clone();
         assert_eq!(
             extra_info["operationMetrics"],
-            serde_json::to_value(&metrics).unwrap()
+            operation_metrics(&metrics).unwrap()
         );
+        assert_eq!(extra_info["operationMetrics"]["numUpdatedRows"], json!(2));
+        assert_eq!(extra_info["operationMetrics"]["numCopiedRows"], json!(3));
 
         let expected = [
             "+-------+",
@@ -1144,6 +1281,68 @@ mod tests {
             ], &batches }
     }
 
+    #[tokio::test]
+    async fn test_update_nested_field() {
+        let address_fields = arrow_schema::Fields::from(vec![
+            Field::new("city", DataType::Utf8, true),
+            Field::new("zip", DataType::Utf8, true),
+        ]);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new("address", DataType::Struct(address_fields.clone()), true),
+        ]));
+        let address = arrow::array::StructArray::new(
+            address_fields,
+            vec![
+                Arc::new(StringArray::from(vec!["Paris", "Berlin"])),
+                Arc::new(StringArray::from(vec!["75001", "10115"])),
+            ],
+            None,
+        );
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["A", "B"])),
+                Arc::new(address),
+            ],
+        )
+        .unwrap();
+        let table = DeltaOps::new_in_memory().write(vec![batch]).await.unwrap();
+
+        let (table, metrics) = DeltaOps(table)
+            .update()
+            .with_predicate(col("id").eq(lit("A")))
+            .with_update("address.city", lit("NYC"))
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 1);
+        assert_eq!(metrics.num_updated_rows, 1);
+        assert_eq!(metrics.num_copied_rows, 1);
+
+        let expected = vec![
+            "+----+----------------------------+",
+            "| id | address                    |",
+            "+----+----------------------------+",
+            "| A  | {city: NYC, zip: 75001}    |",
+            "| B  | {city: Berlin, zip: 10115} |",
+            "+----+----------------------------+",
+        ];
+        let actual = get_data(&table).await;
+        assert_batches_sorted_eq!(&expected, &actual);
+
+        let res = DeltaOps(table.clone())
+            .update()
+            .with_update("address.country", lit("US"))
+            .await;
+        assert!(res.is_err());
+
+        let res = DeltaOps(table)
+            .update()
+            .with_update("id.city", lit("NYC"))
+            .await;
+        assert!(res.is_err());
+    }
+
     #[tokio::test]
     async fn test_update_cdc_enabled_partitions() {
         // Currently you cannot pass EnableChangeDataFeed through `with_configuration_property`
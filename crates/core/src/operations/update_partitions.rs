@@ -0,0 +1,335 @@
+//! Update partition values of a Delta Table without DataFusion
+//!
+//! Without the `datafusion` feature only partition columns can be updated, and only to literal
+//! values. Files are selected by a predicate on partition columns, see
+//! [`PartitionFilter::from_predicate`]. Since partition values are not stored in the data files,
+//! every matching file is copied into the directory of its new partition within the object
+//! store and the log is updated to reference the copy, without reading or rewriting any data.
+//!
+//! # Example
+//! ```rust ignore
+//! let table = open_table("../path/to/table")?;
+//! let (table, metrics) = DeltaOps(table)
+//!     .update()
+//!     .with_predicate("date = '2022-05-22'")
+//!     .with_update("date", "'2022-05-23'")
+//!     .await?;
+//! ````
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use chrono::Utc;
+use delta_kernel::expressions::Scalar;
+use futures::future::BoxFuture;
+use indexmap::IndexMap;
+use object_store::path::Path;
+use percent_encoding::percent_decode_str;
+use uuid::Uuid;
+
+use super::transaction::{CommitBuilder, CommitProperties, PROTOCOL};
+pub use super::UpdateMetrics;
+use crate::errors::{DeltaResult, DeltaTableError};
+use crate::kernel::{Action, Add, DataType, PartitionsExt};
+use crate::logstore::LogStoreRef;
+use crate::protocol::DeltaOperation;
+use crate::table::state::DeltaTableState;
+use crate::{DeltaTable, PartitionFilter};
+
+/// Update the partition values of the files matching a predicate.
+/// See this module's documentation for more information
+pub struct UpdateBuilder {
+    /// Which partitions to update
+    predicate: Option<String>,
+    /// New values of partition columns, `None` for null
+    updates: HashMap<String, Option<String>>,
+    /// A snapshot of the table's state
+    snapshot: DeltaTableState,
+    /// Delta object store for handling data files
+    log_store: LogStoreRef,
+    /// Commit properties and configuration
+    commit_properties: CommitProperties,
+}
+
+impl super::Operation<()> for UpdateBuilder {}
+
+impl UpdateBuilder {
+    /// Create a new [`UpdateBuilder`]
+    pub fn new(log_store: LogStoreRef, snapshot: DeltaTableState) -> Self {
+        Self {
+            predicate: None,
+            updates: HashMap::new(),
+            snapshot,
+            log_store,
+            commit_properties: CommitProperties::default(),
+        }
+    }
+
+    /// A predicate on partition columns that determines which files are updated,
+    /// see [`PartitionFilter::from_predicate`] for the supported syntax
+    pub fn with_predicate(mut self, predicate: impl Into<String>) -> Self {
+        self.predicate = Some(predicate.into());
+        self
+    }
+
+    /// Set a partition column to a literal value, e.g. `'2022-05-23'`, `42` or `NULL`
+    pub fn with_update(mut self, column: impl Into<String>, value: impl AsRef<str>) -> Self {
+        self.updates
+            .insert(column.into(), parse_literal(value.as_ref()));
+        self
+    }
+
+    /// Additonal information to write to the commit
+    pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
+        self.commit_properties = commit_properties;
+        self
+    }
+}
+
+/// Parse a SQL literal into a serialized partition value
+fn parse_literal(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("null") {
+        None
+    } else if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        Some(value[1..value.len() - 1].replace("''", "'"))
+    } else {
+        Some(value.to_string())
+    }
+}
+
+async fn execute(
+    predicate: Option<String>,
+    updates: HashMap<String, Option<String>>,
+    log_store: LogStoreRef,
+    snapshot: DeltaTableState,
+    mut commit_properties: CommitProperties,
+) -> DeltaResult<(DeltaTableState, UpdateMetrics)> {
+    let exec_start = Instant::now();
+    let mut metrics = UpdateMetrics::default();
+
+    let partition_columns = &snapshot.metadata().partition_columns;
+    if let Some(column) = updates
+        .keys()
+        .find(|column| !partition_columns.contains(column))
+    {
+        return Err(DeltaTableError::Generic(format!(
+            "Updating the non-partition column {column} requires the datafusion feature"
+        )));
+    }
+    let filters = match &predicate {
+        Some(predicate) => PartitionFilter::from_predicate(predicate)?,
+        None => vec![],
+    };
+    if let Some(filter) = filters
+        .iter()
+        .find(|filter| !partition_columns.contains(&filter.key))
+    {
+        return Err(DeltaTableError::Generic(format!(
+            "Updating by a predicate on the non-partition column {} requires the datafusion feature",
+            filter.key
+        )));
+    }
+
+    // partition values are validated against the column types before any file is touched
+    let mut partition_types = HashMap::new();
+    for column in partition_columns {
+        let Some(DataType::Primitive(primitive)) = snapshot
+            .schema()
+            .field(column)
+            .map(|field| field.data_type())
+        else {
+            return Err(DeltaTableError::Generic(format!(
+                "Partition column {column} does not have a primitive type"
+            )));
+        };
+        if let Some(Some(value)) = updates.get(column) {
+            primitive.parse_scalar(value)?;
+        }
+        partition_types.insert(column.as_str(), primitive);
+    }
+
+    let scan_start = Instant::now();
+    let mut matches = HashMap::new();
+    for file in snapshot.get_active_add_actions_by_partitions(&filters)? {
+        let file = file?;
+        metrics.num_updated_rows += file.num_records().unwrap_or_default();
+        matches.insert(
+            file.path().to_string(),
+            (file.object_store_path(), file.remove_action(true)),
+        );
+    }
+    metrics.scan_time_ms = Instant::now().duration_since(scan_start).as_millis() as u64;
+
+    let rewrite_start = Instant::now();
+    let store = log_store.object_store();
+    let mut actions = Vec::new();
+    for add in snapshot.file_actions_iter()? {
+        let path = percent_decode_str(&add.path).decode_utf8_lossy();
+        let Some((location, remove)) = matches.remove(path.as_ref()) else {
+            continue;
+        };
+        let mut partition_values = add.partition_values.clone();
+        partition_values.extend(updates.clone());
+
+        let mut typed_values = IndexMap::new();
+        for column in partition_columns {
+            let value = match partition_values.get(column).cloned().flatten() {
+                Some(value) => partition_types[column.as_str()].parse_scalar(&value)?,
+                None => Scalar::Null(DataType::Primitive(
+                    partition_types[column.as_str()].clone(),
+                )),
+            };
+            typed_values.insert(column.clone(), value);
+        }
+        let extension = location
+            .filename()
+            .and_then(|name| name.split_once('.'))
+            .map(|(_, extension)| extension)
+            .unwrap_or("parquet");
+        let new_location = Path::parse(typed_values.hive_partition_path())?
+            .child(format!("part-00000-{}-c000.{extension}", Uuid::new_v4()));
+        store.copy(&location, &new_location).await?;
+
+        actions.push(Action::Remove(remove));
+        actions.push(Action::Add(Add {
+            path: new_location.to_string(),
+            partition_values,
+            modification_time: Utc::now().timestamp_millis(),
+            data_change: true,
+            ..add
+        }));
+        metrics.num_removed_files += 1;
+        metrics.num_added_files += 1;
+    }
+    metrics.rewrite_time_ms = Instant::now().duration_since(rewrite_start).as_millis() as u64;
+    metrics.execution_time_ms = Instant::now().duration_since(exec_start).as_millis() as u64;
+
+    // Do not make a commit when there are zero updates to the state
+    if actions.is_empty() {
+        return Ok((snapshot, metrics));
+    }
+
+    commit_properties
+        .app_metadata
+        .insert("readVersion".to_owned(), snapshot.version().into());
+    commit_properties.app_metadata.insert(
+        "operationMetrics".to_owned(),
+        super::operation_metrics(&metrics)?,
+    );
+
+    let operation = DeltaOperation::Update { predicate };
+    let commit = CommitBuilder::from(commit_properties)
+        .with_actions(actions)
+        .build(Some(&snapshot), log_store, operation)
+        .await?;
+    Ok((commit.snapshot(), metrics))
+}
+
+impl std::future::IntoFuture for UpdateBuilder {
+    type Output = DeltaResult<(DeltaTable, UpdateMetrics)>;
+    type IntoFuture = BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let this = self;
+
+        Box::pin(async move {
+            PROTOCOL.check_append_only(&this.snapshot.snapshot)?;
+            PROTOCOL.can_write_to(&this.snapshot.snapshot)?;
+            this.snapshot.ensure_all_files("UPDATE")?;
+
+            let (new_snapshot, metrics) = execute(
+                this.predicate,
+                this.updates,
+                this.log_store.clone(),
+                this.snapshot,
+                this.commit_properties,
+            )
+            .await?;
+
+            Ok((
+                DeltaTable::new_with_state(this.log_store, new_snapshot),
+                metrics,
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_literal;
+    use crate::operations::DeltaOps;
+    use crate::writer::test_utils::{create_initialized_table, get_record_batch};
+    use crate::writer::{DeltaWriter, RecordBatchWriter};
+    use crate::DeltaTable;
+
+    async fn setup_table() -> DeltaTable {
+        let mut table = create_initialized_table(&["modified".to_string()]).await;
+        let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+        writer.write(get_record_batch(None, false)).await.unwrap();
+        writer.flush_and_commit(&mut table).await.unwrap();
+        assert_eq!(table.version(), 1);
+        assert_eq!(table.get_files_count(), 2);
+        table
+    }
+
+    #[test]
+    fn test_parse_literal() {
+        assert_eq!(
+            parse_literal("'2021-02-03'"),
+            Some("2021-02-03".to_string())
+        );
+        assert_eq!(parse_literal("'it''s'"), Some("it's".to_string()));
+        assert_eq!(parse_literal(" 42 "), Some("42".to_string()));
+        assert_eq!(parse_literal("NULL"), None);
+    }
+
+    #[tokio::test]
+    async fn test_update_partitions() {
+        let table = setup_table().await;
+
+        let (table, metrics) = DeltaOps(table)
+            .update()
+            .with_predicate("modified = '2021-02-02'")
+            .with_update("modified", "'2021-02-03'")
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 2);
+        assert_eq!(table.get_files_count(), 2);
+        assert_eq!(metrics.num_removed_files, 1);
+        assert_eq!(metrics.num_added_files, 1);
+        assert_eq!(metrics.num_updated_rows, 3);
+        assert!(table
+            .get_files_iter()
+            .unwrap()
+            .any(|path| path.as_ref().starts_with("modified=2021-02-03/")));
+        assert!(!table
+            .get_files_iter()
+            .unwrap()
+            .any(|path| path.as_ref().starts_with("modified=2021-02-02/")));
+
+        // the copied file can be read from its new location
+        let store = table.object_store();
+        for path in table.get_files_iter().unwrap() {
+            store.head(&path).await.unwrap();
+        }
+
+        // nothing matches, so no commit is made
+        let (table, metrics) = DeltaOps(table)
+            .update()
+            .with_predicate("modified = '2021-02-02'")
+            .with_update("modified", "'2021-02-04'")
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 2);
+        assert_eq!(metrics.num_removed_files, 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_non_partition_column() {
+        let table = setup_table().await;
+
+        let res = DeltaOps(table).update().with_update("value", "1").await;
+        assert!(res.is_err());
+    }
+}
@@ -12,6 +12,21 @@
 //! When you run vacuum then you cannot use time travel to a version older than
 //! the specified retention period.
 //!
+//! By default the table directory is listed to find the files to delete. A [`VacuumMode::Lite`]
+//! vacuum only reads the transaction log instead, which is much faster on tables with many
+//! objects, see [`VacuumMode`].
+//!
+//! The vacuum is recorded in the log by a `VACUUM START` commit before deleting any file and a
+//! `VACUUM END` commit afterwards, carrying the same operation metrics as Spark. This can be
+//! disabled with [`VacuumBuilder::with_operation_logging`].
+//!
+//! Files are deleted with several concurrent requests, optionally limited to a number of
+//! requests per second. On S3, batches of up to 1000 files are deleted by a single bulk
+//! `DeleteObjects` request.
+//!
+//! A vacuum given a [`CancellationToken`] stops listing or deleting files once the token is
+//! cancelled. Files deleted up to then stay deleted, and no `VACUUM END` commit is made.
+//!
 //! Warning: Vacuum does not support partitioned tables on Windows. This is due
 //! to Windows not using unix style paths. See #682
 //!
@@ -21,8 +36,9 @@
 //! let (table, metrics) = VacuumBuilder::new(table.object_store(). table.state).await?;
 //! ````
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::num::NonZeroU32;
 use std::sync::Arc;
 
 use chrono::{Duration, Utc};
@@ -31,11 +47,18 @@ use futures::{StreamExt, TryStreamExt};
 use object_store::Error;
 use object_store::{path::Path, ObjectStore};
 use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{info_span, Instrument};
 
+use super::cancellation::cancellable;
+use super::filesystem_check::is_absolute_path;
+use super::progress::{report, ProgressReporterRef, ProgressStage};
 use super::transaction::{CommitBuilder, CommitProperties};
 use crate::errors::{DeltaResult, DeltaTableError};
 use crate::logstore::LogStoreRef;
 use crate::protocol::DeltaOperation;
+use crate::storage::ObjectStoreRef;
+use crate::table::governance::{DestructiveOperation, GovernanceError, GovernancePolicy};
 use crate::table::state::DeltaTableState;
 use crate::DeltaTable;
 
@@ -59,6 +82,14 @@ enum VacuumError {
 
     #[error(transparent)]
     Protocol(#[from] crate::protocol::ProtocolError),
+
+    /// Error returned when the vacuum is rejected by the governance policy
+    #[error(transparent)]
+    Governance(#[from] GovernanceError),
+
+    /// Error returned when commit properties are set for a vacuum which is not committed
+    #[error("Commit properties cannot be used when the operation logging of vacuum is disabled")]
+    CommitPropertiesWithoutLogging,
 }
 
 impl From<VacuumError> for DeltaTableError {
@@ -69,6 +100,25 @@ impl From<VacuumError> for DeltaTableError {
     }
 }
 
+/// Default number of concurrent delete requests sent by a vacuum
+pub const DEFAULT_MAX_CONCURRENT_DELETES: usize = 10;
+
+/// Number of files removed by one bulk delete request, the maximum supported by S3
+const DELETE_BATCH_SIZE: usize = 1000;
+
+/// How a vacuum determines the files to delete
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VacuumMode {
+    /// Delete the files removed from the table before the retention horizon, according to the
+    /// remove actions in the transaction log. The table directory is not listed, which makes
+    /// this much faster on large tables. Files whose remove actions have been dropped from the
+    /// log by a checkpoint are left behind, and are only cleaned up by a full vacuum.
+    Lite,
+    /// List all files of the table and delete those removed before the retention horizon
+    #[default]
+    Full,
+}
+
 /// A source of time
 pub trait Clock: Debug + Send + Sync {
     /// get the current time in milliseconds since epoch
@@ -91,8 +141,24 @@ pub struct VacuumBuilder {
     dry_run: bool,
     /// Override the source of time
     clock: Option<Arc<dyn Clock>>,
+    /// Policy checked before deleting any files
+    governance_policy: Option<Arc<dyn GovernancePolicy>>,
     /// Additional information to add to the commit
-    commit_properties: CommitProperties,
+    commit_properties: Option<CommitProperties>,
+    /// Maximum number of delete requests in flight
+    max_concurrent_deletes: usize,
+    /// Maximum number of delete requests sent per second
+    max_requests_per_second: Option<NonZeroU32>,
+    /// Delete files in batches with the bulk delete API of the store
+    bulk_delete: Option<bool>,
+    /// How the files to delete are found
+    mode: VacuumMode,
+    /// Record the vacuum with `VACUUM START` and `VACUUM END` commits
+    operation_logging: bool,
+    /// Token to cancel the vacuum
+    cancellation_token: Option<CancellationToken>,
+    /// Receiver of the number of deleted files
+    progress_reporter: Option<ProgressReporterRef>,
 }
 
 impl super::Operation<()> for VacuumBuilder {}
@@ -137,7 +203,15 @@ impl VacuumBuilder {
             enforce_retention_duration: true,
             dry_run: false,
             clock: None,
-            commit_properties: CommitProperties::default(),
+            governance_policy: None,
+            commit_properties: None,
+            max_concurrent_deletes: DEFAULT_MAX_CONCURRENT_DELETES,
+            max_requests_per_second: None,
+            bulk_delete: None,
+            mode: VacuumMode::default(),
+            operation_logging: true,
+            cancellation_token: None,
+            progress_reporter: None,
         }
     }
 
@@ -147,6 +221,12 @@ impl VacuumBuilder {
         self
     }
 
+    /// Set how the files to delete are found, see [`VacuumMode`]
+    pub fn with_mode(mut self, mode: VacuumMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
     /// Only determine which files should be deleted
     pub fn with_dry_run(mut self, dry_run: bool) -> Self {
         self.dry_run = dry_run;
@@ -166,9 +246,66 @@ impl VacuumBuilder {
         self
     }
 
+    /// Check the vacuum against a governance policy before deleting any files
+    pub fn with_governance_policy(mut self, policy: Arc<dyn GovernancePolicy>) -> Self {
+        self.governance_policy = Some(policy);
+        self
+    }
+
     /// Additional metadata to be added to commit info
     pub fn with_commit_properties(mut self, commit_properties: CommitProperties) -> Self {
-        self.commit_properties = commit_properties;
+        self.commit_properties = Some(commit_properties);
+        self
+    }
+
+    /// Record the vacuum in the log with `VACUUM START` and `VACUUM END` commits, defaults to
+    /// `true`
+    ///
+    /// The commits carry the metrics of the vacuum, `numFilesToDelete` and `sizeOfDataToDelete`
+    /// at the start and `numDeletedFiles` at the end, along with the commit properties. Nothing is
+    /// committed when there are no files to delete. Disabling it fails the vacuum if commit
+    /// properties are set, as these would never be written.
+    pub fn with_operation_logging(mut self, operation_logging: bool) -> Self {
+        self.operation_logging = operation_logging;
+        self
+    }
+
+    /// Maximum number of delete requests sent concurrently, defaults to
+    /// [`DEFAULT_MAX_CONCURRENT_DELETES`]
+    pub fn with_max_concurrent_deletes(mut self, max_concurrent_deletes: usize) -> Self {
+        self.max_concurrent_deletes = max_concurrent_deletes.max(1);
+        self
+    }
+
+    /// Limit the number of delete requests sent per second
+    ///
+    /// A bulk delete request counts as a single request.
+    pub fn with_max_requests_per_second(mut self, max_requests_per_second: NonZeroU32) -> Self {
+        self.max_requests_per_second = Some(max_requests_per_second);
+        self
+    }
+
+    /// Whether to delete files in batches through [`ObjectStore::delete_stream`]
+    ///
+    /// Stores with a bulk delete API then remove up to 1000 files per request, e.g. S3 with
+    /// `DeleteObjects`. Stores without such an API delete the files of a batch one at a time, so
+    /// this is enabled by default for S3 tables only.
+    pub fn with_bulk_delete(mut self, bulk_delete: bool) -> Self {
+        self.bulk_delete = Some(bulk_delete);
+        self
+    }
+
+    /// Abort the vacuum once `token` is cancelled.
+    ///
+    /// The vacuum then returns [`DeltaTableError::Cancelled`] and stops deleting files.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Report the number of deleted files as [`ProgressStage::VacuumDelete`]
+    pub fn with_progress_reporter(mut self, reporter: ProgressReporterRef) -> Self {
+        self.progress_reporter = Some(reporter);
         self
     }
 
@@ -190,6 +327,13 @@ impl VacuumBuilder {
             });
         }
 
+        if let Some(policy) = &self.governance_policy {
+            let operation = DestructiveOperation::Vacuum {
+                retention_period: retention_period.to_std().unwrap_or_default(),
+            };
+            policy.check(&self.snapshot.table_config().governance(), &operation)?;
+        }
+
         let now_millis = match &self.clock {
             Some(clock) => clock.current_timestamp_millis(),
             None => Utc::now().timestamp_millis(),
@@ -206,22 +350,43 @@ impl VacuumBuilder {
 
         let mut files_to_delete = vec![];
         let mut file_sizes = vec![];
-        let object_store = self.log_store.object_store();
-        let mut all_files = object_store.list(None);
-        let partition_columns = &self.snapshot.metadata().partition_columns;
-
-        while let Some(obj_meta) = all_files.next().await {
-            // TODO should we allow NotFound here in case we have a temporary commit file in the list
-            let obj_meta = obj_meta.map_err(DeltaTableError::from)?;
-            if valid_files.contains(&obj_meta.location) // file is still being tracked in table
-            || !expired_tombstones.contains(obj_meta.location.as_ref()) // file is not an expired tombstone
-            || is_hidden_directory(partition_columns, &obj_meta.location)?
-            {
-                continue;
-            }
 
-            files_to_delete.push(obj_meta.location);
-            file_sizes.push(obj_meta.size as i64);
+        match self.mode {
+            VacuumMode::Lite => {
+                let mut expired_tombstones = expired_tombstones.into_iter().collect::<Vec<_>>();
+                expired_tombstones.sort();
+                for (path, size) in expired_tombstones {
+                    // files outside of the table are not managed by it
+                    if is_absolute_path(&path)? {
+                        continue;
+                    }
+                    let location = Path::parse(&path).unwrap_or_else(|_| Path::from(path));
+                    if valid_files.contains(&location) {
+                        continue;
+                    }
+                    files_to_delete.push(location);
+                    file_sizes.push(size);
+                }
+            }
+            VacuumMode::Full => {
+                let object_store = self.log_store.object_store();
+                let mut all_files = object_store.list(None);
+                let partition_columns = &self.snapshot.metadata().partition_columns;
+
+                while let Some(obj_meta) = all_files.next().await {
+                    // TODO should we allow NotFound here in case we have a temporary commit file in the list
+                    let obj_meta = obj_meta.map_err(DeltaTableError::from)?;
+                    if valid_files.contains(&obj_meta.location) // file is still being tracked in table
+                    || !expired_tombstones.contains_key(obj_meta.location.as_ref()) // file is not an expired tombstone
+                    || is_hidden_directory(partition_columns, &obj_meta.location)?
+                    {
+                        continue;
+                    }
+
+                    files_to_delete.push(obj_meta.location);
+                    file_sizes.push(obj_meta.size as i64);
+                }
+            }
         }
 
         Ok(VacuumPlan {
@@ -240,8 +405,21 @@ impl std::future::IntoFuture for VacuumBuilder {
 
     fn into_future(self) -> Self::IntoFuture {
         let this = self;
+        let cancellation_token = this.cancellation_token.clone();
+        let span = info_span!(
+            "delta.vacuum",
+            table_uri = %this.log_store.root_uri(),
+            version = this.snapshot.version(),
+            retention_period = ?this.retention_period,
+            dry_run = this.dry_run,
+        );
+
+        let future = cancellable(cancellation_token, async move {
+            this.snapshot.ensure_all_files("VACUUM")?;
+            if !this.operation_logging && this.commit_properties.is_some() {
+                return Err(VacuumError::CommitPropertiesWithoutLogging.into());
+            }
 
-        Box::pin(async move {
             let plan = this.create_vacuum_plan().await?;
             if this.dry_run {
                 return Ok((
@@ -253,21 +431,121 @@ impl std::future::IntoFuture for VacuumBuilder {
                 ));
             }
 
+            let bulk_delete = this.bulk_delete.unwrap_or_else(|| {
+                matches!(this.log_store.config().location.scheme(), "s3" | "s3a")
+            });
+            let delete_config = DeleteConfig {
+                max_concurrent_deletes: this.max_concurrent_deletes,
+                max_requests_per_second: this.max_requests_per_second,
+                bulk_delete,
+                progress_reporter: this.progress_reporter.clone(),
+            };
             let metrics = plan
                 .execute(
                     this.log_store.clone(),
                     &this.snapshot,
-                    this.commit_properties,
+                    this.operation_logging
+                        .then(|| this.commit_properties.unwrap_or_default()),
+                    delete_config,
                 )
                 .await?;
             Ok((
                 DeltaTable::new_with_state(this.log_store, this.snapshot),
                 metrics,
             ))
-        })
+        });
+
+        Box::pin(future.instrument(span))
     }
 }
 
+/// How the files of a vacuum are deleted from the object store
+#[derive(Debug, Clone)]
+struct DeleteConfig {
+    max_concurrent_deletes: usize,
+    max_requests_per_second: Option<NonZeroU32>,
+    bulk_delete: bool,
+    progress_reporter: Option<ProgressReporterRef>,
+}
+
+/// Spaces out requests to stay below a maximum number of requests per second
+#[derive(Debug)]
+struct RateLimiter {
+    interval: std::time::Duration,
+    next: tokio::sync::Mutex<tokio::time::Instant>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_second: NonZeroU32) -> Self {
+        Self {
+            interval: std::time::Duration::from_secs(1) / requests_per_second.get(),
+            next: tokio::sync::Mutex::new(tokio::time::Instant::now()),
+        }
+    }
+
+    /// Wait until the next request may be sent
+    async fn acquire(&self) {
+        let mut next = self.next.lock().await;
+        let now = tokio::time::Instant::now();
+        if *next > now {
+            tokio::time::sleep_until(*next).await;
+        }
+        *next = (*next).max(now) + self.interval;
+    }
+}
+
+/// Delete `files` from `store`, returning the paths of the deleted files
+async fn delete_files(
+    store: ObjectStoreRef,
+    files: Vec<Path>,
+    config: DeleteConfig,
+) -> DeltaResult<Vec<String>> {
+    let limiter = config.max_requests_per_second.map(RateLimiter::new);
+    let batch_size = if config.bulk_delete {
+        DELETE_BATCH_SIZE
+    } else {
+        1
+    };
+    let batches = files
+        .chunks(batch_size)
+        .map(|batch| batch.to_vec())
+        .collect::<Vec<_>>();
+
+    let total = files.len() as u64;
+    let mut done = 0;
+    let store = &store;
+    let limiter = &limiter;
+    let deleted = futures::stream::iter(batches)
+        .map(|batch| async move {
+            if let Some(limiter) = limiter {
+                limiter.acquire().await;
+            }
+            let locations = futures::stream::iter(batch).map(Result::Ok).boxed();
+            store
+                .delete_stream(locations)
+                .map(|res| match res {
+                    Ok(path) => Ok(path.to_string()),
+                    Err(Error::NotFound { path, .. }) => Ok(path),
+                    Err(err) => Err(err),
+                })
+                .try_collect::<Vec<_>>()
+                .await
+        })
+        .buffered(config.max_concurrent_deletes)
+        .inspect_ok(|batch| {
+            done += batch.len() as u64;
+            report(
+                config.progress_reporter.as_ref(),
+                ProgressStage::VacuumDelete,
+                done,
+                total,
+            );
+        })
+        .try_concat()
+        .await?;
+    Ok(deleted)
+}
+
 /// Encapsulate which files are to be deleted and the parameters used to make that decision
 struct VacuumPlan {
     /// What files are to be deleted
@@ -284,11 +562,14 @@ struct VacuumPlan {
 
 impl VacuumPlan {
     /// Execute the vacuum plan and delete files from underlying storage
+    ///
+    /// The vacuum is recorded in the log when `commit_properties` are given.
     pub async fn execute(
         self,
         store: LogStoreRef,
         snapshot: &DeltaTableState,
-        mut commit_properties: CommitProperties,
+        commit_properties: Option<CommitProperties>,
+        delete_config: DeleteConfig,
     ) -> Result<VacuumMetrics, DeltaTableError> {
         if self.files_to_delete.is_empty() {
             return Ok(VacuumMetrics {
@@ -297,6 +578,15 @@ impl VacuumPlan {
             });
         }
 
+        let Some(mut commit_properties) = commit_properties else {
+            let files_deleted =
+                delete_files(store.object_store(), self.files_to_delete, delete_config).await?;
+            return Ok(VacuumMetrics {
+                files_deleted,
+                dry_run: false,
+            });
+        };
+
         let start_operation = DeltaOperation::VacuumStart {
             retention_check_enabled: self.retention_check_enabled,
             specified_retention_millis: self.specified_retention_millis,
@@ -325,20 +615,8 @@ impl VacuumPlan {
             .await?;
         // Finish VACUUM START COMMIT
 
-        let locations = futures::stream::iter(self.files_to_delete)
-            .map(Result::Ok)
-            .boxed();
-
-        let files_deleted = store
-            .object_store()
-            .delete_stream(locations)
-            .map(|res| match res {
-                Ok(path) => Ok(path.to_string()),
-                Err(Error::NotFound { path, .. }) => Ok(path),
-                Err(err) => Err(err),
-            })
-            .try_collect::<Vec<_>>()
-            .await?;
+        let files_deleted =
+            delete_files(store.object_store(), self.files_to_delete, delete_config).await?;
 
         // Create end metadata
         let end_metrics = VacuumEndOperationMetrics {
@@ -377,26 +655,27 @@ fn is_hidden_directory(partition_columns: &[String], path: &Path) -> Result<bool
             .any(|partition_column| path_name.starts_with(partition_column)))
 }
 
-/// List files no longer referenced by a Delta table and are older than the retention threshold.
+/// List files no longer referenced by a Delta table and are older than the retention threshold,
+/// along with their size.
 async fn get_stale_files(
     snapshot: &DeltaTableState,
     retention_period: Duration,
     now_timestamp_millis: i64,
     store: Arc<dyn ObjectStore>,
-) -> DeltaResult<HashSet<String>> {
+) -> DeltaResult<HashMap<String, i64>> {
     let tombstone_retention_timestamp = now_timestamp_millis - retention_period.num_milliseconds();
-    Ok(snapshot
-        .all_tombstones(store)
-        .await?
-        .collect::<Vec<_>>()
-        .into_iter()
-        .filter(|tombstone| {
+    snapshot
+        .tombstones_stream(store)?
+        .try_filter_map(|tombstone| {
             // if the file has a creation time before the `tombstone_retention_timestamp`
             // then it's considered as a stale file
-            tombstone.deletion_timestamp.unwrap_or(0) < tombstone_retention_timestamp
+            let stale = tombstone.deletion_timestamp.unwrap_or(0) < tombstone_retention_timestamp;
+            futures::future::ready(Ok(
+                stale.then(|| (tombstone.path, tombstone.size.unwrap_or_default()))
+            ))
         })
-        .map(|tombstone| tombstone.path)
-        .collect::<HashSet<_>>())
+        .try_collect::<HashMap<_, _>>()
+        .await
 }
 
 #[cfg(test)]
@@ -458,4 +737,45 @@ mod tests {
 
         assert_eq!(result.files_deleted, empty);
     }
+
+    #[tokio::test]
+    async fn vacuum_governance_policy() {
+        use crate::table::governance::DefaultGovernancePolicy;
+
+        let table = open_table("../test/tests/data/delta-0.8.0").await.unwrap();
+
+        let result = VacuumBuilder::new(table.log_store(), table.snapshot().unwrap().clone())
+            .with_retention_period(Duration::hours(0))
+            .with_dry_run(true)
+            .with_enforce_retention_duration(false)
+            .with_governance_policy(Arc::new(DefaultGovernancePolicy::default()))
+            .await;
+        assert!(result.is_err());
+
+        let (_table, result) =
+            VacuumBuilder::new(table.log_store(), table.snapshot().unwrap().clone())
+                .with_retention_period(Duration::hours(169))
+                .with_dry_run(true)
+                .with_governance_policy(Arc::new(DefaultGovernancePolicy::default()))
+                .await
+                .unwrap();
+        assert_eq!(
+            result.files_deleted,
+            vec!["part-00001-911a94a2-43f6-4acb-8620-5e68c2654989-c000.snappy.parquet"]
+        );
+    }
+
+    #[tokio::test]
+    async fn vacuum_cancelled() {
+        let table = open_table("../test/tests/data/delta-0.8.0").await.unwrap();
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = VacuumBuilder::new(table.log_store(), table.snapshot().unwrap().clone())
+            .with_retention_period(Duration::hours(169))
+            .with_dry_run(true)
+            .with_cancellation_token(token)
+            .await;
+        assert!(matches!(result, Err(DeltaTableError::Cancelled)));
+    }
 }
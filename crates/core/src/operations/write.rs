@@ -12,7 +12,8 @@
 //!    present, then the partitioning of the table is respected.
 //!
 //! In combination with `Overwrite`, a `replaceWhere` option can be used to transactionally
-//! replace data that matches a predicate.
+//! replace data that matches a predicate. Alternatively, a dynamic [`OverwriteMode`] only
+//! replaces the partitions present in the written data.
 //!
 //! # Example
 //! ```rust ignore
@@ -24,7 +25,7 @@
 //! let table = ops.write(vec![batch]).await?;
 //! ````
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -33,37 +34,50 @@ use std::vec;
 use arrow_array::RecordBatch;
 use arrow_cast::can_cast_types;
 use arrow_schema::{ArrowError, DataType, Fields, SchemaRef as ArrowSchemaRef};
+use chrono::Utc;
 use datafusion::execution::context::{SessionContext, SessionState, TaskContext};
+use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use datafusion::physical_plan::filter::FilterExec;
-use datafusion::physical_plan::{memory::MemoryExec, ExecutionPlan};
-use datafusion_common::DFSchema;
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::{memory::MemoryExec, ExecutionPlan, Partitioning};
+use datafusion_common::{DFSchema, ScalarValue};
 use datafusion_expr::Expr;
+use datafusion_physical_expr::{expressions, PhysicalExpr};
 use futures::future::BoxFuture;
 use futures::StreamExt;
 use parquet::file::properties::WriterProperties;
+use tokio_util::sync::CancellationToken;
 use tracing::log::*;
+use tracing::{field, info_span, Instrument};
 
+use super::cancellation::{cancellable, AbortOnDrop, UncommittedFiles};
 use super::datafusion_utils::Expression;
 use super::transaction::{CommitBuilder, CommitProperties, TableReference, PROTOCOL};
-use super::writer::{DeltaWriter, WriterConfig};
+use super::writer::{with_bloom_filters, DeltaWriter, WriterConfig};
 use super::CreateBuilder;
 use crate::delta_datafusion::expr::fmt_expr_to_sql;
 use crate::delta_datafusion::expr::parse_predicate_expression;
 use crate::delta_datafusion::{find_files, register_store, DeltaScanBuilder};
 use crate::delta_datafusion::{DataFusionMixins, DeltaDataChecker};
 use crate::errors::{DeltaResult, DeltaTableError};
-use crate::kernel::{Action, Add, Metadata, PartitionsExt, Remove, StructType};
-use crate::logstore::LogStoreRef;
-use crate::operations::cast::{cast_record_batch, merge_schema};
+use crate::kernel::{Action, Add, Metadata, PartitionsExt, Remove, StructType, Transaction};
+use crate::logstore::{operation_log_store, LogStoreRef};
+use crate::operations::cast::{cast_record_batch, cast_to_canonical_types, merge_schema};
 use crate::protocol::{DeltaOperation, SaveMode};
 use crate::storage::ObjectStoreRef;
+use crate::table::config::{BloomFilterConfig, TableConfig};
 use crate::table::state::DeltaTableState;
 use crate::table::Constraint as DeltaConstraint;
 use crate::writer::record_batch::divide_by_partition_values;
+use crate::writer::AuditColumns;
 use crate::DeltaTable;
 
 use tokio::sync::mpsc::Sender;
 
+pub use crate::protocol::SchemaMode;
+
 #[derive(thiserror::Error, Debug)]
 enum WriteError {
     #[error("No data source supplied to write command.")]
@@ -82,6 +96,9 @@ enum WriteError {
         expected: Vec<String>,
         got: Vec<String>,
     },
+
+    #[error("A replaceWhere predicate cannot be used with dynamic partition overwrite")]
+    DynamicOverwriteWithPredicate,
 }
 
 impl From<WriteError> for DeltaTableError {
@@ -92,24 +109,25 @@ impl From<WriteError> for DeltaTableError {
     }
 }
 
-///Specifies how to handle schema drifts
-#[derive(PartialEq, Clone, Copy)]
-pub enum SchemaMode {
-    /// Overwrite the schema with the new schema
-    Overwrite,
-    /// Append the new schema to the existing schema
-    Merge,
+/// Specifies which data is replaced by an overwrite
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum OverwriteMode {
+    /// Replace all data in the table
+    #[default]
+    Static,
+    /// Only replace the partitions present in the written data
+    Dynamic,
 }
 
-impl FromStr for SchemaMode {
+impl FromStr for OverwriteMode {
     type Err = DeltaTableError;
 
     fn from_str(s: &str) -> DeltaResult<Self> {
         match s.to_ascii_lowercase().as_str() {
-            "overwrite" => Ok(SchemaMode::Overwrite),
-            "merge" => Ok(SchemaMode::Merge),
+            "static" => Ok(OverwriteMode::Static),
+            "dynamic" => Ok(OverwriteMode::Dynamic),
             _ => Err(DeltaTableError::Generic(format!(
-                "Invalid schema write mode provided: {}, only these are supported: ['overwrite', 'merge']",
+                "Invalid overwrite mode provided: {}, only these are supported: ['static', 'dynamic']",
                 s
             ))),
         }
@@ -128,6 +146,8 @@ pub struct WriteBuilder {
     state: Option<SessionState>,
     /// SaveMode defines how to treat data already written to table location
     mode: SaveMode,
+    /// Which data is replaced when using `Overwrite` mode
+    overwrite_mode: OverwriteMode,
     /// Column names for table partitioning
     partition_columns: Option<Vec<String>>,
     /// When using `Overwrite` mode, replace data that matches a predicate
@@ -152,6 +172,16 @@ pub struct WriteBuilder {
     description: Option<String>,
     /// Configurations of the delta table, only used when table doesn't exist
     configuration: HashMap<String, Option<String>>,
+    /// Audit columns to append to the written data
+    audit_columns: Option<AuditColumns>,
+    /// Columns whose min and max values are recorded as add action tags
+    file_tag_columns: Vec<String>,
+    /// Columns to write parquet bloom filters for, in addition to the ones of the table config
+    bloom_filter_columns: HashMap<String, BloomFilterConfig>,
+    /// Whether to redistribute the data before writing, defaults to the table config
+    optimize_write: Option<bool>,
+    /// Token to cancel the write
+    cancellation_token: Option<CancellationToken>,
 }
 
 impl super::Operation<()> for WriteBuilder {}
@@ -165,6 +195,7 @@ impl WriteBuilder {
             input: None,
             state: None,
             mode: SaveMode::Append,
+            overwrite_mode: OverwriteMode::Static,
             partition_columns: None,
             predicate: None,
             target_file_size: None,
@@ -177,6 +208,11 @@ impl WriteBuilder {
             name: None,
             description: None,
             configuration: Default::default(),
+            audit_columns: None,
+            file_tag_columns: Vec::new(),
+            bloom_filter_columns: HashMap::new(),
+            optimize_write: None,
+            cancellation_token: None,
         }
     }
 
@@ -186,6 +222,16 @@ impl WriteBuilder {
         self
     }
 
+    /// Specify which data is replaced when using `Overwrite` mode.
+    ///
+    /// With [`OverwriteMode::Dynamic`] only the partitions present in the written data are
+    /// replaced, matching Spark's `partitionOverwriteMode=dynamic`. Unpartitioned tables are
+    /// replaced as a whole.
+    pub fn with_overwrite_mode(mut self, overwrite_mode: OverwriteMode) -> Self {
+        self.overwrite_mode = overwrite_mode;
+        self
+    }
+
     /// Add Schema Write Mode
     pub fn with_schema_mode(mut self, schema_mode: SchemaMode) -> Self {
         self.schema_mode = Some(schema_mode);
@@ -227,6 +273,9 @@ impl WriteBuilder {
     }
 
     /// Specify the target file size for data files written to the delta table.
+    ///
+    /// Data is buffered per partition until a file of roughly this size can be written. Defaults
+    /// to the `delta.targetFileSize` of the table.
     pub fn with_target_file_size(mut self, target_file_size: usize) -> Self {
         self.target_file_size = Some(target_file_size);
         self
@@ -257,6 +306,21 @@ impl WriteBuilder {
         self
     }
 
+    /// Record an application transaction (`txn` action) with the given version in the commit.
+    ///
+    /// Streaming writers can look up the last committed version for their `app_id` via
+    /// [`crate::DeltaTable::get_app_transaction_version_for`] to skip already written batches.
+    pub fn with_application_transaction(mut self, app_id: impl ToString, version: i64) -> Self {
+        self.commit_properties =
+            self.commit_properties
+                .with_application_transaction(Transaction::new_with_last_update(
+                    app_id,
+                    version,
+                    Some(Utc::now().timestamp_millis()),
+                ));
+        self
+    }
+
     /// Specify the table name. Optionally qualified with
     /// a database name [database_name.] table_name.
     pub fn with_table_name(mut self, name: impl Into<String>) -> Self {
@@ -282,10 +346,85 @@ impl WriteBuilder {
         self
     }
 
+    /// Append audit columns recording when and from where the data was ingested.
+    ///
+    /// The columns are added to the schema of newly created tables. Existing tables must
+    /// already declare them, e.g. via [`CreateBuilder::with_audit_columns`].
+    pub fn with_audit_columns(mut self, audit_columns: AuditColumns) -> Self {
+        self.audit_columns = Some(audit_columns);
+        self
+    }
+
+    /// Record the min and max values of the given columns as tags of the written files.
+    ///
+    /// File statistics only cover the first `delta.dataSkippingNumIndexedCols` columns. Tagged
+    /// columns are used to prune files in scans even if they are not covered by the statistics,
+    /// e.g. to select the files of a tenant without collecting stats for all preceding columns.
+    pub fn with_file_tag_columns(
+        mut self,
+        columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.file_tag_columns = columns.into_iter().map(|c| c.into()).collect();
+        self
+    }
+
+    /// Write parquet bloom filters with the default settings for the given columns.
+    ///
+    /// Bloom filters allow point lookups on high cardinality columns to skip row groups. They are
+    /// also written for the columns enabled via `delta.bloomFilter.<column>.enabled` in the table
+    /// configuration. Nested columns are addressed by their dot separated path.
+    pub fn with_bloom_filter_columns(
+        mut self,
+        columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.bloom_filter_columns.extend(
+            columns
+                .into_iter()
+                .map(|c| (c.into(), BloomFilterConfig::default())),
+        );
+        self
+    }
+
+    /// Write a parquet bloom filter for `column` with the given settings.
+    ///
+    /// Takes precedence over the settings of the column in the table configuration.
+    pub fn with_bloom_filter_column(
+        mut self,
+        column: impl Into<String>,
+        config: BloomFilterConfig,
+    ) -> Self {
+        self.bloom_filter_columns.insert(column.into(), config);
+        self
+    }
+
+    /// Redistribute the data before writing so fewer, larger files are written.
+    ///
+    /// Rows of a table partition are always written by a single writer. With optimized writes, the
+    /// data of unpartitioned tables is written by a single writer as well, trading write
+    /// parallelism for file size. Defaults to `delta.autoOptimize.optimizeWrite` of the table.
+    pub fn with_optimize_write(mut self, optimize_write: bool) -> Self {
+        self.optimize_write = Some(optimize_write);
+        self
+    }
+
+    /// Abort the write once `token` is cancelled.
+    ///
+    /// The write then returns [`DeltaTableError::Cancelled`] without committing, and deletes
+    /// the data files it has written so far.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
     async fn check_preconditions(&self) -> DeltaResult<Vec<Action>> {
         match &self.snapshot {
             Some(snapshot) => {
                 PROTOCOL.can_write_to(snapshot)?;
+                if self.mode == SaveMode::Overwrite || self.predicate.is_some() {
+                    snapshot.ensure_all_files("OVERWRITE")?;
+                } else if !snapshot.snapshot().partition_filters().is_empty() {
+                    return Err(DeltaTableError::LoadedWithPartitionFilters("WRITE".into()));
+                }
 
                 if let Some(plan) = &self.input {
                     let schema: StructType = (plan.schema()).try_into()?;
@@ -345,6 +484,8 @@ pub struct WriterStatsConfig {
     num_indexed_cols: i32,
     /// Optional list of columns which to collect stats for, takes precedende over num_index_cols
     stats_columns: Option<Vec<String>>,
+    /// Columns whose min and max values are recorded as add action tags
+    file_tag_columns: Vec<String>,
 }
 
 impl WriterStatsConfig {
@@ -353,8 +494,45 @@ impl WriterStatsConfig {
         Self {
             num_indexed_cols,
             stats_columns,
+            file_tag_columns: Vec::new(),
         }
     }
+
+    /// Record the min and max values of the given columns as tags of the written files
+    pub fn with_file_tag_columns(mut self, file_tag_columns: Vec<String>) -> Self {
+        self.file_tag_columns = file_tag_columns;
+        self
+    }
+}
+
+/// Prepare the input of a write so small inputs end up in few, large files.
+///
+/// Small batches are coalesced up to `batch_size` rows, and the rows of a table partition are
+/// routed to a single writer instead of being spread over the writers of all input partitions.
+/// With `optimize_write`, the input of unpartitioned tables is funneled into a single writer.
+fn coalesce_input(
+    plan: Arc<dyn ExecutionPlan>,
+    partition_columns: &[String],
+    batch_size: usize,
+    optimize_write: bool,
+) -> DeltaResult<Arc<dyn ExecutionPlan>> {
+    let partition_count = plan.properties().output_partitioning().partition_count();
+    let plan = if partition_count > 1 && !partition_columns.is_empty() {
+        let schema = plan.schema();
+        let exprs = partition_columns
+            .iter()
+            .map(|name| expressions::col(name, &schema))
+            .collect::<Result<Vec<_>, _>>()?;
+        Arc::new(RepartitionExec::try_new(
+            plan,
+            Partitioning::Hash(exprs, partition_count),
+        )?) as Arc<dyn ExecutionPlan>
+    } else if partition_count > 1 && optimize_write {
+        Arc::new(CoalescePartitionsExec::new(plan))
+    } else {
+        plan
+    };
+    Ok(Arc::new(CoalesceBatchesExec::new(plan, batch_size)))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -372,6 +550,7 @@ async fn write_execution_plan_with_predicate(
     schema_mode: Option<SchemaMode>,
     writer_stats_config: WriterStatsConfig,
     sender: Option<Sender<RecordBatch>>,
+    optimize_write: bool,
 ) -> DeltaResult<Vec<Action>> {
     let schema: ArrowSchemaRef = if schema_mode.is_some() {
         plan.schema()
@@ -393,6 +572,12 @@ async fn write_execution_plan_with_predicate(
         }
         _ => checker,
     };
+    let plan = coalesce_input(
+        plan,
+        &partition_columns,
+        state.config().batch_size(),
+        optimize_write,
+    )?;
 
     // Write data to disk
     let mut tasks = vec![];
@@ -408,7 +593,8 @@ async fn write_execution_plan_with_predicate(
             write_batch_size,
             writer_stats_config.num_indexed_cols,
             writer_stats_config.stats_columns.clone(),
-        );
+        )
+        .with_file_tag_columns(writer_stats_config.file_tag_columns.clone());
         let mut writer = DeltaWriter::new(object_store.clone(), config);
         let checker_stream = checker.clone();
         let sender_stream = sender.clone();
@@ -445,7 +631,7 @@ async fn write_execution_plan_with_predicate(
             },
         );
 
-        tasks.push(handle);
+        tasks.push(AbortOnDrop::new(handle));
     }
     let actions = futures::future::join_all(tasks)
         .await
@@ -490,6 +676,9 @@ pub(crate) async fn write_execution_plan(
         schema_mode,
         writer_stats_config,
         sender,
+        snapshot
+            .map(|snapshot| snapshot.table_config().optimize_write())
+            .unwrap_or(false),
     )
     .await
 }
@@ -601,9 +790,39 @@ impl std::future::IntoFuture for WriteBuilder {
     type IntoFuture = BoxFuture<'static, Self::Output>;
 
     fn into_future(self) -> Self::IntoFuture {
-        let this = self;
+        let mut this = self;
+        let cancellation_token = this.cancellation_token.take();
+        let span = info_span!(
+            "delta.write",
+            table_uri = %this.log_store.root_uri(),
+            version = this.snapshot.as_ref().map(|snapshot| snapshot.version()),
+            mode = ?this.mode,
+            partition_columns = ?this.partition_columns,
+            predicate = this.predicate.as_ref().map(field::display),
+        );
 
-        Box::pin(async move {
+        let future = cancellable(cancellation_token, async move {
+            // files are written and committed through the operation's log store, while the
+            // returned table keeps the log store it was loaded with
+            let table_log_store = this.log_store.clone();
+            this.log_store = operation_log_store(&table_log_store);
+
+            if let Some(audit_columns) = this.audit_columns.take() {
+                // All rows written in this operation share the same ingestion time
+                let ingested_at = audit_columns.ingested_at.unwrap_or_else(Utc::now);
+                let audit_columns = audit_columns.with_ingested_at(ingested_at);
+                if let Some(batches) = this.batches.take() {
+                    this.batches = Some(
+                        batches
+                            .iter()
+                            .map(|batch| audit_columns.append_to(batch))
+                            .collect::<Result<_, _>>()?,
+                    );
+                }
+                if let Some(plan) = this.input.take() {
+                    this.input = Some(project_audit_columns(plan, &audit_columns)?);
+                }
+            }
             if this.mode == SaveMode::Overwrite {
                 if let Some(snapshot) = &this.snapshot {
                     PROTOCOL.check_append_only(&snapshot.snapshot)?;
@@ -614,6 +833,9 @@ impl std::future::IntoFuture for WriteBuilder {
                     "Schema overwrite not supported for Append".to_string(),
                 ));
             }
+            if this.overwrite_mode == OverwriteMode::Dynamic && this.predicate.is_some() {
+                return Err(WriteError::DynamicOverwriteWithPredicate.into());
+            }
 
             // Create table actions to initialize table in case it does not yet exist and should be created
             let mut actions = this.check_preconditions().await?;
@@ -652,6 +874,11 @@ impl std::future::IntoFuture for WriteBuilder {
                 if batches.is_empty() {
                     Err(WriteError::MissingData)
                 } else {
+                    // large, view and unsigned types are cast to the types written for their delta type
+                    let batches = batches
+                        .iter()
+                        .map(cast_to_canonical_types)
+                        .collect::<DeltaResult<Vec<_>>>()?;
                     let schema = batches[0].schema();
 
                     let mut new_schema = None;
@@ -780,13 +1007,24 @@ impl std::future::IntoFuture for WriteBuilder {
                 .as_ref()
                 .map(|snapshot| snapshot.table_config());
 
+            // new tables are written with the configuration they are created with
+            let new_table_config = TableConfig(&this.configuration);
+            let table_config = config.as_ref().unwrap_or(&new_table_config);
+
+            let mut bloom_filter_columns = table_config.bloom_filter_columns();
+            bloom_filter_columns.extend(this.bloom_filter_columns);
+            this.writer_properties =
+                with_bloom_filters(this.writer_properties, &bloom_filter_columns);
+            let target_file_size = this
+                .target_file_size
+                .unwrap_or(table_config.target_file_size() as usize);
+            let optimize_write = this.optimize_write.unwrap_or(table_config.optimize_write());
+
             let (num_indexed_cols, stats_columns) =
                 super::get_num_idx_cols_and_stats_columns(config, this.configuration);
 
-            let writer_stats_config = WriterStatsConfig {
-                num_indexed_cols,
-                stats_columns,
-            };
+            let writer_stats_config = WriterStatsConfig::new(num_indexed_cols, stats_columns)
+                .with_file_tag_columns(this.file_tag_columns);
             // Here we need to validate if the new data conforms to a predicate if one is provided
             let add_actions = write_execution_plan_with_predicate(
                 predicate.clone(),
@@ -795,15 +1033,18 @@ impl std::future::IntoFuture for WriteBuilder {
                 plan,
                 partition_columns.clone(),
                 this.log_store.object_store().clone(),
-                this.target_file_size,
+                Some(target_file_size),
                 this.write_batch_size,
                 this.writer_properties.clone(),
                 this.safe_cast,
                 this.schema_mode,
                 writer_stats_config.clone(),
                 None,
+                optimize_write,
             )
             .await?;
+            let mut uncommitted = UncommittedFiles::new(this.log_store.object_store());
+            uncommitted.track(&add_actions);
             actions.extend(add_actions);
 
             // Collect remove actions if we are overwriting the table
@@ -841,15 +1082,48 @@ impl std::future::IntoFuture for WriteBuilder {
                                 writer_stats_config,
                             )
                             .await?;
+                            uncommitted.track(&predicate_actions);
                             if !predicate_actions.is_empty() {
                                 actions.extend(predicate_actions);
                             }
                         }
                         _ => {
+                            // Partitions written by this operation, only these are replaced in dynamic mode
+                            let written_partitions = (this.overwrite_mode
+                                == OverwriteMode::Dynamic
+                                && !partition_columns.is_empty())
+                            .then(|| {
+                                actions
+                                    .iter()
+                                    .filter_map(|action| match action {
+                                        Action::Add(add) => Some(
+                                            add.partition_values
+                                                .clone()
+                                                .into_iter()
+                                                .collect::<BTreeMap<_, _>>(),
+                                        ),
+                                        _ => None,
+                                    })
+                                    .collect::<HashSet<_>>()
+                            });
                             let remove_actions = snapshot
                                 .log_data()
                                 .into_iter()
-                                .map(|p| p.remove_action(true).into());
+                                .map(|p| p.remove_action(true))
+                                .filter(|remove| match &written_partitions {
+                                    Some(written_partitions) => {
+                                        let partition_values = remove
+                                            .partition_values
+                                            .clone()
+                                            .unwrap_or_default()
+                                            .into_iter()
+                                            .collect::<BTreeMap<_, _>>();
+                                        written_partitions.contains(&partition_values)
+                                    }
+                                    None => true,
+                                })
+                                .map(Action::Remove)
+                                .collect::<Vec<_>>();
                             actions.extend(remove_actions);
                         }
                     };
@@ -866,6 +1140,7 @@ impl std::future::IntoFuture for WriteBuilder {
                 predicate: predicate_str,
             };
 
+            uncommitted.committed();
             let commit = CommitBuilder::from(this.commit_properties)
                 .with_actions(actions)
                 .build(
@@ -875,11 +1150,52 @@ impl std::future::IntoFuture for WriteBuilder {
                 )
                 .await?;
 
-            Ok(DeltaTable::new_with_state(this.log_store, commit.snapshot))
-        })
+            Ok(DeltaTable::new_with_state(table_log_store, commit.snapshot))
+        });
+
+        Box::pin(future.instrument(span))
     }
 }
 
+/// Project `plan` to replace or append the audit columns with constant values
+fn project_audit_columns(
+    plan: Arc<dyn ExecutionPlan>,
+    audit_columns: &AuditColumns,
+) -> DeltaResult<Arc<dyn ExecutionPlan>> {
+    let ingested_at = audit_columns
+        .ingested_at
+        .unwrap_or_else(Utc::now)
+        .timestamp_micros();
+    let literals = [
+        ScalarValue::TimestampMicrosecond(Some(ingested_at), Some("UTC".into())),
+        ScalarValue::Utf8(audit_columns.source_file().map(|s| s.to_string())),
+        ScalarValue::Utf8(Some(audit_columns.batch_id().to_string())),
+    ];
+
+    let expressions: Vec<(Arc<dyn PhysicalExpr>, String)> = plan
+        .schema()
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| !AuditColumns::is_audit_column(field.name()))
+        .map(|(idx, field)| -> (Arc<dyn PhysicalExpr>, String) {
+            (
+                Arc::new(expressions::Column::new(field.name(), idx)),
+                field.name().to_owned(),
+            )
+        })
+        .chain(AuditColumns::fields().iter().zip(literals).map(
+            |(field, value)| -> (Arc<dyn PhysicalExpr>, String) {
+                (
+                    Arc::new(expressions::Literal::new(value)),
+                    field.name().to_owned(),
+                )
+            },
+        ))
+        .collect();
+    Ok(Arc::new(ProjectionExec::try_new(expressions, plan)?))
+}
+
 fn try_cast_batch(from_fields: &Fields, to_fields: &Fields) -> Result<(), ArrowError> {
     if from_fields.len() != to_fields.len() {
         return Err(ArrowError::SchemaError(format!(
@@ -1058,6 +1374,107 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_write_cancelled() {
+        let table_schema = get_delta_schema();
+        let batch = get_record_batch(None, false);
+
+        let table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(table_schema.fields().cloned())
+            .await
+            .unwrap();
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let err = DeltaOps(table.clone())
+            .write(vec![batch])
+            .with_cancellation_token(token)
+            .await
+            .expect_err("write should be cancelled");
+        assert!(matches!(err, DeltaTableError::Cancelled));
+
+        let mut table = table;
+        table.update().await.unwrap();
+        assert_eq!(table.version(), 0);
+        assert_eq!(table.get_files_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_write_bloom_filters() {
+        let batch = get_record_batch(None, false);
+        let table = DeltaOps::new_in_memory()
+            .write(vec![batch])
+            .with_configuration(vec![
+                ("delta.bloomFilter.id.enabled", Some("true")),
+                ("delta.bloomFilter.id.fpp", Some("0.01")),
+            ])
+            .with_bloom_filter_columns(["value"])
+            .await
+            .unwrap();
+
+        let path = table.get_files_iter().unwrap().next().unwrap();
+        let data = table
+            .object_store()
+            .get(&path)
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        let metadata = parquet::file::footer::parse_metadata(&data).unwrap();
+        for column in metadata.row_group(0).columns() {
+            let has_bloom_filter = column.bloom_filter_offset().is_some();
+            match column.column_path().string().as_str() {
+                "id" | "value" => assert!(has_bloom_filter),
+                _ => assert!(!has_bloom_filter),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_audit_columns() {
+        let batch = get_record_batch(None, false);
+        let audit_columns = AuditColumns::new().with_batch_id("batch-1");
+
+        // the columns are declared when creating the table
+        let table = DeltaOps::new_in_memory()
+            .write(vec![batch.clone()])
+            .with_audit_columns(audit_columns.clone())
+            .await
+            .unwrap();
+        let schema = table.get_schema().unwrap();
+        for field in AuditColumns::fields() {
+            assert_eq!(schema.field(field.name()), Some(&field));
+        }
+
+        let table = DeltaOps(table)
+            .write(vec![batch.clone()])
+            .with_audit_columns(audit_columns.with_batch_id("batch-2"))
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 1);
+
+        let ctx = SessionContext::new();
+        ctx.register_table("test", Arc::new(table)).unwrap();
+        let batches = ctx
+            .sql("SELECT _batch_id, count(*) AS n FROM test WHERE _source_file IS NULL GROUP BY _batch_id ORDER BY _batch_id")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let expected = vec![
+            "+-----------+----+",
+            "| _batch_id | n  |",
+            "+-----------+----+",
+            "| batch-1   | 11 |",
+            "| batch-2   | 11 |",
+            "+-----------+----+",
+        ];
+        assert_batches_eq!(&expected, &batches);
+    }
+
     #[tokio::test]
     async fn test_write_different_types() {
         // Ensure write data is casted when data of a different type from the table is provided.
@@ -1158,6 +1575,44 @@ mod tests {
         assert_batches_sorted_eq!(&expected, &actual);
     }
 
+    #[tokio::test]
+    async fn test_write_large_and_view_types() {
+        let batch = get_record_batch(None, false);
+        let table = DeltaOps::new_in_memory()
+            .write(vec![batch.clone()])
+            .with_partition_columns(["modified"])
+            .with_save_mode(SaveMode::ErrorIfExists)
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 0);
+
+        // the same data, with the string columns as large and view types
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::LargeUtf8, true),
+            Field::new("value", DataType::Int32, true),
+            Field::new("modified", DataType::Utf8View, true),
+        ]));
+        let columns = schema
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .map(|(field, column)| arrow_cast::cast(column, field.data_type()).unwrap())
+            .collect();
+        let batch = RecordBatch::try_new(schema, columns).unwrap();
+
+        let table = DeltaOps(table).write(vec![batch]).await.unwrap();
+        assert_eq!(table.version(), 1);
+        let schema = table.snapshot().unwrap().arrow_schema().unwrap();
+        assert_eq!(
+            schema.field_with_name("id").unwrap().data_type(),
+            &DataType::Utf8
+        );
+        assert_eq!(table.get_files_count(), 4);
+        let data = get_data(&table).await;
+        let rows: usize = data.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(rows, 22);
+    }
+
     #[tokio::test]
     async fn test_write_nonexistent() {
         let batch = get_record_batch(None, false);
@@ -1192,6 +1647,145 @@ mod tests {
         assert_eq!(table.get_files_count(), 4)
     }
 
+    #[tokio::test]
+    async fn test_write_coalesces_input_partitions() {
+        let batch = get_record_batch(None, false);
+        let schema = batch.schema();
+        // many small batches spread over several input partitions
+        let partitions = (0..4)
+            .map(|_| {
+                (0..batch.num_rows())
+                    .map(|i| batch.slice(i, 1))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let plan = Arc::new(MemoryExec::try_new(&partitions, schema, None).unwrap());
+
+        let table = DeltaOps::new_in_memory()
+            .write(vec![])
+            .with_input_execution_plan(plan)
+            .with_partition_columns(["modified"])
+            .await
+            .unwrap();
+        // one file per table partition
+        assert_eq!(table.get_files_count(), 2);
+
+        let table = DeltaOps::new_in_memory()
+            .write(vec![batch])
+            .with_configuration(vec![("delta.targetFileSize", Some("1"))])
+            .with_write_batch_size(1)
+            .await
+            .unwrap();
+        // the target file size of the table config is respected
+        assert_eq!(table.get_files_count(), 11);
+    }
+
+    #[tokio::test]
+    async fn test_write_optimize_write() {
+        let batch = get_record_batch(None, false);
+        let schema = batch.schema();
+        let partitions = vec![vec![batch.clone()]; 4];
+
+        let plan = Arc::new(MemoryExec::try_new(&partitions, schema.clone(), None).unwrap());
+        let table = DeltaOps::new_in_memory()
+            .write(vec![])
+            .with_input_execution_plan(plan)
+            .await
+            .unwrap();
+        assert_eq!(table.get_files_count(), 4);
+
+        let plan = Arc::new(MemoryExec::try_new(&partitions, schema, None).unwrap());
+        let table = DeltaOps::new_in_memory()
+            .write(vec![])
+            .with_input_execution_plan(plan)
+            .with_configuration(vec![("delta.autoOptimize.optimizeWrite", Some("true"))])
+            .await
+            .unwrap();
+        assert_eq!(table.get_files_count(), 1);
+
+        let table = DeltaOps(table)
+            .write(vec![batch])
+            .with_optimize_write(false)
+            .await
+            .unwrap();
+        assert_eq!(table.get_files_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_write_auto_compact() {
+        let batch = get_record_batch(None, false);
+        let commit_properties = CommitProperties::default().with_auto_compact_min_num_files(3);
+        let mut table = DeltaOps::new_in_memory()
+            .write(vec![batch.clone()])
+            .with_partition_columns(["modified"])
+            .with_configuration(vec![("delta.autoOptimize.autoCompact", Some("true"))])
+            .with_commit_properties(commit_properties.clone())
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            table = DeltaOps(table)
+                .write(vec![batch.clone()])
+                .with_commit_properties(commit_properties.clone())
+                .await
+                .unwrap();
+        }
+        // the third write left three small files in both partitions, which are compacted
+        // in a single optimize commit
+        assert_eq!(table.version(), 3);
+        assert_eq!(table.get_files_count(), 2);
+        let history = table.history(None).await.unwrap();
+        assert_eq!(history[0].operation.as_deref(), Some("OPTIMIZE"));
+
+        let actual = get_data(&table).await;
+        let rows: usize = actual.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(rows, 3 * batch.num_rows());
+    }
+
+    #[tokio::test]
+    async fn test_write_auto_compact_timestamp_partitions() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                true,
+            ),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["A", "B"])),
+                Arc::new(
+                    TimestampMicrosecondArray::from(vec![
+                        Some(1_612_137_600_123_456),
+                        Some(1_612_137_600_123_456),
+                    ])
+                    .with_timezone("UTC"),
+                ),
+            ],
+        )
+        .unwrap();
+        let commit_properties = CommitProperties::default().with_auto_compact_min_num_files(3);
+        let mut table = DeltaOps::new_in_memory()
+            .write(vec![batch.clone()])
+            .with_partition_columns(["ts"])
+            .with_configuration(vec![("delta.autoOptimize.autoCompact", Some("true"))])
+            .with_commit_properties(commit_properties.clone())
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            table = DeltaOps(table)
+                .write(vec![batch.clone()])
+                .with_commit_properties(commit_properties.clone())
+                .await
+                .unwrap();
+        }
+        assert_eq!(table.version(), 3);
+        assert_eq!(table.get_files_count(), 1);
+        let history = table.history(None).await.unwrap();
+        assert_eq!(history[0].operation.as_deref(), Some("OPTIMIZE"));
+    }
+
     #[tokio::test]
     async fn test_merge_schema() {
         let batch = get_record_batch(None, false);
@@ -1688,4 +2282,126 @@ mod tests {
         let actual = get_data_sorted(&table, "id,value,modified").await;
         assert_batches_sorted_eq!(&expected, &actual);
     }
+
+    #[tokio::test]
+    async fn test_replace_where_non_partition_column() {
+        let schema = get_arrow_schema(&None);
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec![
+                    "A", "B", "C", "D", "F",
+                ])),
+                Arc::new(arrow::array::Int32Array::from(vec![1, 10, 2, 20, 3])),
+                Arc::new(arrow::array::StringArray::from(vec![
+                    "2021-02-02",
+                    "2021-02-02",
+                    "2021-02-03",
+                    "2021-02-03",
+                    "2021-02-04",
+                ])),
+            ],
+        )
+        .unwrap();
+
+        let table = DeltaOps::new_in_memory()
+            .write(vec![batch])
+            .with_partition_columns(["modified"])
+            .await
+            .unwrap();
+        assert_eq!(table.get_files_count(), 3);
+        let files_before = table.get_files_iter().unwrap().collect::<Vec<_>>();
+
+        let batch_add = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["E"])),
+                Arc::new(arrow::array::Int32Array::from(vec![30])),
+                Arc::new(arrow::array::StringArray::from(vec!["2021-02-02"])),
+            ],
+        )
+        .unwrap();
+
+        let table = DeltaOps(table)
+            .write(vec![batch_add])
+            .with_save_mode(SaveMode::Overwrite)
+            .with_replace_where(col("value").gt(lit(5)))
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 1);
+
+        // files without matching rows are kept as is
+        let kept = table
+            .get_files_iter()
+            .unwrap()
+            .filter(|file| files_before.contains(file))
+            .collect::<Vec<_>>();
+        assert_eq!(kept.len(), 1);
+        assert!(kept[0].as_ref().starts_with("modified=2021-02-04"));
+
+        let expected = [
+            "+----+-------+------------+",
+            "| id | value | modified   |",
+            "+----+-------+------------+",
+            "| A  | 1     | 2021-02-02 |",
+            "| C  | 2     | 2021-02-03 |",
+            "| E  | 30    | 2021-02-02 |",
+            "| F  | 3     | 2021-02-04 |",
+            "+----+-------+------------+",
+        ];
+        let actual = get_data_sorted(&table, "id,value,modified").await;
+        assert_batches_sorted_eq!(&expected, &actual);
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_partition_overwrite() {
+        let schema = get_arrow_schema(&None);
+        let batch = get_record_batch(None, false);
+        let table = DeltaOps::new_in_memory()
+            .write(vec![batch])
+            .with_partition_columns(["modified"])
+            .await
+            .unwrap();
+        assert_eq!(table.get_files_count(), 2);
+        let files_before = table.get_files_iter().unwrap().collect::<Vec<_>>();
+
+        let batch_overwrite = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(arrow::array::StringArray::from(vec!["C", "C"])),
+                Arc::new(arrow::array::Int32Array::from(vec![20, 21])),
+                Arc::new(arrow::array::StringArray::from(vec![
+                    "2021-02-02",
+                    "2024-01-01",
+                ])),
+            ],
+        )
+        .unwrap();
+
+        let err = DeltaOps(table.clone())
+            .write(vec![batch_overwrite.clone()])
+            .with_save_mode(SaveMode::Overwrite)
+            .with_overwrite_mode(OverwriteMode::Dynamic)
+            .with_replace_where(col("id").eq(lit("A")))
+            .await
+            .expect_err("predicate is not allowed with dynamic overwrite");
+        assert!(err.to_string().contains("dynamic partition overwrite"));
+
+        let table = DeltaOps(table)
+            .write(vec![batch_overwrite])
+            .with_save_mode(SaveMode::Overwrite)
+            .with_overwrite_mode(OverwriteMode::Dynamic)
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 1);
+        assert_eq!(table.get_files_count(), 3);
+        // only the file of the partition not present in the written data is kept
+        let kept = table
+            .get_files_iter()
+            .unwrap()
+            .filter(|file| files_before.contains(file))
+            .collect::<Vec<_>>();
+        assert_eq!(kept.len(), 1);
+        assert!(kept[0].as_ref().starts_with("modified=2021-02-01"));
+    }
 }
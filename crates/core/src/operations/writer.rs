@@ -12,14 +12,17 @@ use object_store::{path::Path, ObjectStore};
 use parquet::arrow::ArrowWriter;
 use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
+use parquet::schema::types::ColumnPath;
 use tracing::debug;
 
 use crate::crate_version;
 use crate::errors::{DeltaResult, DeltaTableError};
 use crate::kernel::{Add, PartitionsExt};
+use crate::metrics::{self, Counter};
 use crate::storage::ObjectStoreRef;
+use crate::table::config::BloomFilterConfig;
 use crate::writer::record_batch::{divide_by_partition_values, PartitionResult};
-use crate::writer::stats::create_add;
+use crate::writer::stats::{create_add, min_max_tags};
 use crate::writer::utils::{
     arrow_schema_without_partitions, next_data_path, record_batch_without_partitions,
     ShareableBuffer,
@@ -84,6 +87,8 @@ pub struct WriterConfig {
     num_indexed_cols: i32,
     /// Stats columns, specific columns to collect stats from, takes precedence over num_indexed_cols
     stats_columns: Option<Vec<String>>,
+    /// Columns whose min and max values are recorded as add action tags
+    file_tag_columns: Vec<String>,
 }
 
 impl WriterConfig {
@@ -113,15 +118,52 @@ impl WriterConfig {
             write_batch_size,
             num_indexed_cols,
             stats_columns,
+            file_tag_columns: Vec::new(),
         }
     }
 
+    /// Record the min and max values of the given columns as tags of the written files
+    pub fn with_file_tag_columns(
+        mut self,
+        columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.file_tag_columns = columns.into_iter().map(|c| c.into()).collect();
+        self
+    }
+
     /// Schema of files written to disk
     pub fn file_schema(&self) -> ArrowSchemaRef {
         arrow_schema_without_partitions(&self.table_schema, &self.partition_columns)
     }
 }
 
+/// Enable parquet bloom filters for `columns` in the given writer properties.
+///
+/// Nested columns are addressed by their dot separated path. Without explicit properties the
+/// default properties of the [`WriterConfig`] are extended.
+pub(crate) fn with_bloom_filters(
+    writer_properties: Option<WriterProperties>,
+    columns: &HashMap<String, BloomFilterConfig>,
+) -> Option<WriterProperties> {
+    if columns.is_empty() {
+        return writer_properties;
+    }
+    let mut builder = writer_properties
+        .map(|props| props.into_builder())
+        .unwrap_or_else(|| WriterProperties::builder().set_compression(Compression::SNAPPY));
+    for (column, config) in columns {
+        let path = ColumnPath::new(column.split('.').map(|c| c.to_string()).collect());
+        builder = builder.set_column_bloom_filter_enabled(path.clone(), true);
+        if let Some(fpp) = config.fpp {
+            builder = builder.set_column_bloom_filter_fpp(path.clone(), fpp);
+        }
+        if let Some(num_items) = config.num_items {
+            builder = builder.set_column_bloom_filter_ndv(path, num_items);
+        }
+    }
+    Some(builder.build())
+}
+
 #[derive(Debug)]
 /// A parquet writer implementation tailored to the needs of writing data to a delta table.
 pub struct DeltaWriter {
@@ -191,7 +233,8 @@ impl DeltaWriter {
                     config,
                     self.config.num_indexed_cols,
                     self.config.stats_columns.clone(),
-                )?;
+                )?
+                .with_file_tag_columns(self.config.file_tag_columns.clone());
                 writer.write(&record_batch).await?;
                 let _ = self.partition_writers.insert(partition_key, writer);
             }
@@ -292,6 +335,8 @@ pub struct PartitionWriter {
     num_indexed_cols: i32,
     /// Stats columns, specific columns to collect stats from, takes precedence over num_indexed_cols
     stats_columns: Option<Vec<String>>,
+    /// Columns whose min and max values are recorded as add action tags
+    file_tag_columns: Vec<String>,
 }
 
 impl PartitionWriter {
@@ -319,9 +364,16 @@ impl PartitionWriter {
             files_written: Vec::new(),
             num_indexed_cols,
             stats_columns,
+            file_tag_columns: Vec::new(),
         })
     }
 
+    /// Record the min and max values of the given columns as tags of the written files
+    pub fn with_file_tag_columns(mut self, columns: Vec<String>) -> Self {
+        self.file_tag_columns = columns;
+        self
+    }
+
     fn next_data_path(&mut self) -> Path {
         self.part_counter += 1;
 
@@ -370,19 +422,31 @@ impl PartitionWriter {
 
         // write file to object store
         self.object_store.put(&path, buffer.into()).await?;
-        self.files_written.push(
-            create_add(
-                &self.config.partition_values,
-                path.to_string(),
-                file_size,
-                &metadata,
-                self.num_indexed_cols,
-                &self.stats_columns,
-            )
-            .map_err(|err| WriteError::CreateAdd {
-                source: Box::new(err),
-            })?,
-        );
+        metrics::increment(Counter::FilesWritten, 1, &[]);
+        metrics::increment(Counter::BytesWritten, file_size as u64, &[]);
+        let mut add = create_add(
+            &self.config.partition_values,
+            path.to_string(),
+            file_size,
+            &metadata,
+            self.num_indexed_cols,
+            &self.stats_columns,
+        )
+        .map_err(|err| WriteError::CreateAdd {
+            source: Box::new(err),
+        })?;
+        let tags = min_max_tags(
+            &self.config.partition_values,
+            &metadata,
+            &self.file_tag_columns,
+        )
+        .map_err(|err| WriteError::CreateAdd {
+            source: Box::new(err),
+        })?;
+        if !tags.is_empty() {
+            add.tags = Some(tags);
+        }
+        self.files_written.push(add);
 
         Ok(())
     }
@@ -499,6 +563,40 @@ mod tests {
         assert_eq!(head.size, adds[0].size as usize)
     }
 
+    #[tokio::test]
+    async fn test_write_file_tags() {
+        let log_store = DeltaTableBuilder::from_uri("memory://")
+            .build_storage()
+            .unwrap();
+        let object_store = log_store.object_store();
+        let batch = get_record_batch(None, false);
+
+        // only collect stats for the first column, but tag files with the range of "value"
+        let config = WriterConfig::new(batch.schema(), vec![], None, None, None, 1, None)
+            .with_file_tag_columns(["value"]);
+        let mut writer = DeltaWriter::new(object_store, config);
+        writer.write(&batch).await.unwrap();
+        let adds = writer.close().await.unwrap();
+        assert_eq!(adds.len(), 1);
+
+        let stats = adds[0].get_stats().unwrap().unwrap();
+        assert!(!stats.min_values.contains_key("value"));
+        let tags = adds[0].tags.clone().unwrap();
+        assert_eq!(
+            tags,
+            HashMap::from([
+                (
+                    format!("{}value", crate::writer::MIN_VALUE_TAG_PREFIX),
+                    Some("1".to_string())
+                ),
+                (
+                    format!("{}value", crate::writer::MAX_VALUE_TAG_PREFIX),
+                    Some("11".to_string())
+                ),
+            ])
+        );
+    }
+
     #[tokio::test]
     async fn test_write_partition_with_parts() {
         let base_int = Arc::new(Int32Array::from((0..10000).collect::<Vec<i32>>()));
@@ -23,8 +23,10 @@ use super::{time_utils, ProtocolError};
 use crate::kernel::arrow::delta_log_schema_for_table;
 use crate::kernel::{
     Action, Add as AddAction, DataType, PrimitiveType, Protocol, Remove, StructField,
+    WriterFeatures,
 };
 use crate::logstore::LogStore;
+use crate::operations::progress::{report, ProgressReporterRef, ProgressStage};
 use crate::table::state::DeltaTableState;
 use crate::table::{get_partition_col_data_types, CheckPoint, CheckPointBuilder};
 use crate::{open_table_with_version, DeltaTable};
@@ -60,6 +62,12 @@ enum CheckpointError {
 
     #[error("missing rewquired action type in snapshot: {0}")]
     MissingActionType(String),
+
+    /// Caller attempt to create a checkpoint from a table state not holding all of its files
+    #[error(
+        "Attempted to create a checkpoint from a table state that was loaded without all files"
+    )]
+    FilesNotLoaded,
 }
 
 impl From<CheckpointError> for ProtocolError {
@@ -69,7 +77,9 @@ impl From<CheckpointError> for ProtocolError {
             CheckpointError::Arrow { source } => Self::Arrow { source },
             CheckpointError::StaleTableVersion(..) => Self::Generic(value.to_string()),
             CheckpointError::Parquet { source } => Self::ParquetParseError { source },
-            CheckpointError::MissingActionType(_) => Self::Generic(value.to_string()),
+            CheckpointError::MissingActionType(_) | CheckpointError::FilesNotLoaded => {
+                Self::Generic(value.to_string())
+            }
         }
     }
 }
@@ -95,24 +105,31 @@ pub async fn create_checkpoint(table: &DeltaTable) -> Result<(), ProtocolError>
     Ok(())
 }
 
-/// Delete expires log files before given version from table. The table log retention is based on
-/// the `logRetentionDuration` property of the Delta Table, 30 days by default.
-pub async fn cleanup_metadata(table: &DeltaTable) -> Result<usize, ProtocolError> {
-    let log_retention_timestamp = Utc::now().timestamp_millis()
-        - table
-            .snapshot()
-            .map_err(|_| ProtocolError::NoMetaData)?
-            .table_config()
-            .log_retention_duration()
-            .as_millis() as i64;
-    cleanup_expired_logs_for(
+/// Creates checkpoint at current table version, reporting the number of written actions as
+/// [`ProgressStage::CheckpointWrite`]
+pub async fn create_checkpoint_with_progress(
+    table: &DeltaTable,
+    reporter: ProgressReporterRef,
+) -> Result<(), ProtocolError> {
+    write_checkpoint(
         table.version(),
+        table.snapshot().map_err(|_| ProtocolError::NoMetaData)?,
         table.log_store.as_ref(),
-        log_retention_timestamp,
+        Some(&reporter),
     )
     .await
 }
 
+/// Delete expires log files before given version from table. The table log retention is based on
+/// the `logRetentionDuration` property of the Delta Table, 30 days by default.
+pub async fn cleanup_metadata(table: &DeltaTable) -> Result<usize, ProtocolError> {
+    let snapshot = table.snapshot().map_err(|_| ProtocolError::NoMetaData)?;
+    let log_retention_timestamp = Utc::now().timestamp_millis()
+        - snapshot.table_config().log_retention_duration().as_millis() as i64;
+    cleanup_expired_logs_for_snapshot(snapshot, table.log_store.as_ref(), log_retention_timestamp)
+        .await
+}
+
 /// Loads table from given `table_uri` at given `version` and creates checkpoint for it.
 /// The `cleanup` param decides whether to run metadata cleanup of obsolete logs.
 /// If it's empty then the table's `enableExpiredLogCleanup` is used.
@@ -143,6 +160,15 @@ pub async fn create_checkpoint_for(
     version: i64,
     state: &DeltaTableState,
     log_store: &dyn LogStore,
+) -> Result<(), ProtocolError> {
+    write_checkpoint(version, state, log_store, None).await
+}
+
+async fn write_checkpoint(
+    version: i64,
+    state: &DeltaTableState,
+    log_store: &dyn LogStore,
+    progress_reporter: Option<&ProgressReporterRef>,
 ) -> Result<(), ProtocolError> {
     if version != state.version() {
         error!(
@@ -151,6 +177,9 @@ pub async fn create_checkpoint_for(
         );
         return Err(CheckpointError::StaleTableVersion(version, state.version()).into());
     }
+    if !state.load_config().require_files || !state.snapshot().partition_filters().is_empty() {
+        return Err(CheckpointError::FilesNotLoaded.into());
+    }
 
     // TODO: checkpoints _can_ be multi-part... haven't actually found a good reference for
     // an appropriate split point yet though so only writing a single part currently.
@@ -163,7 +192,8 @@ pub async fn create_checkpoint_for(
         .await
         .map_err(|_| ProtocolError::Generic("filed to get tombstones".into()))?
         .collect::<Vec<_>>();
-    let (checkpoint, parquet_bytes) = parquet_bytes_from_state(state, tombstones)?;
+    let (checkpoint, parquet_bytes) =
+        parquet_bytes_from_state(state, tombstones, progress_reporter)?;
 
     let file_name = format!("{version:020}.checkpoint.parquet");
     let checkpoint_path = log_store.log_path().child(file_name);
@@ -185,18 +215,69 @@ pub async fn create_checkpoint_for(
     Ok(())
 }
 
+/// Version before which commits and checkpoints of the table are protected by the
+/// checkpointProtection writer feature, if the table has it.
+pub fn checkpoint_protection_version(snapshot: &DeltaTableState) -> Option<i64> {
+    let protocol = snapshot.protocol();
+    let enabled = protocol.min_writer_version >= 7
+        && protocol
+            .writer_features
+            .as_ref()
+            .is_some_and(|features| features.contains(&WriterFeatures::CheckpointProtection));
+    enabled
+        .then(|| {
+            snapshot
+                .table_config()
+                .require_checkpoint_protection_before_version()
+        })
+        .flatten()
+}
+
 /// Deletes all delta log commits that are older than the cutoff time
 /// and less than the specified version.
+///
+/// The protocol of the table is not known here, so commits and checkpoints protected by the
+/// checkpointProtection feature may be deleted. Prefer [`cleanup_expired_logs_for_snapshot`].
 pub async fn cleanup_expired_logs_for(
     until_version: i64,
     log_store: &dyn LogStore,
     cutoff_timestamp: i64,
 ) -> Result<usize, ProtocolError> {
-    lazy_static! {
-        static ref DELTA_LOG_REGEX: Regex =
-            Regex::new(r"_delta_log/(\d{20})\.(json|checkpoint|json.tmp).*$").unwrap();
-    }
+    cleanup_expired_logs(until_version, log_store, cutoff_timestamp, None).await
+}
 
+/// Deletes all delta log commits that are older than the cutoff time and less than the version
+/// of `snapshot`.
+///
+/// If the table has the checkpointProtection writer feature, commits and checkpoints before
+/// `delta.requireCheckpointProtectionBeforeVersion` are only deleted if all of them are expired
+/// and the latest checkpoint is not before that version, so that no reader is left with a
+/// partial history of the protected versions.
+pub async fn cleanup_expired_logs_for_snapshot(
+    snapshot: &DeltaTableState,
+    log_store: &dyn LogStore,
+    cutoff_timestamp: i64,
+) -> Result<usize, ProtocolError> {
+    cleanup_expired_logs(
+        snapshot.version(),
+        log_store,
+        cutoff_timestamp,
+        checkpoint_protection_version(snapshot),
+    )
+    .await
+}
+
+lazy_static! {
+    static ref DELTA_LOG_REGEX: Regex =
+        Regex::new(r"_delta_log/(\d{20})\.(json|checkpoint|json.tmp).*$").unwrap();
+}
+
+async fn cleanup_expired_logs(
+    until_version: i64,
+    log_store: &dyn LogStore,
+    cutoff_timestamp: i64,
+    protected_before_version: Option<i64>,
+) -> Result<usize, ProtocolError> {
     let object_store = log_store.object_store();
     let maybe_last_checkpoint = object_store
         .get(&log_store.log_path().child("_last_checkpoint"))
@@ -210,6 +291,17 @@ pub async fn cleanup_expired_logs_for(
     let last_checkpoint: CheckPoint = serde_json::from_slice(&last_checkpoint)?;
     let until_version = i64::min(until_version, last_checkpoint.version);
 
+    if let Some(protected_before_version) = protected_before_version.filter(|version| *version > 0)
+    {
+        return cleanup_protected_logs(
+            until_version,
+            log_store,
+            cutoff_timestamp,
+            protected_before_version,
+        )
+        .await;
+    }
+
     // Feed a stream of candidate deletion files directly into the delete_stream
     // function to try to improve the speed of cleanup and reduce the need for
     // intermediate memory.
@@ -251,12 +343,65 @@ pub async fn cleanup_expired_logs_for(
     Ok(deleted.len())
 }
 
+/// Deletes the expired logs, but those before `protected_before_version` only if all of them
+/// are expired.
+async fn cleanup_protected_logs(
+    until_version: i64,
+    log_store: &dyn LogStore,
+    cutoff_timestamp: i64,
+    protected_before_version: i64,
+) -> Result<usize, ProtocolError> {
+    // all files have to be seen before deciding whether the protected ones can be deleted
+    let object_store = log_store.object_store();
+    let mut expired = Vec::new();
+    let mut retains_protected = false;
+    let mut files = object_store.list(Some(log_store.log_path()));
+    while let Some(meta) = files.next().await {
+        let meta = match meta {
+            Ok(meta) => meta,
+            Err(err) => {
+                error!("Error received while cleaning up expired logs: {:?}", err);
+                // the file we failed to see may be a protected one which is retained
+                retains_protected = true;
+                continue;
+            }
+        };
+        let Some(captures) = DELTA_LOG_REGEX.captures(meta.location.as_ref()) else {
+            continue;
+        };
+        let log_ver: i64 = captures.get(1).unwrap().as_str().parse().unwrap();
+        if log_ver < until_version && meta.last_modified.timestamp_millis() <= cutoff_timestamp {
+            expired.push((log_ver, meta.location));
+        } else if log_ver < protected_before_version {
+            retains_protected = true;
+        }
+    }
+    if retains_protected {
+        debug!("Retaining the logs before the protected version {protected_before_version}");
+        expired.retain(|(log_ver, _)| *log_ver >= protected_before_version);
+    }
+
+    let deleted = object_store
+        .delete_stream(
+            futures::stream::iter(expired.into_iter().map(|(_, location)| Ok(location))).boxed(),
+        )
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    debug!("Deleted {} expired logs", deleted.len());
+    Ok(deleted.len())
+}
+
 fn parquet_bytes_from_state(
     state: &DeltaTableState,
     mut tombstones: Vec<Remove>,
+    progress_reporter: Option<&ProgressReporterRef>,
 ) -> Result<(CheckPoint, bytes::Bytes), ProtocolError> {
     let current_metadata = state.metadata();
     let schema = current_metadata.schema()?;
+    let config = state.table_config();
+    let write_stats_as_json = config.write_stats_as_json();
+    let write_stats_as_struct = config.write_stats_as_struct();
 
     let partition_col_data_types = get_partition_col_data_types(&schema, current_metadata);
 
@@ -323,7 +468,13 @@ fn parquet_bytes_from_state(
     .map(|a| serde_json::to_value(a).map_err(ProtocolError::from))
     // adds
     .chain(files.iter().map(|f| {
-        checkpoint_add_from_state(f, partition_col_data_types.as_slice(), &stats_conversions)
+        checkpoint_add_from_state(
+            f,
+            partition_col_data_types.as_slice(),
+            &stats_conversions,
+            write_stats_as_json,
+            write_stats_as_struct,
+        )
     }));
 
     // Create the arrow schema that represents the Checkpoint parquet file.
@@ -331,6 +482,7 @@ fn parquet_bytes_from_state(
         (&schema).try_into()?,
         current_metadata.partition_columns.as_slice(),
         use_extended_remove_schema,
+        write_stats_as_struct,
     );
 
     debug!("Writing to checkpoint parquet buffer...");
@@ -349,10 +501,20 @@ fn parquet_bytes_from_state(
         .with_batch_size(CHECKPOINT_RECORD_BATCH_SIZE)
         .build_decoder()?;
     let jsons = jsons.collect::<Result<Vec<serde_json::Value>, _>>()?;
-    decoder.serialize(&jsons)?;
-
-    while let Some(batch) = decoder.flush()? {
-        writer.write(&batch)?;
+    let total = jsons.len() as u64;
+    let mut done = 0;
+    for chunk in jsons.chunks(CHECKPOINT_RECORD_BATCH_SIZE) {
+        decoder.serialize(chunk)?;
+        while let Some(batch) = decoder.flush()? {
+            writer.write(&batch)?;
+        }
+        done += chunk.len() as u64;
+        report(
+            progress_reporter,
+            ProgressStage::CheckpointWrite,
+            done,
+            total,
+        );
     }
 
     let _ = writer.close()?;
@@ -364,16 +526,30 @@ fn parquet_bytes_from_state(
     Ok((checkpoint, bytes::Bytes::from(bytes)))
 }
 
+/// Serialize an add action for the checkpoint, with its statistics as a JSON string and/or
+/// typed struct according to the `delta.checkpoint.writeStatsAsJson` and
+/// `delta.checkpoint.writeStatsAsStruct` properties of the table.
 fn checkpoint_add_from_state(
     add: &AddAction,
     partition_col_data_types: &[(&String, &DataType)],
     stats_conversions: &[(SchemaPath, DataType)],
+    write_stats_as_json: bool,
+    write_stats_as_struct: bool,
 ) -> Result<Value, ProtocolError> {
     let mut v = serde_json::to_value(Action::Add(add.clone()))
         .map_err(|err| ArrowError::JsonError(err.to_string()))?;
 
     v["add"]["dataChange"] = Value::Bool(false);
 
+    if !write_stats_as_json {
+        if let Some(add) = v["add"].as_object_mut() {
+            add.remove("stats");
+        }
+    }
+    if !write_stats_as_struct {
+        return Ok(v);
+    }
+
     if !add.partition_values.is_empty() {
         let mut partition_values_parsed: HashMap<String, Value> = HashMap::new();
 
@@ -419,7 +595,9 @@ fn typed_partition_value_from_string(
 ) -> Result<Value, ProtocolError> {
     match data_type {
         DataType::Primitive(primitive_type) => match primitive_type {
-            PrimitiveType::String | PrimitiveType::Binary => Ok(string_value.to_owned().into()),
+            PrimitiveType::String | PrimitiveType::Binary | PrimitiveType::Decimal(_, _) => {
+                Ok(string_value.to_owned().into())
+            }
             PrimitiveType::Long
             | PrimitiveType::Integer
             | PrimitiveType::Short
@@ -542,11 +720,14 @@ mod tests {
     use std::sync::Arc;
 
     use arrow_array::builder::{Int32Builder, ListBuilder, StructBuilder};
-    use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int64Type;
+    use arrow_array::{Array, ArrayRef, Int32Array, RecordBatch, StringArray};
     use arrow_schema::Schema as ArrowSchema;
     use chrono::Duration;
     use lazy_static::lazy_static;
     use object_store::path::Path;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
     use serde_json::json;
 
     use super::*;
@@ -586,6 +767,27 @@ mod tests {
         assert_eq!(last_checkpoint.version, 0);
     }
 
+    #[tokio::test]
+    async fn test_create_checkpoint_with_progress() {
+        use crate::operations::progress::tests::RecordingReporter;
+
+        let table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await
+            .unwrap();
+        let reporter = Arc::new(RecordingReporter::default());
+        create_checkpoint_with_progress(&table, reporter.clone())
+            .await
+            .unwrap();
+
+        // protocol and metadata
+        assert_eq!(
+            *reporter.calls.lock(),
+            vec![(ProgressStage::CheckpointWrite, 2, 2)]
+        );
+    }
+
     /// This test validates that a checkpoint can be written and re-read with the minimum viable
     /// Metadata. There was a bug which didn't handle the optionality of createdTime.
     #[tokio::test]
@@ -916,6 +1118,74 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_cleanup_with_checkpoint_protection() {
+        async fn commit_metadata(table: &mut DeltaTable) {
+            let metadata = table.metadata().unwrap().clone();
+            let operation = crate::protocol::DeltaOperation::StreamingUpdate {
+                output_mode: crate::protocol::OutputMode::Append,
+                query_id: "test".into(),
+                epoch_id: table.version(),
+            };
+            CommitBuilder::default()
+                .with_actions(vec![Action::Metadata(metadata)])
+                .build(
+                    table.state.as_ref().map(|f| f as &dyn TableReference),
+                    table.log_store(),
+                    operation,
+                )
+                .await
+                .unwrap();
+            table.load().await.unwrap();
+            create_checkpoint(table).await.unwrap();
+        }
+
+        let protocol =
+            Protocol::new(1, 7).with_writer_features([WriterFeatures::CheckpointProtection]);
+        let mut table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_actions([Action::Protocol(protocol)])
+            .with_configuration_property(
+                crate::DeltaConfigKey::RequireCheckpointProtectionBeforeVersion,
+                Some("2"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            checkpoint_protection_version(table.snapshot().unwrap()),
+            Some(2)
+        );
+        commit_metadata(&mut table).await;
+
+        // deleting version 0 would leave a partial history before version 2
+        let cutoff_timestamp = Utc::now().timestamp_millis() + Duration::days(1).num_milliseconds();
+        let count = cleanup_expired_logs_for_snapshot(
+            table.snapshot().unwrap(),
+            table.log_store().as_ref(),
+            cutoff_timestamp,
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 0);
+        let path = Path::from("_delta_log/00000000000000000000.json");
+        assert!(table.log_store().object_store().get(&path).await.is_ok());
+
+        commit_metadata(&mut table).await;
+
+        // the protected commits and checkpoints are deleted all at once
+        let count = cleanup_expired_logs_for_snapshot(
+            table.snapshot().unwrap(),
+            table.log_store().as_ref(),
+            cutoff_timestamp,
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 3);
+        let path = Path::from("_delta_log/00000000000000000002.checkpoint.parquet");
+        assert!(table.log_store().object_store().get(&path).await.is_ok());
+    }
+
     #[test]
     fn apply_stats_conversion_test() {
         let mut stats = STATS_JSON.clone();
@@ -1005,6 +1275,66 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_checkpoint_stats_format() {
+        let batch = RecordBatch::try_from_iter(vec![
+            ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+            (
+                "part",
+                Arc::new(StringArray::from(vec!["a", "a", "a"])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let checkpoint_adds = |configuration: Vec<(&'static str, &'static str)>| {
+            let batch = batch.clone();
+            async move {
+                let table = DeltaOps::new_in_memory()
+                    .write(vec![batch])
+                    .with_partition_columns(["part"])
+                    .with_configuration(configuration.into_iter().map(|(k, v)| (k, Some(v))))
+                    .await
+                    .unwrap();
+                let (_, bytes) =
+                    parquet_bytes_from_state(table.snapshot().unwrap(), vec![], None).unwrap();
+                let batches = ParquetRecordBatchReaderBuilder::try_new(bytes)
+                    .unwrap()
+                    .build()
+                    .unwrap()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                // protocol, metadata and the single add action
+                assert_eq!(batches[0].num_rows(), 3);
+                batches[0]
+                    .column_by_name("add")
+                    .unwrap()
+                    .as_struct()
+                    .clone()
+            }
+        };
+
+        // by default stats are written both as JSON and struct
+        let add = checkpoint_adds(vec![]).await;
+        assert!(add.column_by_name("stats").unwrap().is_valid(2));
+        let stats_parsed = add.column_by_name("stats_parsed").unwrap().as_struct();
+        let num_records = stats_parsed.column_by_name("numRecords").unwrap();
+        assert_eq!(num_records.as_primitive::<Int64Type>().value(2), 3);
+        let partition_values = add
+            .column_by_name("partitionValues_parsed")
+            .unwrap()
+            .as_struct();
+        let part = partition_values.column_by_name("part").unwrap();
+        assert_eq!(part.as_string::<i32>().value(2), "a");
+
+        let add = checkpoint_adds(vec![("delta.checkpoint.writeStatsAsJson", "false")]).await;
+        assert!(add.column_by_name("stats").unwrap().is_null(2));
+        assert!(add.column_by_name("stats_parsed").unwrap().is_valid(2));
+
+        let add = checkpoint_adds(vec![("delta.checkpoint.writeStatsAsStruct", "false")]).await;
+        assert!(add.column_by_name("stats").unwrap().is_valid(2));
+        assert!(add.column_by_name("stats_parsed").is_none());
+        assert!(add.column_by_name("partitionValues_parsed").is_none());
+    }
+
     #[tokio::test]
     async fn test_struct_with_single_list_field() {
         // you need another column otherwise the entire stats struct is empty
@@ -5,6 +5,7 @@
 pub mod checkpoints;
 mod parquet_read;
 mod time_utils;
+pub mod uniform;
 
 use arrow_schema::ArrowError;
 use futures::StreamExt;
@@ -14,7 +15,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::borrow::Borrow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::hash::{Hash, Hasher};
 use std::mem::take;
 use std::str::FromStr;
@@ -377,6 +378,21 @@ pub enum DeltaOperation {
         name: String,
     },
 
+    /// Add table features to a table
+    AddFeature {
+        /// Name of the features
+        name: Vec<String>,
+    },
+
+    /// Drops a table feature from a table
+    #[serde(rename_all = "camelCase")]
+    DropFeature {
+        /// Name of the feature
+        name: String,
+        /// Whether the history of the table has been truncated
+        truncate_history: bool,
+    },
+
     /// Merge data with a source data with the following predicate
     #[serde(rename_all = "camelCase")]
     Merge {
@@ -476,6 +492,8 @@ impl DeltaOperation {
             DeltaOperation::VacuumEnd { .. } => "VACUUM END",
             DeltaOperation::AddConstraint { .. } => "ADD CONSTRAINT",
             DeltaOperation::DropConstraint { .. } => "DROP CONSTRAINT",
+            DeltaOperation::AddFeature { .. } => "ADD FEATURE",
+            DeltaOperation::DropFeature { .. } => "DROP FEATURE",
         }
     }
 
@@ -492,11 +510,7 @@ impl DeltaOperation {
                 .map(|(k, v)| {
                     (
                         k.to_owned(),
-                        serde_json::Value::String(if v.is_string() {
-                            String::from(v.as_str().unwrap())
-                        } else {
-                            v.to_string()
-                        }),
+                        serde_json::Value::String(normalize_operation_parameter(k, v)),
                     )
                 })
                 .collect())
@@ -516,7 +530,9 @@ impl DeltaOperation {
             | Self::VacuumStart { .. }
             | Self::VacuumEnd { .. }
             | Self::AddConstraint { .. }
-            | Self::DropConstraint { .. } => false,
+            | Self::DropConstraint { .. }
+            | Self::AddFeature { .. }
+            | Self::DropFeature { .. } => false,
             Self::Create { .. }
             | Self::FileSystemCheck {}
             | Self::StreamingUpdate { .. }
@@ -560,6 +576,91 @@ impl DeltaOperation {
     }
 }
 
+/// Operation parameters holding arrays in which the order of the elements is meaningful.
+const ORDERED_OPERATION_PARAMETERS: [&str; 4] = [
+    "partitionBy",
+    "matchedPredicates",
+    "notMatchedPredicates",
+    "notMatchedBySourcePredicates",
+];
+
+fn is_predicate_parameter(key: &str) -> bool {
+    key == "predicate" || key.ends_with("Predicate")
+}
+
+/// Render an operation parameter as a canonical string.
+///
+/// Predicates are stripped of redundant whitespace, object keys are sorted and arrays are
+/// sorted unless the order of their elements is meaningful. Logically identical parameters
+/// are thus always recorded identically in the commit info, regardless of how they were
+/// specified by the user.
+fn normalize_operation_parameter(key: &str, value: &Value) -> String {
+    match value {
+        Value::String(s) if is_predicate_parameter(key) => normalize_predicate(s),
+        Value::String(s) => s.clone(),
+        other => canonical_json(key, other),
+    }
+}
+
+fn canonical_json(key: &str, value: &Value) -> String {
+    match value {
+        Value::String(s) if is_predicate_parameter(key) => {
+            Value::String(normalize_predicate(s)).to_string()
+        }
+        Value::Array(items) => {
+            let mut items = items
+                .iter()
+                .map(|item| canonical_json(key, item))
+                .collect::<Vec<_>>();
+            if !ORDERED_OPERATION_PARAMETERS.contains(&key) {
+                items.sort_unstable();
+            }
+            format!("[{}]", items.join(","))
+        }
+        Value::Object(map) => {
+            let entries = map
+                .iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, canonical_json(k, v)))
+                .collect::<BTreeMap<_, _>>()
+                .into_iter()
+                .map(|(k, v)| format!("{}:{}", Value::String(k.clone()), v))
+                .collect::<Vec<_>>();
+            format!("{{{}}}", entries.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Collapse all whitespace outside of quoted literals and identifiers into single spaces.
+fn normalize_predicate(predicate: &str) -> String {
+    let mut normalized = String::with_capacity(predicate.len());
+    let mut quote = None;
+    let mut pending_space = false;
+    for c in predicate.trim().chars() {
+        match quote {
+            Some(q) => {
+                normalized.push(c);
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c.is_whitespace() => pending_space = true,
+            None => {
+                if pending_space {
+                    normalized.push(' ');
+                    pending_space = false;
+                }
+                if matches!(c, '\'' | '"' | '`') {
+                    quote = Some(c);
+                }
+                normalized.push(c);
+            }
+        }
+    }
+    normalized
+}
+
 /// The SaveMode used when performing a DeltaOperation
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum SaveMode {
@@ -590,6 +691,30 @@ impl FromStr for SaveMode {
     }
 }
 
+///Specifies how to handle schema drifts
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SchemaMode {
+    /// Overwrite the schema with the new schema
+    Overwrite,
+    /// Append the new schema to the existing schema
+    Merge,
+}
+
+impl FromStr for SchemaMode {
+    type Err = DeltaTableError;
+
+    fn from_str(s: &str) -> DeltaResult<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "overwrite" => Ok(SchemaMode::Overwrite),
+            "merge" => Ok(SchemaMode::Merge),
+            _ => Err(DeltaTableError::Generic(format!(
+                "Invalid schema write mode provided: {}, only these are supported: ['overwrite', 'merge']",
+                s
+            ))),
+        }
+    }
+}
+
 /// The OutputMode used in streaming operations.
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
 pub enum OutputMode {
@@ -680,6 +805,81 @@ mod tests {
     use super::*;
     use crate::kernel::Action;
 
+    #[test]
+    fn test_operation_parameters_are_normalized() {
+        let operation = DeltaOperation::Delete {
+            predicate: Some("  value  >  1 AND  name = 'a  b'\n".into()),
+        };
+        let parameters = operation.operation_parameters().unwrap();
+        assert_eq!(
+            parameters["predicate"],
+            Value::String("value > 1 AND name = 'a  b'".into())
+        );
+
+        let operation = DeltaOperation::SetTableProperties {
+            properties: HashMap::from([
+                (
+                    "delta.logRetentionDuration".into(),
+                    "interval 2 days".into(),
+                ),
+                ("delta.appendOnly".into(), "true".into()),
+            ]),
+        };
+        let parameters = operation.operation_parameters().unwrap();
+        assert_eq!(
+            parameters["properties"],
+            Value::String(
+                r#"{"delta.appendOnly":"true","delta.logRetentionDuration":"interval 2 days"}"#
+                    .into()
+            )
+        );
+
+        let operation = DeltaOperation::Merge {
+            predicate: None,
+            merge_predicate: Some("target.id =   source.id".into()),
+            matched_predicates: vec![
+                MergePredicate {
+                    action_type: "update".into(),
+                    predicate: Some("source.value  > 1".into()),
+                },
+                MergePredicate {
+                    action_type: "delete".into(),
+                    predicate: None,
+                },
+            ],
+            not_matched_predicates: vec![],
+            not_matched_by_source_predicates: vec![],
+        };
+        let parameters = operation.operation_parameters().unwrap();
+        assert_eq!(
+            parameters["mergePredicate"],
+            Value::String("target.id = source.id".into())
+        );
+        assert_eq!(
+            parameters["matchedPredicates"],
+            Value::String(
+                r#"[{"actionType":"update","predicate":"source.value > 1"},{"actionType":"delete"}]"#
+                    .into()
+            )
+        );
+        assert_eq!(
+            parameters["notMatchedPredicates"],
+            Value::String("[]".into())
+        );
+
+        let operation = DeltaOperation::Write {
+            mode: SaveMode::Append,
+            partition_by: Some(vec!["b".into(), "a".into()]),
+            predicate: None,
+        };
+        let parameters = operation.operation_parameters().unwrap();
+        assert_eq!(
+            parameters["partitionBy"],
+            Value::String(r#"["b","a"]"#.into())
+        );
+        assert_eq!(parameters["mode"], Value::String("Append".into()));
+    }
+
     #[test]
     fn test_load_table_stats() {
         let action = Add {
@@ -0,0 +1,177 @@
+//! Minimal writer for Avro object container files.
+//!
+//! Iceberg manifests and manifest lists are Avro files whose schemas carry Iceberg field ids as
+//! custom attributes, so the schema is passed through verbatim as JSON. Data blocks are written
+//! uncompressed.
+use bytes::Bytes;
+
+const MAGIC: &[u8] = b"Obj\x01";
+
+/// A value in the Avro binary encoding
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Boolean(bool),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Bytes(Vec<u8>),
+    Fixed(Vec<u8>),
+    String(String),
+    /// Branch of a union, by index in the union schema
+    Union(usize, Box<Value>),
+    /// Values of the record fields, in schema order
+    Record(Vec<Value>),
+}
+
+impl Value {
+    /// Value of an optional field, encoded as a `["null", T]` union
+    pub(crate) fn optional(value: Option<Value>) -> Self {
+        match value {
+            Some(value) => Value::Union(1, Box::new(value)),
+            None => Value::Union(0, Box::new(Value::Null)),
+        }
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            Value::Null => {}
+            Value::Boolean(b) => buf.push(*b as u8),
+            Value::Int(i) => encode_long(*i as i64, buf),
+            Value::Long(l) => encode_long(*l, buf),
+            Value::Float(f) => buf.extend_from_slice(&f.to_le_bytes()),
+            Value::Double(d) => buf.extend_from_slice(&d.to_le_bytes()),
+            Value::Bytes(b) => encode_bytes(b, buf),
+            Value::Fixed(b) => buf.extend_from_slice(b),
+            Value::String(s) => encode_bytes(s.as_bytes(), buf),
+            Value::Union(index, value) => {
+                encode_long(*index as i64, buf);
+                value.encode(buf);
+            }
+            Value::Record(fields) => fields.iter().for_each(|field| field.encode(buf)),
+        }
+    }
+}
+
+fn encode_long(value: i64, buf: &mut Vec<u8>) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n & !0x7f != 0 {
+        buf.push(((n & 0x7f) | 0x80) as u8);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+fn encode_bytes(value: &[u8], buf: &mut Vec<u8>) {
+    encode_long(value.len() as i64, buf);
+    buf.extend_from_slice(value);
+}
+
+/// Write `records` to an Avro object container file with the given schema and file metadata
+pub(crate) fn write_container(
+    schema: &str,
+    metadata: &[(&str, String)],
+    records: &[Value],
+) -> Bytes {
+    let mut buf = MAGIC.to_vec();
+
+    let entries = std::iter::once(("avro.schema", schema))
+        .chain(std::iter::once(("avro.codec", "null")))
+        .chain(metadata.iter().map(|(key, value)| (*key, value.as_str())))
+        .collect::<Vec<_>>();
+    encode_long(entries.len() as i64, &mut buf);
+    for (key, value) in entries {
+        encode_bytes(key.as_bytes(), &mut buf);
+        encode_bytes(value.as_bytes(), &mut buf);
+    }
+    encode_long(0, &mut buf);
+
+    let sync = *uuid::Uuid::new_v4().as_bytes();
+    buf.extend_from_slice(&sync);
+
+    if !records.is_empty() {
+        let mut block = Vec::new();
+        records.iter().for_each(|record| record.encode(&mut block));
+        encode_long(records.len() as i64, &mut buf);
+        encode_bytes(&block, &mut buf);
+        buf.extend_from_slice(&sync);
+    }
+
+    buf.into()
+}
+
+/// Turn `name` into a valid Avro name, following the escaping used by Iceberg
+pub(crate) fn sanitize_name(name: &str) -> String {
+    let mut sanitized = String::with_capacity(name.len());
+    for (i, c) in name.chars().enumerate() {
+        let valid = c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit());
+        if valid {
+            sanitized.push(c);
+        } else if c.is_ascii_digit() {
+            sanitized.push('_');
+            sanitized.push(c);
+        } else {
+            sanitized.push_str(&format!("_x{:X}", c as u32));
+        }
+    }
+    sanitized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(value: Value) -> Vec<u8> {
+        let mut buf = Vec::new();
+        value.encode(&mut buf);
+        buf
+    }
+
+    #[test]
+    fn test_encode_values() {
+        assert_eq!(encoded(Value::Long(0)), vec![0x00]);
+        assert_eq!(encoded(Value::Long(-1)), vec![0x01]);
+        assert_eq!(encoded(Value::Long(1)), vec![0x02]);
+        assert_eq!(encoded(Value::Int(64)), vec![0x80, 0x01]);
+        assert_eq!(encoded(Value::Long(-65)), vec![0x81, 0x01]);
+        assert_eq!(
+            encoded(Value::String("foo".to_string())),
+            vec![0x06, b'f', b'o', b'o']
+        );
+        assert_eq!(encoded(Value::optional(None)), vec![0x00]);
+        assert_eq!(
+            encoded(Value::optional(Some(Value::Boolean(true)))),
+            vec![0x02, 0x01]
+        );
+        assert_eq!(
+            encoded(Value::Record(vec![Value::Int(1), Value::Fixed(vec![0xff])])),
+            vec![0x02, 0xff]
+        );
+    }
+
+    #[test]
+    fn test_write_container() {
+        let schema = r#"{"type":"record","name":"r","fields":[{"name":"a","type":"long"}]}"#;
+        let data = write_container(
+            schema,
+            &[("format-version", "2".to_string())],
+            &[Value::Record(vec![Value::Long(1)])],
+        );
+        assert!(data.starts_with(MAGIC));
+        // metadata map with three entries
+        assert_eq!(data[4], 0x06);
+        // block of one record of one byte, followed by the sync marker
+        let sync = &data[data.len() - 16..];
+        assert_eq!(&data[data.len() - 19..data.len() - 16], &[0x02, 0x02, 0x02]);
+        assert_eq!(&data[data.len() - 35..data.len() - 19], sync);
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("value"), "value");
+        assert_eq!(sanitize_name("1st"), "_1st");
+        assert_eq!(sanitize_name("a-b"), "a_x2Db");
+        assert_eq!(sanitize_name("a_1"), "a_1");
+    }
+}
@@ -0,0 +1,1073 @@
+//! Universal format (UniForm) support, exposing Delta tables to Iceberg readers.
+//!
+//! When `delta.universalFormat.enabledFormats` contains `iceberg`, every commit also writes
+//! Iceberg (format version 2) metadata reflecting the new Delta snapshot to the `metadata`
+//! folder of the table:
+//!
+//! - a manifest listing the data files of the snapshot
+//! - a manifest list referencing the manifest
+//! - `v<N>.metadata.json` holding the schema, partition spec and snapshot history
+//! - `version-hint.text` with the version of the latest metadata file
+//!
+//! The Iceberg snapshot of Delta version `v` has snapshot id and sequence number `v + 1`, and
+//! the metadata keeps the history of the last [MAX_HISTORY] snapshots. Metadata files are
+//! created exclusively, a writer racing another one converts its version again on top of the
+//! metadata file written by the other writer.
+//!
+//! Iceberg readers can then query the table by registering the latest metadata file in their
+//! catalog, or through a Hadoop catalog pointing at the table location. Iceberg field ids are
+//! taken from the column mapping metadata when present, and assigned in schema order
+//! otherwise; a name mapping is added so parquet files without field ids can be read.
+//!
+//! Failing to write the Iceberg metadata does not fail the Delta commit. Tables with deletion
+//! vectors can not be converted, since Iceberg does not support them.
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use delta_kernel::expressions::Scalar;
+use object_store::path::Path;
+use object_store::{Error as ObjectStoreError, ObjectStore, PutMode};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+use self::avro::{sanitize_name, write_container, Value};
+use crate::kernel::{
+    Action, ColumnMetadataKey, DataType, MetadataValue, PrimitiveType, StructField, StructType,
+};
+use crate::logstore::LogStoreRef;
+use crate::table::state::DeltaTableState;
+use crate::{DeltaResult, DeltaTable, DeltaTableError};
+
+mod avro;
+
+/// Name of the Iceberg format in `delta.universalFormat.enabledFormats`
+pub const ICEBERG_FORMAT: &str = "iceberg";
+
+/// Property of the Iceberg table holding the Delta version it reflects
+pub const DELTA_VERSION_PROPERTY: &str = "delta-version";
+
+const METADATA_FOLDER: &str = "metadata";
+const VERSION_HINT: &str = "version-hint.text";
+const NAME_MAPPING_PROPERTY: &str = "schema.name-mapping.default";
+const PARTITION_FIELD_ID_START: i32 = 1000;
+/// Number of snapshots and metadata files kept in the history of the Iceberg metadata, which
+/// matches the default of `write.metadata.previous-versions-max` in Iceberg
+pub const MAX_HISTORY: usize = 100;
+/// Attempts to write the metadata of a version while other writers keep creating metadata files
+const MAX_ATTEMPTS: usize = 10;
+
+/// Errors that can occur while generating Iceberg metadata
+#[derive(thiserror::Error, Debug)]
+enum UniformError {
+    /// Deletion vectors can not be represented in Iceberg
+    #[error("Deletion vectors are not supported by Iceberg, found one for {path}")]
+    DeletionVector {
+        /// Path of the data file
+        path: String,
+    },
+
+    /// Iceberg requires the record count of every data file
+    #[error("Missing number of records in the statistics of {path}")]
+    MissingNumRecords {
+        /// Path of the data file
+        path: String,
+    },
+
+    /// Only primitive partition columns are supported
+    #[error("Unsupported partition column: {column}")]
+    UnsupportedPartitionColumn {
+        /// Name of the partition column
+        column: String,
+    },
+
+    /// The version hint of the existing Iceberg metadata is not a number
+    #[error("Invalid Iceberg version hint: {hint}")]
+    InvalidVersionHint {
+        /// Content of the version hint
+        hint: String,
+    },
+}
+
+impl From<UniformError> for DeltaTableError {
+    fn from(err: UniformError) -> Self {
+        DeltaTableError::GenericError {
+            source: Box::new(err),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+struct TableMetadata {
+    format_version: i32,
+    table_uuid: String,
+    location: String,
+    last_sequence_number: i64,
+    last_updated_ms: i64,
+    last_column_id: i32,
+    schemas: Vec<JsonValue>,
+    current_schema_id: i32,
+    partition_specs: Vec<PartitionSpec>,
+    default_spec_id: i32,
+    last_partition_id: i32,
+    properties: HashMap<String, String>,
+    current_snapshot_id: Option<i64>,
+    snapshots: Vec<Snapshot>,
+    snapshot_log: Vec<SnapshotLogEntry>,
+    metadata_log: Vec<MetadataLogEntry>,
+    sort_orders: Vec<JsonValue>,
+    default_sort_order_id: i32,
+    refs: HashMap<String, SnapshotReference>,
+}
+
+impl TableMetadata {
+    fn new(location: &str) -> Self {
+        Self {
+            format_version: 2,
+            table_uuid: uuid::Uuid::new_v4().to_string(),
+            location: location.to_string(),
+            last_sequence_number: 0,
+            last_updated_ms: 0,
+            last_column_id: 0,
+            schemas: Vec::new(),
+            current_schema_id: 0,
+            partition_specs: Vec::new(),
+            default_spec_id: 0,
+            last_partition_id: PARTITION_FIELD_ID_START - 1,
+            properties: HashMap::new(),
+            current_snapshot_id: None,
+            snapshots: Vec::new(),
+            snapshot_log: Vec::new(),
+            metadata_log: Vec::new(),
+            sort_orders: vec![json!({"order-id": 0, "fields": []})],
+            default_sort_order_id: 0,
+            refs: HashMap::new(),
+        }
+    }
+
+    fn delta_version(&self) -> Option<i64> {
+        self.properties
+            .get(DELTA_VERSION_PROPERTY)
+            .and_then(|version| version.parse().ok())
+    }
+
+    fn current_snapshot(&self) -> Option<&Snapshot> {
+        let snapshot_id = self.current_snapshot_id?;
+        self.snapshots
+            .iter()
+            .find(|snapshot| snapshot.snapshot_id == snapshot_id)
+    }
+
+    /// Id of the schema with the given fields, adding it if it does not exist yet
+    fn schema_id(&mut self, fields: &JsonValue) -> i32 {
+        let id_of = |schema: &JsonValue| schema["schema-id"].as_i64().unwrap_or_default() as i32;
+        if let Some(schema) = self.schemas.iter().find(|s| &s["fields"] == fields) {
+            return id_of(schema);
+        }
+        let id = self.schemas.iter().map(id_of).max().map_or(0, |id| id + 1);
+        self.schemas
+            .push(json!({"type": "struct", "schema-id": id, "fields": fields}));
+        id
+    }
+
+    /// Id of the partition spec with the given fields, adding it if it does not exist yet
+    fn spec_id(&mut self, fields: &[PartitionField]) -> i32 {
+        if let Some(spec) = self.partition_specs.iter().find(|s| s.fields == fields) {
+            return spec.spec_id;
+        }
+        let spec_id = self
+            .partition_specs
+            .iter()
+            .map(|spec| spec.spec_id)
+            .max()
+            .map_or(0, |id| id + 1);
+        self.partition_specs.push(PartitionSpec {
+            spec_id,
+            fields: fields.to_vec(),
+        });
+        spec_id
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+struct PartitionSpec {
+    spec_id: i32,
+    fields: Vec<PartitionField>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+struct PartitionField {
+    name: String,
+    transform: String,
+    source_id: i32,
+    field_id: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+struct Snapshot {
+    snapshot_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_snapshot_id: Option<i64>,
+    sequence_number: i64,
+    timestamp_ms: i64,
+    manifest_list: String,
+    summary: HashMap<String, String>,
+    schema_id: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+struct SnapshotLogEntry {
+    snapshot_id: i64,
+    timestamp_ms: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+struct MetadataLogEntry {
+    metadata_file: String,
+    timestamp_ms: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+struct SnapshotReference {
+    snapshot_id: i64,
+    #[serde(rename = "type")]
+    ref_type: String,
+}
+
+/// Converts a Delta schema to an Iceberg schema and its name mapping
+struct SchemaConverter {
+    next_id: i32,
+}
+
+impl SchemaConverter {
+    fn new(schema: &StructType) -> Self {
+        fn max_id<'a>(fields: impl Iterator<Item = &'a StructField>) -> i32 {
+            fields
+                .map(|field| {
+                    let id = match field
+                        .metadata
+                        .get(ColumnMetadataKey::ColumnMappingId.as_ref())
+                    {
+                        Some(MetadataValue::Number(id)) => *id,
+                        _ => 0,
+                    };
+                    let nested = match field.data_type() {
+                        DataType::Struct(inner) => max_id(inner.fields()),
+                        _ => 0,
+                    };
+                    id.max(nested)
+                })
+                .max()
+                .unwrap_or_default()
+        }
+        Self {
+            next_id: max_id(schema.fields()) + 1,
+        }
+    }
+
+    fn last_column_id(&self) -> i32 {
+        self.next_id - 1
+    }
+
+    fn next_id(&mut self) -> i32 {
+        self.next_id += 1;
+        self.next_id - 1
+    }
+
+    fn struct_fields<'a>(
+        &mut self,
+        fields: impl Iterator<Item = &'a StructField>,
+    ) -> (Vec<JsonValue>, Vec<JsonValue>) {
+        // assign the ids of all fields of the struct before descending into nested types
+        let fields = fields
+            .map(|field| {
+                let id = match field
+                    .metadata
+                    .get(ColumnMetadataKey::ColumnMappingId.as_ref())
+                {
+                    Some(MetadataValue::Number(id)) => *id,
+                    _ => self.next_id(),
+                };
+                (field, id)
+            })
+            .collect::<Vec<_>>();
+
+        fields
+            .into_iter()
+            .map(|(field, id)| {
+                let (field_type, nested_mapping) = self.convert_type(field.data_type());
+                let mut names = vec![field.name().to_string()];
+                if let Some(MetadataValue::String(physical_name)) = field
+                    .metadata
+                    .get(ColumnMetadataKey::ColumnMappingPhysicalName.as_ref())
+                {
+                    if physical_name != field.name() {
+                        names.push(physical_name.clone());
+                    }
+                }
+                let field = json!({
+                    "id": id,
+                    "name": field.name(),
+                    "required": !field.is_nullable(),
+                    "type": field_type,
+                });
+                (field, name_mapping(id, names, nested_mapping))
+            })
+            .unzip()
+    }
+
+    fn convert_type(&mut self, data_type: &DataType) -> (JsonValue, Option<Vec<JsonValue>>) {
+        match data_type {
+            DataType::Primitive(primitive) => (json!(iceberg_type(primitive)), None),
+            DataType::Struct(inner) => {
+                let (fields, mapping) = self.struct_fields(inner.fields());
+                (json!({"type": "struct", "fields": fields}), Some(mapping))
+            }
+            DataType::Array(inner) => {
+                let element_id = self.next_id();
+                let (element, mapping) = self.convert_type(inner.element_type());
+                let data_type = json!({
+                    "type": "list",
+                    "element-id": element_id,
+                    "element": element,
+                    "element-required": !inner.contains_null(),
+                });
+                let element = name_mapping(element_id, vec!["element".to_string()], mapping);
+                (data_type, Some(vec![element]))
+            }
+            DataType::Map(inner) => {
+                let key_id = self.next_id();
+                let value_id = self.next_id();
+                let (key, key_mapping) = self.convert_type(inner.key_type());
+                let (value, value_mapping) = self.convert_type(inner.value_type());
+                let data_type = json!({
+                    "type": "map",
+                    "key-id": key_id,
+                    "key": key,
+                    "value-id": value_id,
+                    "value": value,
+                    "value-required": !inner.value_contains_null(),
+                });
+                let mapping = vec![
+                    name_mapping(key_id, vec!["key".to_string()], key_mapping),
+                    name_mapping(value_id, vec!["value".to_string()], value_mapping),
+                ];
+                (data_type, Some(mapping))
+            }
+        }
+    }
+}
+
+fn name_mapping(id: i32, names: Vec<String>, fields: Option<Vec<JsonValue>>) -> JsonValue {
+    let mut mapping = json!({"field-id": id, "names": names});
+    if let Some(fields) = fields {
+        mapping["fields"] = fields.into();
+    }
+    mapping
+}
+
+fn iceberg_type(data_type: &PrimitiveType) -> String {
+    match data_type {
+        PrimitiveType::String => "string".to_string(),
+        PrimitiveType::Long => "long".to_string(),
+        PrimitiveType::Integer | PrimitiveType::Short | PrimitiveType::Byte => "int".to_string(),
+        PrimitiveType::Float => "float".to_string(),
+        PrimitiveType::Double => "double".to_string(),
+        PrimitiveType::Boolean => "boolean".to_string(),
+        PrimitiveType::Binary => "binary".to_string(),
+        PrimitiveType::Date => "date".to_string(),
+        PrimitiveType::Timestamp => "timestamptz".to_string(),
+        PrimitiveType::TimestampNtz => "timestamp".to_string(),
+        PrimitiveType::Decimal(precision, scale) => format!("decimal({precision}, {scale})"),
+    }
+}
+
+/// Avro type of a partition value, see the Iceberg spec on Avro type mappings
+fn partition_avro_type(name: &str, data_type: &PrimitiveType) -> JsonValue {
+    match data_type {
+        PrimitiveType::String => json!("string"),
+        PrimitiveType::Long => json!("long"),
+        PrimitiveType::Integer | PrimitiveType::Short | PrimitiveType::Byte => json!("int"),
+        PrimitiveType::Float => json!("float"),
+        PrimitiveType::Double => json!("double"),
+        PrimitiveType::Boolean => json!("boolean"),
+        PrimitiveType::Binary => json!("bytes"),
+        PrimitiveType::Date => json!({"type": "int", "logicalType": "date"}),
+        PrimitiveType::Timestamp => {
+            json!({"type": "long", "logicalType": "timestamp-micros", "adjust-to-utc": true})
+        }
+        PrimitiveType::TimestampNtz => {
+            json!({"type": "long", "logicalType": "timestamp-micros", "adjust-to-utc": false})
+        }
+        PrimitiveType::Decimal(precision, scale) => json!({
+            "type": "fixed",
+            "name": format!("fixed_{name}"),
+            "size": decimal_required_bytes(*precision),
+            "logicalType": "decimal",
+            "precision": precision,
+            "scale": scale,
+        }),
+    }
+}
+
+/// Minimum number of bytes of a fixed holding a decimal with the given precision
+fn decimal_required_bytes(precision: u8) -> usize {
+    ((precision as f64 * 10f64.log2() + 1.0) / 8.0).ceil() as usize
+}
+
+fn partition_avro_value(value: &Scalar, column: &str) -> Result<Option<Value>, UniformError> {
+    Ok(Some(match value {
+        Scalar::Null(_) => return Ok(None),
+        Scalar::String(s) => Value::String(s.clone()),
+        Scalar::Long(l) => Value::Long(*l),
+        Scalar::Integer(i) => Value::Int(*i),
+        Scalar::Short(s) => Value::Int(*s as i32),
+        Scalar::Byte(b) => Value::Int(*b as i32),
+        Scalar::Float(f) => Value::Float(*f),
+        Scalar::Double(d) => Value::Double(*d),
+        Scalar::Boolean(b) => Value::Boolean(*b),
+        Scalar::Binary(b) => Value::Bytes(b.clone()),
+        Scalar::Date(days) => Value::Int(*days),
+        Scalar::Timestamp(ts) | Scalar::TimestampNtz(ts) => Value::Long(*ts),
+        Scalar::Decimal(value, precision, _) => {
+            let size = decimal_required_bytes(*precision);
+            Value::Fixed(value.to_be_bytes()[16 - size..].to_vec())
+        }
+        Scalar::Struct(_) => {
+            return Err(UniformError::UnsupportedPartitionColumn {
+                column: column.to_string(),
+            })
+        }
+    }))
+}
+
+fn manifest_schema(partition_fields: Vec<JsonValue>) -> JsonValue {
+    json!({
+        "type": "record",
+        "name": "manifest_entry",
+        "fields": [
+            {"name": "status", "type": "int", "field-id": 0},
+            {"name": "snapshot_id", "type": ["null", "long"], "default": null, "field-id": 1},
+            {"name": "sequence_number", "type": ["null", "long"], "default": null, "field-id": 3},
+            {"name": "file_sequence_number", "type": ["null", "long"], "default": null, "field-id": 4},
+            {"name": "data_file", "field-id": 2, "type": {
+                "type": "record",
+                "name": "r2",
+                "fields": [
+                    {"name": "content", "type": "int", "field-id": 134},
+                    {"name": "file_path", "type": "string", "field-id": 100},
+                    {"name": "file_format", "type": "string", "field-id": 101},
+                    {"name": "partition", "field-id": 102, "type": {
+                        "type": "record",
+                        "name": "r102",
+                        "fields": partition_fields,
+                    }},
+                    {"name": "record_count", "type": "long", "field-id": 103},
+                    {"name": "file_size_in_bytes", "type": "long", "field-id": 104},
+                ],
+            }},
+        ],
+    })
+}
+
+fn manifest_list_schema() -> JsonValue {
+    json!({
+        "type": "record",
+        "name": "manifest_file",
+        "fields": [
+            {"name": "manifest_path", "type": "string", "field-id": 500},
+            {"name": "manifest_length", "type": "long", "field-id": 501},
+            {"name": "partition_spec_id", "type": "int", "field-id": 502},
+            {"name": "content", "type": "int", "field-id": 517},
+            {"name": "sequence_number", "type": "long", "field-id": 515},
+            {"name": "min_sequence_number", "type": "long", "field-id": 516},
+            {"name": "added_snapshot_id", "type": "long", "field-id": 503},
+            {"name": "added_files_count", "type": "int", "field-id": 504},
+            {"name": "existing_files_count", "type": "int", "field-id": 505},
+            {"name": "deleted_files_count", "type": "int", "field-id": 506},
+            {"name": "added_rows_count", "type": "long", "field-id": 512},
+            {"name": "existing_rows_count", "type": "long", "field-id": 513},
+            {"name": "deleted_rows_count", "type": "long", "field-id": 514},
+        ],
+    })
+}
+
+fn metadata_path(version: i64) -> Path {
+    Path::from_iter([METADATA_FOLDER, &format!("v{version}.metadata.json")])
+}
+
+/// Read the latest Iceberg metadata of the table and its version, if any
+async fn read_latest_metadata(
+    store: &dyn ObjectStore,
+) -> DeltaResult<Option<(i64, TableMetadata)>> {
+    let hint = match store
+        .get(&Path::from_iter([METADATA_FOLDER, VERSION_HINT]))
+        .await
+    {
+        Ok(result) => result.bytes().await?,
+        Err(ObjectStoreError::NotFound { .. }) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let hint = String::from_utf8_lossy(&hint).trim().to_string();
+    let mut version = hint
+        .parse::<i64>()
+        .map_err(|_| UniformError::InvalidVersionHint { hint })?;
+    // the hint is written after the metadata file, so it may lag behind
+    loop {
+        match store.head(&metadata_path(version + 1)).await {
+            Ok(_) => version += 1,
+            Err(ObjectStoreError::NotFound { .. }) => break,
+            Err(err) => return Err(err.into()),
+        }
+    }
+    let data = store.get(&metadata_path(version)).await?.bytes().await?;
+    Ok(Some((version, serde_json::from_slice(&data)?)))
+}
+
+/// Write Iceberg metadata reflecting the current version of `table`.
+///
+/// All data files are recorded as added in the new Iceberg snapshot. This can be used to
+/// generate the Iceberg metadata of tables which did not enable UniForm when they were written.
+pub async fn create_iceberg_metadata(table: &DeltaTable) -> DeltaResult<()> {
+    write_iceberg_metadata(table.snapshot()?, &table.log_store(), None).await
+}
+
+/// Write Iceberg metadata reflecting `snapshot` to the `metadata` folder of the table.
+///
+/// Data files added by `actions` are recorded as added in the new Iceberg snapshot, the other
+/// ones as existing. When `actions` is `None`, all data files are recorded as added.
+pub(crate) async fn write_iceberg_metadata(
+    snapshot: &DeltaTableState,
+    log_store: &LogStoreRef,
+    actions: Option<&[Action]>,
+) -> DeltaResult<()> {
+    let mut attempt = 1;
+    loop {
+        match try_write_iceberg_metadata(snapshot, log_store, actions).await {
+            // another writer created the next metadata file first
+            Err(DeltaTableError::ObjectStore {
+                source: ObjectStoreError::AlreadyExists { .. },
+            }) if attempt < MAX_ATTEMPTS => attempt += 1,
+            result => return result,
+        }
+    }
+}
+
+async fn try_write_iceberg_metadata(
+    snapshot: &DeltaTableState,
+    log_store: &LogStoreRef,
+    actions: Option<&[Action]>,
+) -> DeltaResult<()> {
+    let store = log_store.object_store();
+    let location = log_store
+        .config()
+        .location
+        .as_str()
+        .trim_end_matches('/')
+        .to_string();
+
+    let previous = read_latest_metadata(store.as_ref()).await?;
+    if let Some((_, metadata)) = &previous {
+        if metadata
+            .delta_version()
+            .is_some_and(|version| version >= snapshot.version())
+        {
+            return Ok(());
+        }
+    }
+    let mut metadata = match &previous {
+        Some((_, metadata)) => metadata.clone(),
+        None => TableMetadata::new(&location),
+    };
+
+    // schema and partition spec
+    let schema = snapshot.schema();
+    let mut converter = SchemaConverter::new(schema);
+    let (fields, name_mapping) = converter.struct_fields(schema.fields());
+    let field_ids: HashMap<&str, i32> = fields
+        .iter()
+        .filter_map(|field| Some((field["name"].as_str()?, field["id"].as_i64()? as i32)))
+        .collect();
+
+    let partition_columns = &snapshot.metadata().partition_columns;
+    let mut partition_fields = Vec::with_capacity(partition_columns.len());
+    let mut partition_avro_fields = Vec::with_capacity(partition_columns.len());
+    for (i, column) in partition_columns.iter().enumerate() {
+        let unsupported = || UniformError::UnsupportedPartitionColumn {
+            column: column.clone(),
+        };
+        let data_type = match schema.field(column).map(|field| field.data_type()) {
+            Some(DataType::Primitive(primitive)) => primitive,
+            _ => return Err(unsupported().into()),
+        };
+        let field_id = PARTITION_FIELD_ID_START + i as i32;
+        partition_fields.push(PartitionField {
+            name: column.clone(),
+            transform: "identity".to_string(),
+            source_id: *field_ids.get(column.as_str()).ok_or_else(unsupported)?,
+            field_id,
+        });
+        let name = sanitize_name(column);
+        partition_avro_fields.push(json!({
+            "name": name,
+            "type": ["null", partition_avro_type(&name, data_type)],
+            "default": null,
+            "field-id": field_id,
+        }));
+    }
+
+    let fields = JsonValue::from(fields);
+    let schema_id = metadata.schema_id(&fields);
+    let spec_id = metadata.spec_id(&partition_fields);
+    let iceberg_schema = json!({"type": "struct", "schema-id": schema_id, "fields": fields});
+
+    // manifest
+    let parent = metadata.current_snapshot().cloned();
+    // sequence number 0 is reserved for data files written before sequence numbers existed
+    let sequence_number = snapshot.version() + 1;
+    let snapshot_id = snapshot.version() + 1;
+    let added_paths: Option<HashSet<String>> = actions.map(|actions| {
+        actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::Add(add) => Some(percent_decode_str(&add.path).decode_utf8_lossy().into()),
+                _ => None,
+            })
+            .collect()
+    });
+
+    let mut entries = Vec::new();
+    let (mut added_files, mut added_rows, mut existing_files, mut existing_rows) = (0, 0, 0, 0);
+    let mut min_sequence_number = sequence_number;
+    for file in snapshot.snapshot.files() {
+        let path = file.path().to_string();
+        if file.deletion_vector().is_some() {
+            return Err(UniformError::DeletionVector { path }.into());
+        }
+        let num_records = file
+            .num_records()
+            .ok_or_else(|| UniformError::MissingNumRecords { path: path.clone() })?
+            as i64;
+
+        let partition_values = file.partition_values()?;
+        let partition = partition_columns
+            .iter()
+            .map(|column| match partition_values.get(column.as_str()) {
+                Some(value) => partition_avro_value(value, column).map(Value::optional),
+                None => Ok(Value::optional(None)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let added = match (&parent, &added_paths) {
+            (Some(_), Some(added_paths)) => added_paths.contains(&path),
+            _ => true,
+        };
+        let (status, entry_snapshot_id, entry_sequence_number) = match &parent {
+            Some(parent) if !added => {
+                existing_files += 1;
+                existing_rows += num_records;
+                (0, parent.snapshot_id, parent.sequence_number)
+            }
+            _ => {
+                added_files += 1;
+                added_rows += num_records;
+                (1, snapshot_id, sequence_number)
+            }
+        };
+        min_sequence_number = min_sequence_number.min(entry_sequence_number);
+
+        let file_path = if path.contains("://") {
+            path
+        } else {
+            format!("{location}/{path}")
+        };
+        entries.push(Value::Record(vec![
+            Value::Int(status),
+            Value::optional(Some(Value::Long(entry_snapshot_id))),
+            Value::optional(Some(Value::Long(entry_sequence_number))),
+            Value::optional(Some(Value::Long(entry_sequence_number))),
+            Value::Record(vec![
+                Value::Int(0),
+                Value::String(file_path),
+                Value::String("PARQUET".to_string()),
+                Value::Record(partition),
+                Value::Long(num_records),
+                Value::Long(file.size()),
+            ]),
+        ]));
+    }
+
+    let commit_uuid = uuid::Uuid::new_v4();
+    let mut manifests = Vec::new();
+    if !entries.is_empty() {
+        let manifest = write_container(
+            &manifest_schema(partition_avro_fields).to_string(),
+            &[
+                ("schema", iceberg_schema.to_string()),
+                ("schema-id", schema_id.to_string()),
+                ("partition-spec", serde_json::to_string(&partition_fields)?),
+                ("partition-spec-id", spec_id.to_string()),
+                ("format-version", "2".to_string()),
+                ("content", "data".to_string()),
+            ],
+            &entries,
+        );
+        let manifest_path = Path::from_iter([METADATA_FOLDER, &format!("{commit_uuid}-m0.avro")]);
+        let manifest_length = manifest.len() as i64;
+        store.put(&manifest_path, manifest.into()).await?;
+        manifests.push(Value::Record(vec![
+            Value::String(format!("{location}/{manifest_path}")),
+            Value::Long(manifest_length),
+            Value::Int(spec_id),
+            Value::Int(0),
+            Value::Long(sequence_number),
+            Value::Long(min_sequence_number),
+            Value::Long(snapshot_id),
+            Value::Int(added_files),
+            Value::Int(existing_files),
+            Value::Int(0),
+            Value::Long(added_rows),
+            Value::Long(existing_rows),
+            Value::Long(0),
+        ]));
+    }
+
+    // manifest list
+    let parent_snapshot_id = parent.as_ref().map(|parent| parent.snapshot_id);
+    let manifest_list = write_container(
+        &manifest_list_schema().to_string(),
+        &[
+            ("snapshot-id", snapshot_id.to_string()),
+            (
+                "parent-snapshot-id",
+                parent_snapshot_id.map_or("null".to_string(), |id| id.to_string()),
+            ),
+            ("sequence-number", sequence_number.to_string()),
+            ("format-version", "2".to_string()),
+        ],
+        &manifests,
+    );
+    let manifest_list_path = Path::from_iter([
+        METADATA_FOLDER,
+        &format!("snap-{snapshot_id}-1-{commit_uuid}.avro"),
+    ]);
+    store.put(&manifest_list_path, manifest_list.into()).await?;
+
+    // table metadata
+    let removes_files = actions.map_or(true, |actions| {
+        actions
+            .iter()
+            .any(|action| matches!(action, Action::Remove(_)))
+    });
+    let operation = match parent {
+        Some(_) if removes_files => "overwrite",
+        _ => "append",
+    };
+    let timestamp_ms = Utc::now().timestamp_millis();
+    metadata.snapshots.push(Snapshot {
+        snapshot_id,
+        parent_snapshot_id,
+        sequence_number,
+        timestamp_ms,
+        manifest_list: format!("{location}/{manifest_list_path}"),
+        summary: HashMap::from([
+            ("operation".to_string(), operation.to_string()),
+            ("added-data-files".to_string(), added_files.to_string()),
+            ("added-records".to_string(), added_rows.to_string()),
+            (
+                "total-data-files".to_string(),
+                (added_files + existing_files).to_string(),
+            ),
+            (
+                "total-records".to_string(),
+                (added_rows + existing_rows).to_string(),
+            ),
+        ]),
+        schema_id,
+    });
+    metadata.snapshot_log.push(SnapshotLogEntry {
+        snapshot_id,
+        timestamp_ms,
+    });
+    if let Some((version, previous)) = &previous {
+        metadata.metadata_log.push(MetadataLogEntry {
+            metadata_file: format!("{location}/{}", metadata_path(*version)),
+            timestamp_ms: previous.last_updated_ms,
+        });
+    }
+    metadata.refs.insert(
+        "main".to_string(),
+        SnapshotReference {
+            snapshot_id,
+            ref_type: "branch".to_string(),
+        },
+    );
+    truncate_history(&mut metadata.snapshots);
+    truncate_history(&mut metadata.snapshot_log);
+    truncate_history(&mut metadata.metadata_log);
+    metadata.current_snapshot_id = Some(snapshot_id);
+    metadata.current_schema_id = schema_id;
+    metadata.default_spec_id = spec_id;
+    metadata.last_sequence_number = sequence_number;
+    metadata.last_updated_ms = timestamp_ms;
+    metadata.last_column_id = metadata.last_column_id.max(converter.last_column_id());
+    metadata.last_partition_id = metadata
+        .last_partition_id
+        .max(PARTITION_FIELD_ID_START - 1 + partition_fields.len() as i32);
+    metadata.properties.insert(
+        DELTA_VERSION_PROPERTY.to_string(),
+        snapshot.version().to_string(),
+    );
+    metadata.properties.insert(
+        NAME_MAPPING_PROPERTY.to_string(),
+        JsonValue::from(name_mapping).to_string(),
+    );
+
+    let version = previous.map_or(1, |(version, _)| version + 1);
+    let data = serde_json::to_vec(&metadata)?;
+    match store
+        .put_opts(
+            &metadata_path(version),
+            data.clone().into(),
+            PutMode::Create.into(),
+        )
+        .await
+    {
+        // stores without conditional puts can not detect concurrent writers
+        Err(ObjectStoreError::NotImplemented) => {
+            store.put(&metadata_path(version), data.into()).await?;
+        }
+        result => {
+            result?;
+        }
+    }
+    store
+        .put(
+            &Path::from_iter([METADATA_FOLDER, VERSION_HINT]),
+            version.to_string().into_bytes().into(),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Drop the oldest entries of a history beyond [MAX_HISTORY]
+fn truncate_history<T>(history: &mut Vec<T>) {
+    if history.len() > MAX_HISTORY {
+        history.drain(..history.len() - MAX_HISTORY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::MapType;
+    use crate::writer::test_utils::{get_delta_schema, get_record_batch};
+    use crate::{DeltaConfigKey, DeltaOps};
+    use itertools::Itertools;
+
+    async fn read_metadata(table: &DeltaTable) -> (i64, TableMetadata) {
+        read_latest_metadata(table.object_store().as_ref())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_convert_schema() {
+        let schema = StructType::new(vec![
+            StructField::new("id", DataType::LONG, false),
+            StructField::new(
+                "tags",
+                MapType::new(DataType::STRING, DataType::INTEGER, true),
+                true,
+            ),
+            StructField::new("name", DataType::STRING, true).with_metadata([
+                (
+                    ColumnMetadataKey::ColumnMappingId.as_ref(),
+                    MetadataValue::Number(7),
+                ),
+                (
+                    ColumnMetadataKey::ColumnMappingPhysicalName.as_ref(),
+                    MetadataValue::String("col-7".to_string()),
+                ),
+            ]),
+        ]);
+        let mut converter = SchemaConverter::new(&schema);
+        let (fields, name_mapping) = converter.struct_fields(schema.fields());
+
+        assert_eq!(
+            JsonValue::from(fields),
+            json!([
+                {"id": 8, "name": "id", "required": true, "type": "long"},
+                {"id": 9, "name": "tags", "required": false, "type": {
+                    "type": "map",
+                    "key-id": 10,
+                    "key": "string",
+                    "value-id": 11,
+                    "value": "int",
+                    "value-required": false,
+                }},
+                {"id": 7, "name": "name", "required": false, "type": "string"},
+            ])
+        );
+        assert_eq!(
+            name_mapping[2],
+            json!({"field-id": 7, "names": ["name", "col-7"]})
+        );
+        assert_eq!(converter.last_column_id(), 11);
+    }
+
+    #[test]
+    fn test_decimal_required_bytes() {
+        assert_eq!(decimal_required_bytes(1), 1);
+        assert_eq!(decimal_required_bytes(9), 4);
+        assert_eq!(decimal_required_bytes(10), 5);
+        assert_eq!(decimal_required_bytes(38), 16);
+    }
+
+    #[tokio::test]
+    async fn test_write_iceberg_metadata_on_commit() {
+        let table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_partition_columns(["modified"])
+            .with_configuration_property(
+                DeltaConfigKey::UniversalFormatEnabledFormats,
+                Some(ICEBERG_FORMAT),
+            )
+            .await
+            .unwrap();
+        let (version, metadata) = read_metadata(&table).await;
+        assert_eq!(version, 1);
+        assert_eq!(metadata.delta_version(), Some(0));
+        assert_eq!(metadata.partition_specs[0].fields[0].name, "modified");
+        assert_eq!(metadata.partition_specs[0].fields[0].source_id, 3);
+
+        let table = DeltaOps(table)
+            .write(vec![get_record_batch(None, false)])
+            .await
+            .unwrap();
+        let table = DeltaOps(table)
+            .write(vec![get_record_batch(None, false)])
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 2);
+
+        let (version, metadata) = read_metadata(&table).await;
+        assert_eq!(version, 3);
+        assert_eq!(metadata.delta_version(), Some(2));
+        assert_eq!(metadata.snapshots.len(), 3);
+        assert_eq!(metadata.current_snapshot_id, Some(3));
+        assert_eq!(metadata.last_sequence_number, 3);
+        assert_eq!(metadata.metadata_log.len(), 2);
+        assert_eq!(metadata.schemas.len(), 1);
+        assert_eq!(metadata.last_column_id, 3);
+
+        let current = metadata.current_snapshot().unwrap();
+        assert_eq!(current.summary["operation"], "append");
+        assert_eq!(current.summary["added-data-files"], "2");
+        assert_eq!(current.summary["total-data-files"], "4");
+        assert_eq!(current.summary["total-records"], "22");
+
+        let store = table.object_store();
+        let manifest_list = current
+            .manifest_list
+            .strip_prefix(&format!("{}/", metadata.location))
+            .unwrap();
+        let data = store
+            .get(&Path::from(manifest_list))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert!(data.starts_with(b"Obj\x01"));
+
+        // converting the same version again is a no-op
+        create_iceberg_metadata(&table).await.unwrap();
+        assert_eq!(read_metadata(&table).await.0, 3);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_iceberg_metadata() {
+        let table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_configuration_property(
+                DeltaConfigKey::UniversalFormatEnabledFormats,
+                Some(ICEBERG_FORMAT),
+            )
+            .await
+            .unwrap();
+
+        // another writer created the next metadata file, but did not update the hint yet
+        let store = table.object_store();
+        store
+            .copy(&metadata_path(1), &metadata_path(2))
+            .await
+            .unwrap();
+        let err = store
+            .put_opts(
+                &metadata_path(2),
+                Vec::<u8>::new().into(),
+                PutMode::Create.into(),
+            )
+            .await;
+        assert!(matches!(err, Err(ObjectStoreError::AlreadyExists { .. })));
+
+        let table = DeltaOps(table)
+            .write(vec![get_record_batch(None, false)])
+            .await
+            .unwrap();
+        let (version, metadata) = read_metadata(&table).await;
+        assert_eq!(version, 3);
+        assert_eq!(metadata.delta_version(), Some(1));
+        assert_eq!(metadata.current_snapshot_id, Some(2));
+        assert_eq!(metadata.metadata_log.len(), 1);
+    }
+
+    #[test]
+    fn test_truncate_history() {
+        let mut history = (0..MAX_HISTORY + 5).collect_vec();
+        truncate_history(&mut history);
+        assert_eq!(history.len(), MAX_HISTORY);
+        assert_eq!(history[0], 5);
+
+        let mut history = vec![1, 2];
+        truncate_history(&mut history);
+        assert_eq!(history, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_no_iceberg_metadata_without_uniform() {
+        let table = DeltaOps::new_in_memory()
+            .create()
+            .with_columns(get_delta_schema().fields().cloned())
+            .await
+            .unwrap();
+        let table = DeltaOps(table)
+            .write(vec![get_record_batch(None, false)])
+            .await
+            .unwrap();
+        let store = table.object_store();
+        assert!(read_latest_metadata(store.as_ref())
+            .await
+            .unwrap()
+            .is_none());
+
+        create_iceberg_metadata(&table).await.unwrap();
+        let (version, metadata) = read_metadata(&table).await;
+        assert_eq!(version, 1);
+        assert_eq!(metadata.delta_version(), Some(1));
+        let current = metadata.current_snapshot().unwrap();
+        assert_eq!(current.summary["added-records"], "11");
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Serialize, Serializer};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::ops::Bound;
 
 use crate::errors::DeltaTableError;
 use crate::kernel::{scalars::ScalarExt, DataType, PrimitiveType};
@@ -13,11 +14,19 @@ use crate::kernel::{scalars::ScalarExt, DataType, PrimitiveType};
 pub const NULL_PARTITION_VALUE_DATA_PATH: &str = "__HIVE_DEFAULT_PARTITION__";
 
 /// A Enum used for selecting the partition value operation when filtering a DeltaTable partition.
+///
+/// Values are compared as values of the partition column type, e.g. `02` equals `2` for an
+/// integer column. Null partition values, written as `__HIVE_DEFAULT_PARTITION__` in partition
+/// paths, only match [`IsNull`](PartitionValue::IsNull) or an equality with an empty string or
+/// `__HIVE_DEFAULT_PARTITION__`, and never match comparisons, as in SQL.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PartitionValue {
     /// The partition value with the equal operator
     Equal(String),
     /// The partition value with the not equal operator
+    ///
+    /// Follows SQL three-valued logic: a null partition is neither equal nor unequal to any
+    /// value, so `x != 'a'` does not match files where `x` is null.
     NotEqual(String),
     /// The partition value with the greater than operator
     GreaterThan(String),
@@ -30,11 +39,25 @@ pub enum PartitionValue {
     /// The partition values with the in operator
     In(Vec<String>),
     /// The partition values with the not in operator
+    ///
+    /// Like [`NotEqual`](PartitionValue::NotEqual), a null partition never matches, so
+    /// `x NOT IN ('a', 'b')` skips files where `x` is null.
     NotIn(Vec<String>),
+    /// The partition value within the range with the given lower and upper bounds
+    Range(Bound<String>, Bound<String>),
+    /// The partition value is null
+    IsNull,
+    /// The partition value is not null
+    IsNotNull,
+}
+
+/// Whether `value` represents a null partition value
+fn is_null_value(value: &str) -> bool {
+    value.is_empty() || value == NULL_PARTITION_VALUE_DATA_PATH
 }
 
 #[derive(Clone, Debug, PartialEq)]
-struct ScalarHelper<'a>(&'a Scalar);
+pub(crate) struct ScalarHelper<'a>(pub(crate) &'a Scalar);
 
 impl PartialOrd for ScalarHelper<'_> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -93,6 +116,35 @@ fn compare_typed_value(
     }
 }
 
+/// Whether the non-null `partition_value` equals `filter_value`, comparing them as values of
+/// `data_type` when the filter value can be parsed as such
+fn equals_typed_value(partition_value: &Scalar, filter_value: &str, data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Primitive(PrimitiveType::String) => partition_value.serialize() == filter_value,
+        _ => compare_typed_value(partition_value, filter_value, data_type)
+            .map(|x| x.is_eq())
+            .unwrap_or_else(|| partition_value.serialize() == filter_value),
+    }
+}
+
+/// Whether `partition_value` lies on the inner side of `bound`, given the comparison of the
+/// partition value with the bound value
+fn within_bound(
+    partition_value: &Scalar,
+    bound: &Bound<String>,
+    data_type: &DataType,
+    inner: Ordering,
+) -> bool {
+    match bound {
+        Bound::Unbounded => true,
+        Bound::Included(value) => compare_typed_value(partition_value, value, data_type)
+            .is_some_and(|x| x == inner || x.is_eq()),
+        Bound::Excluded(value) => {
+            compare_typed_value(partition_value, value, data_type).is_some_and(|x| x == inner)
+        }
+    }
+}
+
 /// Partition filters methods for filtering the DeltaTable partitions.
 impl PartitionFilter {
     /// Indicates if a DeltaTable partition matches with the partition filter by key and value.
@@ -100,28 +152,23 @@ impl PartitionFilter {
         if self.key != partition.key {
             return false;
         }
-        if self.value == PartitionValue::Equal("".to_string()) {
-            return partition.value.is_null();
+        if partition.value.is_null() {
+            return match &self.value {
+                PartitionValue::IsNull => true,
+                PartitionValue::Equal(value) => is_null_value(value),
+                PartitionValue::In(values) => values.iter().any(|value| is_null_value(value)),
+                _ => false,
+            };
         }
 
         match &self.value {
+            PartitionValue::IsNull => false,
+            PartitionValue::IsNotNull => true,
             PartitionValue::Equal(value) => {
-                if let DataType::Primitive(PrimitiveType::Timestamp) = data_type {
-                    compare_typed_value(&partition.value, value, data_type)
-                        .map(|x| x.is_eq())
-                        .unwrap_or(false)
-                } else {
-                    partition.value.serialize() == *value
-                }
+                !is_null_value(value) && equals_typed_value(&partition.value, value, data_type)
             }
             PartitionValue::NotEqual(value) => {
-                if let DataType::Primitive(PrimitiveType::Timestamp) = data_type {
-                    compare_typed_value(&partition.value, value, data_type)
-                        .map(|x| !x.is_eq())
-                        .unwrap_or(false)
-                } else {
-                    !(partition.value.serialize() == *value)
-                }
+                is_null_value(value) || !equals_typed_value(&partition.value, value, data_type)
             }
             PartitionValue::GreaterThan(value) => {
                 compare_typed_value(&partition.value, value, data_type)
@@ -143,8 +190,16 @@ impl PartitionFilter {
                     .map(|x| x.is_le())
                     .unwrap_or(false)
             }
-            PartitionValue::In(value) => value.contains(&partition.value.serialize()),
-            PartitionValue::NotIn(value) => !value.contains(&partition.value.serialize()),
+            PartitionValue::In(values) => values.iter().any(|value| {
+                !is_null_value(value) && equals_typed_value(&partition.value, value, data_type)
+            }),
+            PartitionValue::NotIn(values) => values.iter().all(|value| {
+                is_null_value(value) || !equals_typed_value(&partition.value, value, data_type)
+            }),
+            PartitionValue::Range(lower, upper) => {
+                within_bound(&partition.value, lower, data_type, Ordering::Greater)
+                    && within_bound(&partition.value, upper, data_type, Ordering::Less)
+            }
         }
     }
 
@@ -187,6 +242,28 @@ impl Serialize for PartitionFilter {
                     values.iter().map(|v| format!("'{}'", v)).collect();
                 format!("{} NOT IN ({})", self.key, quoted_values.join(", "))
             }
+            PartitionValue::Range(Bound::Included(lower), Bound::Included(upper)) => {
+                format!("{} BETWEEN '{}' AND '{}'", self.key, lower, upper)
+            }
+            PartitionValue::Range(lower, upper) => {
+                let lower = match lower {
+                    Bound::Included(value) => Some(format!("{} >= '{}'", self.key, value)),
+                    Bound::Excluded(value) => Some(format!("{} > '{}'", self.key, value)),
+                    Bound::Unbounded => None,
+                };
+                let upper = match upper {
+                    Bound::Included(value) => Some(format!("{} <= '{}'", self.key, value)),
+                    Bound::Excluded(value) => Some(format!("{} < '{}'", self.key, value)),
+                    Bound::Unbounded => None,
+                };
+                match (lower, upper) {
+                    (Some(lower), Some(upper)) => format!("{lower} AND {upper}"),
+                    (Some(bound), None) | (None, Some(bound)) => bound,
+                    (None, None) => format!("{} IS NOT NULL", self.key),
+                }
+            }
+            PartitionValue::IsNull => format!("{} IS NULL", self.key),
+            PartitionValue::IsNotNull => format!("{} IS NOT NULL", self.key),
         };
         serializer.serialize_str(&s)
     }
@@ -204,7 +281,7 @@ impl TryFrom<(&str, &str, &str)> for PartitionFilter {
                 key: key.to_owned(),
                 value: PartitionValue::Equal(value.to_owned()),
             }),
-            (key, "!=", value) if !key.is_empty() => Ok(PartitionFilter {
+            (key, "!=" | "<>", value) if !key.is_empty() => Ok(PartitionFilter {
                 key: key.to_owned(),
                 value: PartitionValue::NotEqual(value.to_owned()),
             }),
@@ -224,6 +301,26 @@ impl TryFrom<(&str, &str, &str)> for PartitionFilter {
                 key: key.to_owned(),
                 value: PartitionValue::LessThanOrEqual(value.to_owned()),
             }),
+            (key, op, value)
+                if !key.is_empty()
+                    && op.eq_ignore_ascii_case("is")
+                    && value.eq_ignore_ascii_case("null") =>
+            {
+                Ok(PartitionFilter {
+                    key: key.to_owned(),
+                    value: PartitionValue::IsNull,
+                })
+            }
+            (key, op, value)
+                if !key.is_empty()
+                    && op.eq_ignore_ascii_case("is not")
+                    && value.eq_ignore_ascii_case("null") =>
+            {
+                Ok(PartitionFilter {
+                    key: key.to_owned(),
+                    value: PartitionValue::IsNotNull,
+                })
+            }
             (_, _, _) => Err(DeltaTableError::InvalidPartitionFilter {
                 partition_filter: format!("{filter:?}"),
             }),
@@ -247,6 +344,13 @@ impl TryFrom<(&str, &str, &[&str])> for PartitionFilter {
                 key: key.to_owned(),
                 value: PartitionValue::NotIn(value.iter().map(|x| x.to_string()).collect()),
             }),
+            (key, "between", [lower, upper]) if !key.is_empty() => Ok(PartitionFilter {
+                key: key.to_owned(),
+                value: PartitionValue::Range(
+                    Bound::Included(lower.to_string()),
+                    Bound::Included(upper.to_string()),
+                ),
+            }),
             (_, _, _) => Err(DeltaTableError::InvalidPartitionFilter {
                 partition_filter: format!("{filter:?}"),
             }),
@@ -254,6 +358,171 @@ impl TryFrom<(&str, &str, &[&str])> for PartitionFilter {
     }
 }
 
+/// Token of a partition predicate
+#[derive(Clone, Debug, PartialEq)]
+enum PredicateToken {
+    Word(String),
+    Quoted(String),
+    Operator(String),
+    OpenParen,
+    CloseParen,
+    Comma,
+}
+
+impl PredicateToken {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, PredicateToken::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+}
+
+fn tokenize_predicate(predicate: &str) -> Option<Vec<PredicateToken>> {
+    let mut tokens = Vec::new();
+    let mut chars = predicate.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push(PredicateToken::OpenParen),
+            ')' => tokens.push(PredicateToken::CloseParen),
+            ',' => tokens.push(PredicateToken::Comma),
+            '\'' | '"' => {
+                // quotes are escaped by doubling them
+                let mut value = String::new();
+                loop {
+                    match chars.next()? {
+                        q if q == c && chars.peek() == Some(&c) => {
+                            chars.next();
+                            value.push(c);
+                        }
+                        q if q == c => break,
+                        other => value.push(other),
+                    }
+                }
+                tokens.push(if c == '"' {
+                    PredicateToken::Word(value)
+                } else {
+                    PredicateToken::Quoted(value)
+                });
+            }
+            '=' => tokens.push(PredicateToken::Operator("=".to_string())),
+            '!' | '<' | '>' => {
+                let mut op = c.to_string();
+                if let Some(next) = chars.next_if(|n| *n == '=' || (c == '<' && *n == '>')) {
+                    op.push(next);
+                }
+                if op == "!" {
+                    return None;
+                }
+                tokens.push(PredicateToken::Operator(if op == "<>" {
+                    "!=".to_string()
+                } else {
+                    op
+                }));
+            }
+            c => {
+                let mut word = c.to_string();
+                while let Some(next) = chars.next_if(|n| {
+                    !n.is_whitespace() && !matches!(n, '(' | ')' | ',' | '=' | '!' | '<' | '>')
+                }) {
+                    word.push(next);
+                }
+                tokens.push(PredicateToken::Word(word));
+            }
+        }
+    }
+    Some(tokens)
+}
+
+fn parse_predicate_term(
+    tokens: &mut impl Iterator<Item = PredicateToken>,
+) -> Option<PartitionFilter> {
+    let Some(PredicateToken::Word(key)) = tokens.next() else {
+        return None;
+    };
+    let value = |token: Option<PredicateToken>| match token? {
+        PredicateToken::Word(value) | PredicateToken::Quoted(value) => Some(value),
+        _ => None,
+    };
+    match tokens.next()? {
+        PredicateToken::Operator(op) => {
+            PartitionFilter::try_from((key.as_str(), op.as_str(), value(tokens.next())?.as_str()))
+                .ok()
+        }
+        token if token.is_keyword("is") => {
+            let value = match tokens.next()? {
+                token if token.is_keyword("null") => PartitionValue::IsNull,
+                token if token.is_keyword("not") && tokens.next()?.is_keyword("null") => {
+                    PartitionValue::IsNotNull
+                }
+                _ => return None,
+            };
+            Some(PartitionFilter { key, value })
+        }
+        token if token.is_keyword("between") => {
+            let lower = value(tokens.next())?;
+            if !tokens.next()?.is_keyword("and") {
+                return None;
+            }
+            let upper = value(tokens.next())?;
+            Some(PartitionFilter {
+                key,
+                value: PartitionValue::Range(Bound::Included(lower), Bound::Included(upper)),
+            })
+        }
+        token if token.is_keyword("in") || token.is_keyword("not") => {
+            let negated = token.is_keyword("not");
+            if negated && !tokens.next()?.is_keyword("in") {
+                return None;
+            }
+            if tokens.next()? != PredicateToken::OpenParen {
+                return None;
+            }
+            let mut values = vec![value(tokens.next())?];
+            loop {
+                match tokens.next()? {
+                    PredicateToken::Comma => values.push(value(tokens.next())?),
+                    PredicateToken::CloseParen => break,
+                    _ => return None,
+                }
+            }
+            Some(PartitionFilter {
+                key,
+                value: if negated {
+                    PartitionValue::NotIn(values)
+                } else {
+                    PartitionValue::In(values)
+                },
+            })
+        }
+        _ => None,
+    }
+}
+
+impl PartitionFilter {
+    /// Parse a SQL predicate on partition columns into the partition filters it is made of.
+    ///
+    /// The predicate is a conjunction of comparisons (`=`, `!=`, `<>`, `<`, `<=`, `>`, `>=`) of
+    /// a column with a value, `BETWEEN` ranges, `IN` and `NOT IN` lists and `IS [NOT] NULL`
+    /// checks, e.g.
+    /// `date = '2022-05-22' AND region IN ('eu', 'us')`. Values may be single quoted, column
+    /// names double quoted. This allows evaluating simple predicates without DataFusion.
+    pub fn from_predicate(predicate: &str) -> Result<Vec<PartitionFilter>, DeltaTableError> {
+        let invalid = || DeltaTableError::InvalidPartitionFilter {
+            partition_filter: predicate.to_string(),
+        };
+        let mut tokens = tokenize_predicate(predicate)
+            .ok_or_else(invalid)?
+            .into_iter();
+        let mut filters = vec![parse_predicate_term(&mut tokens).ok_or_else(invalid)?];
+        while let Some(token) = tokens.next() {
+            if !token.is_keyword("and") {
+                return Err(invalid());
+            }
+            filters.push(parse_predicate_term(&mut tokens).ok_or_else(invalid)?);
+        }
+        Ok(filters)
+    }
+}
+
 /// A Struct DeltaTablePartition used to represent a partition of a DeltaTable.
 #[derive(Clone, Debug, PartialEq)]
 pub struct DeltaTablePartition {
@@ -325,5 +594,157 @@ mod tests {
             .unwrap(),
             "date NOT IN ('2023-11-04', '2023-06-07')",
         );
+        check_json_serialize(
+            PartitionFilter::try_from((
+                "date",
+                "between",
+                vec!["2023-06-07", "2023-11-04"].as_slice(),
+            ))
+            .unwrap(),
+            "date BETWEEN '2023-06-07' AND '2023-11-04'",
+        );
+        check_json_serialize(
+            PartitionFilter {
+                key: "date".to_string(),
+                value: PartitionValue::Range(
+                    Bound::Excluded("2023-06-07".to_string()),
+                    Bound::Excluded("2023-11-04".to_string()),
+                ),
+            },
+            "date > '2023-06-07' AND date < '2023-11-04'",
+        );
+        check_json_serialize(
+            PartitionFilter::try_from(("date", "is not", "null")).unwrap(),
+            "date IS NOT NULL",
+        );
+    }
+
+    #[test]
+    fn test_match_partition() {
+        let int_type = DataType::Primitive(PrimitiveType::Integer);
+        let partition = |value: Scalar| DeltaTablePartition {
+            key: "x".to_string(),
+            value,
+        };
+        let filter = |value: PartitionValue| PartitionFilter {
+            key: "x".to_string(),
+            value,
+        };
+        let two = partition(Scalar::Integer(2));
+        let null = partition(Scalar::Null(DataType::INTEGER));
+
+        let cases = [
+            (PartitionValue::Equal("02".to_string()), true, false),
+            (PartitionValue::NotEqual("3".to_string()), true, false),
+            (PartitionValue::NotEqual("".to_string()), true, false),
+            (PartitionValue::LessThan("3".to_string()), true, false),
+            (
+                PartitionValue::In(vec!["1".into(), "2".into()]),
+                true,
+                false,
+            ),
+            (PartitionValue::In(vec!["1".into(), "".into()]), false, true),
+            (
+                PartitionValue::NotIn(vec!["1".into(), "3".into()]),
+                true,
+                false,
+            ),
+            (
+                PartitionValue::Range(Bound::Included("2".into()), Bound::Excluded("3".into())),
+                true,
+                false,
+            ),
+            (
+                PartitionValue::Range(Bound::Excluded("2".into()), Bound::Unbounded),
+                false,
+                false,
+            ),
+            (PartitionValue::Equal("".to_string()), false, true),
+            (
+                PartitionValue::Equal(NULL_PARTITION_VALUE_DATA_PATH.to_string()),
+                false,
+                true,
+            ),
+            (PartitionValue::IsNull, false, true),
+            (PartitionValue::IsNotNull, true, false),
+        ];
+        for (value, matches_two, matches_null) in cases {
+            let filter = filter(value);
+            assert_eq!(
+                filter.match_partition(&two, &int_type),
+                matches_two,
+                "{filter:?} on 2"
+            );
+            assert_eq!(
+                filter.match_partition(&null, &int_type),
+                matches_null,
+                "{filter:?} on null"
+            );
+        }
+    }
+
+    #[test]
+    fn test_negated_filters_skip_null_partitions() {
+        let string_type = DataType::Primitive(PrimitiveType::String);
+        let null = DeltaTablePartition {
+            key: "region".to_string(),
+            value: Scalar::Null(DataType::STRING),
+        };
+        let eu = DeltaTablePartition {
+            key: "region".to_string(),
+            value: Scalar::String("eu".to_string()),
+        };
+
+        let not_equal = PartitionFilter::try_from(("region", "!=", "us")).unwrap();
+        assert!(not_equal.match_partition(&eu, &string_type));
+        assert!(!not_equal.match_partition(&null, &string_type));
+
+        let not_in =
+            PartitionFilter::try_from(("region", "not in", vec!["us", "ap"].as_slice())).unwrap();
+        assert!(not_in.match_partition(&eu, &string_type));
+        assert!(!not_in.match_partition(&null, &string_type));
+
+        let is_null = PartitionFilter::try_from(("region", "is", "null")).unwrap();
+        assert!(is_null.match_partition(&null, &string_type));
+    }
+
+    #[test]
+    fn test_partition_filters_from_predicate() {
+        let filters = PartitionFilter::from_predicate(
+            "date >= '2022-05-22' and \"my col\" <> 'it''s' AND year=2022 \
+             AND region NOT IN ('eu', 'us') AND country is null AND city IS NOT NULL \
+             AND month BETWEEN 1 AND 3",
+        )
+        .unwrap();
+        assert_eq!(
+            filters,
+            vec![
+                PartitionFilter::try_from(("date", ">=", "2022-05-22")).unwrap(),
+                PartitionFilter::try_from(("my col", "!=", "it's")).unwrap(),
+                PartitionFilter::try_from(("year", "=", "2022")).unwrap(),
+                PartitionFilter::try_from(("region", "not in", vec!["eu", "us"].as_slice()))
+                    .unwrap(),
+                PartitionFilter::try_from(("country", "is", "null")).unwrap(),
+                PartitionFilter::try_from(("city", "is not", "null")).unwrap(),
+                PartitionFilter::try_from(("month", "between", vec!["1", "3"].as_slice())).unwrap(),
+            ]
+        );
+
+        for predicate in [
+            "",
+            "date",
+            "date = ",
+            "date = '2022",
+            "date = 1 OR date = 2",
+            "date IN ('a', 'b'",
+            "date IS NOT",
+            "date BETWEEN 1 OR 3",
+            "date ! 1",
+        ] {
+            assert!(
+                PartitionFilter::from_predicate(predicate).is_err(),
+                "{predicate} should not parse"
+            );
+        }
     }
 }
@@ -0,0 +1,642 @@
+//! Read-only object store backed by an archive file.
+//!
+//! Allows opening a table whose `_delta_log` (and optionally its data files) has been packed
+//! into a `.tar`, `.tar.gz`/`.tgz` or `.zip` file, e.g. to inspect log snapshots shared for
+//! debugging without first unpacking them into a bucket. The archive is loaded into memory when
+//! the store is created. If the archive contains a directory wrapping the table, the directory
+//! holding `_delta_log` is used as the table root.
+//!
+//! Tables are opened via `archive://` urls pointing at the archive on the local file system,
+//! once the handlers are registered with [register_handlers]. Such archives can be created from
+//! a loaded table with [export_snapshot].
+//!
+//! ```rust, no_run
+//! # async fn run() -> deltalake_core::DeltaResult<()> {
+//! use deltalake_core::storage::archive::{export_snapshot, register_handlers};
+//!
+//! let table = deltalake_core::open_table("s3://bucket/table").await?;
+//! export_snapshot(table.snapshot()?, table.log_store().as_ref(), "/tmp/snapshot.tar.gz").await?;
+//!
+//! register_handlers(None);
+//! let table = deltalake_core::open_table("archive:///tmp/snapshot.tar.gz").await?;
+//! # Ok(())
+//! # }
+//! ```
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::ops::Range;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::{Path, PathPart};
+use object_store::{
+    GetOptions, GetRange, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result as ObjectStoreResult,
+};
+use percent_encoding::percent_decode_str;
+use url::Url;
+
+use super::{factories, ObjectStoreFactory, ObjectStoreRef, StorageOptions};
+use crate::logstore::{default_logstore, logstore_factories, LogStore, LogStoreFactory};
+use crate::table::state::DeltaTableState;
+use crate::{DeltaResult, DeltaTableError, ObjectStoreError};
+
+const STORE_NAME: &str = "ArchiveObjectStore";
+
+/// Supported archive formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// Uncompressed tar archive
+    Tar,
+    /// Gzip compressed tar archive
+    TarGz,
+    /// Zip archive
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Infer the archive format from the extension of the archive file
+    pub fn from_path(path: &FsPath) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ArchiveEntry {
+    data: Bytes,
+    last_modified: DateTime<Utc>,
+}
+
+impl ArchiveEntry {
+    fn meta(&self, location: &Path) -> ObjectMeta {
+        ObjectMeta {
+            location: location.clone(),
+            last_modified: self.last_modified,
+            size: self.data.len(),
+            e_tag: None,
+            version: None,
+        }
+    }
+}
+
+/// Read-only [`ObjectStore`] serving the files contained in an archive
+#[derive(Debug)]
+pub struct ArchiveObjectStore {
+    archive: PathBuf,
+    entries: BTreeMap<Path, ArchiveEntry>,
+}
+
+impl std::fmt::Display for ArchiveObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ArchiveObjectStore({})", self.archive.display())
+    }
+}
+
+impl ArchiveObjectStore {
+    /// Load the archive at `path`, inferring its format from the file extension.
+    pub fn try_new(path: impl AsRef<FsPath>) -> DeltaResult<Self> {
+        let path = path.as_ref();
+        Self::try_new_with_format(path, infer_format(path)?)
+    }
+
+    /// Load the archive at `path` in the given format.
+    pub fn try_new_with_format(
+        path: impl AsRef<FsPath>,
+        format: ArchiveFormat,
+    ) -> DeltaResult<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|err| archive_error(path, err))?;
+        let files = match format {
+            ArchiveFormat::Tar => read_tar(BufReader::new(file)),
+            ArchiveFormat::TarGz => read_tar(flate2::read::GzDecoder::new(BufReader::new(file))),
+            ArchiveFormat::Zip => read_zip(file).map_err(std::io::Error::from),
+        }
+        .map_err(|err| archive_error(path, err))?;
+
+        let root = table_root(files.keys());
+        let entries = files
+            .into_iter()
+            .filter_map(|(location, entry)| {
+                let relative: Vec<PathPart> = location.prefix_match(&root)?.collect();
+                (!relative.is_empty()).then(|| (Path::from_iter(relative), entry))
+            })
+            .collect();
+
+        Ok(Self {
+            archive: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    fn entry(&self, location: &Path) -> ObjectStoreResult<&ArchiveEntry> {
+        self.entries
+            .get(location)
+            .ok_or_else(|| ObjectStoreError::NotFound {
+                path: location.to_string(),
+                source: format!("{location} is not contained in the archive").into(),
+            })
+    }
+
+    fn read_only<T>(&self) -> ObjectStoreResult<T> {
+        Err(ObjectStoreError::NotSupported {
+            source: format!("{self} is read-only").into(),
+        })
+    }
+}
+
+/// Export the slice of the `_delta_log` needed to reproduce `snapshot` into an archive.
+///
+/// The archive holds the checkpoint the snapshot was loaded from and all later commits up to the
+/// snapshot version, but no data files. It can be re-opened read-only via an `archive://` url,
+/// e.g. to share the metadata of a table when reporting issues with planning or file pruning.
+/// The format is inferred from the extension of `destination`.
+pub async fn export_snapshot(
+    snapshot: &DeltaTableState,
+    log_store: &dyn LogStore,
+    destination: impl AsRef<FsPath>,
+) -> DeltaResult<()> {
+    let destination = destination.as_ref();
+    let format = infer_format(destination)?;
+    let snapshot = snapshot.snapshot().snapshot();
+    let table_root = snapshot.table_root();
+    let store = log_store.object_store();
+
+    let mut files = Vec::new();
+    for meta in snapshot.log_files() {
+        let data = store.get(&meta.location).await?.bytes().await?;
+        let location = match meta.location.prefix_match(&table_root) {
+            Some(parts) => Path::from_iter(parts),
+            None => meta.location.clone(),
+        };
+        files.push((
+            location,
+            ArchiveEntry {
+                data,
+                last_modified: meta.last_modified,
+            },
+        ));
+    }
+    let path = destination.to_path_buf();
+    tokio::task::spawn_blocking(move || write_archive(&path, format, &files))
+        .await
+        .map_err(|err| archive_error(destination, err))?
+        .map_err(|err| archive_error(destination, err))
+}
+
+fn infer_format(path: &FsPath) -> DeltaResult<ArchiveFormat> {
+    ArchiveFormat::from_path(path).ok_or_else(|| {
+        DeltaTableError::Generic(format!(
+            "Unsupported archive format for {}, expected .tar, .tar.gz, .tgz or .zip",
+            path.display()
+        ))
+    })
+}
+
+fn archive_error(
+    path: &FsPath,
+    err: impl std::error::Error + Send + Sync + 'static,
+) -> DeltaTableError {
+    DeltaTableError::ObjectStore {
+        source: ObjectStoreError::Generic {
+            store: STORE_NAME,
+            source: format!("Failed to access archive {}: {err}", path.display()).into(),
+        },
+    }
+}
+
+/// Normalize a path as stored in an archive, dropping `.` and empty segments
+fn archive_path(name: &str) -> Path {
+    Path::from_iter(
+        name.split(['/', '\\'])
+            .filter(|part| !part.is_empty() && *part != "."),
+    )
+}
+
+/// The table root is the outermost directory in the archive containing a `_delta_log`
+fn table_root<'a>(locations: impl Iterator<Item = &'a Path>) -> Path {
+    locations
+        .filter_map(|location| {
+            let parts: Vec<_> = location.parts().collect();
+            let idx = parts
+                .iter()
+                .position(|part| part.as_ref() == "_delta_log")?;
+            Some(Path::from_iter(parts.into_iter().take(idx)))
+        })
+        .min_by_key(|root| root.parts().count())
+        .unwrap_or_default()
+}
+
+fn read_tar(reader: impl Read) -> std::io::Result<BTreeMap<Path, ArchiveEntry>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut files = BTreeMap::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let location = archive_path(&entry.path()?.to_string_lossy());
+        let last_modified = Utc
+            .timestamp_opt(entry.header().mtime()? as i64, 0)
+            .single()
+            .unwrap_or_default();
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut data)?;
+        files.insert(
+            location,
+            ArchiveEntry {
+                data: data.into(),
+                last_modified,
+            },
+        );
+    }
+    Ok(files)
+}
+
+fn read_zip(file: File) -> zip::result::ZipResult<BTreeMap<Path, ArchiveEntry>> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(file))?;
+    let mut files = BTreeMap::new();
+    for idx in 0..archive.len() {
+        let mut entry = archive.by_index(idx)?;
+        if !entry.is_file() {
+            continue;
+        }
+        let location = archive_path(entry.name());
+        let last_modified = entry
+            .last_modified()
+            .and_then(|ts| chrono::NaiveDateTime::try_from(ts).ok())
+            .map(|ts| ts.and_utc())
+            .unwrap_or_default();
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut data)?;
+        files.insert(
+            location,
+            ArchiveEntry {
+                data: data.into(),
+                last_modified,
+            },
+        );
+    }
+    Ok(files)
+}
+
+fn write_archive(
+    path: &FsPath,
+    format: ArchiveFormat,
+    files: &[(Path, ArchiveEntry)],
+) -> std::io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut file = match format {
+        ArchiveFormat::Tar => write_tar(file, files)?,
+        ArchiveFormat::TarGz => {
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            write_tar(encoder, files)?.finish()?
+        }
+        ArchiveFormat::Zip => write_zip(file, files).map_err(std::io::Error::from)?,
+    };
+    file.flush()
+}
+
+fn write_tar<W: Write>(writer: W, files: &[(Path, ArchiveEntry)]) -> std::io::Result<W> {
+    let mut builder = tar::Builder::new(writer);
+    for (location, entry) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(entry.data.len() as u64);
+        header.set_mtime(entry.last_modified.timestamp().max(0) as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, location.as_ref(), entry.data.as_ref())?;
+    }
+    builder.into_inner()
+}
+
+fn write_zip<W: Write + std::io::Seek>(
+    writer: W,
+    files: &[(Path, ArchiveEntry)],
+) -> zip::result::ZipResult<W> {
+    let mut archive = zip::ZipWriter::new(writer);
+    for (location, entry) in files {
+        let mut options = zip::write::SimpleFileOptions::default();
+        if let Ok(last_modified) = zip::DateTime::try_from(entry.last_modified.naive_utc()) {
+            options = options.last_modified_time(last_modified);
+        }
+        archive.start_file(location.as_ref(), options)?;
+        archive.write_all(&entry.data)?;
+    }
+    archive.finish()
+}
+
+/// Resolve a requested range against an object of `len` bytes
+fn resolve_range(range: &GetRange, len: usize) -> ObjectStoreResult<Range<usize>> {
+    match range {
+        GetRange::Bounded(r) if r.start < r.end && r.start < len => Ok(r.start..r.end.min(len)),
+        GetRange::Offset(offset) if *offset < len => Ok(*offset..len),
+        GetRange::Suffix(n) => Ok(len.saturating_sub(*n)..len),
+        _ => Err(ObjectStoreError::Generic {
+            store: STORE_NAME,
+            source: format!("Invalid range {range:?} for object of {len} bytes").into(),
+        }),
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for ArchiveObjectStore {
+    async fn put(&self, _location: &Path, _bytes: PutPayload) -> ObjectStoreResult<PutResult> {
+        self.read_only()
+    }
+
+    async fn put_opts(
+        &self,
+        _location: &Path,
+        _bytes: PutPayload,
+        _options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        self.read_only()
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        let entry = self.entry(location)?;
+        let meta = entry.meta(location);
+        let range = match &options.range {
+            Some(range) => resolve_range(range, entry.data.len())?,
+            None => 0..entry.data.len(),
+        };
+        let data = entry.data.slice(range.clone());
+        let stream = futures::stream::once(futures::future::ready(Ok(data)));
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream.boxed()),
+            meta,
+            range,
+            attributes: Default::default(),
+        })
+    }
+
+    async fn head(&self, location: &Path) -> ObjectStoreResult<ObjectMeta> {
+        Ok(self.entry(location)?.meta(location))
+    }
+
+    async fn delete(&self, _location: &Path) -> ObjectStoreResult<()> {
+        self.read_only()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        let prefix = prefix.cloned().unwrap_or_default();
+        let metas: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|(location, _)| location.prefix_matches(&prefix))
+            .map(|(location, entry)| Ok(entry.meta(location)))
+            .collect();
+        futures::stream::iter(metas).boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        let prefix = prefix.cloned().unwrap_or_default();
+        let mut common_prefixes = BTreeSet::new();
+        let mut objects = vec![];
+        for (location, entry) in self.entries.iter() {
+            let mut parts = match location.prefix_match(&prefix) {
+                Some(parts) => parts,
+                None => continue,
+            };
+            let child = match parts.next() {
+                Some(child) => child,
+                None => continue,
+            };
+            if parts.next().is_some() {
+                common_prefixes.insert(prefix.child(child));
+            } else {
+                objects.push(entry.meta(location));
+            }
+        }
+        Ok(ListResult {
+            common_prefixes: common_prefixes.into_iter().collect(),
+            objects,
+        })
+    }
+
+    async fn copy(&self, _from: &Path, _to: &Path) -> ObjectStoreResult<()> {
+        self.read_only()
+    }
+
+    async fn copy_if_not_exists(&self, _from: &Path, _to: &Path) -> ObjectStoreResult<()> {
+        self.read_only()
+    }
+
+    async fn put_multipart(&self, _location: &Path) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.read_only()
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        _location: &Path,
+        _options: PutMultipartOpts,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.read_only()
+    }
+}
+
+/// [ObjectStoreFactory] and [LogStoreFactory] for `archive://` urls
+#[derive(Clone, Debug, Default)]
+pub struct ArchiveFactory {}
+
+impl ObjectStoreFactory for ArchiveFactory {
+    fn parse_url_opts(
+        &self,
+        url: &Url,
+        _options: &StorageOptions,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let path = percent_decode_str(url.path())
+            .decode_utf8()
+            .map_err(|_| DeltaTableError::InvalidTableLocation(url.to_string()))?;
+        let store = ArchiveObjectStore::try_new(path.as_ref())?;
+        Ok((Arc::new(store), Path::default()))
+    }
+}
+
+impl LogStoreFactory for ArchiveFactory {
+    fn with_options(
+        &self,
+        store: ObjectStoreRef,
+        location: &Url,
+        options: &StorageOptions,
+    ) -> DeltaResult<Arc<dyn LogStore>> {
+        Ok(default_logstore(store, location, options))
+    }
+}
+
+/// Register the [ArchiveFactory] for `archive://` urls
+pub fn register_handlers(_additional_prefixes: Option<Url>) {
+    let factory = Arc::new(ArchiveFactory::default());
+    let url = Url::parse("archive://").unwrap();
+    factories().insert(url.clone(), factory.clone());
+    logstore_factories().insert(url, factory);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use std::io::Write;
+
+    fn write_tar(path: &FsPath, files: &[(&str, &str)]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mtime(1_700_000_000);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    #[test]
+    fn test_archive_format_from_path() {
+        for (name, format) in [
+            ("log.tar", Some(ArchiveFormat::Tar)),
+            ("log.TAR.GZ", Some(ArchiveFormat::TarGz)),
+            ("log.tgz", Some(ArchiveFormat::TarGz)),
+            ("log.zip", Some(ArchiveFormat::Zip)),
+            ("log.json", None),
+        ] {
+            assert_eq!(ArchiveFormat::from_path(FsPath::new(name)), format);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_tar_archive() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let archive = tmp_dir.path().join("snapshot.tar");
+        write_tar(
+            &archive,
+            &[
+                ("./table/_delta_log/00000000000000000000.json", "{}"),
+                ("./table/_delta_log/00000000000000000001.json", "{}"),
+                ("./table/part-00000.parquet", "data"),
+            ],
+        );
+
+        let store = ArchiveObjectStore::try_new(&archive).unwrap();
+        let listed: Vec<_> = store
+            .list(Some(&Path::from("_delta_log")))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].last_modified.timestamp(), 1_700_000_000);
+
+        let data = store
+            .get(&Path::from("part-00000.parquet"))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(data, Bytes::from("data"));
+        let range = store
+            .get_range(&Path::from("part-00000.parquet"), 1..3)
+            .await
+            .unwrap();
+        assert_eq!(range, Bytes::from("at"));
+
+        let listed = store.list_with_delimiter(None).await.unwrap();
+        assert_eq!(listed.common_prefixes, vec![Path::from("_delta_log")]);
+        assert_eq!(listed.objects.len(), 1);
+
+        let res = store.put(&Path::from("other"), Bytes::new().into()).await;
+        assert!(matches!(res, Err(ObjectStoreError::NotSupported { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_read_zip_archive() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let archive = tmp_dir.path().join("snapshot.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+        writer
+            .start_file(
+                "_delta_log/00000000000000000000.json",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(b"{}").unwrap();
+        writer.finish().unwrap();
+
+        let store = ArchiveObjectStore::try_new(&archive).unwrap();
+        let meta = store
+            .head(&Path::from("_delta_log/00000000000000000000.json"))
+            .await
+            .unwrap();
+        assert_eq!(meta.size, 2);
+    }
+
+    #[tokio::test]
+    async fn test_open_table_from_archive() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let archive = tmp_dir.path().join("simple_table.tar.gz");
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        ));
+        builder
+            .append_dir_all("simple_table", "../test/tests/data/simple_table")
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        register_handlers(None);
+        let table = crate::open_table(format!("archive://{}", archive.display()))
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_export_snapshot() {
+        let source = crate::open_table("../test/tests/data/latest_not_checkpointed")
+            .await
+            .unwrap();
+        let tmp_dir = tempfile::tempdir().unwrap();
+        register_handlers(None);
+
+        for name in ["bundle.tar", "bundle.tar.gz", "bundle.zip"] {
+            let archive = tmp_dir.path().join(name);
+            export_snapshot(
+                source.snapshot().unwrap(),
+                source.log_store().as_ref(),
+                &archive,
+            )
+            .await
+            .unwrap();
+
+            // only the checkpoint and the commits after it are exported
+            let store = ArchiveObjectStore::try_new(&archive).unwrap();
+            let exported: Vec<_> = store.list(None).try_collect().await.unwrap();
+            assert_eq!(exported.len(), 2);
+
+            let table = crate::open_table(format!("archive://{}", archive.display()))
+                .await
+                .unwrap();
+            assert_eq!(table.version(), source.version());
+            assert_eq!(
+                table.get_files_iter().unwrap().count(),
+                source.get_files_iter().unwrap().count()
+            );
+        }
+    }
+}
@@ -0,0 +1,734 @@
+//! Read-through cache for immutable files of remote tables
+//!
+//! Parquet files, i.e. data files and checkpoints, are never modified once written. Repeated
+//! queries over the same table thus read identical bytes, which [`CachingStore`] keeps in memory
+//! and optionally on local disk instead of downloading them again. Files are cached as a whole
+//! on the first read and keyed by their path and e-tag, both tiers evict the least recently
+//! used files once their size budget is exceeded. Files evicted from memory are moved to disk.
+//! Concurrent reads of a file which is not cached yet download it only once.
+//!
+//! The cache is enabled by setting [`storage_constants::OBJECT_STORE_CACHE_MEMORY_BYTES`] or
+//! [`storage_constants::OBJECT_STORE_CACHE_DISK_PATH`] in the storage options of a table.
+//!
+//! [`storage_constants::OBJECT_STORE_CACHE_MEMORY_BYTES`]: super::storage_constants::OBJECT_STORE_CACHE_MEMORY_BYTES
+//! [`storage_constants::OBJECT_STORE_CACHE_DISK_PATH`]: super::storage_constants::OBJECT_STORE_CACHE_DISK_PATH
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+use std::ops::Range;
+use std::path::PathBuf;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetRange, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult,
+};
+use parking_lot::Mutex;
+use tracing::debug;
+#[cfg(not(target_arch = "wasm32"))]
+use tracing::warn;
+
+use super::{storage_constants, ObjectStoreRef, ObjectStoreResult, StorageOptions};
+use crate::{DeltaResult, DeltaTableError};
+
+/// Default size budget of the disk tier, if only the cache directory is configured
+const DEFAULT_DISK_BYTES: usize = 10 * 1024 * 1024 * 1024;
+
+/// Maximum number of object metadata entries kept, including those of files too large to cache
+const MAX_CACHED_METAS: usize = 100_000;
+
+/// Size budgets and location of a [`CachingStore`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// Maximum number of bytes kept in memory
+    pub memory_bytes: usize,
+    /// Directory the disk tier is kept in, the disk tier is disabled if not set
+    pub disk_path: Option<PathBuf>,
+    /// Maximum number of bytes kept on disk
+    pub disk_bytes: usize,
+}
+
+impl CacheConfig {
+    /// Read the cache configuration from the storage options.
+    ///
+    /// Returns `None` if neither a memory budget nor a cache directory is configured.
+    pub fn from_options(options: &StorageOptions) -> DeltaResult<Option<Self>> {
+        let memory_bytes =
+            parse_bytes(options, storage_constants::OBJECT_STORE_CACHE_MEMORY_BYTES)?;
+        let disk_path = options
+            .0
+            .get(storage_constants::OBJECT_STORE_CACHE_DISK_PATH)
+            .map(PathBuf::from);
+        if memory_bytes.is_none() && disk_path.is_none() {
+            return Ok(None);
+        }
+        let disk_bytes = match disk_path {
+            Some(_) => parse_bytes(options, storage_constants::OBJECT_STORE_CACHE_DISK_BYTES)?
+                .unwrap_or(DEFAULT_DISK_BYTES),
+            None => 0,
+        };
+        Ok(Some(Self {
+            memory_bytes: memory_bytes.unwrap_or_default(),
+            disk_path,
+            disk_bytes,
+        }))
+    }
+
+    /// Files larger than this are not cached
+    fn max_file_size(&self) -> usize {
+        self.memory_bytes.max(self.disk_bytes)
+    }
+}
+
+fn parse_bytes(options: &StorageOptions, key: &str) -> DeltaResult<Option<usize>> {
+    options
+        .0
+        .get(key)
+        .map(|value| {
+            value.trim().parse().map_err(|_| {
+                DeltaTableError::Generic(format!(
+                    "Invalid value '{value}' for storage option {key}"
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Wrap the given [ObjectStore] in a [CachingStore] if a cache is configured
+///
+/// Reference [CacheConfig::from_options] for the recognized options.
+pub fn cache_store_handler<T: ObjectStore>(
+    store: T,
+    options: &StorageOptions,
+) -> DeltaResult<ObjectStoreRef> {
+    match CacheConfig::from_options(options)? {
+        Some(config) => Ok(Arc::new(CachingStore::try_new(store, config)?)),
+        None => Ok(Arc::new(store)),
+    }
+}
+
+/// Cached file, identified by its location and e-tag
+type CacheKey = (Path, Option<String>);
+
+/// Least recently used entries within a size budget
+#[derive(Debug)]
+struct Lru<K, V> {
+    entries: HashMap<K, (V, usize, u64)>,
+    order: BTreeMap<u64, K>,
+    size: usize,
+    capacity: usize,
+    tick: u64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Lru<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            size: 0,
+            capacity,
+            tick: 0,
+        }
+    }
+
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let (value, _, last_used) = self.entries.get_mut(key)?;
+        self.order.remove(last_used);
+        *last_used = self.tick;
+        self.order.insert(self.tick, key.clone());
+        Some(value.clone())
+    }
+
+    /// Insert the value, returning the entries evicted to stay within the budget
+    fn insert(&mut self, key: K, value: V, size: usize) -> Vec<(K, V)> {
+        let mut evicted = self.remove(&key).into_iter().collect::<Vec<_>>();
+        if size > self.capacity {
+            evicted.push((key, value));
+            return evicted;
+        }
+        while self.size + size > self.capacity {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some((value, size, _)) = self.entries.remove(&oldest) {
+                self.size -= size;
+                evicted.push((oldest, value));
+            }
+        }
+        self.tick += 1;
+        self.size += size;
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, (value, size, self.tick));
+        evicted
+    }
+
+    fn remove(&mut self, key: &K) -> Option<(K, V)> {
+        let (value, size, last_used) = self.entries.remove(key)?;
+        self.order.remove(&last_used);
+        self.size -= size;
+        Some((key.clone(), value))
+    }
+}
+
+impl<V: Clone> Lru<CacheKey, V> {
+    /// Remove all versions of the file at `location`
+    fn remove_location(&mut self, location: &Path) -> Vec<(CacheKey, V)> {
+        let keys = self
+            .entries
+            .keys()
+            .filter(|(path, _)| path == location)
+            .cloned()
+            .collect::<Vec<_>>();
+        keys.iter().filter_map(|key| self.remove(key)).collect()
+    }
+}
+
+/// Files cached in a directory on local disk
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+struct DiskCache {
+    dir: PathBuf,
+    files: Mutex<Lru<CacheKey, PathBuf>>,
+    next_file: AtomicU64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl DiskCache {
+    fn try_new(root: PathBuf, capacity: usize) -> DeltaResult<Self> {
+        // every store owns a directory, so stores sharing the root don't evict each other's files
+        let dir = root.join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            files: Mutex::new(Lru::new(capacity)),
+            next_file: AtomicU64::new(0),
+        })
+    }
+
+    async fn get(&self, key: &CacheKey) -> Option<Bytes> {
+        let file = self.files.lock().get(key)?;
+        match tokio::fs::read(&file).await {
+            Ok(data) => Some(data.into()),
+            Err(err) => {
+                warn!("Failed to read cached file {}: {err}", file.display());
+                self.files.lock().remove(key);
+                None
+            }
+        }
+    }
+
+    async fn insert(&self, key: CacheKey, data: Bytes) {
+        let file = self
+            .dir
+            .join(self.next_file.fetch_add(1, Ordering::Relaxed).to_string());
+        if let Err(err) = tokio::fs::write(&file, &data).await {
+            warn!("Failed to write cached file {}: {err}", file.display());
+            return;
+        }
+        let evicted = self.files.lock().insert(key, file, data.len());
+        Self::delete(evicted).await;
+    }
+
+    async fn remove_location(&self, location: &Path) {
+        let removed = self.files.lock().remove_location(location);
+        Self::delete(removed).await;
+    }
+
+    async fn delete(files: Vec<(CacheKey, PathBuf)>) {
+        for (_, file) in files {
+            if let Err(err) = tokio::fs::remove_file(&file).await {
+                debug!("Failed to delete cached file {}: {err}", file.display());
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for DiskCache {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Stand-in for the disk tier on targets without a local file system, e.g. WebAssembly
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug)]
+struct DiskCache;
+
+#[cfg(target_arch = "wasm32")]
+impl DiskCache {
+    fn try_new(root: PathBuf, _capacity: usize) -> DeltaResult<Self> {
+        Err(DeltaTableError::Generic(format!(
+            "Cannot cache files in {}, no local file system is available on this target",
+            root.display()
+        )))
+    }
+
+    async fn get(&self, _key: &CacheKey) -> Option<Bytes> {
+        None
+    }
+
+    async fn insert(&self, _key: CacheKey, _data: Bytes) {}
+
+    async fn remove_location(&self, _location: &Path) {}
+}
+
+/// Store caching parquet files read from the inner store in memory and on local disk
+#[derive(Debug)]
+pub struct CachingStore<T: ObjectStore> {
+    inner: T,
+    config: CacheConfig,
+    metas: Mutex<Lru<Path, ObjectMeta>>,
+    memory: Mutex<Lru<CacheKey, Bytes>>,
+    disk: Option<DiskCache>,
+    /// Locks of the files currently being downloaded, so concurrent misses wait for the download
+    downloads: Mutex<HashMap<Path, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl<T: ObjectStore> CachingStore<T> {
+    /// Create a new [`CachingStore`], creating the cache directory if configured
+    pub fn try_new(inner: T, config: CacheConfig) -> DeltaResult<Self> {
+        let disk = config
+            .disk_path
+            .clone()
+            .map(|path| DiskCache::try_new(path, config.disk_bytes))
+            .transpose()?;
+        Ok(Self {
+            inner,
+            memory: Mutex::new(Lru::new(config.memory_bytes)),
+            metas: Mutex::new(Lru::new(MAX_CACHED_METAS)),
+            disk,
+            downloads: Mutex::new(HashMap::new()),
+            config,
+        })
+    }
+
+    /// The configuration of the cache
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    fn is_cacheable(location: &Path) -> bool {
+        location.extension() == Some("parquet")
+    }
+
+    fn insert_meta(&self, meta: ObjectMeta) {
+        self.metas.lock().insert(meta.location.clone(), meta, 1);
+    }
+
+    /// Look up a cached file, promoting files found on disk to memory
+    async fn cached(&self, location: &Path) -> Option<(ObjectMeta, Bytes)> {
+        let meta = self.metas.lock().get(location)?;
+        let key = (location.clone(), meta.e_tag.clone());
+        let cached = self.memory.lock().get(&key);
+        if let Some(data) = cached {
+            return Some((meta, data));
+        }
+        let data = self.disk.as_ref()?.get(&key).await?;
+        self.insert_memory(key, data.clone()).await;
+        Some((meta, data))
+    }
+
+    async fn insert_memory(&self, key: CacheKey, data: Bytes) {
+        let size = data.len();
+        let evicted = self.memory.lock().insert(key, data, size);
+        if let Some(disk) = &self.disk {
+            for (key, data) in evicted {
+                disk.insert(key, data).await;
+            }
+        }
+    }
+
+    /// Read the file at `location` from the cache or download it into the cache.
+    ///
+    /// Returns `None` for files which are not cached.
+    async fn read(&self, location: &Path) -> ObjectStoreResult<Option<(ObjectMeta, Bytes)>> {
+        if !Self::is_cacheable(location) {
+            return Ok(None);
+        }
+        if let Some(cached) = self.cached(location).await {
+            return Ok(Some(cached));
+        }
+        let download = self
+            .downloads
+            .lock()
+            .entry(location.clone())
+            .or_default()
+            .clone();
+        let result = {
+            let _guard = download.lock().await;
+            self.download(location).await
+        };
+        let mut downloads = self.downloads.lock();
+        if downloads
+            .get(location)
+            .is_some_and(|current| Arc::ptr_eq(current, &download))
+        {
+            downloads.remove(location);
+        }
+        result
+    }
+
+    /// Download the file into the cache, unless a concurrent read did so in the meantime
+    async fn download(&self, location: &Path) -> ObjectStoreResult<Option<(ObjectMeta, Bytes)>> {
+        if let Some(cached) = self.cached(location).await {
+            return Ok(Some(cached));
+        }
+        let meta = self.metas.lock().get(location);
+        let meta = match meta {
+            Some(meta) => meta,
+            None => {
+                let meta = self.inner.head(location).await?;
+                self.insert_meta(meta.clone());
+                meta
+            }
+        };
+        if meta.size > self.config.max_file_size() {
+            return Ok(None);
+        }
+        let result = self.inner.get(location).await?;
+        let meta = result.meta.clone();
+        let data = result.bytes().await?;
+        debug!("caching {} bytes of {location}", data.len());
+        let key = (location.clone(), meta.e_tag.clone());
+        self.insert_meta(meta.clone());
+        if data.len() <= self.config.memory_bytes {
+            self.insert_memory(key, data.clone()).await;
+        } else if let Some(disk) = &self.disk {
+            disk.insert(key, data.clone()).await;
+        }
+        Ok(Some((meta, data)))
+    }
+
+    async fn invalidate(&self, location: &Path) {
+        if !Self::is_cacheable(location) {
+            return;
+        }
+        // the data may outlive its evicted meta, so it is removed in any case
+        self.metas.lock().remove(location);
+        self.memory.lock().remove_location(location);
+        if let Some(disk) = &self.disk {
+            disk.remove_location(location).await;
+        }
+    }
+}
+
+impl<T: ObjectStore> Display for CachingStore<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CachingStore({})", self.inner)
+    }
+}
+
+/// Whether the request can be answered from the cache
+fn is_plain_read(options: &GetOptions) -> bool {
+    options.if_match.is_none()
+        && options.if_none_match.is_none()
+        && options.if_modified_since.is_none()
+        && options.if_unmodified_since.is_none()
+        && options.version.is_none()
+        && !options.head
+}
+
+/// Resolve a requested range against an object of `len` bytes
+fn resolve_range(range: &GetRange, len: usize) -> Option<Range<usize>> {
+    match range {
+        GetRange::Bounded(r) if r.start < r.end && r.start < len => Some(r.start..r.end.min(len)),
+        GetRange::Offset(offset) if *offset < len => Some(*offset..len),
+        GetRange::Suffix(n) => Some(len.saturating_sub(*n)..len),
+        _ => None,
+    }
+}
+
+fn slice(data: &Bytes, range: &Range<usize>) -> Option<Bytes> {
+    (range.start <= range.end && range.end <= data.len()).then(|| data.slice(range.clone()))
+}
+
+#[async_trait::async_trait]
+impl<T: ObjectStore> ObjectStore for CachingStore<T> {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        self.invalidate(location).await;
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.invalidate(location).await;
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        if !is_plain_read(&options) {
+            return self.inner.get_opts(location, options).await;
+        }
+        let Some((meta, data)) = self.read(location).await? else {
+            return self.inner.get_opts(location, options).await;
+        };
+        let range = match &options.range {
+            Some(range) => match resolve_range(range, data.len()) {
+                Some(range) => range,
+                // let the inner store report invalid ranges
+                None => return self.inner.get_opts(location, options).await,
+            },
+            None => 0..data.len(),
+        };
+        let data = data.slice(range.clone());
+        let stream = futures::stream::once(futures::future::ready(Ok(data)));
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream.boxed()),
+            meta,
+            range,
+            attributes: Default::default(),
+        })
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> ObjectStoreResult<Bytes> {
+        if let Some((_, data)) = self.read(location).await? {
+            if let Some(data) = slice(&data, &range) {
+                return Ok(data);
+            }
+        }
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> ObjectStoreResult<Vec<Bytes>> {
+        if let Some((_, data)) = self.read(location).await? {
+            if let Some(data) = ranges
+                .iter()
+                .map(|range| slice(&data, range))
+                .collect::<Option<Vec<_>>>()
+            {
+                return Ok(data);
+            }
+        }
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> ObjectStoreResult<ObjectMeta> {
+        if let Some(meta) = self.metas.lock().get(location) {
+            return Ok(meta);
+        }
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> ObjectStoreResult<()> {
+        self.invalidate(location).await;
+        self.inner.delete(location).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'a, ObjectStoreResult<Path>> {
+        let locations = locations
+            .then(|location| async move {
+                if let Ok(location) = &location {
+                    self.invalidate(location).await;
+                }
+                location
+            })
+            .boxed();
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.invalidate(to).await;
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.invalidate(from).await;
+        self.invalidate(to).await;
+        self.inner.rename(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.invalidate(from).await;
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::storage::{InstrumentedStore, ObjectStoreMetrics, ObjectStoreOperation};
+
+    fn instrumented() -> (InstrumentedStore<InMemory>, Arc<ObjectStoreMetrics>) {
+        let metrics = Arc::new(ObjectStoreMetrics::new());
+        metrics.set_enabled(true);
+        (
+            InstrumentedStore::new(InMemory::new(), metrics.clone()),
+            metrics,
+        )
+    }
+
+    fn requests(metrics: &ObjectStoreMetrics, operation: ObjectStoreOperation) -> u64 {
+        metrics
+            .snapshot()
+            .get(&operation)
+            .map(|stats| stats.requests)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_cache_config_from_options() {
+        let options = StorageOptions(HashMap::new());
+        assert_eq!(CacheConfig::from_options(&options).unwrap(), None);
+
+        let options = StorageOptions(HashMap::from_iter(vec![
+            ("OBJECT_STORE_CACHE_MEMORY_BYTES".into(), "1024".into()),
+            ("OBJECT_STORE_CACHE_DISK_PATH".into(), "/tmp/cache".into()),
+        ]));
+        let expected = CacheConfig {
+            memory_bytes: 1024,
+            disk_path: Some("/tmp/cache".into()),
+            disk_bytes: DEFAULT_DISK_BYTES,
+        };
+        assert_eq!(CacheConfig::from_options(&options).unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let key = |name: &str| -> CacheKey { (Path::from(name), None) };
+        let mut lru = Lru::new(10);
+        assert!(lru.insert(key("a"), 1, 4).is_empty());
+        assert!(lru.insert(key("b"), 2, 4).is_empty());
+        assert_eq!(lru.get(&key("a")), Some(1));
+        assert_eq!(lru.insert(key("c"), 3, 4), vec![(key("b"), 2)]);
+        assert_eq!(lru.insert(key("d"), 4, 11), vec![(key("d"), 4)]);
+        assert_eq!(lru.get(&key("b")), None);
+        assert_eq!(lru.size, 8);
+    }
+
+    #[tokio::test]
+    async fn test_caching_store() {
+        let (inner, metrics) = instrumented();
+        let config = CacheConfig {
+            memory_bytes: 1024,
+            ..Default::default()
+        };
+        let store = CachingStore::try_new(inner, config).unwrap();
+        let data_file = Path::from("part-00000.parquet");
+        let commit = Path::from("_delta_log/00000000000000000000.json");
+        store.put(&data_file, "0123456789".into()).await.unwrap();
+        store.put(&commit, "{}".into()).await.unwrap();
+
+        for _ in 0..3 {
+            let data = store.get_range(&data_file, 2..5).await.unwrap();
+            assert_eq!(data, Bytes::from("234"));
+            let data = store.get(&data_file).await.unwrap().bytes().await.unwrap();
+            assert_eq!(data, Bytes::from("0123456789"));
+            store.get(&commit).await.unwrap();
+        }
+        // the data file is downloaded once, the commit on every read
+        assert_eq!(requests(&metrics, ObjectStoreOperation::Get), 4);
+        assert_eq!(store.head(&data_file).await.unwrap().size, 10);
+
+        // writes invalidate the cached file
+        store.put(&data_file, "abc".into()).await.unwrap();
+        let data = store.get(&data_file).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data, Bytes::from("abc"));
+        assert_eq!(requests(&metrics, ObjectStoreOperation::Get), 5);
+    }
+
+    #[tokio::test]
+    async fn test_large_and_concurrent_reads() {
+        let (inner, metrics) = instrumented();
+        let config = CacheConfig {
+            memory_bytes: 4,
+            ..Default::default()
+        };
+        let store = CachingStore::try_new(inner, config).unwrap();
+        let large_file = Path::from("part-00000.parquet");
+        let small_file = Path::from("part-00001.parquet");
+        store.put(&large_file, "0123456789".into()).await.unwrap();
+        store.put(&small_file, "0123".into()).await.unwrap();
+
+        // the size of files too large to cache is only requested once
+        for _ in 0..3 {
+            let data = store.get_range(&large_file, 2..5).await.unwrap();
+            assert_eq!(data, Bytes::from("234"));
+        }
+        assert_eq!(requests(&metrics, ObjectStoreOperation::Head), 1);
+        assert_eq!(requests(&metrics, ObjectStoreOperation::Get), 3);
+
+        let reads = (0..8).map(|_| store.get_range(&small_file, 0..2));
+        for data in futures::future::try_join_all(reads).await.unwrap() {
+            assert_eq!(data, Bytes::from("01"));
+        }
+        assert_eq!(requests(&metrics, ObjectStoreOperation::Head), 2);
+        assert_eq!(requests(&metrics, ObjectStoreOperation::Get), 4);
+        assert!(store.downloads.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disk_tier() {
+        let dir = tempfile::tempdir().unwrap();
+        let (inner, metrics) = instrumented();
+        let config = CacheConfig {
+            memory_bytes: 10,
+            disk_path: Some(dir.path().to_path_buf()),
+            disk_bytes: 100,
+        };
+        let store = CachingStore::try_new(inner, config).unwrap();
+        let files = (0..3)
+            .map(|i| Path::from(format!("part-0000{i}.parquet")))
+            .collect::<Vec<_>>();
+        for file in &files {
+            store.put(file, "0123456789".into()).await.unwrap();
+        }
+
+        for _ in 0..2 {
+            for file in &files {
+                let data = store.get(file).await.unwrap().bytes().await.unwrap();
+                assert_eq!(data.len(), 10);
+            }
+        }
+        // only one file fits in memory, the others are read back from disk
+        assert_eq!(requests(&metrics, ObjectStoreOperation::Get), 3);
+
+        drop(store);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
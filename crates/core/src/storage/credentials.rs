@@ -0,0 +1,120 @@
+//! User supplied credentials for object store backends
+//!
+//! Backends usually discover credentials from storage options or the environment. A
+//! [`CredentialProvider`] passed to
+//! [`DeltaTableBuilder::with_credential_provider`](crate::DeltaTableBuilder::with_credential_provider)
+//! takes precedence over these and is asked for a credential before every request, e.g. to
+//! use tokens issued by a secrets manager without implementing a custom object store.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::DeltaResult;
+
+/// A credential used to authenticate requests against a storage backend
+#[derive(Clone, PartialEq, Eq)]
+pub enum StorageCredential {
+    /// AWS access key, used to sign requests to S3
+    AwsAccessKey {
+        /// The access key id
+        key_id: String,
+        /// The secret access key
+        secret_key: String,
+        /// The session token of temporary credentials
+        token: Option<String>,
+    },
+    /// An OAuth bearer token, used for Azure and Google Cloud Storage
+    BearerToken(String),
+    /// The base64 encoded access key of an Azure storage account
+    AzureAccessKey(String),
+}
+
+impl StorageCredential {
+    /// Name of the credential variant, for use in error messages
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::AwsAccessKey { .. } => "aws_access_key",
+            Self::BearerToken(_) => "bearer_token",
+            Self::AzureAccessKey(_) => "azure_access_key",
+        }
+    }
+}
+
+impl Debug for StorageCredential {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Never print the secrets themselves
+        match self {
+            Self::AwsAccessKey { key_id, .. } => f
+                .debug_struct("AwsAccessKey")
+                .field("key_id", key_id)
+                .finish_non_exhaustive(),
+            Self::BearerToken(_) => f.write_str("BearerToken(..)"),
+            Self::AzureAccessKey(_) => f.write_str("AzureAccessKey(..)"),
+        }
+    }
+}
+
+/// Source of the credentials used by a storage backend
+///
+/// [`get_credential`](Self::get_credential) is called before every request to the backend,
+/// implementations are expected to cache credentials until they expire.
+#[async_trait]
+pub trait CredentialProvider: Debug + Send + Sync {
+    /// Return the credential to authenticate the next request with
+    async fn get_credential(&self) -> DeltaResult<StorageCredential>;
+}
+
+/// Sharable reference to a [`CredentialProvider`]
+pub type CredentialProviderRef = Arc<dyn CredentialProvider>;
+
+/// A [`CredentialProvider`] always returning the same credential
+#[derive(Debug, Clone)]
+pub struct StaticCredentialProvider {
+    credential: StorageCredential,
+}
+
+impl StaticCredentialProvider {
+    /// Create a new [`StaticCredentialProvider`]
+    pub fn new(credential: StorageCredential) -> Self {
+        Self { credential }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for StaticCredentialProvider {
+    async fn get_credential(&self) -> DeltaResult<StorageCredential> {
+        Ok(self.credential.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_hides_secrets() {
+        let credential = StorageCredential::AwsAccessKey {
+            key_id: "key".to_string(),
+            secret_key: "secret".to_string(),
+            token: Some("token".to_string()),
+        };
+        let debug = format!("{credential:?}");
+        assert!(debug.contains("key"));
+        assert!(!debug.contains("secret"));
+        assert!(!debug.contains("token"));
+
+        let debug = format!("{:?}", StorageCredential::BearerToken("secret".to_string()));
+        assert!(!debug.contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_static_provider() {
+        let provider = StaticCredentialProvider::new(StorageCredential::BearerToken("t".into()));
+        assert_eq!(
+            provider.get_credential().await.unwrap(),
+            StorageCredential::BearerToken("t".into())
+        );
+    }
+}
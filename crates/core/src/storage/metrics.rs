@@ -0,0 +1,560 @@
+//! Instrumentation of object store requests
+//!
+//! Every object store created for a table location is wrapped in an [`InstrumentedStore`],
+//! which records the requests issued by the log store and by scans of the table in the process
+//! wide [`object_store_metrics`]. Instrumentation is disabled by default and can be toggled at
+//! runtime, while disabled requests are passed to the inner store as is.
+//!
+//! ```rust
+//! # use deltalake_core::storage::{object_store_metrics, ObjectStoreOperation};
+//! let metrics = object_store_metrics();
+//! metrics.set_enabled(true);
+//! // load a table ...
+//! for (operation, stats) in metrics.snapshot() {
+//!     println!("{operation}: {} requests, p99 {:?}", stats.requests, stats.p99_latency);
+//! }
+//! ```
+//!
+//! While enabled, each request is also executed within a `object_store` [`tracing`] span.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::task::Poll;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::stream::{self, BoxStream, StreamExt};
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts,
+    PutOptions, PutPayload, PutResult,
+};
+use tracing::{debug_span, Instrument};
+
+use super::ObjectStoreResult;
+
+const LATENCY_BUCKETS: usize = 40;
+
+/// Kind of request issued to an object store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ObjectStoreOperation {
+    /// Reading an object or a range of it
+    Get,
+    /// Reading the metadata of an object
+    Head,
+    /// Writing an object
+    Put,
+    /// Listing objects
+    List,
+    /// Deleting an object
+    Delete,
+    /// Copying an object
+    Copy,
+    /// Renaming an object
+    Rename,
+}
+
+impl ObjectStoreOperation {
+    const ALL: [Self; 7] = [
+        Self::Get,
+        Self::Head,
+        Self::Put,
+        Self::List,
+        Self::Delete,
+        Self::Copy,
+        Self::Rename,
+    ];
+
+    /// Name of the operation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "get",
+            Self::Head => "head",
+            Self::Put => "put",
+            Self::List => "list",
+            Self::Delete => "delete",
+            Self::Copy => "copy",
+            Self::Rename => "rename",
+        }
+    }
+}
+
+impl Display for ObjectStoreOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Aggregated statistics of the requests of one [`ObjectStoreOperation`]
+///
+/// Latency percentiles are approximated by power of two buckets and report the upper bound of
+/// the bucket containing the percentile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperationStats {
+    /// Number of requests
+    pub requests: u64,
+    /// Number of failed requests
+    pub errors: u64,
+    /// Number of bytes read or written
+    pub bytes: u64,
+    /// Summed up latency of all requests
+    pub total_latency: Duration,
+    /// Median latency
+    pub p50_latency: Duration,
+    /// 90th percentile latency
+    pub p90_latency: Duration,
+    /// 99th percentile latency
+    pub p99_latency: Duration,
+}
+
+#[derive(Debug)]
+struct OperationMetrics {
+    errors: AtomicU64,
+    bytes: AtomicU64,
+    total_latency_micros: AtomicU64,
+    // bucket `i` counts latencies below 2^i microseconds
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS],
+}
+
+impl Default for OperationMetrics {
+    fn default() -> Self {
+        Self {
+            errors: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            total_latency_micros: AtomicU64::new(0),
+            latency_buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+}
+
+impl OperationMetrics {
+    fn record(&self, latency: Duration, bytes: u64, failed: bool) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        let bucket = ((u64::BITS - micros.leading_zeros()) as usize).min(LATENCY_BUCKETS - 1);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.total_latency_micros
+            .fetch_add(micros, Ordering::Relaxed);
+        self.latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        if failed {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn stats(&self) -> OperationStats {
+        let buckets = self
+            .latency_buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect::<Vec<_>>();
+        let requests = buckets.iter().sum();
+        if requests == 0 {
+            return OperationStats::default();
+        }
+        let total_latency_micros = self.total_latency_micros.load(Ordering::Relaxed);
+        OperationStats {
+            requests,
+            errors: self.errors.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            total_latency: Duration::from_micros(total_latency_micros),
+            p50_latency: percentile(&buckets, requests, 0.5),
+            p90_latency: percentile(&buckets, requests, 0.9),
+            p99_latency: percentile(&buckets, requests, 0.99),
+        }
+    }
+
+    fn reset(&self) {
+        self.errors.store(0, Ordering::Relaxed);
+        self.bytes.store(0, Ordering::Relaxed);
+        self.total_latency_micros.store(0, Ordering::Relaxed);
+        for bucket in &self.latency_buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+fn percentile(buckets: &[u64], requests: u64, percentile: f64) -> Duration {
+    let target = ((requests as f64) * percentile).ceil().max(1.0) as u64;
+    let mut seen = 0;
+    for (bucket, count) in buckets.iter().enumerate() {
+        seen += count;
+        if seen >= target {
+            return Duration::from_micros(1 << bucket);
+        }
+    }
+    Duration::from_micros(1 << (LATENCY_BUCKETS - 1))
+}
+
+/// Counters of the requests issued to instrumented object stores
+#[derive(Debug, Default)]
+pub struct ObjectStoreMetrics {
+    enabled: AtomicBool,
+    operations: [OperationMetrics; ObjectStoreOperation::ALL.len()],
+}
+
+impl ObjectStoreMetrics {
+    /// Create new, disabled metrics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether requests are currently recorded
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Start or stop recording requests
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Record a finished request
+    pub fn record(
+        &self,
+        operation: ObjectStoreOperation,
+        latency: Duration,
+        bytes: u64,
+        failed: bool,
+    ) {
+        self.operations[operation as usize].record(latency, bytes, failed);
+    }
+
+    /// Statistics of the operations recorded so far, operations without requests are omitted
+    pub fn snapshot(&self) -> BTreeMap<ObjectStoreOperation, OperationStats> {
+        ObjectStoreOperation::ALL
+            .into_iter()
+            .map(|operation| (operation, self.operations[operation as usize].stats()))
+            .filter(|(_, stats)| stats.requests > 0)
+            .collect()
+    }
+
+    /// Clear all recorded requests
+    pub fn reset(&self) {
+        for operation in &self.operations {
+            operation.reset();
+        }
+    }
+}
+
+/// The process wide metrics recorded by the object stores of all tables
+pub fn object_store_metrics() -> Arc<ObjectStoreMetrics> {
+    static METRICS: OnceLock<Arc<ObjectStoreMetrics>> = OnceLock::new();
+    METRICS.get_or_init(Default::default).clone()
+}
+
+/// Store recording the requests issued to the inner store in [`ObjectStoreMetrics`]
+///
+/// The latency of reads covers the time until the response started, streaming the body is not
+/// included. Listings are recorded when the stream is exhausted.
+#[derive(Debug)]
+pub struct InstrumentedStore<T: ObjectStore> {
+    inner: T,
+    metrics: Arc<ObjectStoreMetrics>,
+}
+
+impl<T: ObjectStore> InstrumentedStore<T> {
+    /// Create a new [`InstrumentedStore`] recording requests in `metrics`
+    pub fn new(inner: T, metrics: Arc<ObjectStoreMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+
+    /// The metrics the requests are recorded in
+    pub fn metrics(&self) -> &Arc<ObjectStoreMetrics> {
+        &self.metrics
+    }
+
+    async fn instrument<R, Fut>(
+        &self,
+        operation: ObjectStoreOperation,
+        location: &Path,
+        bytes: impl FnOnce(&R) -> u64,
+        request: Fut,
+    ) -> ObjectStoreResult<R>
+    where
+        Fut: Future<Output = ObjectStoreResult<R>>,
+    {
+        if !self.metrics.is_enabled() {
+            return request.await;
+        }
+        let span = debug_span!("object_store", operation = operation.as_str(), %location);
+        let start = Instant::now();
+        let result = request.instrument(span).await;
+        let bytes = result.as_ref().map(bytes).unwrap_or_default();
+        let failed = result.is_err();
+        self.metrics
+            .record(operation, start.elapsed(), bytes, failed);
+        result
+    }
+
+    fn instrument_list<'a>(
+        &self,
+        prefix: Option<&Path>,
+        list: BoxStream<'a, ObjectStoreResult<ObjectMeta>>,
+    ) -> BoxStream<'a, ObjectStoreResult<ObjectMeta>> {
+        if !self.metrics.is_enabled() {
+            return list;
+        }
+        // the span is closed once the listing is exhausted
+        let mut span = Some(debug_span!("object_store", operation = "list", location = ?prefix));
+        let start = Instant::now();
+        let metrics = self.metrics.clone();
+        let failed = Arc::new(AtomicBool::new(false));
+        let failed_item = failed.clone();
+        list.inspect(move |result| {
+            if result.is_err() {
+                failed_item.store(true, Ordering::Relaxed);
+            }
+        })
+        .chain(stream::poll_fn(move |_| {
+            if span.take().is_some() {
+                let failed = failed.load(Ordering::Relaxed);
+                metrics.record(ObjectStoreOperation::List, start.elapsed(), 0, failed);
+            }
+            Poll::Ready(None)
+        }))
+        .boxed()
+    }
+}
+
+impl<T: ObjectStore> Display for InstrumentedStore<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "InstrumentedStore({})", self.inner)
+    }
+}
+
+fn ranges_len(ranges: &[Range<usize>]) -> u64 {
+    ranges.iter().map(|range| range.len() as u64).sum()
+}
+
+#[async_trait::async_trait]
+impl<T: ObjectStore> ObjectStore for InstrumentedStore<T> {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        let bytes = payload.content_length() as u64;
+        self.instrument(
+            ObjectStoreOperation::Put,
+            location,
+            |_| bytes,
+            self.inner.put_opts(location, payload, opts),
+        )
+        .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.instrument(
+            ObjectStoreOperation::Put,
+            location,
+            |_| 0,
+            self.inner.put_multipart_opts(location, opts),
+        )
+        .await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        let operation = if options.head {
+            ObjectStoreOperation::Head
+        } else {
+            ObjectStoreOperation::Get
+        };
+        self.instrument(
+            operation,
+            location,
+            |result: &GetResult| result.range.len() as u64,
+            self.inner.get_opts(location, options),
+        )
+        .await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> ObjectStoreResult<Bytes> {
+        self.instrument(
+            ObjectStoreOperation::Get,
+            location,
+            |bytes: &Bytes| bytes.len() as u64,
+            self.inner.get_range(location, range),
+        )
+        .await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> ObjectStoreResult<Vec<Bytes>> {
+        self.instrument(
+            ObjectStoreOperation::Get,
+            location,
+            |_| ranges_len(ranges),
+            self.inner.get_ranges(location, ranges),
+        )
+        .await
+    }
+
+    async fn head(&self, location: &Path) -> ObjectStoreResult<ObjectMeta> {
+        self.instrument(
+            ObjectStoreOperation::Head,
+            location,
+            |_| 0,
+            self.inner.head(location),
+        )
+        .await
+    }
+
+    async fn delete(&self, location: &Path) -> ObjectStoreResult<()> {
+        self.instrument(
+            ObjectStoreOperation::Delete,
+            location,
+            |_| 0,
+            self.inner.delete(location),
+        )
+        .await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'a, ObjectStoreResult<Path>> {
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        self.instrument_list(prefix, self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        self.instrument_list(prefix, self.inner.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        let root = Path::default();
+        self.instrument(
+            ObjectStoreOperation::List,
+            prefix.unwrap_or(&root),
+            |_| 0,
+            self.inner.list_with_delimiter(prefix),
+        )
+        .await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.instrument(
+            ObjectStoreOperation::Copy,
+            from,
+            |_| 0,
+            self.inner.copy(from, to),
+        )
+        .await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.instrument(
+            ObjectStoreOperation::Copy,
+            from,
+            |_| 0,
+            self.inner.copy_if_not_exists(from, to),
+        )
+        .await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.instrument(
+            ObjectStoreOperation::Rename,
+            from,
+            |_| 0,
+            self.inner.rename(from, to),
+        )
+        .await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.instrument(
+            ObjectStoreOperation::Rename,
+            from,
+            |_| 0,
+            self.inner.rename_if_not_exists(from, to),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_instrumented_store() {
+        let metrics = Arc::new(ObjectStoreMetrics::new());
+        let store = InstrumentedStore::new(InMemory::new(), metrics.clone());
+        let location = Path::from("_delta_log/00000000000000000000.json");
+
+        // nothing is recorded while disabled
+        store.put(&location, "commit".into()).await.unwrap();
+        assert!(metrics.snapshot().is_empty());
+
+        metrics.set_enabled(true);
+        store.put(&location, "commit".into()).await.unwrap();
+        store.get(&location).await.unwrap().bytes().await.unwrap();
+        store.get_range(&location, 0..3).await.unwrap();
+        store.head(&location).await.unwrap();
+        assert!(store.head(&Path::from("missing")).await.is_err());
+        let files = store.list(None).try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(files.len(), 1);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(
+            snapshot.keys().copied().collect::<Vec<_>>(),
+            vec![
+                ObjectStoreOperation::Get,
+                ObjectStoreOperation::Head,
+                ObjectStoreOperation::Put,
+                ObjectStoreOperation::List,
+            ]
+        );
+        let get = &snapshot[&ObjectStoreOperation::Get];
+        assert_eq!((get.requests, get.errors, get.bytes), (2, 0, 9));
+        assert!(get.p50_latency <= get.p99_latency);
+        let head = &snapshot[&ObjectStoreOperation::Head];
+        assert_eq!((head.requests, head.errors), (2, 1));
+        assert_eq!(snapshot[&ObjectStoreOperation::Put].bytes, 6);
+        assert_eq!(snapshot[&ObjectStoreOperation::List].requests, 1);
+
+        metrics.reset();
+        assert!(metrics.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let metrics = ObjectStoreMetrics::new();
+        for millis in 1..=100 {
+            metrics.record(
+                ObjectStoreOperation::Get,
+                Duration::from_millis(millis),
+                0,
+                false,
+            );
+        }
+        let stats = &metrics.snapshot()[&ObjectStoreOperation::Get];
+        assert_eq!(stats.requests, 100);
+        assert!(stats.p50_latency >= Duration::from_millis(50));
+        assert!(stats.p50_latency < Duration::from_millis(100));
+        assert!(stats.p99_latency >= Duration::from_millis(99));
+        assert!(stats.p99_latency < Duration::from_millis(200));
+    }
+}
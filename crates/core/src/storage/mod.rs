@@ -9,13 +9,29 @@ use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+#[cfg(feature = "archive")]
+pub mod archive;
+pub mod cache;
+pub mod credentials;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod file;
+pub mod metrics;
 pub mod retry_ext;
+pub mod retry_store;
 pub mod utils;
 
 use crate::{DeltaResult, DeltaTableError};
 
+pub use cache::{cache_store_handler, CacheConfig, CachingStore};
+pub use credentials::{
+    CredentialProvider, CredentialProviderRef, StaticCredentialProvider, StorageCredential,
+};
+pub use metrics::{
+    object_store_metrics, InstrumentedStore, ObjectStoreMetrics, ObjectStoreOperation,
+    OperationStats,
+};
 pub use object_store;
+#[cfg(not(target_arch = "wasm32"))]
 use object_store::local::LocalFileSystem;
 use object_store::memory::InMemory;
 pub use object_store::path::{Path, DELIMITER};
@@ -25,6 +41,7 @@ pub use object_store::{
     ObjectStore, Result as ObjectStoreResult,
 };
 pub use retry_ext::ObjectStoreRetryExt;
+pub use retry_store::{retry_store_handler, RetryConfig, RetryStore};
 pub use utils::*;
 
 lazy_static! {
@@ -42,6 +59,23 @@ pub trait ObjectStoreFactory: Send + Sync {
         url: &Url,
         options: &StorageOptions,
     ) -> DeltaResult<(ObjectStoreRef, Path)>;
+
+    /// Create a store authenticating with the credentials of the given provider instead of
+    /// the ones configured in `options` or the environment.
+    ///
+    /// Factories not supporting custom credentials return an error.
+    fn parse_url_opts_with_credentials(
+        &self,
+        url: &Url,
+        options: &StorageOptions,
+        credentials: CredentialProviderRef,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let _ = (options, credentials);
+        Err(DeltaTableError::Generic(format!(
+            "Custom credential providers are not supported for '{}' urls",
+            url.scheme()
+        )))
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -57,19 +91,33 @@ impl ObjectStoreFactory for DefaultObjectStoreFactory {
             "memory" => {
                 let path = Path::from_url_path(url.path())?;
                 let inner = Arc::new(InMemory::new()) as ObjectStoreRef;
-                let store = limit_store_handler(url_prefix_handler(inner, path.clone()), options);
+                let store = retry_store_handler(
+                    limit_store_handler(url_prefix_handler(inner, path.clone()), options),
+                    options,
+                )?;
                 Ok((store, path))
             }
+            #[cfg(not(target_arch = "wasm32"))]
             "file" => {
                 let inner = Arc::new(LocalFileSystem::new_with_prefix(
                     url.to_file_path().unwrap(),
                 )?) as ObjectStoreRef;
-                let store = limit_store_handler(inner, options);
+                let store = retry_store_handler(limit_store_handler(inner, options), options)?;
                 Ok((store, Path::from("/")))
             }
             _ => Err(DeltaTableError::InvalidTableLocation(url.clone().into())),
         }
     }
+
+    fn parse_url_opts_with_credentials(
+        &self,
+        url: &Url,
+        options: &StorageOptions,
+        _credentials: CredentialProviderRef,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        // local and in-memory stores do not authenticate
+        self.parse_url_opts(url, options)
+    }
 }
 
 /// TODO
@@ -187,6 +235,26 @@ pub mod storage_constants {
     /// The number of concurrent connections the underlying object store can create
     /// Reference [LimitStore](https://docs.rs/object_store/latest/object_store/limit/struct.LimitStore.html) for more information
     pub const OBJECT_STORE_CONCURRENCY_LIMIT: &str = "OBJECT_STORE_CONCURRENCY_LIMIT";
+
+    /// The maximum number of times a failed object store operation is retried.
+    /// Retries are only enabled if this option is set, reference [RetryStore](super::RetryStore) for more information
+    pub const OBJECT_STORE_MAX_RETRIES: &str = "OBJECT_STORE_MAX_RETRIES";
+    /// The backoff in milliseconds before the first retry, doubled for every following retry
+    pub const OBJECT_STORE_RETRY_INIT_BACKOFF_MS: &str = "OBJECT_STORE_RETRY_INIT_BACKOFF_MS";
+    /// The maximum backoff in milliseconds between two retries
+    pub const OBJECT_STORE_RETRY_MAX_BACKOFF_MS: &str = "OBJECT_STORE_RETRY_MAX_BACKOFF_MS";
+    /// The time in seconds after which an operation, including all its retries, is cancelled
+    pub const OBJECT_STORE_RETRY_TIMEOUT_SECONDS: &str = "OBJECT_STORE_RETRY_TIMEOUT_SECONDS";
+    /// Comma separated list of HTTP status codes which are retried, e.g. `429,500,502,503,504`
+    pub const OBJECT_STORE_RETRYABLE_STATUS_CODES: &str = "OBJECT_STORE_RETRYABLE_STATUS_CODES";
+
+    /// The number of bytes of parquet files cached in memory.
+    /// Reference [CachingStore](super::CachingStore) for more information
+    pub const OBJECT_STORE_CACHE_MEMORY_BYTES: &str = "OBJECT_STORE_CACHE_MEMORY_BYTES";
+    /// Local directory parquet files are cached in once evicted from memory
+    pub const OBJECT_STORE_CACHE_DISK_PATH: &str = "OBJECT_STORE_CACHE_DISK_PATH";
+    /// The number of bytes of parquet files cached on disk, 10 GiB by default
+    pub const OBJECT_STORE_CACHE_DISK_BYTES: &str = "OBJECT_STORE_CACHE_DISK_BYTES";
 }
 
 #[cfg(test)]
@@ -0,0 +1,681 @@
+//! Object store wrapper retrying transient failures of idempotent operations
+//!
+//! The HTTP clients of the cloud backends only retry individual requests and give up on errors
+//! surfacing after the response started, e.g. throttling while streaming a listing or
+//! `503 Slow Down` responses once their own budget is exhausted. [`RetryStore`] adds a retry
+//! policy on the level of [`ObjectStore`] operations which applies uniformly to all backends.
+//!
+//! Operations with preconditions, i.e. conditional puts, `copy_if_not_exists` and
+//! `rename_if_not_exists`, are never retried. A retried request may have succeeded on the server
+//! before, in which case the precondition would fail and a successful commit would be reported
+//! as a conflict. Renames are not retried either, as a retry of a rename which succeeded on the
+//! server fails with the source being gone.
+//!
+//! Listings are retried until their first item arrived. Failures later on are returned to the
+//! caller, as the items already yielded can't be taken back.
+
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::stream::{self, BoxStream, StreamExt};
+use lazy_static::lazy_static;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMode,
+    PutMultipartOpts, PutOptions, PutPayload, PutResult,
+};
+use rand::Rng;
+use regex::Regex;
+use tracing::debug;
+
+use super::{storage_constants, ObjectStoreError, ObjectStoreResult, StorageOptions};
+use crate::{DeltaResult, DeltaTableError};
+
+lazy_static! {
+    static ref STATUS_CODE: Regex = Regex::new(r"(?i)\bstatus\b\D{0,32}?\b([1-5]\d{2})\b").unwrap();
+}
+
+/// Retry policy of a [`RetryStore`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// The maximum number of times an operation is retried
+    pub max_retries: usize,
+    /// The backoff before the first retry, doubled for every following retry
+    pub init_backoff: Duration,
+    /// The upper bound of the backoff between two retries
+    pub max_backoff: Duration,
+    /// Upper bound of an operation including all its attempts and backoffs. Requests still
+    /// pending after it are cancelled, so a single hanging request can't stall the operation.
+    pub retry_timeout: Duration,
+    /// HTTP status codes considered transient
+    pub retryable_status_codes: Vec<u16>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 10,
+            init_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(15),
+            retry_timeout: Duration::from_secs(180),
+            retryable_status_codes: vec![408, 429, 500, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Read the retry policy from the storage options.
+    ///
+    /// Returns `None` if [`storage_constants::OBJECT_STORE_MAX_RETRIES`] is not set, other
+    /// settings fall back to the defaults of [`RetryConfig`].
+    pub fn from_options(options: &StorageOptions) -> DeltaResult<Option<Self>> {
+        let max_retries = match parse_option(options, storage_constants::OBJECT_STORE_MAX_RETRIES)?
+        {
+            Some(max_retries) => max_retries,
+            None => return Ok(None),
+        };
+        let mut config = Self {
+            max_retries,
+            ..Default::default()
+        };
+        if let Some(millis) = parse_option(
+            options,
+            storage_constants::OBJECT_STORE_RETRY_INIT_BACKOFF_MS,
+        )? {
+            config.init_backoff = Duration::from_millis(millis);
+        }
+        if let Some(millis) = parse_option(
+            options,
+            storage_constants::OBJECT_STORE_RETRY_MAX_BACKOFF_MS,
+        )? {
+            config.max_backoff = Duration::from_millis(millis);
+        }
+        if let Some(secs) = parse_option(
+            options,
+            storage_constants::OBJECT_STORE_RETRY_TIMEOUT_SECONDS,
+        )? {
+            config.retry_timeout = Duration::from_secs(secs);
+        }
+        if let Some(codes) = options
+            .0
+            .get(storage_constants::OBJECT_STORE_RETRYABLE_STATUS_CODES)
+        {
+            config.retryable_status_codes = codes
+                .split(',')
+                .map(str::trim)
+                .filter(|code| !code.is_empty())
+                .map(|code| {
+                    code.parse().map_err(|_| {
+                        invalid_option(
+                            storage_constants::OBJECT_STORE_RETRYABLE_STATUS_CODES,
+                            codes,
+                        )
+                    })
+                })
+                .collect::<DeltaResult<_>>()?;
+        }
+        Ok(Some(config))
+    }
+
+    /// Whether the error is a transient failure.
+    ///
+    /// Only generic errors of the backend are retried, as errors like [`ObjectStoreError::NotFound`]
+    /// won't change by trying again. The backends do not expose the HTTP status of a failed
+    /// request, it is taken from the error messages instead. Errors without a status, e.g. a
+    /// connection reset, are considered transient.
+    pub fn is_retryable(&self, err: &ObjectStoreError) -> bool {
+        match err {
+            ObjectStoreError::Generic { source, .. } => match status_code(source.as_ref()) {
+                Some(status) => self.retryable_status_codes.contains(&status),
+                None => true,
+            },
+            _ => false,
+        }
+    }
+
+    /// Exponential backoff with jitter before the given retry
+    fn backoff(&self, retry: usize) -> Duration {
+        jittered_backoff(self.init_backoff, self.max_backoff, retry)
+    }
+}
+
+/// Exponential backoff before the given retry, starting at zero, with a random jitter.
+///
+/// The backoff doubles from `init` for every retry up to `max`, and a random duration of up to
+/// half of it is taken off, so that clients failing at the same time spread out their retries.
+pub(crate) fn jittered_backoff(init: Duration, max: Duration, retry: usize) -> Duration {
+    let factor = 2u32.saturating_pow(u32::try_from(retry).unwrap_or(u32::MAX));
+    let backoff = init.saturating_mul(factor).min(max);
+    rand::thread_rng().gen_range(backoff / 2..=backoff)
+}
+
+fn parse_option<T: std::str::FromStr>(
+    options: &StorageOptions,
+    key: &str,
+) -> DeltaResult<Option<T>> {
+    options
+        .0
+        .get(key)
+        .map(|value| value.trim().parse().map_err(|_| invalid_option(key, value)))
+        .transpose()
+}
+
+fn invalid_option(key: &str, value: &str) -> DeltaTableError {
+    DeltaTableError::Generic(format!("Invalid value '{value}' for storage option {key}"))
+}
+
+fn status_code(err: &(dyn std::error::Error + 'static)) -> Option<u16> {
+    let mut current = Some(err);
+    while let Some(err) = current {
+        if let Some(captures) = STATUS_CODE.captures(&err.to_string()) {
+            return captures[1].parse().ok();
+        }
+        current = err.source();
+    }
+    None
+}
+
+/// Wrap the given [ObjectStore] in a [RetryStore] if a retry policy is configured
+///
+/// Reference [RetryConfig::from_options] for the recognized options.
+pub fn retry_store_handler<T: ObjectStore>(
+    store: T,
+    options: &StorageOptions,
+) -> DeltaResult<super::ObjectStoreRef> {
+    match RetryConfig::from_options(options)? {
+        Some(config) => Ok(std::sync::Arc::new(RetryStore::new(store, config))),
+        None => Ok(std::sync::Arc::new(store)),
+    }
+}
+
+/// Store retrying transient failures of the idempotent operations of the inner store
+#[derive(Debug)]
+pub struct RetryStore<T: ObjectStore> {
+    inner: T,
+    config: RetryConfig,
+}
+
+impl<T: ObjectStore> RetryStore<T> {
+    /// Create a new [`RetryStore`] retrying operations of `inner` according to `config`
+    pub fn new(inner: T, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    /// The retry policy of the store
+    pub fn config(&self) -> &RetryConfig {
+        &self.config
+    }
+
+    /// Run the operation until it succeeds, fails permanently or the retry budget is exhausted.
+    ///
+    /// The operation receives the number of the attempt, starting with zero. All attempts
+    /// together are bounded by [`RetryConfig::retry_timeout`].
+    async fn retry<R, F, Fut>(
+        &self,
+        name: &str,
+        location: &str,
+        mut operation: F,
+    ) -> ObjectStoreResult<R>
+    where
+        F: FnMut(usize) -> Fut,
+        Fut: Future<Output = ObjectStoreResult<R>>,
+    {
+        let start = Instant::now();
+        let attempts = async {
+            let mut retries = 0;
+            loop {
+                let err = match operation(retries).await {
+                    Ok(result) => return Ok(result),
+                    Err(err) => err,
+                };
+                if retries >= self.config.max_retries || !self.config.is_retryable(&err) {
+                    return Err(err);
+                }
+                let backoff = self.config.backoff(retries);
+                if start.elapsed() + backoff > self.config.retry_timeout {
+                    return Err(err);
+                }
+                retries += 1;
+                debug!("{name} {location} failed, retry {retries} in {backoff:?}: {err}");
+                tokio::time::sleep(backoff).await;
+            }
+        };
+        match tokio::time::timeout(self.config.retry_timeout, attempts).await {
+            Ok(result) => result,
+            Err(_) => Err(ObjectStoreError::Generic {
+                store: "RetryStore",
+                source: format!(
+                    "{name} {location} timed out after {:?}",
+                    self.config.retry_timeout
+                )
+                .into(),
+            }),
+        }
+    }
+
+    /// Open the listing until its first item arrives or it fails permanently
+    fn retry_list<'a, F>(
+        &'a self,
+        name: &'static str,
+        prefix: Option<&Path>,
+        open: F,
+    ) -> BoxStream<'a, ObjectStoreResult<ObjectMeta>>
+    where
+        F: Fn() -> BoxStream<'a, ObjectStoreResult<ObjectMeta>> + Send + Sync + 'a,
+    {
+        let location = prefix.map(|p| p.to_string()).unwrap_or_default();
+        stream::once(async move {
+            let first = self
+                .retry(name, &location, |_| {
+                    let mut listing = open();
+                    async move {
+                        match listing.next().await {
+                            Some(Ok(meta)) => Ok(Some((meta, listing))),
+                            Some(Err(err)) => Err(err),
+                            None => Ok(None),
+                        }
+                    }
+                })
+                .await;
+            match first {
+                Ok(Some((meta, listing))) => {
+                    stream::once(async move { Ok(meta) }).chain(listing).boxed()
+                }
+                Ok(None) => stream::empty().boxed(),
+                Err(err) => stream::once(async move { Err(err) }).boxed(),
+            }
+        })
+        .flatten()
+        .boxed()
+    }
+}
+
+impl<T: ObjectStore> Display for RetryStore<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "RetryStore({}, {})", self.config.max_retries, self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: ObjectStore> ObjectStore for RetryStore<T> {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        if !matches!(opts.mode, PutMode::Overwrite) {
+            return self.inner.put_opts(location, payload, opts).await;
+        }
+        self.retry("put", location.as_ref(), |_| {
+            self.inner.put_opts(location, payload.clone(), opts.clone())
+        })
+        .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        self.retry("get", location.as_ref(), |_| {
+            self.inner.get_opts(location, options.clone())
+        })
+        .await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> ObjectStoreResult<Bytes> {
+        self.retry("get_range", location.as_ref(), |_| {
+            self.inner.get_range(location, range.clone())
+        })
+        .await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> ObjectStoreResult<Vec<Bytes>> {
+        self.retry("get_ranges", location.as_ref(), |_| {
+            self.inner.get_ranges(location, ranges)
+        })
+        .await
+    }
+
+    async fn head(&self, location: &Path) -> ObjectStoreResult<ObjectMeta> {
+        self.retry("head", location.as_ref(), |_| self.inner.head(location))
+            .await
+    }
+
+    async fn delete(&self, location: &Path) -> ObjectStoreResult<()> {
+        self.retry("delete", location.as_ref(), |attempt| async move {
+            match self.inner.delete(location).await {
+                // a previous attempt may have deleted the object before failing
+                Err(ObjectStoreError::NotFound { .. }) if attempt > 0 => Ok(()),
+                result => result,
+            }
+        })
+        .await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'a, ObjectStoreResult<Path>> {
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        let owned_prefix = prefix.cloned();
+        self.retry_list("list", prefix, move || {
+            self.inner.list(owned_prefix.as_ref())
+        })
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        let owned_prefix = prefix.cloned();
+        let offset = offset.clone();
+        self.retry_list("list_with_offset", prefix, move || {
+            self.inner.list_with_offset(owned_prefix.as_ref(), &offset)
+        })
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        let location = prefix.map(|p| p.as_ref()).unwrap_or_default();
+        self.retry("list_with_delimiter", location, |_| {
+            self.inner.list_with_delimiter(prefix)
+        })
+        .await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.retry("copy", from.as_ref(), |_| self.inner.copy(from, to))
+            .await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    /// Fails the first `failures` writes and reads with the given error message, reads never
+    /// respond while `hang` is set
+    #[derive(Debug)]
+    struct FlakyStore {
+        inner: InMemory,
+        failures: AtomicUsize,
+        hang: AtomicBool,
+        message: &'static str,
+    }
+
+    impl FlakyStore {
+        fn new(failures: usize, message: &'static str) -> Self {
+            Self {
+                inner: InMemory::new(),
+                failures: AtomicUsize::new(failures),
+                hang: AtomicBool::new(false),
+                message,
+            }
+        }
+
+        fn fail(&self) -> ObjectStoreResult<()> {
+            let failing = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if failing {
+                Err(ObjectStoreError::Generic {
+                    store: "Flaky",
+                    source: self.message.into(),
+                })
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl Display for FlakyStore {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "Flaky")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ObjectStore for FlakyStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> ObjectStoreResult<PutResult> {
+            self.fail()?;
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOpts,
+        ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(
+            &self,
+            location: &Path,
+            options: GetOptions,
+        ) -> ObjectStoreResult<GetResult> {
+            if self.hang.load(Ordering::SeqCst) {
+                futures::future::pending::<()>().await;
+            }
+            self.fail()?;
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn delete(&self, location: &Path) -> ObjectStoreResult<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+            match self.fail() {
+                Ok(()) => self.inner.list(prefix),
+                Err(err) => stream::once(async move { Err(err) }).boxed(),
+            }
+        }
+
+        async fn list_with_delimiter(
+            &self,
+            prefix: Option<&Path>,
+        ) -> ObjectStoreResult<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    fn config(max_retries: usize) -> RetryConfig {
+        RetryConfig {
+            max_retries,
+            init_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(2),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_retry_config_from_options() {
+        let options = StorageOptions(HashMap::new());
+        assert_eq!(RetryConfig::from_options(&options).unwrap(), None);
+
+        let options = StorageOptions(HashMap::from_iter(vec![
+            ("OBJECT_STORE_MAX_RETRIES".into(), "3".into()),
+            ("OBJECT_STORE_RETRY_INIT_BACKOFF_MS".into(), "50".into()),
+            ("OBJECT_STORE_RETRY_TIMEOUT_SECONDS".into(), "30".into()),
+            (
+                "OBJECT_STORE_RETRYABLE_STATUS_CODES".into(),
+                "503, 429".into(),
+            ),
+        ]));
+        let expected = RetryConfig {
+            max_retries: 3,
+            init_backoff: Duration::from_millis(50),
+            retry_timeout: Duration::from_secs(30),
+            retryable_status_codes: vec![503, 429],
+            ..Default::default()
+        };
+        assert_eq!(RetryConfig::from_options(&options).unwrap(), Some(expected));
+
+        let options = StorageOptions(HashMap::from_iter(vec![(
+            "OBJECT_STORE_MAX_RETRIES".into(),
+            "many".into(),
+        )]));
+        assert!(RetryConfig::from_options(&options).is_err());
+    }
+
+    #[test]
+    fn test_retry_store_handler() {
+        let options = StorageOptions(HashMap::from_iter(vec![(
+            "OBJECT_STORE_MAX_RETRIES".into(),
+            "5".into(),
+        )]));
+        let store = retry_store_handler(InMemory::new(), &options).unwrap();
+        assert_eq!(String::from("RetryStore(5, InMemory)"), format!("{store}"));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let config = RetryConfig::default();
+        let generic = |message: &str| ObjectStoreError::Generic {
+            store: "S3",
+            source: message.to_string().into(),
+        };
+        assert!(config.is_retryable(&generic(
+            "Client error with status 503 Service Unavailable: Slow Down"
+        )));
+        assert!(config.is_retryable(&generic(
+            "HTTP status server error (500 Internal Server Error) for url (http://localhost)"
+        )));
+        assert!(config.is_retryable(&generic("connection reset by peer")));
+        assert!(!config.is_retryable(&generic(
+            "Client error with status 403 Forbidden: Access Denied"
+        )));
+        assert!(!config.is_retryable(&ObjectStoreError::NotFound {
+            path: "foo".into(),
+            source: "not found".into(),
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_failures() {
+        let message = "Client error with status 503 Service Unavailable: Slow Down";
+        let store = RetryStore::new(FlakyStore::new(2, message), config(3));
+        let location = Path::from("data.parquet");
+
+        store.put(&location, "data".into()).await.unwrap();
+        assert_eq!(store.inner.failures.load(Ordering::SeqCst), 0);
+
+        store.inner.failures.store(4, Ordering::SeqCst);
+        let result = store.get(&location).await;
+        assert!(matches!(result, Err(ObjectStoreError::Generic { .. })));
+        store.get(&location).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retry_timeout_bounds_hanging_requests() {
+        let store = RetryStore::new(
+            FlakyStore::new(0, "connection reset"),
+            RetryConfig {
+                retry_timeout: Duration::from_millis(50),
+                ..config(3)
+            },
+        );
+        let location = Path::from("data.parquet");
+        store.put(&location, "data".into()).await.unwrap();
+
+        store.inner.hang.store(true, Ordering::SeqCst);
+        let result = tokio::time::timeout(Duration::from_secs(5), store.get(&location))
+            .await
+            .expect("retry timeout should cancel the hanging request");
+        match result {
+            Err(ObjectStoreError::Generic { store, source }) => {
+                assert_eq!(store, "RetryStore");
+                assert!(source.to_string().contains("timed out"), "{source}");
+            }
+            Err(err) => panic!("expected a timeout error, got {err}"),
+            Ok(_) => panic!("expected the hanging request to time out"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_listing() {
+        let message = "Client error with status 503 Service Unavailable: Slow Down";
+        let store = RetryStore::new(FlakyStore::new(0, message), config(3));
+        store
+            .put(&Path::from("data.parquet"), "data".into())
+            .await
+            .unwrap();
+
+        store.inner.failures.store(2, Ordering::SeqCst);
+        let listed = store.list(None).collect::<Vec<_>>().await;
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].is_ok());
+
+        store.inner.failures.store(4, Ordering::SeqCst);
+        let listed = store.list(None).collect::<Vec<_>>().await;
+        assert_eq!(listed.len(), 1);
+        assert!(matches!(listed[0], Err(ObjectStoreError::Generic { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_for_conditional_put_and_permanent_failures() {
+        let store = RetryStore::new(FlakyStore::new(1, "connection reset"), config(3));
+        let result = store
+            .put_opts(
+                &Path::from("_delta_log/00000000000000000000.json"),
+                "commit".into(),
+                PutMode::Create.into(),
+            )
+            .await;
+        assert!(matches!(result, Err(ObjectStoreError::Generic { .. })));
+
+        let message = "Client error with status 403 Forbidden: Access Denied";
+        let store = RetryStore::new(FlakyStore::new(1, message), config(3));
+        let result = store.put(&Path::from("data.parquet"), "data".into()).await;
+        assert!(matches!(result, Err(ObjectStoreError::Generic { .. })));
+        assert_eq!(store.inner.failures.load(Ordering::SeqCst), 0);
+    }
+}
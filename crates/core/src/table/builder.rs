@@ -1,7 +1,6 @@
 //! Create or load DeltaTables
 
 use std::collections::HashMap;
-use std::path::PathBuf;
 use std::sync::Arc;
 
 use chrono::{DateTime, FixedOffset, Utc};
@@ -10,10 +9,15 @@ use serde::{Deserialize, Serialize};
 use tracing::debug;
 use url::Url;
 
+use super::uri::{local_path_to_url, parse_table_location, TableLocation, OBJECT_STORE_SCHEME};
 use super::DeltaTable;
+use crate::data_catalog::{data_catalogs, DataCatalogError};
 use crate::errors::{DeltaResult, DeltaTableError};
-use crate::logstore::LogStoreRef;
-use crate::storage::{factories, StorageOptions};
+use crate::logstore::{default_logstore, LogStoreRef};
+use crate::storage::{
+    factories, url_prefix_handler, CredentialProvider, CredentialProviderRef, StorageOptions,
+};
+use crate::Path;
 
 #[allow(dead_code)]
 #[derive(Debug, thiserror::Error)]
@@ -67,6 +71,7 @@ pub struct DeltaTableConfig {
     ///
     /// Some append-only applications might have no need of tracking any files.
     /// Hence, DeltaTable will be loaded with significant memory reduction.
+    /// Operations rewriting or reading the files of the table are not supported without them.
     pub require_files: bool,
     /// Controls how many files to buffer from the commit log when updating the table.
     /// This defaults to 4 * number of cpus
@@ -148,6 +153,7 @@ impl DeltaTableLoadOptions {
 pub struct DeltaTableBuilder {
     options: DeltaTableLoadOptions,
     storage_options: Option<HashMap<String, String>>,
+    credential_provider: Option<CredentialProviderRef>,
     #[allow(unused_variables)]
     allow_http: Option<bool>,
 }
@@ -167,6 +173,68 @@ impl DeltaTableBuilder {
         DeltaTableBuilder::from_valid_uri(url).expect("Failed to create valid builder")
     }
 
+    /// Creates `DeltaTableBuilder` for a table registered in a data catalog.
+    ///
+    /// The storage location of the table is resolved by the catalog registered under the
+    /// name `catalog` in [`data_catalogs`].
+    pub async fn from_catalog(
+        catalog: &str,
+        database_name: &str,
+        table_name: &str,
+    ) -> DeltaResult<Self> {
+        let data_catalog = data_catalogs()
+            .get(catalog)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| DataCatalogError::InvalidDataCatalog {
+                data_catalog: catalog.to_string(),
+            });
+        let location = match data_catalog {
+            Ok(data_catalog) => data_catalog
+                .get_table(database_name, table_name)
+                .await
+                .map(|table| table.location),
+            Err(err) => Err(err),
+        }
+        .map_err(|err| DeltaTableError::GenericError {
+            source: Box::new(err),
+        })?;
+        debug!("Resolved {database_name}.{table_name} in {catalog} catalog to {location}");
+        Self::from_valid_uri(location)
+    }
+
+    /// Creates `DeltaTableBuilder` for the table located at `prefix` in an existing object store.
+    ///
+    /// The store is used as is, without being configured through storage options, which allows
+    /// to open tables with stores wrapped in custom middlewares. The table is committed to using
+    /// the default log store, so the store must support conditional writes to be safe for
+    /// concurrent writers. The table uri is a synthetic `delta-rs-store://` url unique to the
+    /// builder.
+    ///
+    /// ```rust
+    /// # use std::sync::Arc;
+    /// # use deltalake_core::table::builder::*;
+    /// # use deltalake_core::Path;
+    /// use object_store::memory::InMemory;
+    /// let builder = DeltaTableBuilder::from_object_store(Arc::new(InMemory::new()), Path::from("table"));
+    /// assert!(builder.build().is_ok());
+    /// ```
+    pub fn from_object_store(store: Arc<DynObjectStore>, prefix: Path) -> Self {
+        let location = Url::parse(&format!(
+            "{OBJECT_STORE_SCHEME}://{}/{prefix}",
+            uuid::Uuid::new_v4()
+        ))
+        .expect("object store paths are valid url paths");
+        debug!("creating table builder for object store {store} at {location}");
+        let mut options = DeltaTableLoadOptions::new(location.clone());
+        options.storage_backend = Some((url_prefix_handler(store, prefix), location));
+        Self {
+            options,
+            storage_options: None,
+            credential_provider: None,
+            allow_http: None,
+        }
+    }
+
     /// Creates `DeltaTableBuilder` from verified table uri.
     ///
     /// ```rust
@@ -175,23 +243,17 @@ impl DeltaTableBuilder {
     /// assert!(builder.is_ok(), "Builder failed with {builder:?}");
     /// ```
     pub fn from_valid_uri(table_uri: impl AsRef<str>) -> DeltaResult<Self> {
-        if let Ok(url) = Url::parse(table_uri.as_ref()) {
-            if url.scheme() == "file" {
-                let path = url.to_file_path().map_err(|_| {
-                    DeltaTableError::InvalidTableLocation(table_uri.as_ref().to_string())
-                })?;
-                ensure_file_location_exists(path)?;
-            }
-        } else {
-            ensure_file_location_exists(PathBuf::from(table_uri.as_ref()))?;
-        }
-
-        let url = ensure_table_uri(&table_uri)?;
+        let url = match parse_table_location(&table_uri)? {
+            TableLocation::LocalPath(path) => local_path_to_url(&path, false)?,
+            TableLocation::Url(url) => url,
+        };
+        let url = ensure_table_uri(url)?;
         debug!("creating table builder with {url}");
 
         Ok(Self {
             options: DeltaTableLoadOptions::new(url),
             storage_options: None,
+            credential_provider: None,
             allow_http: None,
         })
     }
@@ -203,6 +265,9 @@ impl DeltaTableBuilder {
     }
 
     /// Sets `require_files=false` to the builder
+    ///
+    /// The files of such a table can still be listed with [`DeltaTable::stream_file_actions`],
+    /// operations requiring all files fail with [`DeltaTableError::NotInitializedWithFiles`].
     pub fn without_files(mut self) -> Self {
         self.options.require_files = false;
         self
@@ -265,6 +330,15 @@ impl DeltaTableBuilder {
         self
     }
 
+    /// Set the provider of the credentials used to authenticate against the storage backend.
+    ///
+    /// The provider takes precedence over credentials configured in the storage options or the
+    /// environment. It is not used for a backend set with [`Self::with_storage_backend`].
+    pub fn with_credential_provider(mut self, provider: Arc<dyn CredentialProvider>) -> Self {
+        self.credential_provider = Some(provider);
+        self
+    }
+
     /// Allows unsecure connections via http.
     ///
     /// This setting is most useful for testing / development when connecting to emulated services.
@@ -297,11 +371,25 @@ impl DeltaTableBuilder {
 
         if let Some((store, _url)) = self.options.storage_backend.as_ref() {
             debug!("Loading a logstore with a custom store: {store:?}");
+            if location.scheme() == OBJECT_STORE_SCHEME {
+                return Ok(default_logstore(
+                    store.clone(),
+                    &location,
+                    &self.storage_options(),
+                ));
+            }
             crate::logstore::logstore_with(store.clone(), location, self.storage_options())
         } else {
             // If there has been no backend defined just default to the normal logstore look up
             debug!("Loading a logstore based off the location: {location:?}");
-            crate::logstore::logstore_for(location, self.storage_options())
+            match self.credential_provider.clone() {
+                Some(credentials) => crate::logstore::logstore_for_with_credentials(
+                    location,
+                    self.storage_options(),
+                    credentials,
+                ),
+                None => crate::logstore::logstore_for(location, self.storage_options()),
+            }
         }
     }
 
@@ -332,52 +420,14 @@ impl DeltaTableBuilder {
     }
 }
 
-enum UriType {
-    LocalPath(PathBuf),
-    Url(Url),
-}
-
-/// Utility function to figure out whether string representation of the path
-/// is either local path or some kind or URL.
-///
-/// Will return an error if the path is not valid.
-fn resolve_uri_type(table_uri: impl AsRef<str>) -> DeltaResult<UriType> {
-    let table_uri = table_uri.as_ref();
-    let known_schemes: Vec<_> = factories()
-        .iter()
-        .map(|v| v.key().scheme().to_owned())
-        .collect();
-
-    if let Ok(url) = Url::parse(table_uri) {
-        let scheme = url.scheme().to_string();
-        if url.scheme() == "file" {
-            Ok(UriType::LocalPath(url.to_file_path().map_err(|err| {
-                let msg = format!("Invalid table location: {}\nError: {:?}", table_uri, err);
-                DeltaTableError::InvalidTableLocation(msg)
-            })?))
-        // NOTE this check is required to support absolute windows paths which may properly parse as url
-        } else if known_schemes.contains(&scheme) {
-            Ok(UriType::Url(url))
-        // NOTE this check is required to support absolute windows paths which may properly parse as url
-        // we assume here that a single character scheme is a windows drive letter
-        } else if scheme.len() == 1 {
-            Ok(UriType::LocalPath(PathBuf::from(table_uri)))
-        } else {
-            Err(DeltaTableError::InvalidTableLocation(format!(
-                "Unknown scheme: {}",
-                scheme
-            )))
-        }
-    } else {
-        Ok(UriType::LocalPath(PathBuf::from(table_uri)))
-    }
-}
-
 /// Attempt to create a Url from given table location.
 ///
 /// The location could be:
 ///  * A valid URL, which will be parsed and returned
 ///  * A path to a directory, which will be created and then converted to a URL.
+///  * A `dbfs:/` uri, which is resolved to the local DBFS mount.
+///
+/// See [`parse_table_location`] for details.
 ///
 /// If it is a local path, it will be created if it doesn't exist.
 ///
@@ -387,34 +437,10 @@ fn resolve_uri_type(table_uri: impl AsRef<str>) -> DeltaResult<UriType> {
 pub fn ensure_table_uri(table_uri: impl AsRef<str>) -> DeltaResult<Url> {
     let table_uri = table_uri.as_ref();
 
-    let uri_type: UriType = resolve_uri_type(table_uri)?;
-
     // If it is a local path, we need to create it if it does not exist.
-    let mut url = match uri_type {
-        UriType::LocalPath(path) => {
-            if !path.exists() {
-                std::fs::create_dir_all(&path).map_err(|err| {
-                    let msg = format!(
-                        "Could not create local directory: {}\nError: {:?}",
-                        table_uri, err
-                    );
-                    DeltaTableError::InvalidTableLocation(msg)
-                })?;
-            }
-            let path = std::fs::canonicalize(path).map_err(|err| {
-                let msg = format!("Invalid table location: {}\nError: {:?}", table_uri, err);
-                DeltaTableError::InvalidTableLocation(msg)
-            })?;
-            Url::from_directory_path(path).map_err(|_| {
-                let msg = format!(
-                    "Could not construct a URL from canonicalized path: {}.\n\
-                    Something must be very wrong with the table path.",
-                    table_uri
-                );
-                DeltaTableError::InvalidTableLocation(msg)
-            })?
-        }
-        UriType::Url(url) => url,
+    let mut url = match parse_table_location(table_uri)? {
+        TableLocation::LocalPath(path) => local_path_to_url(&path, true)?,
+        TableLocation::Url(url) => url,
     };
 
     let trimmed_path = url.path().trim_end_matches('/').to_owned();
@@ -422,19 +448,6 @@ pub fn ensure_table_uri(table_uri: impl AsRef<str>) -> DeltaResult<Url> {
     Ok(url)
 }
 
-/// Validate that the given [PathBuf] does exist, otherwise return a
-/// [DeltaTableError::InvalidTableLocation]
-fn ensure_file_location_exists(path: PathBuf) -> DeltaResult<()> {
-    if !path.exists() {
-        let msg = format!(
-            "Local path \"{}\" does not exist or you don't have access!",
-            path.as_path().display(),
-        );
-        return Err(DeltaTableError::InvalidTableLocation(msg));
-    }
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -561,4 +574,74 @@ mod tests {
         DeltaTableBuilder::from_valid_uri("this://is.nonsense")
             .expect_err("this should be an error");
     }
+
+    #[derive(Debug)]
+    struct TestCatalog {}
+
+    #[async_trait::async_trait]
+    impl crate::data_catalog::DataCatalog for TestCatalog {
+        async fn get_table_storage_location(
+            &self,
+            _catalog_id: Option<String>,
+            database_name: &str,
+            table_name: &str,
+        ) -> Result<String, DataCatalogError> {
+            Ok(format!("memory:///{database_name}/{table_name}"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_from_catalog() {
+        data_catalogs().insert("test".to_string(), Arc::new(TestCatalog {}));
+
+        let builder = DeltaTableBuilder::from_catalog("test", "db", "table")
+            .await
+            .unwrap();
+        assert_eq!(builder.options.table_uri, "memory:///db/table");
+        DeltaTableBuilder::from_catalog("missing", "db", "table")
+            .await
+            .expect_err("catalog is not registered");
+    }
+
+    #[tokio::test]
+    async fn test_from_object_store() {
+        use crate::kernel::DataType;
+        use crate::operations::create::CreateBuilder;
+        use object_store::memory::InMemory;
+        use object_store::ObjectStore;
+
+        let store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let log_store = DeltaTableBuilder::from_object_store(store.clone(), Path::from("a/table"))
+            .build_storage()
+            .unwrap();
+        assert!(log_store.root_uri().starts_with("delta-rs-store://"));
+        CreateBuilder::new()
+            .with_log_store(log_store)
+            .with_column("id", DataType::INTEGER, true, None)
+            .await
+            .unwrap();
+        assert!(store
+            .head(&Path::from("a/table/_delta_log/00000000000000000000.json"))
+            .await
+            .is_ok());
+
+        let table = DeltaTableBuilder::from_object_store(store, Path::from("a/table"))
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(table.version(), 0);
+    }
+
+    #[test]
+    fn test_with_credential_provider() {
+        use crate::storage::{StaticCredentialProvider, StorageCredential};
+
+        let provider = Arc::new(StaticCredentialProvider::new(
+            StorageCredential::BearerToken("token".to_string()),
+        ));
+        let builder = DeltaTableBuilder::from_uri("memory:///").with_credential_provider(provider);
+        assert!(builder.credential_provider.is_some());
+        // in-memory stores ignore credentials
+        builder.build_storage().unwrap();
+    }
 }
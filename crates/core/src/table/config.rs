@@ -69,9 +69,16 @@ pub enum DeltaConfigKey {
     /// true to enable deletion vectors and predictive I/O for updates.
     EnableDeletionVectors,
 
+    /// true to record the time of each commit as an in-commit timestamp in its commit info.
+    EnableInCommitTimestamps,
+
+    /// The version of the commit enabling in-commit timestamps. Commits before it are timed by
+    /// the modification times of their files. Absent if enabled when the table was created.
+    InCommitTimestampEnablementVersion,
+
     /// The degree to which a transaction must be isolated from modifications made by concurrent transactions.
     ///
-    /// Valid values are `Serializable` and `WriteSerializable`.
+    /// Valid values are `Serializable`, `WriteSerializable` and `SnapshotIsolation`.
     IsolationLevel,
 
     /// How long the history for a Delta table is kept.
@@ -101,6 +108,10 @@ pub enum DeltaConfigKey {
     /// When delta.randomizeFilePrefixes is set to true, the number of characters that Delta Lake generates for random prefixes.
     RandomPrefixLength,
 
+    /// With the checkpointProtection writer feature, commits and checkpoints before this version
+    /// may only be cleaned up together with all others before it.
+    RequireCheckpointProtectionBeforeVersion,
+
     /// The shortest duration within which new snapshots will retain transaction identifiers (for example, SetTransactions).
     /// When a new snapshot sees a transaction identifier older than or equal to the duration specified by this property,
     /// the snapshot considers it expired and ignores it. The SetTransaction identifier is used when making the writes idempotent.
@@ -114,6 +125,10 @@ pub enum DeltaConfigKey {
 
     /// 'classic' for classic Delta Lake checkpoints. 'v2' for v2 checkpoints.
     CheckpointPolicy,
+
+    /// A comma-separated list of table formats for which metadata is generated on every commit,
+    /// allowing readers of these formats to query the table. Only `iceberg` is supported.
+    UniversalFormatEnabledFormats,
 }
 
 impl AsRef<str> for DeltaConfigKey {
@@ -132,6 +147,8 @@ impl AsRef<str> for DeltaConfigKey {
             Self::DeletedFileRetentionDuration => "delta.deletedFileRetentionDuration",
             Self::EnableChangeDataFeed => "delta.enableChangeDataFeed",
             Self::EnableDeletionVectors => "delta.enableDeletionVectors",
+            Self::EnableInCommitTimestamps => "delta.enableInCommitTimestamps",
+            Self::InCommitTimestampEnablementVersion => "delta.inCommitTimestampEnablementVersion",
             Self::IsolationLevel => "delta.isolationLevel",
             Self::LogRetentionDuration => "delta.logRetentionDuration",
             Self::EnableExpiredLogCleanup => "delta.enableExpiredLogCleanup",
@@ -139,9 +156,13 @@ impl AsRef<str> for DeltaConfigKey {
             Self::MinWriterVersion => "delta.minWriterVersion",
             Self::RandomizeFilePrefixes => "delta.randomizeFilePrefixes",
             Self::RandomPrefixLength => "delta.randomPrefixLength",
+            Self::RequireCheckpointProtectionBeforeVersion => {
+                "delta.requireCheckpointProtectionBeforeVersion"
+            }
             Self::SetTransactionRetentionDuration => "delta.setTransactionRetentionDuration",
             Self::TargetFileSize => "delta.targetFileSize",
             Self::TuneFileSizesForRewrites => "delta.tuneFileSizesForRewrites",
+            Self::UniversalFormatEnabledFormats => "delta.universalFormat.enabledFormats",
         }
     }
 }
@@ -166,6 +187,10 @@ impl FromStr for DeltaConfigKey {
             }
             "delta.enableChangeDataFeed" => Ok(Self::EnableChangeDataFeed),
             "delta.enableDeletionVectors" => Ok(Self::EnableDeletionVectors),
+            "delta.enableInCommitTimestamps" => Ok(Self::EnableInCommitTimestamps),
+            "delta.inCommitTimestampEnablementVersion" => {
+                Ok(Self::InCommitTimestampEnablementVersion)
+            }
             "delta.isolationLevel" => Ok(Self::IsolationLevel),
             "delta.logRetentionDuration" | "logRetentionDuration" => Ok(Self::LogRetentionDuration),
             "delta.enableExpiredLogCleanup" | "enableExpiredLogCleanup" => {
@@ -175,14 +200,80 @@ impl FromStr for DeltaConfigKey {
             "delta.minWriterVersion" => Ok(Self::MinWriterVersion),
             "delta.randomizeFilePrefixes" => Ok(Self::RandomizeFilePrefixes),
             "delta.randomPrefixLength" => Ok(Self::RandomPrefixLength),
+            "delta.requireCheckpointProtectionBeforeVersion" => {
+                Ok(Self::RequireCheckpointProtectionBeforeVersion)
+            }
             "delta.setTransactionRetentionDuration" => Ok(Self::SetTransactionRetentionDuration),
             "delta.targetFileSize" => Ok(Self::TargetFileSize),
             "delta.tuneFileSizesForRewrites" => Ok(Self::TuneFileSizesForRewrites),
+            "delta.universalFormat.enabledFormats" => Ok(Self::UniversalFormatEnabledFormats),
             _ => Err(DeltaTableError::Generic("unknown config key".into())),
         }
     }
 }
 
+impl DeltaConfigKey {
+    /// Validate that `value` is a valid value of the property
+    ///
+    /// Properties without a well known format, e.g. the target file size which may carry a unit,
+    /// are accepted as is.
+    pub fn validate_value(&self, value: &str) -> Result<(), DeltaConfigError> {
+        match self {
+            Self::AppendOnly
+            | Self::AutoOptimizeOptimizeWrite
+            | Self::CheckpointWriteStatsAsJson
+            | Self::CheckpointWriteStatsAsStruct
+            | Self::EnableChangeDataFeed
+            | Self::EnableDeletionVectors
+            | Self::EnableInCommitTimestamps
+            | Self::EnableExpiredLogCleanup
+            | Self::RandomizeFilePrefixes => parse_bool(value).map(|_| ()),
+            Self::AutoOptimizeAutoCompact => match value.trim().to_ascii_lowercase().as_str() {
+                "true" | "false" | "auto" | "legacy" => Ok(()),
+                _ => Err(DeltaConfigError::Validation(format!(
+                    "'{value}' is not one of 'true', 'false', 'auto' or 'legacy'"
+                ))),
+            },
+            Self::CheckpointInterval | Self::RandomPrefixLength => match parse_int(value)? {
+                number if number > 0 => Ok(()),
+                _ => Err(DeltaConfigError::Validation(format!(
+                    "'{value}' must be a positive integer"
+                ))),
+            },
+            Self::RequireCheckpointProtectionBeforeVersion
+            | Self::InCommitTimestampEnablementVersion => match parse_int(value)? {
+                number if number >= 0 => Ok(()),
+                _ => Err(DeltaConfigError::Validation(format!(
+                    "'{value}' must be a non-negative integer"
+                ))),
+            },
+            Self::DataSkippingNumIndexedCols => match parse_int(value)? {
+                number if number >= -1 => Ok(()),
+                _ => Err(DeltaConfigError::Validation(format!(
+                    "'{value}' must be -1 or a non-negative integer"
+                ))),
+            },
+            Self::DeletedFileRetentionDuration
+            | Self::LogRetentionDuration
+            | Self::SetTransactionRetentionDuration => parse_interval(value).map(|_| ()),
+            Self::IsolationLevel => value.parse::<IsolationLevel>().map(|_| ()).map_err(|_| {
+                DeltaConfigError::Validation(format!("'{value}' is not an isolation level"))
+            }),
+            Self::CheckpointPolicy => value.parse::<CheckpointPolicy>().map(|_| ()).map_err(|_| {
+                DeltaConfigError::Validation(format!("'{value}' is not a checkpoint policy"))
+            }),
+            Self::ColumnMappingMode => {
+                value.parse::<ColumnMappingMode>().map(|_| ()).map_err(|_| {
+                    DeltaConfigError::Validation(format!(
+                        "'{value}' is not one of 'none', 'id' or 'name'"
+                    ))
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Delta configuration error
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
 pub enum DeltaConfigError {
@@ -211,6 +302,19 @@ pub struct TableConfig<'a>(pub(crate) &'a HashMap<String, Option<String>>);
 /// Default num index cols
 pub const DEFAULT_NUM_INDEX_COLS: i32 = 32;
 
+/// Prefix of the table properties configuring the bloom filter of a column, e.g.
+/// `delta.bloomFilter.id.enabled`
+pub const BLOOM_FILTER_PREFIX: &str = "delta.bloomFilter.";
+
+/// Settings of the parquet bloom filter written for a column
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BloomFilterConfig {
+    /// False positive probability of the filter, defaults to the parquet writer default
+    pub fpp: Option<f64>,
+    /// Expected number of distinct values per row group, defaults to the parquet writer default
+    pub num_items: Option<u64>,
+}
+
 impl<'a> TableConfig<'a> {
     table_config!(
         (
@@ -228,11 +332,11 @@ impl<'a> TableConfig<'a> {
             true
         ),
         (
-            "true for Delta Lake to write file statistics to checkpoints in struct format",
+            "true for Delta Lake to write file statistics and partition values to checkpoints in struct format",
             DeltaConfigKey::CheckpointWriteStatsAsStruct,
             write_stats_as_struct,
             bool,
-            false
+            true
         ),
         (
             "The target file size in bytes or higher units for file tuning",
@@ -265,6 +369,13 @@ impl<'a> TableConfig<'a> {
             i32,
             32
         ),
+        (
+            "true to redistribute data by partition before writing, producing fewer and larger files",
+            DeltaConfigKey::AutoOptimizeOptimizeWrite,
+            optimize_write,
+            bool,
+            false
+        ),
         (
             "whether to cleanup expired logs",
             DeltaConfigKey::EnableExpiredLogCleanup,
@@ -334,6 +445,34 @@ impl<'a> TableConfig<'a> {
             .unwrap_or_default()
     }
 
+    /// Version before which commits and checkpoints are protected, according to
+    /// delta.requireCheckpointProtectionBeforeVersion
+    ///
+    /// Only honored if the table has the checkpointProtection writer feature.
+    pub fn require_checkpoint_protection_before_version(&self) -> Option<i64> {
+        self.0
+            .get(DeltaConfigKey::RequireCheckpointProtectionBeforeVersion.as_ref())
+            .and_then(|o| o.as_ref().and_then(|v| v.parse().ok()))
+    }
+
+    /// First version timed by its in-commit timestamp, according to
+    /// delta.enableInCommitTimestamps and delta.inCommitTimestampEnablementVersion
+    ///
+    /// Returns `None` if in-commit timestamps are not enabled.
+    pub fn in_commit_timestamps_since(&self) -> Option<i64> {
+        let enabled = self
+            .0
+            .get(DeltaConfigKey::EnableInCommitTimestamps.as_ref())
+            .and_then(|o| o.as_ref().and_then(|v| parse_bool(v).ok()))
+            .unwrap_or(false);
+        enabled.then(|| {
+            self.0
+                .get(DeltaConfigKey::InCommitTimestampEnablementVersion.as_ref())
+                .and_then(|o| o.as_ref().and_then(|v| v.parse().ok()))
+                .unwrap_or(0)
+        })
+    }
+
     /// Return the column mapping mode according to delta.columnMapping.mode
     pub fn column_mapping_mode(&self) -> ColumnMappingMode {
         self.0
@@ -342,6 +481,38 @@ impl<'a> TableConfig<'a> {
             .unwrap_or_default()
     }
 
+    /// Table formats for which metadata is generated on every commit, according to
+    /// delta.universalFormat.enabledFormats
+    pub fn universal_formats(&self) -> Vec<String> {
+        self.0
+            .get(DeltaConfigKey::UniversalFormatEnabledFormats.as_ref())
+            .and_then(|o| o.as_ref())
+            .map(|formats| {
+                formats
+                    .split(',')
+                    .map(|format| format.trim().to_ascii_lowercase())
+                    .filter(|format| !format.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether small files are compacted after writes, according to delta.autoOptimize.autoCompact
+    ///
+    /// Besides `true`, the values `auto` and `legacy` used by Databricks enable auto compaction.
+    pub fn auto_compact(&self) -> bool {
+        self.0
+            .get(DeltaConfigKey::AutoOptimizeAutoCompact.as_ref())
+            .and_then(|o| o.as_ref())
+            .map(|value| {
+                matches!(
+                    value.trim().to_ascii_lowercase().as_str(),
+                    "true" | "auto" | "legacy"
+                )
+            })
+            .unwrap_or(false)
+    }
+
     /// Return the check constraints on the current table
     pub fn get_constraints(&self) -> Vec<Constraint> {
         self.0
@@ -361,7 +532,46 @@ impl<'a> TableConfig<'a> {
     pub fn stats_columns(&self) -> Option<Vec<&str>> {
         self.0
             .get(DeltaConfigKey::DataSkippingStatsColumns.as_ref())
-            .and_then(|o| o.as_ref().map(|v| v.split(',').collect()))
+            .and_then(|o| o.as_ref().map(|v| v.split(',').map(|c| c.trim()).collect()))
+    }
+
+    /// Columns for which parquet bloom filters are written.
+    ///
+    /// Bloom filters are enabled with `delta.bloomFilter.<column>.enabled = true`, and tuned with
+    /// `delta.bloomFilter.<column>.fpp` and `delta.bloomFilter.<column>.numItems`.
+    pub fn bloom_filter_columns(&self) -> HashMap<String, BloomFilterConfig> {
+        let mut options: HashMap<&str, Vec<(&str, &str)>> = HashMap::new();
+        for (key, value) in self.0.iter() {
+            let Some((column, option)) = key
+                .strip_prefix(BLOOM_FILTER_PREFIX)
+                .and_then(|key| key.rsplit_once('.'))
+            else {
+                continue;
+            };
+            if let Some(value) = value {
+                options.entry(column).or_default().push((option, value));
+            }
+        }
+
+        options
+            .into_iter()
+            .filter(|(_, options)| {
+                options.iter().any(|(option, value)| {
+                    *option == "enabled" && value.eq_ignore_ascii_case("true")
+                })
+            })
+            .map(|(column, options)| {
+                let mut config = BloomFilterConfig::default();
+                for (option, value) in options {
+                    match option {
+                        "fpp" => config.fpp = value.parse().ok(),
+                        "numItems" => config.num_items = value.parse().ok(),
+                        _ => {}
+                    }
+                }
+                (column.to_string(), config)
+            })
+            .collect()
     }
 }
 
@@ -503,6 +713,13 @@ fn parse_interval(value: &str) -> Result<Duration, DeltaConfigError> {
     Ok(duration)
 }
 
+fn parse_bool(value: &str) -> Result<bool, DeltaConfigError> {
+    value
+        .to_ascii_lowercase()
+        .parse()
+        .map_err(|_| DeltaConfigError::Validation(format!("'{value}' is not a boolean")))
+}
+
 fn parse_int(value: &str) -> Result<i64, DeltaConfigError> {
     value.parse().map_err(|e| {
         DeltaConfigError::Validation(format!("Cannot parse '{value}' as integer: {e}"))
@@ -547,6 +764,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_universal_formats_test() {
+        let md = dummy_metadata();
+        let config = TableConfig(&md.configuration);
+        assert!(config.universal_formats().is_empty());
+
+        let mut md = dummy_metadata();
+        md.configuration.insert(
+            DeltaConfigKey::UniversalFormatEnabledFormats
+                .as_ref()
+                .to_string(),
+            Some("Iceberg, hudi".to_string()),
+        );
+        let config = TableConfig(&md.configuration);
+        assert_eq!(config.universal_formats(), vec!["iceberg", "hudi"]);
+    }
+
     #[test]
     fn get_long_from_metadata_test() {
         let md = dummy_metadata();
@@ -573,6 +807,40 @@ mod tests {
         assert!(!config.enable_expired_log_cleanup());
     }
 
+    #[test]
+    fn get_bloom_filter_columns_test() {
+        let mut md = dummy_metadata();
+        for (key, value) in [
+            ("delta.bloomFilter.id.enabled", "true"),
+            ("delta.bloomFilter.id.fpp", "0.01"),
+            ("delta.bloomFilter.a.b.enabled", "TRUE"),
+            ("delta.bloomFilter.a.b.numItems", "1000"),
+            ("delta.bloomFilter.value.enabled", "false"),
+            ("delta.bloomFilter.name.fpp", "0.1"),
+        ] {
+            md.configuration
+                .insert(key.to_string(), Some(value.to_string()));
+        }
+        let config = TableConfig(&md.configuration);
+
+        let columns = config.bloom_filter_columns();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(
+            columns["id"],
+            BloomFilterConfig {
+                fpp: Some(0.01),
+                num_items: None
+            }
+        );
+        assert_eq!(
+            columns["a.b"],
+            BloomFilterConfig {
+                fpp: None,
+                num_items: Some(1000)
+            }
+        );
+    }
+
     #[test]
     fn parse_interval_test() {
         assert_eq!(
@@ -656,6 +924,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_value_test() {
+        assert!(DeltaConfigKey::AppendOnly.validate_value("TRUE").is_ok());
+        assert!(DeltaConfigKey::AppendOnly.validate_value("yes").is_err());
+        assert!(DeltaConfigKey::CheckpointInterval
+            .validate_value("5")
+            .is_ok());
+        assert!(DeltaConfigKey::CheckpointInterval
+            .validate_value("0")
+            .is_err());
+        assert!(DeltaConfigKey::DataSkippingNumIndexedCols
+            .validate_value("-1")
+            .is_ok());
+        assert!(DeltaConfigKey::LogRetentionDuration
+            .validate_value("interval 2 days")
+            .is_ok());
+        assert!(DeltaConfigKey::LogRetentionDuration
+            .validate_value("2 days")
+            .is_err());
+        assert!(DeltaConfigKey::ColumnMappingMode
+            .validate_value("name")
+            .is_ok());
+        assert!(DeltaConfigKey::ColumnMappingMode
+            .validate_value("other")
+            .is_err());
+        assert!(DeltaConfigKey::TargetFileSize
+            .validate_value("100mb")
+            .is_ok());
+    }
+
     #[test]
     fn parse_interval_invalid_test() {
         assert_eq!(
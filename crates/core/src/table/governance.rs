@@ -0,0 +1,215 @@
+//! Compliance metadata stored in the table properties and policies guarding destructive operations
+//!
+//! Besides the retention settings defined by the protocol, tables can carry governance metadata
+//! in their configuration:
+//!
+//! - `governance.encryption`: the encryption applied to the data files, e.g. `SSE-KMS`
+//! - `governance.classification`: the data classification of the table, e.g. `pii`
+//! - `governance.masking.<column>`: the masking applied to a column, e.g. `sha256`
+//!
+//! [`GovernancePolicy`]s can be passed to operations that irreversibly remove data or
+//! guarantees from a table, and are checked before the operation is committed.
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::Duration;
+
+use super::config::TableConfig;
+use crate::errors::DeltaTableError;
+
+/// Table property holding the encryption applied to the data files
+pub const ENCRYPTION_KEY: &str = "governance.encryption";
+/// Table property holding the data classification of the table
+pub const CLASSIFICATION_KEY: &str = "governance.classification";
+/// Prefix of the table properties holding the masking of a column
+pub const MASKING_PREFIX: &str = "governance.masking.";
+
+/// Errors raised by governance policies
+#[derive(thiserror::Error, Debug)]
+pub enum GovernanceError {
+    /// The operation is not allowed by the policy
+    #[error("{operation} rejected by governance policy: {reason}")]
+    Rejected {
+        /// The rejected operation
+        operation: &'static str,
+        /// Why the operation has been rejected
+        reason: String,
+    },
+}
+
+impl From<GovernanceError> for DeltaTableError {
+    fn from(err: GovernanceError) -> Self {
+        DeltaTableError::GenericError {
+            source: Box::new(err),
+        }
+    }
+}
+
+/// Compliance information of a table, derived from the table properties
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableGovernance {
+    /// Minimum time to keep logically deleted data files
+    pub deleted_file_retention_duration: Duration,
+    /// How long the history of the table is kept
+    pub log_retention_duration: Duration,
+    /// Whether existing records can be updated or deleted
+    pub append_only: bool,
+    /// Encryption applied to the data files
+    pub encryption: Option<String>,
+    /// Data classification of the table
+    pub classification: Option<String>,
+    /// Masking applied to columns, by column name
+    pub masking: HashMap<String, String>,
+}
+
+impl TableGovernance {
+    /// Masking applied to `column`, if any
+    pub fn column_masking(&self, column: &str) -> Option<&str> {
+        self.masking.get(column).map(|m| m.as_str())
+    }
+}
+
+impl<'a> TableConfig<'a> {
+    /// Compliance information stored in the table properties
+    pub fn governance(&self) -> TableGovernance {
+        let value = |key: &str| self.0.get(key).cloned().flatten();
+        TableGovernance {
+            deleted_file_retention_duration: self.deleted_file_retention_duration(),
+            log_retention_duration: self.log_retention_duration(),
+            append_only: self.append_only(),
+            encryption: value(ENCRYPTION_KEY),
+            classification: value(CLASSIFICATION_KEY),
+            masking: self
+                .0
+                .iter()
+                .filter_map(|(key, value)| {
+                    let column = key.strip_prefix(MASKING_PREFIX)?;
+                    Some((column.to_string(), value.clone()?))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Destructive operations checked by a [`GovernancePolicy`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum DestructiveOperation {
+    /// Vacuum deleting files older than the retention period
+    Vacuum {
+        /// Retention period requested for the vacuum
+        retention_period: Duration,
+    },
+    /// Dropping a check constraint
+    DropConstraint {
+        /// Name of the constraint
+        name: String,
+    },
+}
+
+impl DestructiveOperation {
+    /// Name of the operation
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Vacuum { .. } => "VACUUM",
+            Self::DropConstraint { .. } => "DROP CONSTRAINT",
+        }
+    }
+}
+
+/// A policy deciding whether a destructive operation may proceed on a table
+pub trait GovernancePolicy: Debug + Send + Sync {
+    /// Check whether `operation` may be applied to a table with the given `governance`
+    fn check(
+        &self,
+        governance: &TableGovernance,
+        operation: &DestructiveOperation,
+    ) -> Result<(), GovernanceError>;
+}
+
+/// Policy rejecting vacuums below the retention of the table, and dropping constraints on
+/// classified tables
+#[derive(Debug, Default, Clone)]
+pub struct DefaultGovernancePolicy {}
+
+impl GovernancePolicy for DefaultGovernancePolicy {
+    fn check(
+        &self,
+        governance: &TableGovernance,
+        operation: &DestructiveOperation,
+    ) -> Result<(), GovernanceError> {
+        match operation {
+            DestructiveOperation::Vacuum { retention_period }
+                if *retention_period < governance.deleted_file_retention_duration =>
+            {
+                Err(GovernanceError::Rejected {
+                    operation: operation.name(),
+                    reason: format!(
+                        "retention period of {} hours is below the table retention of {} hours",
+                        retention_period.as_secs() / 3600,
+                        governance.deleted_file_retention_duration.as_secs() / 3600
+                    ),
+                })
+            }
+            DestructiveOperation::DropConstraint { name } => match &governance.classification {
+                Some(classification) => Err(GovernanceError::Rejected {
+                    operation: operation.name(),
+                    reason: format!(
+                        "constraint {name} protects a table classified as {classification}"
+                    ),
+                }),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn governance(configuration: &HashMap<String, Option<String>>) -> TableGovernance {
+        TableConfig(configuration).governance()
+    }
+
+    #[test]
+    fn test_governance_from_config() {
+        let configuration = HashMap::from([
+            (
+                "delta.deletedFileRetentionDuration".to_string(),
+                Some("interval 2 days".to_string()),
+            ),
+            (ENCRYPTION_KEY.to_string(), Some("SSE-KMS".to_string())),
+            (CLASSIFICATION_KEY.to_string(), Some("pii".to_string())),
+            (format!("{MASKING_PREFIX}email"), Some("sha256".to_string())),
+        ]);
+        let governance = governance(&configuration);
+        assert_eq!(
+            governance.deleted_file_retention_duration,
+            Duration::from_secs(2 * 24 * 3600)
+        );
+        assert_eq!(governance.encryption.as_deref(), Some("SSE-KMS"));
+        assert_eq!(governance.classification.as_deref(), Some("pii"));
+        assert_eq!(governance.column_masking("email"), Some("sha256"));
+        assert_eq!(governance.column_masking("name"), None);
+        assert!(!governance.append_only);
+    }
+
+    #[test]
+    fn test_default_policy() {
+        let policy = DefaultGovernancePolicy::default();
+        let mut governance = governance(&HashMap::new());
+
+        let vacuum = |hours: u64| DestructiveOperation::Vacuum {
+            retention_period: Duration::from_secs(hours * 3600),
+        };
+        assert!(policy.check(&governance, &vacuum(168)).is_ok());
+        assert!(policy.check(&governance, &vacuum(1)).is_err());
+
+        let drop_constraint = DestructiveOperation::DropConstraint {
+            name: "id_gt_0".to_string(),
+        };
+        assert!(policy.check(&governance, &drop_constraint).is_ok());
+        governance.classification = Some("pii".to_string());
+        assert!(policy.check(&governance, &drop_constraint).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! Options for reading the commit history of a table.
+//!
+//! [`DeltaTable::history_stream`](super::DeltaTable::history_stream) reads the commit infos
+//! lazily, newest first, and only returns the commits selected by [`HistoryOptions`]. Commits
+//! whose log files have already been cleaned up are not part of the history.
+
+use chrono::{DateTime, Utc};
+
+use crate::kernel::CommitInfo;
+
+/// Selects the commits returned when reading the history of a table
+#[derive(Debug, Clone, Default)]
+pub struct HistoryOptions {
+    pub(crate) limit: Option<usize>,
+    pub(crate) offset: usize,
+    pub(crate) operations: Option<Vec<String>>,
+    pub(crate) start_time: Option<DateTime<Utc>>,
+    pub(crate) end_time: Option<DateTime<Utc>>,
+}
+
+impl HistoryOptions {
+    /// Create new options selecting all commits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return at most `limit` commits
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the `offset` most recent of the selected commits
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Only return commits of the given operations, e.g. `WRITE` or `MERGE`
+    pub fn with_operations(
+        mut self,
+        operations: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.operations = Some(operations.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Only return commits made at or after `start_time`
+    pub fn with_start_time(mut self, start_time: DateTime<Utc>) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    /// Only return commits made at or before `end_time`
+    pub fn with_end_time(mut self, end_time: DateTime<Utc>) -> Self {
+        self.end_time = Some(end_time);
+        self
+    }
+
+    /// Whether the options filter commits by their content
+    pub(crate) fn has_filters(&self) -> bool {
+        self.operations.is_some() || self.start_time.is_some() || self.end_time.is_some()
+    }
+
+    /// Whether the commit made at `timestamp` (in milliseconds) is selected
+    pub(crate) fn matches(&self, commit_info: &CommitInfo, timestamp: i64) -> bool {
+        let operation_matches = match (&self.operations, &commit_info.operation) {
+            (None, _) => true,
+            (Some(operations), Some(operation)) => operations.contains(operation),
+            (Some(_), None) => false,
+        };
+        operation_matches
+            && self
+                .start_time
+                .map(|start| timestamp >= start.timestamp_millis())
+                .unwrap_or(true)
+            && self
+                .end_time
+                .map(|end| timestamp <= end.timestamp_millis())
+                .unwrap_or(true)
+    }
+}
@@ -1,22 +1,23 @@
 //! Delta Table read and write implementation
 
-use std::cmp::{min, Ordering};
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
 
 use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
 use futures::{StreamExt, TryStreamExt};
-use object_store::{path::Path, ObjectStore};
+use object_store::{path::Path, Error as ObjectStoreError, ObjectStore};
 use serde::de::{Error, SeqAccess, Visitor};
 use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use self::builder::DeltaTableConfig;
-use self::state::DeltaTableState;
+use self::history::HistoryOptions;
+use self::state::{DeltaTableState, PartitionStatistics, StatsSummary};
 use crate::kernel::{
-    Action, CommitInfo, DataCheck, DataType, LogicalFile, Metadata, Protocol, StructType,
-    Transaction,
+    Action, Add, CommitInfo, DataCheck, DataType, LogicalFile, Metadata, Protocol, Snapshot,
+    SnapshotMemoryUsage, StructType, Transaction,
 };
 use crate::logstore::{self, extract_version_from_filename, LogStoreConfig, LogStoreRef};
 use crate::partitions::PartitionFilter;
@@ -25,8 +26,14 @@ use crate::{DeltaResult, DeltaTableError};
 
 pub mod builder;
 pub mod config;
+pub mod governance;
+pub mod history;
 pub mod state;
 pub mod state_arrow;
+pub mod uri;
+
+/// Key of the in-commit timestamp in the commit info of tables enabling in-commit timestamps
+const IN_COMMIT_TIMESTAMP: &str = "inCommitTimestamp";
 
 /// Metadata for a checkpoint file
 #[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
@@ -356,6 +363,51 @@ impl DeltaTable {
         }
     }
 
+    /// Updates the DeltaTable to `max_version`, or the latest version, by applying only the
+    /// commits newer than the loaded version to the existing state.
+    ///
+    /// Newer checkpoints are skipped, which keeps the latency low when tailing an active table.
+    /// The state is updated from the latest checkpoint instead if some of these commits have
+    /// already been removed from the log, or once the commits since the last checkpoint read
+    /// exceed the `delta.checkpointInterval` of the table. Fails if `max_version` does not exist.
+    pub async fn update_from_commits(
+        &mut self,
+        max_version: Option<i64>,
+    ) -> Result<(), DeltaTableError> {
+        match self.state.as_mut() {
+            Some(state) => {
+                state
+                    .update_from_commits(self.log_store.clone(), max_version)
+                    .await
+            }
+            None => self.update_incremental(max_version).await,
+        }
+    }
+
+    /// Loads the latest version of the table, retaining only the files in partitions matching
+    /// all `filters`.
+    ///
+    /// Files of other partitions are dropped while the log is replayed, and the filters remain
+    /// in effect when the table is updated. This reduces memory usage and loading time when
+    /// only a few partitions of a large table are read. The loaded state does not reflect the
+    /// whole table, so operations writing to the table fail with
+    /// [`DeltaTableError::LoadedWithPartitionFilters`].
+    pub async fn load_with_partition_filters(
+        &mut self,
+        filters: &[PartitionFilter],
+    ) -> Result<(), DeltaTableError> {
+        let state = DeltaTableState::try_new_with_partition_filters(
+            &Path::default(),
+            self.log_store.object_store(),
+            self.config.clone(),
+            None,
+            filters.to_vec(),
+        )
+        .await?;
+        self.state = Some(state);
+        Ok(())
+    }
+
     /// Loads the DeltaTable state for the given version.
     pub async fn load_version(&mut self, version: i64) -> Result<(), DeltaTableError> {
         if let Some(snapshot) = &self.state {
@@ -366,6 +418,102 @@ impl DeltaTable {
         self.update_incremental(Some(version)).await
     }
 
+    /// Loads the table states for all versions between `start_version` and `end_version`
+    /// (both inclusive).
+    ///
+    /// The log is only replayed once, so this is considerably faster than calling
+    /// [`DeltaTable::load_version`] for each version in the range.
+    pub async fn load_version_range(
+        &self,
+        start_version: i64,
+        end_version: i64,
+    ) -> Result<Vec<DeltaTableState>, DeltaTableError> {
+        DeltaTableState::try_new_range(
+            self.log_store.clone(),
+            self.config.clone(),
+            start_version,
+            end_version,
+        )
+        .await
+    }
+
+    /// Returns the earliest version of the table with a commit file in the log
+    async fn get_earliest_version(&self) -> DeltaResult<i64> {
+        let object_store = self.object_store();
+        // the log of most tables still starts at the first version
+        match object_store.head(&commit_uri_from_version(0)).await {
+            Ok(_) => return Ok(0),
+            Err(ObjectStoreError::NotFound { .. }) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let mut earliest_version = None;
+        let mut files = object_store.list(Some(self.log_store.log_path()));
+        while let Some(obj_meta) = files.next().await {
+            let obj_meta = obj_meta?;
+            if obj_meta.location.extension() != Some("json") {
+                continue;
+            }
+            if let Some(version) = extract_version_from_filename(obj_meta.location.as_ref()) {
+                earliest_version = Some(earliest_version.map_or(version, |v: i64| v.min(version)));
+            }
+        }
+        earliest_version.ok_or_else(|| DeltaTableError::not_a_table(self.table_uri()))
+    }
+
+    /// Read the in-commit timestamp from the commit info of a commit, if it has one
+    async fn read_in_commit_timestamp(&self, version: i64) -> DeltaResult<Option<i64>> {
+        let bytes = self
+            .log_store
+            .read_commit_entry(version)
+            .await?
+            .ok_or(DeltaTableError::InvalidVersion(version))?;
+        // tables enabling in-commit timestamps write the commit info as the first action
+        let first_line = bytes
+            .split(|byte| *byte == b'\n')
+            .next()
+            .unwrap_or_default();
+        Ok(match serde_json::from_slice::<Action>(first_line) {
+            Ok(Action::CommitInfo(commit_info)) => commit_info
+                .info
+                .get(IN_COMMIT_TIMESTAMP)
+                .and_then(|timestamp| timestamp.as_i64()),
+            _ => None,
+        })
+    }
+
+    /// First version timed by its in-commit timestamp according to the table configuration at
+    /// `version`, or `None` if in-commit timestamps are not enabled
+    async fn in_commit_timestamps_since(&self, version: i64) -> DeltaResult<Option<i64>> {
+        if let Some(state) = self.state.as_ref().filter(|s| s.version() == version) {
+            return Ok(state.table_config().in_commit_timestamps_since());
+        }
+        let snapshot = Snapshot::try_new(
+            &Path::default(),
+            self.object_store(),
+            self.config.clone(),
+            Some(version),
+        )
+        .await?;
+        Ok(snapshot.table_config().in_commit_timestamps_since())
+    }
+
+    /// Timestamp of a commit in milliseconds, which is its in-commit timestamp if the commit is
+    /// at or after `in_commit_timestamps_since` and has one, and the modification time of the
+    /// commit file otherwise
+    async fn get_commit_timestamp(
+        &self,
+        version: i64,
+        in_commit_timestamps_since: Option<i64>,
+    ) -> DeltaResult<i64> {
+        if in_commit_timestamps_since.is_some_and(|since| version >= since) {
+            if let Some(timestamp) = self.read_in_commit_timestamp(version).await? {
+                return Ok(timestamp);
+            }
+        }
+        self.get_version_timestamp(version).await
+    }
+
     pub(crate) async fn get_version_timestamp(&self, version: i64) -> Result<i64, DeltaTableError> {
         match self
             .state
@@ -400,6 +548,23 @@ impl DeltaTable {
         Ok(infos.into_iter().flatten().collect())
     }
 
+    /// Stream the versions and commit infos of the commits up to the loaded version, newest
+    /// first, selected by `options`.
+    ///
+    /// Unlike [`DeltaTable::history`], the commit files are read as the stream is consumed, so
+    /// large histories can be paged through with a limit and offset. Commits whose log files
+    /// have been cleaned up are not returned.
+    pub async fn history_stream(
+        &self,
+        options: HistoryOptions,
+    ) -> DeltaResult<BoxStream<'_, DeltaResult<(i64, CommitInfo)>>> {
+        self.snapshot()?
+            .snapshot
+            .snapshot()
+            .commit_history(self.object_store(), options)
+            .await
+    }
+
     /// Obtain Add actions for files that match the filter
     pub fn get_active_add_actions_by_partitions<'a>(
         &'a self,
@@ -411,6 +576,25 @@ impl DeltaTable {
             .get_active_add_actions_by_partitions(filters)
     }
 
+    /// Returns the partitions of the loaded table state along with the number of files, the
+    /// total size in bytes and the number of records derived from file statistics.
+    pub fn get_partitions(&self) -> DeltaResult<Vec<PartitionStatistics>> {
+        self.state
+            .as_ref()
+            .ok_or(DeltaTableError::NoMetadata)?
+            .partitions()
+    }
+
+    /// Returns the number of files, their total size, the total number of records and the
+    /// bounds of every column with statistics, computed from the log without reading data.
+    pub fn get_stats_summary(&self) -> DeltaResult<StatsSummary> {
+        Ok(self
+            .state
+            .as_ref()
+            .ok_or(DeltaTableError::NoMetadata)?
+            .stats_summary())
+    }
+
     /// Returns the file list tracked in current table state filtered by provided
     /// `PartitionFilter`s.
     pub fn get_files_by_partitions(
@@ -447,6 +631,19 @@ impl DeltaTable {
             .file_paths_iter())
     }
 
+    /// Stream the add actions of the files present in the loaded version from the log.
+    ///
+    /// The actions are read from the checkpoint and commit files as the stream is consumed,
+    /// which keeps memory usage low for tables with a very large number of files. Load the
+    /// table with [`without_files`](crate::DeltaTableBuilder::without_files) to not hold the
+    /// files in the state as well.
+    pub fn stream_file_actions(&self) -> DeltaResult<BoxStream<'_, DeltaResult<Add>>> {
+        self.state
+            .as_ref()
+            .ok_or(DeltaTableError::NoMetadata)?
+            .stream_file_actions(self.object_store())
+    }
+
     /// Returns a URIs for all active files present in the current table version.
     pub fn get_file_uris(&self) -> DeltaResult<impl Iterator<Item = String> + '_> {
         Ok(self
@@ -462,6 +659,14 @@ impl DeltaTable {
         self.state.as_ref().map(|s| s.files_count()).unwrap_or(0)
     }
 
+    /// Estimate the memory held by the loaded state - returns zero sizes if no metadata is loaded
+    pub fn memory_usage(&self) -> SnapshotMemoryUsage {
+        self.state
+            .as_ref()
+            .map(|s| s.memory_usage())
+            .unwrap_or_default()
+    }
+
     /// Returns the currently loaded state snapshot.
     pub fn snapshot(&self) -> DeltaResult<&DeltaTableState> {
         self.state.as_ref().ok_or(DeltaTableError::NotInitialized)
@@ -481,8 +686,8 @@ impl DeltaTable {
         Ok(self.snapshot()?.metadata())
     }
 
-    /// Returns the current version of the DeltaTable based on the loaded metadata.
-    pub fn get_app_transaction_version(&self) -> HashMap<String, Transaction> {
+    /// Returns the latest application transaction recorded for every application.
+    pub fn get_app_transactions(&self) -> HashMap<String, Transaction> {
         self.state
             .as_ref()
             .and_then(|s| s.app_transaction_version().ok())
@@ -490,6 +695,23 @@ impl DeltaTable {
             .unwrap_or_default()
     }
 
+    /// Returns the latest application transaction recorded for every application.
+    #[deprecated(since = "0.18.2", note = "Please use get_app_transactions")]
+    pub fn get_app_transaction_version(&self) -> HashMap<String, Transaction> {
+        self.get_app_transactions()
+    }
+
+    /// Returns the latest transaction version committed by the application with the given `app_id`.
+    ///
+    /// Returns `None` if the application has not yet committed a transaction to the table.
+    pub fn get_app_transaction_version_for(&self, app_id: impl AsRef<str>) -> Option<i64> {
+        self.state
+            .as_ref()
+            .and_then(|s| s.app_transaction_version().ok())
+            .and_then(|mut it| it.find(|t| t.app_id == app_id.as_ref()))
+            .map(|t| t.version)
+    }
+
     /// Return table schema parsed from transaction log. Return None if table hasn't been loaded or
     /// no metadata was found in the log.
     pub fn schema(&self) -> Option<&StructType> {
@@ -505,60 +727,60 @@ impl DeltaTable {
     /// Time travel Delta table to the latest version that's created at or before provided
     /// `datetime` argument.
     ///
-    /// Internally, this methods performs a binary search on all Delta transaction logs.
+    /// The version is found by a binary search over the commit timestamps, which are the
+    /// in-commit timestamps for commits since `delta.enableInCommitTimestamps` was enabled, and
+    /// the modification times of the commit files otherwise. An error is returned if `datetime` is before the creation of the table
+    /// or after the latest commit. If the first commits have been removed from the log, the
+    /// earliest version still in the log is loaded for timestamps before it.
     pub async fn load_with_datetime(
         &mut self,
         datetime: DateTime<Utc>,
     ) -> Result<(), DeltaTableError> {
-        let mut min_version: i64 = -1;
-        let log_store = self.log_store();
-        let prefix = Some(log_store.log_path());
-        let offset_path = commit_uri_from_version(min_version);
-        let object_store = log_store.object_store();
-        let mut files = object_store.list_with_offset(prefix, &offset_path);
-
-        while let Some(obj_meta) = files.next().await {
-            let obj_meta = obj_meta?;
-            if let Some(log_version) = extract_version_from_filename(obj_meta.location.as_ref()) {
-                if min_version == -1 {
-                    min_version = log_version
-                } else {
-                    min_version = min(min_version, log_version);
-                }
-            }
-            if min_version == 0 {
-                break;
-            }
-        }
-        let mut max_version = self.get_latest_version().await?;
-        let mut version = min_version;
-        let lowest_table_version = min_version;
         let target_ts = datetime.timestamp_millis();
-
-        // binary search
-        while min_version <= max_version {
-            let pivot = (max_version + min_version) / 2;
-            version = pivot;
-            let pts = self.get_version_timestamp(pivot).await?;
-            match pts.cmp(&target_ts) {
-                Ordering::Equal => {
-                    break;
-                }
-                Ordering::Less => {
-                    min_version = pivot + 1;
-                }
-                Ordering::Greater => {
-                    max_version = pivot - 1;
-                    version = max_version
-                }
-            }
+        let earliest_version = self.get_earliest_version().await?;
+        let latest_version = self.get_latest_version().await?;
+        let in_commit_timestamps_since = self.in_commit_timestamps_since(latest_version).await?;
+        let committed_at =
+            |timestamp: i64| DateTime::from_timestamp_millis(timestamp).unwrap_or_default();
+
+        let earliest_ts = self
+            .get_commit_timestamp(earliest_version, in_commit_timestamps_since)
+            .await?;
+        if target_ts < earliest_ts && earliest_version == 0 {
+            return Err(DeltaTableError::TimestampBeforeEarliestVersion {
+                timestamp: datetime,
+                version: earliest_version,
+                committed_at: committed_at(earliest_ts),
+            });
+        }
+        let latest_ts = self
+            .get_commit_timestamp(latest_version, in_commit_timestamps_since)
+            .await?;
+        if target_ts > latest_ts {
+            return Err(DeltaTableError::TimestampAfterLatestVersion {
+                timestamp: datetime,
+                version: latest_version,
+                committed_at: committed_at(latest_ts),
+            });
         }
 
-        if version < lowest_table_version {
-            version = lowest_table_version;
+        // binary search for the latest version committed at or before the target timestamp,
+        // the earliest version is only committed later if its predecessors have been removed
+        let (mut low, mut high) = (earliest_version, latest_version);
+        while low < high {
+            let pivot = low + (high - low + 1) / 2;
+            if self
+                .get_commit_timestamp(pivot, in_commit_timestamps_since)
+                .await?
+                <= target_ts
+            {
+                low = pivot;
+            } else {
+                high = pivot - 1;
+            }
         }
 
-        self.load_version(version).await
+        self.load_version(low).await
     }
 }
 
@@ -1,24 +1,57 @@
 //! The module for delta table state.
 
-use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
 use chrono::Utc;
-use futures::TryStreamExt;
+use delta_kernel::expressions::Scalar;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use indexmap::IndexMap;
 use object_store::{path::Path, ObjectStore};
 use serde::{Deserialize, Serialize};
 
 use super::{config::TableConfig, get_partition_col_data_types, DeltaTableConfig};
+use crate::kernel::scalars::ScalarExt;
 #[cfg(test)]
 use crate::kernel::Action;
 use crate::kernel::{
     ActionType, Add, AddCDCFile, DataType, EagerSnapshot, LogDataHandler, LogicalFile, Metadata,
-    Protocol, Remove, StructType, Transaction,
+    Protocol, Remove, SnapshotMemoryUsage, StructType, Transaction,
 };
 use crate::logstore::LogStore;
-use crate::partitions::{DeltaTablePartition, PartitionFilter};
+use crate::partitions::{DeltaTablePartition, PartitionFilter, ScalarHelper};
 use crate::{DeltaResult, DeltaTableError};
 
+/// Aggregated statistics of the files in a single partition of a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionStatistics {
+    /// Serialized value of every partition column, `None` for null partition values.
+    pub partition_values: IndexMap<String, Option<String>>,
+    /// Number of files in the partition.
+    pub num_files: usize,
+    /// Total size of the files in the partition in bytes.
+    pub size_bytes: i64,
+    /// Total number of records in the partition, `None` if any file lacks statistics.
+    pub num_records: Option<usize>,
+}
+
+/// Summary of the files and column statistics of a table state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsSummary {
+    /// Number of files in the table.
+    pub num_files: usize,
+    /// Total size of the files in bytes.
+    pub size_bytes: i64,
+    /// Total number of records, `None` if any file lacks statistics.
+    pub num_records: Option<usize>,
+    /// Smallest value of every column with statistics, keyed by the dot separated column path.
+    pub min_values: IndexMap<String, Scalar>,
+    /// Largest value of every column with statistics, keyed by the dot separated column path.
+    pub max_values: IndexMap<String, Scalar>,
+}
+
 /// State snapshot currently held by the Delta Table instance.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -45,6 +78,48 @@ impl DeltaTableState {
         Ok(Self { snapshot })
     }
 
+    /// Create a new DeltaTableState retaining only the files matching all `partition_filters`
+    pub async fn try_new_with_partition_filters(
+        table_root: &Path,
+        store: Arc<dyn ObjectStore>,
+        config: DeltaTableConfig,
+        version: Option<i64>,
+        partition_filters: Vec<PartitionFilter>,
+    ) -> DeltaResult<Self> {
+        let snapshot = EagerSnapshot::try_new_with_partition_filters(
+            table_root,
+            store.clone(),
+            config,
+            version,
+            HashSet::from([ActionType::Txn]),
+            partition_filters,
+        )
+        .await?;
+        Ok(Self { snapshot })
+    }
+
+    /// Create the table states for every version between `start_version` and `end_version`
+    /// (both inclusive), sharing the log replay of the base version across all states.
+    pub async fn try_new_range(
+        log_store: Arc<dyn LogStore>,
+        config: DeltaTableConfig,
+        start_version: i64,
+        end_version: i64,
+    ) -> DeltaResult<Vec<Self>> {
+        let snapshots = EagerSnapshot::try_new_range(
+            log_store,
+            config,
+            start_version,
+            end_version,
+            HashSet::from([ActionType::Txn]),
+        )
+        .await?;
+        Ok(snapshots
+            .into_iter()
+            .map(|snapshot| Self { snapshot })
+            .collect())
+    }
+
     /// Return table version
     pub fn version(&self) -> i64 {
         self.snapshot.version()
@@ -99,19 +174,34 @@ impl DeltaTableState {
         self.snapshot.log_data()
     }
 
-    /// Full list of tombstones (remove actions) representing files removed from table state).
-    pub async fn all_tombstones(
+    /// Stream the tombstones (remove actions) representing files removed from table state.
+    ///
+    /// Tombstones are not retained in the loaded state, since only few operations like vacuum
+    /// or checkpointing need them. They are read from the log on demand instead, so consumers
+    /// should filter the stream rather than collect all tombstones of large tables.
+    pub fn tombstones_stream(
         &self,
         store: Arc<dyn ObjectStore>,
-    ) -> DeltaResult<impl Iterator<Item = Remove>> {
+    ) -> DeltaResult<BoxStream<'_, DeltaResult<Remove>>> {
         Ok(self
             .snapshot
             .snapshot()
             .tombstones(store)?
+            .map_ok(|removes| futures::stream::iter(removes.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed())
+    }
+
+    /// Full list of tombstones (remove actions) representing files removed from table state).
+    pub async fn all_tombstones(
+        &self,
+        store: Arc<dyn ObjectStore>,
+    ) -> DeltaResult<impl Iterator<Item = Remove>> {
+        Ok(self
+            .tombstones_stream(store)?
             .try_collect::<Vec<_>>()
             .await?
-            .into_iter()
-            .flatten())
+            .into_iter())
     }
 
     /// List of unexpired tombstones (remove actions) representing files removed from table state.
@@ -125,10 +215,14 @@ impl DeltaTableState {
                 .table_config()
                 .deleted_file_retention_duration()
                 .as_millis() as i64;
-        let tombstones = self.all_tombstones(store).await?.collect::<Vec<_>>();
-        Ok(tombstones
-            .into_iter()
-            .filter(move |t| t.deletion_timestamp.unwrap_or(0) > retention_timestamp))
+        let tombstones = self
+            .tombstones_stream(store)?
+            .try_filter(|t| {
+                futures::future::ready(t.deletion_timestamp.unwrap_or(0) > retention_timestamp)
+            })
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(tombstones.into_iter())
     }
 
     /// Full list of add actions representing all parquet files that are part of the current
@@ -143,6 +237,26 @@ impl DeltaTableState {
         self.snapshot.file_actions()
     }
 
+    /// Stream the add actions of the current table state from the log, without holding all of
+    /// them in memory at once.
+    pub fn stream_file_actions(
+        &self,
+        store: Arc<dyn ObjectStore>,
+    ) -> DeltaResult<BoxStream<'_, DeltaResult<Add>>> {
+        Ok(self
+            .snapshot
+            .snapshot()
+            .stream_file_actions(store)?
+            .map_ok(|adds| futures::stream::iter(adds.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed())
+    }
+
+    /// Estimate the memory held by the current table state
+    pub fn memory_usage(&self) -> SnapshotMemoryUsage {
+        self.snapshot.memory_usage()
+    }
+
     /// Get the number of files in the current table state
     pub fn files_count(&self) -> usize {
         self.snapshot.files_count()
@@ -186,6 +300,27 @@ impl DeltaTableState {
         self.snapshot.table_config()
     }
 
+    /// The configuration the state was loaded with
+    pub fn load_config(&self) -> &DeltaTableConfig {
+        self.snapshot.load_config()
+    }
+
+    /// Ensure the state holds all files of the table, as required by `operation`.
+    ///
+    /// States loaded without files or with partition filters only hold a part of the table,
+    /// operations rewriting or removing files must not act on them.
+    pub(crate) fn ensure_all_files(&self, operation: &str) -> DeltaResult<()> {
+        if !self.load_config().require_files {
+            return Err(DeltaTableError::NotInitializedWithFiles(operation.into()));
+        }
+        if !self.snapshot.partition_filters().is_empty() {
+            return Err(DeltaTableError::LoadedWithPartitionFilters(
+                operation.into(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Obtain the Eager snapshot of the state
     pub fn snapshot(&self) -> &EagerSnapshot {
         &self.snapshot
@@ -201,6 +336,83 @@ impl DeltaTableState {
         Ok(())
     }
 
+    /// Update the state of the table to the given version by applying only the commits newer
+    /// than the loaded version.
+    pub async fn update_from_commits(
+        &mut self,
+        log_store: Arc<dyn LogStore>,
+        version: Option<i64>,
+    ) -> Result<(), DeltaTableError> {
+        self.snapshot
+            .update_from_commits(log_store, version)
+            .await?;
+        Ok(())
+    }
+
+    /// Aggregate the files of the current table state by partition.
+    ///
+    /// Partitions are ordered by their serialized partition values. Unpartitioned tables yield a
+    /// single entry without partition values, unless the table has no files.
+    pub fn partitions(&self) -> DeltaResult<Vec<PartitionStatistics>> {
+        let mut partitions: BTreeMap<Vec<(String, Option<String>)>, PartitionStatistics> =
+            BTreeMap::new();
+        for file in self.log_data() {
+            let values = file
+                .partition_values()?
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        Scalar::Null(_) => None,
+                        value => Some(value.serialize()),
+                    };
+                    (name.to_string(), value)
+                })
+                .collect::<Vec<_>>();
+            let num_records = file.num_records();
+            let entry = partitions
+                .entry(values.clone())
+                .or_insert_with(|| PartitionStatistics {
+                    partition_values: values.into_iter().collect(),
+                    num_files: 0,
+                    size_bytes: 0,
+                    num_records: Some(0),
+                });
+            entry.num_files += 1;
+            entry.size_bytes += file.size();
+            entry.num_records = entry.num_records.zip(num_records).map(|(a, b)| a + b);
+        }
+        Ok(partitions.into_values().collect())
+    }
+
+    /// Summarize the files of the current table state from the statistics in the log.
+    ///
+    /// Column bounds only consider files with statistics for the column and are missing for
+    /// columns without statistics in any file.
+    pub fn stats_summary(&self) -> StatsSummary {
+        let mut summary = StatsSummary {
+            num_files: 0,
+            size_bytes: 0,
+            num_records: Some(0),
+            min_values: IndexMap::new(),
+            max_values: IndexMap::new(),
+        };
+        for file in self.log_data() {
+            summary.num_files += 1;
+            summary.size_bytes += file.size();
+            summary.num_records = summary
+                .num_records
+                .zip(file.num_records())
+                .map(|(a, b)| a + b);
+            if let Some(min_values) = file.min_values() {
+                merge_bounds(&mut summary.min_values, "", min_values, Ordering::Less);
+            }
+            if let Some(max_values) = file.max_values() {
+                merge_bounds(&mut summary.max_values, "", max_values, Ordering::Greater);
+            }
+        }
+        summary
+    }
+
     /// Obtain Add actions for files that match the filter
     pub fn get_active_add_actions_by_partitions<'a>(
         &'a self,
@@ -249,3 +461,36 @@ impl DeltaTableState {
         }))
     }
 }
+
+/// Merge the column values of a statistics struct into `bounds`, keeping the value that
+/// compares as `keep` for every column.
+fn merge_bounds(
+    bounds: &mut IndexMap<String, Scalar>,
+    prefix: &str,
+    value: Scalar,
+    keep: Ordering,
+) {
+    match value {
+        Scalar::Null(_) => {}
+        Scalar::Struct(data) => {
+            for (field, value) in data.fields().iter().zip(data.values()) {
+                let name = if prefix.is_empty() {
+                    field.name().to_string()
+                } else {
+                    format!("{prefix}.{}", field.name())
+                };
+                merge_bounds(bounds, &name, value.clone(), keep);
+            }
+        }
+        value => match bounds.get_mut(prefix) {
+            Some(current) => {
+                if ScalarHelper(&value).partial_cmp(&ScalarHelper(current)) == Some(keep) {
+                    *current = value;
+                }
+            }
+            None => {
+                bounds.insert(prefix.to_string(), value);
+            }
+        },
+    }
+}
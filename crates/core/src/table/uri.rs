@@ -0,0 +1,194 @@
+//! Normalization of user supplied table locations
+//!
+//! Tables may be located by urls of a registered object store, `file://` urls, absolute or
+//! relative local paths, Windows paths with a drive letter, or Databricks `dbfs:/` uris which
+//! refer to the local mount of DBFS under `/dbfs`. [`parse_table_location`] maps all of these
+//! to either a local path or a url, so that every entry point resolves locations the same way.
+
+use std::path::{Path, PathBuf};
+
+use url::Url;
+
+use crate::storage::factories;
+use crate::{DeltaResult, DeltaTableError};
+
+/// Mount point of the Databricks file system on cluster nodes
+const DBFS_MOUNT: &str = "/dbfs";
+
+/// Url scheme of tables opened with [`DeltaTableBuilder::from_object_store`]
+///
+/// [`DeltaTableBuilder::from_object_store`]: crate::DeltaTableBuilder::from_object_store
+pub(crate) const OBJECT_STORE_SCHEME: &str = "delta-rs-store";
+
+/// A table location resolved by [`parse_table_location`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableLocation {
+    /// A path on the local file system, possibly relative
+    LocalPath(PathBuf),
+    /// A url handled by a registered object store factory
+    Url(Url),
+}
+
+/// Resolve a user supplied table location.
+///
+/// Will return an error for urls with a scheme no object store factory is registered for.
+pub fn parse_table_location(table_uri: impl AsRef<str>) -> DeltaResult<TableLocation> {
+    let table_uri = table_uri.as_ref();
+    let Ok(url) = Url::parse(table_uri) else {
+        return Ok(TableLocation::LocalPath(PathBuf::from(table_uri)));
+    };
+
+    match url.scheme() {
+        #[cfg(not(target_arch = "wasm32"))]
+        "file" => url
+            .to_file_path()
+            .map(TableLocation::LocalPath)
+            .map_err(|err| {
+                DeltaTableError::InvalidTableLocation(format!(
+                    "Invalid table location: {table_uri}\nError: {err:?}"
+                ))
+            }),
+        #[cfg(target_arch = "wasm32")]
+        "file" => Err(DeltaTableError::InvalidTableLocation(format!(
+            "Invalid table location: {table_uri}\nError: no local file system on this target"
+        ))),
+        "dbfs" => {
+            if url.host_str().is_some_and(|host| !host.is_empty()) {
+                return Err(DeltaTableError::InvalidTableLocation(format!(
+                    "Invalid table location: {table_uri}\nError: dbfs uris must not have a host"
+                )));
+            }
+            let path = percent_encoding::percent_decode_str(url.path())
+                .decode_utf8()
+                .map_err(|err| {
+                    DeltaTableError::InvalidTableLocation(format!(
+                        "Invalid table location: {table_uri}\nError: {err}"
+                    ))
+                })?;
+            Ok(TableLocation::LocalPath(
+                Path::new(DBFS_MOUNT).join(path.trim_start_matches('/')),
+            ))
+        }
+        // the store of these tables is passed in directly instead of created by a factory
+        OBJECT_STORE_SCHEME => Ok(TableLocation::Url(url)),
+        scheme
+            if factories()
+                .iter()
+                .any(|entry| entry.key().scheme() == scheme) =>
+        {
+            Ok(TableLocation::Url(url))
+        }
+        // NOTE this check is required to support absolute windows paths which may properly parse as url
+        // we assume here that a single character scheme is a windows drive letter
+        scheme if scheme.len() == 1 => Ok(TableLocation::LocalPath(PathBuf::from(table_uri))),
+        scheme => Err(DeltaTableError::InvalidTableLocation(format!(
+            "Unknown scheme: {scheme}"
+        ))),
+    }
+}
+
+/// Convert a local path into a `file://` url of the canonicalized directory.
+///
+/// The directory is created first if `create` is set, otherwise it has to exist.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn local_path_to_url(path: &Path, create: bool) -> DeltaResult<Url> {
+    if !path.exists() {
+        if !create {
+            return Err(DeltaTableError::InvalidTableLocation(format!(
+                "Local path \"{}\" does not exist or you don't have access!",
+                path.display(),
+            )));
+        }
+        std::fs::create_dir_all(path).map_err(|err| {
+            DeltaTableError::InvalidTableLocation(format!(
+                "Could not create local directory: {}\nError: {err:?}",
+                path.display()
+            ))
+        })?;
+    }
+    let canonical = std::fs::canonicalize(path).map_err(|err| {
+        DeltaTableError::InvalidTableLocation(format!(
+            "Invalid table location: {}\nError: {err:?}",
+            path.display()
+        ))
+    })?;
+    Url::from_directory_path(canonical).map_err(|_| {
+        DeltaTableError::InvalidTableLocation(format!(
+            "Could not construct a URL from canonicalized path: {}.\n\
+            Something must be very wrong with the table path.",
+            path.display()
+        ))
+    })
+}
+
+/// Local paths can not be resolved on targets without a file system, e.g. WebAssembly.
+#[cfg(target_arch = "wasm32")]
+pub fn local_path_to_url(path: &Path, _create: bool) -> DeltaResult<Url> {
+    Err(DeltaTableError::InvalidTableLocation(format!(
+        "Local path \"{}\" can not be accessed, no local file system on this target",
+        path.display()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_local_paths() {
+        assert_eq!(
+            parse_table_location("relative/path").unwrap(),
+            TableLocation::LocalPath(PathBuf::from("relative/path"))
+        );
+        #[cfg(not(windows))]
+        {
+            assert_eq!(
+                parse_table_location("/abs/path").unwrap(),
+                TableLocation::LocalPath(PathBuf::from("/abs/path"))
+            );
+            assert_eq!(
+                parse_table_location("file:///abs/my%20table").unwrap(),
+                TableLocation::LocalPath(PathBuf::from("/abs/my table"))
+            );
+        }
+        assert_eq!(
+            parse_table_location("C:/data/table").unwrap(),
+            TableLocation::LocalPath(PathBuf::from("C:/data/table"))
+        );
+    }
+
+    #[test]
+    fn test_parse_dbfs() {
+        for uri in ["dbfs:/mnt/table", "dbfs:///mnt/table"] {
+            assert_eq!(
+                parse_table_location(uri).unwrap(),
+                TableLocation::LocalPath(PathBuf::from("/dbfs/mnt/table"))
+            );
+        }
+        assert_eq!(
+            parse_table_location("dbfs:/mnt/my%20table").unwrap(),
+            TableLocation::LocalPath(PathBuf::from("/dbfs/mnt/my table"))
+        );
+        assert!(parse_table_location("dbfs://host/mnt/table").is_err());
+    }
+
+    #[test]
+    fn test_parse_urls() {
+        let url = Url::parse("memory:///table").unwrap();
+        assert_eq!(
+            parse_table_location(url.as_str()).unwrap(),
+            TableLocation::Url(url)
+        );
+        assert!(parse_table_location("unknown://bucket/table").is_err());
+    }
+
+    #[test]
+    fn test_local_path_to_url() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("new table");
+        assert!(local_path_to_url(&path, false).is_err());
+        let url = local_path_to_url(&path, true).unwrap();
+        assert!(path.exists());
+        assert!(url.as_str().ends_with("/new%20table/"));
+    }
+}
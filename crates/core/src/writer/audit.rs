@@ -0,0 +1,187 @@
+//! Audit columns recording the provenance of ingested data.
+//!
+//! Tables are declared with the audit columns when they are created, e.g. with
+//! [`CreateBuilder::with_audit_columns`](crate::operations::create::CreateBuilder::with_audit_columns).
+//! Writers configured with [`AuditColumns`] then fill them for every row they write, so that
+//! pipelines do not need to add them to their data up front.
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow_schema::{ArrowError, Field as ArrowField, Schema as ArrowSchema};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::kernel::{DataType, PrimitiveType, StructField};
+
+/// Time at which the row was written
+pub const INGESTED_AT_COLUMN: &str = "_ingested_at";
+/// File the row was read from, if any
+pub const SOURCE_FILE_COLUMN: &str = "_source_file";
+/// Identifier shared by all rows written together
+pub const BATCH_ID_COLUMN: &str = "_batch_id";
+
+/// Values of the audit columns appended to written data
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditColumns {
+    pub(crate) ingested_at: Option<DateTime<Utc>>,
+    source_file: Option<String>,
+    batch_id: String,
+}
+
+impl Default for AuditColumns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuditColumns {
+    /// Create audit columns with a random batch id.
+    ///
+    /// Unless set explicitly, the ingestion time is the time at which rows are appended.
+    pub fn new() -> Self {
+        Self {
+            ingested_at: None,
+            source_file: None,
+            batch_id: Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Set the file the data was read from
+    pub fn with_source_file(mut self, source_file: impl Into<String>) -> Self {
+        self.source_file = Some(source_file.into());
+        self
+    }
+
+    /// Set the identifier of the batch
+    pub fn with_batch_id(mut self, batch_id: impl Into<String>) -> Self {
+        self.batch_id = batch_id.into();
+        self
+    }
+
+    /// Set the ingestion time recorded for all rows
+    pub fn with_ingested_at(mut self, ingested_at: DateTime<Utc>) -> Self {
+        self.ingested_at = Some(ingested_at);
+        self
+    }
+
+    /// The file the data was read from
+    pub fn source_file(&self) -> Option<&str> {
+        self.source_file.as_deref()
+    }
+
+    /// The identifier of the batch
+    pub fn batch_id(&self) -> &str {
+        &self.batch_id
+    }
+
+    /// Columns to declare in the schema of tables written with audit columns
+    pub fn fields() -> Vec<StructField> {
+        vec![
+            StructField::new(
+                INGESTED_AT_COLUMN,
+                DataType::Primitive(PrimitiveType::Timestamp),
+                false,
+            ),
+            StructField::new(
+                SOURCE_FILE_COLUMN,
+                DataType::Primitive(PrimitiveType::String),
+                true,
+            ),
+            StructField::new(
+                BATCH_ID_COLUMN,
+                DataType::Primitive(PrimitiveType::String),
+                false,
+            ),
+        ]
+    }
+
+    /// Whether `name` is one of the audit columns
+    pub fn is_audit_column(name: &str) -> bool {
+        [INGESTED_AT_COLUMN, SOURCE_FILE_COLUMN, BATCH_ID_COLUMN].contains(&name)
+    }
+
+    /// Append the audit columns to `batch`, replacing any audit columns it already contains
+    pub fn append_to(&self, batch: &RecordBatch) -> Result<RecordBatch, ArrowError> {
+        let num_rows = batch.num_rows();
+        let ingested_at = self.ingested_at.unwrap_or_else(Utc::now);
+        let audit_columns: [ArrayRef; 3] = [
+            Arc::new(
+                TimestampMicrosecondArray::from_value(ingested_at.timestamp_micros(), num_rows)
+                    .with_timezone("UTC"),
+            ),
+            Arc::new(StringArray::from(vec![
+                self.source_file.as_deref();
+                num_rows
+            ])),
+            Arc::new(StringArray::from(vec![
+                Some(self.batch_id.as_str());
+                num_rows
+            ])),
+        ];
+
+        let schema = batch.schema();
+        let (mut fields, mut columns): (Vec<_>, Vec<_>) = schema
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .filter(|(field, _)| !Self::is_audit_column(field.name()))
+            .map(|(field, column)| (field.clone(), column.clone()))
+            .unzip();
+        for (field, column) in Self::fields().iter().zip(audit_columns) {
+            fields.push(Arc::new(ArrowField::try_from(field)?));
+            columns.push(column);
+        }
+        let schema = ArrowSchema::new_with_metadata(fields, schema.metadata().clone());
+        RecordBatch::try_new(Arc::new(schema), columns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::TimestampMicrosecondType;
+    use arrow_array::{Array, Int32Array};
+    use arrow_schema::DataType as ArrowDataType;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_append_audit_columns() {
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "id",
+            ArrowDataType::Int32,
+            false,
+        )]));
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2]))]).unwrap();
+        let ingested_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let audit = AuditColumns::new()
+            .with_batch_id("batch-1")
+            .with_ingested_at(ingested_at);
+
+        let result = audit.append_to(&batch).unwrap();
+        assert_eq!(result.num_columns(), 4);
+        let timestamps = result
+            .column_by_name(INGESTED_AT_COLUMN)
+            .unwrap()
+            .as_primitive::<TimestampMicrosecondType>();
+        assert_eq!(timestamps.value(1), ingested_at.timestamp_micros());
+        let source_files = result.column_by_name(SOURCE_FILE_COLUMN).unwrap();
+        assert_eq!(source_files.null_count(), 2);
+        let batch_ids = result
+            .column_by_name(BATCH_ID_COLUMN)
+            .unwrap()
+            .as_string::<i32>();
+        assert_eq!(batch_ids.value(0), "batch-1");
+
+        // Appending again replaces the existing audit columns
+        let audit = audit.with_source_file("input.csv");
+        let result = audit.append_to(&result).unwrap();
+        assert_eq!(result.num_columns(), 4);
+        let source_files = result
+            .column_by_name(SOURCE_FILE_COLUMN)
+            .unwrap()
+            .as_string::<i32>();
+        assert_eq!(source_files.value(0), "input.csv");
+    }
+}
@@ -0,0 +1,169 @@
+//! Policies controlling when buffered writers flush their data to storage.
+//!
+//! The [`RecordBatchWriter`](super::RecordBatchWriter) and [`JsonWriter`](super::JsonWriter)
+//! buffer data in memory until they are flushed. A [`FlushPolicy`] is consulted after every
+//! write with the current [`BufferStats`] of the writer, and can trigger a flush once enough
+//! data has been buffered. Files flushed by a policy are kept by the writer and returned by the
+//! next call to [`DeltaWriter::flush`](super::DeltaWriter::flush), so they are committed by
+//! [`DeltaWriter::flush_and_commit`](super::DeltaWriter::flush_and_commit) or
+//! [`DeltaWriter::close`](super::DeltaWriter::close).
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::kernel::Add;
+
+/// Data currently buffered by a writer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferStats {
+    /// Size of the in memory buffers in bytes
+    pub bytes: usize,
+    /// Number of buffered rows
+    pub rows: usize,
+    /// Number of partitions with buffered data
+    pub partitions: usize,
+    /// Time elapsed since the first write after the last flush
+    pub age: Duration,
+}
+
+/// Policy deciding when a writer flushes its buffers
+pub trait FlushPolicy: Debug + Send + Sync {
+    /// Whether the writer should flush its buffers
+    fn should_flush(&self, stats: &BufferStats) -> bool;
+
+    /// Called with the files written to storage whenever the writer flushes
+    fn on_flush(&self, _adds: &[Add]) {}
+}
+
+/// Flush once any of the configured thresholds is reached
+#[derive(Debug, Clone, Default)]
+pub struct ThresholdFlushPolicy {
+    max_bytes: Option<usize>,
+    max_rows: Option<usize>,
+    max_age: Option<Duration>,
+    max_partitions: Option<usize>,
+}
+
+impl ThresholdFlushPolicy {
+    /// Create a new policy without any thresholds
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flush once the buffers hold at least `max_bytes` bytes
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Flush once at least `max_rows` rows are buffered
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Flush once data has been buffered for at least `max_age`
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Flush once data is buffered for at least `max_partitions` partitions
+    pub fn with_max_partitions(mut self, max_partitions: usize) -> Self {
+        self.max_partitions = Some(max_partitions);
+        self
+    }
+}
+
+impl FlushPolicy for ThresholdFlushPolicy {
+    fn should_flush(&self, stats: &BufferStats) -> bool {
+        if stats.rows == 0 {
+            return false;
+        }
+        let reached = |threshold: Option<usize>, value: usize| {
+            threshold
+                .map(|threshold| value >= threshold)
+                .unwrap_or(false)
+        };
+        reached(self.max_bytes, stats.bytes)
+            || reached(self.max_rows, stats.rows)
+            || reached(self.max_partitions, stats.partitions)
+            || self
+                .max_age
+                .map(|max_age| stats.age >= max_age)
+                .unwrap_or(false)
+    }
+}
+
+/// Flush bookkeeping shared by the writers
+#[derive(Debug, Default)]
+pub(crate) struct FlushState {
+    pub(crate) policy: Option<Arc<dyn FlushPolicy>>,
+    /// Time of the first write after the last flush
+    pub(crate) buffered_since: Option<Instant>,
+    /// Files flushed by the policy which have not been returned by a flush yet
+    pub(crate) pending: Vec<Add>,
+}
+
+impl FlushState {
+    /// Record a write to the buffers
+    pub(crate) fn record_write(&mut self) {
+        self.buffered_since.get_or_insert_with(Instant::now);
+    }
+
+    /// Time elapsed since the first write after the last flush
+    pub(crate) fn age(&self) -> Duration {
+        self.buffered_since
+            .map(|since| since.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Whether the policy asks for the buffers to be flushed
+    pub(crate) fn should_flush(&self, stats: &BufferStats) -> bool {
+        self.policy
+            .as_ref()
+            .map(|policy| policy.should_flush(stats))
+            .unwrap_or(false)
+    }
+
+    /// Record the files written by a flush of the buffers
+    pub(crate) fn flushed(&mut self, adds: &[Add]) {
+        self.buffered_since = None;
+        if let (Some(policy), false) = (&self.policy, adds.is_empty()) {
+            policy.on_flush(adds);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_policy() {
+        let stats = BufferStats {
+            bytes: 100,
+            rows: 10,
+            partitions: 2,
+            age: Duration::from_secs(5),
+        };
+
+        assert!(!ThresholdFlushPolicy::new().should_flush(&stats));
+        assert!(ThresholdFlushPolicy::new()
+            .with_max_bytes(100)
+            .should_flush(&stats));
+        assert!(!ThresholdFlushPolicy::new()
+            .with_max_rows(11)
+            .should_flush(&stats));
+        assert!(ThresholdFlushPolicy::new()
+            .with_max_rows(11)
+            .with_max_partitions(2)
+            .should_flush(&stats));
+        assert!(ThresholdFlushPolicy::new()
+            .with_max_age(Duration::from_secs(1))
+            .should_flush(&stats));
+        assert!(!ThresholdFlushPolicy::new()
+            .with_max_bytes(0)
+            .should_flush(&BufferStats::default()));
+    }
+}
@@ -18,14 +18,17 @@ use serde_json::Value;
 use tracing::{info, warn};
 use uuid::Uuid;
 
+use super::flush::FlushState;
 use super::stats::create_add;
 use super::utils::{
     arrow_schema_without_partitions, next_data_path, record_batch_from_message,
     record_batch_without_partitions,
 };
-use super::{DeltaWriter, DeltaWriterError, WriteMode};
+use super::{BufferStats, DeltaWriter, DeltaWriterError, FlushPolicy, WriteMode};
 use crate::errors::DeltaTableError;
-use crate::kernel::{scalars::ScalarExt, Add, PartitionsExt, StructType};
+use crate::kernel::{scalars::ScalarExt, Add, DataType, PartitionsExt, StructType};
+use crate::metrics::{self, Counter};
+use crate::operations::get_num_idx_cols_and_stats_columns;
 use crate::storage::ObjectStoreRetryExt;
 use crate::table::builder::DeltaTableBuilder;
 use crate::table::config::DEFAULT_NUM_INDEX_COLS;
@@ -34,13 +37,53 @@ use crate::DeltaTable;
 
 type BadValue = (Value, ParquetError);
 
+/// How a [`JsonWriter`] handles records which can not be written to the table
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BadRecordPolicy {
+    /// Fail the write on the first malformed record
+    #[default]
+    FailFast,
+    /// Skip malformed records and write all other records
+    Drop,
+    /// Skip malformed records and collect them as [`DeadLetter`]s, which are handed to the
+    /// caller by [`JsonWriter::take_dead_letters`]
+    DeadLetter,
+}
+
+/// A record rejected by a [`JsonWriter`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadLetter {
+    /// The rejected record
+    pub record: Value,
+    /// Why the record could not be written
+    pub reason: String,
+}
+
+/// Number of records handled by a [`JsonWriter`] since it was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonWriterMetrics {
+    /// Number of records written to the buffers
+    pub num_written_records: usize,
+    /// Number of malformed records skipped by [`BadRecordPolicy::Drop`]
+    pub num_dropped_records: usize,
+    /// Number of malformed records collected by [`BadRecordPolicy::DeadLetter`]
+    pub num_dead_letter_records: usize,
+}
+
 /// Writes messages to a delta lake table.
 pub struct JsonWriter {
     storage: Arc<dyn ObjectStore>,
+    schema: StructType,
     arrow_schema_ref: Arc<arrow_schema::Schema>,
     writer_properties: WriterProperties,
     partition_columns: Vec<String>,
     arrow_writers: HashMap<String, DataArrowWriter>,
+    flush_state: FlushState,
+    num_indexed_cols: i32,
+    stats_columns: Option<Vec<String>>,
+    bad_record_policy: BadRecordPolicy,
+    dead_letters: Vec<DeadLetter>,
+    metrics: JsonWriterMetrics,
 }
 
 /// Writes messages to an underlying arrow buffer.
@@ -51,6 +94,7 @@ pub(crate) struct DataArrowWriter {
     arrow_writer: ArrowWriter<ShareableBuffer>,
     partition_values: IndexMap<String, Scalar>,
     buffered_record_batch_count: usize,
+    buffered_rows: usize,
 }
 
 impl DataArrowWriter {
@@ -127,6 +171,7 @@ impl DataArrowWriter {
         match result {
             Ok(_) => {
                 self.buffered_record_batch_count += 1;
+                self.buffered_rows += record_batch.num_rows();
                 Ok(())
             }
             // If a write fails we need to reset the state of the DeltaArrowWriter
@@ -167,6 +212,7 @@ impl DataArrowWriter {
             arrow_writer,
             partition_values,
             buffered_record_batch_count,
+            buffered_rows: 0,
         })
     }
 
@@ -199,10 +245,17 @@ impl JsonWriter {
 
         Ok(Self {
             storage: storage.object_store(),
+            schema: StructType::try_from(schema.as_ref())?,
             arrow_schema_ref: schema,
             writer_properties,
             partition_columns: partition_columns.unwrap_or_default(),
             arrow_writers: HashMap::new(),
+            flush_state: FlushState::default(),
+            num_indexed_cols: DEFAULT_NUM_INDEX_COLS,
+            stats_columns: None,
+            bad_record_policy: BadRecordPolicy::default(),
+            dead_letters: Vec::new(),
+            metrics: JsonWriterMetrics::default(),
         })
     }
 
@@ -210,9 +263,14 @@ impl JsonWriter {
     pub fn for_table(table: &DeltaTable) -> Result<JsonWriter, DeltaTableError> {
         // Initialize an arrow schema ref from the delta table schema
         let metadata = table.metadata()?;
-        let arrow_schema = <ArrowSchema as TryFrom<&StructType>>::try_from(&metadata.schema()?)?;
+        let schema = metadata.schema()?;
+        let arrow_schema = <ArrowSchema as TryFrom<&StructType>>::try_from(&schema)?;
         let arrow_schema_ref = Arc::new(arrow_schema);
         let partition_columns = metadata.partition_columns.clone();
+        let (num_indexed_cols, stats_columns) = get_num_idx_cols_and_stats_columns(
+            Some(table.snapshot()?.table_config()),
+            HashMap::new(),
+        );
 
         // Initialize writer properties for the underlying arrow writer
         let writer_properties = WriterProperties::builder()
@@ -222,10 +280,17 @@ impl JsonWriter {
 
         Ok(Self {
             storage: table.object_store(),
+            schema,
             arrow_schema_ref,
             writer_properties,
             partition_columns,
             arrow_writers: HashMap::new(),
+            flush_state: FlushState::default(),
+            num_indexed_cols,
+            stats_columns,
+            bad_record_policy: BadRecordPolicy::default(),
+            dead_letters: Vec::new(),
+            metrics: JsonWriterMetrics::default(),
         })
     }
 
@@ -243,9 +308,69 @@ impl JsonWriter {
             .sum()
     }
 
+    /// Returns statistics about the data currently held in the buffers.
+    pub fn buffer_stats(&self) -> BufferStats {
+        BufferStats {
+            bytes: self.buffer_len(),
+            rows: self.arrow_writers.values().map(|w| w.buffered_rows).sum(),
+            partitions: self.arrow_writers.len(),
+            age: self.flush_state.age(),
+        }
+    }
+
+    /// Sets the policy deciding when the buffers are flushed to storage while writing.
+    ///
+    /// The files flushed by the policy are returned by the next call to [`DeltaWriter::flush`].
+    pub fn with_flush_policy(mut self, policy: Arc<dyn FlushPolicy>) -> Self {
+        self.flush_state.policy = Some(policy);
+        self
+    }
+
+    /// Collect file statistics for the first `num_indexed_cols` columns only.
+    ///
+    /// Defaults to `delta.dataSkippingNumIndexedCols` of the table. Use `-1` to collect
+    /// statistics for all columns, and `0` to not collect any column statistics.
+    pub fn with_num_indexed_cols(mut self, num_indexed_cols: i32) -> Self {
+        self.num_indexed_cols = num_indexed_cols;
+        self
+    }
+
+    /// Collect file statistics for the given columns only, e.g. to leave out large binary
+    /// columns whose statistics bloat the log.
+    ///
+    /// Defaults to `delta.dataSkippingStatsColumns` of the table, and takes precedence over
+    /// [`with_num_indexed_cols`](Self::with_num_indexed_cols).
+    pub fn with_stats_columns(
+        mut self,
+        stats_columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.stats_columns = Some(stats_columns.into_iter().map(|c| c.into()).collect());
+        self
+    }
+
+    /// Sets how records which can not be written to the table are handled.
+    ///
+    /// Defaults to [`BadRecordPolicy::FailFast`], which fails the write on the first malformed
+    /// record.
+    pub fn with_bad_record_policy(mut self, policy: BadRecordPolicy) -> Self {
+        self.bad_record_policy = policy;
+        self
+    }
+
+    /// Returns the number of records written and rejected since the writer was created.
+    pub fn metrics(&self) -> JsonWriterMetrics {
+        self.metrics
+    }
+
+    /// Takes the records rejected by [`BadRecordPolicy::DeadLetter`] since the last call.
+    pub fn take_dead_letters(&mut self) -> Vec<DeadLetter> {
+        std::mem::take(&mut self.dead_letters)
+    }
+
     /// Resets internal state.
     pub fn reset(&mut self) {
         self.arrow_writers.clear();
+        self.flush_state.buffered_since = None;
     }
 
     /// Returns the arrow schema representation of the delta table schema defined for the wrapped
@@ -254,6 +379,90 @@ impl JsonWriter {
         self.arrow_schema_ref.clone()
     }
 
+    /// Writes the parquet buffers of all partitions to storage.
+    async fn write_files(&mut self) -> Result<Vec<Add>, DeltaTableError> {
+        let writers = std::mem::take(&mut self.arrow_writers);
+        let mut actions = Vec::new();
+
+        for (_, writer) in writers {
+            let metadata = writer.arrow_writer.close()?;
+            let prefix = writer.partition_values.hive_partition_path();
+            let prefix = Path::parse(prefix)?;
+            let uuid = Uuid::new_v4();
+
+            let path = next_data_path(&prefix, 0, &uuid, &writer.writer_properties);
+            let obj_bytes = Bytes::from(writer.buffer.to_vec());
+            let file_size = obj_bytes.len() as i64;
+            self.storage
+                .put_with_retries(&path, obj_bytes.into(), 15)
+                .await?;
+            metrics::increment(Counter::FilesWritten, 1, &[]);
+            metrics::increment(Counter::BytesWritten, file_size as u64, &[]);
+
+            actions.push(create_add(
+                &writer.partition_values,
+                path.to_string(),
+                file_size,
+                &metadata,
+                self.num_indexed_cols,
+                &self.stats_columns,
+            )?);
+        }
+        Ok(actions)
+    }
+
+    /// Removes the records which can not be decoded into the arrow schema of the table,
+    /// handing them to the bad record policy.
+    fn reject_bad_records(&mut self, values: Vec<Value>) -> Vec<Value> {
+        let mut good = Vec::with_capacity(values.len());
+        let mut bad = Vec::new();
+        for value in values {
+            match validate_record(&value, &self.schema) {
+                Ok(_) => good.push(value),
+                Err(err) => bad.push((value, err.to_string())),
+            }
+        }
+
+        // only decode the records one by one when some of them are malformed
+        let arrow_schema = self.arrow_schema();
+        if !good.is_empty() && record_batch_from_message(arrow_schema.clone(), &good).is_err() {
+            good = good
+                .into_iter()
+                .filter_map(|value| {
+                    match record_batch_from_message(
+                        arrow_schema.clone(),
+                        std::slice::from_ref(&value),
+                    ) {
+                        Ok(_) => Some(value),
+                        Err(err) => {
+                            bad.push((value, err.to_string()));
+                            None
+                        }
+                    }
+                })
+                .collect();
+        }
+
+        for (record, reason) in bad {
+            self.reject(record, reason);
+        }
+        good
+    }
+
+    fn reject(&mut self, record: Value, reason: String) {
+        match self.bad_record_policy {
+            BadRecordPolicy::FailFast => {}
+            BadRecordPolicy::Drop => {
+                warn!("Dropping record {record} which can not be written: {reason}");
+                self.metrics.num_dropped_records += 1;
+            }
+            BadRecordPolicy::DeadLetter => {
+                self.metrics.num_dead_letter_records += 1;
+                self.dead_letters.push(DeadLetter { record, reason });
+            }
+        }
+    }
+
     fn divide_by_partition_values(
         &self,
         records: Vec<Value>,
@@ -303,6 +512,16 @@ impl DeltaWriter<Vec<Value>> for JsonWriter {
         if mode != WriteMode::Default {
             warn!("The JsonWriter does not currently support non-default write modes, falling back to default mode");
         }
+        let values = match self.bad_record_policy {
+            BadRecordPolicy::FailFast => {
+                for value in values.iter() {
+                    validate_record(value, &self.schema)?;
+                }
+                values
+            }
+            BadRecordPolicy::Drop | BadRecordPolicy::DeadLetter => self.reject_bad_records(values),
+        };
+        let num_values = values.len();
         let mut partial_writes: Vec<(Value, ParquetError)> = Vec::new();
         let arrow_schema = self.arrow_schema();
         let divided = self.divide_by_partition_values(values)?;
@@ -329,6 +548,13 @@ impl DeltaWriter<Vec<Value>> for JsonWriter {
             }
         }
 
+        self.metrics.num_written_records += num_values - partial_writes.len();
+        if self.bad_record_policy != BadRecordPolicy::FailFast {
+            for (record, err) in partial_writes.drain(..) {
+                self.reject(record, err.to_string());
+            }
+        }
+
         if !partial_writes.is_empty() {
             return Err(DeltaWriterError::PartialParquetWrite {
                 sample_error: match &partial_writes[0].1 {
@@ -346,36 +572,22 @@ impl DeltaWriter<Vec<Value>> for JsonWriter {
             .into());
         }
 
+        self.flush_state.record_write();
+        if self.flush_state.should_flush(&self.buffer_stats()) {
+            let adds = self.write_files().await?;
+            self.flush_state.flushed(&adds);
+            self.flush_state.pending.extend(adds);
+        }
+
         Ok(())
     }
 
     /// Writes the existing parquet bytes to storage and resets internal state to handle another file.
     async fn flush(&mut self) -> Result<Vec<Add>, DeltaTableError> {
-        let writers = std::mem::take(&mut self.arrow_writers);
-        let mut actions = Vec::new();
-
-        for (_, writer) in writers {
-            let metadata = writer.arrow_writer.close()?;
-            let prefix = writer.partition_values.hive_partition_path();
-            let prefix = Path::parse(prefix)?;
-            let uuid = Uuid::new_v4();
-
-            let path = next_data_path(&prefix, 0, &uuid, &writer.writer_properties);
-            let obj_bytes = Bytes::from(writer.buffer.to_vec());
-            let file_size = obj_bytes.len() as i64;
-            self.storage
-                .put_with_retries(&path, obj_bytes.into(), 15)
-                .await?;
-
-            actions.push(create_add(
-                &writer.partition_values,
-                path.to_string(),
-                file_size,
-                &metadata,
-                DEFAULT_NUM_INDEX_COLS,
-                &None,
-            )?);
-        }
+        let adds = self.write_files().await?;
+        self.flush_state.flushed(&adds);
+        let mut actions = std::mem::take(&mut self.flush_state.pending);
+        actions.extend(adds);
         Ok(actions)
     }
 }
@@ -414,6 +626,100 @@ fn quarantine_failed_parquet_rows(
     Ok((good, bad))
 }
 
+/// Validate a record against the table schema before it is converted to arrow.
+///
+/// Fields of the record which are not part of the schema are ignored, while unknown fields of
+/// nested structs and maps are rejected as they would be dropped silently.
+fn validate_record(record: &Value, schema: &StructType) -> Result<(), DeltaWriterError> {
+    let Some(object) = record.as_object() else {
+        return Err(DeltaWriterError::InvalidRecord(record.to_string()));
+    };
+    for field in schema.fields() {
+        validate_value(
+            field.name(),
+            object.get(field.name()).unwrap_or(&Value::Null),
+            field.data_type(),
+            field.is_nullable(),
+        )?;
+    }
+    Ok(())
+}
+
+fn validate_value(
+    path: &str,
+    value: &Value,
+    data_type: &DataType,
+    nullable: bool,
+) -> Result<(), DeltaWriterError> {
+    let invalid = |reason: String| DeltaWriterError::InvalidValue {
+        column: path.to_string(),
+        reason,
+    };
+    if value.is_null() {
+        return if nullable {
+            Ok(())
+        } else {
+            Err(invalid("null value in a non-nullable field".to_string()))
+        };
+    }
+    match data_type {
+        // primitive values are validated while decoding them into arrow arrays
+        DataType::Primitive(_) => Ok(()),
+        DataType::Struct(struct_type) => {
+            let object = value
+                .as_object()
+                .ok_or_else(|| invalid(format!("expected a struct, found {value}")))?;
+            if let Some(key) = object.keys().find(|k| struct_type.field(k).is_none()) {
+                return Err(invalid(format!("unknown field {key}")));
+            }
+            for field in struct_type.fields() {
+                validate_value(
+                    &format!("{path}.{}", field.name()),
+                    object.get(field.name()).unwrap_or(&Value::Null),
+                    field.data_type(),
+                    field.is_nullable(),
+                )?;
+            }
+            Ok(())
+        }
+        DataType::Array(array_type) => {
+            let elements = value
+                .as_array()
+                .ok_or_else(|| invalid(format!("expected a list, found {value}")))?;
+            for element in elements {
+                validate_value(
+                    &format!("{path}.element"),
+                    element,
+                    array_type.element_type(),
+                    array_type.contains_null(),
+                )?;
+            }
+            Ok(())
+        }
+        DataType::Map(map_type) => {
+            // json objects can only hold string keys, which are decoded into the key type
+            let entries = value
+                .as_object()
+                .ok_or_else(|| invalid(format!("expected a map, found {value}")))?;
+            if !matches!(map_type.key_type(), DataType::Primitive(_)) {
+                return Err(invalid(format!(
+                    "map keys of type {} can not be written from json",
+                    map_type.key_type()
+                )));
+            }
+            for value in entries.values() {
+                validate_value(
+                    &format!("{path}.value"),
+                    value,
+                    map_type.value_type(),
+                    map_type.value_contains_null(),
+                )?;
+            }
+            Ok(())
+        }
+    }
+}
+
 fn extract_partition_values(
     partition_cols: &[String],
     record_batch: &RecordBatch,
@@ -564,6 +870,191 @@ mod tests {
         ));
     }
 
+    fn nested_schema() -> StructType {
+        use crate::kernel::{ArrayType, MapType, PrimitiveType, StructField};
+
+        let address = StructType::new(vec![
+            StructField::new("city", DataType::STRING, true),
+            StructField::new(
+                "geo",
+                DataType::Struct(Box::new(StructType::new(vec![
+                    StructField::new("lat", DataType::DOUBLE, false),
+                    StructField::new("lon", DataType::DOUBLE, false),
+                ]))),
+                true,
+            ),
+        ]);
+        StructType::new(vec![
+            StructField::new("id", DataType::LONG, false),
+            StructField::new("address", DataType::Struct(Box::new(address.clone())), true),
+            StructField::new(
+                "tags",
+                DataType::Array(Box::new(ArrayType::new(DataType::STRING, false))),
+                true,
+            ),
+            StructField::new(
+                "history",
+                DataType::Array(Box::new(ArrayType::new(
+                    DataType::Struct(Box::new(address)),
+                    true,
+                ))),
+                true,
+            ),
+            StructField::new(
+                "scores",
+                DataType::Map(Box::new(MapType::new(
+                    DataType::Primitive(PrimitiveType::String),
+                    DataType::Array(Box::new(ArrayType::new(DataType::INTEGER, true))),
+                    true,
+                ))),
+                true,
+            ),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_write_nested_values() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let schema = nested_schema();
+        let path = table_dir.path().to_str().unwrap().to_string();
+
+        let arrow_schema = <ArrowSchema as TryFrom<&StructType>>::try_from(&schema).unwrap();
+        let mut writer = JsonWriter::try_new(path, Arc::new(arrow_schema), None, None).unwrap();
+
+        let data = vec![
+            serde_json::json!({
+                "id": 1,
+                "address": {"city": "Amsterdam", "geo": {"lat": 52.37, "lon": 4.89}},
+                "tags": ["a", "b"],
+                "history": [{"city": "Utrecht"}, null],
+                "scores": {"math": [1, 2, null], "art": null}
+            }),
+            serde_json::json!({"id": 2}),
+        ];
+        writer.write(data).await.unwrap();
+        let add_actions = writer.flush().await.unwrap();
+        assert_eq!(add_actions.len(), 1);
+
+        let file = File::open(table_dir.path().join(&add_actions[0].path)).unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        let columns = reader
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|desc| desc.path().string())
+            .collect::<Vec<_>>();
+        // lists and maps hold a single leaf column per nested field of their elements
+        assert_eq!(columns.len(), 10);
+        assert_eq!(
+            columns[..4],
+            ["id", "address.city", "address.geo.lat", "address.geo.lon"]
+        );
+    }
+
+    #[test]
+    fn test_validate_nested_values() {
+        let schema = nested_schema();
+        let invalid = |value: Value, column: &str| {
+            let err = validate_record(&value, &schema).unwrap_err();
+            assert!(
+                matches!(&err, DeltaWriterError::InvalidValue { column: c, .. } if c == column),
+                "{err}"
+            );
+        };
+
+        invalid(serde_json::json!({"address": {}}), "id");
+        invalid(
+            serde_json::json!({"id": 1, "address": "Amsterdam"}),
+            "address",
+        );
+        invalid(
+            serde_json::json!({"id": 1, "address": {"zip": "1011"}}),
+            "address",
+        );
+        invalid(
+            serde_json::json!({"id": 1, "address": {"geo": {"lat": 1.0}}}),
+            "address.geo.lon",
+        );
+        invalid(serde_json::json!({"id": 1, "tags": "a"}), "tags");
+        invalid(
+            serde_json::json!({"id": 1, "tags": ["a", null]}),
+            "tags.element",
+        );
+        invalid(
+            serde_json::json!({"id": 1, "history": [{"city": 1, "street": "x"}]}),
+            "history.element",
+        );
+        invalid(serde_json::json!({"id": 1, "scores": [1]}), "scores");
+        invalid(
+            serde_json::json!({"id": 1, "scores": {"math": 1}}),
+            "scores.value",
+        );
+
+        // unknown top level fields are ignored
+        validate_record(&serde_json::json!({"id": 1, "name": "x"}), &schema).unwrap();
+        assert!(matches!(
+            validate_record(&serde_json::json!([1]), &schema),
+            Err(DeltaWriterError::InvalidRecord(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_bad_record_policies() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let path = table_dir.path().to_str().unwrap().to_string();
+        let arrow_schema =
+            <ArrowSchema as TryFrom<&StructType>>::try_from(&get_delta_schema()).unwrap();
+        let data = || {
+            vec![
+                serde_json::json!({"id": "A", "value": 1, "modified": "2021-02-01"}),
+                serde_json::json!({"id": "B", "value": "abc", "modified": "2021-02-01"}),
+                serde_json::json!(["C", 3, "2021-02-01"]),
+                serde_json::json!({"id": "D", "value": 4, "modified": "2021-02-02"}),
+            ]
+        };
+
+        let mut writer =
+            JsonWriter::try_new(path.clone(), Arc::new(arrow_schema.clone()), None, None).unwrap();
+        assert!(writer.write(data()).await.is_err());
+        assert_eq!(writer.metrics(), JsonWriterMetrics::default());
+
+        let mut writer =
+            JsonWriter::try_new(path.clone(), Arc::new(arrow_schema.clone()), None, None)
+                .unwrap()
+                .with_bad_record_policy(BadRecordPolicy::Drop);
+        writer.write(data()).await.unwrap();
+        assert_eq!(
+            writer.metrics(),
+            JsonWriterMetrics {
+                num_written_records: 2,
+                num_dropped_records: 2,
+                num_dead_letter_records: 0,
+            }
+        );
+        assert!(writer.take_dead_letters().is_empty());
+        let add_actions = writer.flush().await.unwrap();
+        assert_eq!(add_actions[0].get_stats().unwrap().unwrap().num_records, 2);
+
+        let mut writer = JsonWriter::try_new(path, Arc::new(arrow_schema), None, None)
+            .unwrap()
+            .with_bad_record_policy(BadRecordPolicy::DeadLetter);
+        writer.write(data()).await.unwrap();
+        assert_eq!(writer.metrics().num_written_records, 2);
+        assert_eq!(writer.metrics().num_dead_letter_records, 2);
+        let dead_letters = writer.take_dead_letters();
+        assert_eq!(
+            dead_letters
+                .iter()
+                .map(|letter| letter.record.clone())
+                .collect::<Vec<_>>(),
+            vec![data()[2].clone(), data()[1].clone()]
+        );
+        assert!(writer.take_dead_letters().is_empty());
+    }
+
     // The following sets of tests are related to #1386 and mergeSchema support
     // <https://github.com/delta-io/delta-rs/issues/1386>
     mod schema_evolution {
@@ -12,10 +12,14 @@ use crate::operations::transaction::CommitBuilder;
 use crate::protocol::{ColumnCountStat, DeltaOperation, SaveMode};
 use crate::DeltaTable;
 
-pub use json::JsonWriter;
+pub use audit::AuditColumns;
+pub use flush::{BufferStats, FlushPolicy, ThresholdFlushPolicy};
+pub use json::{BadRecordPolicy, DeadLetter, JsonWriter, JsonWriterMetrics};
 pub use record_batch::RecordBatchWriter;
-pub use stats::create_add;
+pub use stats::{create_add, MAX_VALUE_TAG_PREFIX, MIN_VALUE_TAG_PREFIX};
 
+pub mod audit;
+pub mod flush;
 pub mod json;
 pub mod record_batch;
 pub(crate) mod stats;
@@ -48,6 +52,15 @@ pub(crate) enum DeltaWriterError {
     #[error("Record {0} is not a JSON object")]
     InvalidRecord(String),
 
+    /// A value of a record does not match the type of its column in the table schema.
+    #[error("Invalid value for column {column}: {reason}")]
+    InvalidValue {
+        /// The path of the column within the schema
+        column: String,
+        /// Why the value is rejected
+        reason: String,
+    },
+
     /// Indicates that a partial write was performed and error records were discarded.
     #[error("Failed to write some values to parquet. Sample error: {sample_error}.")]
     PartialParquetWrite {
@@ -113,7 +126,7 @@ impl From<DeltaWriterError> for DeltaTableError {
         match err {
             DeltaWriterError::Arrow { source } => DeltaTableError::Arrow { source },
             DeltaWriterError::Io { source } => DeltaTableError::Io { source },
-            DeltaWriterError::ObjectStore { source } => DeltaTableError::ObjectStore { source },
+            DeltaWriterError::ObjectStore { source } => source.into(),
             DeltaWriterError::Parquet { source } => DeltaTableError::Parquet { source },
             DeltaWriterError::DeltaTable(e) => e,
             DeltaWriterError::SchemaMismatch { .. } => DeltaTableError::SchemaMismatch {
@@ -146,6 +159,8 @@ pub trait DeltaWriter<T> {
 
     /// Flush the internal write buffers to files in the delta table folder structure.
     /// The corresponding delta [`Add`] actions are returned and should be committed via a transaction.
+    ///
+    /// Files flushed earlier by a [`FlushPolicy`] are returned as well.
     async fn flush(&mut self) -> Result<Vec<Add>, DeltaTableError>;
 
     /// Flush the internal write buffers to files in the delta table folder structure.
@@ -154,6 +169,21 @@ pub trait DeltaWriter<T> {
         let adds: Vec<_> = self.flush().await?.drain(..).map(Action::Add).collect();
         flush_and_commit(adds, table).await
     }
+
+    /// Flush the remaining buffered data and commit all files written by the writer, consuming it.
+    ///
+    /// Returns the version of the table once all data is committed. No new version is created
+    /// if nothing has been written since the last commit.
+    async fn close(mut self, table: &mut DeltaTable) -> Result<i64, DeltaTableError>
+    where
+        Self: Sized + Send,
+    {
+        let adds: Vec<_> = self.flush().await?.drain(..).map(Action::Add).collect();
+        if adds.is_empty() {
+            return Ok(table.version());
+        }
+        flush_and_commit(adds, table).await
+    }
 }
 
 /// Method for flushing to be used by writers
@@ -22,15 +22,19 @@ use parquet::{basic::Compression, file::properties::WriterProperties};
 use tracing::log::*;
 use uuid::Uuid;
 
+use super::flush::FlushState;
 use super::stats::create_add;
 use super::utils::{
     arrow_schema_without_partitions, next_data_path, record_batch_without_partitions,
     ShareableBuffer,
 };
-use super::{DeltaWriter, DeltaWriterError, WriteMode};
+use super::{AuditColumns, BufferStats, DeltaWriter, DeltaWriterError, FlushPolicy, WriteMode};
 use crate::errors::DeltaTableError;
 use crate::kernel::{scalars::ScalarExt, Action, Add, PartitionsExt, StructType};
-use crate::operations::cast::merge_schema;
+use crate::metrics::{self, Counter};
+use crate::operations::cast::{canonical_schema, cast_to_canonical_types, merge_schema};
+use crate::operations::get_num_idx_cols_and_stats_columns;
+use crate::protocol::SchemaMode;
 use crate::storage::ObjectStoreRetryExt;
 use crate::table::builder::DeltaTableBuilder;
 use crate::table::config::DEFAULT_NUM_INDEX_COLS;
@@ -43,8 +47,13 @@ pub struct RecordBatchWriter {
     original_schema_ref: ArrowSchemaRef,
     writer_properties: WriterProperties,
     should_evolve: bool,
+    schema_mode: Option<SchemaMode>,
     partition_columns: Vec<String>,
     arrow_writers: HashMap<String, PartitionWriter>,
+    audit_columns: Option<AuditColumns>,
+    flush_state: FlushState,
+    num_indexed_cols: i32,
+    stats_columns: Option<Vec<String>>,
 }
 
 impl std::fmt::Debug for RecordBatchWriter {
@@ -72,6 +81,9 @@ impl RecordBatchWriter {
             .set_compression(Compression::SNAPPY)
             .build();
 
+        // large, view and unsigned types are cast to the types written for their delta type
+        let schema = canonical_schema(&schema);
+
         Ok(Self {
             storage,
             arrow_schema_ref: schema.clone(),
@@ -79,7 +91,12 @@ impl RecordBatchWriter {
             writer_properties,
             partition_columns: partition_columns.unwrap_or_default(),
             should_evolve: false,
+            schema_mode: None,
             arrow_writers: HashMap::new(),
+            audit_columns: None,
+            flush_state: FlushState::default(),
+            num_indexed_cols: DEFAULT_NUM_INDEX_COLS,
+            stats_columns: None,
         })
     }
 
@@ -91,6 +108,10 @@ impl RecordBatchWriter {
             <ArrowSchema as TryFrom<&StructType>>::try_from(&metadata.schema()?.clone())?;
         let arrow_schema_ref = Arc::new(arrow_schema);
         let partition_columns = metadata.partition_columns.clone();
+        let (num_indexed_cols, stats_columns) = get_num_idx_cols_and_stats_columns(
+            Some(table.snapshot()?.table_config()),
+            HashMap::new(),
+        );
 
         // Initialize writer properties for the underlying arrow writer
         let writer_properties = WriterProperties::builder()
@@ -105,7 +126,12 @@ impl RecordBatchWriter {
             writer_properties,
             partition_columns,
             should_evolve: false,
+            schema_mode: None,
             arrow_writers: HashMap::new(),
+            audit_columns: None,
+            flush_state: FlushState::default(),
+            num_indexed_cols,
+            stats_columns,
         })
     }
 
@@ -123,9 +149,20 @@ impl RecordBatchWriter {
             .sum()
     }
 
+    /// Returns statistics about the data currently held in the buffers.
+    pub fn buffer_stats(&self) -> BufferStats {
+        BufferStats {
+            bytes: self.buffer_len(),
+            rows: self.arrow_writers.values().map(|w| w.buffered_rows).sum(),
+            partitions: self.arrow_writers.len(),
+            age: self.flush_state.age(),
+        }
+    }
+
     /// Resets internal state.
     pub fn reset(&mut self) {
         self.arrow_writers.clear();
+        self.flush_state.buffered_since = None;
     }
 
     /// Returns the arrow schema representation of the delta table schema defined for the wrapped
@@ -169,6 +206,54 @@ impl RecordBatchWriter {
         self
     }
 
+    /// Sets the policy deciding when the buffers are flushed to storage while writing.
+    ///
+    /// The files flushed by the policy are returned by the next call to [`DeltaWriter::flush`].
+    pub fn with_flush_policy(mut self, policy: Arc<dyn FlushPolicy>) -> Self {
+        self.flush_state.policy = Some(policy);
+        self
+    }
+
+    /// Sets how batches whose schema differs from the table schema are handled.
+    ///
+    /// With [`SchemaMode::Merge`] all writes behave like [`WriteMode::MergeSchema`]: batches
+    /// may add nullable columns, which are committed as a new table schema by
+    /// [`DeltaWriter::flush_and_commit`]. Overwriting the schema is not supported by this writer.
+    pub fn with_schema_mode(mut self, schema_mode: SchemaMode) -> Self {
+        self.schema_mode = Some(schema_mode);
+        self
+    }
+
+    /// Appends the given audit columns to all batches written.
+    ///
+    /// The schema of the table must declare the audit columns, see [`AuditColumns::fields`].
+    pub fn with_audit_columns(mut self, audit_columns: AuditColumns) -> Self {
+        self.audit_columns = Some(audit_columns);
+        self
+    }
+
+    /// Collect file statistics for the first `num_indexed_cols` columns only.
+    ///
+    /// Defaults to `delta.dataSkippingNumIndexedCols` of the table. Use `-1` to collect
+    /// statistics for all columns, and `0` to not collect any column statistics.
+    pub fn with_num_indexed_cols(mut self, num_indexed_cols: i32) -> Self {
+        self.num_indexed_cols = num_indexed_cols;
+        self
+    }
+
+    /// Collect file statistics for the given columns only, e.g. to leave out large binary
+    /// columns whose statistics bloat the log.
+    ///
+    /// Defaults to `delta.dataSkippingStatsColumns` of the table, and takes precedence over
+    /// [`with_num_indexed_cols`](Self::with_num_indexed_cols).
+    pub fn with_stats_columns(
+        mut self,
+        stats_columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.stats_columns = Some(stats_columns.into_iter().map(|c| c.into()).collect());
+        self
+    }
+
     fn divide_by_partition_values(
         &mut self,
         values: &RecordBatch,
@@ -179,6 +264,36 @@ impl RecordBatchWriter {
             values,
         )
     }
+
+    /// Writes the parquet buffers of all partitions to storage.
+    async fn write_files(&mut self) -> Result<Vec<Add>, DeltaTableError> {
+        let writers = std::mem::take(&mut self.arrow_writers);
+        let mut actions = Vec::new();
+
+        for (_, writer) in writers {
+            let metadata = writer.arrow_writer.close()?;
+            let prefix = Path::parse(writer.partition_values.hive_partition_path())?;
+            let uuid = Uuid::new_v4();
+            let path = next_data_path(&prefix, 0, &uuid, &writer.writer_properties);
+            let obj_bytes = Bytes::from(writer.buffer.to_vec());
+            let file_size = obj_bytes.len() as i64;
+            self.storage
+                .put_with_retries(&path, obj_bytes.into(), 15)
+                .await?;
+            metrics::increment(Counter::FilesWritten, 1, &[]);
+            metrics::increment(Counter::BytesWritten, file_size as u64, &[]);
+
+            actions.push(create_add(
+                &writer.partition_values,
+                path.to_string(),
+                file_size,
+                &metadata,
+                self.num_indexed_cols,
+                &self.stats_columns,
+            )?);
+        }
+        Ok(actions)
+    }
 }
 
 #[async_trait::async_trait]
@@ -194,15 +309,42 @@ impl DeltaWriter<RecordBatch> for RecordBatchWriter {
         values: RecordBatch,
         mode: WriteMode,
     ) -> Result<(), DeltaTableError> {
+        let mode = match self.schema_mode {
+            Some(SchemaMode::Merge) => WriteMode::MergeSchema,
+            Some(SchemaMode::Overwrite) => {
+                return Err(DeltaTableError::Generic(
+                    "Overwriting the schema is not supported by the RecordBatchWriter".to_owned(),
+                ))
+            }
+            None => mode,
+        };
         if mode == WriteMode::MergeSchema && !self.partition_columns.is_empty() {
             return Err(DeltaTableError::Generic(
                 "Merging Schemas with partition columns present is currently unsupported"
                     .to_owned(),
             ));
         }
-        // Set the should_evolve flag for later in case the writer should perform schema evolution
-        // on its flush_and_commit
-        self.should_evolve = mode == WriteMode::MergeSchema;
+        if mode == WriteMode::MergeSchema {
+            // existing files do not hold values for added columns, so they have to be nullable
+            if values.schema().fields().iter().any(|f| {
+                !f.is_nullable() && self.arrow_schema_ref.field_with_name(f.name()).is_err()
+            }) {
+                return Err(DeltaWriterError::SchemaMismatch {
+                    record_batch_schema: values.schema(),
+                    expected_schema: self.arrow_schema_ref.clone(),
+                }
+                .into());
+            }
+            // Set the should_evolve flag for later in case the writer should perform schema
+            // evolution on its flush_and_commit
+            self.should_evolve = true;
+        }
+
+        let values = match &self.audit_columns {
+            Some(audit_columns) => audit_columns.append_to(&values)?,
+            None => values,
+        };
+        let values = cast_to_canonical_types(&values)?;
 
         for result in self.divide_by_partition_values(&values)? {
             let schema = self
@@ -210,34 +352,22 @@ impl DeltaWriter<RecordBatch> for RecordBatchWriter {
                 .await?;
             self.arrow_schema_ref = schema;
         }
+        self.flush_state.record_write();
+
+        if self.flush_state.should_flush(&self.buffer_stats()) {
+            let adds = self.write_files().await?;
+            self.flush_state.flushed(&adds);
+            self.flush_state.pending.extend(adds);
+        }
         Ok(())
     }
 
     /// Writes the existing parquet bytes to storage and resets internal state to handle another file.
     async fn flush(&mut self) -> Result<Vec<Add>, DeltaTableError> {
-        let writers = std::mem::take(&mut self.arrow_writers);
-        let mut actions = Vec::new();
-
-        for (_, writer) in writers {
-            let metadata = writer.arrow_writer.close()?;
-            let prefix = Path::parse(writer.partition_values.hive_partition_path())?;
-            let uuid = Uuid::new_v4();
-            let path = next_data_path(&prefix, 0, &uuid, &writer.writer_properties);
-            let obj_bytes = Bytes::from(writer.buffer.to_vec());
-            let file_size = obj_bytes.len() as i64;
-            self.storage
-                .put_with_retries(&path, obj_bytes.into(), 15)
-                .await?;
-
-            actions.push(create_add(
-                &writer.partition_values,
-                path.to_string(),
-                file_size,
-                &metadata,
-                DEFAULT_NUM_INDEX_COLS,
-                &None,
-            )?);
-        }
+        let adds = self.write_files().await?;
+        self.flush_state.flushed(&adds);
+        let mut actions = std::mem::take(&mut self.flush_state.pending);
+        actions.extend(adds);
         Ok(actions)
     }
 
@@ -247,7 +377,8 @@ impl DeltaWriter<RecordBatch> for RecordBatchWriter {
         use crate::kernel::{Metadata, StructType};
         let mut adds: Vec<Action> = self.flush().await?.drain(..).map(Action::Add).collect();
 
-        if self.arrow_schema_ref != self.original_schema_ref && self.should_evolve {
+        let evolved = self.arrow_schema_ref != self.original_schema_ref && self.should_evolve;
+        if evolved {
             let schema: StructType = self.arrow_schema_ref.clone().try_into()?;
             if !self.partition_columns.is_empty() {
                 return Err(DeltaTableError::Generic(
@@ -255,11 +386,30 @@ impl DeltaWriter<RecordBatch> for RecordBatchWriter {
                         .to_owned(),
                 ));
             }
-            let part_cols: Vec<String> = vec![];
-            let metadata = Metadata::try_new(schema, part_cols, HashMap::new())?;
+            // keep the identity and configuration of the table, only the schema changes
+            let metadata = Metadata {
+                schema_string: serde_json::to_string(&schema)?,
+                ..table.metadata()?.clone()
+            };
             adds.push(Action::Metadata(metadata));
         }
-        super::flush_and_commit(adds, table).await
+        let version = super::flush_and_commit(adds, table).await?;
+        if evolved {
+            self.original_schema_ref = self.arrow_schema_ref.clone();
+            self.should_evolve = false;
+        }
+        Ok(version)
+    }
+
+    /// Flush the remaining buffered data and commit all files written by the writer, consuming it.
+    async fn close(mut self, table: &mut DeltaTable) -> Result<i64, DeltaTableError>
+    where
+        Self: Sized + Send,
+    {
+        if self.buffer_stats().rows == 0 && self.flush_state.pending.is_empty() {
+            return Ok(table.version());
+        }
+        self.flush_and_commit(table).await
     }
 }
 
@@ -279,6 +429,7 @@ struct PartitionWriter {
     pub(super) arrow_writer: ArrowWriter<ShareableBuffer>,
     pub(super) partition_values: IndexMap<String, Scalar>,
     pub(super) buffered_record_batch_count: usize,
+    pub(super) buffered_rows: usize,
 }
 
 impl PartitionWriter {
@@ -303,6 +454,7 @@ impl PartitionWriter {
             arrow_writer,
             partition_values,
             buffered_record_batch_count,
+            buffered_rows: 0,
         })
     }
 
@@ -357,6 +509,7 @@ impl PartitionWriter {
         match self.arrow_writer.write(record_batch) {
             Ok(_) => {
                 self.buffered_record_batch_count += 1;
+                self.buffered_rows += record_batch.num_rows();
                 Ok(self.arrow_schema.clone())
             }
             // If a write fails we need to reset the state of the PartitionWriter
@@ -466,6 +619,7 @@ mod tests {
     use super::*;
     use crate::operations::create::CreateBuilder;
     use crate::writer::test_utils::*;
+    use crate::writer::ThresholdFlushPolicy;
     use arrow::json::ReaderBuilder;
     use arrow_array::{Int32Array, RecordBatch, StringArray};
     use arrow_schema::{DataType, Field, Schema as ArrowSchema};
@@ -636,6 +790,76 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_write_with_flush_policy() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let mut table = CreateBuilder::new()
+            .with_location(table_dir.path().to_str().unwrap())
+            .with_columns(get_delta_schema().fields().cloned())
+            .await
+            .unwrap();
+
+        let batch = get_record_batch(None, false);
+        let policy = ThresholdFlushPolicy::new().with_max_rows(batch.num_rows());
+        let mut writer = RecordBatchWriter::for_table(&table)
+            .unwrap()
+            .with_flush_policy(Arc::new(policy));
+
+        writer.write(batch.clone()).await.unwrap();
+        assert_eq!(writer.buffer_stats(), BufferStats::default());
+        writer.write(batch.slice(0, 1)).await.unwrap();
+        assert_eq!(writer.buffer_stats().rows, 1);
+
+        let version = writer.close(&mut table).await.unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(table.get_files_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_write_stats_columns() {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table = CreateBuilder::new()
+            .with_location(table_dir.path().to_str().unwrap())
+            .with_columns(get_delta_schema().fields().cloned())
+            .with_configuration_property(
+                crate::DeltaConfigKey::DataSkippingStatsColumns,
+                Some("value, modified"),
+            )
+            .await
+            .unwrap();
+
+        let stats_columns = |adds: Vec<Add>| {
+            let stats = adds[0].get_stats().unwrap().unwrap();
+            let mut columns = stats.min_values.into_keys().collect::<Vec<_>>();
+            columns.sort();
+            columns
+        };
+
+        let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+        writer.write(get_record_batch(None, false)).await.unwrap();
+        let adds = writer.flush().await.unwrap();
+        assert_eq!(stats_columns(adds), vec!["modified", "value"]);
+
+        let mut writer = RecordBatchWriter::for_table(&table)
+            .unwrap()
+            .with_stats_columns(["id"]);
+        writer.write(get_record_batch(None, false)).await.unwrap();
+        let adds = writer.flush().await.unwrap();
+        assert_eq!(stats_columns(adds), vec!["id"]);
+
+        // opt out of column statistics
+        let mut writer =
+            RecordBatchWriter::try_new(table.table_uri(), get_arrow_schema(&None), None, None)
+                .unwrap()
+                .with_num_indexed_cols(0);
+        writer.write(get_record_batch(None, false)).await.unwrap();
+        let adds = writer.flush().await.unwrap();
+        let stats = adds[0].get_stats().unwrap().unwrap();
+        assert_eq!(stats.num_records, 11);
+        assert!(stats.min_values.is_empty());
+        assert!(stats.null_count.is_empty());
+    }
+
     fn validate_partition_map(partitions: Vec<PartitionResult>, expected_keys: Vec<String>) {
         assert_eq!(partitions.len(), expected_keys.len());
         for result in partitions {
@@ -675,6 +899,33 @@ mod tests {
         assert_eq!(adds.len(), 4);
     }
 
+    #[tokio::test]
+    async fn test_write_with_audit_columns() {
+        let table_schema = get_delta_schema();
+        let table_dir = tempfile::tempdir().unwrap();
+        let table_path = table_dir.path();
+
+        let mut table = CreateBuilder::new()
+            .with_location(table_path.to_str().unwrap())
+            .with_columns(table_schema.fields().cloned())
+            .with_audit_columns()
+            .await
+            .unwrap();
+
+        let batch = get_record_batch(None, false);
+        let mut writer = RecordBatchWriter::for_table(&table)
+            .unwrap()
+            .with_audit_columns(AuditColumns::new().with_source_file("input.json"));
+
+        writer.write(batch).await.unwrap();
+        let version = writer.flush_and_commit(&mut table).await.unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(
+            writer.arrow_schema().fields().len(),
+            table_schema.fields().len() + 3
+        );
+    }
+
     // The following sets of tests are related to #1386 and mergeSchema support
     // <https://github.com/delta-io/delta-rs/issues/1386>
     mod schema_evolution {
@@ -789,6 +1040,69 @@ mod tests {
             );
         }
 
+        #[tokio::test]
+        async fn test_write_with_schema_mode_merge() {
+            let mut table = create_initialized_table(&[]).await;
+            let table_id = table.metadata().unwrap().id.clone();
+            let mut writer = RecordBatchWriter::for_table(&table)
+                .unwrap()
+                .with_schema_mode(SchemaMode::Merge);
+            writer.write(get_record_batch(None, false)).await.unwrap();
+
+            // Create a second batch with an added nullable column
+            let batch = get_record_batch(None, false);
+            let mut fields = batch.schema().fields().iter().cloned().collect_vec();
+            fields.push(Field::new("name", DataType::Utf8, true).into());
+            let mut columns = batch.columns().to_vec();
+            columns.push(Arc::new(StringArray::from(vec![
+                Some("will");
+                batch.num_rows()
+            ])));
+            let second_batch =
+                RecordBatch::try_new(Arc::new(ArrowSchema::new(fields)), columns).unwrap();
+            writer.write(second_batch.clone()).await.unwrap();
+            let version = writer.flush_and_commit(&mut table).await.unwrap();
+            assert_eq!(version, 1);
+
+            let metadata = table.metadata().unwrap();
+            assert_eq!(metadata.id, table_id);
+            let found_columns = metadata
+                .schema()
+                .unwrap()
+                .fields()
+                .map(|f| f.name().clone())
+                .collect_vec();
+            assert_eq!(found_columns, vec!["id", "value", "modified", "name"]);
+
+            // the evolved schema is only committed once
+            writer.write(second_batch).await.unwrap();
+            let version = writer.flush_and_commit(&mut table).await.unwrap();
+            assert_eq!(version, 2);
+            let log_path = table
+                .log_store()
+                .log_path()
+                .child("00000000000000000002.json");
+            let commit = table.object_store().get(&log_path).await.unwrap();
+            let commit = commit.bytes().await.unwrap();
+            assert!(!String::from_utf8_lossy(&commit).contains("metaData"));
+
+            // added columns must be nullable
+            let third_batch = RecordBatch::try_new(
+                Arc::new(ArrowSchema::new(vec![Field::new(
+                    "required",
+                    DataType::Int32,
+                    false,
+                )])),
+                vec![Arc::new(Int32Array::from(vec![1]))],
+            )
+            .unwrap();
+            let result = writer.write(third_batch).await;
+            assert!(matches!(
+                result,
+                Err(DeltaTableError::SchemaMismatch { .. })
+            ));
+        }
+
         #[tokio::test]
         async fn test_write_schema_evolution_with_partition_columns_should_fail_as_unsupported() {
             let table_schema = get_delta_schema();
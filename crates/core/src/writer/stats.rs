@@ -68,6 +68,48 @@ pub fn create_add(
     })
 }
 
+/// Prefix of add action tags holding the minimum value of a column in the file
+pub const MIN_VALUE_TAG_PREFIX: &str = "delta-rs.minValue.";
+/// Prefix of add action tags holding the maximum value of a column in the file
+pub const MAX_VALUE_TAG_PREFIX: &str = "delta-rs.maxValue.";
+
+/// Creates add action tags with the min and max values of `tag_columns` in the file.
+///
+/// Unlike the file statistics, tags are not limited by `delta.dataSkippingNumIndexedCols`,
+/// so files can be pruned on columns beyond the stats column limit. Values are json encoded
+/// the same way as in the file statistics.
+pub(crate) fn min_max_tags(
+    partition_values: &IndexMap<String, Scalar>,
+    file_metadata: &FileMetaData,
+    tag_columns: &[String],
+) -> Result<HashMap<String, Option<String>>, DeltaTableError> {
+    if tag_columns.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let stats = stats_from_file_metadata(
+        partition_values,
+        file_metadata,
+        -1,
+        &Some(tag_columns.to_vec()),
+    )?;
+
+    let mut tags = HashMap::new();
+    for (prefix, values) in [
+        (MIN_VALUE_TAG_PREFIX, stats.min_values),
+        (MAX_VALUE_TAG_PREFIX, stats.max_values),
+    ] {
+        for (column, value) in values {
+            if let ColumnValueStat::Value(value) = value {
+                tags.insert(
+                    format!("{prefix}{column}"),
+                    Some(serde_json::to_string(&value)?),
+                );
+            }
+        }
+    }
+    Ok(tags)
+}
+
 // As opposed to `stats_from_file_metadata` which operates on `parquet::format::FileMetaData`,
 // this function produces the stats by reading the metadata from already written out files.
 //
@@ -137,7 +179,10 @@ fn stats_from_metadata(
             .iter()
             .enumerate()
             .filter_map(|(index, col)| {
-                if stats_cols.contains(&col.name().to_string()) {
+                // nested columns can be referenced by their leaf name or their full path
+                if stats_cols.contains(&col.name().to_string())
+                    || stats_cols.contains(&col.path().string())
+                {
                     Some(index)
                 } else {
                     None
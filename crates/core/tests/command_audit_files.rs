@@ -0,0 +1,83 @@
+use deltalake_core::kernel::StructType;
+use deltalake_core::operations::audit_files::OrphanFile;
+use deltalake_core::operations::DeltaOps;
+use deltalake_test::clock::TestClock;
+use deltalake_test::*;
+use object_store::path::Path;
+use serde_json::json;
+
+fn get_xy_date_schema() -> StructType {
+    serde_json::from_value(json!({
+      "type": "struct",
+      "fields": [
+        {"name": "x", "type": "integer", "nullable": false, "metadata": {}},
+        {"name": "y", "type": "integer", "nullable": false, "metadata": {}},
+        {"name": "date", "type": "string", "nullable": false, "metadata": {}},
+      ]
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+// Files neither active nor removed by the log are reported, hidden files are ignored
+async fn test_audit_files() {
+    let mut context = TestContext::from_env().await;
+    let mut table = context
+        .create_table_from_schema(get_xy_date_schema(), &["date"])
+        .await;
+    let clock = TestClock::from_systemtime();
+    let partition_values = [("date", Some("2022-07-03"))];
+
+    let committed = [
+        Path::from("date=2022-07-03/active.parquet"),
+        Path::from("date=2022-07-03/removed.parquet"),
+    ];
+    for path in committed.iter() {
+        add_file(
+            &mut table,
+            path,
+            "random junk".as_bytes().into(),
+            &partition_values,
+            clock.current_timestamp_millis(),
+            true,
+        )
+        .await;
+    }
+    remove_file(
+        &mut table,
+        "date=2022-07-03/removed.parquet",
+        &partition_values,
+        clock.current_timestamp_millis(),
+    )
+    .await;
+
+    let uncommitted = [
+        (Path::from("date=2022-07-03/orphan.parquet"), "orphan"),
+        (Path::from("garbage_file"), "some garbage"),
+        (Path::from("_change_data/cdc.parquet"), "cdc"),
+        (Path::from("date=2022-07-03/.hidden"), "hidden"),
+    ];
+    for (path, content) in uncommitted.iter() {
+        add_file(
+            &mut table,
+            path,
+            content.as_bytes().into(),
+            &[],
+            clock.current_timestamp_millis(),
+            false,
+        )
+        .await;
+    }
+
+    let (_, metrics) = DeltaOps(table).audit_files().await.unwrap();
+    let orphan_files = metrics
+        .orphan_files
+        .iter()
+        .map(|OrphanFile { path, size, .. }| (path.as_str(), *size))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        orphan_files,
+        vec![("date=2022-07-03/orphan.parquet", 6), ("garbage_file", 12)]
+    );
+    assert_eq!(metrics.orphan_files_size, 18);
+}
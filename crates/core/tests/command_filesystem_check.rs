@@ -118,8 +118,7 @@ async fn test_filesystem_check_fails_for_concurrent_delete() -> TestResult {
     let op = DeltaOps::from(table);
     let res = op.filesystem_check().with_dry_run(false).await;
 
-    // TODO check more specific error
-    assert!(matches!(res, Err(DeltaTableError::Transaction { .. })));
+    assert!(matches!(res, Err(DeltaTableError::CommitConflict { .. })));
 
     Ok(())
 }
@@ -9,7 +9,6 @@ use datafusion_common::Column;
 use datafusion_expr::{col, lit, Expr};
 use deltalake_core::kernel::{DataType as DeltaDataType, PrimitiveType, StructField, StructType};
 use deltalake_core::operations::merge::MergeMetrics;
-use deltalake_core::operations::transaction::TransactionError;
 use deltalake_core::protocol::SaveMode;
 use deltalake_core::{open_table, DeltaOps, DeltaResult, DeltaTable, DeltaTableError};
 use std::sync::Arc;
@@ -138,7 +137,27 @@ async fn merge(
 
 #[tokio::test]
 async fn test_merge_concurrent_conflict() {
-    // No partition key or filter predicate -> Commit conflict
+    // No partition key or filter predicate and overlapping join keys -> Commit conflict
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let table_uri = tmp_dir.path().to_str().to_owned().unwrap();
+
+    let table_ref1 = create_table(table_uri, Some(vec!["event_date"])).await;
+    let table_ref2 = open_table(table_uri).await.unwrap();
+    let (df1, _df2) = create_test_data();
+
+    let expr = col("target.id").eq(col("source.id"));
+    let (_table_ref1, _metrics) = merge(table_ref1, df1.clone(), expr.clone()).await.unwrap();
+    let result = merge(table_ref2, df1, expr).await;
+
+    assert!(matches!(
+        result.unwrap_err(),
+        DeltaTableError::CommitConflict { .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_merge_concurrent_disjoint_keys() {
+    // join keys of the sources do not overlap -> Successful merge
     let tmp_dir = tempfile::tempdir().unwrap();
     let table_uri = tmp_dir.path().to_str().to_owned().unwrap();
 
@@ -150,13 +169,7 @@ async fn test_merge_concurrent_conflict() {
     let (_table_ref1, _metrics) = merge(table_ref1, df1, expr.clone()).await.unwrap();
     let result = merge(table_ref2, df2, expr).await;
 
-    assert!(matches!(
-        result.as_ref().unwrap_err(),
-        DeltaTableError::Transaction { .. }
-    ));
-    if let DeltaTableError::Transaction { source } = result.unwrap_err() {
-        assert!(matches!(source, TransactionError::CommitConflict(_)));
-    }
+    assert!(result.as_ref().is_ok());
 }
 
 #[tokio::test]
@@ -207,10 +220,7 @@ async fn test_merge_concurrent_with_overlapping_files() {
     .await;
 
     assert!(matches!(
-        result.as_ref().unwrap_err(),
-        DeltaTableError::Transaction { .. }
+        result.unwrap_err(),
+        DeltaTableError::CommitConflict { .. }
     ));
-    if let DeltaTableError::Transaction { source } = result.unwrap_err() {
-        assert!(matches!(source, TransactionError::CommitConflict(_)));
-    }
 }
@@ -1,14 +1,18 @@
+use std::sync::Mutex;
 use std::time::Duration;
 use std::{error::Error, sync::Arc};
 
+use arrow_array::cast::AsArray;
+use arrow_array::types::Int32Type;
 use arrow_array::{Int32Array, RecordBatch, StringArray};
 use arrow_schema::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
 use arrow_select::concat::concat_batches;
 use deltalake_core::errors::DeltaTableError;
 use deltalake_core::kernel::{Action, DataType, PrimitiveType, StructField};
 use deltalake_core::operations::optimize::{
-    create_merge_plan, MetricDetails, Metrics, OptimizeType,
+    create_merge_plan, Deduplication, MetricDetails, Metrics, OptimizeType,
 };
+use deltalake_core::operations::progress::{ProgressReporter, ProgressStage};
 use deltalake_core::operations::transaction::{CommitBuilder, CommitProperties};
 use deltalake_core::operations::DeltaOps;
 use deltalake_core::protocol::DeltaOperation;
@@ -257,6 +261,56 @@ async fn test_optimize_with_partitions() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[derive(Debug, Default)]
+struct RecordingReporter {
+    calls: Mutex<Vec<(ProgressStage, u64, u64)>>,
+}
+
+impl ProgressReporter for RecordingReporter {
+    fn on_progress(&self, stage: ProgressStage, done: u64, total: u64) {
+        self.calls.lock().unwrap().push((stage, done, total));
+    }
+}
+
+#[tokio::test]
+/// Each rewritten bin is reported
+async fn test_optimize_progress() -> Result<(), Box<dyn Error>> {
+    let context = setup_test(true).await?;
+    let mut dt = context.table;
+    let mut writer = RecordBatchWriter::for_table(&dt)?;
+
+    for (x, date) in [
+        (1, "2022-05-22"),
+        (2, "2022-05-23"),
+        (3, "2022-05-22"),
+        (4, "2022-05-23"),
+    ] {
+        write(
+            &mut writer,
+            &mut dt,
+            tuples_to_batch(vec![(x, 1), (x, 2)], date)?,
+        )
+        .await?;
+    }
+
+    let reporter = Arc::new(RecordingReporter::default());
+    let (_, metrics) = DeltaOps(dt)
+        .optimize()
+        .with_progress_reporter(reporter.clone())
+        .await?;
+    assert_eq!(metrics.num_files_removed, 4);
+
+    let calls = reporter.calls.lock().unwrap().clone();
+    assert_eq!(
+        calls,
+        vec![
+            (ProgressStage::OptimizeRewrite, 1, 2),
+            (ProgressStage::OptimizeRewrite, 2, 2),
+        ]
+    );
+    Ok(())
+}
+
 #[tokio::test]
 /// Validate that optimize fails when a remove action occurs
 async fn test_conflict_for_remove_actions() -> Result<(), Box<dyn Error>> {
@@ -515,6 +569,7 @@ async fn test_idempotent_metrics() -> Result<(), Box<dyn Error>> {
         preserve_insertion_order: true,
         files_added: expected_metric_details.clone(),
         files_removed: expected_metric_details,
+        num_duplicates_removed: None,
     };
 
     assert_eq!(expected, metrics);
@@ -860,6 +915,130 @@ async fn test_zorder_respects_target_size() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_deduplicate_exact() -> Result<(), Box<dyn Error>> {
+    let context = setup_test(true).await?;
+    let mut dt = context.table;
+    let mut writer = RecordBatchWriter::for_table(&dt)?;
+
+    write(
+        &mut writer,
+        &mut dt,
+        tuples_to_batch(vec![(1, 1), (1, 2), (1, 1)], "2022-05-22")?,
+    )
+    .await?;
+    write(
+        &mut writer,
+        &mut dt,
+        tuples_to_batch(vec![(1, 2), (2, 2)], "2022-05-22")?,
+    )
+    .await?;
+    write(
+        &mut writer,
+        &mut dt,
+        tuples_to_batch(vec![(1, 1)], "2022-05-23")?,
+    )
+    .await?;
+
+    let version = dt.version();
+    let optimize = DeltaOps(dt)
+        .optimize()
+        .with_type(OptimizeType::Deduplicate(Deduplication::Exact));
+    let (dt, metrics) = optimize.await?;
+
+    assert_eq!(version + 1, dt.version());
+    assert_eq!(metrics.num_duplicates_removed, Some(2));
+    assert_eq!(metrics.num_files_removed, 3);
+    assert_eq!(metrics.num_files_added, 2);
+    assert_eq!(metrics.partitions_optimized, 2);
+
+    // Files are read in no particular order, so only compare the remaining rows
+    let filter = vec![PartitionFilter::try_from(("date", "=", "2022-05-22"))?];
+    let files = dt.get_files_by_partitions(&filter)?;
+    assert_eq!(files.len(), 1);
+    let actual = read_parquet_file(&files[0], dt.object_store()).await?;
+    let x = actual.column(0).as_primitive::<Int32Type>();
+    let y = actual.column(1).as_primitive::<Int32Type>();
+    let mut rows = x.iter().zip(y.iter()).collect::<Vec<_>>();
+    rows.sort();
+    assert_eq!(
+        rows,
+        vec![(Some(1), Some(1)), (Some(1), Some(2)), (Some(2), Some(2))]
+    );
+
+    // Dropping rows changes the data, so the rewrite is not recorded as an optimize
+    let commit_info = dt.history(Some(1)).await?;
+    assert_eq!(commit_info[0].operation.as_deref(), Some("DELETE"));
+    let metrics = commit_info[0].info["operationMetrics"].clone();
+    assert_eq!(metrics["numDuplicatesRemoved"], json!(2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deduplicate_by_key() -> Result<(), Box<dyn Error>> {
+    let context = setup_test(false).await?;
+    let mut dt = context.table;
+    let mut writer = RecordBatchWriter::for_table(&dt)?;
+
+    write(
+        &mut writer,
+        &mut dt,
+        tuples_to_batch(vec![(1, 1), (2, 5), (1, 3)], "2022-05-22")?,
+    )
+    .await?;
+    write(
+        &mut writer,
+        &mut dt,
+        tuples_to_batch(vec![(1, 2), (2, 4)], "2022-05-23")?,
+    )
+    .await?;
+
+    let optimize =
+        DeltaOps(dt)
+            .optimize()
+            .with_type(OptimizeType::Deduplicate(Deduplication::ByKey {
+                keys: vec!["x".to_string()],
+                version_column: "y".to_string(),
+            }));
+    let (dt, metrics) = optimize.await?;
+
+    assert_eq!(metrics.num_duplicates_removed, Some(3));
+    let files = dt.get_files_iter()?.collect::<Vec<_>>();
+    assert_eq!(files.len(), 1);
+    let actual = read_parquet_file(&files[0], dt.object_store()).await?;
+    let expected = RecordBatch::try_new(
+        actual.schema(),
+        vec![
+            Arc::new(Int32Array::from(vec![2, 1])),
+            Arc::new(Int32Array::from(vec![5, 3])),
+            Arc::new(StringArray::from(vec!["2022-05-22", "2022-05-22"])),
+        ],
+    )?;
+    assert_eq!(actual, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deduplicate_rejects_invalid_columns() -> Result<(), Box<dyn Error>> {
+    let context = setup_test(true).await?;
+    let dt = context.table;
+
+    for (keys, version_column) in [(vec!["z"], "y"), (vec!["x"], "date"), (vec!["date"], "y")] {
+        let result = DeltaOps(dt.clone())
+            .optimize()
+            .with_type(OptimizeType::Deduplicate(Deduplication::ByKey {
+                keys: keys.into_iter().map(String::from).collect(),
+                version_column: version_column.to_string(),
+            }))
+            .await;
+        assert!(result.is_err());
+    }
+
+    Ok(())
+}
+
 async fn read_parquet_file(
     path: &Path,
     object_store: ObjectStoreRef,
@@ -1,11 +1,13 @@
 use chrono::Duration;
 use deltalake_core::kernel::StructType;
-use deltalake_core::operations::vacuum::Clock;
+use deltalake_core::operations::transaction::CommitProperties;
+use deltalake_core::operations::vacuum::{Clock, VacuumMode};
 use deltalake_core::operations::DeltaOps;
 use deltalake_test::clock::TestClock;
 use deltalake_test::*;
 use object_store::{path::Path, Error as ObjectStoreError, ObjectStore};
 use serde_json::json;
+use std::num::NonZeroU32;
 use std::sync::Arc;
 
 /// Basic schema
@@ -85,6 +87,152 @@ async fn test_non_partitioned_table() {
     assert!(!is_deleted(&mut context, &Path::from("dont_delete_me.parquet")).await);
 }
 
+#[tokio::test]
+// Validate a lite vacuum deletes the files removed according to the log
+async fn test_lite_mode() {
+    let mut context = TestContext::from_env().await;
+    let mut table = context
+        .create_table_from_schema(get_xy_date_schema(), &["date"])
+        .await;
+    let clock = TestClock::from_systemtime();
+
+    let paths = [
+        Path::from("date=2022-07-03/delete_me.parquet"),
+        Path::from("date=2022-07-03/dont_delete_me.parquet"),
+    ];
+    let partition_values = [("date", Some("2022-07-03"))];
+
+    for path in paths {
+        add_file(
+            &mut table,
+            &path,
+            "random junk".as_bytes().into(),
+            &partition_values,
+            clock.current_timestamp_millis(),
+            true,
+        )
+        .await;
+    }
+
+    clock.tick(Duration::seconds(10));
+
+    remove_file(
+        &mut table,
+        "date=2022-07-03/delete_me.parquet",
+        &partition_values,
+        clock.current_timestamp_millis(),
+    )
+    .await;
+
+    let res = {
+        clock.tick(Duration::days(8));
+        let (_, metrics) = DeltaOps(table)
+            .vacuum()
+            .with_mode(VacuumMode::Lite)
+            .with_clock(Arc::new(clock.clone()))
+            .await
+            .unwrap();
+        metrics
+    };
+
+    assert_eq!(
+        res.files_deleted,
+        vec!["date=2022-07-03/delete_me.parquet".to_string()]
+    );
+    assert!(
+        is_deleted(
+            &mut context,
+            &Path::from("date=2022-07-03/delete_me.parquet")
+        )
+        .await
+    );
+    assert!(
+        !is_deleted(
+            &mut context,
+            &Path::from("date=2022-07-03/dont_delete_me.parquet")
+        )
+        .await
+    );
+}
+
+#[tokio::test]
+// Validate the vacuum is only recorded in the log when operation logging is enabled
+async fn test_operation_logging() {
+    for operation_logging in [false, true] {
+        let mut context = TestContext::from_env().await;
+        let mut table = context
+            .create_table_from_schema(get_xy_date_schema(), &[])
+            .await;
+        let clock = TestClock::from_systemtime();
+
+        let path = Path::from("delete_me.parquet");
+        add_file(
+            &mut table,
+            &path,
+            "random junk".as_bytes().into(),
+            &[],
+            clock.current_timestamp_millis(),
+            true,
+        )
+        .await;
+
+        clock.tick(Duration::seconds(10));
+
+        remove_file(
+            &mut table,
+            "delete_me.parquet",
+            &[],
+            clock.current_timestamp_millis(),
+        )
+        .await;
+        let version = table.version();
+
+        clock.tick(Duration::days(8));
+        let (mut table, metrics) = DeltaOps(table)
+            .vacuum()
+            .with_clock(Arc::new(clock.clone()))
+            .with_operation_logging(operation_logging)
+            .await
+            .unwrap();
+        assert_eq!(metrics.files_deleted.len(), 1);
+
+        table.load().await.unwrap();
+        if !operation_logging {
+            assert_eq!(table.version(), version);
+            continue;
+        }
+
+        assert_eq!(table.version(), version + 2);
+        let history = table.history(Some(2)).await.unwrap();
+        assert_eq!(history[0].operation.as_deref(), Some("VACUUM END"));
+        assert_eq!(history[1].operation.as_deref(), Some("VACUUM START"));
+        assert_eq!(
+            history[1].info["operationMetrics"]["numFilesToDelete"],
+            json!(1)
+        );
+        assert_eq!(
+            history[0].info["operationMetrics"]["numDeletedFiles"],
+            json!(1)
+        );
+    }
+}
+
+#[tokio::test]
+// Validate commit properties are not silently dropped when the vacuum is not committed
+async fn test_commit_properties_without_operation_logging() {
+    let mut context = TestContext::from_env().await;
+    let table = context
+        .create_table_from_schema(get_xy_date_schema(), &[])
+        .await;
+
+    let result = DeltaOps(table)
+        .vacuum()
+        .with_operation_logging(false)
+        .with_commit_properties(CommitProperties::default())
+        .await;
+    assert!(result.is_err());
+}
+
 #[tokio::test]
 // Validate vacuum works on a table with multiple partitions
 async fn test_partitioned_table() {
@@ -214,6 +362,64 @@ async fn test_partitions_included() {
     );
 }
 
+#[tokio::test]
+// Validate files are deleted with concurrent and rate limited requests
+async fn test_concurrent_deletes() {
+    for bulk_delete in [false, true] {
+        let mut context = TestContext::from_env().await;
+        let mut table = context
+            .create_table_from_schema(get_xy_date_schema(), &[])
+            .await;
+        let clock = TestClock::from_systemtime();
+
+        let paths = (0..5)
+            .map(|i| Path::from(format!("delete_me_{i}.parquet")))
+            .collect::<Vec<_>>();
+
+        for path in &paths {
+            add_file(
+                &mut table,
+                path,
+                "random junk".as_bytes().into(),
+                &[],
+                clock.current_timestamp_millis(),
+                true,
+            )
+            .await;
+        }
+
+        clock.tick(Duration::seconds(10));
+
+        for path in &paths {
+            remove_file(
+                &mut table,
+                path.as_ref(),
+                &[],
+                clock.current_timestamp_millis(),
+            )
+            .await;
+        }
+
+        let res = {
+            clock.tick(Duration::days(8));
+            let (_, metrics) = DeltaOps(table)
+                .vacuum()
+                .with_clock(Arc::new(clock.clone()))
+                .with_max_concurrent_deletes(2)
+                .with_max_requests_per_second(NonZeroU32::new(100).unwrap())
+                .with_bulk_delete(bulk_delete)
+                .await
+                .unwrap();
+            metrics
+        };
+
+        assert_eq!(res.files_deleted.len(), paths.len());
+        for path in &paths {
+            assert!(is_deleted(&mut context, path).await);
+        }
+    }
+}
+
 #[ignore]
 #[tokio::test]
 // files that are not managed by the delta log and have a last_modified greater
@@ -161,6 +161,117 @@ mod local {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_datafusion_insert_into() -> Result<()> {
+        let table_dir = tempfile::tempdir().unwrap();
+        let table_uri = table_dir.path().to_str().unwrap();
+        let table = CreateBuilder::new()
+            .with_location(table_uri)
+            .with_column(
+                "id",
+                DataType::Primitive(PrimitiveType::Integer),
+                true,
+                None,
+            )
+            .with_column(
+                "value",
+                DataType::Primitive(PrimitiveType::String),
+                true,
+                None,
+            )
+            .await?;
+        let table = DeltaOps(table)
+            .add_constraint()
+            .with_constraint("id_positive", "id > 0")
+            .await?;
+
+        let ctx = SessionContext::new();
+        ctx.register_table("demo", Arc::new(table))?;
+        let batches = ctx
+            .sql("INSERT INTO demo VALUES (1, 'a'), (2, 'b')")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+-------+",
+            "| count |",
+            "+-------+",
+            "| 2     |",
+            "+-------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &batches);
+
+        // the constraints of the table are enforced
+        let result = ctx
+            .sql("INSERT INTO demo VALUES (-1, 'c')")
+            .await?
+            .collect()
+            .await;
+        assert!(result.is_err());
+
+        let table = open_table(table_uri).await?;
+        assert_eq!(table.version(), 2);
+        ctx.deregister_table("demo")?;
+        ctx.register_table("demo", Arc::new(table))?;
+        let batches = ctx.sql("SELECT * FROM demo").await?.collect().await?;
+        let expected = vec![
+            "+----+-------+",
+            "| id | value |",
+            "+----+-------+",
+            "| 1  | a     |",
+            "| 2  | b     |",
+            "+----+-------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_datafusion_sql_create_external_table() -> Result<()> {
+        let ctx = context_with_delta_table_factory();
+        let table_dir = tempfile::tempdir().unwrap();
+        let table_uri = table_dir.path().to_str().unwrap();
+
+        // without a table at the location, the columns are required
+        let sql = format!("CREATE EXTERNAL TABLE missing STORED AS DELTA LOCATION '{table_uri}'");
+        assert!(ctx.sql(&sql).await.is_err());
+
+        let sql = format!(
+            "CREATE EXTERNAL TABLE demo (id INT, part VARCHAR) STORED AS DELTA \
+             PARTITIONED BY (part) LOCATION '{table_uri}'"
+        );
+        ctx.sql(&sql).await?;
+        ctx.sql("INSERT INTO demo VALUES (1, 'a'), (2, 'b')")
+            .await?
+            .collect()
+            .await?;
+
+        let table = open_table(table_uri).await?;
+        assert_eq!(table.version(), 1);
+        assert_eq!(table.metadata()?.partition_columns, vec!["part"]);
+
+        // an existing table is attached
+        let sql = format!("CREATE EXTERNAL TABLE attached STORED AS DELTA LOCATION '{table_uri}'");
+        ctx.sql(&sql).await?;
+        let batches = ctx
+            .sql("SELECT id, part FROM attached")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+----+------+",
+            "| id | part |",
+            "+----+------+",
+            "| 1  | a    |",
+            "| 2  | b    |",
+            "+----+------+",
+        ];
+        assert_batches_sorted_eq!(&expected, &batches);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_datafusion_simple_query_partitioned() -> Result<()> {
         let ctx = SessionContext::new();
@@ -1,4 +1,5 @@
 use chrono::{DateTime, FixedOffset, Utc};
+use deltalake_core::DeltaTableError;
 use std::path::Path;
 
 #[tokio::test]
@@ -17,13 +18,22 @@ async fn time_travel_by_ds() {
         utime::set_file_times(Path::new(log_dir).join(fname), ts, ts).unwrap();
     }
 
-    let mut table = deltalake_core::open_table_with_ds(
+    let result = deltalake_core::open_table_with_ds(
         "../test/tests/data/simple_table",
         "2020-05-01T00:47:31-07:00",
     )
+    .await;
+    assert!(matches!(
+        result.unwrap_err(),
+        DeltaTableError::TimestampBeforeEarliestVersion { version: 0, .. }
+    ));
+
+    let mut table = deltalake_core::open_table_with_ds(
+        "../test/tests/data/simple_table",
+        "2020-05-01T22:47:31-07:00",
+    )
     .await
     .unwrap();
-
     assert_eq!(table.version(), 0);
 
     table = deltalake_core::open_table_with_ds(
@@ -74,16 +84,68 @@ async fn time_travel_by_ds() {
     .unwrap();
     assert_eq!(table.version(), 4);
 
-    table = deltalake_core::open_table_with_ds(
+    let result = deltalake_core::open_table_with_ds(
         "../test/tests/data/simple_table",
         "2020-05-25T22:47:31-07:00",
     )
-    .await
-    .unwrap();
-    assert_eq!(table.version(), 4);
+    .await;
+    assert!(matches!(
+        result.unwrap_err(),
+        DeltaTableError::TimestampAfterLatestVersion { version: 4, .. }
+    ));
 }
 
 fn ds_to_ts(ds: &str) -> i64 {
     let fixed_dt = DateTime::<FixedOffset>::parse_from_rfc3339(ds).unwrap();
     DateTime::<Utc>::from(fixed_dt).timestamp()
 }
+
+#[tokio::test]
+async fn time_travel_by_in_commit_timestamps() {
+    // in-commit timestamps are enabled at version 2, earlier commits are timed by their files
+    let metadata = |configuration: &str| {
+        format!(
+            r#"{{"metaData":{{"id":"ict","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{{\"name\":\"id\",\"type\":\"long\",\"nullable\":true,\"metadata\":{{}}}}]}}","partitionColumns":[],"configuration":{{{configuration}}},"createdTime":0}}}}"#
+        )
+    };
+    let commits = [
+        vec![
+            r#"{"commitInfo":{"timestamp":1000000}}"#.to_string(),
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#.to_string(),
+            metadata(""),
+        ],
+        vec![r#"{"commitInfo":{"timestamp":2000000}}"#.to_string()],
+        vec![
+            r#"{"commitInfo":{"inCommitTimestamp":3000000,"timestamp":3000000}}"#.to_string(),
+            metadata(
+                r#""delta.enableInCommitTimestamps":"true","delta.inCommitTimestampEnablementVersion":"2""#,
+            ),
+        ],
+        vec![r#"{"commitInfo":{"inCommitTimestamp":4000000,"timestamp":4000000}}"#.to_string()],
+        vec![r#"{"commitInfo":{"inCommitTimestamp":5000000,"timestamp":5000000}}"#.to_string()],
+    ];
+    // the files of the commits with in-commit timestamps are modified before all others
+    let mtimes = [1000, 2000, 500, 500, 500];
+
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let log_dir = tmp_dir.path().join("_delta_log");
+    std::fs::create_dir(&log_dir).unwrap();
+    for (version, (actions, mtime)) in commits.iter().zip(mtimes).enumerate() {
+        let path = log_dir.join(format!("{version:020}.json"));
+        std::fs::write(&path, actions.join("\n")).unwrap();
+        utime::set_file_times(&path, mtime, mtime).unwrap();
+    }
+
+    let table_uri = tmp_dir.path().to_str().unwrap();
+    for (ds, version) in [
+        ("1970-01-01T00:20:00Z", 0),
+        ("1970-01-01T00:41:40Z", 1),
+        ("1970-01-01T01:06:40Z", 3),
+        ("1970-01-01T01:23:20Z", 4),
+    ] {
+        let table = deltalake_core::open_table_with_ds(table_uri, ds)
+            .await
+            .unwrap();
+        assert_eq!(table.version(), version, "{ds}");
+    }
+}
@@ -11,3 +11,7 @@ pub use deltalake_azure as azure;
 pub use deltalake_gcp as gcp;
 #[cfg(feature = "hdfs")]
 pub use deltalake_hdfs as hdfs;
+#[cfg(feature = "hive")]
+pub use deltalake_catalog_hive as hive;
+#[cfg(feature = "lakefs")]
+pub use deltalake_lakefs as lakefs;
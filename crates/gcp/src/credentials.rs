@@ -0,0 +1,410 @@
+//! Credential providers for Google Cloud Storage not covered by [`object_store`].
+//!
+//! [`object_store`] authenticates using service account keys and authorized user
+//! credentials. This module adds external account credentials as used by workload
+//! identity federation, as well as impersonation of a service account on top of any
+//! other credential.
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use deltalake_core::storage::{CredentialProviderRef, StorageCredential};
+use object_store::gcp::{GcpCredential, GcpCredentialProvider};
+use object_store::CredentialProvider;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use crate::error::{Error, Result};
+
+const STORE: &str = "GCS";
+const DEFAULT_TOKEN_URL: &str = "https://sts.googleapis.com/v1/token";
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+const IAM_CREDENTIALS_URL: &str = "https://iamcredentials.googleapis.com/v1";
+const TOKEN_EXCHANGE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:token-exchange";
+const ACCESS_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:access_token";
+
+/// Lifetime requested for access tokens of impersonated service accounts
+const IMPERSONATION_LIFETIME: Duration = Duration::from_secs(3600);
+
+/// Tokens are refreshed once their remaining lifetime drops below this threshold
+const MIN_TOKEN_TTL: Duration = Duration::from_secs(300);
+
+/// Configuration of an `external_account` credential file
+///
+/// See <https://google.aip.dev/auth/4117> for the specification.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ExternalAccountConfig {
+    audience: String,
+    subject_token_type: String,
+    #[serde(default)]
+    token_url: Option<String>,
+    #[serde(default)]
+    service_account_impersonation_url: Option<String>,
+    credential_source: CredentialSource,
+}
+
+/// Source of the subject token exchanged for a Google access token
+#[derive(Debug, Clone, Deserialize)]
+struct CredentialSource {
+    #[serde(default)]
+    file: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    format: Option<CredentialSourceFormat>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum CredentialSourceFormat {
+    Text,
+    Json { subject_token_field_name: String },
+}
+
+#[derive(Deserialize)]
+struct CredentialFileType {
+    #[serde(rename = "type")]
+    credential_type: String,
+}
+
+impl ExternalAccountConfig {
+    /// Parse an external account configuration, returns `None` for other credential types
+    pub fn try_parse(content: &str) -> Result<Option<Self>> {
+        let file_type: CredentialFileType = serde_json::from_str(content)
+            .map_err(|err| Error::Parse(format!("invalid credential file: {err}")))?;
+        if file_type.credential_type != "external_account" {
+            return Ok(None);
+        }
+        let config: Self = serde_json::from_str(content)
+            .map_err(|err| Error::Parse(format!("invalid external account credentials: {err}")))?;
+        if config.credential_source.file.is_none() && config.credential_source.url.is_none() {
+            return Err(Error::Parse(
+                "external account credentials must read the subject token from a file or url"
+                    .to_string(),
+            ));
+        }
+        Ok(Some(config))
+    }
+
+    /// Extract the subject token from the raw content of the credential source
+    fn parse_subject_token(&self, content: &str) -> object_store::Result<String> {
+        match &self.credential_source.format {
+            Some(CredentialSourceFormat::Json {
+                subject_token_field_name,
+            }) => {
+                let value: serde_json::Value =
+                    serde_json::from_str(content).map_err(|err| generic_err(err.to_string()))?;
+                value
+                    .get(subject_token_field_name)
+                    .and_then(|token| token.as_str())
+                    .map(|token| token.to_string())
+                    .ok_or_else(|| {
+                        generic_err(format!(
+                            "field '{subject_token_field_name}' missing in subject token"
+                        ))
+                    })
+            }
+            Some(CredentialSourceFormat::Text) | None => Ok(content.trim().to_string()),
+        }
+    }
+
+    async fn subject_token(&self, client: &reqwest::Client) -> object_store::Result<String> {
+        let source = &self.credential_source;
+        let content = if let Some(file) = &source.file {
+            std::fs::read_to_string(file).map_err(|err| {
+                generic_err(format!("failed to read subject token from {file}: {err}"))
+            })?
+        } else if let Some(url) = &source.url {
+            let mut request = client.get(url);
+            for (name, value) in &source.headers {
+                request = request.header(name, value);
+            }
+            request
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(generic_err)?
+                .text()
+                .await
+                .map_err(generic_err)?
+        } else {
+            return Err(generic_err("no subject token source configured"));
+        };
+        self.parse_subject_token(&content)
+    }
+}
+
+#[derive(Deserialize)]
+struct StsTokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImpersonatedTokenResponse {
+    access_token: String,
+}
+
+fn generic_err(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: STORE,
+        source: err.into(),
+    }
+}
+
+/// Exchange a bearer token for an access token of the service account behind `url`
+async fn generate_access_token(
+    client: &reqwest::Client,
+    url: &str,
+    bearer: &str,
+) -> object_store::Result<(String, Duration)> {
+    let response: ImpersonatedTokenResponse = client
+        .post(url)
+        .bearer_auth(bearer)
+        .json(&serde_json::json!({
+            "scope": [CLOUD_PLATFORM_SCOPE],
+            "lifetime": format!("{}s", IMPERSONATION_LIFETIME.as_secs()),
+        }))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(generic_err)?
+        .json()
+        .await
+        .map_err(generic_err)?;
+    Ok((response.access_token, IMPERSONATION_LIFETIME))
+}
+
+/// Caches an access token until shortly before it expires
+#[derive(Debug, Default)]
+struct TokenCache {
+    token: Mutex<Option<(Arc<GcpCredential>, Instant)>>,
+}
+
+impl TokenCache {
+    async fn get_or_fetch<F, Fut>(&self, fetch: F) -> object_store::Result<Arc<GcpCredential>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = object_store::Result<(String, Duration)>>,
+    {
+        let mut token = self.token.lock().await;
+        if let Some((credential, expiry)) = token.as_ref() {
+            if expiry.saturating_duration_since(Instant::now()) > MIN_TOKEN_TTL {
+                return Ok(credential.clone());
+            }
+        }
+        let (bearer, ttl) = fetch().await?;
+        let credential = Arc::new(GcpCredential { bearer });
+        *token = Some((credential.clone(), Instant::now() + ttl));
+        Ok(credential)
+    }
+}
+
+/// Credential provider for workload identity federation
+///
+/// Reads a subject token issued by an external identity provider and exchanges it with
+/// the Google security token service, optionally impersonating a service account.
+#[derive(Debug)]
+pub(crate) struct ExternalAccountProvider {
+    config: ExternalAccountConfig,
+    client: reqwest::Client,
+    cache: TokenCache,
+}
+
+impl ExternalAccountProvider {
+    pub fn new(config: ExternalAccountConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+            cache: TokenCache::default(),
+        }
+    }
+
+    async fn fetch_token(&self) -> object_store::Result<(String, Duration)> {
+        let subject_token = self.config.subject_token(&self.client).await?;
+        let token_url = self
+            .config
+            .token_url
+            .as_deref()
+            .unwrap_or(DEFAULT_TOKEN_URL);
+        let response: StsTokenResponse = self
+            .client
+            .post(token_url)
+            .form(&[
+                ("grant_type", TOKEN_EXCHANGE_GRANT_TYPE),
+                ("audience", self.config.audience.as_str()),
+                ("scope", CLOUD_PLATFORM_SCOPE),
+                ("requested_token_type", ACCESS_TOKEN_TYPE),
+                ("subject_token", subject_token.as_str()),
+                (
+                    "subject_token_type",
+                    self.config.subject_token_type.as_str(),
+                ),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(generic_err)?
+            .json()
+            .await
+            .map_err(generic_err)?;
+
+        match &self.config.service_account_impersonation_url {
+            Some(url) => generate_access_token(&self.client, url, &response.access_token).await,
+            None => Ok((
+                response.access_token,
+                Duration::from_secs(response.expires_in.unwrap_or(3600)),
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for ExternalAccountProvider {
+    type Credential = GcpCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<GcpCredential>> {
+        self.cache.get_or_fetch(|| self.fetch_token()).await
+    }
+}
+
+/// Credential provider impersonating a service account using the access token of another credential
+#[derive(Debug)]
+pub(crate) struct ImpersonatedProvider {
+    base: GcpCredentialProvider,
+    url: String,
+    client: reqwest::Client,
+    cache: TokenCache,
+}
+
+impl ImpersonatedProvider {
+    pub fn new(base: GcpCredentialProvider, service_account: &str) -> Self {
+        Self {
+            base,
+            url: format!(
+                "{IAM_CREDENTIALS_URL}/projects/-/serviceAccounts/{service_account}:generateAccessToken"
+            ),
+            client: reqwest::Client::new(),
+            cache: TokenCache::default(),
+        }
+    }
+
+    async fn fetch_token(&self) -> object_store::Result<(String, Duration)> {
+        let base = self.base.get_credential().await?;
+        generate_access_token(&self.client, &self.url, &base.bearer).await
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for ImpersonatedProvider {
+    type Credential = GcpCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<GcpCredential>> {
+        self.cache.get_or_fetch(|| self.fetch_token()).await
+    }
+}
+
+/// Supplies credentials of a user provided [`deltalake_core::storage::CredentialProvider`] to
+/// [`object_store`].
+#[derive(Debug)]
+pub(crate) struct UserCredentialProvider(pub CredentialProviderRef);
+
+#[async_trait]
+impl CredentialProvider for UserCredentialProvider {
+    type Credential = GcpCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<GcpCredential>> {
+        match self.0.get_credential().await.map_err(generic_err)? {
+            StorageCredential::BearerToken(bearer) => Ok(Arc::new(GcpCredential { bearer })),
+            other => Err(generic_err(format!(
+                "unsupported credential for GCS: {}",
+                other.kind()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn external_account(credential_source: serde_json::Value) -> String {
+        serde_json::json!({
+            "type": "external_account",
+            "audience": "//iam.googleapis.com/projects/1/locations/global/workloadIdentityPools/pool/providers/provider",
+            "subject_token_type": "urn:ietf:params:oauth:token-type:jwt",
+            "token_url": "https://sts.googleapis.com/v1/token",
+            "credential_source": credential_source,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_other_credential_types() {
+        let content = serde_json::json!({"type": "service_account"}).to_string();
+        assert!(ExternalAccountConfig::try_parse(&content)
+            .unwrap()
+            .is_none());
+        assert!(ExternalAccountConfig::try_parse("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_requires_credential_source() {
+        let content = external_account(serde_json::json!({"environment_id": "aws1"}));
+        assert!(ExternalAccountConfig::try_parse(&content).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subject_token_from_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "my-token").unwrap();
+        let content = external_account(serde_json::json!({
+            "file": file.path().to_str().unwrap(),
+        }));
+        let config = ExternalAccountConfig::try_parse(&content).unwrap().unwrap();
+        let token = config.subject_token(&reqwest::Client::new()).await.unwrap();
+        assert_eq!(token, "my-token");
+    }
+
+    #[test]
+    fn test_subject_token_json_format() {
+        let content = external_account(serde_json::json!({
+            "file": "/var/run/token",
+            "format": {"type": "json", "subject_token_field_name": "id_token"},
+        }));
+        let config = ExternalAccountConfig::try_parse(&content).unwrap().unwrap();
+        let token = config
+            .parse_subject_token(r#"{"id_token": "my-token"}"#)
+            .unwrap();
+        assert_eq!(token, "my-token");
+        assert!(config.parse_subject_token(r#"{"other": "x"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_token_cache_refreshes_expiring_tokens() {
+        let cache = TokenCache::default();
+        let fetches = AtomicUsize::new(0);
+        let fetch = |ttl: u64| {
+            let n = fetches.fetch_add(1, Ordering::SeqCst);
+            async move { Ok((format!("token-{n}"), Duration::from_secs(ttl))) }
+        };
+
+        let token = cache.get_or_fetch(|| fetch(3600)).await.unwrap();
+        assert_eq!(token.bearer, "token-0");
+        let token = cache.get_or_fetch(|| fetch(3600)).await.unwrap();
+        assert_eq!(token.bearer, "token-0");
+
+        // a token about to expire is replaced on the next request
+        *cache.token.lock().await = Some((token, Instant::now() + Duration::from_secs(10)));
+        let token = cache.get_or_fetch(|| fetch(3600)).await.unwrap();
+        assert_eq!(token.bearer, "token-1");
+    }
+}
@@ -4,7 +4,6 @@ pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum Error {
-    #[allow(dead_code)]
     #[error("failed to parse config: {0}")]
     Parse(String),
 
@@ -16,7 +15,7 @@ impl From<Error> for DeltaTableError {
     fn from(e: Error) -> Self {
         match e {
             Error::Parse(msg) => DeltaTableError::Generic(msg),
-            Error::ObjectStore(e) => DeltaTableError::ObjectStore { source: e },
+            Error::ObjectStore(e) => e.into(),
         }
     }
 }
@@ -2,20 +2,41 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use deltalake_core::logstore::{default_logstore, logstores, LogStore, LogStoreFactory};
+use deltalake_core::logstore::{default_logstore, logstore_factories, LogStore, LogStoreFactory};
 use deltalake_core::storage::{
-    factories, limit_store_handler, url_prefix_handler, ObjectStoreFactory, ObjectStoreRef,
-    StorageOptions,
+    factories, limit_store_handler, retry_store_handler, url_prefix_handler, CredentialProviderRef,
+    ObjectStoreFactory, ObjectStoreRef, StorageOptions,
 };
 use deltalake_core::{DeltaResult, Path};
-use object_store::gcp::GoogleConfigKey;
-use object_store::parse_url_opts;
+use object_store::aws::{AmazonS3Builder, S3CopyIfNotExists};
+use object_store::gcp::{GcpCredentialProvider, GoogleCloudStorageBuilder, GoogleConfigKey};
 use url::Url;
 
+use crate::credentials::{
+    ExternalAccountConfig, ExternalAccountProvider, ImpersonatedProvider, UserCredentialProvider,
+};
+use crate::error::Error;
+
 mod config;
+mod credentials;
 pub mod error;
 mod storage;
 
+pub mod gcp_constants {
+    /// Access key id of an HMAC key. When set together with [`GOOGLE_HMAC_SECRET`], requests
+    /// are signed with the HMAC key against the S3 compatible XML API of Cloud Storage.
+    pub const GOOGLE_HMAC_ACCESS_KEY_ID: &str = "GOOGLE_HMAC_ACCESS_KEY_ID";
+    /// Secret of the HMAC key configured with [`GOOGLE_HMAC_ACCESS_KEY_ID`].
+    pub const GOOGLE_HMAC_SECRET: &str = "GOOGLE_HMAC_SECRET";
+    /// Email of a service account to impersonate. Access tokens of the configured credential
+    /// are exchanged for short lived tokens of this service account, which requires the
+    /// `roles/iam.serviceAccountTokenCreator` role on it.
+    pub const GOOGLE_IMPERSONATE_SERVICE_ACCOUNT: &str = "GOOGLE_IMPERSONATE_SERVICE_ACCOUNT";
+
+    /// Default endpoint of the S3 compatible XML API used with HMAC keys
+    pub(crate) const HMAC_ENDPOINT: &str = "https://storage.googleapis.com";
+}
+
 trait GcpOptions {
     fn as_gcp_options(&self) -> HashMap<GoogleConfigKey, String>;
 }
@@ -34,6 +55,93 @@ impl GcpOptions for StorageOptions {
     }
 }
 
+/// Look up a delta specific option case-insensitively, falling back to the environment
+fn option_or_env(options: &StorageOptions, name: &str) -> Option<String> {
+    options
+        .0
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.clone())
+        .or_else(|| std::env::var(name).ok())
+}
+
+/// Extract external account credentials from the configured credential, if any
+///
+/// [`object_store`] does not support workload identity federation, so these credentials
+/// are removed from the configuration and handled by [`ExternalAccountProvider`].
+fn take_external_account(
+    config: &mut HashMap<GoogleConfigKey, String>,
+) -> Result<Option<ExternalAccountConfig>, Error> {
+    if let Some(key) = config.get(&GoogleConfigKey::ServiceAccountKey) {
+        if let Some(external) = ExternalAccountConfig::try_parse(key)? {
+            config.remove(&GoogleConfigKey::ServiceAccountKey);
+            return Ok(Some(external));
+        }
+    }
+    if let Some(path) = config.get(&GoogleConfigKey::ApplicationCredentials) {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| Error::Parse(format!("failed to read {path}: {err}")))?;
+        if let Some(external) = ExternalAccountConfig::try_parse(&content)? {
+            config.remove(&GoogleConfigKey::ApplicationCredentials);
+            return Ok(Some(external));
+        }
+    }
+    Ok(None)
+}
+
+/// Build a store accessing the bucket through the S3 compatible API using an HMAC key
+fn hmac_store(url: &Url, key_id: String, secret: String) -> DeltaResult<ObjectStoreRef> {
+    let bucket = url
+        .host_str()
+        .ok_or_else(|| Error::Parse(format!("missing bucket name in url: {url}")))?;
+    let store = AmazonS3Builder::new()
+        .with_endpoint(gcp_constants::HMAC_ENDPOINT)
+        .with_region("auto")
+        .with_bucket_name(bucket)
+        .with_access_key_id(key_id)
+        .with_secret_access_key(secret)
+        .with_copy_if_not_exists(S3CopyIfNotExists::Header(
+            "x-goog-if-generation-match".to_string(),
+            "0".to_string(),
+        ))
+        .build()?;
+    Ok(Arc::new(store))
+}
+
+/// Build a store using OAuth credentials, taken from the user provided credentials if present
+fn oauth_store(
+    url: &Url,
+    options: &StorageOptions,
+    user_credentials: Option<CredentialProviderRef>,
+) -> DeltaResult<ObjectStoreRef> {
+    let mut config = config::GcpConfigHelper::try_new(options.as_gcp_options())?.build()?;
+    let external_account = take_external_account(&mut config)?;
+    let mut builder = config.into_iter().fold(
+        GoogleCloudStorageBuilder::new().with_url(url.as_str()),
+        |builder, (key, value)| builder.with_config(key, value),
+    );
+
+    let mut credentials: Option<GcpCredentialProvider> = match user_credentials {
+        Some(user_credentials) => Some(Arc::new(UserCredentialProvider(user_credentials))),
+        None => external_account
+            .map(|config| Arc::new(ExternalAccountProvider::new(config)) as GcpCredentialProvider),
+    };
+    if let Some(service_account) =
+        option_or_env(options, gcp_constants::GOOGLE_IMPERSONATE_SERVICE_ACCOUNT)
+    {
+        let base = match credentials.take() {
+            Some(base) => base,
+            None => builder.clone().build()?.credentials().clone(),
+        };
+        credentials = Some(Arc::new(ImpersonatedProvider::new(base, &service_account)));
+    }
+    if let Some(credentials) = credentials {
+        builder = builder.with_credentials(credentials);
+    }
+
+    Ok(Arc::new(builder.build()?))
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct GcpFactory {}
 
@@ -43,10 +151,38 @@ impl ObjectStoreFactory for GcpFactory {
         url: &Url,
         options: &StorageOptions,
     ) -> DeltaResult<(ObjectStoreRef, Path)> {
-        let config = config::GcpConfigHelper::try_new(options.as_gcp_options())?.build()?;
-        let (inner, prefix) = parse_url_opts(url, config)?;
-        let gcs_backend = crate::storage::GcsStorageBackend::try_new(Arc::new(inner))?;
-        let store = limit_store_handler(url_prefix_handler(gcs_backend, prefix.clone()), options);
+        self.build_store(url, options, None)
+    }
+
+    fn parse_url_opts_with_credentials(
+        &self,
+        url: &Url,
+        options: &StorageOptions,
+        credentials: CredentialProviderRef,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        self.build_store(url, options, Some(credentials))
+    }
+}
+
+impl GcpFactory {
+    fn build_store(
+        &self,
+        url: &Url,
+        options: &StorageOptions,
+        credentials: Option<CredentialProviderRef>,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let hmac_key = option_or_env(options, gcp_constants::GOOGLE_HMAC_ACCESS_KEY_ID)
+            .zip(option_or_env(options, gcp_constants::GOOGLE_HMAC_SECRET));
+        let inner = match (hmac_key, credentials) {
+            (Some((key_id, secret)), None) => hmac_store(url, key_id, secret)?,
+            (_, credentials) => oauth_store(url, options, credentials)?,
+        };
+        let prefix = Path::from_url_path(url.path())?;
+        let gcs_backend = crate::storage::GcsStorageBackend::try_new(inner)?;
+        let store = retry_store_handler(
+            limit_store_handler(url_prefix_handler(gcs_backend, prefix.clone()), options),
+            options,
+        )?;
         Ok((store, prefix))
     }
 }
@@ -68,5 +204,5 @@ pub fn register_handlers(_additional_prefixes: Option<Url>) {
     let scheme = &"gs";
     let url = Url::parse(&format!("{}://", scheme)).unwrap();
     factories().insert(url.clone(), factory.clone());
-    logstores().insert(url.clone(), factory.clone());
+    logstore_factories().insert(url.clone(), factory.clone());
 }
@@ -1,13 +1,19 @@
+//! HDFS support for Delta tables, backed by [hdfs_native_object_store].
+//!
+//! Tables at `hdfs://` and `viewfs://` urls can be used once the handlers are registered with
+//! [register_handlers]. No Java installation or gateway is required.
 use std::sync::Arc;
 
-use deltalake_core::logstore::{default_logstore, logstores, LogStore, LogStoreFactory};
+use deltalake_core::logstore::{default_logstore, logstore_factories, LogStore, LogStoreFactory};
 use deltalake_core::storage::{
-    factories, url_prefix_handler, ObjectStoreFactory, ObjectStoreRef, StorageOptions,
+    factories, retry_store_handler, url_prefix_handler, ObjectStoreFactory, ObjectStoreRef,
+    StorageOptions,
 };
 use deltalake_core::{DeltaResult, Path};
 use hdfs_native_object_store::HdfsObjectStore;
 use url::Url;
 
+/// [ObjectStoreFactory] and [LogStoreFactory] for HDFS tables
 #[derive(Clone, Default, Debug)]
 pub struct HdfsFactory {}
 
@@ -22,7 +28,8 @@ impl ObjectStoreFactory for HdfsFactory {
             options.0.clone(),
         )?);
         let prefix = Path::parse(url.path())?;
-        Ok((url_prefix_handler(store, prefix.clone()), prefix))
+        let store = retry_store_handler(url_prefix_handler(store, prefix.clone()), options)?;
+        Ok((store, prefix))
     }
 }
 
@@ -43,6 +50,21 @@ pub fn register_handlers(_additional_prefixes: Option<Url>) {
     for scheme in ["hdfs", "viewfs"].iter() {
         let url = Url::parse(&format!("{}://", scheme)).unwrap();
         factories().insert(url.clone(), factory.clone());
-        logstores().insert(url.clone(), factory.clone());
+        logstore_factories().insert(url.clone(), factory.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_handlers() {
+        register_handlers(None);
+        for scheme in ["hdfs", "viewfs"] {
+            let url = Url::parse(&format!("{scheme}://")).unwrap();
+            assert!(factories().contains_key(&url));
+            assert!(logstore_factories().contains_key(&url));
+        }
     }
 }
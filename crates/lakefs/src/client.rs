@@ -0,0 +1,149 @@
+//! Minimal client for the parts of the lakeFS REST API required to commit to a table
+use std::collections::HashMap;
+
+use reqwest::{Client, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use url::Url;
+
+use crate::errors::LakeFSError;
+
+/// Client for the lakeFS REST API
+#[derive(Debug, Clone)]
+pub struct LakeFSClient {
+    client: Client,
+    endpoint: Url,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+#[derive(Serialize)]
+struct BranchCreation<'a> {
+    name: &'a str,
+    source: &'a str,
+}
+
+#[derive(Serialize)]
+struct CommitCreation<'a> {
+    message: &'a str,
+    metadata: &'a HashMap<String, String>,
+}
+
+/// A commit on a lakeFS branch
+#[derive(Deserialize, Debug, Clone)]
+pub struct Commit {
+    /// The id of the commit
+    pub id: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+impl LakeFSClient {
+    /// Create a new client for the lakeFS server at `endpoint`
+    pub fn new(
+        endpoint: Url,
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: Client::new(),
+            endpoint,
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/api/v1/{path}",
+            self.endpoint.as_str().trim_end_matches('/')
+        )
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response, LakeFSError> {
+        let response = request
+            .basic_auth(&self.access_key_id, Some(&self.secret_access_key))
+            .send()
+            .await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let message = match response.json::<ErrorResponse>().await {
+            Ok(err) => err.message,
+            Err(_) => status.canonical_reason().unwrap_or_default().to_string(),
+        };
+        Err(LakeFSError::Api { status, message })
+    }
+
+    /// Create `branch` from the head of `source`
+    pub async fn create_branch(
+        &self,
+        repository: &str,
+        branch: &str,
+        source: &str,
+    ) -> Result<(), LakeFSError> {
+        debug!("Creating lakeFS branch {repository}/{branch} from {source}");
+        let request = self
+            .client
+            .post(self.url(&format!("repositories/{repository}/branches")))
+            .json(&BranchCreation {
+                name: branch,
+                source,
+            });
+        self.send(request).await?;
+        Ok(())
+    }
+
+    /// Delete `branch`, discarding all of its changes
+    pub async fn delete_branch(&self, repository: &str, branch: &str) -> Result<(), LakeFSError> {
+        debug!("Deleting lakeFS branch {repository}/{branch}");
+        let request = self
+            .client
+            .delete(self.url(&format!("repositories/{repository}/branches/{branch}")));
+        self.send(request).await?;
+        Ok(())
+    }
+
+    /// Commit all uncommitted changes on `branch`
+    pub async fn commit(
+        &self,
+        repository: &str,
+        branch: &str,
+        message: &str,
+        metadata: &HashMap<String, String>,
+    ) -> Result<Commit, LakeFSError> {
+        let request = self
+            .client
+            .post(self.url(&format!(
+                "repositories/{repository}/branches/{branch}/commits"
+            )))
+            .json(&CommitCreation { message, metadata });
+        Ok(self.send(request).await?.json().await?)
+    }
+
+    /// Merge `source` into `destination`.
+    ///
+    /// lakeFS rejects the merge with a [conflict](LakeFSError::is_conflict) if both branches
+    /// changed the same object since they diverged.
+    pub async fn merge(
+        &self,
+        repository: &str,
+        source: &str,
+        destination: &str,
+        message: &str,
+        metadata: &HashMap<String, String>,
+    ) -> Result<(), LakeFSError> {
+        let request = self
+            .client
+            .post(self.url(&format!(
+                "repositories/{repository}/refs/{source}/merge/{destination}"
+            )))
+            .json(&CommitCreation { message, metadata });
+        self.send(request).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+//! Errors for the lakeFS log store
+
+use deltalake_core::DeltaTableError;
+use reqwest::StatusCode;
+
+pub(crate) const STORE_NAME: &str = "LakeFS";
+
+/// Errors produced when talking to lakeFS
+#[derive(thiserror::Error, Debug)]
+pub enum LakeFSError {
+    /// The table url does not point at a path on a lakeFS branch
+    #[error("Invalid lakeFS table url '{url}', expected lakefs://<repository>/<branch>/<path>")]
+    InvalidUrl {
+        /// The url of the table
+        url: String,
+    },
+
+    /// A required configuration option has not been provided
+    #[error("Missing lakeFS configuration option {0}")]
+    MissingConfig(&'static str),
+
+    /// The lakeFS API rejected a request
+    #[error("lakeFS request failed with status {status}: {message}")]
+    Api {
+        /// Http status of the response
+        status: StatusCode,
+        /// Error message returned by lakeFS
+        message: String,
+    },
+
+    /// The request could not be sent to lakeFS
+    #[error("lakeFS request failed: {source}")]
+    Request {
+        /// Underlying http client error
+        #[from]
+        source: reqwest::Error,
+    },
+
+    /// Staging files on a lakeFS branch failed
+    #[error("Failed to access lakeFS branch: {source}")]
+    ObjectStore {
+        /// Underlying object store error
+        #[from]
+        source: object_store::Error,
+    },
+}
+
+impl LakeFSError {
+    /// Whether lakeFS rejected the request due to conflicting changes
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, Self::Api { status, .. } if *status == StatusCode::CONFLICT)
+    }
+}
+
+impl From<LakeFSError> for DeltaTableError {
+    fn from(err: LakeFSError) -> Self {
+        DeltaTableError::GenericError {
+            source: Box::new(err),
+        }
+    }
+}
+
+impl From<LakeFSError> for object_store::Error {
+    fn from(err: LakeFSError) -> Self {
+        match err {
+            LakeFSError::ObjectStore { source } => source,
+            err => object_store::Error::Generic {
+                store: STORE_NAME,
+                source: Box::new(err),
+            },
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! lakeFS support for Delta tables.
+//!
+//! Tables are addressed as `lakefs://<repository>/<branch>/<path>`. Every operation writes
+//! its files to a dedicated lakeFS branch created from the branch of the table. Once the delta
+//! commit succeeds the transaction branch is committed and merged, so that each delta version
+//! maps to exactly one lakeFS merge commit. Failed or aborted operations delete their branch
+//! and leave the branch of the table untouched.
+//!
+//! Transaction branches are scoped to a single operation, so concurrent operations on the same
+//! [DeltaTable](deltalake_core::DeltaTable) neither see nor discard each other's files. Files
+//! written outside of an operation, e.g. by the
+//! [RecordBatchWriter](deltalake_core::writer::RecordBatchWriter), are merged into the branch
+//! of the table as they are written.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use deltalake_core::logstore::{logstore_factories, LogStore, LogStoreConfig, LogStoreFactory};
+use deltalake_core::storage::{factories, ObjectStoreFactory, ObjectStoreRef, StorageOptions};
+use deltalake_core::{DeltaResult, Path};
+use url::Url;
+
+pub mod client;
+pub mod errors;
+pub mod logstore;
+#[cfg(test)]
+mod mock_server;
+pub mod storage;
+
+pub use client::LakeFSClient;
+pub use errors::LakeFSError;
+pub use logstore::LakeFSLogStore;
+pub use storage::{LakeFSObjectStore, LakeFSTable};
+
+/// Configuration keys for lakeFS tables, also read from environment variables of the same name
+pub mod constants {
+    /// Url of the lakeFS server, e.g. `http://localhost:8000`
+    pub const LAKEFS_ENDPOINT: &str = "LAKEFS_ENDPOINT";
+    /// Access key id used for the lakeFS API and S3 gateway
+    pub const LAKEFS_ACCESS_KEY_ID: &str = "LAKEFS_ACCESS_KEY_ID";
+    /// Secret access key used for the lakeFS API and S3 gateway
+    pub const LAKEFS_SECRET_ACCESS_KEY: &str = "LAKEFS_SECRET_ACCESS_KEY";
+}
+
+pub(crate) fn str_option(map: &HashMap<String, String>, key: &str) -> Option<String> {
+    if let Some(s) = map.get(key) {
+        return Some(s.to_owned());
+    }
+
+    if let Some(s) = map.get(&key.to_ascii_lowercase()) {
+        return Some(s.to_owned());
+    }
+
+    std::env::var(key).ok()
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct LakeFSFactory {}
+
+impl ObjectStoreFactory for LakeFSFactory {
+    fn parse_url_opts(
+        &self,
+        url: &Url,
+        options: &StorageOptions,
+    ) -> DeltaResult<(ObjectStoreRef, Path)> {
+        let store = LakeFSObjectStore::try_new(url, options)?;
+        Ok((Arc::new(store), Path::default()))
+    }
+}
+
+impl LogStoreFactory for LakeFSFactory {
+    fn with_options(
+        &self,
+        _store: ObjectStoreRef,
+        location: &Url,
+        options: &StorageOptions,
+    ) -> DeltaResult<Arc<dyn LogStore>> {
+        // The log store needs to manage transaction branches, which is not possible
+        // through the type erased store
+        let store = LakeFSObjectStore::try_new(location, options)?;
+        Ok(Arc::new(LakeFSLogStore::new(
+            Arc::new(store),
+            LogStoreConfig {
+                location: location.clone(),
+                options: options.clone(),
+            },
+        )))
+    }
+}
+
+/// Register an [ObjectStoreFactory] and [LogStoreFactory] for `lakefs://` urls
+pub fn register_handlers(_additional_prefixes: Option<Url>) {
+    let factory = Arc::new(LakeFSFactory {});
+    let url = Url::parse("lakefs://").unwrap();
+    factories().insert(url.clone(), factory.clone());
+    logstore_factories().insert(url.clone(), factory.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> StorageOptions {
+        StorageOptions(HashMap::from([
+            (
+                constants::LAKEFS_ENDPOINT.to_string(),
+                "http://localhost:8000".to_string(),
+            ),
+            (
+                constants::LAKEFS_ACCESS_KEY_ID.to_lowercase(),
+                "key".to_string(),
+            ),
+            (
+                constants::LAKEFS_SECRET_ACCESS_KEY.to_string(),
+                "secret".to_string(),
+            ),
+        ]))
+    }
+
+    #[test]
+    fn test_logstore_factory() {
+        let url = Url::parse("lakefs://repo/main/table").unwrap();
+        let factory = LakeFSFactory::default();
+        let (store, _) = factory.parse_url_opts(&url, &options()).unwrap();
+        let logstore = factory.with_options(store, &url, &options()).unwrap();
+        assert_eq!(logstore.name(), "LakeFSLogStore");
+    }
+
+    #[test]
+    fn test_missing_endpoint() {
+        let url = Url::parse("lakefs://repo/main/table").unwrap();
+        let mut options = options();
+        options.0.remove(constants::LAKEFS_ENDPOINT);
+        if std::env::var(constants::LAKEFS_ENDPOINT).is_err() {
+            assert!(LakeFSObjectStore::try_new(&url, &options).is_err());
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Log store committing delta transactions as lakeFS merges.
+//!
+//! Each operation gets its own log store, see [LogStore::for_operation], which stages the
+//! files of the operation on a dedicated branch. Committing version `N` writes the temporary
+//! commit to `N.json` on that branch, commits the branch and merges it into the branch of the
+//! table. lakeFS rejects the merge if another writer committed the same version in the
+//! meantime, in which case `N.json` is removed from the transaction branch again and the
+//! temporary commit is kept, so the commit can be retried with the next version. Aborting a
+//! transaction deletes its branch.
+use std::sync::Arc;
+
+use bytes::Bytes;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use tracing::debug;
+use uuid::Uuid;
+
+use deltalake_core::logstore::{LogStore, LogStoreConfig, LogStoreRef};
+use deltalake_core::operations::transaction::TransactionError;
+use deltalake_core::storage::commit_uri_from_version;
+use deltalake_core::DeltaResult;
+
+use crate::storage::LakeFSObjectStore;
+
+/// [`LogStore`] implementation for tables on a lakeFS branch
+#[derive(Debug, Clone)]
+pub struct LakeFSLogStore {
+    pub(crate) storage: Arc<LakeFSObjectStore>,
+    config: LogStoreConfig,
+}
+
+impl LakeFSLogStore {
+    /// Create a new instance of [`LakeFSLogStore`]
+    pub fn new(storage: Arc<LakeFSObjectStore>, config: LogStoreConfig) -> Self {
+        Self { storage, config }
+    }
+}
+
+#[async_trait::async_trait]
+impl LogStore for LakeFSLogStore {
+    fn name(&self) -> String {
+        "LakeFSLogStore".into()
+    }
+
+    async fn read_commit_entry(&self, version: i64) -> DeltaResult<Option<Bytes>> {
+        deltalake_core::logstore::read_commit_entry(self.storage.target().as_ref(), version).await
+    }
+
+    /// Merges `tmp_commit` and the transaction branch of the operation into the branch of the
+    /// table.
+    ///
+    /// Returns [`TransactionError::VersionAlreadyExists`] if `version` has already been
+    /// committed by another writer, in which case `tmp_commit` can be retried with the next
+    /// version.
+    async fn write_commit_entry(
+        &self,
+        version: i64,
+        tmp_commit: &Path,
+    ) -> Result<(), TransactionError> {
+        let commit_uri = commit_uri_from_version(version);
+        if self.storage.target().head(&commit_uri).await.is_ok() {
+            return Err(TransactionError::VersionAlreadyExists(version));
+        }
+        match self.storage.commit_version(version, tmp_commit).await {
+            Ok(()) => {
+                debug!("Merged lakeFS transaction for version {version}");
+                self.storage.end_transaction(tmp_commit).await;
+                Ok(())
+            }
+            Err(err) if err.is_conflict() => Err(TransactionError::VersionAlreadyExists(version)),
+            Err(err) => Err(TransactionError::LogStoreError {
+                msg: format!("Failed to commit version {version} to lakeFS"),
+                source: Box::new(err),
+            }),
+        }
+    }
+
+    async fn abort_commit_entry(
+        &self,
+        _version: i64,
+        tmp_commit: &Path,
+    ) -> Result<(), TransactionError> {
+        self.storage.end_transaction(tmp_commit).await;
+        Ok(())
+    }
+
+    async fn get_latest_version(&self, current_version: i64) -> DeltaResult<i64> {
+        deltalake_core::logstore::get_latest_version(self, current_version).await
+    }
+
+    fn object_store(&self) -> Arc<dyn ObjectStore> {
+        self.storage.clone()
+    }
+
+    fn for_operation(&self, operation_id: Uuid) -> Option<LogStoreRef> {
+        Some(Arc::new(Self::new(
+            Arc::new(self.storage.for_operation(operation_id)),
+            self.config.clone(),
+        )))
+    }
+
+    fn config(&self) -> &LogStoreConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deltalake_core::storage::StorageOptions;
+    use url::Url;
+
+    use crate::mock_server::MockLakeFS;
+    use crate::storage::LakeFSTable;
+
+    fn log_store(server: &MockLakeFS) -> LakeFSLogStore {
+        let location = Url::parse("lakefs://repo/main/table").unwrap();
+        let table = LakeFSTable::try_from_url(&location).unwrap();
+        let storage = LakeFSObjectStore::new(server.client(), table, server.repository_store());
+        LakeFSLogStore::new(
+            Arc::new(storage),
+            LogStoreConfig {
+                location,
+                options: StorageOptions::default(),
+            },
+        )
+    }
+
+    fn operation(log_store: &LakeFSLogStore) -> LakeFSLogStore {
+        LakeFSLogStore::new(
+            Arc::new(log_store.storage.for_operation(Uuid::new_v4())),
+            log_store.config.clone(),
+        )
+    }
+
+    /// Write a data file and a temporary commit adding it
+    async fn stage(log_store: &LakeFSLogStore, file: &str) -> Path {
+        let store = log_store.object_store();
+        store
+            .put(&Path::from(file), Bytes::from_static(b"data").into())
+            .await
+            .unwrap();
+        let tmp_commit = Path::from(format!("_delta_log/_commit_{}.json.tmp", Uuid::new_v4()));
+        store
+            .put(&tmp_commit, Bytes::from(file.to_string()).into())
+            .await
+            .unwrap();
+        tmp_commit
+    }
+
+    async fn on_table_branch(server: &MockLakeFS, file: &str) -> bool {
+        let location = Path::from(format!("main/table/{file}"));
+        server.repository_store().head(&location).await.is_ok()
+    }
+
+    #[tokio::test]
+    async fn test_commit() {
+        let server = MockLakeFS::new();
+        let table = log_store(&server);
+        let operation = operation(&table);
+
+        let tmp_commit = stage(&operation, "part-0.parquet").await;
+        // staged files are only visible within the operation
+        assert!(!on_table_branch(&server, "part-0.parquet").await);
+        assert!(operation
+            .object_store()
+            .head(&Path::from("part-0.parquet"))
+            .await
+            .is_ok());
+        assert!(table.object_store().head(&tmp_commit).await.is_err());
+
+        operation.write_commit_entry(0, &tmp_commit).await.unwrap();
+        assert!(on_table_branch(&server, "part-0.parquet").await);
+        assert_eq!(
+            table.read_commit_entry(0).await.unwrap(),
+            Some(Bytes::from("part-0.parquet"))
+        );
+        assert!(operation.object_store().head(&tmp_commit).await.is_err());
+        assert!(server.branches().await.is_empty());
+
+        server.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_commit_conflict() {
+        let server = MockLakeFS::new();
+        let table = log_store(&server);
+        let first = operation(&table);
+        let second = operation(&table);
+
+        let first_commit = stage(&first, "part-0.parquet").await;
+        let second_commit = stage(&second, "part-1.parquet").await;
+        first.write_commit_entry(0, &first_commit).await.unwrap();
+
+        // version 0 was committed after the second operation checked for it
+        let err = second
+            .storage
+            .commit_version(0, &second_commit)
+            .await
+            .unwrap_err();
+        assert!(err.is_conflict());
+        let result = second.write_commit_entry(0, &second_commit).await;
+        assert!(matches!(
+            result,
+            Err(TransactionError::VersionAlreadyExists(0))
+        ));
+
+        // the temporary commit is kept for the retry with the next version
+        second.write_commit_entry(1, &second_commit).await.unwrap();
+        assert!(on_table_branch(&server, "part-0.parquet").await);
+        assert!(on_table_branch(&server, "part-1.parquet").await);
+        assert_eq!(
+            table.read_commit_entry(0).await.unwrap(),
+            Some(Bytes::from("part-0.parquet"))
+        );
+        assert_eq!(
+            table.read_commit_entry(1).await.unwrap(),
+            Some(Bytes::from("part-1.parquet"))
+        );
+        assert!(server.branches().await.is_empty());
+
+        server.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_abort() {
+        let server = MockLakeFS::new();
+        let table = log_store(&server);
+        let first = operation(&table);
+        let second = operation(&table);
+
+        let first_commit = stage(&first, "part-0.parquet").await;
+        let second_commit = stage(&second, "part-1.parquet").await;
+        second.abort_commit_entry(0, &second_commit).await.unwrap();
+        assert!(second.object_store().head(&second_commit).await.is_err());
+
+        // aborting one operation leaves the files of other operations in place
+        first.write_commit_entry(0, &first_commit).await.unwrap();
+        assert!(on_table_branch(&server, "part-0.parquet").await);
+        assert!(!on_table_branch(&server, "part-1.parquet").await);
+        assert!(server.branches().await.is_empty());
+
+        server.shutdown().await;
+    }
+}
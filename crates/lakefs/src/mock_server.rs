@@ -0,0 +1,245 @@
+//! In-process stand-in for the lakeFS API, backed by an in-memory repository
+use std::collections::{BTreeSet, HashMap};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::TryStreamExt;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use serde::Deserialize;
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
+use url::Url;
+
+use deltalake_core::storage::ObjectStoreRef;
+
+use crate::client::LakeFSClient;
+
+type Snapshot = HashMap<Path, Bytes>;
+
+#[derive(Deserialize)]
+struct BranchCreation {
+    name: String,
+    source: String,
+}
+
+/// Branches of a single repository, stored as `<branch>/<path>` objects
+struct Repository {
+    store: Arc<InMemory>,
+    /// Contents of each created branch when it was created or last merged
+    merge_bases: HashMap<String, Snapshot>,
+}
+
+impl Repository {
+    async fn snapshot(&self, branch: &str) -> Snapshot {
+        let metas: Vec<_> = self
+            .store
+            .list(Some(&Path::from(branch)))
+            .try_collect()
+            .await
+            .unwrap();
+        let mut snapshot = Snapshot::new();
+        for meta in metas {
+            let data = self.store.get(&meta.location).await.unwrap();
+            let path = Path::from_iter(meta.location.parts().skip(1));
+            snapshot.insert(path, data.bytes().await.unwrap());
+        }
+        snapshot
+    }
+
+    async fn create_branch(&mut self, body: Bytes) -> Response<Body> {
+        let creation: BranchCreation = serde_json::from_slice(&body).unwrap();
+        if self.merge_bases.contains_key(&creation.name) {
+            return error(StatusCode::CONFLICT, "branch already exists");
+        }
+        let snapshot = self.snapshot(&creation.source).await;
+        for (path, data) in &snapshot {
+            let location = branch_path(&creation.name, path);
+            self.store
+                .put(&location, data.clone().into())
+                .await
+                .unwrap();
+        }
+        self.merge_bases.insert(creation.name, snapshot);
+        json(StatusCode::CREATED, "{}")
+    }
+
+    async fn delete_branch(&mut self, branch: &str) -> Response<Body> {
+        if self.merge_bases.remove(branch).is_none() {
+            return error(StatusCode::NOT_FOUND, "branch not found");
+        }
+        for path in self.snapshot(branch).await.keys() {
+            self.store.delete(&branch_path(branch, path)).await.unwrap();
+        }
+        json(StatusCode::NO_CONTENT, "")
+    }
+
+    /// Three way merge of the changes on `source` since its merge base into `destination`
+    async fn merge(&mut self, source: &str, destination: &str) -> Response<Body> {
+        let Some(base) = self.merge_bases.get(source) else {
+            return error(StatusCode::NOT_FOUND, "branch not found");
+        };
+        let current = self.snapshot(source).await;
+        let target = self.snapshot(destination).await;
+        let changed: BTreeSet<_> = base
+            .keys()
+            .chain(current.keys())
+            .filter(|path| base.get(*path) != current.get(*path))
+            .cloned()
+            .collect();
+        for path in &changed {
+            let theirs = target.get(path);
+            if theirs != base.get(path) && theirs != current.get(path) {
+                return error(StatusCode::CONFLICT, "conflict");
+            }
+        }
+        for path in &changed {
+            let location = branch_path(destination, path);
+            match current.get(path) {
+                Some(data) => {
+                    self.store
+                        .put(&location, data.clone().into())
+                        .await
+                        .unwrap();
+                }
+                None => self.store.delete(&location).await.unwrap(),
+            }
+        }
+        self.merge_bases.insert(source.to_string(), current);
+        json(StatusCode::OK, "{}")
+    }
+
+    async fn handle(&mut self, request: Request<Body>) -> Response<Body> {
+        let method = request.method().clone();
+        let path = request.uri().path().to_string();
+        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+        let parts: Vec<_> = path.trim_start_matches('/').split('/').collect();
+        match (method, parts.as_slice()) {
+            (Method::POST, ["api", "v1", "repositories", _, "branches"]) => {
+                self.create_branch(body).await
+            }
+            (Method::DELETE, ["api", "v1", "repositories", _, "branches", branch]) => {
+                self.delete_branch(branch).await
+            }
+            (Method::POST, ["api", "v1", "repositories", _, "branches", _, "commits"]) => {
+                json(StatusCode::CREATED, r#"{"id": "c0ffee"}"#)
+            }
+            (Method::POST, ["api", "v1", "repositories", _, "refs", source, "merge", target]) => {
+                self.merge(source, target).await
+            }
+            _ => error(StatusCode::NOT_FOUND, "unknown route"),
+        }
+    }
+}
+
+fn branch_path(branch: &str, path: &Path) -> Path {
+    Path::from_iter(std::iter::once(branch).chain(path.parts().map(|part| part.as_ref())))
+}
+
+fn json(status: StatusCode, body: &'static str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(format!(r#"{{"message": "{message}"}}"#)))
+        .unwrap()
+}
+
+/// A mock lakeFS server for a single repository
+pub struct MockLakeFS {
+    repository: Arc<Mutex<Repository>>,
+    store: Arc<InMemory>,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+    url: Url,
+}
+
+impl Default for MockLakeFS {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockLakeFS {
+    pub fn new() -> Self {
+        let store = Arc::new(InMemory::new());
+        let repository = Arc::new(Mutex::new(Repository {
+            store: store.clone(),
+            merge_bases: HashMap::new(),
+        }));
+
+        let r = Arc::clone(&repository);
+        let make_service = make_service_fn(move |_conn| {
+            let r = Arc::clone(&r);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let r = Arc::clone(&r);
+                    async move { Ok::<_, Infallible>(r.lock().await.handle(req).await) }
+                }))
+            }
+        });
+
+        let (shutdown, rx) = oneshot::channel::<()>();
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+        let url = Url::parse(&format!("http://{}", server.local_addr())).unwrap();
+
+        let handle = tokio::spawn(async move {
+            server
+                .with_graceful_shutdown(async {
+                    rx.await.ok();
+                })
+                .await
+                .unwrap()
+        });
+
+        Self {
+            repository,
+            store,
+            shutdown,
+            handle,
+            url,
+        }
+    }
+
+    /// A client for the mock server
+    pub fn client(&self) -> LakeFSClient {
+        LakeFSClient::new(self.url.clone(), "key", "secret")
+    }
+
+    /// The contents of the repository, as served by the S3 gateway of lakeFS
+    pub fn repository_store(&self) -> ObjectStoreRef {
+        self.store.clone()
+    }
+
+    /// Names of the branches created through the API which have not been deleted
+    pub async fn branches(&self) -> Vec<String> {
+        let mut branches: Vec<_> = self
+            .repository
+            .lock()
+            .await
+            .merge_bases
+            .keys()
+            .cloned()
+            .collect();
+        branches.sort();
+        branches
+    }
+
+    /// Shutdown the mock server
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(());
+        self.handle.await.unwrap()
+    }
+}
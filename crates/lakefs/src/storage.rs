@@ -0,0 +1,574 @@
+//! Object store for tables on a lakeFS branch.
+//!
+//! Stores scoped to an operation, see [LakeFSObjectStore::for_operation], stage the files
+//! written by the operation on a dedicated transaction branch, which is only merged into the
+//! branch of the table once the operation commits. Reads of data files are served from the
+//! transaction branch while one is open, so a writer observes its own changes. Writes through
+//! a store which is not scoped to an operation are committed to the branch of the table
+//! immediately, and so are writes to the delta log, e.g. checkpoints. Temporary commit files
+//! are kept in memory until their commit succeeds or is aborted.
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::aws::AmazonS3Builder;
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts,
+    PutOptions, PutPayload, PutResult, Result as ObjectStoreResult,
+};
+use tokio::runtime::Handle;
+use tracing::warn;
+use url::Url;
+use uuid::Uuid;
+
+use deltalake_core::storage::{
+    commit_uri_from_version, retry_store_handler, url_prefix_handler, ObjectStoreRef,
+    StorageOptions,
+};
+use deltalake_core::DeltaResult;
+
+use crate::client::LakeFSClient;
+use crate::errors::LakeFSError;
+use crate::{constants, str_option};
+
+/// Location of a table within a lakeFS repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LakeFSTable {
+    /// The lakeFS repository
+    pub repository: String,
+    /// The branch the table is read from and committed to
+    pub branch: String,
+    /// The path of the table within the branch
+    pub path: Path,
+}
+
+impl LakeFSTable {
+    /// Parse a `lakefs://<repository>/<branch>/<path>` url
+    pub fn try_from_url(url: &Url) -> Result<Self, LakeFSError> {
+        let invalid = || LakeFSError::InvalidUrl {
+            url: url.to_string(),
+        };
+        if url.scheme() != "lakefs" {
+            return Err(invalid());
+        }
+        let repository = url.host_str().filter(|host| !host.is_empty());
+        let full_path = Path::from_url_path(url.path()).map_err(|_| invalid())?;
+        let mut parts = full_path.parts();
+        match (repository, parts.next()) {
+            (Some(repository), Some(branch)) => Ok(Self {
+                repository: repository.to_string(),
+                branch: branch.as_ref().to_string(),
+                path: Path::from_iter(parts),
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Branch staging the changes of the current transaction
+#[derive(Debug, Clone)]
+pub(crate) struct TransactionBranch {
+    pub(crate) name: String,
+    pub(crate) store: ObjectStoreRef,
+}
+
+fn is_in_log(location: &Path) -> bool {
+    location
+        .parts()
+        .next()
+        .is_some_and(|part| part.as_ref() == "_delta_log")
+}
+
+/// Whether `location` is a temporary commit file
+fn is_tmp_commit(location: &Path) -> bool {
+    is_in_log(location)
+        && location
+            .filename()
+            .is_some_and(|name| name.ends_with(".tmp"))
+}
+
+/// Whether `location` is read from and written to the branch of the table directly
+fn is_log_path(location: &Path) -> bool {
+    is_in_log(location) && !is_tmp_commit(location)
+}
+
+/// [ObjectStore] for a table on a lakeFS branch
+pub struct LakeFSObjectStore {
+    client: LakeFSClient,
+    table: LakeFSTable,
+    /// S3 gateway store for the whole repository
+    repository_store: ObjectStoreRef,
+    /// The table on its branch
+    target: ObjectStoreRef,
+    /// The operation whose files are staged on the transaction branch
+    operation_id: Option<Uuid>,
+    transaction: Mutex<Option<TransactionBranch>>,
+    /// Serializes the creation of transaction branches
+    creating_transaction: tokio::sync::Mutex<()>,
+    /// Temporary commit files, which are kept until their commit succeeds or is aborted
+    tmp_commits: ObjectStoreRef,
+}
+
+impl std::fmt::Display for LakeFSObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "LakeFSObjectStore({}/{}/{})",
+            self.table.repository, self.table.branch, self.table.path
+        )
+    }
+}
+
+impl std::fmt::Debug for LakeFSObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl LakeFSObjectStore {
+    /// Create a store for the table at `url`, accessing lakeFS through its S3 gateway.
+    ///
+    /// Options are described in [constants].
+    pub fn try_new(url: &Url, options: &StorageOptions) -> DeltaResult<Self> {
+        let table = LakeFSTable::try_from_url(url)?;
+        let endpoint = str_option(&options.0, constants::LAKEFS_ENDPOINT)
+            .ok_or(LakeFSError::MissingConfig(constants::LAKEFS_ENDPOINT))?;
+        let endpoint = Url::parse(&endpoint).map_err(|_| LakeFSError::InvalidUrl {
+            url: endpoint.clone(),
+        })?;
+        let access_key_id = str_option(&options.0, constants::LAKEFS_ACCESS_KEY_ID)
+            .ok_or(LakeFSError::MissingConfig(constants::LAKEFS_ACCESS_KEY_ID))?;
+        let secret_access_key = str_option(&options.0, constants::LAKEFS_SECRET_ACCESS_KEY).ok_or(
+            LakeFSError::MissingConfig(constants::LAKEFS_SECRET_ACCESS_KEY),
+        )?;
+
+        let repository_store = retry_store_handler(
+            AmazonS3Builder::new()
+                .with_endpoint(endpoint.as_str().trim_end_matches('/'))
+                .with_bucket_name(&table.repository)
+                .with_region("us-east-1")
+                .with_access_key_id(&access_key_id)
+                .with_secret_access_key(&secret_access_key)
+                .with_allow_http(endpoint.scheme() == "http")
+                .build()?,
+            options,
+        )?;
+        let client = LakeFSClient::new(endpoint, access_key_id, secret_access_key);
+        Ok(Self::new(client, table, repository_store))
+    }
+
+    /// Create a store for `table` on top of the S3 gateway store for its repository
+    pub fn new(client: LakeFSClient, table: LakeFSTable, repository_store: ObjectStoreRef) -> Self {
+        let target = url_prefix_handler(
+            repository_store.clone(),
+            branch_prefix(&table.branch, &table.path),
+        );
+        Self {
+            client,
+            table,
+            repository_store,
+            target,
+            operation_id: None,
+            transaction: Mutex::new(None),
+            creating_transaction: tokio::sync::Mutex::new(()),
+            tmp_commits: Arc::new(InMemory::new()),
+        }
+    }
+
+    /// Create a store for the operation identified by `operation_id`, which stages its files
+    /// on its own transaction branch until the operation commits
+    pub fn for_operation(&self, operation_id: Uuid) -> Self {
+        Self {
+            client: self.client.clone(),
+            table: self.table.clone(),
+            repository_store: self.repository_store.clone(),
+            target: self.target.clone(),
+            operation_id: Some(operation_id),
+            transaction: Mutex::new(None),
+            creating_transaction: tokio::sync::Mutex::new(()),
+            tmp_commits: Arc::new(InMemory::new()),
+        }
+    }
+
+    /// The table on its branch, bypassing any open transaction
+    pub(crate) fn target(&self) -> &ObjectStoreRef {
+        &self.target
+    }
+
+    fn branch_store(&self, branch: &str) -> ObjectStoreRef {
+        url_prefix_handler(
+            self.repository_store.clone(),
+            branch_prefix(branch, &self.table.path),
+        )
+    }
+
+    fn current_transaction(&self) -> Option<TransactionBranch> {
+        self.transaction.lock().unwrap().clone()
+    }
+
+    /// The branch of the current transaction, which is created if necessary
+    async fn transaction(&self) -> Result<TransactionBranch, LakeFSError> {
+        let _guard = self.creating_transaction.lock().await;
+        if let Some(transaction) = self.current_transaction() {
+            return Ok(transaction);
+        }
+        let name = format!("delta-tx-{}", Uuid::new_v4());
+        self.client
+            .create_branch(&self.table.repository, &name, &self.table.branch)
+            .await?;
+        let transaction = TransactionBranch {
+            store: self.branch_store(&name),
+            name,
+        };
+        *self.transaction.lock().unwrap() = Some(transaction.clone());
+        Ok(transaction)
+    }
+
+    /// Commit `tmp_commit` as `version` of the table.
+    ///
+    /// Within an operation the commit is merged into the branch of the table together with the
+    /// files staged on the transaction branch, otherwise it is merged on its own. The temporary
+    /// commit is kept if this fails, so that it can be retried with another version.
+    pub(crate) async fn commit_version(
+        &self,
+        version: i64,
+        tmp_commit: &Path,
+    ) -> Result<(), LakeFSError> {
+        let commit = self.tmp_commits.get(tmp_commit).await?.bytes().await?;
+        let commit_uri = commit_uri_from_version(version);
+        let message = format!("Delta commit version {version}");
+        let Some(operation_id) = self.operation_id else {
+            return self
+                .commit_log_write(message, move |store| async move {
+                    store.put(&commit_uri, commit.into()).await
+                })
+                .await
+                .map(|_| ());
+        };
+
+        let repository = &self.table.repository;
+        let transaction = self.transaction().await?;
+        let metadata = HashMap::from([
+            ("delta_version".to_string(), version.to_string()),
+            ("delta_table".to_string(), self.table.path.to_string()),
+            ("delta_operation_id".to_string(), operation_id.to_string()),
+        ]);
+        let result = async {
+            transaction.store.put(&commit_uri, commit.into()).await?;
+            self.client
+                .commit(repository, &transaction.name, &message, &metadata)
+                .await?;
+            self.client
+                .merge(
+                    repository,
+                    &transaction.name,
+                    &self.table.branch,
+                    &message,
+                    &metadata,
+                )
+                .await
+        }
+        .await;
+        if result.is_err() {
+            // The next attempt commits the transaction branch with another version
+            if let Err(err) = transaction.store.delete(&commit_uri).await {
+                warn!(
+                    "Failed to remove {commit_uri} from lakeFS branch {}: {err}",
+                    transaction.name
+                );
+            }
+        }
+        result
+    }
+
+    /// Drop `tmp_commit`, close the current transaction and delete its branch
+    pub(crate) async fn end_transaction(&self, tmp_commit: &Path) {
+        if let Err(err) = self.tmp_commits.delete(tmp_commit).await {
+            warn!("Failed to remove temporary commit {tmp_commit}: {err}");
+        }
+        let transaction = self.transaction.lock().unwrap().take();
+        if let Some(transaction) = transaction {
+            self.delete_branch(&transaction.name).await;
+        }
+    }
+
+    async fn delete_branch(&self, branch: &str) {
+        if let Err(err) = self
+            .client
+            .delete_branch(&self.table.repository, branch)
+            .await
+        {
+            warn!("Failed to delete lakeFS branch {branch}: {err}");
+        }
+    }
+
+    fn read_store(&self, location: Option<&Path>) -> ObjectStoreRef {
+        if location.is_some_and(is_tmp_commit) {
+            return self.tmp_commits.clone();
+        }
+        match self.current_transaction() {
+            Some(transaction) if !location.is_some_and(is_log_path) => transaction.store,
+            _ => self.target.clone(),
+        }
+    }
+
+    /// The store for writing to `location` within the current operation, or `None` if the
+    /// write has to be committed to the branch of the table right away
+    async fn write_store(&self, location: &Path) -> ObjectStoreResult<Option<ObjectStoreRef>> {
+        if is_tmp_commit(location) {
+            Ok(Some(self.tmp_commits.clone()))
+        } else if is_log_path(location) || self.operation_id.is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(self.transaction().await?.store))
+        }
+    }
+
+    /// Apply a write on a short-lived branch that is merged right away
+    async fn commit_log_write<T, F, Fut>(&self, message: String, op: F) -> Result<T, LakeFSError>
+    where
+        T: Send,
+        F: FnOnce(ObjectStoreRef) -> Fut + Send,
+        Fut: Future<Output = ObjectStoreResult<T>> + Send,
+    {
+        let repository = &self.table.repository;
+        let branch = format!("delta-log-{}", Uuid::new_v4());
+        self.client
+            .create_branch(repository, &branch, &self.table.branch)
+            .await?;
+        let result = async {
+            let value = op(self.branch_store(&branch)).await?;
+            let metadata = HashMap::new();
+            self.client
+                .commit(repository, &branch, &message, &metadata)
+                .await?;
+            self.client
+                .merge(repository, &branch, &self.table.branch, &message, &metadata)
+                .await?;
+            Ok::<_, LakeFSError>(value)
+        }
+        .await;
+        self.delete_branch(&branch).await;
+        result
+    }
+}
+
+fn branch_prefix(branch: &str, path: &Path) -> Path {
+    Path::from_iter(std::iter::once(branch).chain(path.parts().map(|part| part.as_ref())))
+}
+
+impl Drop for LakeFSObjectStore {
+    fn drop(&mut self) {
+        // Delete the branch of an operation which neither committed nor aborted, e.g. because
+        // it failed while writing its files
+        let transaction = self.transaction.get_mut().unwrap().take();
+        if let (Some(transaction), Ok(handle)) = (transaction, Handle::try_current()) {
+            let client = self.client.clone();
+            let repository = self.table.repository.clone();
+            handle.spawn(async move {
+                if let Err(err) = client.delete_branch(&repository, &transaction.name).await {
+                    warn!("Failed to delete lakeFS branch {}: {err}", transaction.name);
+                }
+            });
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for LakeFSObjectStore {
+    async fn put(&self, location: &Path, payload: PutPayload) -> ObjectStoreResult<PutResult> {
+        self.put_opts(location, payload, PutOptions::default())
+            .await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        options: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        if let Some(store) = self.write_store(location).await? {
+            return store.put_opts(location, payload, options).await;
+        }
+        let location = location.clone();
+        Ok(self
+            .commit_log_write(format!("Write {location}"), move |store| async move {
+                store.put_opts(&location, payload, options).await
+            })
+            .await?)
+    }
+
+    async fn put_multipart(&self, location: &Path) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.put_multipart_opts(location, PutMultipartOpts::default())
+            .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        options: PutMultipartOpts,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        match self.write_store(location).await? {
+            Some(store) => store.put_multipart_opts(location, options).await,
+            None => Err(object_store::Error::NotSupported {
+                source: format!(
+                    "Multipart upload of {location} requires a lakeFS store scoped to an operation"
+                )
+                .into(),
+            }),
+        }
+    }
+
+    async fn get(&self, location: &Path) -> ObjectStoreResult<GetResult> {
+        self.read_store(Some(location)).get(location).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        self.read_store(Some(location))
+            .get_opts(location, options)
+            .await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> ObjectStoreResult<Bytes> {
+        self.read_store(Some(location))
+            .get_range(location, range)
+            .await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> ObjectStoreResult<Vec<Bytes>> {
+        self.read_store(Some(location))
+            .get_ranges(location, ranges)
+            .await
+    }
+
+    async fn head(&self, location: &Path) -> ObjectStoreResult<ObjectMeta> {
+        self.read_store(Some(location)).head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> ObjectStoreResult<()> {
+        if let Some(store) = self.write_store(location).await? {
+            return store.delete(location).await;
+        }
+        let location = location.clone();
+        Ok(self
+            .commit_log_write(format!("Delete {location}"), move |store| async move {
+                store.delete(&location).await
+            })
+            .await?)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        let store = self.read_store(prefix);
+        let prefix = prefix.cloned();
+        futures::stream::once(
+            async move { store.list(prefix.as_ref()).try_collect::<Vec<_>>().await },
+        )
+        .map_ok(|metas| futures::stream::iter(metas.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'_, ObjectStoreResult<ObjectMeta>> {
+        let store = self.read_store(prefix);
+        let prefix = prefix.cloned();
+        let offset = offset.clone();
+        futures::stream::once(async move {
+            store
+                .list_with_offset(prefix.as_ref(), &offset)
+                .try_collect::<Vec<_>>()
+                .await
+        })
+        .map_ok(|metas| futures::stream::iter(metas.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.read_store(prefix).list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        if let Some(store) = self.write_store(to).await? {
+            return store.copy(from, to).await;
+        }
+        let (from, to) = (from.clone(), to.clone());
+        Ok(self
+            .commit_log_write(format!("Copy {from} to {to}"), move |store| async move {
+                store.copy(&from, &to).await
+            })
+            .await?)
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> ObjectStoreResult<()> {
+        if let Some(store) = self.write_store(to).await? {
+            return store.copy_if_not_exists(from, to).await;
+        }
+        let (from, to) = (from.clone(), to.clone());
+        Ok(self
+            .commit_log_write(format!("Copy {from} to {to}"), move |store| async move {
+                store.copy_if_not_exists(&from, &to).await
+            })
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_from_url() {
+        let url = Url::parse("lakefs://repo/main/path/to/table").unwrap();
+        assert_eq!(
+            LakeFSTable::try_from_url(&url).unwrap(),
+            LakeFSTable {
+                repository: "repo".into(),
+                branch: "main".into(),
+                path: Path::from("path/to/table"),
+            }
+        );
+
+        let url = Url::parse("lakefs://repo/main").unwrap();
+        let table = LakeFSTable::try_from_url(&url).unwrap();
+        assert_eq!(table.path, Path::default());
+        assert_eq!(
+            branch_prefix(&table.branch, &table.path),
+            Path::from("main")
+        );
+
+        for url in ["lakefs://repo", "lakefs://repo/", "s3://repo/main/table"] {
+            let url = Url::parse(url).unwrap();
+            assert!(LakeFSTable::try_from_url(&url).is_err());
+        }
+    }
+
+    #[test]
+    fn test_is_log_path() {
+        assert!(is_log_path(&Path::from("_delta_log")));
+        assert!(is_log_path(&Path::from(
+            "_delta_log/00000000000000000001.json"
+        )));
+        assert!(is_log_path(&Path::from("_delta_log/_last_checkpoint")));
+        assert!(!is_log_path(&Path::from(
+            "_delta_log/_commit_5a2b.json.tmp"
+        )));
+        assert!(!is_log_path(&Path::from("part-00000.parquet")));
+        assert!(!is_log_path(&Path::from(
+            "date=2024-01-01/part-00000.parquet"
+        )));
+    }
+}
@@ -27,7 +27,7 @@ impl From<Error> for DeltaTableError {
             Error::AllowUnsafeRenameNotSpecified => DeltaTableError::Generic(
                 "The `allow_unsafe_rename` parameter must be specified".to_string(),
             ),
-            Error::ObjectStore(e) => DeltaTableError::ObjectStore { source: e },
+            Error::ObjectStore(e) => e.into(),
         }
     }
 }
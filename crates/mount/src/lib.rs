@@ -2,9 +2,10 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use deltalake_core::logstore::{default_logstore, logstores, LogStore, LogStoreFactory};
+use deltalake_core::logstore::{default_logstore, logstore_factories, LogStore, LogStoreFactory};
 use deltalake_core::storage::{
-    factories, str_is_truthy, ObjectStoreFactory, ObjectStoreRef, StorageOptions,
+    factories, retry_store_handler, str_is_truthy, ObjectStoreFactory, ObjectStoreRef,
+    StorageOptions,
 };
 use deltalake_core::{DeltaResult, DeltaTableError, Path};
 use object_store::local::LocalFileSystem;
@@ -57,22 +58,18 @@ impl ObjectStoreFactory for MountFactory {
                 }
                 // We need to convert the dbfs url to a file url
                 let new_url = Url::parse(&format!("file:///dbfs{}", url.path())).unwrap();
-                let store = Arc::new(file::MountFileStorageBackend::try_new(
-                    new_url.to_file_path().unwrap(),
-                )?) as ObjectStoreRef;
-                Ok((store, Path::from("/")))
+                let store =
+                    file::MountFileStorageBackend::try_new(new_url.to_file_path().unwrap())?;
+                Ok((retry_store_handler(store, options)?, Path::from("/")))
             }
             "file" => {
                 if allow_unsafe_rename {
-                    let store = Arc::new(file::MountFileStorageBackend::try_new(
-                        url.to_file_path().unwrap(),
-                    )?) as ObjectStoreRef;
-                    Ok((store, Path::from("/")))
+                    let store =
+                        file::MountFileStorageBackend::try_new(url.to_file_path().unwrap())?;
+                    Ok((retry_store_handler(store, options)?, Path::from("/")))
                 } else {
-                    let store = Arc::new(LocalFileSystem::new_with_prefix(
-                        url.to_file_path().unwrap(),
-                    )?) as ObjectStoreRef;
-                    Ok((store, Path::from("/")))
+                    let store = LocalFileSystem::new_with_prefix(url.to_file_path().unwrap())?;
+                    Ok((retry_store_handler(store, options)?, Path::from("/")))
                 }
             }
             _ => Err(DeltaTableError::InvalidTableLocation(url.clone().into())),
@@ -97,6 +94,6 @@ pub fn register_handlers(_additional_prefixes: Option<Url>) {
     for scheme in ["dbfs", "file"].iter() {
         let url = Url::parse(&format!("{}://", scheme)).unwrap();
         factories().insert(url.clone(), factory.clone());
-        logstores().insert(url.clone(), factory.clone());
+        logstore_factories().insert(url.clone(), factory.clone());
     }
 }
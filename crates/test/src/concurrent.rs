@@ -1,22 +1,262 @@
-use std::collections::HashMap;
+//! Concurrent writer tests
+//!
+//! [`stress_test_appends`] lets many writers append to a table at the same time and validates
+//! that every append is committed exactly once with a unique version. Downstream users can run
+//! it against their storage backend and log store to validate their atomicity guarantees.
+//!
+//! ```rust,no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use std::collections::HashMap;
+//!
+//! use deltalake_test::concurrent::{stress_test_appends, StressTestConfig};
+//!
+//! let report = stress_test_appends(
+//!     "s3://bucket/stress-test",
+//!     HashMap::new(),
+//!     &StressTestConfig::default(),
+//! )
+//! .await?;
+//! println!("longest append: {:?}", report.max_commit_latency);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
 use std::future::Future;
 use std::iter::FromIterator;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use deltalake_core::kernel::{Action, Add, DataType, PrimitiveType, StructField, StructType};
-use deltalake_core::operations::transaction::CommitBuilder;
+use deltalake_core::metrics::{delta_metrics, Counter};
+use deltalake_core::operations::transaction::{CommitBuilder, CommitProperties};
 use deltalake_core::operations::DeltaOps;
 use deltalake_core::protocol::{DeltaOperation, SaveMode};
-use deltalake_core::{DeltaTable, DeltaTableBuilder};
+use deltalake_core::{DeltaTable, DeltaTableBuilder, DeltaTableError};
+use tokio::sync::Barrier;
 
 use crate::utils::*;
 
 pub async fn test_concurrent_writes(context: &IntegrationContext) -> TestResult {
     let (_table, table_uri) = prepare_table(context).await?;
     run_test(|name| Worker::new(&table_uri, name)).await;
+
+    let table_uri = context.uri_for_table(TestTables::Custom("concurrent_stress".into()));
+    let storage_options = HashMap::from([("allow_http".to_string(), "true".to_string())]);
+    stress_test_appends(&table_uri, storage_options, &StressTestConfig::default()).await?;
     Ok(())
 }
 
+/// Settings of [`stress_test_appends`]
+#[derive(Debug, Clone)]
+pub struct StressTestConfig {
+    /// Number of concurrent writers, each with its own connection to the table
+    pub writers: usize,
+    /// Number of appends committed by every writer
+    pub commits_per_writer: usize,
+    /// Properties of every commit, e.g. to configure retries and their backoff
+    pub commit_properties: CommitProperties,
+}
+
+impl Default for StressTestConfig {
+    fn default() -> Self {
+        Self {
+            writers: 8,
+            commits_per_writer: 10,
+            commit_properties: CommitProperties::default().with_max_retries(100),
+        }
+    }
+}
+
+/// Outcome of a successful [`stress_test_appends`]
+#[derive(Debug, Clone, Default)]
+pub struct StressTestReport {
+    /// Versions committed by every writer, in the order of its appends
+    pub versions: BTreeMap<String, Vec<i64>>,
+    /// Longest time a single append took, including its retries
+    pub max_commit_latency: Duration,
+    /// Retries of appends which lost the race for a version. Counted by the process wide
+    /// metrics, so retries of unrelated commits running at the same time are included.
+    pub conflict_retries: u64,
+}
+
+/// Failure of a [`stress_test_appends`]
+#[derive(Debug)]
+pub enum StressTestError {
+    /// Creating the table or committing failed, e.g. after exhausting all retries
+    Delta(DeltaTableError),
+    /// Two appends were committed with the same version
+    DuplicateVersion {
+        /// The version committed twice
+        version: i64,
+        /// Names of the appends
+        appends: [String; 2],
+    },
+    /// Versions between the first and the last append are missing from the log
+    MissingVersions(Vec<i64>),
+    /// Files of committed appends are missing from the final state of the table
+    MissingFiles(Vec<String>),
+}
+
+impl Display for StressTestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Delta(err) => write!(f, "Failed to append to the table: {err}"),
+            Self::DuplicateVersion { version, appends } => write!(
+                f,
+                "Version {version} was committed by both {} and {}",
+                appends[0], appends[1]
+            ),
+            Self::MissingVersions(versions) => write!(f, "Versions {versions:?} are missing"),
+            Self::MissingFiles(files) => write!(f, "Files {files:?} are missing"),
+        }
+    }
+}
+
+impl std::error::Error for StressTestError {}
+
+impl From<DeltaTableError> for StressTestError {
+    fn from(err: DeltaTableError) -> Self {
+        Self::Delta(err)
+    }
+}
+
+/// Append to the table at `table_uri` from many concurrent writers and validate that every
+/// append was committed exactly once, with unique and consecutive versions.
+///
+/// The table is created if it does not exist yet. It must not be written to by anyone else
+/// while the test runs.
+pub async fn stress_test_appends(
+    table_uri: &str,
+    storage_options: HashMap<String, String>,
+    config: &StressTestConfig,
+) -> Result<StressTestReport, StressTestError> {
+    let schema = StructType::new(vec![StructField::new(
+        "Id".to_string(),
+        DataType::Primitive(PrimitiveType::Integer),
+        true,
+    )]);
+    let table = DeltaOps::try_from_uri_with_storage_options(table_uri, storage_options.clone())
+        .await?
+        .create()
+        .with_columns(schema.fields().cloned())
+        .with_save_mode(SaveMode::Ignore)
+        .await?;
+    let start_version = table.version();
+    let start_retries = delta_metrics().get(Counter::CommitConflictRetries);
+
+    // writers start appending at the same time to maximize contention
+    let barrier = Arc::new(Barrier::new(config.writers));
+    let mut tasks = Vec::new();
+    for writer in 0..config.writers {
+        let name = format!("stress-w{writer}");
+        let table_uri = table_uri.to_string();
+        let storage_options = storage_options.clone();
+        let barrier = barrier.clone();
+        let commits = config.commits_per_writer;
+        let commit_properties = config.commit_properties.clone();
+        tasks.push(tokio::spawn(async move {
+            let table = DeltaTableBuilder::from_uri(&table_uri)
+                .with_storage_options(storage_options)
+                .load()
+                .await;
+            barrier.wait().await;
+            let mut table = table?;
+
+            let mut versions = Vec::new();
+            let mut max_latency = Duration::ZERO;
+            for commit in 0..commits {
+                let start = Instant::now();
+                let version = CommitBuilder::from(commit_properties.clone())
+                    .with_actions(vec![append_action(&format!("{name}-{commit}"))])
+                    .build(
+                        Some(table.snapshot()?),
+                        table.log_store(),
+                        append_operation(),
+                    )
+                    .await?
+                    .version();
+                max_latency = max_latency.max(start.elapsed());
+                versions.push(version);
+                table.update().await?;
+            }
+            Ok::<_, DeltaTableError>((name, versions, max_latency))
+        }));
+    }
+
+    let mut report = StressTestReport::default();
+    let mut appends = HashMap::new();
+    for task in tasks {
+        let (name, versions, max_latency) = task.await.expect("writer panicked")?;
+        for (commit, version) in versions.iter().enumerate() {
+            let append = format!("{name}-{commit}");
+            if let Some(other) = appends.insert(*version, append.clone()) {
+                return Err(StressTestError::DuplicateVersion {
+                    version: *version,
+                    appends: [other, append],
+                });
+            }
+        }
+        report.max_commit_latency = report.max_commit_latency.max(max_latency);
+        report.versions.insert(name, versions);
+    }
+    report.conflict_retries = delta_metrics().get(Counter::CommitConflictRetries) - start_retries;
+
+    let end_version = start_version + appends.len() as i64;
+    let missing_versions = ((start_version + 1)..=end_version)
+        .filter(|version| !appends.contains_key(version))
+        .collect::<Vec<_>>();
+    if !missing_versions.is_empty() {
+        return Err(StressTestError::MissingVersions(missing_versions));
+    }
+
+    let table = DeltaTableBuilder::from_uri(table_uri)
+        .with_storage_options(storage_options)
+        .load()
+        .await?;
+    let files = table
+        .get_files_iter()?
+        .map(|path| path.to_string())
+        .collect::<HashSet<_>>();
+    let mut missing_files = appends
+        .into_values()
+        .map(|append| format!("{append}.parquet"))
+        .filter(|file| !files.contains(file))
+        .collect::<Vec<_>>();
+    if !missing_files.is_empty() {
+        missing_files.sort();
+        return Err(StressTestError::MissingFiles(missing_files));
+    }
+
+    Ok(report)
+}
+
+fn append_operation() -> DeltaOperation {
+    DeltaOperation::Write {
+        mode: SaveMode::Append,
+        partition_by: None,
+        predicate: None,
+    }
+}
+
+fn append_action(name: &str) -> Action {
+    Action::Add(Add {
+        path: format!("{}.parquet", name),
+        size: 396,
+        partition_values: HashMap::new(),
+        modification_time: 1564524294000,
+        data_change: true,
+        stats: None,
+        stats_parsed: None,
+        tags: None,
+        deletion_vector: None,
+        base_row_id: None,
+        default_row_commit_version: None,
+        clustering_provider: None,
+    })
+}
+
 async fn prepare_table(
     context: &IntegrationContext,
 ) -> Result<(DeltaTable, String), Box<dyn std::error::Error + 'static>> {
@@ -118,30 +358,11 @@ impl Worker {
     }
 
     async fn commit_file(&mut self, name: &str) -> i64 {
-        let operation = DeltaOperation::Write {
-            mode: SaveMode::Append,
-            partition_by: None,
-            predicate: None,
-        };
-        let actions = vec![Action::Add(Add {
-            path: format!("{}.parquet", name),
-            size: 396,
-            partition_values: HashMap::new(),
-            modification_time: 1564524294000,
-            data_change: true,
-            stats: None,
-            stats_parsed: None,
-            tags: None,
-            deletion_vector: None,
-            base_row_id: None,
-            default_row_commit_version: None,
-            clustering_provider: None,
-        })];
         let snapshot = self.table.snapshot().unwrap().snapshot();
 
         let version = CommitBuilder::default()
-            .with_actions(actions)
-            .build(Some(snapshot), self.table.log_store(), operation)
+            .with_actions(vec![append_action(name)])
+            .build(Some(snapshot), self.table.log_store(), append_operation())
             .await
             .unwrap()
             .version();
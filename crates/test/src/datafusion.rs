@@ -1,7 +1,7 @@
 use deltalake_core::datafusion::execution::context::{SessionContext, SessionState};
 use deltalake_core::datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 use deltalake_core::datafusion::prelude::SessionConfig;
-use deltalake_core::delta_datafusion::DeltaTableFactory;
+use deltalake_core::delta_datafusion::register_delta_table_factory;
 use std::sync::Arc;
 
 pub fn context_with_delta_table_factory() -> SessionContext {
@@ -9,8 +9,6 @@ pub fn context_with_delta_table_factory() -> SessionContext {
     let env = RuntimeEnv::new(cfg).unwrap();
     let ses = SessionConfig::new();
     let mut state = SessionState::new_with_config_rt(ses, Arc::new(env));
-    state
-        .table_factories_mut()
-        .insert("DELTATABLE".to_string(), Arc::new(DeltaTableFactory {}));
+    register_delta_table_factory(&mut state);
     SessionContext::new_with_state(state)
 }
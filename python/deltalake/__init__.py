@@ -4,6 +4,7 @@ from .data_catalog import DataCatalog as DataCatalog
 from .schema import DataType as DataType
 from .schema import Field as Field
 from .schema import Schema as Schema
+from .table import ColumnProperties as ColumnProperties
 from .table import DeltaTable as DeltaTable
 from .table import Metadata as Metadata
 from .table import WriterProperties as WriterProperties
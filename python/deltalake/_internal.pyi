@@ -54,6 +54,7 @@ class RawDeltaTable:
         retention_hours: Optional[int],
         enforce_retention_duration: bool,
         custom_metadata: Optional[Dict[str, str]],
+        log_operations: bool,
     ) -> List[str]: ...
     def compact_optimize(
         self,
@@ -117,6 +117,69 @@ class Compression(Enum):
             return True
 
 
+def _parquet_compression(
+    compression: Optional[str], compression_level: Optional[int]
+) -> Optional[str]:
+    if compression_level is not None and compression is None:
+        raise ValueError(
+            """Providing a compression level without the compression type is not possible, 
+                         please provide the compression as well."""
+        )
+    if not isinstance(compression, str):
+        return None
+    compression_enum = Compression.from_str(compression)
+    if compression_enum in [
+        Compression.GZIP,
+        Compression.BROTLI,
+        Compression.ZSTD,
+    ]:
+        if compression_level is not None:
+            compression_enum.check_valid_level(compression_level)
+            return f"{compression_enum.value}({compression_level})"
+        return f"{compression_enum.value}({compression_enum.get_default_level()})"
+    return compression_enum.value
+
+
+@dataclass(init=True)
+class ColumnProperties:
+    """Parquet writer properties for a single column, overriding the table wide WriterProperties."""
+
+    def __init__(
+        self,
+        dictionary_enabled: Optional[bool] = None,
+        compression: Optional[
+            Literal[
+                "UNCOMPRESSED",
+                "SNAPPY",
+                "GZIP",
+                "BROTLI",
+                "LZ4",
+                "ZSTD",
+                "LZ4_RAW",
+            ]
+        ] = None,
+        compression_level: Optional[int] = None,
+    ):
+        """Create a Column Properties instance for the Rust parquet writer:
+
+        Args:
+            dictionary_enabled: Enable dictionary encoding for the column.
+            compression: compression type of the column.
+            compression_level: If none and compression has a level, the default level will be used.
+        """
+        self.dictionary_enabled = dictionary_enabled
+        self.compression = _parquet_compression(compression, compression_level)
+
+    def __str__(self) -> str:
+        return f"ColumnProperties(dictionary_enabled: {self.dictionary_enabled}, compression: {self.compression})"
+
+    def _to_dict(self) -> Dict[str, Any]:
+        return {
+            "dictionary_enabled": self.dictionary_enabled,
+            "compression": self.compression,
+        }
+
+
 @dataclass(init=True)
 class WriterProperties:
     """A Writer Properties instance for the Rust parquet writer."""
@@ -140,6 +203,8 @@ class WriterProperties:
             ]
         ] = None,
         compression_level: Optional[int] = None,
+        dictionary_enabled: Optional[bool] = None,
+        column_properties: Optional[Dict[str, ColumnProperties]] = None,
     ):
         """Create a Writer Properties instance for the Rust parquet writer:
 
@@ -154,48 +219,44 @@ class WriterProperties:
                 GZIP: levels (1-9),
                 BROTLI: levels (1-11),
                 ZSTD: levels (1-22),
+            dictionary_enabled: Enable dictionary encoding for all columns.
+            column_properties: Properties of individual columns, keyed by the dot separated column path.
         """
         self.data_page_size_limit = data_page_size_limit
         self.dictionary_page_size_limit = dictionary_page_size_limit
         self.data_page_row_count_limit = data_page_row_count_limit
         self.write_batch_size = write_batch_size
         self.max_row_group_size = max_row_group_size
-        self.compression = None
-
-        if compression_level is not None and compression is None:
-            raise ValueError(
-                """Providing a compression level without the compression type is not possible, 
-                             please provide the compression as well."""
-            )
-        if isinstance(compression, str):
-            compression_enum = Compression.from_str(compression)
-            if compression_enum in [
-                Compression.GZIP,
-                Compression.BROTLI,
-                Compression.ZSTD,
-            ]:
-                if compression_level is not None:
-                    if compression_enum.check_valid_level(compression_level):
-                        parquet_compression = (
-                            f"{compression_enum.value}({compression_level})"
-                        )
-                else:
-                    parquet_compression = f"{compression_enum.value}({compression_enum.get_default_level()})"
-            else:
-                parquet_compression = compression_enum.value
-            self.compression = parquet_compression
+        self.compression = _parquet_compression(compression, compression_level)
+        self.dictionary_enabled = dictionary_enabled
+        self.column_properties = column_properties
 
     def __str__(self) -> str:
+        column_properties = (
+            {column: str(props) for column, props in self.column_properties.items()}
+            if self.column_properties
+            else None
+        )
         return (
             f"WriterProperties(data_page_size_limit: {self.data_page_size_limit}, dictionary_page_size_limit: {self.dictionary_page_size_limit}, "
             f"data_page_row_count_limit: {self.data_page_row_count_limit}, write_batch_size: {self.write_batch_size}, "
-            f"max_row_group_size: {self.max_row_group_size}, compression: {self.compression})"
+            f"max_row_group_size: {self.max_row_group_size}, compression: {self.compression}, "
+            f"dictionary_enabled: {self.dictionary_enabled}, column_properties: {column_properties})"
         )
 
     def _to_dict(self) -> Dict[str, Optional[str]]:
-        values = {}
+        values: Dict[str, Optional[str]] = {}
         for key, value in self.__dict__.items():
-            values[key] = str(value) if isinstance(value, int) else value
+            if key == "dictionary_enabled":
+                if value is not None:
+                    values[key] = str(value).lower()
+            elif key == "column_properties":
+                if value:
+                    values[key] = json.dumps(
+                        {column: props._to_dict() for column, props in value.items()}
+                    )
+            else:
+                values[key] = str(value) if isinstance(value, int) else value
         return values
 
 
@@ -664,6 +725,7 @@ class DeltaTable:
         dry_run: bool = True,
         enforce_retention_duration: bool = True,
         custom_metadata: Optional[Dict[str, str]] = None,
+        log_operations: bool = True,
     ) -> List[str]:
         """
         Run the Vacuum command on the Delta Table: list and delete files no longer referenced by the Delta table and are older than the retention threshold.
@@ -673,6 +735,7 @@ class DeltaTable:
             dry_run: when activated, list only the files, delete otherwise
             enforce_retention_duration: when disabled, accepts retention hours smaller than the value from `configuration.deletedFileRetentionDuration`.
             custom_metadata: custom metadata that will be added to the transaction commit.
+            log_operations: when activated, record the vacuum with `VACUUM START` and `VACUUM END` commits. `custom_metadata` cannot be used when disabled.
         Returns:
             the list of files no longer referenced by the Delta Table and are older than the retention threshold.
         """
@@ -685,6 +748,7 @@ class DeltaTable:
             retention_hours,
             enforce_retention_duration,
             custom_metadata,
+            log_operations,
         )
 
     def update(
@@ -727,7 +791,7 @@ class DeltaTable:
             dt = DeltaTable("tmp")
             dt.update(predicate="id = '3'", updates = {"deleted": 'True'})
 
-            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 1, 'num_copied_rows': 2, 'execution_time_ms': ..., 'scan_time_ms': ...}
+            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 1, 'num_copied_rows': 2, 'execution_time_ms': ..., 'scan_time_ms': ..., 'rewrite_time_ms': ...}
             ```
 
             **Update all row values**
@@ -736,7 +800,7 @@ class DeltaTable:
             ```py
             dt.update(updates = {"deleted": 'True', "id": "concat(id, '_old')"})
 
-            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 3, 'num_copied_rows': 0, 'execution_time_ms': ..., 'scan_time_ms': ...}
+            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 3, 'num_copied_rows': 0, 'execution_time_ms': ..., 'scan_time_ms': ..., 'rewrite_time_ms': ...}
             ```
 
             **Use Python objects instead of SQL strings**
@@ -746,7 +810,7 @@ class DeltaTable:
             ```py
             dt.update(predicate="id = '1_old'", new_values = {"price": 150.10})
 
-            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 1, 'num_copied_rows': 2, 'execution_time_ms': ..., 'scan_time_ms': ...}
+            {'num_added_files': 1, 'num_removed_files': 1, 'num_updated_rows': 1, 'num_copied_rows': 2, 'execution_time_ms': ..., 'scan_time_ms': ..., 'rewrite_time_ms': ...}
             ```
         """
         if updates is None and new_values is not None:
@@ -23,12 +23,16 @@ fn inner_to_py_err(err: DeltaTableError) -> PyErr {
         DeltaTableError::InvalidData { violations } => {
             DeltaProtocolError::new_err(format!("Invariant violations: {:?}", violations))
         }
+        DeltaTableError::ConstraintViolation { .. } => DeltaProtocolError::new_err(err.to_string()),
 
         // commit errors
         DeltaTableError::Transaction { source } => CommitFailedError::new_err(source.to_string()),
+        DeltaTableError::CommitConflict { kind } => CommitFailedError::new_err(kind.to_string()),
 
         // python exceptions
-        DeltaTableError::ObjectStore { source } => object_store_to_py(source),
+        DeltaTableError::ObjectStore { source } | DeltaTableError::Storage { source } => {
+            object_store_to_py(source)
+        }
         DeltaTableError::Io { source } => PyIOError::new_err(source.to_string()),
 
         DeltaTableError::Arrow { source } => arrow_to_py(source),
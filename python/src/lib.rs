@@ -47,6 +47,7 @@ use deltalake::operations::vacuum::VacuumBuilder;
 use deltalake::parquet::basic::Compression;
 use deltalake::parquet::errors::ParquetError;
 use deltalake::parquet::file::properties::WriterProperties;
+use deltalake::parquet::schema::types::ColumnPath;
 use deltalake::partitions::PartitionFilter;
 use deltalake::protocol::{DeltaOperation, SaveMode};
 use deltalake::DeltaTableBuilder;
@@ -56,6 +57,7 @@ use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
 use pyo3::types::{PyDict, PyFrozenSet};
+use serde::Deserialize;
 use serde_json::{Map, Value};
 
 use crate::error::DeltaProtocolError;
@@ -319,7 +321,7 @@ impl RawDeltaTable {
 
     /// Run the Vacuum command on the Delta Table: list and delete files no longer referenced
     /// by the Delta table and are older than the retention threshold.
-    #[pyo3(signature = (dry_run, retention_hours = None, enforce_retention_duration = true, custom_metadata=None))]
+    #[pyo3(signature = (dry_run, retention_hours = None, enforce_retention_duration = true, custom_metadata=None, log_operations = true))]
     pub fn vacuum(
         &mut self,
         py: Python,
@@ -327,6 +329,7 @@ impl RawDeltaTable {
         retention_hours: Option<u64>,
         enforce_retention_duration: bool,
         custom_metadata: Option<HashMap<String, String>>,
+        log_operations: bool,
     ) -> PyResult<Vec<String>> {
         let (table, metrics) = py.allow_threads(|| {
             let mut cmd = VacuumBuilder::new(
@@ -334,7 +337,8 @@ impl RawDeltaTable {
                 self._table.snapshot().map_err(PythonError::from)?.clone(),
             )
             .with_enforce_retention_duration(enforce_retention_duration)
-            .with_dry_run(dry_run);
+            .with_dry_run(dry_run)
+            .with_operation_logging(log_operations);
             if let Some(retention_period) = retention_hours {
                 cmd = cmd.with_retention_period(Duration::hours(retention_period as i64));
             }
@@ -1283,6 +1287,8 @@ fn set_writer_properties(
     let write_batch_size = writer_properties.get("write_batch_size");
     let max_row_group_size = writer_properties.get("max_row_group_size");
     let compression = writer_properties.get("compression");
+    let dictionary_enabled = writer_properties.get("dictionary_enabled");
+    let column_properties = writer_properties.get("column_properties");
 
     if let Some(Some(data_page_size)) = data_page_size_limit {
         properties = properties.set_data_page_size_limit(data_page_size.parse::<usize>().unwrap());
@@ -1309,9 +1315,44 @@ fn set_writer_properties(
 
         properties = properties.set_compression(compress);
     }
+    if let Some(Some(dictionary_enabled)) = dictionary_enabled {
+        properties = properties.set_dictionary_enabled(parse_bool(dictionary_enabled)?);
+    }
+
+    if let Some(Some(column_properties)) = column_properties {
+        let column_properties: HashMap<String, ColumnProperties> =
+            serde_json::from_str(column_properties)
+                .map_err(|err| DeltaTableError::Generic(err.to_string()))?;
+        for (column, props) in column_properties {
+            let path = ColumnPath::new(column.split('.').map(|c| c.to_string()).collect());
+            if let Some(dictionary_enabled) = props.dictionary_enabled {
+                properties =
+                    properties.set_column_dictionary_enabled(path.clone(), dictionary_enabled);
+            }
+            if let Some(compression) = props.compression {
+                let compress: Compression = compression
+                    .parse()
+                    .map_err(|err: ParquetError| DeltaTableError::Generic(err.to_string()))?;
+                properties = properties.set_column_compression(path, compress);
+            }
+        }
+    }
     Ok(properties.build())
 }
 
+/// Parquet writer properties of a single column, as serialized by `ColumnProperties._to_dict`
+#[derive(Deserialize)]
+struct ColumnProperties {
+    dictionary_enabled: Option<bool>,
+    compression: Option<String>,
+}
+
+fn parse_bool(value: &str) -> DeltaResult<bool> {
+    value
+        .parse()
+        .map_err(|_| DeltaTableError::Generic(format!("Invalid boolean value: {value}")))
+}
+
 fn convert_partition_filters(
     partitions_filters: Vec<(PyBackedStr, PyBackedStr, PartitionFilterValue)>,
 ) -> Result<Vec<PartitionFilter>, DeltaTableError> {
@@ -1869,6 +1910,7 @@ fn _internal(m: &Bound<'_, PyModule>) -> PyResult<()> {
     deltalake::azure::register_handlers(None);
     deltalake::gcp::register_handlers(None);
     deltalake::hdfs::register_handlers(None);
+    deltalake::data_catalog::unity::register_handlers(None);
     deltalake_mount::register_handlers(None);
 
     let py = m.py();
@@ -1,4 +1,5 @@
 import pathlib
+from typing import Dict
 
 import pyarrow as pa
 import pytest
@@ -235,7 +236,11 @@ def test_set_table_properties_enable_cdf_with_writer_version_bumped(
     }
     assert protocol.min_reader_version == 1
     assert protocol.min_writer_version == 7
-    assert protocol.writer_features == ["changeDataFeed"]
+    assert list(sorted(protocol.writer_features)) == [  # type: ignore
+        "appendOnly",
+        "changeDataFeed",
+        "invariants",
+    ]
 
 
 def test_set_table_properties_enable_cdf_and_deletion_vectors(
@@ -255,8 +260,10 @@ def test_set_table_properties_enable_cdf_and_deletion_vectors(
     assert protocol.min_reader_version == 3
     assert protocol.min_writer_version == 7
     assert list(sorted(protocol.writer_features)) == [  # type: ignore
+        "appendOnly",
         "changeDataFeed",
         "deletionVectors",
+        "invariants",
     ]
     assert protocol.reader_features == ["deletionVectors"]
 
@@ -288,8 +295,10 @@ def test_convert_checkConstraints_to_feature_after_version_upgrade(
     assert protocol.min_reader_version == 3
     assert protocol.min_writer_version == 7
     assert list(sorted(protocol.writer_features)) == [  # type: ignore
+        "appendOnly",
         "checkConstraints",
         "deletionVectors",
+        "invariants",
     ]
     assert protocol.reader_features == ["deletionVectors"]
 
@@ -303,5 +312,30 @@ def test_set_table_properties_enable_dv(tmp_path: pathlib.Path, sample_table: pa
     assert dt.metadata().configuration == {"delta.enableDeletionVectors": "true"}
     assert protocol.min_reader_version == 3
     assert protocol.min_writer_version == 7
-    assert protocol.writer_features == ["deletionVectors"]
+    assert list(sorted(protocol.writer_features)) == [  # type: ignore
+        "appendOnly",
+        "deletionVectors",
+        "invariants",
+    ]
     assert protocol.reader_features == ["deletionVectors"]
+
+
+@pytest.mark.parametrize(
+    "configuration",
+    [
+        {"delta.appendOnly": "yes"},
+        {"delta.checkpointInterval": "0"},
+        {"delta.logRetentionDuration": "2 days"},
+        {"delta.columnMapping.mode": "name"},
+    ],
+)
+def test_set_table_properties_invalid_value(
+    tmp_path: pathlib.Path, sample_table: pa.Table, configuration: Dict[str, str]
+):
+    write_deltalake(tmp_path, sample_table, mode="append", engine="rust")
+    dt = DeltaTable(tmp_path)
+    with pytest.raises(DeltaError):
+        dt.alter.set_table_properties(configuration)
+
+    assert dt.metadata().configuration == {}
+    assert dt.version() == 0
@@ -1,5 +1,7 @@
 import datetime
+import os
 import pathlib
+from typing import List
 
 import pyarrow as pa
 import pytest
@@ -7,6 +9,14 @@ import pytest
 from deltalake import DeltaTable, write_deltalake
 
 
+def set_commit_times(table_path: str, datestrings: List[str]) -> None:
+    """Set the modification times of the commit files, which are used for time travel"""
+    for version, datestring in enumerate(datestrings):
+        ts = datetime.datetime.fromisoformat(datestring).timestamp()
+        commit_file = os.path.join(table_path, "_delta_log", f"{version:020}.json")
+        os.utime(commit_file, (ts, ts))
+
+
 @pytest.mark.parametrize("use_relative", [True, False])
 def test_restore_with_version(
     tmp_path: pathlib.Path, sample_data: pa.Table, monkeypatch, use_relative: bool
@@ -45,6 +55,14 @@ def test_restore_with_datetime_str(
     write_deltalake(table_path, sample_data, mode="append")
     write_deltalake(table_path, sample_data, mode="append")
     write_deltalake(table_path, sample_data, mode="append")
+    set_commit_times(
+        table_path,
+        [
+            "2020-04-30T00:00:00-07:00",
+            "2020-05-02T00:00:00-07:00",
+            "2020-05-03T00:00:00-07:00",
+        ],
+    )
 
     dt = DeltaTable(table_path)
     old_version = dt.version()
@@ -68,6 +86,14 @@ def test_restore_with_datetime(
     write_deltalake(table_path, sample_data, mode="append")
     write_deltalake(table_path, sample_data, mode="append")
     write_deltalake(table_path, sample_data, mode="append")
+    set_commit_times(
+        table_path,
+        [
+            "2023-04-25T00:00:00+08:00",
+            "2023-04-27T00:00:00+08:00",
+            "2023-04-28T00:00:00+08:00",
+        ],
+    )
 
     dt = DeltaTable(table_path)
     old_version = dt.version()
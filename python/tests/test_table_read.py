@@ -9,7 +9,7 @@ from unittest.mock import Mock
 from packaging import version
 
 from deltalake._util import encode_partition_value
-from deltalake.exceptions import DeltaProtocolError
+from deltalake.exceptions import DeltaError, DeltaProtocolError
 from deltalake.table import ProtocolVersions
 from deltalake.writer import write_deltalake
 
@@ -74,12 +74,39 @@ def test_read_simple_table_using_options_to_dict():
 @pytest.mark.parametrize(
     ["date_value", "expected_version"],
     [
-        ("2020-05-01T00:47:31-07:00", 0),
+        ("2020-05-01T22:47:31-07:00", 0),
         ("2020-05-02T22:47:31-07:00", 1),
-        ("2020-05-25T22:47:31-07:00", 4),
+        ("2020-05-05T22:47:31-07:00", 4),
     ],
 )
 def test_load_as_version_datetime(date_value: str, expected_version):
+    set_simple_table_commit_times()
+
+    table_path = "../crates/test/tests/data/simple_table"
+    dt = DeltaTable(table_path)
+    dt.load_as_version(date_value)
+    assert dt.version() == expected_version
+    dt = DeltaTable(table_path)
+    dt.load_as_version(datetime.fromisoformat(date_value))
+    assert dt.version() == expected_version
+
+
+@pytest.mark.parametrize(
+    ["date_value", "message"],
+    [
+        ("2020-05-01T00:47:31-07:00", "before the table was created"),
+        ("2020-05-25T22:47:31-07:00", "after the latest version"),
+    ],
+)
+def test_load_as_version_datetime_out_of_range(date_value: str, message: str):
+    set_simple_table_commit_times()
+
+    dt = DeltaTable("../crates/test/tests/data/simple_table")
+    with pytest.raises(DeltaError, match=message):
+        dt.load_as_version(date_value)
+
+
+def set_simple_table_commit_times():
     log_dir = "../crates/test/tests/data/simple_table/_delta_log"
     log_mtime_pair = [
         ("00000000000000000000.json", 1588398451.0),
@@ -92,14 +119,6 @@ def test_load_as_version_datetime(date_value: str, expected_version):
         file_path = os.path.join(log_dir, file_name)
         os.utime(file_path, (dt_epoch, dt_epoch))
 
-    table_path = "../crates/test/tests/data/simple_table"
-    dt = DeltaTable(table_path)
-    dt.load_as_version(date_value)
-    assert dt.version() == expected_version
-    dt = DeltaTable(table_path)
-    dt.load_as_version(datetime.fromisoformat(date_value))
-    assert dt.version() == expected_version
-
 
 @pytest.mark.parametrize(
     ["date_value", "expected_version", "log_mtime_pairs"],
@@ -101,3 +101,24 @@ def test_vacuum_transaction_log(tmp_path: pathlib.Path, sample_data: pa.Table):
     assert history[0]["operationMetrics"]["numDeletedFiles"] == 4
     assert history[1]["operationMetrics"]["numFilesToDelete"] == 4
     assert history[1]["operationMetrics"]["sizeOfDataToDelete"] > 0
+
+
+def test_vacuum_without_operation_logging(
+    tmp_path: pathlib.Path, sample_data: pa.Table
+):
+    for i in range(5):
+        write_deltalake(tmp_path, sample_data, mode="overwrite")
+
+    dt = DeltaTable(tmp_path)
+    version = dt.version()
+
+    tombstones = dt.vacuum(
+        retention_hours=0,
+        dry_run=False,
+        enforce_retention_duration=False,
+        log_operations=False,
+    )
+    assert len(tombstones) == 4
+
+    dt = DeltaTable(tmp_path)
+    assert dt.version() == version
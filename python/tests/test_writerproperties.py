@@ -1,10 +1,11 @@
+import json
 import pathlib
 
 import pyarrow as pa
 import pyarrow.parquet as pq
 import pytest
 
-from deltalake import DeltaTable, WriterProperties, write_deltalake
+from deltalake import ColumnProperties, DeltaTable, WriterProperties, write_deltalake
 
 
 def test_writer_properties_all_filled():
@@ -86,3 +87,38 @@ def test_write_with_writerproperties(
     metadata = pq.read_metadata(parquet_path)
 
     assert metadata.to_dict()["row_groups"][0]["columns"][0]["compression"] == "GZIP"
+
+
+def test_writer_properties_column_properties():
+    wp = WriterProperties(
+        dictionary_enabled=False,
+        column_properties={
+            "price": ColumnProperties(compression="ZSTD", compression_level=3),
+            "sold": ColumnProperties(dictionary_enabled=True),
+        },
+    )
+
+    assert wp._to_dict()["dictionary_enabled"] == "false"
+    assert json.loads(wp._to_dict()["column_properties"]) == {
+        "price": {"dictionary_enabled": None, "compression": "ZSTD(3)"},
+        "sold": {"dictionary_enabled": True, "compression": None},
+    }
+
+
+def test_write_with_column_properties(tmp_path: pathlib.Path, sample_table: pa.Table):
+    writer_properties = WriterProperties(
+        compression="SNAPPY",
+        column_properties={"price": ColumnProperties(compression="GZIP")},
+    )
+    write_deltalake(
+        tmp_path, sample_table, engine="rust", writer_properties=writer_properties
+    )
+
+    parquet_path = DeltaTable(tmp_path).file_uris()[0]
+    columns = pq.read_metadata(parquet_path).to_dict()["row_groups"][0]["columns"]
+    compression = {
+        column["path_in_schema"]: column["compression"] for column in columns
+    }
+
+    assert compression["price"] == "GZIP"
+    assert compression["sold"] == "SNAPPY"